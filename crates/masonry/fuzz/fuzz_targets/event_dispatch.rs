@@ -0,0 +1,157 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Random sequences of input events and changes to the widget tree, checking
+//! after each one that the passes left the tree consistent.
+//!
+//! Run with `cargo +nightly fuzz run event_dispatch` from `crates/masonry`.
+//!
+//! Views can't be built outside of Xilem, so the changes rebuilding a view tree
+//! makes are checked the same way by a seeded test of random changes instead, in
+//! Xilem's `keyed` module.
+
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use masonry::testing::TestHarness;
+use masonry::widget::{Button, Checkbox, Flex, Label, Portal, SizedBox, Textbox};
+use masonry::{Vec2, Widget, WindowEvent};
+use winit::dpi::PhysicalSize;
+use winit::event::MouseButton;
+
+/// The longest sequence which is run, so that each input stays fast.
+const MAX_STEPS: usize = 256;
+
+#[derive(Arbitrary, Debug)]
+enum Step {
+    MouseMove { x: u16, y: u16 },
+    MousePress(Mouse),
+    MouseRelease(Mouse),
+    Wheel { x: i8, y: i8 },
+    Type(String),
+    MoveFocus { forward: bool },
+    Animate { millis: u8 },
+    AdvanceTime { millis: u16 },
+    Resize { width: u16, height: u16 },
+    AddChild(Kind),
+    InsertChild(u8, Kind),
+    RemoveChild(u8),
+    MoveChild(u8, u8),
+    SetDisabled(u8, bool),
+    RemoveAll,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Mouse {
+    Left,
+    Right,
+    Middle,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Kind {
+    Label,
+    Button,
+    Checkbox,
+    Textbox,
+    Spacer,
+    Portal,
+    Row,
+}
+
+impl Kind {
+    fn widget(&self) -> Box<dyn Widget> {
+        match self {
+            Kind::Label => Box::new(Label::new("Label")),
+            Kind::Button => Box::new(Button::new("Button")),
+            Kind::Checkbox => Box::new(Checkbox::new(false, "Checkbox")),
+            Kind::Textbox => Box::new(Textbox::new("Text")),
+            Kind::Spacer => Box::new(SizedBox::empty().width(20.0).height(20.0)),
+            Kind::Portal => Box::new(Portal::new(
+                Flex::column()
+                    .with_child(Label::new("Scrolled"))
+                    .with_child(SizedBox::empty().height(1000.0)),
+            )),
+            Kind::Row => Box::new(
+                Flex::row()
+                    .with_child(Button::new("Left"))
+                    .with_child(Textbox::new("Right")),
+            ),
+        }
+    }
+}
+
+impl From<&Mouse> for MouseButton {
+    fn from(mouse: &Mouse) -> Self {
+        match mouse {
+            Mouse::Left => MouseButton::Left,
+            Mouse::Right => MouseButton::Right,
+            Mouse::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+fn run(harness: &mut TestHarness, step: &Step) {
+    match step {
+        Step::MouseMove { x, y } => harness.mouse_move((*x as f64 % 500.0, *y as f64 % 500.0)),
+        Step::MousePress(mouse) => harness.mouse_button_press(mouse.into()),
+        Step::MouseRelease(mouse) => harness.mouse_button_release(mouse.into()),
+        Step::Wheel { x, y } => harness.mouse_wheel(Vec2::new(*x as f64, *y as f64)),
+        Step::Type(text) => {
+            let text: String = text.chars().take(16).collect();
+            harness.keyboard_type_chars(&text);
+        }
+        Step::MoveFocus { forward } => harness.move_focus(*forward),
+        Step::Animate { millis } => harness.animate(std::time::Duration::from_millis(*millis as _)),
+        Step::AdvanceTime { millis } => {
+            harness.advance_time(std::time::Duration::from_millis(*millis as _));
+        }
+        Step::Resize { width, height } => {
+            let size = PhysicalSize::new(*width as u32 % 1000 + 1, *height as u32 % 1000 + 1);
+            harness.process_window_event(WindowEvent::Resize(size));
+        }
+        Step::AddChild(kind) => edit_flex(harness, |flex| flex.add_child(kind.widget())),
+        Step::InsertChild(ix, kind) => edit_flex(harness, |flex| {
+            let ix = *ix as usize % (flex.widget.len() + 1);
+            flex.insert_child(ix, kind.widget());
+        }),
+        Step::RemoveChild(ix) => edit_flex(harness, |flex| {
+            if !flex.widget.is_empty() {
+                flex.remove_child(*ix as usize % flex.widget.len());
+            }
+        }),
+        Step::MoveChild(from, to) => edit_flex(harness, |flex| {
+            let len = flex.widget.len();
+            if len != 0 {
+                flex.move_child(*from as usize % len, *to as usize % len);
+            }
+        }),
+        Step::SetDisabled(ix, disabled) => edit_flex(harness, |flex| {
+            if flex.widget.is_empty() {
+                return;
+            }
+            let ix = *ix as usize % flex.widget.len();
+            if let Some(mut child) = flex.child_mut(ix) {
+                child.ctx.set_disabled(*disabled);
+            }
+        }),
+        Step::RemoveAll => edit_flex(harness, |flex| flex.clear()),
+    }
+}
+
+fn edit_flex(harness: &mut TestHarness, f: impl FnOnce(&mut masonry::widget::WidgetMut<'_, Flex>)) {
+    harness.edit_root_widget(|mut root| f(&mut root.downcast::<Flex>()));
+}
+
+fuzz_target!(|steps: Vec<Step>| {
+    let root = Flex::column()
+        .with_child(Label::new("Fuzzing"))
+        .with_child(Button::new("Press"))
+        .with_child(Textbox::new(""));
+    let mut harness = TestHarness::create(root);
+    for step in steps.iter().take(MAX_STEPS) {
+        run(&mut harness, step);
+        harness.check_invariants();
+    }
+});
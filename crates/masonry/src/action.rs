@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::any::Any;
+use std::ops::Range;
 use std::sync::Arc;
 
 // TODO - Refactor - See issue #1
@@ -17,6 +18,37 @@ pub enum Action {
     TextChanged(String),
     TextEntered(String),
     CheckboxChecked(bool),
+    TokensChanged(Vec<String>),
+    /// A cell of a data grid was edited, with its row, column and new text.
+    CellEdited(usize, usize, String),
+    /// A dialog was accepted, with the text of its input, which is empty if it has none.
+    DialogAccepted(String),
+    /// A dialog was cancelled or dismissed.
+    DialogCancelled,
+    /// A portal was scrolled to within its end threshold of the end of its content.
+    ScrollReachedEnd,
+    /// A portal was pulled down past its top to refresh its content.
+    RefreshRequested,
+    /// A pager was turned to the page with this index.
+    PageChanged(usize),
+    /// A misspelled word was right-clicked and selected, with the spellchecker's
+    /// suggestions for it. See [`spellcheck`](crate::spellcheck).
+    SpellingSuggestions(Vec<String>),
+    /// The user moved to another match of a text widget's find query, with the
+    /// index of the match and the number of matches.
+    FindMatchChanged(usize, usize),
+    /// The value of a knob was changed by the user.
+    ValueChanged(f64),
+    /// A segment of a segmented control was selected, with its index.
+    SegmentSelected(usize),
+    /// The buttons of a toggle group were toggled, with whether each is pressed.
+    TogglesChanged(Vec<bool>),
+    /// The items a virtual list needs children for changed, as they were scrolled
+    /// into or out of view.
+    VisibleItemsChanged(Range<usize>),
+    /// The text of an autocomplete input stopped changing for its debounce delay,
+    /// and suggestions for it should be loaded.
+    SuggestionsRequested(String),
     // FIXME - This is a huge hack
     Other(Arc<dyn Any>),
 }
@@ -28,6 +60,24 @@ impl PartialEq for Action {
             (Self::TextChanged(l0), Self::TextChanged(r0)) => l0 == r0,
             (Self::TextEntered(l0), Self::TextEntered(r0)) => l0 == r0,
             (Self::CheckboxChecked(l0), Self::CheckboxChecked(r0)) => l0 == r0,
+            (Self::TokensChanged(l0), Self::TokensChanged(r0)) => l0 == r0,
+            (Self::CellEdited(l0, l1, l2), Self::CellEdited(r0, r1, r2)) => {
+                l0 == r0 && l1 == r1 && l2 == r2
+            }
+            (Self::DialogAccepted(l0), Self::DialogAccepted(r0)) => l0 == r0,
+            (Self::DialogCancelled, Self::DialogCancelled) => true,
+            (Self::ScrollReachedEnd, Self::ScrollReachedEnd) => true,
+            (Self::RefreshRequested, Self::RefreshRequested) => true,
+            (Self::PageChanged(l0), Self::PageChanged(r0)) => l0 == r0,
+            (Self::SpellingSuggestions(l0), Self::SpellingSuggestions(r0)) => l0 == r0,
+            (Self::FindMatchChanged(l0, l1), Self::FindMatchChanged(r0, r1)) => {
+                l0 == r0 && l1 == r1
+            }
+            (Self::ValueChanged(l0), Self::ValueChanged(r0)) => l0 == r0,
+            (Self::SegmentSelected(l0), Self::SegmentSelected(r0)) => l0 == r0,
+            (Self::TogglesChanged(l0), Self::TogglesChanged(r0)) => l0 == r0,
+            (Self::VisibleItemsChanged(l0), Self::VisibleItemsChanged(r0)) => l0 == r0,
+            (Self::SuggestionsRequested(l0), Self::SuggestionsRequested(r0)) => l0 == r0,
             #[allow(ambiguous_wide_pointer_comparisons)]
             // FIXME
             (Self::Other(val_l), Self::Other(val_r)) => Arc::ptr_eq(val_l, val_r),
@@ -43,6 +93,37 @@ impl std::fmt::Debug for Action {
             Self::TextChanged(text) => f.debug_tuple("TextChanged").field(text).finish(),
             Self::TextEntered(text) => f.debug_tuple("TextEntered").field(text).finish(),
             Self::CheckboxChecked(b) => f.debug_tuple("CheckboxChecked").field(b).finish(),
+            Self::TokensChanged(tokens) => f.debug_tuple("TokensChanged").field(tokens).finish(),
+            Self::CellEdited(row, col, text) => f
+                .debug_tuple("CellEdited")
+                .field(row)
+                .field(col)
+                .field(text)
+                .finish(),
+            Self::DialogAccepted(text) => f.debug_tuple("DialogAccepted").field(text).finish(),
+            Self::DialogCancelled => write!(f, "DialogCancelled"),
+            Self::ScrollReachedEnd => write!(f, "ScrollReachedEnd"),
+            Self::RefreshRequested => write!(f, "RefreshRequested"),
+            Self::PageChanged(page) => f.debug_tuple("PageChanged").field(page).finish(),
+            Self::SpellingSuggestions(words) => {
+                f.debug_tuple("SpellingSuggestions").field(words).finish()
+            }
+            Self::FindMatchChanged(index, count) => f
+                .debug_tuple("FindMatchChanged")
+                .field(index)
+                .field(count)
+                .finish(),
+            Self::ValueChanged(value) => f.debug_tuple("ValueChanged").field(value).finish(),
+            Self::SegmentSelected(ix) => f.debug_tuple("SegmentSelected").field(ix).finish(),
+            Self::TogglesChanged(pressed) => {
+                f.debug_tuple("TogglesChanged").field(pressed).finish()
+            }
+            Self::VisibleItemsChanged(items) => {
+                f.debug_tuple("VisibleItemsChanged").field(items).finish()
+            }
+            Self::SuggestionsRequested(text) => {
+                f.debug_tuple("SuggestionsRequested").field(text).finish()
+            }
             Self::Other(_) => write!(f, "Other(...)"),
         }
     }
@@ -1,8 +1,24 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
+
+use crate::color::ColorSpace;
+use crate::conventions::PlatformConventions;
+use crate::direction::LayoutDirection;
+use crate::feedback::{FeedbackBackend, NoFeedback};
+use crate::idle::ActivityEvent;
+use crate::interaction::InteractionSettings;
+use crate::paint_debug::PaintDebug;
+use crate::popup::PopupMode;
+use crate::render_diagnostics::RenderDiagnostics;
+use crate::render_root::RenderRootSignal;
+use crate::spellcheck::{NoSpellChecker, SpellChecker};
+use crate::taskbar::{AppIcon, NoTaskbar, TaskbarBackend, TaskbarProgress};
+use crate::wake::WakeHandle;
 use crate::widget::WidgetMut;
-use crate::{Action, Widget, WidgetId};
+use crate::window_kind::WindowKind;
+use crate::{Action, IconSet, Keymap, Widget, WidgetId};
 
 // xilem::App will implement AppDriver
 
@@ -13,6 +29,151 @@ pub struct DriverCtx<'a> {
 
 pub trait AppDriver {
     fn on_action(&mut self, ctx: &mut DriverCtx<'_>, widget_id: WidgetId, action: Action);
+
+    /// The keyboard shortcuts used by widgets in the app.
+    ///
+    /// This is called once, before the app starts handling events.
+    fn keymap(&self) -> Keymap {
+        Keymap::default()
+    }
+
+    /// The timings and distances which decide how widgets interpret input, such as
+    /// the double click interval.
+    ///
+    /// This is called once, before the app starts handling events. The default
+    /// uses the platform's usual settings, overridden by environment variables.
+    /// See [`interaction`](crate::interaction) for more.
+    fn interaction_settings(&self) -> InteractionSettings {
+        InteractionSettings::from_env()
+    }
+
+    /// How widgets follow the platform's conventions, such as the order of the
+    /// buttons of dialogs.
+    ///
+    /// This is called once, before the app starts handling events. The default
+    /// follows the current platform. See [`conventions`](crate::conventions) for more.
+    fn conventions(&self) -> PlatformConventions {
+        PlatformConventions::default()
+    }
+
+    /// Where popups, such as menus and dropdowns, are shown.
+    ///
+    /// This is called once, before the app starts handling events. The default
+    /// draws them in the window. See [`popup`](crate::popup) for more.
+    fn popup_mode(&self) -> PopupMode {
+        PopupMode::InWindow
+    }
+
+    /// The color space the app would like to render in, such as
+    /// [`ColorSpace::DisplayP3`] for the wider gamut of recent displays.
+    ///
+    /// This is called once, before the app starts handling events. The window
+    /// renders in sRGB, the default, if the platform can't show the requested space.
+    /// See [`color`](crate::color) for more.
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::Srgb
+    }
+
+    /// The direction the window's layouts run in, which is right to left for apps
+    /// in languages such as Arabic and Hebrew.
+    ///
+    /// This is called once, before the app starts handling events. See
+    /// [`direction`](crate::direction) for more.
+    fn layout_direction(&self) -> LayoutDirection {
+        LayoutDirection::LeftToRight
+    }
+
+    /// Whether to render in software if the GPU can't be used, rather than stopping
+    /// the app with an error.
+    ///
+    /// This is called when the GPU fails. The default falls back. See
+    /// [`render_diagnostics`](crate::render_diagnostics) for more.
+    ///
+    /// A software adapter is only chosen when the window's surface is first
+    /// created, by setting `WGPU_ADAPTER_NAME` until it is, since that is the only
+    /// way to choose one through vello. Changing the environment isn't safe while
+    /// other threads read it, so apps which fall back should start the event loop
+    /// before starting threads of their own.
+    fn software_fallback(&self) -> bool {
+        true
+    }
+
+    /// The icons used by widgets in the app.
+    ///
+    /// This is called once, before the app starts handling events.
+    fn icons(&self) -> IconSet {
+        IconSet::default()
+    }
+
+    /// The backend which plays the sounds and haptic effects requested by widgets.
+    ///
+    /// This is called once, before the app starts handling events. The default
+    /// backend doesn't play anything.
+    fn feedback(&mut self) -> Box<dyn FeedbackBackend> {
+        Box::new(NoFeedback)
+    }
+
+    /// The backend which shows the app's icon, badge and progress in the dock or
+    /// taskbar.
+    ///
+    /// This is called once, before the app starts handling events. The default
+    /// backend doesn't show anything. See [`taskbar`](crate::taskbar) for more.
+    fn taskbar(&mut self) -> Box<dyn TaskbarBackend> {
+        Box::new(NoTaskbar)
+    }
+
+    /// The spellchecker used by text widgets which have spellchecking turned on.
+    ///
+    /// This is called once, before the app starts handling events. The default
+    /// accepts every word. See [`spellcheck`](crate::spellcheck) for more.
+    fn spell_checker(&mut self) -> Box<dyn SpellChecker> {
+        Box::new(NoSpellChecker)
+    }
+
+    /// The key under which the window's position and size are saved when it is
+    /// closed, to be restored when it is next opened, or `None` to not save them.
+    ///
+    /// The key should be unique to the app, such as its name, as it names the
+    /// directory they are saved in. Only [`run`](crate::event_loop_runner::run)
+    /// restores them. See [`persistence`](crate::persistence) for more.
+    fn window_geometry_key(&self) -> Option<String> {
+        None
+    }
+
+    /// How long the user can go without using the mouse or the keyboard before
+    /// [`on_activity`](Self::on_activity) is told they're idle.
+    ///
+    /// This is called once, before the app starts handling events. By default the
+    /// app isn't told when the user is idle.
+    fn idle_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Log widgets which spend longer than this handling an event, laying out or
+    /// painting, to find the ones which make frames late.
+    ///
+    /// This is called once, before the app starts handling events. By default
+    /// widgets aren't timed. See [`widget_timings`](crate::widget_timings) for more.
+    fn slow_widget_threshold(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Tint the window to show the order widgets are painted in, or how much they
+    /// paint over each other.
+    ///
+    /// This is called once, before the app starts handling events. By default this
+    /// is read from the `MASONRY_PAINT_DEBUG` environment variable. See
+    /// [`paint_debug`](crate::paint_debug) for more.
+    fn paint_debug(&self) -> PaintDebug {
+        PaintDebug::from_env()
+    }
+
+    /// Called when the user becomes idle, and when they become active again.
+    ///
+    /// See [`idle_timeout`](Self::idle_timeout).
+    fn on_activity(&mut self, ctx: &mut DriverCtx<'_>, event: ActivityEvent) {
+        let _ = (ctx, event);
+    }
 }
 
 impl<'a> DriverCtx<'a> {
@@ -20,4 +181,49 @@ impl<'a> DriverCtx<'a> {
     pub fn get_root<W: Widget>(&mut self) -> WidgetMut<'_, W> {
         self.main_root_widget.downcast()
     }
+
+    /// A handle which wakes `widget` up from any thread; see [`wake`](crate::wake).
+    pub fn wake_handle(&self, widget: WidgetId) -> WakeHandle {
+        self.main_root_widget.ctx.global_state.wake_handle(widget)
+    }
+
+    /// Set the icon of the window and of the app's dock or taskbar entry, or go
+    /// back to the default icon if `icon` is `None`.
+    pub fn set_icon(&mut self, icon: Option<AppIcon>) {
+        self.push_signal(RenderRootSignal::SetIcon(icon));
+    }
+
+    /// Show `count` on the app's dock or taskbar icon, or no badge if it is `None`.
+    ///
+    /// This does nothing unless the app's [`TaskbarBackend`] shows badges.
+    pub fn set_badge_count(&mut self, count: Option<u32>) {
+        self.push_signal(RenderRootSignal::SetBadgeCount(count));
+    }
+
+    /// Show the progress of a long task on the app's dock or taskbar icon.
+    ///
+    /// This does nothing unless the app's [`TaskbarBackend`] shows progress.
+    pub fn set_progress(&mut self, progress: TaskbarProgress) {
+        self.push_signal(RenderRootSignal::SetProgress(progress));
+    }
+
+    /// Change how the window sits among the other windows on the screen, such as to
+    /// keep it above them.
+    pub fn set_window_kind(&mut self, kind: WindowKind) {
+        self.push_signal(RenderRootSignal::SetWindowKind(kind));
+    }
+
+    /// What the app renders with, and why it fell back to software rendering if
+    /// it did.
+    pub fn render_diagnostics(&self) -> &RenderDiagnostics {
+        &self.main_root_widget.ctx.global_state.render_diagnostics
+    }
+
+    fn push_signal(&mut self, signal: RenderRootSignal) {
+        self.main_root_widget
+            .ctx
+            .global_state
+            .signal_queue
+            .push_back(signal);
+    }
 }
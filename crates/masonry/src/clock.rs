@@ -0,0 +1,77 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The time as seen by widgets.
+//!
+//! Animation frames, drag velocities and double clicks are measured with the
+//! [`Clock`] of the render root, which widgets read with `ctx.now()`, rather than
+//! with [`Instant::now`]. Tests can then use a manual clock, and advance it to
+//! check animations and debounces deterministically instead of sleeping. The
+//! [`TestHarness`](crate::testing::TestHarness) uses one by default.
+//!
+//! Profiling, such as the [frame statistics](crate::frame_stats) and the budget of
+//! idle work, always uses the system clock, since it measures how long the work
+//! actually takes.
+
+use std::time::Duration;
+
+use instant::Instant;
+
+/// The source of the current time of a render root.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Clock {
+    /// The current time of a manual clock, or `None` for the system clock.
+    manual: Option<Instant>,
+}
+
+impl Clock {
+    /// The system clock, which is the default.
+    pub fn system() -> Self {
+        Clock { manual: None }
+    }
+
+    /// A clock which starts at `start`, and only moves when it is
+    /// [advanced](Self::advance).
+    pub fn manual(start: Instant) -> Self {
+        Clock {
+            manual: Some(start),
+        }
+    }
+
+    /// Whether this clock only moves when it is advanced.
+    pub fn is_manual(&self) -> bool {
+        self.manual.is_some()
+    }
+
+    /// The current time.
+    pub fn now(&self) -> Instant {
+        self.manual.unwrap_or_else(Instant::now)
+    }
+
+    /// Move a manual clock forward by `duration`.
+    ///
+    /// The system clock can't be moved, so this does nothing for it.
+    pub fn advance(&mut self, duration: Duration) {
+        if let Some(now) = &mut self.manual {
+            *now += duration;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_only_moves_when_advanced() {
+        let start = Instant::now();
+        let mut clock = Clock::manual(start);
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::from_millis(16));
+        assert_eq!(clock.now(), start + Duration::from_millis(16));
+
+        let mut system = Clock::system();
+        system.advance(Duration::from_secs(60));
+        assert!(system.now() < start + Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Color spaces, and colors which know which space they are in.
+//!
+//! A [`Color`] is a gamma-encoded sRGB color, which is what Vello draws with. Vello
+//! blends and interpolates these encoded values directly, so, of two colors, the
+//! midpoint of a gradient between them is darker than their average in light,
+//! and the gradient can look muddy. [`linear_light_stops`] works around this by
+//! adding stops which follow the gradient in linear light, and the built-in
+//! widgets' gradients use it.
+//!
+//! A [`ManagedColor`] is a color in a given [`ColorSpace`], such as the wider gamut
+//! of Display P3. It is resolved to a [`Color`] for the space the window renders
+//! in with [`PaintCtx::resolve_color`], which converts it, and clips it to the
+//! gamut of sRGB if needed.
+//!
+//! The space the window renders in is chosen by the app with
+//! [`AppDriver::color_space`]. The surface is only rendered in a wide-gamut space
+//! if the platform can show it as such, which isn't yet the case anywhere, since
+//! `wgpu` can't choose the color space of a surface; other windows render in sRGB.
+//!
+//! [`PaintCtx::resolve_color`]: crate::PaintCtx::resolve_color
+//! [`AppDriver::color_space`]: crate::app_driver::AppDriver::color_space
+
+use vello::peniko::{Color, ColorStop, ColorStops, ColorStopsSource};
+
+/// The number of stops added between two stops by [`linear_light_stops`].
+const LINEAR_LIGHT_STEPS: usize = 8;
+
+/// The RGB color spaces Masonry can render in.
+///
+/// Both use the sRGB transfer function and white point, and differ in their
+/// primaries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// The standard color space of the web and most displays.
+    #[default]
+    Srgb,
+    /// The wider gamut of many recent displays, which has more saturated reds and
+    /// greens than sRGB.
+    DisplayP3,
+}
+
+impl ColorSpace {
+    /// The matrix from linear sRGB to this space, in linear light.
+    fn matrix_from_linear_srgb(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorSpace::Srgb => IDENTITY,
+            ColorSpace::DisplayP3 => SRGB_TO_DISPLAY_P3,
+        }
+    }
+
+    /// The matrix from this space to linear sRGB, in linear light.
+    fn matrix_to_linear_srgb(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorSpace::Srgb => IDENTITY,
+            ColorSpace::DisplayP3 => DISPLAY_P3_TO_SRGB,
+        }
+    }
+}
+
+const IDENTITY: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+const SRGB_TO_DISPLAY_P3: [[f32; 3]; 3] = [
+    [0.822_462_1, 0.177_538, 0.0],
+    [0.033_194_1, 0.966_805_8, 0.0],
+    [0.017_082_7, 0.072_397_4, 0.910_519_9],
+];
+const DISPLAY_P3_TO_SRGB: [[f32; 3]; 3] = [
+    [1.224_940_1, -0.224_940_4, 0.0],
+    [-0.042_056_9, 1.042_057_1, 0.0],
+    [-0.019_637_6, -0.078_636_1, 1.098_273_5],
+];
+
+/// A color in a given [`ColorSpace`].
+///
+/// The components are gamma-encoded, between 0 and 1, as in CSS's `color()`
+/// function, and the alpha isn't premultiplied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ManagedColor {
+    pub space: ColorSpace,
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl ManagedColor {
+    /// An opaque sRGB color.
+    pub const fn srgb(r: f32, g: f32, b: f32) -> Self {
+        ManagedColor {
+            space: ColorSpace::Srgb,
+            r,
+            g,
+            b,
+            a: 1.0,
+        }
+    }
+
+    /// An opaque Display P3 color.
+    pub const fn display_p3(r: f32, g: f32, b: f32) -> Self {
+        ManagedColor {
+            space: ColorSpace::DisplayP3,
+            r,
+            g,
+            b,
+            a: 1.0,
+        }
+    }
+
+    /// The same color, with the given alpha.
+    pub const fn with_alpha(mut self, a: f32) -> Self {
+        self.a = a;
+        self
+    }
+
+    /// The components in linear light, in this color's space.
+    pub fn to_linear(self) -> [f32; 3] {
+        [self.r, self.g, self.b].map(decode)
+    }
+
+    /// The same color in `space`, clipped to its gamut.
+    pub fn convert(self, space: ColorSpace) -> Self {
+        if space == self.space {
+            return self;
+        }
+        let linear_srgb = multiply(self.space.matrix_to_linear_srgb(), self.to_linear());
+        let [r, g, b] = multiply(space.matrix_from_linear_srgb(), linear_srgb).map(encode);
+        ManagedColor {
+            space,
+            r,
+            g,
+            b,
+            a: self.a,
+        }
+    }
+
+    /// The color Vello draws with, for a surface which renders in `space`.
+    pub fn to_color(self, space: ColorSpace) -> Color {
+        let color = self.convert(space);
+        Color::rgba(
+            color.r.into(),
+            color.g.into(),
+            color.b.into(),
+            color.a.into(),
+        )
+    }
+}
+
+impl From<Color> for ManagedColor {
+    fn from(color: Color) -> Self {
+        let channel = |value: u8| f32::from(value) / 255.0;
+        ManagedColor::srgb(channel(color.r), channel(color.g), channel(color.b))
+            .with_alpha(channel(color.a))
+    }
+}
+
+/// The stops of a gradient between `stops` which interpolates in linear light,
+/// rather than between the encoded colors.
+///
+/// Vello interpolates between the encoded colors, so stops are added between each
+/// pair of stops, with their colors in linear light.
+pub fn linear_light_stops(stops: impl ColorStopsSource) -> ColorStops {
+    let mut given = ColorStops::new();
+    stops.collect_stops(&mut given);
+    let mut result = ColorStops::new();
+    for (index, stop) in given.iter().enumerate() {
+        result.push(*stop);
+        let Some(next) = given.get(index + 1) else {
+            break;
+        };
+        let (from, to) = (
+            ManagedColor::from(stop.color),
+            ManagedColor::from(next.color),
+        );
+        let (from_linear, to_linear) = (from.to_linear(), to.to_linear());
+        for step in 1..LINEAR_LIGHT_STEPS {
+            let t = step as f32 / LINEAR_LIGHT_STEPS as f32;
+            let mix = |a: f32, b: f32| a + (b - a) * t;
+            let [r, g, b] = [0, 1, 2].map(|i| encode(mix(from_linear[i], to_linear[i])));
+            let color = ManagedColor::srgb(r, g, b).with_alpha(mix(from.a, to.a));
+            result.push(ColorStop {
+                offset: mix(stop.offset, next.offset),
+                color: color.to_color(ColorSpace::Srgb),
+            });
+        }
+    }
+    result
+}
+
+/// Decode a component with the sRGB transfer function, to linear light.
+fn decode(value: f32) -> f32 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a component in linear light with the sRGB transfer function, clipping it
+/// to the gamut.
+fn encode(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn multiply(matrix: [[f32; 3]; 3], vector: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_convert_between_spaces() {
+        let orange = ManagedColor::srgb(1.0, 0.5, 0.25);
+        let back = orange
+            .convert(ColorSpace::DisplayP3)
+            .convert(ColorSpace::Srgb);
+        for (a, b) in [(orange.r, back.r), (orange.g, back.g), (orange.b, back.b)] {
+            assert!((a - b).abs() < 1e-3, "{orange:?} came back as {back:?}");
+        }
+        // Pure P3 red is outside of sRGB, so it's clipped
+        let red = ManagedColor::display_p3(1.0, 0.0, 0.0).to_color(ColorSpace::Srgb);
+        assert_eq!((red.r, red.g, red.b), (255, 0, 0));
+        let white = ManagedColor::srgb(1.0, 1.0, 1.0).convert(ColorSpace::DisplayP3);
+        assert!((white.r - 1.0).abs() < 1e-3 && (white.b - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn gradients_interpolate_in_linear_light() {
+        let stops = linear_light_stops([Color::BLACK, Color::WHITE]);
+        assert_eq!(stops.len(), LINEAR_LIGHT_STEPS + 1);
+        assert_eq!(stops.first().unwrap().color, Color::BLACK);
+        assert_eq!(stops.last().unwrap().color, Color::WHITE);
+        // Half of the light is brighter than the encoded midpoint
+        let mid = stops[LINEAR_LIGHT_STEPS / 2];
+        assert_eq!(mid.offset, 0.5);
+        assert_eq!(mid.color.r, 188);
+    }
+}
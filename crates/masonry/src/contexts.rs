@@ -4,20 +4,47 @@
 //! The context types that are passed into various widget methods.
 
 use std::any::Any;
+#[cfg(feature = "effects")]
+use std::cell::RefCell;
+#[cfg(feature = "effects")]
+use std::rc::Rc;
 use std::time::Duration;
 
-use accesskit::{NodeBuilder, TreeUpdate};
+use accesskit::{NodeBuilder, NodeId, TreeUpdate};
+use instant::Instant;
+use kurbo::Line;
 use parley::FontContext;
 use tracing::{trace, warn};
+use vello::peniko::{Color, Mix};
+use vello::Scene;
 use winit::dpi::LogicalPosition;
 use winit::window::CursorIcon;
 
 use crate::action::Action;
+use crate::color::{ColorSpace, ManagedColor};
+use crate::conventions::PlatformConventions;
+use crate::direction::LayoutDirection;
+#[cfg(feature = "effects")]
+use crate::effects::{LayerEffect, PostProcess};
+use crate::feedback::{Feedback, HapticKind, SoundKind};
+use crate::frame_stats::FrameStats;
+use crate::gpu::{GpuResource, GpuResourceId};
+use crate::icon::IconSet;
+use crate::interaction::InteractionSettings;
+use crate::keymap::Keymap;
+use crate::pixel_snap::{snap_line, snap_stroke, snap_to_pixel};
+use crate::popup::{place_popup, PopupMode, PopupPlacement, PopupRequest};
 use crate::promise::PromiseToken;
-use crate::render_root::{RenderRootSignal, RenderRootState};
-use crate::text_helpers::{ImeChangeSignal, TextFieldRegistration};
-use crate::widget::{CursorChange, WidgetMut, WidgetState};
-use crate::{Insets, Point, Rect, Size, Widget, WidgetId, WidgetPod};
+use crate::raster_cache::{image_transform, raster_size};
+use crate::render_root::{Overlay, RenderRootSignal, RenderRootState};
+use crate::spellcheck::SpellChecker;
+use crate::text_helpers::{ImeChangeSignal, InputHint, TextFieldRegistration};
+use crate::wake::WakeHandle;
+use crate::widget::{update_hot_state, CursorChange, WidgetMut, WidgetRef, WidgetState};
+use crate::widget_tree::{downcast_widget_mut, ArenaMut, ArenaMutChildren, WidgetNode};
+use crate::{
+    Affine, BoxConstraints, Insets, LifeCycle, Point, Rect, Size, Vec2, Widget, WidgetId, WidgetPod,
+};
 
 /// A macro for implementing methods on multiple contexts.
 ///
@@ -45,6 +72,7 @@ pub struct WidgetCtx<'a> {
     pub(crate) global_state: &'a mut RenderRootState,
     pub(crate) parent_widget_state: &'a mut WidgetState,
     pub(crate) widget_state: &'a mut WidgetState,
+    pub(crate) widget_children: ArenaMutChildren<'a, WidgetNode>,
 }
 
 /// A context provided to event handling methods of widgets.
@@ -54,6 +82,7 @@ pub struct WidgetCtx<'a> {
 pub struct EventCtx<'a> {
     pub(crate) global_state: &'a mut RenderRootState,
     pub(crate) widget_state: &'a mut WidgetState,
+    pub(crate) widget_children: ArenaMutChildren<'a, WidgetNode>,
     pub(crate) is_handled: bool,
     pub(crate) request_pan_to_child: Option<Rect>,
 }
@@ -64,6 +93,7 @@ pub struct EventCtx<'a> {
 pub struct LifeCycleCtx<'a> {
     pub(crate) global_state: &'a mut RenderRootState,
     pub(crate) widget_state: &'a mut WidgetState,
+    pub(crate) widget_children: ArenaMutChildren<'a, WidgetNode>,
 }
 
 /// A context provided to layout handling methods of widgets.
@@ -74,6 +104,7 @@ pub struct LifeCycleCtx<'a> {
 pub struct LayoutCtx<'a> {
     pub(crate) global_state: &'a mut RenderRootState,
     pub(crate) widget_state: &'a mut WidgetState,
+    pub(crate) widget_children: ArenaMutChildren<'a, WidgetNode>,
     pub(crate) mouse_pos: Option<Point>,
 }
 
@@ -81,15 +112,21 @@ pub struct LayoutCtx<'a> {
 pub struct PaintCtx<'a> {
     pub(crate) global_state: &'a mut RenderRootState,
     pub(crate) widget_state: &'a WidgetState,
+    pub(crate) widget_children: ArenaMutChildren<'a, WidgetNode>,
+    /// The region of the widget which is visible, in its local coordinates.
+    pub(crate) clip: Rect,
     /// The approximate depth in the tree at the time of painting.
     pub(crate) depth: u32,
     pub(crate) debug_paint: bool,
     pub(crate) debug_widget: bool,
+    /// The area the widget reported drawing in, in its local coordinates.
+    pub(crate) painted_bounds: Option<Rect>,
 }
 
 pub struct AccessCtx<'a> {
     pub(crate) global_state: &'a mut RenderRootState,
     pub(crate) widget_state: &'a WidgetState,
+    pub(crate) widget_children: ArenaMutChildren<'a, WidgetNode>,
     pub(crate) tree_update: &'a mut TreeUpdate,
     pub(crate) current_node: NodeBuilder,
     pub(crate) rebuild_all: bool,
@@ -117,6 +154,73 @@ impl_context_method!(
             self.widget_state.id
         }
 
+        /// Whether the focus is being shown with a focus ring, because the user last
+        /// used the keyboard rather than the pointer.
+        ///
+        /// Widgets which draw their own focus indicator can use this to only draw it
+        /// when the focus ring would be shown.
+        pub fn is_focus_visible(&self) -> bool {
+            self.global_state.focus_visible
+        }
+
+        /// The statistics of the last frame drawn, such as how long it took to lay out
+        /// and paint.
+        pub fn frame_stats(&self) -> &FrameStats {
+            &self.global_state.frame_stats.last
+        }
+
+        /// The app's keyboard shortcuts.
+        ///
+        /// Widgets which react to shortcuts should look them up here, so that
+        /// they can be customized by the app.
+        pub fn keymap(&self) -> &Keymap {
+            &self.global_state.keymap
+        }
+
+        /// The timings and distances which decide how input is interpreted, such as
+        /// how far the pointer must move to start a drag.
+        pub fn interaction_settings(&self) -> &InteractionSettings {
+            &self.global_state.interaction
+        }
+
+        /// The current time, per the render root's [`Clock`](crate::clock::Clock).
+        ///
+        /// Widgets should measure time with this rather than with `Instant::now`,
+        /// so that tests can control it.
+        pub fn now(&self) -> Instant {
+            self.global_state.clock.now()
+        }
+
+        /// Where popups are shown, in the window or in windows of their own.
+        pub fn popup_mode(&self) -> PopupMode {
+            self.global_state.popup_mode
+        }
+
+        /// The platform conventions the app follows, such as which modifier
+        /// shortcuts are held with.
+        pub fn conventions(&self) -> &PlatformConventions {
+            &self.global_state.conventions
+        }
+
+        /// The app's spellchecker, which accepts every word unless the app provides one.
+        pub fn spell_checker(&self) -> &dyn SpellChecker {
+            &*self.global_state.spell_checker
+        }
+
+        /// The app's icons.
+        ///
+        /// Widgets which show icons should look them up here by name, so that the
+        /// app can swap icon packs.
+        pub fn icons(&self) -> &IconSet {
+            &self.global_state.icons
+        }
+
+        /// A handle which wakes this widget up from any thread, with a
+        /// [`LifeCycle::Wake`] event; see [`wake`](crate::wake).
+        pub fn wake_handle(&self) -> WakeHandle {
+            self.global_state.wake_handle(self.widget_id())
+        }
+
         /// Skip iterating over the given child.
         ///
         /// Normally, container widgets are supposed to iterate over each of their
@@ -125,8 +229,50 @@ impl_context_method!(
         ///
         /// This tells the framework that a child was deliberately skipped.
         // TODO - see event flow tutorial - See issue #5
-        pub fn skip_child(&self, child: &mut WidgetPod<impl Widget>) {
-            child.mark_as_visited();
+        pub fn skip_child(&mut self, child: &mut WidgetPod<impl Widget>) {
+            child
+                .node_mut(self.widget_children.reborrow_mut())
+                .item
+                .state
+                .mark_as_visited(true);
+        }
+
+        /// Return a [`WidgetRef`] to a child widget.
+        pub fn get_ref<'c, Child: Widget>(
+            &'c self,
+            child: &'c WidgetPod<Child>,
+        ) -> WidgetRef<'c, Child> {
+            child.as_ref_in(self.widget_children.as_ref())
+        }
+
+        /// The layout rect of a child widget, in this widget's coordinate space.
+        ///
+        /// See [`WidgetState::layout_rect`].
+        pub fn child_layout_rect(&self, child: &WidgetPod<impl Widget>) -> Rect {
+            self.get_ref(child).state().layout_rect()
+        }
+
+        /// The paint rect of a child widget, in this widget's coordinate space.
+        ///
+        /// See [`WidgetState::paint_rect`].
+        pub fn child_paint_rect(&self, child: &WidgetPod<impl Widget>) -> Rect {
+            self.get_ref(child).state().paint_rect()
+        }
+
+        /// The distance from the bottom of a child widget to its baseline.
+        ///
+        /// See [`LayoutCtx::set_baseline_offset`].
+        pub fn child_baseline_offset(&self, child: &WidgetPod<impl Widget>) -> f64 {
+            self.get_ref(child).state().baseline_offset
+        }
+
+        /// Get a GPU resource registered with [`register_gpu_resource`].
+        ///
+        /// Returns `None` if the resource was unregistered or isn't an `R`.
+        ///
+        /// [`register_gpu_resource`]: LifeCycleCtx::register_gpu_resource
+        pub fn gpu_resource_mut<R: GpuResource>(&mut self, id: GpuResourceId) -> Option<&mut R> {
+            self.global_state.gpu_resources.get_mut(id)
         }
     }
 );
@@ -253,6 +399,16 @@ impl_context_method!(
 );
 
 impl_context_method!(EventCtx<'_>, {
+    /// When the render root received the event being handled, per the system clock.
+    ///
+    /// This is for measuring latency, such as in [`crate::frame_stats`]; widgets
+    /// should use [`now`](Self::now) for anything the user sees, like animations.
+    /// Returns `None` when no event is being handled, such as while the app edits
+    /// the widget tree.
+    pub fn event_timestamp(&self) -> Option<Instant> {
+        self.global_state.event_timestamp
+    }
+
     /// Set the cursor icon.
     ///
     /// This setting will be retained until [`clear_cursor`] is called, but it will only take
@@ -303,14 +459,16 @@ impl<'a> WidgetCtx<'a> {
         &'c mut self,
         child: &'c mut WidgetPod<Child>,
     ) -> WidgetMut<'c, Child> {
+        let ArenaMut { item, children, .. } = child.node_mut(self.widget_children.reborrow_mut());
         let child_ctx = WidgetCtx {
             global_state: self.global_state,
             parent_widget_state: self.widget_state,
-            widget_state: &mut child.state,
+            widget_state: &mut item.state,
+            widget_children: children,
         };
         WidgetMut {
             ctx: child_ctx,
-            widget: &mut child.inner,
+            widget: downcast_widget_mut(&mut item.widget),
         }
     }
 }
@@ -322,14 +480,16 @@ impl<'a> EventCtx<'a> {
         &'c mut self,
         child: &'c mut WidgetPod<Child>,
     ) -> WidgetMut<'c, Child> {
+        let ArenaMut { item, children, .. } = child.node_mut(self.widget_children.reborrow_mut());
         let child_ctx = WidgetCtx {
             global_state: self.global_state,
             parent_widget_state: self.widget_state,
-            widget_state: &mut child.state,
+            widget_state: &mut item.state,
+            widget_children: children,
         };
         WidgetMut {
             ctx: child_ctx,
-            widget: &mut child.inner,
+            widget: downcast_widget_mut(&mut item.widget),
         }
     }
 }
@@ -341,14 +501,37 @@ impl<'a> LifeCycleCtx<'a> {
         &'c mut self,
         child: &'c mut WidgetPod<Child>,
     ) -> WidgetMut<'c, Child> {
+        let ArenaMut { item, children, .. } = child.node_mut(self.widget_children.reborrow_mut());
         let child_ctx = WidgetCtx {
             global_state: self.global_state,
             parent_widget_state: self.widget_state,
-            widget_state: &mut child.state,
+            widget_state: &mut item.state,
+            widget_children: children,
         };
         WidgetMut {
             ctx: child_ctx,
-            widget: &mut child.inner,
+            widget: downcast_widget_mut(&mut item.widget),
+        }
+    }
+}
+
+impl<'a> LayoutCtx<'a> {
+    /// Return a [`WidgetMut`] to a child widget.
+    // FIXME - Assert that child's parent is self
+    pub fn get_mut<'c, Child: Widget>(
+        &'c mut self,
+        child: &'c mut WidgetPod<Child>,
+    ) -> WidgetMut<'c, Child> {
+        let ArenaMut { item, children, .. } = child.node_mut(self.widget_children.reborrow_mut());
+        let child_ctx = WidgetCtx {
+            global_state: self.global_state,
+            parent_widget_state: self.widget_state,
+            widget_state: &mut item.state,
+            widget_children: children,
+        };
+        WidgetMut {
+            ctx: child_ctx,
+            widget: downcast_widget_mut(&mut item.widget),
         }
     }
 }
@@ -388,9 +571,16 @@ impl_context_method!(WidgetCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
         self.widget_state.request_anim = true;
     }
 
+    /// Request a [`LifeCycle::Idle`] event, the next time the app has nothing else
+    /// to do.
+    pub fn request_idle_work(&mut self) {
+        trace!("request_idle_work");
+        self.widget_state.request_idle = true;
+    }
+
     /// Indicate that your children have changed.
     ///
-    /// Widgets must call this method after adding a new child or removing a child.
+    /// Widgets must call this method after adding, removing or reordering children.
     pub fn children_changed(&mut self) {
         trace!("children_changed");
         self.widget_state.children_changed = true;
@@ -398,6 +588,26 @@ impl_context_method!(WidgetCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
         self.request_layout();
     }
 
+    /// Remove `child` from the widget tree.
+    ///
+    /// This sends [`LifeCycle::WidgetRemoved`] to `child` and its descendants
+    /// before dropping them. Widgets should use this instead of simply dropping
+    /// their children.
+    pub fn remove_child(&mut self, mut child: WidgetPod<impl Widget>) {
+        if child.is_inserted() {
+            let mut ctx = LifeCycleCtx {
+                global_state: self.global_state,
+                widget_state: self.widget_state,
+                widget_children: self.widget_children.reborrow_mut(),
+            };
+            child.lifecycle(&mut ctx, &LifeCycle::WidgetRemoved);
+            self.widget_children.remove_child(child.id());
+        }
+        self.children_changed();
+        // The child's accessibility node goes away with our list of children
+        self.request_accessibility_update();
+    }
+
     /// Set the disabled state for this widget.
     ///
     /// Setting this to `false` does not mean a widget is not still disabled; for instance it may
@@ -417,10 +627,42 @@ impl_context_method!(WidgetCtx<'_>, EventCtx<'_>, LifeCycleCtx<'_>, {
     ///
     /// **Note:** Stashed widgets are a WIP feature
     pub fn set_stashed(&mut self, child: &mut WidgetPod<impl Widget>, stashed: bool) {
-        child.state.is_stashed = stashed;
+        child
+            .node_mut(self.widget_children.reborrow_mut())
+            .item
+            .state
+            .is_stashed = stashed;
         self.children_changed();
     }
 
+    /// Request keyboard focus.
+    ///
+    /// Because only one widget can be focused at a time, multiple focus requests
+    /// from different widgets during a single event cycle means that the last
+    /// widget that requests focus will override the previous requests.
+    ///
+    /// See [`is_focused`](Self::is_focused) for more information about focus.
+    pub fn request_focus(&mut self) {
+        trace!("request_focus");
+        // We need to send the request even if we're currently focused,
+        // because we may have a sibling widget that already requested focus
+        // and we have no way of knowing that yet. We need to override that
+        // to deliver on the "last focus request wins" promise.
+        let id = self.widget_id();
+        self.global_state.next_focused_widget = Some(id);
+    }
+
+    /// Transfer focus to the widget with the given `WidgetId`.
+    ///
+    /// This can be called when handling [`LifeCycle::WidgetAdded`] to focus a widget
+    /// as soon as it is added, such as the text box of a dialog.
+    ///
+    /// See [`is_focused`](Self::is_focused) for more information about focus.
+    pub fn set_focus(&mut self, target: WidgetId) {
+        trace!("set_focus target={:?}", target);
+        self.global_state.next_focused_widget = Some(target);
+    }
+
     #[allow(unused)]
     /// Indicate that text input state has changed.
     ///
@@ -439,6 +681,22 @@ impl_context_method!(
     LifeCycleCtx<'_>,
     LayoutCtx<'_>,
     {
+        /// Register a resource which this widget keeps on the GPU.
+        ///
+        /// The resource is created on the renderer's device before the next frame,
+        /// released and created again if the device is lost, and dropped when this
+        /// widget is removed from the tree. See the [`gpu`](crate::gpu) module.
+        pub fn register_gpu_resource(&mut self, resource: impl GpuResource) -> GpuResourceId {
+            self.global_state
+                .gpu_resources
+                .register(self.widget_state.id, Box::new(resource))
+        }
+
+        /// Drop a GPU resource before this widget is removed.
+        pub fn unregister_gpu_resource(&mut self, id: GpuResourceId) {
+            self.global_state.gpu_resources.unregister(id);
+        }
+
         /// Submit an [`Action`].
         ///
         /// Note: Actions are still a WIP feature.
@@ -449,6 +707,30 @@ impl_context_method!(
                 .push_back(RenderRootSignal::Action(action, self.widget_state.id));
         }
 
+        /// Ask the app to play a system sound, such as to acknowledge a click.
+        ///
+        /// This does nothing unless the app has a [`FeedbackBackend`] which plays
+        /// sounds; see the [`feedback`](crate::feedback) module.
+        ///
+        /// [`FeedbackBackend`]: crate::feedback::FeedbackBackend
+        pub fn play_system_sound(&mut self, sound: SoundKind) {
+            trace!("play_system_sound {:?}", sound);
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::Feedback(Feedback::Sound(sound)));
+        }
+
+        /// Ask the app to trigger a haptic effect, on platforms which have them.
+        ///
+        /// Like [`play_system_sound`](Self::play_system_sound), this does nothing
+        /// unless the app's feedback backend supports it.
+        pub fn trigger_haptic(&mut self, haptic: HapticKind) {
+            trace!("trigger_haptic {:?}", haptic);
+            self.global_state
+                .signal_queue
+                .push_back(RenderRootSignal::Feedback(Feedback::Haptic(haptic)));
+        }
+
         /// Run the provided function in the background.
         ///
         /// The function takes a [`WorkerCtx`] which it can use to
@@ -483,6 +765,48 @@ impl_context_method!(
         pub fn request_timer(&mut self, _deadline: Duration) -> TimerToken {
             todo!("request_timer");
         }
+
+        /// Set an extra transform for a child, applied when painting it.
+        ///
+        /// The transform is in the child's local coordinate space and is applied
+        /// on top of the translation to the origin set by [`place_child`]. It
+        /// does not affect layout or hit-testing, which makes it suitable for
+        /// animations (eg dragging a child, or animating a reorder).
+        ///
+        /// [`place_child`]: LayoutCtx::place_child
+        pub fn set_child_transform(
+            &mut self,
+            child: &mut WidgetPod<impl Widget>,
+            transform: Affine,
+        ) {
+            let child_state = &mut child
+                .node_mut(self.widget_children.reborrow_mut())
+                .item
+                .state;
+            if child_state.transform != transform {
+                trace!("set_child_transform {:?}", transform);
+                child_state.transform = transform;
+                self.widget_state.needs_paint = true;
+            }
+        }
+
+        /// Set the paint order of a child relative to its siblings.
+        ///
+        /// Children with a higher z-index are painted above those with a lower one,
+        /// and are preferred by the hit-testing of [`WidgetRef::get_child_at_pos`].
+        /// This only has an effect if the container paints its children with
+        /// [`PaintCtx::paint_children`]. Children default to a z-index of `0`.
+        pub fn set_child_z_index(&mut self, child: &mut WidgetPod<impl Widget>, z_index: i32) {
+            let child_state = &mut child
+                .node_mut(self.widget_children.reborrow_mut())
+                .item
+                .state;
+            if child_state.z_index != z_index {
+                trace!("set_child_z_index {}", z_index);
+                child_state.z_index = z_index;
+                self.widget_state.needs_paint = true;
+            }
+        }
     }
 );
 
@@ -516,31 +840,6 @@ impl EventCtx<'_> {
         self.is_handled
     }
 
-    /// Request keyboard focus.
-    ///
-    /// Because only one widget can be focused at a time, multiple focus requests
-    /// from different widgets during a single event cycle means that the last
-    /// widget that requests focus will override the previous requests.
-    ///
-    /// See [`is_focused`](Self::is_focused) for more information about focus.
-    pub fn request_focus(&mut self) {
-        trace!("request_focus");
-        // We need to send the request even if we're currently focused,
-        // because we may have a sibling widget that already requested focus
-        // and we have no way of knowing that yet. We need to override that
-        // to deliver on the "last focus request wins" promise.
-        let id = self.widget_id();
-        self.global_state.next_focused_widget = Some(id);
-    }
-
-    /// Transfer focus to the widget with the given `WidgetId`.
-    ///
-    /// See [`is_focused`](Self::is_focused) for more information about focus.
-    pub fn set_focus(&mut self, target: WidgetId) {
-        trace!("set_focus target={:?}", target);
-        self.global_state.next_focused_widget = Some(target);
-    }
-
     /// Give up focus.
     ///
     /// This should only be called by a widget that currently has focus.
@@ -561,18 +860,6 @@ impl EventCtx<'_> {
 }
 
 impl LifeCycleCtx<'_> {
-    /// Registers a child widget.
-    ///
-    /// This should only be called in response to a `LifeCycle::WidgetAdded` event.
-    ///
-    /// In general, you should not need to call this method; it is handled by
-    /// the `WidgetPod`.
-    // TODO - See issue #9
-    pub(crate) fn register_child(&mut self, child_id: WidgetId) {
-        trace!("register_child id={:?}", child_id);
-        self.widget_state.children.add(&child_id);
-    }
-
     /// Register this widget to be eligile to accept focus automatically.
     ///
     /// This should only be called in response to a [`LifeCycle::BuildFocusChain`] event.
@@ -593,6 +880,19 @@ impl LifeCycleCtx<'_> {
         self.widget_state.text_registrations.push(registration);
     }
 
+    /// Send the last pointer move again after this pass, to the widgets under the
+    /// pointer or to the active widget.
+    ///
+    /// This is for widgets which move their content under a pointer that isn't
+    /// moving, such as a [`Portal`](crate::widget::Portal) scrolling while text is
+    /// being selected by dragging past its edge, so that the content catches up
+    /// with where the pointer now is. The move is sent at the end of the current
+    /// or next animation frame.
+    pub fn request_pointer_move(&mut self) {
+        trace!("request_pointer_move");
+        self.global_state.pointer_move_requested = true;
+    }
+
     // TODO - remove - See issue #15
     /// Register this widget as a portal.
     ///
@@ -603,6 +903,77 @@ impl LifeCycleCtx<'_> {
 }
 
 impl LayoutCtx<'_> {
+    /// Request a paint pass, for widgets whose appearance changed during a layout
+    /// pass they didn't request, such as a popup moved by another one.
+    ///
+    /// Widgets are repainted after layout when they or their parent requested it,
+    /// or when their size changed.
+    pub fn request_paint(&mut self) {
+        trace!("request_paint");
+        self.widget_state.needs_paint = true;
+    }
+
+    /// Report where this widget's text caret is, in its own coordinate space, or
+    /// `None` if it has no caret.
+    ///
+    /// While the widget is focused, this is where the platform's IME shows its
+    /// candidate window, and what [`RenderRoot::caret_rect`] returns, so that tools
+    /// such as screen magnifiers can follow the caret. Text widgets should update
+    /// it in every layout pass, since the caret moves with the text.
+    ///
+    /// [`RenderRoot::caret_rect`]: crate::render_root::RenderRoot::caret_rect
+    pub fn set_caret_rect(&mut self, rect: Option<Rect>) {
+        self.widget_state.caret_rect = rect;
+    }
+
+    /// Report the kind of text this widget takes, or `None` if it isn't a text input.
+    ///
+    /// While the widget is focused, the hint is forwarded to the platform, which
+    /// can show a matching virtual keyboard or hide the IME's suggestions for
+    /// passwords.
+    pub fn set_input_hint(&mut self, hint: Option<InputHint>) {
+        self.widget_state.input_hint = hint;
+    }
+
+    /// The size of the window's content area.
+    ///
+    /// Widgets which position things in window coordinates, such as popovers, can
+    /// use this to keep them inside the window.
+    pub fn window_size(&self) -> Size {
+        self.global_state.window_size
+    }
+
+    /// Place a popup next to `request.anchor`, which is in this widget's coordinate space.
+    ///
+    /// The popup is kept inside the window, away from the popups already placed in this
+    /// layout pass, and aligned to device pixels; see [`place_popup`] for the details.
+    /// With [`PopupMode::NativeWindow`], it is kept on the screen instead, if the
+    /// screen is known. The returned rect is in this widget's coordinate space.
+    ///
+    /// This uses the widget's window origin from the previous layout pass, so a widget
+    /// which places popups should request another layout when it moves in the window,
+    /// as [`Popover`](crate::widget::Popover) does.
+    pub fn place_popup(&mut self, request: PopupRequest) -> PopupPlacement {
+        let offset = self.widget_state.window_origin().to_vec2();
+        let window_request = PopupRequest {
+            anchor: request.anchor + offset,
+            ..request
+        };
+        let bounds = match (self.global_state.popup_mode, self.global_state.screen_rect) {
+            (PopupMode::NativeWindow, Some(screen_rect)) => screen_rect,
+            _ => self.global_state.window_size.to_rect(),
+        };
+        let mut placement = place_popup(
+            &window_request,
+            bounds,
+            &self.global_state.popups,
+            self.global_state.scale_factor,
+        );
+        self.global_state.popups.push(placement.rect);
+        placement.rect = placement.rect - offset;
+        placement
+    }
+
     /// Set explicit paint [`Insets`] for this widget.
     ///
     /// You are not required to set explicit paint bounds unless you need
@@ -620,6 +991,49 @@ impl LayoutCtx<'_> {
         self.widget_state.paint_insets = insets.nonnegative();
     }
 
+    /// Lay out the content of a scrolling container, of which `viewport` is shown,
+    /// in the child's coordinates, such as the viewport of a [`Portal`].
+    ///
+    /// The child can read the offset with [`scroll_offset`](Self::scroll_offset) to
+    /// keep some of its own children in view, as [`Flex`] does with [`Sticky`] ones,
+    /// and the viewport with [`scroll_viewport`](Self::scroll_viewport) to only lay
+    /// out the children which are shown. The viewport may be larger than the
+    /// container ends up being, as its size isn't known until the child is laid out.
+    ///
+    /// [`Portal`]: crate::widget::Portal
+    /// [`Flex`]: crate::widget::Flex
+    /// [`Sticky`]: crate::widget::Sticky
+    pub fn layout_scrolled_child(
+        &mut self,
+        child: &mut WidgetPod<impl Widget>,
+        bc: &BoxConstraints,
+        viewport: Rect,
+    ) -> Size {
+        let outer = self
+            .global_state
+            .scroll_viewport
+            .replace((child.id(), viewport));
+        let size = child.layout(self, bc);
+        self.global_state.scroll_viewport = outer;
+        size
+    }
+
+    /// How far this widget is scrolled, if its parent laid it out with
+    /// [`layout_scrolled_child`](Self::layout_scrolled_child).
+    pub fn scroll_offset(&self) -> Option<Vec2> {
+        self.scroll_viewport()
+            .map(|viewport| viewport.origin().to_vec2())
+    }
+
+    /// The part of this widget which is shown, in its own coordinates, if its parent
+    /// laid it out with [`layout_scrolled_child`](Self::layout_scrolled_child).
+    pub fn scroll_viewport(&self) -> Option<Rect> {
+        match self.global_state.scroll_viewport {
+            Some((id, viewport)) if id == self.widget_state.id => Some(viewport),
+            _ => None,
+        }
+    }
+
     /// Set an explicit baseline position for this widget.
     ///
     /// The baseline position is used to align widgets that contain text,
@@ -634,31 +1048,62 @@ impl LayoutCtx<'_> {
         self.widget_state.baseline_offset = baseline;
     }
 
+    /// Mark child widget as stashed, from within `layout`.
+    ///
+    /// Unlike the other contexts' `set_stashed`, this doesn't request another
+    /// layout pass: the caller is expected to skip laying out stashed children.
+    ///
+    /// **Note:** Stashed widgets are a WIP feature
+    pub fn set_stashed(&mut self, child: &mut WidgetPod<impl Widget>, stashed: bool) {
+        child
+            .node_mut(self.widget_children.reborrow_mut())
+            .item
+            .state
+            .is_stashed = stashed;
+    }
+
+    /// Given this widget's layout size, determine the paint `Insets` it needs
+    /// for the paint rect of `child`.
+    ///
+    /// This lets a widget which manages a child propagate the child's paint
+    /// rect, if it extends beyond the bounds of the widget's layout rect. The
+    /// child must have been placed with [`place_child`](Self::place_child).
+    pub fn compute_insets_from_child(
+        &self,
+        child: &WidgetPod<impl Widget>,
+        my_size: Size,
+    ) -> Insets {
+        let parent_bounds = Rect::ZERO.with_size(my_size);
+        let union_paint_rect = self.child_paint_rect(child).union(parent_bounds);
+        union_paint_rect - parent_bounds
+    }
+
     /// Set the position of a child widget, in the paren't coordinate space. This
     /// will also implicitly change "hot" status and affect the parent's display rect.
     ///
     /// Container widgets must call this method with each non-stashed child in their
     /// layout method, after calling `child.layout(...)`.
     pub fn place_child(&mut self, child: &mut WidgetPod<impl Widget>, origin: Point) {
-        if origin != child.state.origin {
-            child.state.origin = origin;
-            child.state.needs_window_origin = true;
+        let mut node = child.node_mut(self.widget_children.reborrow_mut());
+        let child_state = &mut node.item.state;
+        if origin != child_state.origin {
+            child_state.origin = origin;
+            child_state.needs_window_origin = true;
+            // Our cached fragment has the child at its old position.
+            self.widget_state.needs_paint = true;
         }
-        child.state.is_expecting_place_child_call = false;
+        child_state.is_expecting_place_child_call = false;
 
-        self.widget_state.local_paint_rect =
-            self.widget_state.local_paint_rect.union(child.paint_rect());
+        self.widget_state.local_paint_rect = self
+            .widget_state
+            .local_paint_rect
+            .union(child_state.paint_rect());
 
         let mouse_pos = self.mouse_pos.map(|pos| LogicalPosition::new(pos.x, pos.y));
         // if the widget has moved, it may have moved under the mouse, in which
         // case we need to handle that.
-        if WidgetPod::update_hot_state(
-            &mut child.inner,
-            &mut child.state,
-            self.global_state,
-            mouse_pos,
-        ) {
-            self.widget_state.merge_up(&mut child.state);
+        if update_hot_state(&mut node, self.global_state, mouse_pos) {
+            self.widget_state.merge_up(&mut node.item.state);
         }
     }
 }
@@ -667,9 +1112,253 @@ impl_context_method!(LayoutCtx<'_>, PaintCtx<'_>, {
     pub fn font_ctx(&mut self) -> &mut FontContext {
         &mut self.global_state.font_context
     }
+
+    /// The direction the window's layouts run in.
+    ///
+    /// See [`direction`](crate::direction) for more.
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.global_state.layout_direction
+    }
 });
 
 impl PaintCtx<'_> {
+    /// The color space the window renders in.
+    pub fn color_space(&self) -> ColorSpace {
+        self.global_state.color_space
+    }
+
+    /// The color to draw `color` with, in the space the window renders in.
+    ///
+    /// Colors outside of the window's gamut are clipped to it.
+    pub fn resolve_color(&self, color: impl Into<ManagedColor>) -> Color {
+        color.into().to_color(self.global_state.color_space)
+    }
+
+    /// Paint above the rest of the window, in a layer of its own, such as for the
+    /// panel of a popover or a menu.
+    ///
+    /// `paint` draws in this widget's coordinates, and isn't clipped by its
+    /// ancestors. The overlay is cached separately from the main content: painting
+    /// it again doesn't repaint the widgets below it, and it's kept until this widget
+    /// is painted again. Overlays of nested widgets are painted above those of their
+    /// ancestors.
+    ///
+    /// The overlay is hidden while this widget is culled, so its paint rect should
+    /// include it, such as with [`LayoutCtx::set_paint_insets`].
+    pub fn paint_overlay(&mut self, paint: impl FnOnce(&mut PaintCtx, &mut Scene)) {
+        self.push_overlay(None, paint);
+    }
+
+    /// Paint a popup which covers `rect`, in this widget's coordinates, such as the
+    /// panel of a menu.
+    ///
+    /// This is the same as [`paint_overlay`](Self::paint_overlay), except that with
+    /// [`PopupMode::NativeWindow`] the popup is shown in a window of its own, which
+    /// covers `rect` and may extend past the main window. Only what is painted
+    /// inside `rect` is shown then.
+    pub fn paint_popup(&mut self, rect: Rect, paint: impl FnOnce(&mut PaintCtx, &mut Scene)) {
+        let native_rect = match self.global_state.popup_mode {
+            PopupMode::InWindow => None,
+            PopupMode::NativeWindow => Some(rect + self.widget_state.window_origin().to_vec2()),
+        };
+        self.push_overlay(native_rect, paint);
+    }
+
+    /// Paint with `paint`, and reuse an image of what it painted on later frames,
+    /// until a widget it painted is painted again.
+    ///
+    /// This is for expensive content which rarely changes, such as the children of
+    /// [`CachePaint`](crate::widget::CachePaint). Only what is painted inside this
+    /// widget's layout rect is kept. Overlays aren't cached.
+    pub fn paint_cached(
+        &mut self,
+        scene: &mut Scene,
+        paint: impl FnOnce(&mut PaintCtx, &mut Scene),
+    ) {
+        let id = self.widget_state.id;
+        let scale_factor = self.global_state.scale_factor;
+        let size = raster_size(self.widget_state.size(), scale_factor);
+        let painted_before = self.global_state.frame_stats.painted_widgets();
+        let mut content = Scene::new();
+        paint(self, &mut content);
+        let repainted = self.global_state.frame_stats.painted_widgets() != painted_before;
+
+        let cache = &mut self.global_state.raster_cache;
+        if !repainted {
+            if let Some(image) = cache.get(id, size) {
+                scene.draw_image(image, image_transform(scale_factor));
+                return;
+            }
+        }
+        if repainted || !cache.is_pending(id, size) {
+            let mut pixels = Scene::new();
+            pixels.append(&content, Some(Affine::scale(scale_factor)));
+            cache.invalidate(id, pixels, size);
+        }
+        // Until the image is ready, the content is drawn as it will be cached
+        let clip = self.widget_state.size().to_rect();
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &clip);
+        scene.append(&content, None);
+        scene.pop_layer();
+    }
+
+    fn push_overlay(
+        &mut self,
+        native_rect: Option<Rect>,
+        paint: impl FnOnce(&mut PaintCtx, &mut Scene),
+    ) {
+        let origin = self.widget_state.window_origin().to_vec2();
+        let clip = native_rect.unwrap_or(self.global_state.window_size.to_rect()) - origin;
+        let mut scene = Scene::new();
+        let mut ctx = PaintCtx {
+            global_state: self.global_state,
+            widget_state: self.widget_state,
+            widget_children: self.widget_children.reborrow_mut(),
+            clip,
+            depth: self.depth,
+            debug_paint: self.debug_paint,
+            debug_widget: self.debug_widget,
+            painted_bounds: None,
+        };
+        paint(&mut ctx, &mut scene);
+        self.global_state.overlays.push(Overlay {
+            owner: self.widget_state.id,
+            transform: Affine::translate(origin),
+            scene,
+            native_rect,
+            #[cfg(feature = "effects")]
+            effect: None,
+        });
+    }
+
+    /// Paint in a layer of its own, which `pass` is run over before it is drawn
+    /// over the window, such as to blur or desaturate it.
+    ///
+    /// `paint` draws in this widget's coordinates, and only what it draws inside
+    /// this widget's layout rect is kept. Like an overlay, the layer is shown above
+    /// the rest of the window. See [`effects`](crate::effects) for how it is rendered.
+    #[cfg(feature = "effects")]
+    pub fn paint_effect(
+        &mut self,
+        pass: Rc<RefCell<dyn PostProcess>>,
+        paint: impl FnOnce(&mut PaintCtx, &mut Scene),
+    ) {
+        self.push_effect(pass, false, paint);
+    }
+
+    /// Run `pass` over what is painted beneath this widget's layout rect, such as
+    /// to blur it, and paint over its output.
+    ///
+    /// This is the same as [`paint_effect`](Self::paint_effect), except that the
+    /// pass doesn't see what `paint` draws.
+    #[cfg(feature = "effects")]
+    pub fn paint_backdrop_effect(
+        &mut self,
+        pass: Rc<RefCell<dyn PostProcess>>,
+        paint: impl FnOnce(&mut PaintCtx, &mut Scene),
+    ) {
+        self.push_effect(pass, true, paint);
+    }
+
+    #[cfg(feature = "effects")]
+    fn push_effect(
+        &mut self,
+        pass: Rc<RefCell<dyn PostProcess>>,
+        backdrop: bool,
+        paint: impl FnOnce(&mut PaintCtx, &mut Scene),
+    ) {
+        let origin = self.widget_state.window_origin().to_vec2();
+        let rect = self.widget_state.size().to_rect() + origin;
+        self.push_overlay(None, paint);
+        if let Some(overlay) = self.global_state.overlays.last_mut() {
+            overlay.effect = Some(LayerEffect {
+                rect,
+                pass,
+                backdrop,
+            });
+        }
+    }
+
+    /// Report that the widget drew in `bounds`, in its local coordinates.
+    ///
+    /// Widgets which draw outside of their layout rect, such as a shadow, should
+    /// report it so that the region is repainted and isn't culled while it's on
+    /// screen. If the widget draws outside of its paint rect, the next layout pass
+    /// grows the paint rect to include it. Calling this more than once reports the
+    /// union of `bounds`.
+    pub fn set_painted_bounds(&mut self, bounds: Rect) {
+        self.painted_bounds = Some(match self.painted_bounds {
+            Some(painted) => painted.union(bounds),
+            None => bounds,
+        });
+    }
+
+    /// Paint the given children in ascending order of their z-index.
+    ///
+    /// Children with the same z-index are painted in the order they are given,
+    /// so a container which never calls [`set_child_z_index`] paints its children
+    /// the same way as calling [`WidgetPod::paint`] on each of them in turn.
+    ///
+    /// [`set_child_z_index`]: LayoutCtx::set_child_z_index
+    pub fn paint_children<'c, W: Widget>(
+        &mut self,
+        scene: &mut Scene,
+        children: impl IntoIterator<Item = &'c mut WidgetPod<W>>,
+    ) {
+        let mut children: Vec<_> = children.into_iter().collect();
+        // sort_by_key is stable, which preserves order within a z-index.
+        children.sort_by_key(|child| self.get_ref(child).state().z_index);
+        for child in children {
+            child.paint(self, scene);
+        }
+    }
+
+    /// The region of this widget which is visible in the window, in local coordinates.
+    ///
+    /// Children whose paint rect is entirely outside of this region are not painted.
+    pub fn clip(&self) -> Rect {
+        self.clip
+    }
+
+    /// Restrict the region in which children of this widget are painted to `rect`.
+    ///
+    /// Widgets which clip their children, such as scroll views, should call this
+    /// before painting them, so that children which aren't visible can be skipped.
+    pub fn clip_children(&mut self, rect: Rect) {
+        self.clip = self.clip.intersect(rect);
+    }
+
+    /// Snap `rect`, in this widget's coordinates, to the nearest physical pixels.
+    ///
+    /// See [`pixel_snap`](crate::pixel_snap) for more.
+    pub fn snap_rect(&self, rect: Rect) -> Rect {
+        let origin = self.window_origin().to_vec2();
+        snap_to_pixel(rect + origin, self.global_state.scale_factor) - origin
+    }
+
+    /// Snap the outline of a stroke of `width` around `rect`, in this widget's
+    /// coordinates, so that the stroke covers whole physical pixels. This returns
+    /// the outline to stroke and the width to stroke it with.
+    ///
+    /// See [`pixel_snap`](crate::pixel_snap) for more.
+    pub fn snap_stroke(&self, rect: Rect, width: f64) -> (Rect, f64) {
+        let origin = self.window_origin().to_vec2();
+        let (rect, width) = snap_stroke(rect + origin, width, self.global_state.scale_factor);
+        (rect - origin, width)
+    }
+
+    /// Snap a horizontal or vertical line stroked with `width`, in this widget's
+    /// coordinates, so that the stroke covers whole physical pixels. This returns
+    /// the line to stroke and the width to stroke it with.
+    ///
+    /// See [`pixel_snap`](crate::pixel_snap) for more.
+    pub fn snap_line(&self, line: Line, width: f64) -> (Line, f64) {
+        let origin = self.window_origin().to_vec2();
+        let line = Line::new(line.p0 + origin, line.p1 + origin);
+        let (line, width) = snap_line(line, width, self.global_state.scale_factor);
+        (Line::new(line.p0 - origin, line.p1 - origin), width)
+    }
+
     /// The depth in the tree of the currently painting widget.
     ///
     /// This may be used in combination with [`paint_with_z_index`](Self::paint_with_z_index) in order
@@ -688,6 +1377,16 @@ impl AccessCtx<'_> {
         &mut self.current_node
     }
 
+    /// Add a node which isn't a widget, such as a line of text, as a child of this
+    /// widget's node.
+    ///
+    /// `id` must not be used by any other node. Ids taken from [`WidgetId::next`]
+    /// don't clash with those of widgets.
+    pub fn push_child_node(&mut self, id: NodeId, node: NodeBuilder) {
+        self.current_node.push_child(id);
+        self.tree_update.nodes.push((id, node.build()));
+    }
+
     /// Report whether accessibility was requested on this widget.
     ///
     /// This method is primarily intended for containers. The `accessibility`
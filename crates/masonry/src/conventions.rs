@@ -0,0 +1,217 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! How things are usually done on each platform, so that one app feels native on
+//! all of them.
+//!
+//! Widgets read the app's [`PlatformConventions`] with
+//! [`EventCtx::conventions`], rather than checking which platform they're built
+//! for, so that apps can follow another platform's conventions, such as in tests or
+//! to match a design. Apps set them with [`AppDriver::conventions`], which defaults
+//! to those of the current platform.
+//!
+//! Wheel scrolling isn't covered, since the platforms already apply the user's
+//! natural scrolling setting to the deltas they report.
+//!
+//! [`EventCtx::conventions`]: crate::EventCtx::conventions
+//! [`AppDriver::conventions`]: crate::app_driver::AppDriver::conventions
+
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::Keymap;
+
+/// The platforms with their own conventions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Platform {
+    MacOs,
+    Windows,
+    /// Linux and the other Unix-like platforms, following GNOME and KDE.
+    Linux,
+}
+
+impl Platform {
+    /// The platform the app is built for.
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
+/// The order of the buttons of a dialog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ButtonOrder {
+    /// "Cancel", then "OK", as on macOS and GNOME.
+    AcceptLast,
+    /// "OK", then "Cancel", as on Windows and KDE.
+    AcceptFirst,
+}
+
+/// How things are usually done on a platform.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlatformConventions {
+    /// The platform these conventions are from.
+    pub platform: Platform,
+    /// The modifier which app shortcuts, such as copying, are held with: <kbd>⌘</kbd>
+    /// on macOS, and <kbd>Ctrl</kbd> elsewhere.
+    ///
+    /// Text widgets also add carets with it held down while clicking.
+    pub command_modifier: ModifiersState,
+    /// The order of the accept and cancel buttons of dialogs.
+    pub dialog_button_order: ButtonOrder,
+    /// Whether shortcuts are written with symbols, like `⇧⌘S`, rather than words,
+    /// like `Ctrl+Shift+S`.
+    pub shortcut_symbols: bool,
+}
+
+impl Default for PlatformConventions {
+    /// The conventions of the current platform.
+    fn default() -> Self {
+        PlatformConventions::for_platform(Platform::current())
+    }
+}
+
+impl PlatformConventions {
+    /// The conventions of `platform`.
+    pub fn for_platform(platform: Platform) -> Self {
+        match platform {
+            Platform::MacOs => PlatformConventions {
+                platform,
+                command_modifier: ModifiersState::SUPER,
+                dialog_button_order: ButtonOrder::AcceptLast,
+                shortcut_symbols: true,
+            },
+            Platform::Windows => PlatformConventions {
+                platform,
+                command_modifier: ModifiersState::CONTROL,
+                dialog_button_order: ButtonOrder::AcceptFirst,
+                shortcut_symbols: false,
+            },
+            Platform::Linux => PlatformConventions {
+                platform,
+                command_modifier: ModifiersState::CONTROL,
+                dialog_button_order: ButtonOrder::AcceptLast,
+                shortcut_symbols: false,
+            },
+        }
+    }
+
+    /// Whether the [`command_modifier`](Self::command_modifier) is held in `mods`.
+    pub fn is_command(&self, mods: ModifiersState) -> bool {
+        mods.contains(self.command_modifier)
+    }
+
+    /// The platform's usual text editing shortcuts.
+    pub fn keymap(&self) -> Keymap {
+        match self.platform {
+            Platform::MacOs => Keymap::macos(),
+            Platform::Windows | Platform::Linux => Keymap::standard(),
+        }
+    }
+
+    /// Write the shortcut of `key` with `mods` held, as menus and tooltips show it.
+    pub fn format_shortcut(&self, key: &Key, mods: ModifiersState) -> String {
+        let mut shortcut = String::new();
+        if self.shortcut_symbols {
+            for (modifier, symbol) in [
+                (ModifiersState::CONTROL, "⌃"),
+                (ModifiersState::ALT, "⌥"),
+                (ModifiersState::SHIFT, "⇧"),
+                (ModifiersState::SUPER, "⌘"),
+            ] {
+                if mods.contains(modifier) {
+                    shortcut.push_str(symbol);
+                }
+            }
+        } else {
+            let super_name = match self.platform {
+                Platform::Windows => "Win",
+                Platform::MacOs | Platform::Linux => "Super",
+            };
+            for (modifier, name) in [
+                (ModifiersState::CONTROL, "Ctrl"),
+                (ModifiersState::ALT, "Alt"),
+                (ModifiersState::SHIFT, "Shift"),
+                (ModifiersState::SUPER, super_name),
+            ] {
+                if mods.contains(modifier) {
+                    shortcut.push_str(name);
+                    shortcut.push('+');
+                }
+            }
+        }
+        shortcut.push_str(&key_name(key, self.shortcut_symbols));
+        shortcut
+    }
+}
+
+/// The name of `key` in a shortcut, as a symbol if `symbols` is set.
+fn key_name(key: &Key, symbols: bool) -> String {
+    let name = match key {
+        Key::Character(c) => return c.to_uppercase(),
+        Key::Named(NamedKey::Space) => "Space",
+        Key::Named(NamedKey::Enter) if symbols => "↩",
+        Key::Named(NamedKey::Enter) => "Enter",
+        Key::Named(NamedKey::Escape) if symbols => "⎋",
+        Key::Named(NamedKey::Escape) => "Esc",
+        Key::Named(NamedKey::Tab) if symbols => "⇥",
+        Key::Named(NamedKey::Tab) => "Tab",
+        Key::Named(NamedKey::Backspace) if symbols => "⌫",
+        Key::Named(NamedKey::Backspace) => "Backspace",
+        Key::Named(NamedKey::Delete) if symbols => "⌦",
+        Key::Named(NamedKey::Delete) => "Del",
+        Key::Named(NamedKey::ArrowLeft) if symbols => "←",
+        Key::Named(NamedKey::ArrowLeft) => "Left",
+        Key::Named(NamedKey::ArrowRight) if symbols => "→",
+        Key::Named(NamedKey::ArrowRight) => "Right",
+        Key::Named(NamedKey::ArrowUp) if symbols => "↑",
+        Key::Named(NamedKey::ArrowUp) => "Up",
+        Key::Named(NamedKey::ArrowDown) if symbols => "↓",
+        Key::Named(NamedKey::ArrowDown) => "Down",
+        Key::Named(NamedKey::PageUp) => "PgUp",
+        Key::Named(NamedKey::PageDown) => "PgDn",
+        // Other named keys, such as `F3` and `Home`, go by their names
+        Key::Named(named) => return format!("{named:?}"),
+        Key::Dead(Some(c)) => return c.to_string(),
+        Key::Dead(None) | Key::Unidentified(_) => "?",
+    };
+    name.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcuts_are_written_the_platforms_way() {
+        let save_as = ModifiersState::SUPER | ModifiersState::SHIFT;
+        let mac = PlatformConventions::for_platform(Platform::MacOs);
+        assert_eq!(
+            mac.format_shortcut(&Key::Character("s".into()), save_as),
+            "⇧⌘S"
+        );
+        assert!(mac.is_command(save_as));
+        assert_eq!(mac.keymap(), Keymap::macos());
+
+        let windows = PlatformConventions::for_platform(Platform::Windows);
+        let save_as = windows.command_modifier | ModifiersState::SHIFT;
+        assert_eq!(
+            windows.format_shortcut(&Key::Character("s".into()), save_as),
+            "Ctrl+Shift+S"
+        );
+        assert_eq!(
+            windows.format_shortcut(&Key::Named(NamedKey::F3), ModifiersState::empty()),
+            "F3"
+        );
+        assert!(!windows.is_command(ModifiersState::SUPER));
+        assert_eq!(windows.dialog_button_order, ButtonOrder::AcceptFirst);
+    }
+}
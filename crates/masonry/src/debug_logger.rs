@@ -213,6 +213,8 @@ impl DebugLogger {
             StateTree::new("has_active", w_state.has_active),
             StateTree::new("has_focus", w_state.has_focus),
             StateTree::new("request_anim", w_state.request_anim),
+            StateTree::new("paint_rect", w_state.paint_rect()),
+            StateTree::new("painted_bounds", w_state.painted_bounds()),
             StateTree::new("children_changed", w_state.children_changed),
             StateTree::new(
                 "is_explicitly_disabled_new",
@@ -0,0 +1,213 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Laying out for languages written from right to left.
+//!
+//! The [`LayoutDirection`] of the window is chosen by the app, with
+//! [`AppDriver::layout_direction`] or [`RenderRoot::set_layout_direction`], and
+//! widgets read it with [`LayoutCtx::layout_direction`]. In right-to-left layouts,
+//! rows of a [`Flex`] start on the right, and the cross axis of its columns starts on
+//! the right.
+//!
+//! Other edges are physical, such as the `x0` of an [`Insets`], unless they are
+//! given as logical edges, which name the *start* and *end* of lines rather than
+//! their left and right. Widgets which take [`EdgeInsets`], such as the padding of a
+//! [`SizedBox`], accept either, and [`Align::start`] and [`Align::end`] align to
+//! the logical edges.
+//!
+//! [`AppDriver::layout_direction`]: crate::app_driver::AppDriver::layout_direction
+//! [`RenderRoot::set_layout_direction`]: crate::render_root::RenderRoot::set_layout_direction
+//! [`LayoutCtx::layout_direction`]: crate::LayoutCtx::layout_direction
+//! [`Flex`]: crate::widget::Flex
+//! [`SizedBox`]: crate::widget::SizedBox
+//! [`Align::start`]: crate::widget::Align::start
+//! [`Align::end`]: crate::widget::Align::end
+
+use kurbo::Insets;
+
+/// The direction in which lines of text, and the rows of a layout, run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LayoutDirection {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+impl LayoutDirection {
+    pub fn is_rtl(self) -> bool {
+        self == LayoutDirection::RightToLeft
+    }
+}
+
+/// Insets whose horizontal edges are the start and end of lines, which are on the
+/// left and the right in left-to-right layouts, and the other way around in
+/// right-to-left ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LogicalInsets {
+    pub start: f64,
+    pub top: f64,
+    pub end: f64,
+    pub bottom: f64,
+}
+
+impl LogicalInsets {
+    pub const ZERO: LogicalInsets = LogicalInsets::new(0.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(start: f64, top: f64, end: f64, bottom: f64) -> Self {
+        LogicalInsets {
+            start,
+            top,
+            end,
+            bottom,
+        }
+    }
+
+    /// The same insets on the start and end, and on the top and bottom.
+    pub const fn uniform_xy(horizontal: f64, vertical: f64) -> Self {
+        LogicalInsets::new(horizontal, vertical, horizontal, vertical)
+    }
+
+    /// The physical insets for a layout running in `direction`.
+    pub fn to_physical(self, direction: LayoutDirection) -> Insets {
+        match direction {
+            LayoutDirection::LeftToRight => {
+                Insets::new(self.start, self.top, self.end, self.bottom)
+            }
+            LayoutDirection::RightToLeft => {
+                Insets::new(self.end, self.top, self.start, self.bottom)
+            }
+        }
+    }
+}
+
+/// Insets with either physical or [logical](LogicalInsets) horizontal edges.
+///
+/// This converts from anything kurbo's [`Insets`] does, which are physical.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeInsets {
+    Physical(Insets),
+    Logical(LogicalInsets),
+}
+
+impl EdgeInsets {
+    pub const ZERO: EdgeInsets = EdgeInsets::Physical(Insets::ZERO);
+
+    /// The physical insets for a layout running in `direction`.
+    pub fn resolve(self, direction: LayoutDirection) -> Insets {
+        match self {
+            EdgeInsets::Physical(insets) => insets,
+            EdgeInsets::Logical(insets) => insets.to_physical(direction),
+        }
+    }
+}
+
+impl Default for EdgeInsets {
+    fn default() -> Self {
+        EdgeInsets::ZERO
+    }
+}
+
+impl From<LogicalInsets> for EdgeInsets {
+    fn from(insets: LogicalInsets) -> Self {
+        EdgeInsets::Logical(insets)
+    }
+}
+
+impl From<Insets> for EdgeInsets {
+    fn from(insets: Insets) -> Self {
+        EdgeInsets::Physical(insets)
+    }
+}
+
+impl From<f64> for EdgeInsets {
+    fn from(inset: f64) -> Self {
+        EdgeInsets::Physical(inset.into())
+    }
+}
+
+impl From<(f64, f64)> for EdgeInsets {
+    fn from(insets: (f64, f64)) -> Self {
+        EdgeInsets::Physical(insets.into())
+    }
+}
+
+impl From<(f64, f64, f64, f64)> for EdgeInsets {
+    fn from(insets: (f64, f64, f64, f64)) -> Self {
+        EdgeInsets::Physical(insets.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Align, CrossAxisAlignment, Flex, SizedBox};
+    use crate::{Size, WidgetId};
+
+    #[test]
+    fn rtl_layouts_mirror_rows_logical_padding_and_alignment() {
+        let [first, second, padded, aligned] = widget_ids();
+        let square = || SizedBox::empty().width(20.0).height(20.0);
+        let row = Flex::row()
+            .with_child_id(square(), first)
+            .with_child_id(square(), second);
+        let padded_box = SizedBox::new(square()).padding(LogicalInsets::new(10.0, 0.0, 0.0, 0.0));
+        let widget = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(row)
+            .with_child_id(padded_box, padded)
+            .with_child_id(Align::start(square()), aligned);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(100.0, 100.0));
+        let x =
+            |harness: &TestHarness, id: WidgetId| harness.get_widget(id).state().layout_rect().x0;
+        let row_x = |harness: &TestHarness| {
+            let row = harness.root_widget().children()[0];
+            row.state().layout_rect().x0
+        };
+        let child_x = |harness: &TestHarness, id: WidgetId| {
+            harness.get_widget(id).children()[0]
+                .state()
+                .layout_rect()
+                .x0
+        };
+        assert_eq!(
+            (row_x(&harness), x(&harness, first), x(&harness, second)),
+            (0.0, 0.0, 20.0)
+        );
+        assert_eq!(
+            (child_x(&harness, padded), child_x(&harness, aligned)),
+            (10.0, 0.0)
+        );
+
+        harness.set_layout_direction(LayoutDirection::RightToLeft);
+        // The row is at the right of the column, with its first child on the right
+        assert_eq!(
+            (row_x(&harness), x(&harness, first), x(&harness, second)),
+            (60.0, 20.0, 0.0)
+        );
+        assert_eq!(
+            (child_x(&harness, padded), child_x(&harness, aligned)),
+            (0.0, 80.0)
+        );
+    }
+
+    #[test]
+    fn changing_direction_repaints_moved_children() {
+        let [row_id, first] = widget_ids();
+        let square = SizedBox::empty().width(20.0).height(20.0);
+        let row = Flex::row().with_child_id(square, first);
+        let widget = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child_id(row, row_id);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(100.0, 100.0));
+        let _ = harness.render();
+        harness.reset_widget_counters();
+
+        harness.set_layout_direction(LayoutDirection::RightToLeft);
+        let _ = harness.render();
+        assert_eq!(harness.get_widget(row_id).state().layout_rect().x0, 80.0);
+        let painted = harness.painted_widgets();
+        assert!(painted.contains(&row_id), "{painted:?}");
+        assert!(painted.contains(&first), "{painted:?}");
+    }
+}
@@ -0,0 +1,658 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Post-processing the rendered content of a widget subtree with custom wgpu
+//! passes, such as to blur, desaturate or distort it.
+//!
+//! This is an advanced hook, behind the `effects` feature. An [`Effect`] widget
+//! paints its child into a layer of its own, which is rendered to an intermediate
+//! texture. Its [`PostProcess`] pass then reads that texture and writes the
+//! result to another, which is drawn over the window at the child's place.
+//!
+//! A backdrop pass, such as the [`Blur`] of a [`BackdropBlur`] widget, runs over
+//! what is painted beneath the widget instead, and the widget's own content is
+//! drawn over its output, such as for a frosted glass panel.
+//!
+//! Like an overlay, the processed layer is composited above the rest of the
+//! window, so the subtree shouldn't be covered by other widgets. The pass runs on
+//! every frame in which the window is redrawn, not only when the subtree changes,
+//! so it can animate by requesting animation frames.
+//!
+//! [`Effect`]: crate::widget::Effect
+//! [`BackdropBlur`]: crate::widget::BackdropBlur
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use tracing::warn;
+use vello::kurbo::{Affine, Rect};
+use vello::peniko::Color;
+use vello::{AaConfig, RenderParams, Renderer, Scene};
+use wgpu::util::DeviceExt as _;
+use wgpu::{CommandEncoder, Device, Queue, Texture, TextureFormat, TextureView};
+
+use crate::WidgetId;
+
+/// The format of the textures given to a [`PostProcess`] pass.
+pub const EFFECT_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+/// A custom pass run over the rendered content of an [`Effect`] widget.
+///
+/// [`Effect`]: crate::widget::Effect
+pub trait PostProcess {
+    /// Read the subtree's layer from `frame.input` and write the processed layer to
+    /// `frame.output`, by recording commands in `frame.encoder`.
+    ///
+    /// Pipelines and other GPU objects should be created the first time this is
+    /// called, and kept until [`release`](Self::release).
+    fn apply(&mut self, frame: EffectFrame<'_>);
+
+    /// Drop the GPU objects created on the current device, as it was lost. They
+    /// are created again on the new device by the next call to `apply`.
+    fn release(&mut self) {}
+}
+
+/// What a [`PostProcess`] pass runs with.
+///
+/// Both textures are in [`EFFECT_TEXTURE_FORMAT`], and are `width` by `height`
+/// physical pixels. Colors are not premultiplied by alpha. The output can be used
+/// as a render attachment or a storage texture, and starts out transparent.
+pub struct EffectFrame<'a> {
+    pub device: &'a Device,
+    pub queue: &'a Queue,
+    pub encoder: &'a mut CommandEncoder,
+    pub input: &'a TextureView,
+    pub output: &'a TextureView,
+    pub width: u32,
+    pub height: u32,
+    /// The number of physical pixels per logical pixel, to scale distances such
+    /// as a blur radius by.
+    pub scale_factor: f64,
+}
+
+/// A layer painted by an [`Effect`] widget, which its pass is run over.
+///
+/// [`Effect`]: crate::widget::Effect
+pub struct EffectLayer {
+    /// The widget which painted the layer.
+    pub owner: WidgetId,
+    /// Where the layer goes, in window coordinates.
+    pub rect: Rect,
+    /// The content of the layer, in its own coordinates.
+    pub scene: Scene,
+    pub pass: Rc<RefCell<dyn PostProcess>>,
+    /// Whether the pass runs over what is painted beneath the layer, with the
+    /// layer's content drawn over its output.
+    pub backdrop: bool,
+}
+
+/// The pass a widget's overlay was painted with, in
+/// [`PaintCtx::paint_effect`](crate::PaintCtx::paint_effect).
+pub(crate) struct LayerEffect {
+    /// The rect the layer covers, in window coordinates.
+    pub(crate) rect: Rect,
+    pub(crate) pass: Rc<RefCell<dyn PostProcess>>,
+    pub(crate) backdrop: bool,
+}
+
+/// Runs the passes of effect layers and draws their output over a frame.
+#[derive(Default)]
+pub(crate) struct EffectRenderer {
+    compositor: Option<Compositor>,
+}
+
+/// The pipeline which draws the output of a pass over the frame.
+struct Compositor {
+    format: TextureFormat,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+}
+
+const COMPOSITE_SHADER: &str = r"
+struct Placement {
+    // The layer's rect in physical pixels
+    rect: vec4<f32>,
+    // The size of the frame in physical pixels
+    frame: vec4<f32>,
+}
+
+@group(0) @binding(0) var layer: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> placement: Placement;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let corner = vec2<f32>(f32(index & 1u), f32(index >> 1u));
+    let pixel = mix(placement.rect.xy, placement.rect.zw, corner);
+    let ndc = pixel / placement.frame.xy * 2.0 - 1.0;
+    return vec4<f32>(ndc.x, -ndc.y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let texel = vec2<i32>(floor(position.xy - placement.rect.xy));
+    return textureLoad(layer, texel, 0);
+}
+";
+
+impl Compositor {
+    fn new(device: &Device, format: TextureFormat) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("effect composite"),
+            source: wgpu::ShaderSource::Wgsl(COMPOSITE_SHADER.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("effect composite"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("effect composite"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("effect composite"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+        Compositor {
+            format,
+            pipeline,
+            bind_group_layout,
+        }
+    }
+
+    /// Draw `layer` over `target`, covering `rect` in physical pixels.
+    fn draw(
+        &self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        layer: &TextureView,
+        target: &TextureView,
+        rect: Rect,
+        target_size: (u32, u32),
+    ) {
+        let placement: [f32; 8] = [
+            rect.x0 as f32,
+            rect.y0 as f32,
+            rect.x1 as f32,
+            rect.y1 as f32,
+            target_size.0 as f32,
+            target_size.1 as f32,
+            0.0,
+            0.0,
+        ];
+        let placement = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("effect placement"),
+            contents: &placement
+                .iter()
+                .flat_map(|value| value.to_ne_bytes())
+                .collect::<Vec<_>>(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("effect composite"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(layer),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: placement.as_entire_binding(),
+                },
+            ],
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("effect composite"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..4, 0..1);
+        }
+    }
+}
+
+/// The texture the effect layers are drawn over, with the device it is on.
+pub(crate) struct EffectTarget<'a> {
+    pub(crate) device: &'a Device,
+    pub(crate) queue: &'a Queue,
+    pub(crate) texture: &'a Texture,
+}
+
+impl EffectRenderer {
+    /// Run the pass of each of `layers`, and draw their output over `target`, in
+    /// order.
+    ///
+    /// `window_scene` is what was rendered to `target` before, in logical pixels,
+    /// which backdrop passes run over.
+    pub(crate) fn render(
+        &mut self,
+        layers: &[EffectLayer],
+        window_scene: &Scene,
+        renderer: &mut Renderer,
+        target: EffectTarget<'_>,
+        scale_factor: f64,
+    ) {
+        if layers.is_empty() {
+            return;
+        }
+        let EffectTarget {
+            device,
+            queue,
+            texture: target,
+        } = target;
+        let format = target.format();
+        let compositor = match &mut self.compositor {
+            Some(compositor) if compositor.format == format => compositor,
+            compositor => compositor.insert(Compositor::new(device, format)),
+        };
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let target_size = (target.width(), target.height());
+
+        for layer in layers {
+            let pixels = pixel_rect(layer.rect, scale_factor);
+            let max_size = device.limits().max_texture_dimension_2d;
+            let width = (pixels.width() as u32).min(max_size);
+            let height = (pixels.height() as u32).min(max_size);
+            if width == 0 || height == 0 {
+                continue;
+            }
+            let pixels = pixels.with_size((width as f64, height as f64));
+            let input = layer_texture(device, width, height, "effect input");
+            let output = layer_texture(device, width, height, "effect output");
+            let input_view = input.create_view(&wgpu::TextureViewDescriptor::default());
+            let output_view = output.create_view(&wgpu::TextureViewDescriptor::default());
+
+            let to_layer =
+                Affine::translate(-pixels.origin().to_vec2()) * Affine::scale(scale_factor);
+            let mut render_layer = |scene: &Scene, base_color| {
+                let mut layer_scene = Scene::new();
+                layer_scene.append(scene, Some(to_layer));
+                let params = RenderParams {
+                    base_color,
+                    width,
+                    height,
+                    antialiasing_method: AaConfig::Area,
+                };
+                renderer
+                    .render_to_texture(device, queue, &layer_scene, &input_view, &params)
+                    .map_err(|err| warn!("failed to render the layer of an effect: {err}"))
+            };
+            // The window is rendered over black, so the backdrop is too
+            let source = if layer.backdrop {
+                render_layer(window_scene, Color::BLACK)
+            } else {
+                render_layer(&layer.scene, Color::TRANSPARENT)
+            };
+            if source.is_err() {
+                continue;
+            }
+
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("effect"),
+            });
+            layer.pass.borrow_mut().apply(EffectFrame {
+                device,
+                queue,
+                encoder: &mut encoder,
+                input: &input_view,
+                output: &output_view,
+                width,
+                height,
+                scale_factor,
+            });
+            compositor.draw(
+                device,
+                &mut encoder,
+                &output_view,
+                &target_view,
+                pixels,
+                target_size,
+            );
+            queue.submit([encoder.finish()]);
+
+            // The layer's own content goes over the processed backdrop. The input
+            // can be reused, as the pass reading it was submitted above
+            if layer.backdrop {
+                if render_layer(&layer.scene, Color::TRANSPARENT).is_err() {
+                    continue;
+                }
+                let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("effect content"),
+                });
+                compositor.draw(
+                    device,
+                    &mut encoder,
+                    &input_view,
+                    &target_view,
+                    pixels,
+                    target_size,
+                );
+                queue.submit([encoder.finish()]);
+            }
+        }
+    }
+
+    /// Drop the compositor and the GPU objects of the passes of `layers`, as the
+    /// device was lost.
+    pub(crate) fn release(&mut self, layers: &[EffectLayer]) {
+        self.compositor = None;
+        for layer in layers {
+            layer.pass.borrow_mut().release();
+        }
+    }
+}
+
+/// The largest blur radius, in physical pixels, so that large radii don't stall
+/// the GPU.
+const MAX_BLUR_RADIUS: f64 = 128.0;
+
+const BLUR_SHADER: &str = r"
+struct Params {
+    // The axis the blur runs along on this pass
+    direction: vec2<i32>,
+    // The radius in physical pixels
+    radius: i32,
+    padding: i32,
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var destination: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+// The textures are sRGB-encoded and not premultiplied, so the texels are averaged
+// in linear light and premultiplied, for the edges of shapes not to darken
+fn to_linear(color: vec3<f32>) -> vec3<f32> {
+    let curve = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(curve, color / 12.92, color <= vec3<f32>(0.04045));
+}
+
+fn to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let curve = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(curve, color * 12.92, color <= vec3<f32>(0.0031308));
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<i32>(textureDimensions(source));
+    let pixel = vec2<i32>(id.xy);
+    if pixel.x >= size.x || pixel.y >= size.y {
+        return;
+    }
+    let sigma = max(f32(params.radius) / 2.0, 0.5);
+    var sum = vec4<f32>(0.0);
+    var weights = 0.0;
+    for (var i = -params.radius; i <= params.radius; i += 1) {
+        let at = clamp(pixel + params.direction * i, vec2<i32>(0), size - 1);
+        let weight = exp(-f32(i * i) / (2.0 * sigma * sigma));
+        let texel = textureLoad(source, at, 0);
+        sum += vec4<f32>(to_linear(texel.rgb) * texel.a, texel.a) * weight;
+        weights += weight;
+    }
+    let average = sum / weights;
+    var color = vec3<f32>(0.0);
+    if average.a > 0.0 {
+        color = to_srgb(average.rgb / average.a);
+    }
+    textureStore(destination, pixel, vec4<f32>(color, average.a));
+}
+";
+
+/// A gaussian blur, such as for a [`BackdropBlur`].
+///
+/// [`BackdropBlur`]: crate::widget::BackdropBlur
+pub struct Blur {
+    radius: f64,
+    pipeline: Option<(wgpu::ComputePipeline, wgpu::BindGroupLayout)>,
+}
+
+impl Blur {
+    /// A blur reaching `radius` logical pixels away.
+    pub fn new(radius: f64) -> Self {
+        Blur {
+            radius,
+            pipeline: None,
+        }
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    pub fn set_radius(&mut self, radius: f64) {
+        self.radius = radius;
+    }
+
+    fn create_pipeline(device: &Device) -> (wgpu::ComputePipeline, wgpu::BindGroupLayout) {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("blur"),
+            source: wgpu::ShaderSource::Wgsl(BLUR_SHADER.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("blur"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: EFFECT_TEXTURE_FORMAT,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("blur"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("blur"),
+            layout: Some(&layout),
+            module: &module,
+            entry_point: "main",
+        });
+        (pipeline, bind_group_layout)
+    }
+}
+
+impl PostProcess for Blur {
+    fn apply(&mut self, frame: EffectFrame<'_>) {
+        let device = frame.device;
+        let (pipeline, bind_group_layout) = self
+            .pipeline
+            .get_or_insert_with(|| Blur::create_pipeline(device));
+        let radius = (self.radius * frame.scale_factor)
+            .round()
+            .clamp(0.0, MAX_BLUR_RADIUS) as i32;
+        let between = layer_texture(device, frame.width, frame.height, "blur");
+        let between = between.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Blur along each axis in turn, which gives the same result as blurring
+        // along both at once, with far fewer samples
+        let passes = [
+            ([1, 0], frame.input, &between),
+            ([0, 1], &between, frame.output),
+        ];
+        for (direction, source, destination) in passes {
+            let params: [i32; 4] = [direction[0], direction[1], radius, 0];
+            let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("blur params"),
+                contents: &params
+                    .iter()
+                    .flat_map(|value| value.to_ne_bytes())
+                    .collect::<Vec<_>>(),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("blur"),
+                layout: bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(destination),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: params.as_entire_binding(),
+                    },
+                ],
+            });
+            let mut pass = frame
+                .encoder
+                .begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("blur"),
+                    timestamp_writes: None,
+                });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(frame.width.div_ceil(8), frame.height.div_ceil(8), 1);
+        }
+    }
+
+    fn release(&mut self) {
+        self.pipeline = None;
+    }
+}
+
+/// `rect`, in window coordinates, in physical pixels, with its origin snapped to a
+/// pixel and its size rounded up to whole pixels.
+fn pixel_rect(rect: Rect, scale_factor: f64) -> Rect {
+    let rect = rect.scale_from_origin(scale_factor);
+    Rect::from_origin_size(rect.origin().round(), rect.size().ceil())
+}
+
+fn layer_texture(device: &Device, width: u32, height: u32, label: &str) -> Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: EFFECT_TEXTURE_FORMAT,
+        usage: wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use vello::kurbo::Size;
+
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Effect, Flex, SizedBox};
+
+    struct Grayscale;
+
+    impl PostProcess for Grayscale {
+        fn apply(&mut self, _frame: EffectFrame<'_>) {}
+    }
+
+    #[test]
+    fn effect_layers_cover_their_subtree() {
+        let effect = Effect::new(SizedBox::empty().width(40.0).height(30.0), Grayscale);
+        let root = Flex::column()
+            .with_child(SizedBox::empty().height(20.0))
+            .with_child(effect);
+        let mut harness = TestHarness::create_with_size(root, Size::new(100.0, 100.0));
+        let _ = harness.render();
+        let layers = harness.effect_layers();
+        assert_eq!(layers.len(), 1);
+        let rect = layers[0].1;
+        assert_eq!((rect.y0, rect.size()), (20.0, Size::new(40.0, 30.0)));
+
+        assert_eq!(
+            pixel_rect(Rect::new(-0.4, 10.2, 10.0, 20.0), 2.0),
+            Rect::new(-1.0, 20.0, 20.0, 40.0)
+        );
+    }
+}
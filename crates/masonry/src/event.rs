@@ -10,6 +10,7 @@ use crate::WidgetId;
 use std::{collections::HashSet, path::PathBuf};
 
 use accesskit::{Action, ActionData};
+use instant::Instant;
 use winit::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
 use winit::event::{Ime, KeyEvent, Modifiers, MouseButton};
 use winit::keyboard::ModifiersState;
@@ -74,11 +75,13 @@ pub struct PointerState {
     pub position: LogicalPosition<f64>,
     pub buttons: HashSet<MouseButton>,
     pub mods: Modifiers,
+    /// The number of clicks in quick succession, such as 2 for a double click.
     pub count: u8,
     pub focus: bool,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WindowTheme {
     Light,
     Dark,
@@ -112,6 +115,20 @@ pub enum LifeCycle {
     /// of events.
     WidgetAdded,
 
+    /// Sent to a `Widget` and all its descendants when it is removed from the
+    /// widget tree. This is the last message that each widget receives.
+    ///
+    /// Widgets should release resources which outlive the widget itself in
+    /// [`Widget::on_removed`](crate::Widget::on_removed), which is called after
+    /// this event has reached all of their descendants.
+    ///
+    /// Container widgets should remove children with [`LifeCycleCtx::remove_child`]
+    /// (or the equivalent method on other contexts), which sends this event,
+    /// and forward it to their own children like any other event.
+    ///
+    /// [`LifeCycleCtx::remove_child`]: crate::LifeCycleCtx::remove_child
+    WidgetRemoved,
+
     /// Called at the beginning of a new animation frame.
     ///
     /// On the first frame when transitioning from idle to animating, `interval`
@@ -124,6 +141,40 @@ pub enum LifeCycle {
     /// the monitor's refresh, causing lag or jerky animations.
     AnimFrame(u64),
 
+    /// Called when the app has nothing else to do, for widgets which asked for it
+    /// with [`request_idle_work`].
+    ///
+    /// Widgets can use this to prepare work they are likely to need soon, such as
+    /// laying out the next page of a pager. They should do it in small steps until
+    /// the deadline has passed, and call [`request_idle_work`] again if there is
+    /// more left, so that the app stays responsive.
+    ///
+    /// [`request_idle_work`]: crate::LifeCycleCtx::request_idle_work
+    Idle(Instant),
+
+    /// Sent to a widget when one of its [`WakeHandle`]s was woken, such as by work
+    /// it was waiting on in the background.
+    ///
+    /// Containers forward it to their children like other lifecycle events, but
+    /// only the woken widget receives it.
+    ///
+    /// [`WakeHandle`]: crate::wake::WakeHandle
+    Wake,
+
+    /// Sent to all widgets when the GPU device is lost, after the
+    /// [`GpuResource`]s of every widget have been released.
+    ///
+    /// [`GpuResource`]: crate::gpu::GpuResource
+    GpuDeviceLost,
+
+    /// Sent to all widgets when the GPU device has been replaced after being lost,
+    /// after the [`GpuResource`]s of every widget have been created again on it.
+    ///
+    /// Widgets which paint from their GPU resources should request a paint.
+    ///
+    /// [`GpuResource`]: crate::gpu::GpuResource
+    GpuDeviceRecreated,
+
     // TODO - Put in StatusChange
     /// Called when the Disabled state of the widgets is changed.
     ///
@@ -180,6 +231,15 @@ pub enum InternalLifeCycle {
     /// Used to route the `DisabledChanged` event to the required widgets.
     RouteDisabledChanged,
 
+    /// Used to repaint the given widgets, such as once their content was cached.
+    RouteRequestPaint { widgets: Vec<WidgetId> },
+
+    /// Used to repaint every widget, such as when a window-wide setting changed.
+    RouteRequestPaintAll,
+
+    /// Used to route the `Wake` event to the given widgets.
+    RouteWake { widgets: Vec<WidgetId> },
+
     /// The parents widget origin in window coordinate space has changed.
     ParentWindowOrigin {
         mouse_pos: Option<LogicalPosition<f64>>,
@@ -346,7 +406,11 @@ impl LifeCycle {
         match self {
             LifeCycle::Internal(internal) => internal.should_propagate_to_hidden(),
             LifeCycle::WidgetAdded => true,
+            LifeCycle::WidgetRemoved => true,
             LifeCycle::AnimFrame(_) => true,
+            LifeCycle::Idle(_) => true,
+            LifeCycle::Wake => true,
+            LifeCycle::GpuDeviceLost | LifeCycle::GpuDeviceRecreated => true,
             LifeCycle::DisabledChanged(_) => true,
             LifeCycle::BuildFocusChain => false,
             LifeCycle::RequestPanToChild(_) => false,
@@ -362,10 +426,18 @@ impl LifeCycle {
                 InternalLifeCycle::RouteWidgetAdded => "RouteWidgetAdded",
                 InternalLifeCycle::RouteFocusChanged { .. } => "RouteFocusChanged",
                 InternalLifeCycle::RouteDisabledChanged => "RouteDisabledChanged",
+                InternalLifeCycle::RouteRequestPaint { .. } => "RouteRequestPaint",
+                InternalLifeCycle::RouteRequestPaintAll => "RouteRequestPaintAll",
+                InternalLifeCycle::RouteWake { .. } => "RouteWake",
                 InternalLifeCycle::ParentWindowOrigin { .. } => "ParentWindowOrigin",
             },
             LifeCycle::WidgetAdded => "WidgetAdded",
+            LifeCycle::WidgetRemoved => "WidgetRemoved",
             LifeCycle::AnimFrame(_) => "AnimFrame",
+            LifeCycle::Idle(_) => "Idle",
+            LifeCycle::Wake => "Wake",
+            LifeCycle::GpuDeviceLost => "GpuDeviceLost",
+            LifeCycle::GpuDeviceRecreated => "GpuDeviceRecreated",
             LifeCycle::DisabledChanged(_) => "DisabledChanged",
             LifeCycle::BuildFocusChain => "BuildFocusChain",
             LifeCycle::RequestPanToChild(_) => "RequestPanToChild",
@@ -386,8 +458,11 @@ impl InternalLifeCycle {
         match self {
             InternalLifeCycle::RouteWidgetAdded
             | InternalLifeCycle::RouteFocusChanged { .. }
-            | InternalLifeCycle::RouteDisabledChanged => true,
-            InternalLifeCycle::ParentWindowOrigin { .. } => false,
+            | InternalLifeCycle::RouteDisabledChanged
+            | InternalLifeCycle::RouteRequestPaintAll
+            | InternalLifeCycle::RouteWake { .. } => true,
+            InternalLifeCycle::RouteRequestPaint { .. }
+            | InternalLifeCycle::ParentWindowOrigin { .. } => false,
         }
     }
 }
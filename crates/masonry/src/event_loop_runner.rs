@@ -1,50 +1,162 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashMap;
+use std::fmt;
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use accesskit_winit::Adapter;
+use instant::Instant;
 use tracing::subscriber::SetGlobalDefaultError;
-use tracing::{debug, warn};
+use tracing::{debug, error, warn};
 use vello::kurbo::Affine;
-use vello::util::{RenderContext, RenderSurface};
+use vello::util::RenderSurface;
 use vello::{peniko::Color, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
-use wgpu::PresentMode;
+use wgpu::{AdapterInfo, DeviceLostReason, DeviceType, PresentMode};
 use winit::application::ApplicationHandler;
-use winit::dpi::LogicalPosition;
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalPosition};
 use winit::error::EventLoopError;
 use winit::event::WindowEvent as WinitWindowEvent;
-use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::window::{Window, WindowAttributes, WindowId};
 
 use crate::app_driver::{AppDriver, DriverCtx};
+use crate::color::ColorSpace;
+#[cfg(feature = "effects")]
+use crate::effects::{EffectRenderer, EffectTarget};
 use crate::event::{PointerState, WindowEvent};
+use crate::feedback::FeedbackBackend;
+use crate::idle::{ActivityEvent, IdleDetector};
+use crate::persistence::{MonitorArea, Store, WindowGeometry};
+use crate::popup::PopupMode;
+use crate::render_context::RenderContext;
+use crate::render_diagnostics::{GpuInfo, RenderDiagnostics, RenderError};
 use crate::render_root::{self, RenderRoot, WindowSizePolicy};
-use crate::{PointerEvent, TextEvent, Widget};
+use crate::taskbar::{AppIcon, TaskbarBackend};
+use crate::window_kind::WindowKind;
+use crate::{PointerEvent, Rect, TextEvent, Widget, WidgetId};
+
+/// How long widgets get for idle work each time the event loop runs out of events,
+/// which is short enough that an event arriving meanwhile isn't noticeably delayed.
+const IDLE_WORK_BUDGET: Duration = Duration::from_millis(5);
 
 struct MainState<'a> {
     window: Arc<Window>,
     render_cx: RenderContext,
     surface: RenderSurface<'a>,
+    /// Set by the device's lost callback, so we can recreate it before the next frame
+    device_lost: Arc<AtomicBool>,
     render_root: RenderRoot,
     renderer: Option<Renderer>,
+    /// Runs the passes of the layers painted by `Effect` widgets
+    #[cfg(feature = "effects")]
+    effects: EffectRenderer,
     pointer_state: PointerState,
+    /// When and where the last click was, to count multiple clicks.
+    last_click: Option<(Instant, LogicalPosition<f64>)>,
     app_driver: Box<dyn AppDriver>,
+    feedback: Box<dyn FeedbackBackend>,
+    taskbar: Box<dyn TaskbarBackend>,
+    /// Set if the app wants to know when the user is idle
+    idle: Option<IdleDetector>,
     accesskit_adapter: Adapter,
+    /// The windows of the popups shown in windows of their own, by owner
+    popup_windows: HashMap<WidgetId, PopupWindow<'a>>,
+    /// Where the window's geometry is saved when it is closed, if the app wants it to be
+    geometry_store: Option<Store>,
+    /// Why the app stopped, if it couldn't render at all
+    render_error: Option<RenderError>,
+}
+
+/// A popup shown in a window of its own, with [`PopupMode::NativeWindow`].
+struct PopupWindow<'a> {
+    window: Arc<Window>,
+    surface: RenderSurface<'a>,
+    /// Where the window is, in the main window's coordinates
+    rect: Rect,
+    scene: Scene,
+}
+
+/// The events sent to the event loop from other threads.
+pub enum MasonryUserEvent {
+    AccessKit(accesskit_winit::Event),
+    /// A [`WakeHandle`](crate::wake::WakeHandle) was woken.
+    Wake,
+}
+
+impl From<accesskit_winit::Event> for MasonryUserEvent {
+    fn from(event: accesskit_winit::Event) -> Self {
+        MasonryUserEvent::AccessKit(event)
+    }
+}
+
+/// Why the event loop stopped before the app's window was closed.
+#[derive(Debug)]
+pub enum RunError {
+    EventLoop(EventLoopError),
+    /// The app couldn't render, even in software. See
+    /// [`render_diagnostics`](crate::render_diagnostics).
+    Render(RenderError),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::EventLoop(err) => err.fmt(f),
+            RunError::Render(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RunError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RunError::EventLoop(err) => Some(err),
+            RunError::Render(err) => Some(err),
+        }
+    }
+}
+
+impl From<EventLoopError> for RunError {
+    fn from(err: EventLoopError) -> Self {
+        RunError::EventLoop(err)
+    }
+}
+
+impl From<RenderError> for RunError {
+    fn from(err: RenderError) -> Self {
+        RunError::Render(err)
+    }
 }
 
 pub fn run(
     window_attributes: WindowAttributes,
     root_widget: impl Widget,
     app_driver: impl AppDriver + 'static,
-) -> Result<(), EventLoopError> {
+) -> Result<(), RunError> {
     let visible = window_attributes.visible;
     let window_attributes = window_attributes.with_visible(false);
 
     let event_loop = EventLoop::with_user_event().build()?;
     #[allow(deprecated)]
     let window = event_loop.create_window(window_attributes).unwrap();
+    // The window is restored while it's hidden, so it doesn't jump after being shown
+    let saved_geometry = app_driver
+        .window_geometry_key()
+        .and_then(|key| Store::new(&key))
+        .and_then(|store| store.load::<WindowGeometry>(WindowGeometry::STORE_NAME));
+    if let Some(geometry) = saved_geometry {
+        let monitors: Vec<_> = window
+            .available_monitors()
+            .map(|monitor| MonitorArea::from_handle(&monitor))
+            .collect();
+        if let Some(geometry) = geometry.fit_to(&monitors) {
+            geometry.apply_to_window(&window);
+        }
+    }
 
     let event_loop_proxy = event_loop.create_proxy();
     let adapter = Adapter::with_event_loop_proxy(&window, event_loop_proxy);
@@ -57,32 +169,69 @@ pub fn run(
 
 pub fn run_with(
     window: Window,
-    event_loop: EventLoop<accesskit_winit::Event>,
+    event_loop: EventLoop<MasonryUserEvent>,
     accesskit_adapter: Adapter,
     root_widget: impl Widget,
-    app_driver: impl AppDriver + 'static,
-) -> Result<(), EventLoopError> {
+    mut app_driver: impl AppDriver + 'static,
+) -> Result<(), RunError> {
     let window = Arc::new(window);
-    let mut render_cx = RenderContext::new().unwrap();
-    let size = window.inner_size();
-    let surface = pollster::block_on(render_cx.create_surface(
-        window.clone(),
-        size.width,
-        size.height,
-        PresentMode::AutoVsync,
-    ))
-    .unwrap();
+    let mut render_cx = RenderContext::new(None);
+    let mut diagnostics = RenderDiagnostics::default();
+    let surface = create_surface(
+        &mut render_cx,
+        &window,
+        app_driver.software_fallback(),
+        &mut diagnostics,
+    )?;
+    let device_lost = Arc::new(AtomicBool::new(false));
+    watch_device_lost(&render_cx, &surface, &device_lost);
     let scale_factor = window.scale_factor();
+    let mut render_root = RenderRoot::new(root_widget, WindowSizePolicy::User, scale_factor);
+    let wake_proxy = event_loop.create_proxy();
+    render_root.set_wake_notifier(move || {
+        // This only fails once the event loop has exited
+        let _ = wake_proxy.send_event(MasonryUserEvent::Wake);
+    });
+    render_root.set_render_diagnostics(diagnostics);
+    render_root.set_keymap(app_driver.keymap());
+    render_root.set_icons(app_driver.icons());
+    render_root.set_interaction_settings(app_driver.interaction_settings());
+    render_root.set_conventions(app_driver.conventions());
+    render_root.set_popup_mode(app_driver.popup_mode());
+    render_root.set_color_space(surface_color_space(app_driver.color_space()));
+    render_root.set_layout_direction(app_driver.layout_direction());
+    render_root.set_slow_widget_threshold(app_driver.slow_widget_threshold());
+    render_root.set_paint_debug(app_driver.paint_debug());
+    render_root.set_spell_checker(app_driver.spell_checker());
+    let feedback = app_driver.feedback();
+    let taskbar = app_driver.taskbar();
+    let geometry_store = app_driver
+        .window_geometry_key()
+        .and_then(|key| Store::new(&key));
+    let idle = app_driver
+        .idle_timeout()
+        .map(|timeout| IdleDetector::new(timeout, Instant::now()));
     let mut main_state = MainState {
         window,
         render_cx,
         surface,
-        render_root: RenderRoot::new(root_widget, WindowSizePolicy::User, scale_factor),
+        device_lost,
+        render_root,
         renderer: None,
+        #[cfg(feature = "effects")]
+        effects: EffectRenderer::default(),
         pointer_state: PointerState::empty(),
+        last_click: None,
         app_driver: Box::new(app_driver),
+        feedback,
+        taskbar,
+        idle,
         accesskit_adapter,
+        popup_windows: HashMap::new(),
+        geometry_store,
+        render_error: None,
     };
+    main_state.update_screen_rect();
 
     // If there is no default tracing subscriber, we set our own. If one has
     // already been set, we get an error which we swallow.
@@ -90,27 +239,109 @@ pub fn run_with(
     // to try to set their own subscriber once the event loop has started.
     let _ = try_init_tracing();
 
-    event_loop.run_app(&mut main_state)
+    event_loop.run_app(&mut main_state)?;
+    match main_state.render_error {
+        Some(err) => Err(err.into()),
+        None => Ok(()),
+    }
 }
 
-impl ApplicationHandler<accesskit_winit::Event> for MainState<'_> {
+impl ApplicationHandler<MasonryUserEvent> for MainState<'_> {
     fn resumed(&mut self, _event_loop: &ActiveEventLoop) {
         // FIXME: initialize window in this handler because initializing it before running the event loop is deprecated
     }
 
-    fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WinitWindowEvent) {
-        self.accesskit_adapter.process_event(&self.window, &event);
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        mut event: WinitWindowEvent,
+    ) {
+        if window_id == self.window.id() {
+            self.accesskit_adapter.process_event(&self.window, &event);
+        } else {
+            // Input in a popup's window goes to the main window's widgets, at the
+            // popup's place in the main window
+            let Some((owner, offset)) = self
+                .popup_windows
+                .iter()
+                .find(|(_, popup)| popup.window.id() == window_id)
+                .map(|(owner, popup)| (*owner, popup.rect.origin().to_vec2()))
+            else {
+                return;
+            };
+            match &mut event {
+                WinitWindowEvent::RedrawRequested => {
+                    self.render_popup(owner);
+                    return;
+                }
+                WinitWindowEvent::CursorMoved { position, .. } => {
+                    let scale = self.window.scale_factor();
+                    position.x += offset.x * scale;
+                    position.y += offset.y * scale;
+                }
+                WinitWindowEvent::CursorLeft { .. }
+                | WinitWindowEvent::MouseInput { .. }
+                | WinitWindowEvent::MouseWheel { .. }
+                | WinitWindowEvent::KeyboardInput { .. }
+                | WinitWindowEvent::ModifiersChanged(_)
+                | WinitWindowEvent::Ime(_) => {}
+                _ => return,
+            }
+        }
+
+        if is_user_input(&event) {
+            let activity = self
+                .idle
+                .as_mut()
+                .and_then(|idle| idle.on_input(Instant::now()));
+            if let Some(activity) = activity {
+                self.on_activity(activity);
+            }
+        }
 
         match event {
             WinitWindowEvent::RedrawRequested => {
+                if self.device_lost.swap(false, Ordering::AcqRel) {
+                    if let Err(err) = self.recreate_device() {
+                        self.stop_rendering(event_loop, err);
+                        return;
+                    }
+                }
+                if self.renderer.is_none() {
+                    if let Err(err) = self.create_renderer() {
+                        self.stop_rendering(event_loop, err);
+                        return;
+                    }
+                }
+                let device_handle = &self.render_cx.devices[self.surface.dev_id];
+                self.render_root
+                    .prepare_gpu_resources(&device_handle.device, &device_handle.queue);
+                // Widgets which requested an animation frame are updated before painting
+                self.render_root.handle_window_event(WindowEvent::AnimFrame);
                 let (scene, tree_update) = self.render_root.redraw();
+                let render_start = Instant::now();
                 self.render(scene);
+                self.render_root.record_render_time(render_start.elapsed());
                 self.accesskit_adapter.update_if_active(|| tree_update);
+                self.update_popup_windows(event_loop);
+            }
+            WinitWindowEvent::CloseRequested => {
+                if let Some(store) = &self.geometry_store {
+                    let geometry = WindowGeometry::of_window(&self.window);
+                    if let Err(err) = store.save(WindowGeometry::STORE_NAME, &geometry) {
+                        warn!("failed to save the window's geometry: {err}");
+                    }
+                }
+                event_loop.exit();
             }
-            WinitWindowEvent::CloseRequested => event_loop.exit(),
             WinitWindowEvent::Resized(size) => {
                 self.render_root
                     .handle_window_event(WindowEvent::Resize(size));
+                self.update_screen_rect();
+            }
+            WinitWindowEvent::Moved(_) | WinitWindowEvent::ScaleFactorChanged { .. } => {
+                self.update_screen_rect();
             }
             WinitWindowEvent::ModifiersChanged(modifiers) => {
                 self.pointer_state.mods = modifiers;
@@ -146,6 +377,20 @@ impl ApplicationHandler<accesskit_winit::Event> for MainState<'_> {
             }
             WinitWindowEvent::MouseInput { state, button, .. } => match state {
                 winit::event::ElementState::Pressed => {
+                    let now = self.render_root.clock().now();
+                    let position = self.pointer_state.position;
+                    let settings = self.render_root.interaction_settings();
+                    let is_repeat = self.last_click.is_some_and(|(time, last_position)| {
+                        now - time < settings.double_click_interval
+                            && (position.x - last_position.x).hypot(position.y - last_position.y)
+                                < settings.double_click_distance
+                    });
+                    self.pointer_state.count = if is_repeat {
+                        self.pointer_state.count.saturating_add(1)
+                    } else {
+                        1
+                    };
+                    self.last_click = Some((now, position));
                     self.render_root
                         .handle_pointer_event(PointerEvent::PointerDown(
                             button,
@@ -181,7 +426,41 @@ impl ApplicationHandler<accesskit_winit::Event> for MainState<'_> {
         self.handle_signals(event_loop);
     }
 
-    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: accesskit_winit::Event) {
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if let Some(activity) = self
+            .idle
+            .as_mut()
+            .and_then(|idle| idle.poll(Instant::now()))
+        {
+            self.on_activity(activity);
+            self.handle_signals(event_loop);
+        }
+        if self.render_root.wants_idle_work() {
+            self.render_root.run_idle_work(IDLE_WORK_BUDGET);
+            self.handle_signals(event_loop);
+        }
+        let control_flow = if self.render_root.wants_idle_work() {
+            // Come straight back for more idle work, if no events arrive first
+            ControlFlow::Poll
+        } else {
+            // Wake up when the user would become idle, if they aren't already
+            match self.idle.as_ref().and_then(IdleDetector::deadline) {
+                Some(deadline) => ControlFlow::WaitUntil(deadline),
+                None => ControlFlow::Wait,
+            }
+        };
+        event_loop.set_control_flow(control_flow);
+    }
+
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: MasonryUserEvent) {
+        let event = match event {
+            MasonryUserEvent::AccessKit(event) => event,
+            MasonryUserEvent::Wake => {
+                self.render_root.handle_wakes();
+                self.handle_signals(event_loop);
+                return;
+            }
+        };
         match event.window_event {
             // Note that this event can be called at any time, even multiple times if
             // the user restarts their screen reader.
@@ -199,37 +478,164 @@ impl ApplicationHandler<accesskit_winit::Event> for MainState<'_> {
     }
 }
 
-impl MainState<'_> {
-    fn render(&mut self, scene: Scene) {
-        let scale = self.window.scale_factor();
-        let size = self.window.inner_size();
-        let width = size.width;
-        let height = size.height;
+/// Whether `event` means the user is using the app.
+fn is_user_input(event: &WinitWindowEvent) -> bool {
+    matches!(
+        event,
+        WinitWindowEvent::KeyboardInput { .. }
+            | WinitWindowEvent::Ime(_)
+            | WinitWindowEvent::CursorMoved { .. }
+            | WinitWindowEvent::MouseInput { .. }
+            | WinitWindowEvent::MouseWheel { .. }
+            | WinitWindowEvent::Touch(_)
+    )
+}
 
-        if self.surface.config.width != width || self.surface.config.height != height {
-            self.render_cx
-                .resize_surface(&mut self.surface, width, height);
+/// Create the surface of `window`, on a software adapter if no GPU can draw to it
+/// and `software_fallback` is set, and record which one in `diagnostics`.
+///
+/// Once the software adapter is chosen, `render_cx` keeps creating devices on it.
+fn create_surface(
+    render_cx: &mut RenderContext,
+    window: &Arc<Window>,
+    software_fallback: bool,
+    diagnostics: &mut RenderDiagnostics,
+) -> Result<RenderSurface<'static>, RenderError> {
+    let size = window.inner_size();
+    let create = |render_cx: &mut RenderContext| {
+        pollster::block_on(render_cx.create_surface(
+            window.clone(),
+            size.width,
+            size.height,
+            PresentMode::AutoVsync,
+        ))
+        .map_err(RenderError::Surface)
+    };
+    let surface = match create(render_cx) {
+        Ok(surface) => surface,
+        Err(err) => {
+            // Either the user or an earlier fallback already chose the adapter
+            let configured =
+                std::env::var_os(ADAPTER_VAR).is_some() || render_cx.adapter_name().is_some();
+            let adapters = adapter_infos(&render_cx.instance);
+            let Some(name) = software_adapter(software_fallback, configured, &adapters) else {
+                return Err(err);
+            };
+            warn!("{err}, falling back to {name}");
+            render_cx.set_adapter_name(Some(name));
+            diagnostics.fallback_reason = Some(err);
+            create(render_cx)?
         }
+    };
+    let adapter = &render_cx.devices[surface.dev_id].adapter;
+    diagnostics.gpu = Some(GpuInfo::from_adapter_info(&adapter.get_info()));
+    Ok(surface)
+}
 
-        let transformed_scene = if scale == 1.0 {
-            None
-        } else {
-            let mut new_scene = Scene::new();
-            new_scene.append(&scene, Some(Affine::scale(scale)));
-            Some(new_scene)
-        };
-        let scene_ref = transformed_scene.as_ref().unwrap_or(&scene);
+/// The variable wgpu reads the name of the adapter to use from, if the user
+/// chose one themselves.
+const ADAPTER_VAR: &str = "WGPU_ADAPTER_NAME";
 
-        let Ok(surface_texture) = self.surface.surface.get_current_texture() else {
-            warn!("failed to acquire next swapchain texture");
-            return;
-        };
-        let dev_id = self.surface.dev_id;
-        let device = &self.render_cx.devices[dev_id].device;
-        let queue = &self.render_cx.devices[dev_id].queue;
-        let renderer_options = RendererOptions {
+/// The color space the window renders in, for the `requested` space.
+///
+/// The surface is always in sRGB, as wgpu can't set the color space of a surface
+/// yet, so other spaces fall back to it.
+fn surface_color_space(requested: ColorSpace) -> ColorSpace {
+    if requested != ColorSpace::Srgb {
+        warn!("{requested:?} isn't supported by the surface, rendering in sRGB");
+    }
+    ColorSpace::Srgb
+}
+
+/// The adapter to create the surface on after no GPU could draw to it: one which
+/// renders on the CPU, such as llvmpipe or WARP, if `software_fallback` is set and
+/// the user didn't choose an adapter themselves.
+fn software_adapter(
+    software_fallback: bool,
+    adapter_configured: bool,
+    adapters: &[AdapterInfo],
+) -> Option<String> {
+    if !software_fallback || adapter_configured {
+        return None;
+    }
+    adapters
+        .iter()
+        .find(|info| info.device_type == DeviceType::Cpu)
+        .map(|info| info.name.clone())
+}
+
+fn adapter_infos(instance: &wgpu::Instance) -> Vec<AdapterInfo> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        instance
+            .enumerate_adapters(wgpu::Backends::all())
+            .into_iter()
+            .map(|adapter| adapter.get_info())
+            .collect()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = instance;
+        Vec::new()
+    }
+}
+
+/// Make `device_lost` true when the device `surface` renders with is lost.
+fn watch_device_lost(
+    render_cx: &RenderContext,
+    surface: &RenderSurface,
+    device_lost: &Arc<AtomicBool>,
+) {
+    let device_lost = device_lost.clone();
+    render_cx.devices[surface.dev_id]
+        .device
+        .set_device_lost_callback(move |reason, message| {
+            // The callback is also called when we drop or replace it ourselves
+            if let DeviceLostReason::Unknown | DeviceLostReason::Destroyed = reason {
+                warn!("GPU device lost: {message}");
+                device_lost.store(true, Ordering::Release);
+            }
+        });
+}
+
+impl MainState<'_> {
+    /// Replace the lost device with a new one, and recreate the widgets' GPU resources on it.
+    fn recreate_device(&mut self) -> Result<(), RenderError> {
+        self.render_root.handle_device_lost();
+        self.renderer = None;
+        #[cfg(feature = "effects")]
+        self.effects.release(&self.render_root.effect_layers());
+
+        // The new device is created on the same adapter, if one was chosen
+        let mut render_cx = RenderContext::new(self.render_cx.adapter_name().map(String::from));
+        let mut diagnostics = self.render_root.render_diagnostics().clone();
+        let surface = create_surface(
+            &mut render_cx,
+            &self.window,
+            self.app_driver.software_fallback(),
+            &mut diagnostics,
+        )?;
+        self.render_root.set_render_diagnostics(diagnostics);
+        watch_device_lost(&render_cx, &surface, &self.device_lost);
+        self.surface = surface;
+        self.render_cx = render_cx;
+        // The popups' surfaces were on the lost device, so their windows are opened
+        // again after the next frame
+        self.popup_windows.clear();
+
+        let device_handle = &self.render_cx.devices[self.surface.dev_id];
+        self.render_root
+            .handle_device_recreated(&device_handle.device, &device_handle.queue);
+        Ok(())
+    }
+
+    /// Create the renderer on the surface's device, falling back to vello's CPU
+    /// pipeline if it can't run on the GPU and the app allows it.
+    fn create_renderer(&mut self) -> Result<(), RenderError> {
+        let device = &self.render_cx.devices[self.surface.dev_id].device;
+        let options = |use_cpu| RendererOptions {
             surface_format: Some(self.surface.format),
-            use_cpu: false,
+            use_cpu,
             antialiasing_support: AaSupport {
                 area: true,
                 msaa8: false,
@@ -237,18 +643,187 @@ impl MainState<'_> {
             },
             num_init_threads: NonZeroUsize::new(1),
         };
-        let render_params = RenderParams {
-            base_color: Color::BLACK,
-            width,
-            height,
-            antialiasing_method: vello::AaConfig::Area,
+        let renderer = match Renderer::new(device, options(false)) {
+            Ok(renderer) => renderer,
+            Err(err) => {
+                let err = RenderError::Renderer {
+                    gpu: self.render_root.render_diagnostics().gpu.clone(),
+                    message: err.to_string(),
+                };
+                if !self.app_driver.software_fallback() {
+                    return Err(err);
+                }
+                warn!("{err}, falling back to the CPU");
+                let renderer = Renderer::new(device, options(true)).map_err(|_| err.clone())?;
+                let mut diagnostics = self.render_root.render_diagnostics().clone();
+                diagnostics.fallback_reason = Some(err);
+                self.render_root.set_render_diagnostics(diagnostics);
+                renderer
+            }
+        };
+        self.renderer = Some(renderer);
+        Ok(())
+    }
+
+    /// Stop the app, since it can't render, so that [`run`] returns `err`.
+    fn stop_rendering(&mut self, event_loop: &ActiveEventLoop, err: RenderError) {
+        error!("{err}");
+        self.render_error = Some(err);
+        event_loop.exit();
+    }
+
+    fn render(&mut self, scene: Scene) {
+        let Some(renderer) = &mut self.renderer else {
+            return;
+        };
+        let Some(frame) = render_to_window(
+            &mut self.render_cx,
+            renderer,
+            &mut self.surface,
+            &self.window,
+            &scene,
+        ) else {
+            return;
         };
-        self.renderer
-            .get_or_insert_with(|| Renderer::new(device, renderer_options).unwrap())
-            .render_to_surface(device, queue, scene_ref, &surface_texture, &render_params)
-            .expect("failed to render to surface");
-        surface_texture.present();
-        device.poll(wgpu::Maintain::Wait);
+        let device_handle = &self.render_cx.devices[self.surface.dev_id];
+        #[cfg(feature = "effects")]
+        self.effects.render(
+            &self.render_root.effect_layers(),
+            &scene,
+            renderer,
+            EffectTarget {
+                device: &device_handle.device,
+                queue: &device_handle.queue,
+                texture: &frame.texture,
+            },
+            self.window.scale_factor(),
+        );
+        frame.present();
+        device_handle.device.poll(wgpu::Maintain::Wait);
+        self.render_root
+            .rasterize_cached(renderer, &device_handle.device, &device_handle.queue);
+    }
+
+    fn render_popup(&mut self, owner: WidgetId) {
+        let popup = self.popup_windows.get_mut(&owner);
+        if let (Some(popup), Some(renderer)) = (popup, &mut self.renderer) {
+            let frame = render_to_window(
+                &mut self.render_cx,
+                renderer,
+                &mut popup.surface,
+                &popup.window,
+                &popup.scene,
+            );
+            if let Some(frame) = frame {
+                frame.present();
+                let device = &self.render_cx.devices[popup.surface.dev_id].device;
+                device.poll(wgpu::Maintain::Wait);
+            }
+        }
+    }
+
+    /// Open, move and close the windows of popups to match those painted in the last
+    /// frame, and redraw them.
+    fn update_popup_windows(&mut self, event_loop: &ActiveEventLoop) {
+        let popups = self.render_root.native_popups();
+        self.popup_windows
+            .retain(|owner, _| popups.iter().any(|popup| popup.owner == *owner));
+        if popups.is_empty() {
+            return;
+        }
+        // Platforms which don't let windows know where they are, such as Wayland,
+        // can't place popups next to their anchors
+        let Ok(origin) = self.window.inner_position() else {
+            warn!("the window's position is unknown, so popups can't be shown in windows");
+            return;
+        };
+        let scale = self.window.scale_factor();
+        for popup in popups {
+            let position = PhysicalPosition::new(
+                origin.x + (popup.rect.x0 * scale).round() as i32,
+                origin.y + (popup.rect.y0 * scale).round() as i32,
+            );
+            let size = LogicalSize::new(popup.rect.width(), popup.rect.height());
+            if let Some(window) = self.popup_windows.get_mut(&popup.owner) {
+                if window.rect != popup.rect {
+                    window.window.set_outer_position(position);
+                    let _ = window.window.request_inner_size(size);
+                    window.rect = popup.rect;
+                }
+                window.scene = popup.scene;
+                window.window.request_redraw();
+                continue;
+            }
+
+            let attributes = WindowKind::Popup.apply(
+                Window::default_attributes()
+                    .with_position(position)
+                    .with_inner_size(size)
+                    .with_active(false),
+            );
+            let window = match event_loop.create_window(attributes) {
+                Ok(window) => Arc::new(window),
+                Err(err) => {
+                    warn!("failed to open a popup window: {err}");
+                    continue;
+                }
+            };
+            let physical_size = window.inner_size();
+            let surface = match pollster::block_on(self.render_cx.create_surface(
+                window.clone(),
+                physical_size.width,
+                physical_size.height,
+                PresentMode::AutoVsync,
+            )) {
+                Ok(surface) => surface,
+                Err(err) => {
+                    warn!("failed to create the surface of a popup window: {err}");
+                    continue;
+                }
+            };
+            window.request_redraw();
+            self.popup_windows.insert(
+                popup.owner,
+                PopupWindow {
+                    window,
+                    surface,
+                    rect: popup.rect,
+                    scene: popup.scene,
+                },
+            );
+        }
+    }
+
+    /// Tell the widgets which part of the screen popups in windows of their own can use.
+    fn update_screen_rect(&mut self) {
+        if self.render_root.popup_mode() != PopupMode::NativeWindow {
+            return;
+        }
+        let screen_rect = self.window.current_monitor().and_then(|monitor| {
+            let origin = self.window.inner_position().ok()?;
+            let scale = self.window.scale_factor();
+            let position = monitor.position();
+            let size = monitor.size();
+            let x0 = (position.x - origin.x) as f64 / scale;
+            let y0 = (position.y - origin.y) as f64 / scale;
+            Some(Rect::new(
+                x0,
+                y0,
+                x0 + size.width as f64 / scale,
+                y0 + size.height as f64 / scale,
+            ))
+        });
+        self.render_root.set_screen_rect(screen_rect);
+    }
+
+    fn on_activity(&mut self, activity: ActivityEvent) {
+        debug!("User activity changed: {:?}", activity);
+        self.render_root.edit_root_widget(|root| {
+            let mut driver_ctx = DriverCtx {
+                main_root_widget: root,
+            };
+            self.app_driver.on_activity(&mut driver_ctx, activity);
+        });
     }
 
     fn handle_signals(&mut self, _event_loop: &ActiveEventLoop) {
@@ -276,8 +851,15 @@ impl MainState<'_> {
                 render_root::RenderRootSignal::ImeStarted => {
                     // TODO
                 }
-                render_root::RenderRootSignal::ImeMoved => {
-                    // TODO
+                render_root::RenderRootSignal::ImeMoved(rect) => {
+                    self.window.set_ime_cursor_area(
+                        LogicalPosition::new(rect.x0, rect.y0),
+                        LogicalSize::new(rect.width(), rect.height()),
+                    );
+                }
+                render_root::RenderRootSignal::InputHintChanged(hint) => {
+                    let hint = hint.unwrap_or_default();
+                    self.window.set_ime_purpose(hint.ime_purpose());
                 }
                 render_root::RenderRootSignal::ImeInvalidated => {
                     // TODO
@@ -305,11 +887,89 @@ impl MainState<'_> {
                 render_root::RenderRootSignal::SetTitle(title) => {
                     self.window.set_title(&title);
                 }
+                render_root::RenderRootSignal::Feedback(feedback) => {
+                    feedback.play(&mut *self.feedback);
+                }
+                render_root::RenderRootSignal::SetIcon(icon) => {
+                    self.window
+                        .set_window_icon(icon.as_ref().and_then(AppIcon::to_window_icon));
+                    self.taskbar.set_icon(icon.as_ref());
+                }
+                render_root::RenderRootSignal::SetBadgeCount(count) => {
+                    self.taskbar.set_badge_count(count);
+                }
+                render_root::RenderRootSignal::SetProgress(progress) => {
+                    self.taskbar.set_progress(progress);
+                }
+                render_root::RenderRootSignal::SetWindowKind(kind) => {
+                    kind.apply_to_window(&self.window);
+                }
             }
         }
     }
 }
 
+/// Render `scene` to `surface`, which shows `window`.
+///
+/// Returns the frame, which is shown once it is presented, or `None` if it
+/// couldn't be rendered.
+fn render_to_window(
+    render_cx: &mut RenderContext,
+    renderer: &mut Renderer,
+    surface: &mut RenderSurface<'_>,
+    window: &Window,
+    scene: &Scene,
+) -> Option<wgpu::SurfaceTexture> {
+    let scale = window.scale_factor();
+    let size = window.inner_size();
+    let width = size.width;
+    let height = size.height;
+
+    if surface.config.width != width || surface.config.height != height {
+        render_cx.resize_surface(surface, width, height);
+    }
+
+    let transformed_scene = if scale == 1.0 {
+        None
+    } else {
+        let mut new_scene = Scene::new();
+        new_scene.append(scene, Some(Affine::scale(scale)));
+        Some(new_scene)
+    };
+    let scene_ref = transformed_scene.as_ref().unwrap_or(scene);
+
+    let surface_texture = match surface.surface.get_current_texture() {
+        Ok(surface_texture) => surface_texture,
+        // The device is fine, so only the surface needs to be configured again
+        Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+            warn!("surface lost, reconfiguring it");
+            render_cx.resize_surface(surface, width, height);
+            window.request_redraw();
+            return None;
+        }
+        Err(_) => {
+            warn!("failed to acquire next swapchain texture");
+            return None;
+        }
+    };
+    let dev_id = surface.dev_id;
+    let device = &render_cx.devices[dev_id].device;
+    let queue = &render_cx.devices[dev_id].queue;
+    let render_params = RenderParams {
+        base_color: Color::BLACK,
+        width,
+        height,
+        antialiasing_method: vello::AaConfig::Area,
+    };
+    if let Err(err) =
+        renderer.render_to_surface(device, queue, scene_ref, &surface_texture, &render_params)
+    {
+        warn!("failed to render to surface: {err}");
+        return None;
+    }
+    Some(surface_texture)
+}
+
 pub(crate) fn try_init_tracing() -> Result<(), SetGlobalDefaultError> {
     #[cfg(not(target_arch = "wasm32"))]
     {
@@ -372,3 +1032,36 @@ pub(crate) fn try_init_tracing() -> Result<(), SetGlobalDefaultError> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter(name: &str, device_type: DeviceType) -> AdapterInfo {
+        AdapterInfo {
+            name: name.to_string(),
+            vendor: 0,
+            device: 0,
+            device_type,
+            driver: String::new(),
+            driver_info: String::new(),
+            backend: wgpu::Backend::Vulkan,
+        }
+    }
+
+    #[test]
+    fn software_adapter_is_only_chosen_when_allowed() {
+        let adapters = [
+            adapter("GPU", DeviceType::DiscreteGpu),
+            adapter("llvmpipe", DeviceType::Cpu),
+        ];
+        assert_eq!(
+            software_adapter(true, false, &adapters).as_deref(),
+            Some("llvmpipe")
+        );
+        assert_eq!(software_adapter(false, false, &adapters), None);
+        // The user's choice of adapter is kept
+        assert_eq!(software_adapter(true, true, &adapters), None);
+        assert_eq!(software_adapter(true, false, &adapters[..1]), None);
+    }
+}
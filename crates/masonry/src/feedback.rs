@@ -0,0 +1,81 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sounds and haptic effects which acknowledge the user's input.
+//!
+//! Widgets request feedback with [`EventCtx::play_system_sound`] and
+//! [`EventCtx::trigger_haptic`], and the app plays it with its
+//! [`FeedbackBackend`], from [`AppDriver::feedback`]. The default backend does
+//! nothing, as the platforms Masonry runs on don't give it access to sounds or
+//! haptics, so apps which want feedback need to provide a backend which does.
+//!
+//! Built-in widgets only request feedback when it is turned on for them, such as
+//! with [`Checkbox::with_feedback`].
+//!
+//! [`EventCtx::play_system_sound`]: crate::EventCtx::play_system_sound
+//! [`EventCtx::trigger_haptic`]: crate::EventCtx::trigger_haptic
+//! [`AppDriver::feedback`]: crate::app_driver::AppDriver::feedback
+//! [`Checkbox::with_feedback`]: crate::widget::Checkbox::with_feedback
+
+/// A sound from the platform's set of system sounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SoundKind {
+    /// A control was clicked or toggled.
+    Click,
+    /// Something needs the user's attention.
+    Alert,
+    /// An action failed.
+    Error,
+    /// An action succeeded.
+    Success,
+}
+
+/// A haptic effect, on platforms with haptic feedback.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HapticKind {
+    /// A light tick, such as when a control changes state.
+    Tick,
+    /// A stronger tap which confirms an action.
+    Confirm,
+    /// An effect which signals that an action was refused.
+    Reject,
+}
+
+/// Feedback requested by a widget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Feedback {
+    Sound(SoundKind),
+    Haptic(HapticKind),
+}
+
+/// Plays the feedback requested by widgets.
+///
+/// Both methods do nothing by default, so a backend only needs to implement the
+/// kinds of feedback its platform supports.
+pub trait FeedbackBackend {
+    fn play_sound(&mut self, sound: SoundKind) {
+        let _ = sound;
+    }
+
+    fn trigger_haptic(&mut self, haptic: HapticKind) {
+        let _ = haptic;
+    }
+}
+
+/// A [`FeedbackBackend`] which doesn't play anything.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoFeedback;
+
+impl FeedbackBackend for NoFeedback {}
+
+impl Feedback {
+    /// Play this feedback with `backend`.
+    pub fn play(self, backend: &mut dyn FeedbackBackend) {
+        match self {
+            Feedback::Sound(sound) => backend.play_sound(sound),
+            Feedback::Haptic(haptic) => backend.trigger_haptic(haptic),
+        }
+    }
+}
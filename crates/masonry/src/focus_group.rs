@@ -0,0 +1,331 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A roving focus shared by the items of a composite widget.
+
+use tracing::trace;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::{AccessCtx, EventCtx, LifeCycle, LifeCycleCtx, StatusChange, TextEvent, WidgetId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Arrangement {
+    Row,
+    Column,
+    Grid { columns: usize },
+}
+
+/// The keyboard focus of a composite widget whose items are reached with the
+/// arrow keys, such as a radio group, a toolbar, a menu or a grid.
+///
+/// The widget which owns the group is a single stop in the <kbd>Tab</kbd> order,
+/// and the group tracks which of its items is active. The arrow keys move between
+/// the items, and <kbd>Home</kbd> and <kbd>End</kbd> go to the first and last one.
+/// The active item is reported to assistive technologies as the active
+/// descendant of the widget, so the items don't need to be focusable themselves.
+///
+/// The widget forwards its events to the group with [`on_text_event`],
+/// [`on_status_change`], [`lifecycle`] and [`accessibility`], and paints the active item so the user
+/// can see which one it is.
+///
+/// [`on_text_event`]: FocusGroup::on_text_event
+/// [`on_status_change`]: FocusGroup::on_status_change
+/// [`lifecycle`]: FocusGroup::lifecycle
+/// [`accessibility`]: FocusGroup::accessibility
+#[derive(Clone, Debug, PartialEq)]
+pub struct FocusGroup {
+    arrangement: Arrangement,
+    wrap: bool,
+    len: usize,
+    active: Option<usize>,
+}
+
+impl FocusGroup {
+    /// A group of items laid out left to right, moved between with
+    /// <kbd>←</kbd> and <kbd>→</kbd>.
+    pub fn row(len: usize) -> Self {
+        Self::new(Arrangement::Row, len)
+    }
+
+    /// A group of items laid out top to bottom, moved between with
+    /// <kbd>↑</kbd> and <kbd>↓</kbd>.
+    pub fn column(len: usize) -> Self {
+        Self::new(Arrangement::Column, len)
+    }
+
+    /// A group of items laid out in rows of `columns` items, moved between with
+    /// all four arrow keys.
+    pub fn grid(len: usize, columns: usize) -> Self {
+        Self::new(
+            Arrangement::Grid {
+                columns: columns.max(1),
+            },
+            len,
+        )
+    }
+
+    fn new(arrangement: Arrangement, len: usize) -> Self {
+        FocusGroup {
+            arrangement,
+            wrap: false,
+            len,
+            active: None,
+        }
+    }
+
+    /// Builder-style method to make moving past the last item go back to the
+    /// first one, and the other way around.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Builder-style method to set the active item.
+    pub fn with_active(mut self, active: usize) -> Self {
+        self.set_active(Some(active));
+        self
+    }
+
+    /// The number of items in the group.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the group has no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The index of the active item, if any.
+    pub fn active(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// Set the active item, such as when the user clicks it.
+    pub fn set_active(&mut self, active: Option<usize>) {
+        self.active = active.filter(|&ix| ix < self.len);
+    }
+
+    /// Set the number of items, such as when one is added or removed.
+    ///
+    /// If the active item no longer exists, the last one becomes active.
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        if let Some(active) = self.active {
+            self.active = len.checked_sub(1).map(|last| active.min(last));
+        }
+    }
+
+    /// The item which `key` moves the active item to, if it is a key the group
+    /// uses.
+    pub fn target_for_key(&self, key: &Key) -> Option<usize> {
+        let Key::Named(key) = key else {
+            return None;
+        };
+        let last = self.len.checked_sub(1)?;
+        let Some(active) = self.active else {
+            // The first key press only shows where the group starts
+            return matches!(
+                key,
+                NamedKey::ArrowLeft
+                    | NamedKey::ArrowRight
+                    | NamedKey::ArrowUp
+                    | NamedKey::ArrowDown
+                    | NamedKey::Home
+                    | NamedKey::End
+            )
+            .then_some(if *key == NamedKey::End { last } else { 0 });
+        };
+        let step = match (self.arrangement, key) {
+            (_, NamedKey::Home) => return Some(0),
+            (_, NamedKey::End) => return Some(last),
+            (Arrangement::Row | Arrangement::Grid { .. }, NamedKey::ArrowLeft) => -1,
+            (Arrangement::Row | Arrangement::Grid { .. }, NamedKey::ArrowRight) => 1,
+            (Arrangement::Column, NamedKey::ArrowUp) => -1,
+            (Arrangement::Column, NamedKey::ArrowDown) => 1,
+            (Arrangement::Grid { columns }, NamedKey::ArrowUp) => -(columns as isize),
+            (Arrangement::Grid { columns }, NamedKey::ArrowDown) => columns as isize,
+            _ => return None,
+        };
+        let target = active as isize + step;
+        if (0..self.len as isize).contains(&target) {
+            Some(target as usize)
+        } else if self.wrap {
+            Some(target.rem_euclid(self.len as isize) as usize)
+        } else {
+            Some(active)
+        }
+    }
+
+    /// Move the active item for an arrow key, returning whether it changed.
+    ///
+    /// The event is marked as handled if the group used it, even if the active
+    /// item was already at the edge of the group.
+    pub fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) -> bool {
+        let TextEvent::KeyboardKey(key, _) = event else {
+            return false;
+        };
+        if !key.state.is_pressed() || ctx.is_handled() {
+            return false;
+        }
+        let Some(target) = self.target_for_key(&key.logical_key) else {
+            return false;
+        };
+        ctx.set_handled();
+        if self.active == Some(target) {
+            return false;
+        }
+        trace!("FocusGroup of {:?} moved to {}", ctx.widget_id(), target);
+        self.active = Some(target);
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+        true
+    }
+
+    /// Make the widget the only stop in the focus chain for its subtree.
+    ///
+    /// This must be called after the widget's children have handled `event`, so
+    /// that it can take their places in the focus chain.
+    pub fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain => {
+                ctx.widget_state.focus_chain.clear();
+                if !self.is_empty() {
+                    ctx.register_for_focus();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Make the first item active when the widget gains focus, if none is.
+    pub fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, event: &StatusChange) {
+        if let StatusChange::FocusChanged(true) = event {
+            if self.active.is_none() && !self.is_empty() {
+                self.active = Some(0);
+                ctx.request_paint();
+                ctx.request_accessibility_update();
+            }
+        }
+    }
+
+    /// Report the active item, whose id is given by `item_id`, as the active
+    /// descendant of the widget.
+    pub fn accessibility(&self, ctx: &mut AccessCtx, item_id: impl FnOnce(usize) -> WidgetId) {
+        if let Some(active) = self.active {
+            ctx.current_node()
+                .set_active_descendant(item_id(active).into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use smallvec::smallvec;
+
+    use super::*;
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
+    use crate::widget::{Flex, Label};
+    use crate::{Point, PointerEvent, Size, Widget, WidgetPod};
+
+    #[test]
+    fn arrow_keys_move_between_items() {
+        let key = |group: &FocusGroup, key| group.target_for_key(&Key::Named(key));
+
+        let row = FocusGroup::row(3);
+        assert_eq!(key(&row, NamedKey::ArrowRight), Some(0));
+        assert_eq!(key(&row, NamedKey::End), Some(2));
+        let row = row.with_active(2);
+        assert_eq!(key(&row, NamedKey::ArrowRight), Some(2));
+        assert_eq!(key(&row, NamedKey::ArrowLeft), Some(1));
+        assert_eq!(key(&row, NamedKey::ArrowDown), None);
+        assert_eq!(key(&row.with_wrap(true), NamedKey::ArrowRight), Some(0));
+
+        let column = FocusGroup::column(3).with_active(0).with_wrap(true);
+        assert_eq!(key(&column, NamedKey::ArrowUp), Some(2));
+        assert_eq!(key(&column, NamedKey::ArrowDown), Some(1));
+        assert_eq!(key(&column, NamedKey::ArrowLeft), None);
+
+        // A grid of two rows of three, with an item missing at the end
+        let grid = FocusGroup::grid(5, 3).with_active(1);
+        assert_eq!(key(&grid, NamedKey::ArrowDown), Some(4));
+        assert_eq!(key(&grid, NamedKey::ArrowUp), Some(1));
+        assert_eq!(
+            key(&grid.clone().with_active(2), NamedKey::ArrowDown),
+            Some(2)
+        );
+        assert_eq!(key(&grid, NamedKey::Home), Some(0));
+        assert_eq!(key(&grid, NamedKey::Tab), None);
+        assert_eq!(key(&FocusGroup::row(0), NamedKey::Home), None);
+
+        let mut group = FocusGroup::row(3).with_active(2);
+        group.set_len(2);
+        assert_eq!(group.active(), Some(1));
+        group.set_active(Some(5));
+        assert_eq!(group.active(), None);
+    }
+
+    #[test]
+    fn group_is_a_single_tab_stop() {
+        fn focusable() -> impl Widget {
+            ModularWidget::new(())
+                .pointer_event_fn(|_, ctx, event| {
+                    if let PointerEvent::PointerDown(..) = event {
+                        ctx.request_focus();
+                    }
+                })
+                .lifecycle_fn(|_, ctx, event| {
+                    if let LifeCycle::BuildFocusChain = event {
+                        ctx.register_for_focus();
+                    }
+                })
+                .layout_fn(|_, _, _| Size::new(20.0, 20.0))
+        }
+
+        let items = Flex::row()
+            .with_child(focusable())
+            .with_child(focusable())
+            .with_child(Label::new("Three"));
+        let active = Rc::new(Cell::new(None));
+        let group = ModularWidget::new((FocusGroup::row(3), WidgetPod::new(items)))
+            .lifecycle_fn(|(group, items), ctx, event| {
+                items.lifecycle(ctx, event);
+                group.lifecycle(ctx, event);
+            })
+            .status_change_fn({
+                let active = active.clone();
+                move |(group, _), ctx, event| {
+                    group.on_status_change(ctx, event);
+                    active.set(group.active());
+                }
+            })
+            .layout_fn(|(_, items), ctx, bc| {
+                let size = items.layout(ctx, bc);
+                ctx.place_child(items, Point::ORIGIN);
+                size
+            })
+            .children_fn(|(_, items)| smallvec![items.id()]);
+
+        let [before, group_id, after] = widget_ids();
+        let widget = Flex::row()
+            .with_child_id(focusable(), before)
+            .with_child_id(group, group_id)
+            .with_child_id(focusable(), after);
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(before);
+        let mut next_focus = || {
+            harness.move_focus(true);
+            harness.focused_widget().unwrap().id()
+        };
+
+        // The items of the group are skipped, and focusing it makes its first item active
+        assert_eq!(active.get(), None);
+        assert_eq!(next_focus(), group_id);
+        assert_eq!(active.get(), Some(0));
+        assert_eq!(next_focus(), after);
+        assert_eq!(next_focus(), before);
+    }
+}
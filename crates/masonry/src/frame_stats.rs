@@ -0,0 +1,278 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Where the time of each frame goes, for performance work.
+//!
+//! The render root measures every frame, and widgets can read the statistics of
+//! the last one with [`LifeCycleCtx::frame_stats`]. The
+//! [`FrameStatsOverlay`] widget shows them.
+//!
+//! The input latency is measured too: every event is timestamped when the render
+//! root receives it, and the events which changed something, by requesting a
+//! repaint or a layout or by submitting an action, are followed until the frame
+//! showing the change is presented. The percentiles of these latencies over the
+//! last [`LATENCY_WINDOW`] such events are in [`FrameStats::latency`], so that
+//! regressions show up in the overlay and can be tracked in benchmarks.
+//!
+//! [`LifeCycleCtx::frame_stats`]: crate::LifeCycleCtx::frame_stats
+//! [`FrameStatsOverlay`]: crate::widget::FrameStatsOverlay
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use instant::Instant;
+use kurbo::Rect;
+
+/// How far back frames are counted to compute the frame rate.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+/// How many of the latest latencies the percentiles are computed over.
+pub const LATENCY_WINDOW: usize = 120;
+
+/// The time spent in each phase of a frame, and what it drew.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    /// The number of frames drawn in the last second.
+    pub fps: f64,
+    /// The time spent handling events since the previous frame.
+    pub event: Duration,
+    /// The time spent by the app editing the widget tree since the previous
+    /// frame, such as when Xilem rebuilds its views.
+    pub rebuild: Duration,
+    pub layout: Duration,
+    /// The time spent encoding the scene, not counting rendering it.
+    pub paint: Duration,
+    /// The time spent rendering the scene on the GPU, as reported by the event loop.
+    pub render: Duration,
+    pub widget_count: usize,
+    /// The bounds of the widgets which were repainted, in window coordinates.
+    ///
+    /// Widgets are repainted along with their ancestors, so only the innermost
+    /// repainted widgets count.
+    pub damage: Rect,
+    pub latency: LatencyStats,
+}
+
+/// How long an event took to change the app and to be shown, from when the
+/// render root received it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Latency {
+    /// The time until the event was handled, including the rebuild of the widget
+    /// tree by the app if the event submitted an action.
+    pub state_change: Duration,
+    /// The time until the frame showing the change was presented.
+    pub present: Duration,
+}
+
+/// The end-to-end latencies of the latest events which changed something.
+///
+/// When several events arrive before a frame is presented, the frame only counts
+/// for the first of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LatencyStats {
+    /// The latency of the latest event, if any event was measured.
+    pub last: Option<Latency>,
+    /// The median of [`Latency::present`].
+    pub p50: Duration,
+    /// The 95th percentile of [`Latency::present`].
+    pub p95: Duration,
+    /// The 99th percentile of [`Latency::present`].
+    pub p99: Duration,
+    /// How many events these are computed from, at most [`LATENCY_WINDOW`].
+    pub samples: usize,
+}
+
+impl LatencyStats {
+    fn new(latencies: &VecDeque<Latency>) -> Self {
+        let mut present: Vec<_> = latencies.iter().map(|latency| latency.present).collect();
+        present.sort_unstable();
+        // The nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (present.len() * p).div_ceil(100);
+            present
+                .get(rank.saturating_sub(1))
+                .copied()
+                .unwrap_or_default()
+        };
+        LatencyStats {
+            last: latencies.back().copied(),
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+            samples: present.len(),
+        }
+    }
+}
+
+/// An event which changed something, waiting for its frame to be presented.
+#[derive(Clone, Copy, Debug)]
+struct PendingLatency {
+    received: Instant,
+    changed: Instant,
+}
+
+impl FrameStats {
+    /// The area of [`damage`](Self::damage), in logical pixels.
+    pub fn damage_area(&self) -> f64 {
+        self.damage.area()
+    }
+}
+
+/// Collects the statistics of the frame being prepared.
+#[derive(Debug, Default)]
+pub(crate) struct FrameRecorder {
+    /// The statistics of the last frame drawn.
+    pub(crate) last: FrameStats,
+    current: FrameStats,
+    /// The number of widgets repainted in the current frame, to tell whether a
+    /// widget's children were repainted.
+    painted_widgets: usize,
+    frame_times: VecDeque<Instant>,
+    pending_latency: Option<PendingLatency>,
+    latencies: VecDeque<Latency>,
+}
+
+impl FrameRecorder {
+    pub(crate) fn add_event(&mut self, duration: Duration) {
+        self.current.event += duration;
+    }
+
+    pub(crate) fn add_rebuild(&mut self, duration: Duration) {
+        self.current.rebuild += duration;
+    }
+
+    pub(crate) fn add_layout(&mut self, duration: Duration) {
+        self.current.layout += duration;
+    }
+
+    pub(crate) fn add_paint(&mut self, duration: Duration) {
+        self.current.paint += duration;
+    }
+
+    /// Record that an event received at `received` was handled at `now`, and
+    /// whether it changed something which will be shown.
+    pub(crate) fn add_handled_event(&mut self, received: Instant, now: Instant, changed: bool) {
+        if changed && self.pending_latency.is_none() {
+            self.pending_latency = Some(PendingLatency {
+                received,
+                changed: now,
+            });
+        }
+    }
+
+    /// Record that the app finished changing the widget tree at `now`, which is
+    /// part of the state change of a pending event.
+    pub(crate) fn add_state_change(&mut self, now: Instant) {
+        if let Some(pending) = &mut self.pending_latency {
+            pending.changed = now;
+        }
+    }
+
+    pub(crate) fn painted_widgets(&self) -> usize {
+        self.painted_widgets
+    }
+
+    /// Record that a widget at `rect` was repainted, after its children.
+    pub(crate) fn add_painted_widget(&mut self, rect: Rect, painted_children: bool) {
+        self.painted_widgets += 1;
+        if !painted_children {
+            self.current.damage = if self.current.damage.is_empty() {
+                rect
+            } else {
+                self.current.damage.union(rect)
+            };
+        }
+    }
+
+    /// Finish the current frame, drawn at `now`.
+    pub(crate) fn finish_frame(&mut self, now: Instant, widget_count: usize) {
+        self.frame_times.push_back(now);
+        while self
+            .frame_times
+            .front()
+            .is_some_and(|time| now.duration_since(*time) > FPS_WINDOW)
+        {
+            self.frame_times.pop_front();
+        }
+        let current = std::mem::take(&mut self.current);
+        self.last = FrameStats {
+            fps: self.frame_times.len() as f64 / FPS_WINDOW.as_secs_f64(),
+            widget_count,
+            latency: self.last.latency,
+            ..current
+        };
+        self.painted_widgets = 0;
+    }
+
+    /// Record the time the last frame took to render, and that it was presented
+    /// at `now`.
+    pub(crate) fn set_render(&mut self, duration: Duration, now: Instant) {
+        self.last.render = duration;
+        if let Some(pending) = self.pending_latency.take() {
+            if self.latencies.len() == LATENCY_WINDOW {
+                self.latencies.pop_front();
+            }
+            self.latencies.push_back(Latency {
+                state_change: pending.changed.duration_since(pending.received),
+                present: now.duration_since(pending.received),
+            });
+            self.last.latency = LatencyStats::new(&self.latencies);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_are_counted_over_a_second() {
+        let mut recorder = FrameRecorder::default();
+        let start = Instant::now();
+        for frame in 0..30 {
+            recorder.add_layout(Duration::from_millis(2));
+            recorder.add_painted_widget(Rect::new(0.0, 0.0, 10.0, 10.0), false);
+            recorder.add_painted_widget(Rect::new(0.0, 0.0, 100.0, 100.0), true);
+            recorder.finish_frame(start + Duration::from_millis(frame * 50), 12);
+        }
+        let stats = recorder.last;
+        // Frames 9 to 29 are within a second of the last one
+        assert_eq!(stats.fps, 21.0);
+        assert_eq!(stats.layout, Duration::from_millis(2));
+        assert_eq!(stats.widget_count, 12);
+        // The parent's repaint doesn't count, as it was only for its child
+        assert_eq!(stats.damage_area(), 100.0);
+        assert_eq!(recorder.painted_widgets(), 0);
+    }
+
+    #[test]
+    fn latency_percentiles_follow_changed_events() {
+        let mut recorder = FrameRecorder::default();
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        for latency in 1..=200 {
+            let received = start + ms(latency * 1000);
+            recorder.add_handled_event(received, received + ms(1), true);
+            // A later event before the frame doesn't count on its own
+            recorder.add_handled_event(received + ms(1), received + ms(1), true);
+            recorder.add_state_change(received + ms(2));
+            recorder.set_render(ms(1), received + ms(latency));
+        }
+        // An event which changed nothing isn't measured
+        recorder.add_handled_event(start, start, false);
+        recorder.set_render(ms(1), start + ms(500));
+
+        let latency = recorder.last.latency;
+        assert_eq!(latency.samples, LATENCY_WINDOW);
+        assert_eq!(
+            latency.last,
+            Some(Latency {
+                state_change: ms(2),
+                present: ms(200)
+            })
+        );
+        // The window holds the latencies from 81ms to 200ms
+        assert_eq!(latency.p50, ms(140));
+        assert_eq!(latency.p95, ms(194));
+        assert_eq!(latency.p99, ms(199));
+    }
+}
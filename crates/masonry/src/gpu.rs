@@ -0,0 +1,176 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! GPU resources owned by widgets, such as textures.
+//!
+//! Widgets which keep their own GPU state register it with
+//! [`LifeCycleCtx::register_gpu_resource`] (or the equivalent method on other
+//! contexts). The renderer then creates the resource on its device before the
+//! next frame, releases it when the widget is removed from the tree, and
+//! recreates it if the device is lost and replaced.
+//!
+//! Images painted with [`vello`] don't need this: the renderer uploads them itself.
+//!
+//! [`LifeCycleCtx::register_gpu_resource`]: crate::LifeCycleCtx::register_gpu_resource
+
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use wgpu::{Device, Queue};
+
+use crate::{AsAny, WidgetId};
+
+/// State which a widget keeps on the GPU.
+///
+/// Resources are created lazily, so a resource should hold its GPU objects in
+/// `Option`s, or similar, which are empty until [`create`](Self::create) is called.
+pub trait GpuResource: AsAny {
+    /// Create the resource's GPU objects on `device`.
+    ///
+    /// This is called before the first frame after the resource is registered, and
+    /// again after the device is lost and replaced.
+    fn create(&mut self, device: &Device, queue: &Queue);
+
+    /// Release the resource's GPU objects.
+    ///
+    /// This is called when the device is lost. When the owning widget is removed,
+    /// or it unregisters the resource, the resource is dropped instead.
+    fn release(&mut self);
+}
+
+/// A handle to a [`GpuResource`] registered by a widget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GpuResourceId(NonZeroU64);
+
+impl GpuResourceId {
+    fn next() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(1);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        GpuResourceId(id.try_into().unwrap())
+    }
+}
+
+struct Entry {
+    owner: WidgetId,
+    resource: Box<dyn GpuResource>,
+    created: bool,
+}
+
+/// The GPU resources of all the widgets in a window.
+#[derive(Default)]
+pub(crate) struct GpuResources {
+    entries: HashMap<GpuResourceId, Entry>,
+}
+
+impl GpuResources {
+    pub(crate) fn register(
+        &mut self,
+        owner: WidgetId,
+        resource: Box<dyn GpuResource>,
+    ) -> GpuResourceId {
+        let id = GpuResourceId::next();
+        self.entries.insert(
+            id,
+            Entry {
+                owner,
+                resource,
+                created: false,
+            },
+        );
+        id
+    }
+
+    pub(crate) fn unregister(&mut self, id: GpuResourceId) {
+        self.entries.remove(&id);
+    }
+
+    /// Drop all resources registered by `owner`.
+    pub(crate) fn remove_owned_by(&mut self, owner: WidgetId) {
+        self.entries.retain(|_, entry| entry.owner != owner);
+    }
+
+    pub(crate) fn get_mut<R: GpuResource>(&mut self, id: GpuResourceId) -> Option<&mut R> {
+        let entry = self.entries.get_mut(&id)?;
+        entry.resource.as_mut_dyn_any().downcast_mut()
+    }
+
+    /// Create the resources which don't exist on the current device yet.
+    pub(crate) fn create_pending(&mut self, device: &Device, queue: &Queue) {
+        for entry in self.entries.values_mut().filter(|entry| !entry.created) {
+            entry.resource.create(device, queue);
+            entry.created = true;
+        }
+    }
+
+    /// Release every resource, so that they are all created again on the next device.
+    pub(crate) fn release_all(&mut self) {
+        for entry in self.entries.values_mut().filter(|entry| entry.created) {
+            entry.resource.release();
+            entry.created = false;
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::testing::{ModularWidget, Record, Recording, TestHarness, TestWidgetExt as _};
+    use crate::widget::Flex;
+    use crate::LifeCycle;
+
+    struct Texture {
+        dropped: Rc<Cell<bool>>,
+    }
+
+    impl GpuResource for Texture {
+        fn create(&mut self, _device: &Device, _queue: &Queue) {}
+        fn release(&mut self) {}
+    }
+
+    impl Drop for Texture {
+        fn drop(&mut self) {
+            self.dropped.set(true);
+        }
+    }
+
+    #[test]
+    fn resources_follow_their_widget() {
+        let dropped = Rc::new(Cell::new(false));
+        let recording = Recording::default();
+        let texture_dropped = dropped.clone();
+        let widget =
+            ModularWidget::new(Some(texture_dropped)).lifecycle_fn(|dropped, ctx, event| {
+                if let (LifeCycle::WidgetAdded, Some(dropped)) = (event, dropped.take()) {
+                    ctx.register_gpu_resource(Texture { dropped });
+                }
+            });
+        let root = Flex::row().with_child(widget.record(&recording));
+
+        let mut harness = TestHarness::create(root);
+        assert_eq!(harness.gpu_resources().len(), 1);
+
+        recording.clear();
+        harness.lose_gpu_device();
+        assert!(recording
+            .drain()
+            .iter()
+            .any(|event| matches!(event, Record::L(LifeCycle::GpuDeviceLost))));
+        assert!(!dropped.get());
+
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<Flex>();
+            root.remove_child(0);
+        });
+        assert!(dropped.get());
+        assert_eq!(harness.gpu_resources().len(), 0);
+    }
+}
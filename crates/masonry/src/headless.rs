@@ -0,0 +1,170 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rendering widgets to images, without a window or an event loop.
+//!
+//! [`render_to_image`] lays out and paints a widget tree once, renders it on the
+//! GPU and reads the pixels back, such as to generate thumbnails, email previews
+//! or screenshots for documentation in CI. Machines without a GPU need a software
+//! adapter, such as llvmpipe or WARP, for this to work.
+
+use std::num::NonZeroUsize;
+
+use image::RgbaImage;
+use vello::kurbo::{Affine, Size};
+use vello::peniko::Color;
+use vello::util::RenderContext;
+use vello::{AaConfig, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
+use winit::dpi::PhysicalSize;
+
+use crate::conventions::PlatformConventions;
+use crate::event::WindowEvent;
+use crate::icon::IconSet;
+use crate::raster_cache;
+use crate::render_diagnostics::RenderError;
+use crate::render_root::{RenderRoot, WindowSizePolicy};
+use crate::{theme, Widget};
+
+/// How an image rendered with [`render_to_image`] looks.
+#[derive(Clone, Debug)]
+pub struct HeadlessTheme {
+    /// The color behind the widgets, which defaults to the window background.
+    pub background: Color,
+    /// The number of image pixels per logical pixel, such as `2.0` for an image
+    /// as it would be shown on a high density display.
+    pub scale_factor: f64,
+    /// The platform conventions widgets follow, such as the order of dialog
+    /// buttons, which default to those of the current platform.
+    pub conventions: PlatformConventions,
+    pub icons: IconSet,
+}
+
+impl Default for HeadlessTheme {
+    fn default() -> Self {
+        HeadlessTheme {
+            background: theme::WINDOW_BACKGROUND_COLOR,
+            scale_factor: 1.0,
+            conventions: PlatformConventions::default(),
+            icons: IconSet::default(),
+        }
+    }
+}
+
+/// Lay out `root_widget` in `size` logical pixels, and render it to an image.
+///
+/// The image is `size` times the theme's scale factor, rounded up to whole pixels.
+/// Animations are shown as of their first frame, and content which loads in the
+/// background as it is before it loads.
+pub fn render_to_image(
+    root_widget: impl Widget,
+    size: Size,
+    theme: &HeadlessTheme,
+) -> Result<RgbaImage, RenderError> {
+    let (mut render_root, pixels) = headless_root(root_widget, size, theme);
+    let (scene, _) = render_root.redraw();
+
+    let mut render_cx = RenderContext::new().map_err(|err| RenderError::Renderer {
+        gpu: None,
+        message: err.to_string(),
+    })?;
+    let device_id = pollster::block_on(render_cx.device(None)).ok_or(RenderError::Renderer {
+        gpu: None,
+        message: "no GPU adapter was found".into(),
+    })?;
+    let device_handle = &render_cx.devices[device_id];
+    let (device, queue) = (&device_handle.device, &device_handle.queue);
+    let renderer_error = |message: String| RenderError::Renderer { gpu: None, message };
+    render_root.prepare_gpu_resources(device, queue);
+    let mut renderer = Renderer::new(
+        device,
+        RendererOptions {
+            surface_format: None,
+            use_cpu: false,
+            antialiasing_support: AaSupport::area_only(),
+            num_init_threads: NonZeroUsize::new(1),
+        },
+    )
+    .map_err(|err| renderer_error(err.to_string()))?;
+
+    let mut scaled = Scene::new();
+    scaled.append(&scene, Some(Affine::scale(theme.scale_factor)));
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("headless target"),
+        size: wgpu::Extent3d {
+            width: pixels.width,
+            height: pixels.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+    let params = RenderParams {
+        base_color: theme.background,
+        width: pixels.width,
+        height: pixels.height,
+        antialiasing_method: AaConfig::Area,
+    };
+    renderer
+        .render_to_texture(device, queue, &scaled, &view, &params)
+        .map_err(|err| renderer_error(err.to_string()))?;
+    #[cfg(feature = "effects")]
+    crate::effects::EffectRenderer::default().render(
+        &render_root.effect_layers(),
+        &scene,
+        &mut renderer,
+        crate::effects::EffectTarget {
+            device,
+            queue,
+            texture: &target,
+        },
+        theme.scale_factor,
+    );
+
+    let data = raster_cache::read_texture(device, queue, &target).map_err(renderer_error)?;
+    RgbaImage::from_raw(pixels.width, pixels.height, data)
+        .ok_or_else(|| renderer_error("the image was read back with the wrong size".into()))
+}
+
+/// A render root for `root_widget`, laid out in `size` logical pixels, and the size
+/// of its image in physical pixels.
+fn headless_root(
+    root_widget: impl Widget,
+    size: Size,
+    theme: &HeadlessTheme,
+) -> (RenderRoot, PhysicalSize<u32>) {
+    let pixels = (size * theme.scale_factor).ceil();
+    let pixels = PhysicalSize::new(pixels.width.max(1.0) as u32, pixels.height.max(1.0) as u32);
+    let mut render_root = RenderRoot::new(root_widget, WindowSizePolicy::User, theme.scale_factor);
+    render_root.set_conventions(theme.conventions);
+    render_root.set_icons(theme.icons.clone());
+    render_root.handle_window_event(WindowEvent::Resize(pixels));
+    (render_root, pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::{Flex, SizedBox};
+
+    #[test]
+    fn headless_root_fills_the_image() {
+        let theme = HeadlessTheme {
+            scale_factor: 2.0,
+            ..HeadlessTheme::default()
+        };
+        let widget = Flex::column().with_child(SizedBox::empty().width(20.0).height(10.0));
+        let (mut render_root, pixels) = headless_root(widget, Size::new(120.0, 80.0), &theme);
+        assert_eq!(pixels, PhysicalSize::new(240, 160));
+
+        let _ = render_root.redraw();
+        let root = render_root.root_widget();
+        assert_eq!(root.state().layout_rect().size(), Size::new(120.0, 80.0));
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The icons available to widgets.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use kurbo::{BezPath, Size, SvgParseError};
+
+/// The name of an icon in an [`IconSet`].
+///
+/// The constants are the icons in the default set. Icon packs should use the same
+/// names for the same icons, so that they can be swapped without changing the app.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IconName(Cow<'static, str>);
+
+impl IconName {
+    pub const ADD: IconName = IconName::new_static("add");
+    pub const REMOVE: IconName = IconName::new_static("remove");
+    pub const CLOSE: IconName = IconName::new_static("close");
+    pub const CHECK: IconName = IconName::new_static("check");
+    pub const MENU: IconName = IconName::new_static("menu");
+    pub const CHEVRON_LEFT: IconName = IconName::new_static("chevron_left");
+    pub const CHEVRON_RIGHT: IconName = IconName::new_static("chevron_right");
+    pub const CHEVRON_UP: IconName = IconName::new_static("chevron_up");
+    pub const CHEVRON_DOWN: IconName = IconName::new_static("chevron_down");
+
+    pub const fn new_static(name: &'static str) -> Self {
+        IconName(Cow::Borrowed(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&'static str> for IconName {
+    fn from(name: &'static str) -> Self {
+        IconName::new_static(name)
+    }
+}
+
+impl From<String> for IconName {
+    fn from(name: String) -> Self {
+        IconName(Cow::Owned(name))
+    }
+}
+
+/// The outline of an icon, which is filled with the icon's color.
+#[derive(Clone, Debug)]
+pub struct IconShape {
+    path: BezPath,
+    view_box: Size,
+}
+
+impl IconShape {
+    /// Create an icon from a path drawn in a box of `view_box`, with its origin at
+    /// the top left.
+    pub fn new(path: BezPath, view_box: Size) -> Self {
+        IconShape { path, view_box }
+    }
+
+    /// Create an icon from SVG path data, such as the `d` attribute of an SVG
+    /// `<path>`, drawn in a box of `view_box`.
+    pub fn from_svg_path(data: &str, view_box: Size) -> Result<Self, SvgParseError> {
+        Ok(IconShape::new(BezPath::from_svg(data)?, view_box))
+    }
+
+    pub fn path(&self) -> &BezPath {
+        &self.path
+    }
+
+    pub fn view_box(&self) -> Size {
+        self.view_box
+    }
+}
+
+/// The paths of the default icons, drawn in a 24x24 box.
+const DEFAULT_ICONS: &[(IconName, &str)] = &[
+    (IconName::ADD, "M11 5h2v6h6v2h-6v6h-2v-6H5v-2h6z"),
+    (IconName::REMOVE, "M5 11h14v2H5z"),
+    (
+        IconName::CLOSE,
+        "M6.4 5L12 10.6 17.6 5 19 6.4 13.4 12 19 17.6 17.6 19 12 13.4 6.4 19 5 17.6 10.6 12 5 6.4z",
+    ),
+    (
+        IconName::CHECK,
+        "M9.5 15.6L5.4 11.5 4 12.9l5.5 5.5L20 7.9l-1.4-1.4z",
+    ),
+    (IconName::MENU, "M4 6h16v2H4zm0 5h16v2H4zm0 5h16v2H4z"),
+    (
+        IconName::CHEVRON_LEFT,
+        "M14.6 6L16 7.4 11.4 12 16 16.6 14.6 18l-6-6z",
+    ),
+    (
+        IconName::CHEVRON_RIGHT,
+        "M9.4 6L8 7.4 12.6 12 8 16.6 9.4 18l6-6z",
+    ),
+    (
+        IconName::CHEVRON_UP,
+        "M6 14.6L7.4 16 12 11.4 16.6 16 18 14.6l-6-6z",
+    ),
+    (
+        IconName::CHEVRON_DOWN,
+        "M6 9.4L7.4 8 12 12.6 16.6 8 18 9.4l-6 6z",
+    ),
+];
+
+/// The icons available to widgets, keyed by name.
+///
+/// The default set contains a few common icons, named by the constants on
+/// [`IconName`]. Design systems can replace it with their own icon pack, or add to
+/// it, with [`RenderRoot::set_icons`](crate::render_root::RenderRoot::set_icons).
+#[derive(Clone, Debug)]
+pub struct IconSet {
+    icons: Arc<HashMap<IconName, IconShape>>,
+}
+
+impl IconSet {
+    /// A set without any icons.
+    pub fn empty() -> Self {
+        IconSet {
+            icons: Default::default(),
+        }
+    }
+
+    /// Builder-style method to add `icon`, replacing any icon with the same name.
+    pub fn with_icon(mut self, name: impl Into<IconName>, icon: IconShape) -> Self {
+        self.insert(name, icon);
+        self
+    }
+
+    /// Add `icon`, replacing any icon with the same name.
+    pub fn insert(&mut self, name: impl Into<IconName>, icon: IconShape) {
+        Arc::make_mut(&mut self.icons).insert(name.into(), icon);
+    }
+
+    /// Add all the icons of `other`, replacing the icons with the same names.
+    pub fn extend(&mut self, other: &IconSet) {
+        let icons = Arc::make_mut(&mut self.icons);
+        for (name, icon) in other.icons.iter() {
+            icons.insert(name.clone(), icon.clone());
+        }
+    }
+
+    pub fn get(&self, name: &IconName) -> Option<&IconShape> {
+        self.icons.get(name)
+    }
+}
+
+impl Default for IconSet {
+    fn default() -> Self {
+        let view_box = Size::new(24.0, 24.0);
+        let mut icons = IconSet::empty();
+        for (name, data) in DEFAULT_ICONS {
+            let icon = IconShape::from_svg_path(data, view_box).unwrap();
+            icons.insert(name.clone(), icon);
+        }
+        icons
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::Shape as _;
+
+    use super::*;
+
+    #[test]
+    fn icon_packs_replace_default_icons() {
+        let defaults = IconSet::default();
+        for (name, _) in DEFAULT_ICONS {
+            let icon = defaults.get(name).unwrap();
+            let view_box = icon.view_box().to_rect();
+            let bounds = icon.path().bounding_box();
+            assert_eq!(view_box.union(bounds), view_box, "{name:?}");
+        }
+
+        let square = IconShape::from_svg_path("M0 0h8v8H0z", Size::new(8.0, 8.0)).unwrap();
+        let mut icons = defaults.clone();
+        icons.extend(&IconSet::empty().with_icon(IconName::ADD, square));
+        assert_eq!(
+            icons.get(&IconName::ADD).unwrap().view_box(),
+            Size::new(8.0, 8.0)
+        );
+        assert_eq!(
+            defaults.get(&IconName::ADD).unwrap().view_box(),
+            Size::new(24.0, 24.0)
+        );
+        assert!(icons.get(&"unknown".into()).is_none());
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Telling the app when the user stops and starts using it.
+//!
+//! Apps which want to know, such as to autosave, to show a screensaver or to pause
+//! expensive animations, return a timeout from [`AppDriver::idle_timeout`]. Once
+//! the user hasn't used the mouse or the keyboard for that long,
+//! [`AppDriver::on_activity`] is called with [`ActivityEvent::UserIdle`], and
+//! then with [`ActivityEvent::UserActive`] when they use either again.
+//!
+//! [`AppDriver::idle_timeout`]: crate::app_driver::AppDriver::idle_timeout
+//! [`AppDriver::on_activity`]: crate::app_driver::AppDriver::on_activity
+
+use std::time::Duration;
+
+use instant::Instant;
+
+/// A change in whether the user is using the app.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivityEvent {
+    /// The user hasn't used the mouse or the keyboard for this long.
+    UserIdle(Duration),
+    /// The user used the mouse or the keyboard after being idle.
+    UserActive,
+}
+
+/// Tracks the user's input to tell when they become idle.
+///
+/// The event loop reports input with [`on_input`](Self::on_input), and calls
+/// [`poll`](Self::poll) when it wakes up at the [`deadline`](Self::deadline).
+#[derive(Clone, Debug)]
+pub struct IdleDetector {
+    timeout: Duration,
+    last_input: Instant,
+    idle: bool,
+}
+
+impl IdleDetector {
+    /// A detector for when the user is idle for `timeout`, counting from `now`.
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        IdleDetector {
+            timeout,
+            last_input: now,
+            idle: false,
+        }
+    }
+
+    /// Whether the user is currently idle.
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    /// Record that the user used the mouse or the keyboard at `now`, returning
+    /// [`ActivityEvent::UserActive`] if they were idle.
+    pub fn on_input(&mut self, now: Instant) -> Option<ActivityEvent> {
+        self.last_input = now;
+        std::mem::take(&mut self.idle).then_some(ActivityEvent::UserActive)
+    }
+
+    /// Check whether the user has become idle by `now`, returning
+    /// [`ActivityEvent::UserIdle`] the first time they have.
+    pub fn poll(&mut self, now: Instant) -> Option<ActivityEvent> {
+        let inactive = now.saturating_duration_since(self.last_input);
+        if self.idle || inactive < self.timeout {
+            return None;
+        }
+        self.idle = true;
+        Some(ActivityEvent::UserIdle(inactive))
+    }
+
+    /// When the user will become idle if they don't use the app before then, or
+    /// `None` if they already are.
+    pub fn deadline(&self) -> Option<Instant> {
+        (!self.idle).then(|| self.last_input + self.timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_after_timeout_until_input() {
+        let start = Instant::now();
+        let timeout = Duration::from_secs(60);
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut detector = IdleDetector::new(timeout, start);
+        assert_eq!(detector.deadline(), Some(at(60)));
+
+        assert_eq!(detector.poll(at(30)), None);
+        assert_eq!(detector.on_input(at(30)), None);
+        assert_eq!(detector.poll(at(60)), None);
+        assert_eq!(detector.deadline(), Some(at(90)));
+
+        assert_eq!(
+            detector.poll(at(95)),
+            Some(ActivityEvent::UserIdle(Duration::from_secs(65)))
+        );
+        assert!(detector.is_idle());
+        assert_eq!(detector.deadline(), None);
+        // The user is only reported as idle once
+        assert_eq!(detector.poll(at(120)), None);
+
+        assert_eq!(detector.on_input(at(130)), Some(ActivityEvent::UserActive));
+        assert_eq!(detector.on_input(at(131)), None);
+        assert_eq!(detector.deadline(), Some(at(191)));
+    }
+}
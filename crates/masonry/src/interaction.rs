@@ -0,0 +1,214 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The timings and distances which decide how input is interpreted.
+//!
+//! Widgets read the app's [`InteractionSettings`] with
+//! [`EventCtx::interaction_settings`], rather than hardcoding them, so that apps and
+//! users can adjust them, such as for users who need more time to double click.
+//! Apps set them with [`AppDriver::interaction_settings`], which defaults to
+//! [`InteractionSettings::from_env`].
+//!
+//! [`EventCtx::interaction_settings`]: crate::EventCtx::interaction_settings
+//! [`AppDriver::interaction_settings`]: crate::app_driver::AppDriver::interaction_settings
+
+use std::time::Duration;
+
+/// The timings and distances which decide how input is interpreted.
+///
+/// Distances are in logical pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InteractionSettings {
+    /// The longest time between two clicks for them to count as a double click.
+    pub double_click_interval: Duration,
+    /// The furthest the pointer can move between two clicks for them to count as a
+    /// double click.
+    pub double_click_distance: f64,
+    /// How far the pointer must move while pressed before it starts a drag, so that
+    /// a click with a slightly shaky hand isn't a drag.
+    pub drag_threshold: f64,
+    /// How long the pointer must rest on a widget before it shows a tooltip or
+    /// other hover feedback.
+    pub hover_delay: Duration,
+    /// How long a button must be held before it starts repeating, such as the
+    /// buttons of a stepper.
+    pub repeat_delay: Duration,
+    /// The time between repeats of a held button.
+    pub repeat_interval: Duration,
+    /// How long the text caret is shown, and then hidden, while it blinks.
+    ///
+    /// A zero interval keeps the caret shown, for users who find blinking
+    /// distracting.
+    pub caret_blink_interval: Duration,
+    /// The longest pause between typed characters for them to be searched for as
+    /// one prefix, when typing to jump to an item of a list.
+    pub type_ahead_timeout: Duration,
+}
+
+impl Default for InteractionSettings {
+    /// The usual settings of the current platform.
+    fn default() -> Self {
+        // These are the defaults of each platform's settings, which apps can't
+        // read without platform-specific dependencies
+        let double_click_interval = if cfg!(target_os = "linux") {
+            // GTK's default
+            Duration::from_millis(400)
+        } else {
+            Duration::from_millis(500)
+        };
+        InteractionSettings {
+            double_click_interval,
+            double_click_distance: 4.0,
+            drag_threshold: if cfg!(target_os = "windows") {
+                4.0
+            } else {
+                8.0
+            },
+            hover_delay: Duration::from_millis(500),
+            repeat_delay: Duration::from_millis(500),
+            repeat_interval: Duration::from_millis(33),
+            caret_blink_interval: if cfg!(target_os = "windows") {
+                Duration::from_millis(530)
+            } else {
+                // Half of GTK's blink cycle
+                Duration::from_millis(600)
+            },
+            type_ahead_timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+impl InteractionSettings {
+    /// The usual settings of the current platform, overridden by any of these
+    /// environment variables which are set:
+    ///
+    /// - `MASONRY_DOUBLE_CLICK_MS`
+    /// - `MASONRY_DOUBLE_CLICK_DISTANCE`
+    /// - `MASONRY_DRAG_THRESHOLD`
+    /// - `MASONRY_HOVER_DELAY_MS`
+    /// - `MASONRY_REPEAT_DELAY_MS`
+    /// - `MASONRY_REPEAT_INTERVAL_MS`
+    /// - `MASONRY_CARET_BLINK_MS`
+    /// - `MASONRY_TYPE_AHEAD_MS`
+    ///
+    /// Variables which can't be parsed are ignored, with a warning.
+    pub fn from_env() -> Self {
+        Self::default().with_overrides(|name| std::env::var(name).ok())
+    }
+
+    /// Builder-style method to set [`double_click_interval`](Self::double_click_interval).
+    pub fn with_double_click_interval(mut self, interval: Duration) -> Self {
+        self.double_click_interval = interval;
+        self
+    }
+
+    /// Builder-style method to set [`drag_threshold`](Self::drag_threshold).
+    pub fn with_drag_threshold(mut self, threshold: f64) -> Self {
+        self.drag_threshold = threshold;
+        self
+    }
+
+    /// Builder-style method to set [`hover_delay`](Self::hover_delay).
+    pub fn with_hover_delay(mut self, delay: Duration) -> Self {
+        self.hover_delay = delay;
+        self
+    }
+
+    /// Builder-style method to set [`repeat_delay`](Self::repeat_delay) and
+    /// [`repeat_interval`](Self::repeat_interval).
+    pub fn with_repeat(mut self, delay: Duration, interval: Duration) -> Self {
+        self.repeat_delay = delay;
+        self.repeat_interval = interval;
+        self
+    }
+
+    /// Builder-style method to set [`caret_blink_interval`](Self::caret_blink_interval).
+    pub fn with_caret_blink_interval(mut self, interval: Duration) -> Self {
+        self.caret_blink_interval = interval;
+        self
+    }
+
+    /// Builder-style method to set [`type_ahead_timeout`](Self::type_ahead_timeout).
+    pub fn with_type_ahead_timeout(mut self, timeout: Duration) -> Self {
+        self.type_ahead_timeout = timeout;
+        self
+    }
+
+    /// Whether the pointer has moved far enough from where it was pressed to start
+    /// a drag.
+    pub fn is_drag(&self, distance: f64) -> bool {
+        distance.abs() >= self.drag_threshold
+    }
+
+    fn with_overrides(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        let parse = |name: &str| -> Option<f64> {
+            let value = var(name)?;
+            match value.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() && value >= 0.0 => Some(value),
+                _ => {
+                    tracing::warn!("Ignoring invalid value '{value}' for {name}");
+                    None
+                }
+            }
+        };
+        let millis = |name: &str| parse(name).map(|ms| Duration::from_secs_f64(ms / 1000.0));
+
+        if let Some(interval) = millis("MASONRY_DOUBLE_CLICK_MS") {
+            self.double_click_interval = interval;
+        }
+        if let Some(distance) = parse("MASONRY_DOUBLE_CLICK_DISTANCE") {
+            self.double_click_distance = distance;
+        }
+        if let Some(threshold) = parse("MASONRY_DRAG_THRESHOLD") {
+            self.drag_threshold = threshold;
+        }
+        if let Some(delay) = millis("MASONRY_HOVER_DELAY_MS") {
+            self.hover_delay = delay;
+        }
+        if let Some(delay) = millis("MASONRY_REPEAT_DELAY_MS") {
+            self.repeat_delay = delay;
+        }
+        if let Some(interval) = millis("MASONRY_REPEAT_INTERVAL_MS") {
+            self.repeat_interval = interval;
+        }
+        if let Some(interval) = millis("MASONRY_CARET_BLINK_MS") {
+            self.caret_blink_interval = interval;
+        }
+        if let Some(timeout) = millis("MASONRY_TYPE_AHEAD_MS") {
+            self.type_ahead_timeout = timeout;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_overrides_defaults() {
+        let settings = InteractionSettings::default().with_overrides(|name| match name {
+            "MASONRY_DOUBLE_CLICK_MS" => Some("750".into()),
+            "MASONRY_DRAG_THRESHOLD" => Some(" 12 ".into()),
+            "MASONRY_HOVER_DELAY_MS" => Some("soon".into()),
+            "MASONRY_REPEAT_INTERVAL_MS" => Some("-5".into()),
+            "MASONRY_CARET_BLINK_MS" => Some("0".into()),
+            _ => None,
+        });
+        let defaults = InteractionSettings::default();
+        assert_eq!(settings.double_click_interval, Duration::from_millis(750));
+        assert_eq!(settings.drag_threshold, 12.0);
+        assert_eq!(settings.caret_blink_interval, Duration::ZERO);
+        // Invalid values are ignored
+        assert_eq!(settings.hover_delay, defaults.hover_delay);
+        assert_eq!(settings.repeat_interval, defaults.repeat_interval);
+        assert_eq!(
+            settings.double_click_distance,
+            defaults.double_click_distance
+        );
+
+        assert!(!settings.is_drag(-11.0));
+        assert!(settings.is_drag(-12.0));
+    }
+}
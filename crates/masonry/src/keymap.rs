@@ -0,0 +1,338 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The keyboard shortcuts used by widgets.
+
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::conventions::PlatformConventions;
+use crate::text2::Selectable;
+
+/// A movement of the caret through some text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CaretMovement {
+    /// To the previous grapheme cluster.
+    PrevGrapheme,
+    /// To the next grapheme cluster.
+    NextGrapheme,
+    /// To the start of the current or previous word.
+    PrevWord,
+    /// To the end of the current or next word.
+    NextWord,
+    /// To the start of the current line.
+    ///
+    /// Lines are delimited by hard line breaks.
+    LineStart,
+    /// To the end of the current line.
+    ///
+    /// Lines are delimited by hard line breaks.
+    LineEnd,
+    /// To the start of the text.
+    DocumentStart,
+    /// To the end of the text.
+    DocumentEnd,
+}
+
+impl CaretMovement {
+    /// Whether this movement goes byte-wise backwards in the text.
+    pub fn is_backward(self) -> bool {
+        matches!(
+            self,
+            CaretMovement::PrevGrapheme
+                | CaretMovement::PrevWord
+                | CaretMovement::LineStart
+                | CaretMovement::DocumentStart
+        )
+    }
+
+    /// The offset reached by moving from `offset` in `text`.
+    pub fn target(self, text: &impl Selectable, offset: usize) -> usize {
+        match self {
+            CaretMovement::PrevGrapheme => text.prev_grapheme_offset(offset).unwrap_or(0),
+            CaretMovement::NextGrapheme => text.next_grapheme_offset(offset).unwrap_or(offset),
+            CaretMovement::PrevWord => text.prev_word_offset(offset).unwrap_or(0),
+            CaretMovement::NextWord => text.next_word_offset(offset).unwrap_or(text.len()),
+            CaretMovement::LineStart => text.preceding_line_break(offset),
+            CaretMovement::LineEnd => text.next_line_break(offset),
+            CaretMovement::DocumentStart => 0,
+            CaretMovement::DocumentEnd => text.len(),
+        }
+    }
+}
+
+/// An operation on text performed in response to a keyboard shortcut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EditAction {
+    /// Move the caret, collapsing the selection.
+    Move(CaretMovement),
+    /// Move the active end of the selection, keeping its anchor in place.
+    Select(CaretMovement),
+    /// Delete the selection or, if the selection is a caret, the text between
+    /// the caret and the end of the movement.
+    ///
+    /// This is ignored by text which can't be edited.
+    Delete(CaretMovement),
+    SelectAll,
+    Copy,
+    /// Select the next match of the text's find query, if it has one.
+    FindNext,
+    /// Select the previous match of the text's find query, if it has one.
+    FindPrevious,
+    /// Drop the extra carets added with <kbd>Ctrl</kbd>+click or a column
+    /// selection, keeping only the primary one.
+    CollapseSelections,
+}
+
+/// A table mapping keyboard shortcuts to [`EditAction`]s.
+///
+/// Each widget tree has one keymap, which widgets access with
+/// [`EventCtx::keymap`](crate::EventCtx::keymap) and apps replace with
+/// [`RenderRoot::set_keymap`](crate::render_root::RenderRoot::set_keymap), for
+/// example to add Emacs-style bindings. Individual text widgets can also be given
+/// their own keymap.
+///
+/// The [`Default`] table follows the conventions of the current platform, e.g.
+/// moving by word with <kbd>Ctrl</kbd>+<kbd>←</kbd>, or <kbd>⌥</kbd>+<kbd>←</kbd> on macOS.
+/// It can be changed with [`bind`](Self::bind) and [`unbind`](Self::unbind).
+///
+/// Keys are matched as if no modifiers were pressed (so <kbd>Shift</kbd>+<kbd>a</kbd>
+/// is matched as `a` with the shift modifier), and modifiers have to match exactly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Key, ModifiersState, EditAction)>,
+}
+
+impl Keymap {
+    /// A table without any bindings.
+    pub fn empty() -> Self {
+        Keymap {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Bind `key` pressed with exactly `mods` to `action`, replacing any previous binding.
+    pub fn bind(&mut self, key: Key, mods: ModifiersState, action: EditAction) {
+        self.unbind(&key, mods);
+        self.bindings.push((key, mods, action));
+    }
+
+    /// Builder-style method for [`bind`](Self::bind).
+    pub fn with_binding(mut self, key: Key, mods: ModifiersState, action: EditAction) -> Self {
+        self.bind(key, mods, action);
+        self
+    }
+
+    /// Remove the binding of `key` pressed with exactly `mods`, if any.
+    pub fn unbind(&mut self, key: &Key, mods: ModifiersState) {
+        self.bindings
+            .retain(|(bound_key, bound_mods, _)| !(bound_key == key && *bound_mods == mods));
+    }
+
+    /// The action bound to `key` pressed with exactly `mods`, if any.
+    pub fn action(&self, key: &Key, mods: ModifiersState) -> Option<EditAction> {
+        self.bindings
+            .iter()
+            .find(|(bound_key, bound_mods, _)| bound_key == key && *bound_mods == mods)
+            .map(|(_, _, action)| *action)
+    }
+
+    /// Bind `key` to moving the caret, and `key` with <kbd>Shift</kbd> to extending the selection.
+    fn bind_movement(&mut self, key: Key, mods: ModifiersState, movement: CaretMovement) {
+        self.bind(key.clone(), mods, EditAction::Move(movement));
+        self.bind(
+            key,
+            mods | ModifiersState::SHIFT,
+            EditAction::Select(movement),
+        );
+    }
+
+    fn bind_deletion(&mut self, key: Key, mods: ModifiersState, movement: CaretMovement) {
+        self.bind(key, mods, EditAction::Delete(movement));
+    }
+
+    /// The default bindings on macOS.
+    pub fn macos() -> Self {
+        use CaretMovement::*;
+        let none = ModifiersState::empty();
+        let alt = ModifiersState::ALT;
+        let cmd = ModifiersState::SUPER;
+
+        let mut bindings = Keymap::empty();
+        bindings.bind_movement(named(NamedKey::ArrowLeft), none, PrevGrapheme);
+        bindings.bind_movement(named(NamedKey::ArrowRight), none, NextGrapheme);
+        bindings.bind_movement(named(NamedKey::ArrowLeft), alt, PrevWord);
+        bindings.bind_movement(named(NamedKey::ArrowRight), alt, NextWord);
+        bindings.bind_movement(named(NamedKey::ArrowLeft), cmd, LineStart);
+        bindings.bind_movement(named(NamedKey::ArrowRight), cmd, LineEnd);
+        bindings.bind_movement(named(NamedKey::Home), none, LineStart);
+        bindings.bind_movement(named(NamedKey::End), none, LineEnd);
+        bindings.bind_movement(named(NamedKey::ArrowUp), cmd, DocumentStart);
+        bindings.bind_movement(named(NamedKey::ArrowDown), cmd, DocumentEnd);
+
+        bindings.bind_deletion(named(NamedKey::Backspace), none, PrevGrapheme);
+        bindings.bind_deletion(
+            named(NamedKey::Backspace),
+            ModifiersState::SHIFT,
+            PrevGrapheme,
+        );
+        bindings.bind_deletion(named(NamedKey::Delete), none, NextGrapheme);
+        bindings.bind_deletion(named(NamedKey::Backspace), alt, PrevWord);
+        bindings.bind_deletion(named(NamedKey::Delete), alt, NextWord);
+        bindings.bind_deletion(named(NamedKey::Backspace), cmd, LineStart);
+
+        bindings.bind(character("a"), cmd, EditAction::SelectAll);
+        bindings.bind(character("c"), cmd, EditAction::Copy);
+        bindings.bind(character("g"), cmd, EditAction::FindNext);
+        bindings.bind(
+            character("g"),
+            cmd | ModifiersState::SHIFT,
+            EditAction::FindPrevious,
+        );
+        bindings.bind(
+            named(NamedKey::Escape),
+            none,
+            EditAction::CollapseSelections,
+        );
+        bindings
+    }
+
+    /// The default bindings on platforms other than macOS.
+    pub fn standard() -> Self {
+        use CaretMovement::*;
+        let none = ModifiersState::empty();
+        let ctrl = ModifiersState::CONTROL;
+
+        let mut bindings = Keymap::empty();
+        bindings.bind_movement(named(NamedKey::ArrowLeft), none, PrevGrapheme);
+        bindings.bind_movement(named(NamedKey::ArrowRight), none, NextGrapheme);
+        bindings.bind_movement(named(NamedKey::ArrowLeft), ctrl, PrevWord);
+        bindings.bind_movement(named(NamedKey::ArrowRight), ctrl, NextWord);
+        bindings.bind_movement(named(NamedKey::Home), none, LineStart);
+        bindings.bind_movement(named(NamedKey::End), none, LineEnd);
+        bindings.bind_movement(named(NamedKey::Home), ctrl, DocumentStart);
+        bindings.bind_movement(named(NamedKey::End), ctrl, DocumentEnd);
+
+        bindings.bind_deletion(named(NamedKey::Backspace), none, PrevGrapheme);
+        bindings.bind_deletion(
+            named(NamedKey::Backspace),
+            ModifiersState::SHIFT,
+            PrevGrapheme,
+        );
+        bindings.bind_deletion(named(NamedKey::Delete), none, NextGrapheme);
+        bindings.bind_deletion(named(NamedKey::Backspace), ctrl, PrevWord);
+        bindings.bind_deletion(named(NamedKey::Delete), ctrl, NextWord);
+
+        bindings.bind(character("a"), ctrl, EditAction::SelectAll);
+        bindings.bind(character("c"), ctrl, EditAction::Copy);
+        bindings.bind(named(NamedKey::F3), none, EditAction::FindNext);
+        bindings.bind(
+            named(NamedKey::F3),
+            ModifiersState::SHIFT,
+            EditAction::FindPrevious,
+        );
+        bindings.bind(
+            named(NamedKey::Escape),
+            none,
+            EditAction::CollapseSelections,
+        );
+        bindings
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        PlatformConventions::default().keymap()
+    }
+}
+
+fn named(key: NamedKey) -> Key {
+    Key::Named(key)
+}
+
+fn character(c: &str) -> Key {
+    Key::Character(c.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_bindings() {
+        let bindings = Keymap::standard();
+        let ctrl = ModifiersState::CONTROL;
+        let ctrl_shift = ModifiersState::CONTROL | ModifiersState::SHIFT;
+
+        assert_eq!(
+            bindings.action(&named(NamedKey::ArrowLeft), ctrl),
+            Some(EditAction::Move(CaretMovement::PrevWord))
+        );
+        assert_eq!(
+            bindings.action(&named(NamedKey::ArrowRight), ctrl_shift),
+            Some(EditAction::Select(CaretMovement::NextWord))
+        );
+        assert_eq!(
+            bindings.action(&named(NamedKey::Backspace), ctrl),
+            Some(EditAction::Delete(CaretMovement::PrevWord))
+        );
+        assert_eq!(
+            bindings.action(&character("a"), ctrl),
+            Some(EditAction::SelectAll)
+        );
+        assert_eq!(
+            bindings.action(&character("a"), ModifiersState::empty()),
+            None
+        );
+        assert_eq!(
+            bindings.action(&named(NamedKey::F3), ModifiersState::SHIFT),
+            Some(EditAction::FindPrevious)
+        );
+        assert_eq!(
+            bindings.action(&named(NamedKey::Escape), ModifiersState::empty()),
+            Some(EditAction::CollapseSelections)
+        );
+    }
+
+    #[test]
+    fn override_bindings() {
+        let mut bindings = Keymap::standard();
+        let ctrl = ModifiersState::CONTROL;
+
+        bindings.bind(
+            character("a"),
+            ctrl,
+            EditAction::Move(CaretMovement::LineStart),
+        );
+        bindings.unbind(&named(NamedKey::Home), ModifiersState::empty());
+
+        assert_eq!(
+            bindings.action(&character("a"), ctrl),
+            Some(EditAction::Move(CaretMovement::LineStart))
+        );
+        assert_eq!(
+            bindings.action(&named(NamedKey::Home), ModifiersState::empty()),
+            None
+        );
+    }
+
+    #[test]
+    fn movement_targets() {
+        let text = String::from("hello brave\nnew world");
+        let offset = text.find("brave").unwrap() + 2;
+
+        assert_eq!(
+            CaretMovement::PrevGrapheme.target(&text, offset),
+            offset - 1
+        );
+        assert_eq!(CaretMovement::PrevWord.target(&text, offset), 6);
+        assert_eq!(CaretMovement::NextWord.target(&text, offset), 11);
+        assert_eq!(CaretMovement::LineStart.target(&text, offset), 0);
+        assert_eq!(CaretMovement::LineEnd.target(&text, offset), 11);
+        assert_eq!(CaretMovement::LineStart.target(&text, 14), 12);
+        assert_eq!(CaretMovement::DocumentEnd.target(&text, offset), text.len());
+        assert_eq!(
+            CaretMovement::NextGrapheme.target(&text, text.len()),
+            text.len()
+        );
+    }
+}
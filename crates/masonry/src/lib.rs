@@ -95,16 +95,45 @@ mod util;
 mod action;
 mod bloom;
 mod box_constraints;
+pub mod clock;
+pub mod color;
 mod contexts;
+pub mod conventions;
+pub mod direction;
+#[cfg(feature = "effects")]
+pub mod effects;
 mod event;
+pub mod feedback;
+mod focus_group;
+pub mod frame_stats;
+pub mod gpu;
+pub mod headless;
+mod icon;
+pub mod idle;
+pub mod interaction;
+mod keymap;
+pub mod paint_debug;
 pub mod paint_scene_helpers;
+pub mod persistence;
+pub mod pixel_snap;
+pub mod popup;
 pub mod promise;
+mod raster_cache;
+mod render_context;
+pub mod render_diagnostics;
 pub mod render_root;
+pub mod spellcheck;
+pub mod task_pool;
+pub mod taskbar;
 pub mod testing;
 // mod text;
 pub mod text_helpers;
 pub mod theme;
+pub mod wake;
 pub mod widget;
+pub mod widget_timings;
+mod widget_tree;
+pub mod window_kind;
 
 // TODO
 pub mod app_driver;
@@ -117,12 +146,16 @@ pub use action::Action;
 pub use box_constraints::BoxConstraints;
 pub use contexts::{AccessCtx, EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, WidgetCtx};
 pub use event::{
-    AccessEvent, InternalLifeCycle, LifeCycle, PointerEvent, StatusChange, TextEvent, WindowTheme,
+    AccessEvent, InternalLifeCycle, LifeCycle, PointerEvent, StatusChange, TextEvent, WindowEvent,
+    WindowTheme,
 };
+pub use focus_group::FocusGroup;
+pub use icon::{IconName, IconSet, IconShape};
+pub use keymap::{CaretMovement, EditAction, Keymap};
 pub use kurbo::{Affine, Insets, Point, Rect, Size, Vec2};
 pub use parley::layout::Alignment as TextAlignment;
 pub use util::{AsAny, Handled};
 pub use vello::peniko::{Color, Gradient};
-pub use widget::{BackgroundBrush, Widget, WidgetId, WidgetPod, WidgetState};
+pub use widget::{BackgroundBrush, Interactivity, Widget, WidgetId, WidgetPod, WidgetState};
 
-pub use text_helpers::ArcStr;
+pub use text_helpers::{ArcStr, InputHint};
@@ -0,0 +1,123 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Seeing the order widgets are painted in, and how often each part of the window
+//! is painted over.
+//!
+//! Widgets are painted depth-first. A widget's [`paint`](crate::Widget::paint)
+//! paints each of its children where it chooses, so what it draws before painting
+//! a child is below the child, and what it draws after is above. Siblings are
+//! painted in the order their parent paints them, which for most containers is the
+//! order of their children, so a later sibling covers an earlier one where they
+//! overlap. Overlays, such as [popups](crate::popup), are painted after the whole
+//! window, with those of nested widgets above those of their ancestors. A widget
+//! which should appear above its siblings, but is painted below one of them, is
+//! usually painted as part of its parent rather than as an overlay.
+//!
+//! To see this order, set a [`PaintDebug`] mode with
+//! [`AppDriver::paint_debug`], the `MASONRY_PAINT_DEBUG` environment variable, or
+//! [`RenderRoot::set_paint_debug`]. While a mode is on, every widget is repainted
+//! each frame, bypassing the cached paint of unchanged widgets, and the widgets
+//! are listed in paint order by [`RenderRoot::paint_order`].
+//!
+//! [`AppDriver::paint_debug`]: crate::app_driver::AppDriver::paint_debug
+//! [`RenderRoot::set_paint_debug`]: crate::render_root::RenderRoot::set_paint_debug
+//! [`RenderRoot::paint_order`]: crate::render_root::RenderRoot::paint_order
+
+use kurbo::Rect;
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::{Color, WidgetId};
+
+/// A way of tinting the window to show how it was painted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PaintDebug {
+    /// The window is painted as usual.
+    #[default]
+    Off,
+    /// Each widget is outlined and tinted from blue, for the first widget painted,
+    /// to red, for the last, so widgets which are painted over others are redder.
+    PaintOrder,
+    /// Each widget adds a faint layer of red, so parts of the window which many
+    /// widgets paint over are more red.
+    Overdraw,
+}
+
+impl PaintDebug {
+    /// The mode named by the `MASONRY_PAINT_DEBUG` environment variable, which can
+    /// be `order` or `overdraw`, or [`PaintDebug::Off`] if it isn't set.
+    ///
+    /// Other values are ignored, with a warning.
+    pub fn from_env() -> Self {
+        match std::env::var("MASONRY_PAINT_DEBUG") {
+            Ok(value) => Self::parse(&value).unwrap_or_else(|| {
+                tracing::warn!("Ignoring invalid value '{value}' for MASONRY_PAINT_DEBUG");
+                PaintDebug::Off
+            }),
+            Err(_) => PaintDebug::Off,
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "" | "off" => Some(PaintDebug::Off),
+            "order" => Some(PaintDebug::PaintOrder),
+            "overdraw" => Some(PaintDebug::Overdraw),
+            _ => None,
+        }
+    }
+}
+
+/// Paint the tints of `mode` above everything else, for the widgets painted in
+/// `order`, with their rects in window coordinates.
+pub(crate) fn paint_tints(scene: &mut Scene, mode: PaintDebug, order: &[(WidgetId, Rect)]) {
+    match mode {
+        PaintDebug::Off => (),
+        PaintDebug::PaintOrder => {
+            let last = order.len().saturating_sub(1).max(1) as f64;
+            for (ix, (_, rect)) in order.iter().enumerate() {
+                let t = ix as f64 / last;
+                let lerp = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t) as u8;
+                let color = Color::rgb8(lerp(0x30, 0xff), lerp(0x60, 0x40), lerp(0xff, 0x30));
+                fill_color(scene, rect, color.with_alpha_factor(0.15_f32));
+                stroke(scene, &rect.inset(-0.5), color, 1.0);
+            }
+        }
+        PaintDebug::Overdraw => {
+            for (_, rect) in order {
+                fill_color(scene, rect, Color::rgba8(0xff, 0x30, 0x30, 0x28));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::{Flex, Label};
+
+    #[test]
+    fn paint_order_is_depth_first() {
+        let [first_id, second_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(Label::new("First").with_id(first_id))
+            .with_child(Label::new("Second").with_id(second_id));
+        let mut harness = TestHarness::create(widget);
+        harness.render();
+        assert!(harness.paint_order().is_empty());
+
+        harness.set_paint_debug(PaintDebug::PaintOrder);
+        harness.render();
+        let order = harness.paint_order();
+        let position = |id| order.iter().position(|painted| *painted == id).unwrap();
+        let first_box = harness.get_parent_id(first_id).unwrap();
+        assert_eq!(order[0], harness.root_widget().id());
+        assert!(position(first_box) < position(first_id));
+        assert!(position(first_id) < position(second_id));
+
+        assert_eq!(PaintDebug::parse(" overdraw"), Some(PaintDebug::Overdraw));
+        assert_eq!(PaintDebug::parse("sometimes"), None);
+    }
+}
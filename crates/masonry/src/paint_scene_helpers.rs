@@ -9,9 +9,11 @@ use vello::{
     Scene,
 };
 
+use crate::color::linear_light_stops;
+
 // TODO - Remove this file
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct UnitPoint {
     u: f64,
     v: f64,
@@ -78,7 +80,9 @@ pub fn fill_lin_gradient(
     end: UnitPoint,
 ) {
     let rect = path.bounding_box();
-    let brush = Gradient::new_linear(start.resolve(rect), end.resolve(rect)).with_stops(stops);
+    let stops = linear_light_stops(stops);
+    let brush =
+        Gradient::new_linear(start.resolve(rect), end.resolve(rect)).with_stops(stops.as_slice());
     scene.fill(Fill::NonZero, Affine::IDENTITY, &brush, None, path);
 }
 
@@ -0,0 +1,265 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Saving small pieces of app state between launches, such as where the window was.
+//!
+//! A [`Store`] keeps each piece as a JSON file in a directory of the app's own,
+//! in the platform's usual place for settings.
+//!
+//! Apps which give a key from [`AppDriver::window_geometry_key`] get their window's
+//! [`WindowGeometry`] saved when it is closed, and restored when it is next opened,
+//! as long as it would still be on one of the monitors.
+//!
+//! With the `serde` feature, the app-facing settings, such as the
+//! [`InteractionSettings`](crate::interaction::InteractionSettings) and the
+//! [`WindowKind`](crate::window_kind::WindowKind), and the geometry types, such
+//! as a [`Portal`](crate::widget::Portal)'s scroll position, can be saved too.
+//!
+//! [`AppDriver::window_geometry_key`]: crate::app_driver::AppDriver::window_geometry_key
+
+use std::io;
+use std::path::PathBuf;
+
+use kurbo::Rect;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle;
+use winit::window::Window;
+
+/// How much of a restored window, in physical pixels along each axis, needs to be
+/// on a monitor for the user to be able to grab it.
+const MIN_VISIBLE: f64 = 64.0;
+
+/// A directory of saved app state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    /// The store of the app called `app_key`, in the platform's settings directory.
+    ///
+    /// Returns `None` if the platform has no settings directory, such as on the web.
+    pub fn new(app_key: &str) -> Option<Self> {
+        Some(Store::in_dir(config_dir()?.join(app_key)))
+    }
+
+    /// A store in `dir`, which is created when something is first saved.
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        Store { dir: dir.into() }
+    }
+
+    /// The value saved as `name`, if there is one and it can be read.
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let path = self.path(name);
+        let file = std::fs::read(&path).ok()?;
+        match serde_json::from_slice(&file) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                warn!("ignoring unreadable saved state {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Save `value` as `name`, replacing what was saved before.
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_vec_pretty(value)?;
+        std::fs::write(self.path(name), json)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name).with_extension("json")
+    }
+}
+
+/// The platform's directory for app settings: `%APPDATA%` on Windows,
+/// `~/Library/Application Support` on macOS, and `$XDG_CONFIG_HOME` or `~/.config`
+/// elsewhere.
+pub fn config_dir() -> Option<PathBuf> {
+    let from_env = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    if cfg!(target_os = "windows") {
+        from_env("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        from_env("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else if cfg!(target_arch = "wasm32") {
+        None
+    } else {
+        from_env("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| from_env("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+}
+
+/// Where a window was and how big it was, in physical pixels.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// The position of the window's outer top left corner, if the platform tells
+    /// windows where they are.
+    pub position: Option<(i32, i32)>,
+    /// The size of the window's content area.
+    pub size: (u32, u32),
+    pub maximized: bool,
+    /// The name of the monitor the window was on, if known.
+    pub monitor: Option<String>,
+}
+
+/// A monitor a window can be restored on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorArea {
+    pub name: Option<String>,
+    /// The monitor's area, in physical pixels.
+    pub rect: Rect,
+}
+
+impl MonitorArea {
+    pub fn from_handle(monitor: &MonitorHandle) -> Self {
+        let PhysicalPosition { x, y } = monitor.position();
+        let PhysicalSize { width, height } = monitor.size();
+        MonitorArea {
+            name: monitor.name(),
+            rect: Rect::new(
+                x as f64,
+                y as f64,
+                x as f64 + width as f64,
+                y as f64 + height as f64,
+            ),
+        }
+    }
+}
+
+impl WindowGeometry {
+    /// The name geometries are saved as in a [`Store`].
+    pub const STORE_NAME: &'static str = "window";
+
+    /// The current geometry of `window`.
+    ///
+    /// A maximized window keeps the size it had before being maximized where the
+    /// platform remembers it, so it is restored to that size when un-maximized.
+    pub fn of_window(window: &Window) -> Self {
+        let size = window.inner_size();
+        WindowGeometry {
+            position: window
+                .outer_position()
+                .ok()
+                .map(|position| (position.x, position.y)),
+            size: (size.width, size.height),
+            maximized: window.is_maximized(),
+            monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+        }
+    }
+
+    /// This geometry, changed to fit the current `monitors`.
+    ///
+    /// The window shrinks to fit on the monitor it was on, or the largest monitor
+    /// if that one is gone. If not enough of it would be on any monitor to grab it,
+    /// such as after unplugging the monitor it was on, it loses its position so that
+    /// the platform places it. Returns `None` if there are no monitors to check
+    /// against.
+    pub fn fit_to(mut self, monitors: &[MonitorArea]) -> Option<Self> {
+        let largest = monitors
+            .iter()
+            .max_by(|a, b| a.rect.area().total_cmp(&b.rect.area()))?;
+        let home = monitors
+            .iter()
+            .find(|monitor| monitor.name.is_some() && monitor.name == self.monitor)
+            .unwrap_or(largest);
+        self.size = (
+            self.size.0.min(home.rect.width() as u32),
+            self.size.1.min(home.rect.height() as u32),
+        );
+
+        if let Some((x, y)) = self.position {
+            let rect = Rect::new(
+                x as f64,
+                y as f64,
+                x as f64 + self.size.0 as f64,
+                y as f64 + self.size.1 as f64,
+            );
+            let grabbable = monitors.iter().any(|monitor| {
+                let visible = rect.intersect(monitor.rect);
+                visible.width() >= MIN_VISIBLE && visible.height() >= MIN_VISIBLE
+            });
+            if !grabbable {
+                self.position = None;
+                self.monitor = None;
+            }
+        }
+        Some(self)
+    }
+
+    /// Move and resize `window` to this geometry, preferably before it is shown.
+    pub fn apply_to_window(&self, window: &Window) {
+        if let Some((x, y)) = self.position {
+            window.set_outer_position(PhysicalPosition::new(x, y));
+        }
+        let _ = window.request_inner_size(PhysicalSize::new(self.size.0, self.size.1));
+        window.set_maximized(self.maximized);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_is_kept_on_screen() {
+        let monitors = [
+            MonitorArea {
+                name: Some("left".into()),
+                rect: Rect::new(0.0, 0.0, 1920.0, 1080.0),
+            },
+            MonitorArea {
+                name: Some("right".into()),
+                rect: Rect::new(1920.0, 0.0, 3200.0, 800.0),
+            },
+        ];
+        let saved = WindowGeometry {
+            position: Some((2000, 100)),
+            size: (1600, 900),
+            maximized: false,
+            monitor: Some("right".into()),
+        };
+        // The window shrinks to fit its monitor
+        let fitted = saved.clone().fit_to(&monitors).unwrap();
+        assert_eq!(fitted.size, (1280, 800));
+        assert_eq!(fitted.position, Some((2000, 100)));
+
+        // Once that monitor is unplugged, the platform places the window
+        let fitted = saved.fit_to(&monitors[..1]).unwrap();
+        assert_eq!(fitted.size, (1600, 900));
+        assert_eq!((fitted.position, &fitted.monitor), (None, &None));
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::in_dir(dir.path().join("app"));
+        assert_eq!(
+            store.load::<WindowGeometry>(WindowGeometry::STORE_NAME),
+            None
+        );
+        store.save(WindowGeometry::STORE_NAME, &fitted).unwrap();
+        assert_eq!(store.load(WindowGeometry::STORE_NAME), Some(fitted));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn settings_round_trip() {
+        use crate::interaction::InteractionSettings;
+        use crate::widget::Axis;
+        use crate::window_kind::WindowKind;
+        use kurbo::Point;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::in_dir(dir.path());
+        let saved = (
+            InteractionSettings::default(),
+            WindowKind::Tool,
+            Axis::Vertical,
+            Point::new(0.0, 480.0),
+        );
+        store.save("layout", &saved).unwrap();
+        assert_eq!(store.load("layout"), Some(saved));
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Placing shapes on physical pixel boundaries, so that they render crisply at any
+//! scale factor.
+//!
+//! Logical pixels only line up with physical pixels at whole scale factors. At 125%
+//! or 150%, a one pixel line at a whole logical coordinate is smeared over two
+//! physical pixels, and looks blurry. These helpers move coordinates to the nearest
+//! physical pixel boundary, where `scale` is the number of physical pixels per
+//! logical pixel.
+//!
+//! The coordinates must be in window space, since that is where the physical
+//! pixels are. Widgets should use [`PaintCtx::snap_rect`], [`PaintCtx::snap_stroke`]
+//! and [`PaintCtx::snap_line`], which convert from their own coordinates.
+//!
+//! [`PaintCtx::snap_rect`]: crate::PaintCtx::snap_rect
+//! [`PaintCtx::snap_stroke`]: crate::PaintCtx::snap_stroke
+//! [`PaintCtx::snap_line`]: crate::PaintCtx::snap_line
+
+use kurbo::{Line, Point, Rect};
+
+/// A value which can be moved to the nearest physical pixel boundaries.
+pub trait SnapToPixel {
+    /// Move to the nearest physical pixel boundaries, at `scale` physical pixels per
+    /// logical pixel.
+    fn snap_to_pixel(self, scale: f64) -> Self;
+}
+
+impl SnapToPixel for f64 {
+    fn snap_to_pixel(self, scale: f64) -> Self {
+        if scale <= 0.0 {
+            return self;
+        }
+        (self * scale).round() / scale
+    }
+}
+
+impl SnapToPixel for Point {
+    fn snap_to_pixel(self, scale: f64) -> Self {
+        Point::new(self.x.snap_to_pixel(scale), self.y.snap_to_pixel(scale))
+    }
+}
+
+impl SnapToPixel for Rect {
+    /// Snap each edge, so that the rect covers whole physical pixels.
+    fn snap_to_pixel(self, scale: f64) -> Self {
+        Rect::new(
+            self.x0.snap_to_pixel(scale),
+            self.y0.snap_to_pixel(scale),
+            self.x1.snap_to_pixel(scale),
+            self.y1.snap_to_pixel(scale),
+        )
+    }
+}
+
+/// Move `value` to the nearest physical pixel boundary, at `scale` physical pixels
+/// per logical pixel.
+pub fn snap_to_pixel<T: SnapToPixel>(value: T, scale: f64) -> T {
+    value.snap_to_pixel(scale)
+}
+
+/// Round a stroke width to a whole number of physical pixels, and at least one so
+/// that hairlines don't disappear.
+pub fn snap_stroke_width(width: f64, scale: f64) -> f64 {
+    if scale <= 0.0 {
+        return width;
+    }
+    (width * scale).round().max(1.0) / scale
+}
+
+/// Move the center line of a stroke of `width` at `center`, so that both edges of
+/// the stroke land on physical pixel boundaries.
+///
+/// `width` should already be snapped with [`snap_stroke_width`].
+fn snap_stroke_center(center: f64, width: f64, scale: f64) -> f64 {
+    if scale <= 0.0 {
+        return center;
+    }
+    let half_width = width * scale / 2.0;
+    ((center * scale - half_width).round() + half_width) / scale
+}
+
+/// Snap the outline of a stroke of `width` around `rect`, returning the outline
+/// and the snapped width.
+///
+/// The edges of `rect` are the center lines of the stroke, as with
+/// [`Scene::stroke`](vello::Scene::stroke).
+pub fn snap_stroke(rect: Rect, width: f64, scale: f64) -> (Rect, f64) {
+    let width = snap_stroke_width(width, scale);
+    let snap = |center| snap_stroke_center(center, width, scale);
+    let rect = Rect::new(snap(rect.x0), snap(rect.y0), snap(rect.x1), snap(rect.y1));
+    (rect, width)
+}
+
+/// Snap a line stroked with `width`, returning the line and the snapped width.
+///
+/// This is meant for horizontal and vertical lines, such as dividers.
+pub fn snap_line(line: Line, width: f64, scale: f64) -> (Line, f64) {
+    let width = snap_stroke_width(width, scale);
+    let snap = |p: Point| {
+        Point::new(
+            snap_stroke_center(p.x, width, scale),
+            snap_stroke_center(p.y, width, scale),
+        )
+    };
+    (Line::new(snap(line.p0), snap(line.p1)), width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_land_on_physical_pixels() {
+        let on_pixels = |value: f64, scale: f64| {
+            let physical = value * scale;
+            (physical - physical.round()).abs() < 1e-9
+        };
+
+        for scale in [1.0, 1.25, 1.5, 2.0] {
+            let rect = snap_to_pixel(Rect::new(10.3, 20.0, 50.9, 41.0), scale);
+            for edge in [rect.x0, rect.y0, rect.x1, rect.y1] {
+                assert!(on_pixels(edge, scale), "{edge} at {scale}");
+            }
+
+            // Both edges of a stroke are on pixel boundaries, not just its center
+            let (outline, width) = snap_stroke(Rect::new(0.5, 0.5, 20.5, 10.5), 1.0, scale);
+            assert!(on_pixels(width, scale));
+            for edge in [outline.x0, outline.y0, outline.x1, outline.y1] {
+                assert!(on_pixels(edge - width / 2.0, scale), "{edge} at {scale}");
+                assert!(on_pixels(edge + width / 2.0, scale), "{edge} at {scale}");
+            }
+        }
+
+        // Integer-aligned shapes are unchanged at 100%
+        let rect = Rect::new(0.5, 0.5, 20.5, 10.5);
+        assert_eq!(snap_stroke(rect, 1.0, 1.0), (rect, 1.0));
+        // A hairline keeps one physical pixel at any scale
+        assert_eq!(snap_stroke_width(0.5, 1.25), 0.8);
+        let line = Line::new((0.0, 10.0), (100.0, 10.0));
+        let (line, width) = snap_line(line, 1.0, 1.5);
+        assert_eq!((line.p0.y, width), (10.0, 2.0 / 1.5));
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Placement of popups, such as popovers, menus, dropdowns and tooltips, next to
+//! the widget they belong to.
+//!
+//! Widgets usually place popups with [`LayoutCtx::place_popup`], which keeps
+//! them inside the window and away from each other, and paint them with
+//! [`PaintCtx::paint_popup`].
+//!
+//! Popups are drawn in the window by default. With [`PopupMode::NativeWindow`],
+//! from [`AppDriver::popup_mode`], each one is shown in a borderless window of its
+//! own, so that it can extend past the edges of a small window; it is then kept on
+//! the screen rather than in the window. The widgets are the same either way, and
+//! stay in the main window's widget tree.
+//!
+//! [`LayoutCtx::place_popup`]: crate::LayoutCtx::place_popup
+//! [`PaintCtx::paint_popup`]: crate::PaintCtx::paint_popup
+//! [`AppDriver::popup_mode`]: crate::app_driver::AppDriver::popup_mode
+
+use kurbo::{Point, Rect, Size};
+
+/// Where popups are shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PopupMode {
+    /// Popups are drawn above the rest of the window, and kept inside it.
+    #[default]
+    InWindow,
+    /// Popups are shown in windows of their own, and kept on the screen.
+    NativeWindow,
+}
+
+/// The side of its anchor which a popup is shown on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    Above,
+    #[default]
+    Below,
+    Left,
+    Right,
+}
+
+impl Placement {
+    /// The other side of the anchor.
+    pub fn opposite(self) -> Self {
+        match self {
+            Placement::Above => Placement::Below,
+            Placement::Below => Placement::Above,
+            Placement::Left => Placement::Right,
+            Placement::Right => Placement::Left,
+        }
+    }
+}
+
+/// What a popup needs to be placed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PopupRequest {
+    /// The rect the popup is attached to.
+    pub anchor: Rect,
+    /// The size of the popup.
+    pub size: Size,
+    /// The side of the anchor the popup is shown on, if there is room.
+    pub placement: Placement,
+    /// The space between the anchor and the popup.
+    pub gap: f64,
+}
+
+/// Where a popup was placed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PopupPlacement {
+    /// The popup's rect, in the coordinate space of the request.
+    pub rect: Rect,
+    /// The side of the anchor the popup ended up on, which may be the opposite of
+    /// the one requested.
+    pub side: Placement,
+}
+
+impl PopupRequest {
+    /// Create a request for a popup of `size` below `anchor`, without a gap.
+    pub fn new(anchor: Rect, size: Size) -> Self {
+        PopupRequest {
+            anchor,
+            size,
+            placement: Placement::default(),
+            gap: 0.0,
+        }
+    }
+
+    /// Builder-style method to set the side of the anchor the popup prefers.
+    pub fn with_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Builder-style method to set the space between the anchor and the popup.
+    pub fn with_gap(mut self, gap: f64) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// The space between the anchor and the edge of `bounds` on `side`.
+    fn room(&self, side: Placement, bounds: Rect) -> f64 {
+        match side {
+            Placement::Above => self.anchor.y0 - bounds.y0,
+            Placement::Below => bounds.y1 - self.anchor.y1,
+            Placement::Left => self.anchor.x0 - bounds.x0,
+            Placement::Right => bounds.x1 - self.anchor.x1,
+        }
+    }
+
+    fn fits(&self, side: Placement, bounds: Rect) -> bool {
+        let needed = match side {
+            Placement::Above | Placement::Below => self.size.height + self.gap,
+            Placement::Left | Placement::Right => self.size.width + self.gap,
+        };
+        self.room(side, bounds) >= needed
+    }
+
+    /// The popup's rect on `side`, moved to stay inside `bounds`.
+    fn rect_on(&self, side: Placement, bounds: Rect) -> Rect {
+        let Size { width, height } = self.size;
+        let center = self.anchor.center();
+        let origin = match side {
+            Placement::Above => {
+                Point::new(center.x - width / 2.0, self.anchor.y0 - self.gap - height)
+            }
+            Placement::Below => Point::new(center.x - width / 2.0, self.anchor.y1 + self.gap),
+            Placement::Left => {
+                Point::new(self.anchor.x0 - self.gap - width, center.y - height / 2.0)
+            }
+            Placement::Right => Point::new(self.anchor.x1 + self.gap, center.y - height / 2.0),
+        };
+        // If the popup is bigger than the bounds, its top left corner stays in them
+        let origin = Point::new(
+            origin.x.min(bounds.x1 - width).max(bounds.x0),
+            origin.y.min(bounds.y1 - height).max(bounds.y0),
+        );
+        Rect::from_origin_size(origin, self.size)
+    }
+}
+
+/// Place a popup inside `bounds`, avoiding the `occupied` rects of other popups.
+///
+/// The popup goes on the requested side of its anchor if it fits there, or on the
+/// opposite side if it only fits there or it would cover another popup on the
+/// requested side. If it fits on neither, it goes on the side with more room. The
+/// popup is centered along its anchor, moved to stay inside `bounds`, and aligned to
+/// device pixels for the given `scale_factor`.
+pub fn place_popup(
+    request: &PopupRequest,
+    bounds: Rect,
+    occupied: &[Rect],
+    scale_factor: f64,
+) -> PopupPlacement {
+    let overlaps = |rect: Rect| {
+        occupied
+            .iter()
+            .any(|other| rect.intersect(*other).area() > 0.0)
+    };
+    let candidates = [request.placement, request.placement.opposite()]
+        .map(|side| (side, request.rect_on(side, bounds)));
+    let fitting = || {
+        candidates
+            .iter()
+            .filter(|(side, _)| request.fits(*side, bounds))
+    };
+    let (side, rect) = fitting()
+        .find(|(_, rect)| !overlaps(*rect))
+        .or_else(|| fitting().next())
+        .copied()
+        .unwrap_or_else(|| {
+            let [preferred, opposite] = candidates;
+            if request.room(preferred.0, bounds) >= request.room(opposite.0, bounds) {
+                preferred
+            } else {
+                opposite
+            }
+        });
+
+    let snap = |value: f64| (value * scale_factor).round() / scale_factor;
+    let origin = Point::new(snap(rect.x0), snap(rect.y0));
+    PopupPlacement {
+        rect: Rect::from_origin_size(origin, request.size),
+        side,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn place_popup_flips_and_clamps() {
+        let bounds = Rect::new(0.0, 0.0, 200.0, 200.0);
+        let size = Size::new(100.0, 50.0);
+
+        let request = PopupRequest::new(Rect::new(50.0, 20.0, 90.0, 40.0), size).with_gap(8.0);
+        let placement = place_popup(&request, bounds, &[], 1.0);
+        assert_eq!(placement.side, Placement::Below);
+        assert_eq!(placement.rect, Rect::new(20.0, 48.0, 120.0, 98.0));
+
+        // There's no room below, so the popup flips above and moves left to fit
+        let request = PopupRequest::new(Rect::new(160.0, 170.0, 200.0, 190.0), size).with_gap(8.0);
+        let placement = place_popup(&request, bounds, &[], 1.0);
+        assert_eq!(placement.side, Placement::Above);
+        assert_eq!(placement.rect, Rect::new(100.0, 112.0, 200.0, 162.0));
+    }
+
+    #[test]
+    fn place_popup_avoids_other_popups() {
+        let bounds = Rect::new(0.0, 0.0, 200.0, 200.0);
+        let request = PopupRequest::new(Rect::new(80.0, 90.0, 120.0, 110.0), Size::new(40.0, 30.0));
+        let below = place_popup(&request, bounds, &[], 1.0);
+        assert_eq!(below.side, Placement::Below);
+
+        let placement = place_popup(&request, bounds, &[below.rect], 1.0);
+        assert_eq!(placement.side, Placement::Above);
+        assert_eq!(placement.rect, Rect::new(80.0, 60.0, 120.0, 90.0));
+
+        // Positions are rounded to device pixels
+        let request = PopupRequest::new(Rect::new(10.3, 0.0, 20.0, 10.0), Size::new(10.0, 10.0));
+        let placement = place_popup(&request, bounds, &[], 2.0);
+        assert_eq!(placement.rect.origin(), Point::new(10.0, 10.0));
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reusing a rasterized image of static content across frames.
+//!
+//! Masonry keeps the scene painted by each widget until it asks to be painted
+//! again, but the renderer still draws all of it on every frame. For expensive
+//! content which rarely changes, such as rendered markdown or a chart below an
+//! animation, a [`CachePaint`] widget can have its child drawn once to an image,
+//! which is drawn instead until a widget in the subtree is painted again.
+//!
+//! The image is rendered after the frame in which the content was painted, so
+//! that frame and the next one still draw the content itself. After that, the
+//! widget is painted again and draws the image.
+//!
+//! [`CachePaint`]: crate::widget::CachePaint
+
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc};
+
+use vello::kurbo::Affine;
+use vello::peniko::{Blob, Color, Format, Image};
+use vello::{AaConfig, RenderParams, Renderer, Scene};
+use wgpu::{Device, Queue};
+
+use crate::WidgetId;
+
+/// The cached images of the widgets in a window, by owner.
+#[derive(Default)]
+pub(crate) struct RasterCache {
+    entries: HashMap<WidgetId, Entry>,
+}
+
+struct Entry {
+    /// The size of the image, in physical pixels.
+    size: (u32, u32),
+    image: Option<Image>,
+    /// The content to draw to the image, in physical pixels, if it hasn't been
+    /// drawn yet.
+    pending: Option<Scene>,
+}
+
+impl RasterCache {
+    /// The image of `owner`'s content, if it was drawn at `size`.
+    pub(crate) fn get(&self, owner: WidgetId, size: (u32, u32)) -> Option<&Image> {
+        let entry = self.entries.get(&owner)?;
+        entry.image.as_ref().filter(|_| entry.size == size)
+    }
+
+    /// Whether the content `owner` has to be drawn to an image at `size`.
+    pub(crate) fn is_pending(&self, owner: WidgetId, size: (u32, u32)) -> bool {
+        let entry = self.entries.get(&owner);
+        entry.is_some_and(|entry| entry.size == size && entry.pending.is_some())
+    }
+
+    /// Drop the image of `owner`, and draw `content` at `size` instead.
+    pub(crate) fn invalidate(&mut self, owner: WidgetId, content: Scene, size: (u32, u32)) {
+        self.entries.insert(
+            owner,
+            Entry {
+                size,
+                image: None,
+                pending: Some(content),
+            },
+        );
+    }
+
+    pub(crate) fn remove(&mut self, owner: WidgetId) {
+        self.entries.remove(&owner);
+    }
+
+    /// Draw the pending content with `rasterize`, and return the owners of the new
+    /// images, which should be painted again to use them.
+    ///
+    /// Content which is empty, or has no area, is skipped, as there's nothing to
+    /// draw and the renderer can't draw it.
+    pub(crate) fn rasterize_pending(
+        &mut self,
+        mut rasterize: impl FnMut(&Scene, (u32, u32)) -> Option<Image>,
+    ) -> Vec<WidgetId> {
+        let mut rasterized = Vec::new();
+        for (owner, entry) in &mut self.entries {
+            let Some(content) = entry.pending.take() else {
+                continue;
+            };
+            let (width, height) = entry.size;
+            if content.encoding().is_empty() || width == 0 || height == 0 {
+                continue;
+            }
+            entry.image = rasterize(&content, entry.size);
+            if entry.image.is_some() {
+                rasterized.push(*owner);
+            }
+        }
+        rasterized
+    }
+}
+
+/// Render `scene`, in physical pixels, to an image of `size` in main memory.
+///
+/// Returns `None` if the image couldn't be rendered or read back.
+pub(crate) fn rasterize(
+    renderer: &mut Renderer,
+    device: &Device,
+    queue: &Queue,
+    scene: &Scene,
+    (width, height): (u32, u32),
+) -> Option<Image> {
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("raster cache"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let params = RenderParams {
+        base_color: Color::TRANSPARENT,
+        width,
+        height,
+        antialiasing_method: AaConfig::Area,
+    };
+    if let Err(err) = renderer.render_to_texture(device, queue, scene, &view, &params) {
+        tracing::warn!("failed to render cached content: {err}");
+        return None;
+    }
+
+    let pixels = match read_texture(device, queue, &texture) {
+        Ok(pixels) => pixels,
+        Err(err) => {
+            tracing::warn!("failed to read back cached content: {err}");
+            return None;
+        }
+    };
+    Some(Image::new(
+        Blob::new(Arc::new(pixels)),
+        Format::Rgba8,
+        width,
+        height,
+    ))
+}
+
+/// Copy the pixels of `texture`, which is in an 8-bit RGBA format, to main memory.
+pub(crate) fn read_texture(
+    device: &Device,
+    queue: &Queue,
+    texture: &wgpu::Texture,
+) -> Result<Vec<u8>, String> {
+    let (width, height) = (texture.width(), texture.height());
+    let padded_byte_width = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("read back"),
+        size: padded_byte_width as u64 * height as u64,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("read back"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_byte_width),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    let (sender, receiver) = mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    match receiver.recv() {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => return Err(err.to_string()),
+        Err(_) => return Err("the buffer was never mapped".into()),
+    }
+
+    let data = slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for row in data.chunks(padded_byte_width as usize) {
+        pixels.extend_from_slice(&row[..(width * 4) as usize]);
+    }
+    Ok(pixels)
+}
+
+/// The size of the image of content which covers `size` logical pixels.
+pub(crate) fn raster_size(size: vello::kurbo::Size, scale_factor: f64) -> (u32, u32) {
+    let size = (size * scale_factor).ceil();
+    (size.width as u32, size.height as u32)
+}
+
+/// The transform which draws an image of content, rendered at `scale_factor`, in
+/// the content's coordinates.
+pub(crate) fn image_transform(scale_factor: f64) -> Affine {
+    Affine::scale(scale_factor.recip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content() -> Scene {
+        let mut scene = Scene::new();
+        let rect = vello::kurbo::Rect::new(0.0, 0.0, 4.0, 2.0);
+        scene.fill(
+            vello::peniko::Fill::NonZero,
+            Affine::IDENTITY,
+            Color::WHITE,
+            None,
+            &rect,
+        );
+        scene
+    }
+
+    #[test]
+    fn images_are_dropped_when_invalidated() {
+        let owner = WidgetId::next();
+        let mut cache = RasterCache::default();
+        cache.invalidate(owner, content(), (4, 2));
+        assert!(cache.is_pending(owner, (4, 2)));
+        assert!(cache.get(owner, (4, 2)).is_none());
+
+        let image = |_: &Scene, (width, height): (u32, u32)| {
+            let pixels = vec![0; (width * height * 4) as usize];
+            Some(Image::new(pixels.into(), Format::Rgba8, width, height))
+        };
+        assert_eq!(cache.rasterize_pending(image), [owner]);
+        assert!(cache.rasterize_pending(image).is_empty());
+        assert!(cache.get(owner, (4, 2)).is_some());
+        // An image of another size is stale
+        assert!(cache.get(owner, (8, 4)).is_none());
+
+        cache.invalidate(owner, content(), (4, 2));
+        assert!(cache.get(owner, (4, 2)).is_none());
+        cache.remove(owner);
+        assert!(!cache.is_pending(owner, (4, 2)));
+    }
+
+    #[test]
+    fn empty_content_is_not_rasterized() {
+        let [empty, flat] = [WidgetId::next(), WidgetId::next()];
+        let mut cache = RasterCache::default();
+        cache.invalidate(empty, Scene::new(), (4, 2));
+        cache.invalidate(flat, content(), (4, 0));
+
+        let rasterize = |_: &Scene, _: (u32, u32)| -> Option<Image> {
+            panic!("empty content shouldn't be rasterized")
+        };
+        assert!(cache.rasterize_pending(rasterize).is_empty());
+        assert!(!cache.is_pending(empty, (4, 2)));
+        assert!(cache.get(empty, (4, 2)).is_none());
+        assert!(cache.get(flat, (4, 0)).is_none());
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The wgpu state the windows are rendered with.
+//!
+//! This does the same as vello's `util::RenderContext`, except that the adapter
+//! devices are created on can be chosen, rather than only through wgpu's
+//! `WGPU_ADAPTER_NAME` environment variable, and the adapter of each device is
+//! public so that we can tell which GPU is used.
+
+use vello::util::RenderSurface;
+use wgpu::{Adapter, Device, Instance, Queue, Surface, SurfaceTarget, TextureFormat};
+
+pub(crate) struct RenderContext {
+    pub(crate) instance: Instance,
+    pub(crate) devices: Vec<DeviceHandle>,
+    /// The name of the adapter to create devices on, instead of the one wgpu picks
+    adapter_name: Option<String>,
+}
+
+pub(crate) struct DeviceHandle {
+    pub(crate) adapter: Adapter,
+    pub(crate) device: Device,
+    pub(crate) queue: Queue,
+}
+
+impl RenderContext {
+    /// Create a context whose devices are created on the adapter called
+    /// `adapter_name`, or on the one wgpu picks if it is `None`.
+    pub(crate) fn new(adapter_name: Option<String>) -> Self {
+        let instance = Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::PRIMARY),
+            dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
+            ..Default::default()
+        });
+        Self {
+            instance,
+            devices: Vec::new(),
+            adapter_name,
+        }
+    }
+
+    /// The name of the adapter devices are created on, if one was chosen.
+    pub(crate) fn adapter_name(&self) -> Option<&str> {
+        self.adapter_name.as_deref()
+    }
+
+    /// Create devices on the adapter called `name` from now on, or on the one wgpu
+    /// picks if it is `None`.
+    pub(crate) fn set_adapter_name(&mut self, name: Option<String>) {
+        self.adapter_name = name;
+    }
+
+    /// Create a surface for `window`, of the given size in physical pixels.
+    pub(crate) async fn create_surface<'w>(
+        &mut self,
+        window: impl Into<SurfaceTarget<'w>>,
+        width: u32,
+        height: u32,
+        present_mode: wgpu::PresentMode,
+    ) -> Result<RenderSurface<'w>, String> {
+        let surface = self
+            .instance
+            .create_surface(window.into())
+            .map_err(|err| err.to_string())?;
+        let dev_id = match self.device(&surface).await {
+            Some(dev_id) => dev_id,
+            None => match &self.adapter_name {
+                Some(name) => return Err(format!("couldn't create a device on {name}")),
+                None => return Err("couldn't create a device".into()),
+            },
+        };
+
+        let capabilities = surface.get_capabilities(&self.devices[dev_id].adapter);
+        let Some(format) = capabilities
+            .formats
+            .into_iter()
+            .find(|it| matches!(it, TextureFormat::Rgba8Unorm | TextureFormat::Bgra8Unorm))
+        else {
+            return Err("the surface doesn't support Rgba8Unorm or Bgra8Unorm".into());
+        };
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+        let surface = RenderSurface {
+            surface,
+            config,
+            dev_id,
+            format,
+        };
+        self.configure_surface(&surface);
+        Ok(surface)
+    }
+
+    /// Resize `surface` to the given size in physical pixels.
+    pub(crate) fn resize_surface(&self, surface: &mut RenderSurface, width: u32, height: u32) {
+        surface.config.width = width;
+        surface.config.height = height;
+        self.configure_surface(surface);
+    }
+
+    fn configure_surface(&self, surface: &RenderSurface) {
+        let device = &self.devices[surface.dev_id].device;
+        surface.surface.configure(device, &surface.config);
+    }
+
+    /// The id of a device which can draw to `surface`, which is created if there
+    /// isn't one yet.
+    async fn device(&mut self, surface: &Surface<'_>) -> Option<usize> {
+        let compatible = self
+            .devices
+            .iter()
+            .position(|device| device.adapter.is_surface_supported(surface));
+        match compatible {
+            Some(dev_id) => Some(dev_id),
+            None => self.new_device(surface).await,
+        }
+    }
+
+    async fn new_device(&mut self, surface: &Surface<'_>) -> Option<usize> {
+        let adapter = match &self.adapter_name {
+            Some(name) => named_adapter(&self.instance, name, surface)?,
+            None => {
+                wgpu::util::initialize_adapter_from_env_or_default(&self.instance, Some(surface))
+                    .await?
+            }
+        };
+        let features = adapter.features() & wgpu::Features::CLEAR_TEXTURE;
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    required_features: features,
+                    required_limits: wgpu::Limits::default(),
+                },
+                None,
+            )
+            .await
+            .ok()?;
+        self.devices.push(DeviceHandle {
+            adapter,
+            device,
+            queue,
+        });
+        Some(self.devices.len() - 1)
+    }
+}
+
+/// The adapter called `name`, if it can draw to `surface`.
+fn named_adapter(instance: &Instance, name: &str, surface: &Surface<'_>) -> Option<Adapter> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        instance
+            .enumerate_adapters(wgpu::Backends::all())
+            .into_iter()
+            .find(|adapter| {
+                adapter.get_info().name == name && adapter.is_surface_supported(surface)
+            })
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (instance, name, surface);
+        None
+    }
+}
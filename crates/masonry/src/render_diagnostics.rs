@@ -0,0 +1,156 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Which GPU the app renders with, and what went wrong if it can't use one.
+//!
+//! If the window's GPU can't be set up, or can't run the renderer, Masonry falls
+//! back to a software renderer where the platform has one, such as llvmpipe or
+//! WARP, unless the app turns this off with [`AppDriver::software_fallback`]. If
+//! there is no way to render at all, [`run`] returns the [`RenderError`] instead of
+//! panicking.
+//!
+//! Apps can read the [`RenderDiagnostics`] with [`DriverCtx::render_diagnostics`],
+//! such as to tell the user why the app is slow when it fell back, or to include
+//! the GPU and driver in a bug report.
+//!
+//! [`AppDriver::software_fallback`]: crate::app_driver::AppDriver::software_fallback
+//! [`DriverCtx::render_diagnostics`]: crate::app_driver::DriverCtx::render_diagnostics
+//! [`run`]: crate::event_loop_runner::run
+
+use std::fmt;
+
+use wgpu::{AdapterInfo, DeviceType};
+
+/// The GPU, or software renderer, the app renders with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GpuInfo {
+    /// The name of the adapter, such as "NVIDIA GeForce RTX 3060" or "llvmpipe".
+    pub name: String,
+    /// The graphics API used to talk to it, such as "Vulkan" or "Metal".
+    pub backend: String,
+    /// The name and version of the driver, if the platform reports them.
+    pub driver: String,
+    /// Whether the adapter renders on the CPU.
+    pub software: bool,
+}
+
+impl GpuInfo {
+    pub fn from_adapter_info(info: &AdapterInfo) -> Self {
+        let driver = [info.driver.as_str(), info.driver_info.as_str()]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+        GpuInfo {
+            name: info.name.clone(),
+            backend: format!("{:?}", info.backend),
+            driver,
+            software: info.device_type == DeviceType::Cpu,
+        }
+    }
+}
+
+impl fmt::Display for GpuInfo {
+    /// The GPU, API and driver on one line, such as for an error dialog.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}", self.name, self.backend)?;
+        if !self.driver.is_empty() {
+            write!(f, ", {}", self.driver)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Why the app couldn't render with a GPU.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RenderError {
+    /// No GPU could draw to the window, which may mean there is none or that its
+    /// driver isn't installed.
+    Surface(String),
+    /// The GPU was found, but couldn't run the renderer, such as because its driver
+    /// is too old.
+    Renderer {
+        gpu: Option<GpuInfo>,
+        message: String,
+    },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::Surface(message) => {
+                write!(f, "no GPU could draw to the window: {message}")
+            }
+            RenderError::Renderer {
+                gpu: Some(gpu),
+                message,
+            } => write!(f, "the renderer couldn't run on {gpu}: {message}"),
+            RenderError::Renderer { gpu: None, message } => {
+                write!(f, "the renderer couldn't run on the GPU: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// What the app renders with, and whether it had to fall back to software.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RenderDiagnostics {
+    /// The GPU the app renders with, once it is known.
+    pub gpu: Option<GpuInfo>,
+    /// The error which made the app fall back to software rendering, if it did.
+    pub fallback_reason: Option<RenderError>,
+}
+
+impl RenderDiagnostics {
+    /// Whether the app renders in software, which is much slower than on a GPU.
+    pub fn is_software(&self) -> bool {
+        self.fallback_reason.is_some() || self.gpu.as_ref().is_some_and(|gpu| gpu.software)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu::Backend;
+
+    use super::*;
+
+    #[test]
+    fn diagnostics_describe_the_gpu() {
+        let info = AdapterInfo {
+            name: "llvmpipe (LLVM 15.0.7, 256 bits)".into(),
+            vendor: 0x10005,
+            device: 0,
+            device_type: DeviceType::Cpu,
+            driver: "llvmpipe".into(),
+            driver_info: "Mesa 23.2.1".into(),
+            backend: Backend::Vulkan,
+        };
+        let gpu = GpuInfo::from_adapter_info(&info);
+        assert_eq!(
+            gpu.to_string(),
+            "llvmpipe (LLVM 15.0.7, 256 bits) (Vulkan, llvmpipe Mesa 23.2.1)"
+        );
+
+        let diagnostics = RenderDiagnostics {
+            gpu: Some(gpu.clone()),
+            fallback_reason: None,
+        };
+        assert!(diagnostics.is_software());
+
+        let error = RenderError::Renderer {
+            gpu: Some(GpuInfo {
+                software: false,
+                driver: String::new(),
+                ..gpu
+            }),
+            message: "shader failed to compile".into(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "the renderer couldn't run on llvmpipe (LLVM 15.0.7, 256 bits) (Vulkan): shader failed to compile"
+        );
+    }
+}
@@ -1,12 +1,14 @@
 // Copyright 2019 the Xilem Authors and the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
 
 use accesskit::{ActionRequest, NodeBuilder, Tree, TreeUpdate};
 // Automatically defaults to std::time::Instant on non Wasm platforms
 use instant::Instant;
-use kurbo::Affine;
+use kurbo::{Affine, Rect, Size};
 use parley::FontContext;
 use tracing::{debug, info_span, warn};
 use vello::peniko::{Color, Fill};
@@ -15,19 +17,45 @@ use winit::dpi::{LogicalPosition, LogicalSize, PhysicalSize};
 use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::CursorIcon;
 
+use crate::clock::Clock;
+use crate::color::ColorSpace;
 use crate::contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, WidgetCtx, WorkerFn};
+use crate::conventions::PlatformConventions;
 use crate::debug_logger::DebugLogger;
-use crate::event::{PointerEvent, TextEvent, WindowEvent};
+use crate::direction::LayoutDirection;
+#[cfg(feature = "effects")]
+use crate::effects::{EffectLayer, LayerEffect};
+use crate::event::{PointerEvent, PointerState, TextEvent, WindowEvent};
+use crate::feedback::Feedback;
+use crate::frame_stats::{FrameRecorder, FrameStats};
+use crate::gpu::GpuResources;
+use crate::icon::IconSet;
+use crate::interaction::InteractionSettings;
+use crate::keymap::Keymap;
 use crate::kurbo::Point;
-use crate::widget::{WidgetMut, WidgetState};
+use crate::paint_debug::{self, PaintDebug};
+use crate::paint_scene_helpers::stroke;
+use crate::popup::PopupMode;
+use crate::raster_cache::{self, RasterCache};
+use crate::render_diagnostics::RenderDiagnostics;
+use crate::spellcheck::{NoSpellChecker, SpellChecker};
+use crate::taskbar::{AppIcon, TaskbarProgress};
+use crate::text_helpers::InputHint;
+use crate::wake::{WakeHandle, WakeQueue};
+use crate::widget::{WidgetMut, WidgetRef, WidgetState};
+use crate::widget_timings::{SlowWidget, WidgetCounters, WidgetTimings};
+use crate::widget_tree::{TreeArena, WidgetNode};
+use crate::window_kind::WindowKind;
 use crate::{
-    AccessCtx, AccessEvent, Action, BoxConstraints, Handled, InternalLifeCycle, LifeCycle, Widget,
-    WidgetId, WidgetPod,
+    theme, AccessCtx, AccessEvent, Action, BoxConstraints, Handled, InternalLifeCycle, LifeCycle,
+    Widget, WidgetId, WidgetPod,
 };
 
 // TODO - Remove pub(crate)
 pub struct RenderRoot {
     pub(crate) root: WidgetPod<Box<dyn Widget>>,
+    /// Every widget which has been added to the tree, along with its state.
+    pub(crate) widget_arena: TreeArena<WidgetNode>,
     pub(crate) size_policy: WindowSizePolicy,
     pub(crate) size: PhysicalSize<u32>,
     // TODO - Currently this is always 1.0
@@ -36,7 +64,13 @@ pub struct RenderRoot {
     /// Is `Some` if the most recently displayed frame was an animation frame.
     pub(crate) last_anim: Option<Instant>,
     pub(crate) last_mouse_pos: Option<LogicalPosition<f64>>,
+    /// The state of the pointer in the last pointer event, while it's in the window.
+    pub(crate) last_pointer_state: Option<PointerState>,
     pub(crate) cursor_icon: CursorIcon,
+    /// The caret rect last sent with [`RenderRootSignal::ImeMoved`].
+    pub(crate) ime_area: Option<Rect>,
+    /// The input hint last sent with [`RenderRootSignal::InputHintChanged`].
+    pub(crate) input_hint: Option<InputHint>,
     pub(crate) state: RenderRootState,
     // TODO - Add "access_tree_active" to detect when you don't need to update the
     // access tree
@@ -48,7 +82,102 @@ pub(crate) struct RenderRootState {
     pub(crate) signal_queue: VecDeque<RenderRootSignal>,
     pub(crate) focused_widget: Option<WidgetId>,
     pub(crate) next_focused_widget: Option<WidgetId>,
+    /// Whether the focused widget should show that it's focused, which is the case when
+    /// the user last used the keyboard rather than the pointer.
+    pub(crate) focus_visible: bool,
     pub(crate) font_context: FontContext,
+    pub(crate) keymap: Keymap,
+    pub(crate) icons: IconSet,
+    pub(crate) interaction: InteractionSettings,
+    pub(crate) clock: Clock,
+    pub(crate) conventions: PlatformConventions,
+    pub(crate) spell_checker: Box<dyn SpellChecker>,
+    /// The size of the window's content area, as of the last layout pass.
+    pub(crate) window_size: Size,
+    pub(crate) scale_factor: f64,
+    /// The rects of the popups placed in the current layout pass, in window coordinates.
+    pub(crate) popups: Vec<Rect>,
+    pub(crate) popup_mode: PopupMode,
+    pub(crate) color_space: ColorSpace,
+    pub(crate) layout_direction: LayoutDirection,
+    /// The part of the screen the window is on which windows can use, in window
+    /// coordinates, if known.
+    pub(crate) screen_rect: Option<Rect>,
+    /// A widget painted outside of its paint rect, which needs another layout pass
+    /// to take into account.
+    pub(crate) paint_rect_outgrown: bool,
+    /// A widget asked for the last pointer move to be sent again, with
+    /// [`LifeCycleCtx::request_pointer_move`].
+    pub(crate) pointer_move_requested: bool,
+    /// The child a scrolling container is laying out, and the part of it which is shown.
+    pub(crate) scroll_viewport: Option<(WidgetId, Rect)>,
+    /// The positions of the portals with a scroll key, by key.
+    pub(crate) scroll_positions: HashMap<String, Point>,
+    /// What widgets painted above the rest of the window, in paint order.
+    pub(crate) overlays: Vec<Overlay>,
+    pub(crate) gpu_resources: GpuResources,
+    pub(crate) raster_cache: RasterCache,
+    pub(crate) widget_timings: WidgetTimings,
+    pub(crate) wake_queue: Arc<WakeQueue>,
+    pub(crate) frame_stats: FrameRecorder,
+    /// When the event being handled was received, per the system clock.
+    pub(crate) event_timestamp: Option<Instant>,
+    /// Whether changes were already waiting to be shown when the event being
+    /// handled was received.
+    pub(crate) changes_before_event: bool,
+    pub(crate) render_diagnostics: RenderDiagnostics,
+    pub(crate) paint_debug: PaintDebug,
+    /// The widgets painted in the last frame, in paint order, with their layout
+    /// rects in window coordinates; only recorded while `paint_debug` is on.
+    pub(crate) paint_order: Vec<(WidgetId, Rect)>,
+}
+
+/// Content painted by a widget in the layer above the rest of the window, with
+/// [`PaintCtx::paint_overlay`].
+///
+/// The overlay is kept until its owner is painted again, so the main content and
+/// the overlays are cached separately.
+pub(crate) struct Overlay {
+    pub(crate) owner: WidgetId,
+    /// The transform from the owner's coordinates to the window.
+    pub(crate) transform: Affine,
+    pub(crate) scene: Scene,
+    /// The rect of the window the overlay is shown in instead of the main window,
+    /// in window coordinates, with [`PopupMode::NativeWindow`].
+    pub(crate) native_rect: Option<Rect>,
+    /// The pass run over the overlay, if it was painted with
+    /// [`PaintCtx::paint_effect`].
+    #[cfg(feature = "effects")]
+    pub(crate) effect: Option<LayerEffect>,
+}
+
+/// A popup to be shown in a window of its own, with [`PopupMode::NativeWindow`].
+pub struct NativePopup {
+    /// The widget which painted the popup.
+    pub owner: WidgetId,
+    /// Where the popup's window goes, in the main window's coordinates.
+    pub rect: Rect,
+    /// The content of the popup's window, in its own coordinates.
+    pub scene: Scene,
+}
+
+impl RenderRootState {
+    pub(crate) fn wake_handle(&self, widget: WidgetId) -> WakeHandle {
+        WakeHandle::new(widget, self.wake_queue.clone())
+    }
+
+    /// Drop the overlays owned by `widget`, before it paints new ones or is removed.
+    pub(crate) fn remove_overlays(&mut self, widget: WidgetId) {
+        self.overlays.retain(|overlay| overlay.owner != widget);
+    }
+    pub(crate) fn set_focus_visible(&mut self, focus_visible: bool) {
+        if self.focus_visible != focus_visible {
+            self.focus_visible = focus_visible;
+            if self.focused_widget.is_some() {
+                self.signal_queue.push_back(RenderRootSignal::RequestRedraw);
+            }
+        }
+    }
 }
 
 /// Defines how a windows size should be determined
@@ -73,7 +202,10 @@ pub enum RenderRootSignal {
     TextFieldRemoved,
     TextFieldFocused,
     ImeStarted,
-    ImeMoved,
+    /// The caret of the focused text widget moved to this rect, in window coordinates.
+    ImeMoved(Rect),
+    /// The focused widget takes this kind of text, or isn't a text input if `None`.
+    InputHintChanged(Option<InputHint>),
     ImeInvalidated,
     RequestRedraw,
     RequestAnimFrame,
@@ -82,24 +214,62 @@ pub enum RenderRootSignal {
     SetCursor(CursorIcon),
     SetSize(PhysicalSize<u32>),
     SetTitle(String),
+    Feedback(Feedback),
+    SetIcon(Option<AppIcon>),
+    SetBadgeCount(Option<u32>),
+    SetProgress(TaskbarProgress),
+    SetWindowKind(WindowKind),
 }
 
 impl RenderRoot {
     pub fn new(root_widget: impl Widget, size_policy: WindowSizePolicy, scale_factor: f64) -> Self {
         let mut root = RenderRoot {
             root: WidgetPod::new(root_widget).boxed(),
+            widget_arena: TreeArena::default(),
             size_policy,
             size: PhysicalSize::new(0, 0),
             scale_factor,
             last_anim: None,
             last_mouse_pos: None,
+            last_pointer_state: None,
             cursor_icon: CursorIcon::Default,
+            ime_area: None,
+            input_hint: None,
             state: RenderRootState {
                 debug_logger: DebugLogger::new(false),
                 signal_queue: VecDeque::new(),
                 focused_widget: None,
                 next_focused_widget: None,
+                focus_visible: false,
                 font_context: FontContext::default(),
+                keymap: Keymap::default(),
+                icons: IconSet::default(),
+                interaction: InteractionSettings::default(),
+                clock: Clock::system(),
+                conventions: PlatformConventions::default(),
+                spell_checker: Box::new(NoSpellChecker),
+                window_size: Size::ZERO,
+                scale_factor,
+                popups: Vec::new(),
+                popup_mode: PopupMode::default(),
+                color_space: ColorSpace::default(),
+                layout_direction: LayoutDirection::default(),
+                screen_rect: None,
+                paint_rect_outgrown: false,
+                pointer_move_requested: false,
+                scroll_viewport: None,
+                scroll_positions: HashMap::new(),
+                overlays: Vec::new(),
+                gpu_resources: GpuResources::default(),
+                raster_cache: RasterCache::default(),
+                widget_timings: WidgetTimings::default(),
+                wake_queue: Arc::default(),
+                frame_stats: FrameRecorder::default(),
+                event_timestamp: None,
+                changes_before_event: false,
+                render_diagnostics: RenderDiagnostics::default(),
+                paint_debug: PaintDebug::Off,
+                paint_order: Vec::new(),
             },
             rebuild_access_tree: true,
         };
@@ -119,9 +289,10 @@ impl RenderRoot {
         match event {
             WindowEvent::Rescale(scale_factor) => {
                 self.scale_factor = scale_factor;
-                // TODO - What we'd really like is to request a repaint and an accessibility
-                // pass for every single widget.
-                self.root.state.needs_layout = true;
+                // TODO - What we'd really like is to request an accessibility pass
+                // for every single widget.
+                self.root_state_mut().needs_layout = true;
+                self.request_paint_all();
                 self.state
                     .signal_queue
                     .push_back(RenderRootSignal::RequestRedraw);
@@ -129,14 +300,14 @@ impl RenderRoot {
             }
             WindowEvent::Resize(size) => {
                 self.size = size;
-                self.root.state.needs_layout = true;
+                self.root_state_mut().needs_layout = true;
                 self.state
                     .signal_queue
                     .push_back(RenderRootSignal::RequestRedraw);
                 Handled::Yes
             }
             WindowEvent::AnimFrame => {
-                let now = Instant::now();
+                let now = self.state.clock.now();
                 // TODO: this calculation uses wall-clock time of the paint call, which
                 // potentially has jitter.
                 //
@@ -148,6 +319,7 @@ impl RenderRoot {
                     self.root_lifecycle(LifeCycle::AnimFrame(elapsed_ns));
                     self.last_anim = Some(now);
                 }
+                self.replay_pointer_move();
                 Handled::Yes
             }
             WindowEvent::RebuildAccessTree => {
@@ -160,12 +332,81 @@ impl RenderRoot {
         }
     }
 
+    /// Send the last pointer move again if a widget asked for it, such as because it
+    /// scrolled its content under a pointer which is being dragged.
+    fn replay_pointer_move(&mut self) {
+        if !std::mem::take(&mut self.state.pointer_move_requested) {
+            return;
+        }
+        let Some(state) = self.last_pointer_state.clone() else {
+            return;
+        };
+        // Widgets need their new positions to make sense of the pointer
+        if self.root_state().needs_layout {
+            self.root_layout();
+        }
+        self.root_on_pointer_event(PointerEvent::PointerMove(state));
+    }
+
     pub fn handle_pointer_event(&mut self, event: PointerEvent) -> Handled {
-        self.root_on_pointer_event(event)
+        let start = self.receive_event();
+        let handled = self.root_on_pointer_event(event);
+        self.finish_event(start);
+        handled
     }
 
     pub fn handle_text_event(&mut self, event: TextEvent) -> Handled {
-        self.root_on_text_event(event)
+        let start = self.receive_event();
+        let handled = self.root_on_text_event(event);
+        self.finish_event(start);
+        handled
+    }
+
+    /// Timestamp an event as it is received, returning the timestamp.
+    fn receive_event(&mut self) -> Instant {
+        let now = Instant::now();
+        self.state.event_timestamp = Some(now);
+        self.state.changes_before_event = self.has_changes();
+        now
+    }
+
+    /// Record the time spent on the event received at `start`, and whether it
+    /// changed something for the latency statistics.
+    fn finish_event(&mut self, start: Instant) {
+        let now = Instant::now();
+        let changed = !self.state.changes_before_event && self.has_changes();
+        self.state.frame_stats.add_event(now - start);
+        self.state
+            .frame_stats
+            .add_handled_event(start, now, changed);
+        self.state.event_timestamp = None;
+    }
+
+    /// Whether something is waiting to be shown, or handled by the app.
+    fn has_changes(&self) -> bool {
+        let root = self.root_state();
+        root.needs_layout
+            || root.needs_paint
+            || self
+                .state
+                .signal_queue
+                .iter()
+                .any(|signal| matches!(signal, RenderRootSignal::Action(..)))
+    }
+
+    /// `true` iff any widget requested idle work since it last got some.
+    pub fn wants_idle_work(&self) -> bool {
+        self.root_state().request_idle
+    }
+
+    /// Give the widgets which requested idle work `budget` to do it, with a
+    /// [`LifeCycle::Idle`] event.
+    ///
+    /// This should be called when there are no events waiting.
+    pub fn run_idle_work(&mut self, budget: Duration) {
+        if self.wants_idle_work() {
+            self.root_lifecycle(LifeCycle::Idle(Instant::now() + budget));
+        }
     }
 
     pub fn redraw(&mut self) -> (Scene, TreeUpdate) {
@@ -174,18 +415,45 @@ impl RenderRoot {
 
         // TODO - if root widget's request_anim is still set by the
         // time this is called, emit a warning
-        if self.root.state().needs_layout {
+        let layout_start = Instant::now();
+        if self.root_state().needs_layout {
+            self.root_layout();
+        }
+        // Widgets which place their children in window coordinates, such as popovers,
+        // need another pass if the first one moved them
+        if self.root_state().needs_layout {
             self.root_layout();
         }
-        if self.root.state().needs_layout {
+        if self.root_state().needs_layout {
             warn!("Widget requested layout during layout pass");
             self.state
                 .signal_queue
                 .push_back(RenderRootSignal::RequestRedraw);
         }
 
+        self.state.frame_stats.add_layout(layout_start.elapsed());
+
         // TODO - Improve caching of scenes.
-        (self.root_paint(), self.root_accessibility())
+        let paint_start = Instant::now();
+        let scene = self.root_paint();
+        self.state.frame_stats.add_paint(paint_start.elapsed());
+        let tree_update = self.root_accessibility();
+        let widget_count = self.widget_arena.len().max(1);
+        self.state
+            .frame_stats
+            .finish_frame(Instant::now(), widget_count);
+        (scene, tree_update)
+    }
+
+    /// The statistics of the last frame drawn.
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.state.frame_stats.last
+    }
+
+    /// Record how long the last frame took to render, after [`redraw`](Self::redraw)
+    /// returned its scene, once it is presented.
+    pub fn record_render_time(&mut self, duration: Duration) {
+        self.state.frame_stats.set_render(duration, Instant::now());
     }
 
     pub fn pop_signal(&mut self) -> Option<RenderRootSignal> {
@@ -200,10 +468,333 @@ impl RenderRoot {
         self.state.signal_queue.remove(idx)
     }
 
+    /// The keyboard shortcuts used by widgets in this tree.
+    pub fn keymap(&self) -> &Keymap {
+        &self.state.keymap
+    }
+
+    /// Replace the keyboard shortcuts used by widgets in this tree.
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.state.keymap = keymap;
+    }
+
+    /// The timings and distances used by widgets in this tree to interpret input.
+    pub fn interaction_settings(&self) -> &InteractionSettings {
+        &self.state.interaction
+    }
+
+    /// Replace the timings and distances used by widgets in this tree to interpret input.
+    pub fn set_interaction_settings(&mut self, settings: InteractionSettings) {
+        self.state.interaction = settings;
+    }
+
+    /// The clock which widgets in this tree measure time with.
+    pub fn clock(&self) -> &Clock {
+        &self.state.clock
+    }
+
+    /// Replace the clock which widgets in this tree measure time with, such as with
+    /// a manual clock in tests.
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.state.clock = clock;
+    }
+
+    /// Move a manual clock forward by `duration`. See [`Clock::advance`].
+    pub fn advance_clock(&mut self, duration: Duration) {
+        self.state.clock.advance(duration);
+    }
+
+    /// The platform conventions followed by widgets in this tree.
+    pub fn conventions(&self) -> &PlatformConventions {
+        &self.state.conventions
+    }
+
+    /// Replace the platform conventions followed by widgets in this tree.
+    pub fn set_conventions(&mut self, conventions: PlatformConventions) {
+        self.state.conventions = conventions;
+        self.root_state_mut().needs_layout = true;
+        self.request_paint_all();
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// Where popups are shown.
+    pub fn popup_mode(&self) -> PopupMode {
+        self.state.popup_mode
+    }
+
+    /// Change where popups are shown.
+    pub fn set_popup_mode(&mut self, mode: PopupMode) {
+        self.state.popup_mode = mode;
+        self.root_state_mut().needs_layout = true;
+        self.request_paint_all();
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// The direction the window's layouts run in.
+    pub fn layout_direction(&self) -> LayoutDirection {
+        self.state.layout_direction
+    }
+
+    /// Lay the window out from right to left or from left to right.
+    ///
+    /// See [`direction`](crate::direction) for more.
+    pub fn set_layout_direction(&mut self, direction: LayoutDirection) {
+        if self.state.layout_direction == direction {
+            return;
+        }
+        self.state.layout_direction = direction;
+        self.root_state_mut().needs_layout = true;
+        self.request_paint_all();
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// The color space the window renders in.
+    pub fn color_space(&self) -> ColorSpace {
+        self.state.color_space
+    }
+
+    /// Change the color space the window renders in, which is for the event loop
+    /// to set, once it knows what the surface supports.
+    pub fn set_color_space(&mut self, space: ColorSpace) {
+        self.state.color_space = space;
+        self.request_paint_all();
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// What the window renders with, as reported by the event loop.
+    pub fn render_diagnostics(&self) -> &RenderDiagnostics {
+        &self.state.render_diagnostics
+    }
+
+    pub fn set_render_diagnostics(&mut self, diagnostics: RenderDiagnostics) {
+        self.state.render_diagnostics = diagnostics;
+    }
+
+    /// Set the part of the screen which windows can use, in window coordinates,
+    /// which popups in windows of their own are kept in.
+    ///
+    /// This should be updated when the window moves to another place or screen.
+    pub fn set_screen_rect(&mut self, screen_rect: Option<Rect>) {
+        if self.state.screen_rect != screen_rect {
+            self.state.screen_rect = screen_rect;
+            if self.state.popup_mode == PopupMode::NativeWindow {
+                self.root_state_mut().needs_layout = true;
+                self.state
+                    .signal_queue
+                    .push_back(RenderRootSignal::RequestRedraw);
+            }
+        }
+    }
+
+    /// Replace the spellchecker used by text widgets in this tree.
+    ///
+    /// Text which was already checked is checked again the next time it is
+    /// laid out.
+    pub fn set_spell_checker(&mut self, spell_checker: Box<dyn SpellChecker>) {
+        self.state.spell_checker = spell_checker;
+        self.root_state_mut().needs_layout = true;
+        self.request_paint_all();
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// Report widgets which spend longer than `threshold` handling an event, laying
+    /// out or painting, or stop reporting them if `threshold` is `None`.
+    ///
+    /// See [`widget_timings`](crate::widget_timings) for more.
+    pub fn set_slow_widget_threshold(&mut self, threshold: Option<Duration>) {
+        self.state.widget_timings.set_threshold(threshold);
+    }
+
+    /// The scroll positions of the portals given a
+    /// [scroll key](crate::widget::Portal::with_scroll_key), by key.
+    ///
+    /// This includes the positions of portals which have since been removed, so
+    /// that portals created later with the same keys are scrolled back there. An
+    /// app can save these with a [`Store`](crate::persistence::Store) to restore them
+    /// in its next launch.
+    pub fn scroll_positions(&self) -> &HashMap<String, Point> {
+        &self.state.scroll_positions
+    }
+
+    /// Replace the remembered scroll positions, such as with those saved in the
+    /// last launch; see [`scroll_positions`](Self::scroll_positions).
+    ///
+    /// Portals which are already shown aren't scrolled.
+    pub fn set_scroll_positions(&mut self, positions: HashMap<String, Point>) {
+        self.state.scroll_positions = positions;
+    }
+
+    /// Tint the window to show the order widgets are painted in, or how often they
+    /// paint over each other.
+    ///
+    /// See [`paint_debug`](crate::paint_debug) for more.
+    pub fn set_paint_debug(&mut self, mode: PaintDebug) {
+        self.state.paint_debug = mode;
+        self.state.paint_order.clear();
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// The current [`PaintDebug`] mode.
+    pub fn paint_debug(&self) -> PaintDebug {
+        self.state.paint_debug
+    }
+
+    /// The widgets painted in the last frame, in the order they were painted.
+    ///
+    /// This is only recorded while a [`PaintDebug`] mode is on, and is empty otherwise.
+    pub fn paint_order(&self) -> Vec<WidgetId> {
+        self.state.paint_order.iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Take the widgets which were slow since this was last called.
+    pub fn take_slow_widgets(&mut self) -> Vec<SlowWidget> {
+        self.state.widget_timings.take_reports()
+    }
+
+    /// How often the methods of the widget with the given id have run.
+    ///
+    /// See [`widget_timings`](crate::widget_timings) for more.
+    pub fn widget_counters(&self, id: WidgetId) -> WidgetCounters {
+        self.state.widget_timings.counters(id)
+    }
+
+    /// The widgets which have painted since the counters were last reset.
+    pub fn painted_widgets(&self) -> Vec<WidgetId> {
+        self.state.widget_timings.painted_widgets()
+    }
+
+    /// Set the counters of all widgets back to zero.
+    pub fn reset_widget_counters(&mut self) {
+        self.state.widget_timings.reset_counters();
+    }
+
+    /// The icons used by widgets in this tree.
+    pub fn icons(&self) -> &IconSet {
+        &self.state.icons
+    }
+
+    /// Replace the icons used by widgets in this tree, such as with another icon pack.
+    pub fn set_icons(&mut self, icons: IconSet) {
+        self.state.icons = icons;
+        self.root_state_mut().needs_layout = true;
+        self.request_paint_all();
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// Create the GPU resources which widgets registered since the last frame.
+    ///
+    /// This should be called before rendering each frame.
+    pub fn prepare_gpu_resources(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.state.gpu_resources.create_pending(device, queue);
+    }
+
+    /// Draw the content of [`CachePaint`](crate::widget::CachePaint) widgets which
+    /// changed in the last frame to images, to be drawn instead from the next frame.
+    ///
+    /// This should be called after rendering each frame. Returns whether the window
+    /// should be redrawn to use the new images.
+    pub fn rasterize_cached(
+        &mut self,
+        renderer: &mut vello::Renderer,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> bool {
+        self.fill_raster_cache(|scene, size| {
+            raster_cache::rasterize(renderer, device, queue, scene, size)
+        })
+    }
+
+    pub(crate) fn fill_raster_cache(
+        &mut self,
+        rasterize: impl FnMut(&Scene, (u32, u32)) -> Option<vello::peniko::Image>,
+    ) -> bool {
+        let widgets = self.state.raster_cache.rasterize_pending(rasterize);
+        if widgets.is_empty() {
+            return false;
+        }
+        self.root_lifecycle(LifeCycle::Internal(InternalLifeCycle::RouteRequestPaint {
+            widgets,
+        }));
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+        true
+    }
+
+    /// Release the GPU resources of every widget, and tell widgets the device is lost.
+    pub fn handle_device_lost(&mut self) {
+        self.state.gpu_resources.release_all();
+        self.root_lifecycle(LifeCycle::GpuDeviceLost);
+    }
+
+    /// Create the GPU resources of every widget on the new `device`, and tell
+    /// widgets they were recreated.
+    pub fn handle_device_recreated(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.state.gpu_resources.create_pending(device, queue);
+        self.root_lifecycle(LifeCycle::GpuDeviceRecreated);
+        self.state
+            .signal_queue
+            .push_back(RenderRootSignal::RequestRedraw);
+    }
+
+    /// A handle which wakes `widget` up from any thread; see [`wake`](crate::wake).
+    pub fn wake_handle(&self, widget: WidgetId) -> WakeHandle {
+        self.state.wake_handle(widget)
+    }
+
+    /// Call `notify` from the thread of a [`WakeHandle`] when it is woken, such as
+    /// to wake up the event loop, which should then call [`handle_wakes`](Self::handle_wakes).
+    pub fn set_wake_notifier(&mut self, notify: impl Fn() + Send + 'static) {
+        self.state.wake_queue.set_notify(Some(Box::new(notify)));
+    }
+
+    /// Send [`LifeCycle::Wake`] to the widgets woken since the last call.
+    pub fn handle_wakes(&mut self) {
+        let widgets = self.state.wake_queue.take();
+        if !widgets.is_empty() {
+            self.root_lifecycle(LifeCycle::Internal(InternalLifeCycle::RouteWake {
+                widgets,
+            }));
+        }
+    }
+
+    /// Wait until a widget has been woken, for up to `timeout`, and return whether
+    /// one has. The wakes still need to be handled with [`handle_wakes`](Self::handle_wakes).
+    pub fn wait_for_wake(&self, timeout: Duration) -> bool {
+        self.state.wake_queue.wait(timeout)
+    }
+
     pub fn cursor_icon(&self) -> CursorIcon {
         self.cursor_icon
     }
 
+    /// A reference to the root widget.
+    pub(crate) fn root_widget(&self) -> WidgetRef<'_, dyn Widget> {
+        self.root.as_ref_in(self.widget_arena.roots()).as_dyn()
+    }
+
+    fn root_state(&self) -> &WidgetState {
+        self.root.as_ref_in(self.widget_arena.roots()).state()
+    }
+
+    fn root_state_mut(&mut self) -> &mut WidgetState {
+        &mut self.root.node_mut(self.widget_arena.roots_mut()).item.state
+    }
+
     pub fn edit_root_widget<R>(
         &mut self,
         f: impl FnOnce(WidgetMut<'_, Box<dyn Widget>>) -> R,
@@ -211,19 +802,24 @@ impl RenderRoot {
         let mut fake_widget_state =
             WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
         self.state.next_focused_widget = self.state.focused_widget;
+        let root = self.root.node_mut(self.widget_arena.roots_mut());
         let root_widget = WidgetMut {
             ctx: WidgetCtx {
                 global_state: &mut self.state,
                 parent_widget_state: &mut fake_widget_state,
-                widget_state: &mut self.root.state,
+                widget_state: &mut root.item.state,
+                widget_children: root.children,
             },
-            widget: &mut self.root.inner,
+            widget: &mut root.item.widget,
         };
 
+        let start = Instant::now();
         let res = {
             let _span = info_span!("edit_root_widget").entered();
             f(root_widget)
         };
+        self.state.frame_stats.add_rebuild(start.elapsed());
+        self.state.frame_stats.add_state_change(Instant::now());
         self.post_event_processing(&mut fake_widget_state);
 
         res
@@ -237,15 +833,25 @@ impl RenderRoot {
         let mut ctx = EventCtx {
             global_state: &mut self.state,
             widget_state: &mut widget_state,
+            widget_children: self.widget_arena.roots_mut(),
             is_handled: false,
             request_pan_to_child: None,
         };
 
+        // Focus reached by clicking doesn't need to be shown
+        if let PointerEvent::PointerDown(..) = event {
+            ctx.global_state.set_focus_visible(false);
+        }
+
         // TODO - Only for primary pointer
         self.last_mouse_pos = match event {
             PointerEvent::PointerLeave(_) | PointerEvent::HoverFile(_, _) => None,
             _ => Some(event.pointer_state().position),
         };
+        self.last_pointer_state = match event {
+            PointerEvent::PointerLeave(_) | PointerEvent::HoverFile(_, _) => None,
+            _ => Some(event.pointer_state().clone()),
+        };
 
         let handled = {
             ctx.global_state
@@ -260,19 +866,15 @@ impl RenderRoot {
             Handled::from(ctx.is_handled)
         };
 
-        if let Some(cursor) = &ctx.widget_state.cursor {
-            // TODO - Add methods and `into()` impl to make this more concise.
-            ctx.global_state
-                .signal_queue
-                .push_back(RenderRootSignal::SetCursor(*cursor));
-        } else {
-            ctx.global_state
-                .signal_queue
-                .push_back(RenderRootSignal::SetCursor(CursorIcon::Default));
-        }
+        let cursor = ctx.widget_state.cursor.unwrap_or(CursorIcon::Default);
+        self.cursor_icon = cursor;
+        // TODO - Add methods and `into()` impl to make this more concise.
+        ctx.global_state
+            .signal_queue
+            .push_back(RenderRootSignal::SetCursor(cursor));
 
         self.post_event_processing(&mut widget_state);
-        self.root.as_dyn().debug_validate(false);
+        self.root_widget().debug_validate(false);
 
         handled
     }
@@ -285,10 +887,17 @@ impl RenderRoot {
         let mut ctx = EventCtx {
             global_state: &mut self.state,
             widget_state: &mut widget_state,
+            widget_children: self.widget_arena.roots_mut(),
             is_handled: false,
             request_pan_to_child: None,
         };
 
+        if let TextEvent::KeyboardKey(key, _) = &event {
+            if key.state.is_pressed() {
+                ctx.global_state.set_focus_visible(true);
+            }
+        }
+
         let handled = {
             ctx.global_state
                 .debug_logger
@@ -305,33 +914,80 @@ impl RenderRoot {
         // If event is tab we handle focus
         if let TextEvent::KeyboardKey(key, mods) = event {
             if handled == Handled::No && key.physical_key == PhysicalKey::Code(KeyCode::Tab) {
-                if !mods.shift_key() {
-                    self.state.next_focused_widget = self.widget_from_focus_chain(true);
-                } else {
-                    self.state.next_focused_widget = self.widget_from_focus_chain(false);
-                }
+                self.state.next_focused_widget = self.widget_from_focus_chain(!mods.shift_key());
             }
         }
 
         self.post_event_processing(&mut widget_state);
-        self.root.as_dyn().debug_validate(false);
+        self.root_widget().debug_validate(false);
 
         handled
     }
 
+    /// Move the focus to the next widget in the focus chain, or the previous one if
+    /// `forward` is false, as if the user pressed <kbd>Tab</kbd>.
+    ///
+    /// The focus is then shown with a focus ring.
+    pub fn move_focus(&mut self, forward: bool) {
+        let mut widget_state =
+            WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
+        self.state.next_focused_widget = self.widget_from_focus_chain(forward);
+        self.state.set_focus_visible(true);
+        self.post_event_processing(&mut widget_state);
+    }
+
+    /// The rect of the focus ring around the focused widget, in window coordinates.
+    ///
+    /// This is `None` when no widget is focused, or when the focus was reached with
+    /// the pointer, which people don't need to be shown.
+    pub fn focus_ring(&self) -> Option<Rect> {
+        if !self.state.focus_visible {
+            return None;
+        }
+        let focused = self.state.focused_widget?;
+        let widget = self.root_widget().find_widget_by_id(focused)?;
+        Some(
+            widget
+                .state()
+                .window_layout_rect()
+                .inflate(theme::FOCUS_RING_OFFSET, theme::FOCUS_RING_OFFSET),
+        )
+    }
+
+    /// Where the caret of the focused widget is, in window coordinates.
+    ///
+    /// See [`LayoutCtx::set_caret_rect`](crate::LayoutCtx::set_caret_rect).
+    pub fn caret_rect(&self) -> Option<Rect> {
+        let focused = self.state.focused_widget?;
+        let widget = self.root_widget().find_widget_by_id(focused)?;
+        widget.state().window_caret_rect()
+    }
+
+    /// The kind of text the focused widget takes, if it's a text input.
+    ///
+    /// See [`LayoutCtx::set_input_hint`](crate::LayoutCtx::set_input_hint).
+    pub fn input_hint(&self) -> Option<InputHint> {
+        let focused = self.state.focused_widget?;
+        let widget = self.root_widget().find_widget_by_id(focused)?;
+        widget.state().input_hint
+    }
+
     pub fn root_on_access_event(&mut self, event: ActionRequest) {
+        let start = self.receive_event();
         let mut widget_state =
             WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
 
         let mut ctx = EventCtx {
             global_state: &mut self.state,
             widget_state: &mut widget_state,
+            widget_children: self.widget_arena.roots_mut(),
             is_handled: false,
             request_pan_to_child: None,
         };
 
         let Ok(id) = event.target.0.try_into() else {
             warn!("Received ActionRequest with id 0. This shouldn't be possible.");
+            self.state.event_timestamp = None;
             return;
         };
         let event = AccessEvent {
@@ -351,7 +1007,8 @@ impl RenderRoot {
         }
 
         self.post_event_processing(&mut widget_state);
-        self.root.as_dyn().debug_validate(false);
+        self.root_widget().debug_validate(false);
+        self.finish_event(start);
     }
 
     fn root_lifecycle(&mut self, event: LifeCycle) {
@@ -360,6 +1017,7 @@ impl RenderRoot {
         let mut ctx = LifeCycleCtx {
             global_state: &mut self.state,
             widget_state: &mut widget_state,
+            widget_children: self.widget_arena.roots_mut(),
         };
 
         {
@@ -377,14 +1035,26 @@ impl RenderRoot {
         self.post_event_processing(&mut widget_state);
     }
 
+    /// Repaint every widget rather than only those whose cached paint is out of
+    /// date, for settings which change how the whole window looks.
+    ///
+    /// Relaying out doesn't repaint widgets which didn't move or resize.
+    fn request_paint_all(&mut self) {
+        self.root_lifecycle(LifeCycle::Internal(InternalLifeCycle::RouteRequestPaintAll));
+    }
+
     pub(crate) fn root_layout(&mut self) {
         let mut widget_state =
             WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
         let size = self.get_kurbo_size();
+        self.state.window_size = size;
+        self.state.scale_factor = self.scale_factor;
+        self.state.popups.clear();
         let mouse_pos = self.last_mouse_pos.map(|pos| (pos.x, pos.y).into());
         let mut layout_ctx = LayoutCtx {
             global_state: &mut self.state,
             widget_state: &mut widget_state,
+            widget_children: self.widget_arena.roots_mut(),
             mouse_pos,
         };
 
@@ -421,20 +1091,63 @@ impl RenderRoot {
     fn root_paint(&mut self) -> Scene {
         // TODO - Handle Xilem's VIEW_CONTEXT_CHANGED
 
-        let widget_state = WidgetState::new(self.root.id(), Some(self.get_kurbo_size()), "<root>");
+        let size = self.get_kurbo_size();
+        let widget_state = WidgetState::new(self.root.id(), Some(size), "<root>");
         let mut ctx = PaintCtx {
             global_state: &mut self.state,
             widget_state: &widget_state,
+            widget_children: self.widget_arena.roots_mut(),
+            clip: size.to_rect(),
             depth: 0,
             debug_paint: false,
             debug_widget: false,
+            painted_bounds: None,
         };
 
+        ctx.global_state.paint_order.clear();
         let mut scene = Scene::new();
         {
             let _span = info_span!("paint").entered();
             self.root.paint(&mut ctx, &mut scene);
         }
+        if std::mem::take(&mut self.state.paint_rect_outgrown) {
+            // Lay out again so that the paint rects of the widget and its ancestors
+            // include what it drew
+            self.root_state_mut().needs_layout = true;
+            self.state
+                .signal_queue
+                .push_back(RenderRootSignal::RequestRedraw);
+        }
+
+        self.paint_overlays(&mut scene);
+        paint_debug::paint_tints(&mut scene, self.state.paint_debug, &self.state.paint_order);
+
+        let ime_area = self.caret_rect();
+        if ime_area != self.ime_area {
+            self.ime_area = ime_area;
+            if let Some(rect) = ime_area {
+                self.state
+                    .signal_queue
+                    .push_back(RenderRootSignal::ImeMoved(rect));
+            }
+        }
+        let input_hint = self.input_hint();
+        if input_hint != self.input_hint {
+            self.input_hint = input_hint;
+            self.state
+                .signal_queue
+                .push_back(RenderRootSignal::InputHintChanged(input_hint));
+        }
+
+        if let Some(ring) = self.focus_ring() {
+            let ring = ring.to_rounded_rect(theme::FOCUS_RING_RADIUS);
+            stroke(
+                &mut scene,
+                &ring,
+                theme::FOCUS_RING_COLOR,
+                theme::FOCUS_RING_WIDTH,
+            );
+        }
 
         // FIXME - This is a workaround to Vello panicking when given an
         // empty scene
@@ -451,8 +1164,116 @@ impl RenderRoot {
         scene
     }
 
+    /// Paint the overlays above the main content, with those of nested widgets above
+    /// those of their ancestors.
+    ///
+    /// Overlays whose owner is stashed, or was culled along with one of its
+    /// ancestors, are not shown.
+    fn paint_overlays(&self, scene: &mut Scene) {
+        let overlays = self.state.overlays.iter();
+        #[cfg(feature = "effects")]
+        let overlays = overlays.filter(|overlay| overlay.effect.is_none());
+        let mut shown: Vec<_> = overlays
+            .filter(|overlay| overlay.native_rect.is_none())
+            .filter_map(|overlay| Some((self.shown_depth(overlay.owner)?, overlay)))
+            .collect();
+        shown.sort_by_key(|(depth, _)| *depth);
+        for (_, overlay) in shown {
+            scene.append(&overlay.scene, Some(overlay.transform));
+        }
+    }
+
+    /// The popups which go in windows of their own, as of the last paint pass, with
+    /// those of nested widgets last.
+    ///
+    /// This is empty unless the [`PopupMode`] is [`PopupMode::NativeWindow`].
+    pub fn native_popups(&self) -> Vec<NativePopup> {
+        let mut shown: Vec<_> = self
+            .state
+            .overlays
+            .iter()
+            .filter_map(|overlay| Some((overlay.native_rect?, overlay)))
+            .filter_map(|(rect, overlay)| Some((self.shown_depth(overlay.owner)?, rect, overlay)))
+            .collect();
+        shown.sort_by_key(|(depth, _, _)| *depth);
+        shown
+            .into_iter()
+            .map(|(_, rect, overlay)| {
+                let mut scene = Scene::new();
+                let transform = Affine::translate(-rect.origin().to_vec2()) * overlay.transform;
+                scene.append(&overlay.scene, Some(transform));
+                NativePopup {
+                    owner: overlay.owner,
+                    rect,
+                    scene,
+                }
+            })
+            .collect()
+    }
+
+    /// The layers painted with [`PaintCtx::paint_effect`] as of the last paint pass,
+    /// with those of nested widgets last.
+    #[cfg(feature = "effects")]
+    pub fn effect_layers(&self) -> Vec<EffectLayer> {
+        let mut shown: Vec<_> = self
+            .state
+            .overlays
+            .iter()
+            .filter_map(|overlay| Some((overlay.effect.as_ref()?, overlay)))
+            .filter_map(|(effect, overlay)| {
+                Some((self.shown_depth(overlay.owner)?, effect, overlay))
+            })
+            .collect();
+        shown.sort_by_key(|(depth, _, _)| *depth);
+        shown
+            .into_iter()
+            .map(|(_, effect, overlay)| {
+                let mut scene = Scene::new();
+                let transform =
+                    Affine::translate(-effect.rect.origin().to_vec2()) * overlay.transform;
+                scene.append(&overlay.scene, Some(transform));
+                EffectLayer {
+                    owner: overlay.owner,
+                    rect: effect.rect,
+                    scene,
+                    pass: effect.pass.clone(),
+                    backdrop: effect.backdrop,
+                }
+            })
+            .collect()
+    }
+
+    /// The depth of `id` in the tree, if it and its ancestors were shown in the
+    /// last paint pass.
+    fn shown_depth(&self, id: WidgetId) -> Option<usize> {
+        let mut path = self.widget_arena.id_path(id)?;
+        path.reverse();
+        let mut widget = self.root_widget();
+        if path.pop() != Some(widget.id()) {
+            return None;
+        }
+        let is_shown = |widget: WidgetRef<'_, dyn Widget>| {
+            let state = widget.state();
+            !state.is_stashed && !state.visible_paint_rect.is_empty()
+        };
+        if !is_shown(widget) {
+            return None;
+        }
+        let depth = path.len();
+        while let Some(next) = path.pop() {
+            widget = widget
+                .children()
+                .into_iter()
+                .find(|child| child.id() == next)?;
+            if !is_shown(widget) {
+                return None;
+            }
+        }
+        Some(depth)
+    }
+
     // TODO - Integrate in unit tests?
-    fn root_accessibility(&mut self) -> TreeUpdate {
+    pub(crate) fn root_accessibility(&mut self) -> TreeUpdate {
         let mut tree_update = TreeUpdate {
             nodes: vec![],
             tree: None,
@@ -463,6 +1284,7 @@ impl RenderRoot {
         let mut ctx = AccessCtx {
             global_state: &mut self.state,
             widget_state: &mut widget_state,
+            widget_children: self.widget_arena.roots_mut(),
             tree_update: &mut tree_update,
             current_node: NodeBuilder::default(),
             rebuild_all: self.rebuild_access_tree,
@@ -509,7 +1331,7 @@ impl RenderRoot {
             self.state.debug_logger.layout_tree.root = Some(self.root.id().to_raw() as u32);
         }
 
-        if self.root.state().needs_window_origin && !self.root.state().needs_layout {
+        if self.root_state().needs_window_origin && !self.root_state().needs_layout {
             let event = LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin {
                 mouse_pos: self.last_mouse_pos,
             });
@@ -518,14 +1340,14 @@ impl RenderRoot {
 
         // Update the disabled state if necessary
         // Always do this before updating the focus-chain
-        if self.root.state().tree_disabled_changed() {
+        if self.root_state().tree_disabled_changed() {
             let event = LifeCycle::Internal(InternalLifeCycle::RouteDisabledChanged);
             self.root_lifecycle(event);
         }
 
         // Update the focus-chain if necessary
         // Always do this before sending focus change, since this event updates the focus chain.
-        if self.root.state().update_focus_chain {
+        if self.root_state().update_focus_chain {
             let event = LifeCycle::BuildFocusChain;
             self.root_lifecycle(event);
         }
@@ -539,7 +1361,7 @@ impl RenderRoot {
                 .push_back(RenderRootSignal::RequestAnimFrame);
         }
 
-        if self.root.state().needs_paint {
+        if self.root_state().needs_paint {
             self.state
                 .signal_queue
                 .push_back(RenderRootSignal::RequestRedraw);
@@ -555,7 +1377,7 @@ impl RenderRoot {
 
     /// `true` iff any child requested an animation frame since the last `AnimFrame` event.
     fn wants_animation_frame(&self) -> bool {
-        self.root.state().request_anim
+        self.root_state().request_anim
     }
 
     fn update_focus(&mut self) {
@@ -569,6 +1391,12 @@ impl RenderRoot {
         if old != new {
             let event = LifeCycle::Internal(InternalLifeCycle::RouteFocusChanged { old, new });
             self.state.focused_widget = new;
+            if self.state.focus_visible {
+                // The focus ring moves to the new widget
+                self.state
+                    .signal_queue
+                    .push_back(RenderRootSignal::RequestRedraw);
+            }
             self.root_lifecycle(event);
 
             // TODO - Handle IME
@@ -606,7 +1434,7 @@ impl RenderRoot {
 
     // TODO - Store in RenderRootState
     pub(crate) fn focus_chain(&self) -> &[WidgetId] {
-        &self.root.state().focus_chain
+        &self.root_state().focus_chain
     }
 }
 
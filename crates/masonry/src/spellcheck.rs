@@ -0,0 +1,83 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flagging misspelled words in editable text.
+//!
+//! Masonry doesn't come with dictionaries. Apps which want spellchecking provide a
+//! [`SpellChecker`] from [`AppDriver::spell_checker`], usually backed by the
+//! platform's spellchecker or a library such as Hunspell, and turn it on for the
+//! text boxes which should be checked, with [`Textbox::with_spellcheck`].
+//!
+//! Text boxes underline the misspelled words with a squiggle. Right-clicking one
+//! selects it and submits [`Action::SpellingSuggestions`], so that the app can
+//! offer the suggestions, and replace the word with
+//! [`WidgetMut<Textbox>::replace_selection`].
+//!
+//! [`AppDriver::spell_checker`]: crate::app_driver::AppDriver::spell_checker
+//! [`Textbox::with_spellcheck`]: crate::widget::Textbox::with_spellcheck
+//! [`Action::SpellingSuggestions`]: crate::Action::SpellingSuggestions
+//! [`WidgetMut<Textbox>::replace_selection`]: crate::widget::WidgetMut::replace_selection
+
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Decides which words are misspelled, and what they might have been meant to be.
+pub trait SpellChecker {
+    /// Whether `word` is spelled correctly.
+    fn is_correct(&self, word: &str) -> bool;
+
+    /// The words `word` might be a misspelling of, most likely first.
+    fn suggestions(&self, word: &str) -> Vec<String> {
+        let _ = word;
+        Vec::new()
+    }
+
+    /// The byte ranges of the misspelled words in `text`.
+    ///
+    /// The default splits `text` at Unicode word boundaries, and checks each word
+    /// containing a letter with [`is_correct`](Self::is_correct).
+    fn misspellings(&self, text: &str) -> Vec<Range<usize>> {
+        text.split_word_bound_indices()
+            .filter(|(_, word)| word.chars().any(char::is_alphabetic))
+            .filter(|(_, word)| !self.is_correct(word))
+            .map(|(start, word)| start..start + word.len())
+            .collect()
+    }
+}
+
+/// A spellchecker which accepts every word, used when the app doesn't provide one.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoSpellChecker;
+
+impl SpellChecker for NoSpellChecker {
+    fn is_correct(&self, _word: &str) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Cats;
+
+    impl SpellChecker for Cats {
+        fn is_correct(&self, word: &str) -> bool {
+            word != "cta"
+        }
+    }
+
+    #[test]
+    fn words_are_checked_one_by_one() {
+        let text = "The cta sat on 2 mats, the cta!";
+        let misspellings = Cats.misspellings(text);
+        let words: Vec<_> = misspellings
+            .iter()
+            .map(|range| &text[range.clone()])
+            .collect();
+        assert_eq!(words, ["cta", "cta"]);
+        assert_eq!(misspellings[0], 4..7);
+        assert!(NoSpellChecker.misspellings(text).is_empty());
+    }
+}
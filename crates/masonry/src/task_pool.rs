@@ -0,0 +1,237 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Running work which would block the UI, such as reading files, on other threads.
+//!
+//! Work is spawned on a [`TaskPool`], which returns a [`Task`] to collect its
+//! result. A widget waiting on a task gives it a [`WakeHandle`] with
+//! [`Task::wake_when_done`], so that it is sent [`LifeCycle::Wake`] once the
+//! result is ready, rather than checking on the task every frame.
+//!
+//! [`LifeCycle::Wake`]: crate::LifeCycle::Wake
+
+use std::panic::AssertUnwindSafe;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::wake::WakeHandle;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed number of threads which run tasks in the order they were spawned.
+///
+/// The threads are only started once the first task is spawned, and they stop
+/// once the pool and all of its clones have been dropped and the tasks spawned
+/// before then are done.
+#[derive(Clone)]
+pub struct TaskPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    threads: usize,
+    sender: Mutex<Option<Sender<Job>>>,
+}
+
+/// The result of some work running in the background.
+///
+/// Tasks are cheap to clone, and all clones share the same result.
+pub struct Task<T> {
+    shared: Arc<Mutex<TaskState<T>>>,
+}
+
+struct TaskState<T> {
+    result: Option<std::thread::Result<T>>,
+    is_done: bool,
+    wake: Option<WakeHandle>,
+}
+
+impl TaskPool {
+    /// A pool with `threads` threads, or one if `threads` is zero.
+    pub fn new(threads: usize) -> Self {
+        TaskPool {
+            inner: Arc::new(PoolInner {
+                threads: threads.max(1),
+                sender: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Run `work` on one of the pool's threads.
+    ///
+    /// If `work` panics, the task's result is the panic's payload, and the thread
+    /// goes on to run the next task.
+    pub fn spawn<T: Send + 'static>(&self, work: impl FnOnce() -> T + Send + 'static) -> Task<T> {
+        let task = Task::new();
+        let finish = task.clone();
+        let job = Box::new(move || finish.finish(work));
+        let mut sender = self.inner.sender.lock().unwrap();
+        let sender = sender.get_or_insert_with(|| self.start());
+        sender
+            .send(job)
+            .expect("task pool threads stopped while the pool was alive");
+        task
+    }
+
+    fn start(&self) -> Sender<Job> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..self.inner.threads {
+            let receiver = receiver.clone();
+            std::thread::Builder::new()
+                .name(format!("masonry-task-{index}"))
+                .spawn(move || run_jobs(&receiver))
+                .expect("failed to start a task pool thread");
+        }
+        sender
+    }
+}
+
+impl Default for TaskPool {
+    /// A pool with a thread for each core, up to four.
+    fn default() -> Self {
+        let cores = std::thread::available_parallelism().map_or(1, usize::from);
+        TaskPool::new(cores.min(4))
+    }
+}
+
+impl std::fmt::Debug for TaskPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskPool")
+            .field("threads", &self.inner.threads)
+            .finish_non_exhaustive()
+    }
+}
+
+fn run_jobs(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // The lock is released before the job runs, so other threads can take jobs
+        let job = receiver.lock().unwrap().recv();
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+impl<T: Send + 'static> Task<T> {
+    /// Run `work` on a new thread of its own, for work which could hold up the
+    /// other tasks of a pool for a long time.
+    pub fn spawn_thread(work: impl FnOnce() -> T + Send + 'static) -> Self {
+        let task = Task::new();
+        let finish = task.clone();
+        std::thread::spawn(move || finish.finish(work));
+        task
+    }
+}
+
+impl<T> Task<T> {
+    fn new() -> Self {
+        Task {
+            shared: Arc::new(Mutex::new(TaskState {
+                result: None,
+                is_done: false,
+                wake: None,
+            })),
+        }
+    }
+
+    fn finish(&self, work: impl FnOnce() -> T) {
+        let result = std::panic::catch_unwind(AssertUnwindSafe(work));
+        let mut state = self.shared.lock().unwrap();
+        state.result = Some(result);
+        state.is_done = true;
+        if let Some(wake) = state.wake.take() {
+            wake.wake();
+        }
+    }
+
+    /// Wake the widget of `handle` once the task is done, or straight away if it
+    /// already is.
+    ///
+    /// This replaces the handle given before, if any.
+    pub fn wake_when_done(&self, handle: WakeHandle) {
+        let mut state = self.shared.lock().unwrap();
+        if state.is_done {
+            handle.wake();
+        } else {
+            state.wake = Some(handle);
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.shared.lock().unwrap().is_done
+    }
+
+    /// Take the result of the task, which is `Err` with the panic's payload if the
+    /// work panicked.
+    ///
+    /// Returns `None` until the task is done, and once its result has been taken.
+    pub fn take(&self) -> Option<std::thread::Result<T>> {
+        self.shared.lock().unwrap().result.take()
+    }
+
+    /// Whether both tasks are clones of the same task.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for Task<T> {
+    fn clone(&self) -> Self {
+        Task {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Task<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Task")
+            .field("is_done", &self.is_done())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Flex, SizedBox};
+
+    #[test]
+    fn finished_task_wakes_its_widget() {
+        let [widget_id] = widget_ids();
+        let widget = Flex::row().with_child_id(SizedBox::empty(), widget_id);
+        let mut harness = TestHarness::create(widget);
+
+        let pool = TaskPool::new(2);
+        let (sender, receiver) = mpsc::channel::<()>();
+        let task = pool.spawn(move || {
+            receiver.recv().unwrap();
+            6 * 7
+        });
+        task.wake_when_done(harness.wake_handle(widget_id));
+        assert!(!task.is_done());
+        assert!(task.take().is_none());
+
+        sender.send(()).unwrap();
+        assert!(harness.wait_for_wake(Duration::from_secs(5)));
+        assert_eq!(task.take().unwrap().unwrap(), 42);
+        assert!(task.take().is_none());
+    }
+
+    #[test]
+    fn panicking_task_keeps_the_pool_running() {
+        let pool = TaskPool::new(1);
+        let panicked = pool.spawn(|| panic!("task failed"));
+        let next = pool.spawn(|| "next");
+        while !next.is_done() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(panicked.take().unwrap().is_err());
+        assert_eq!(next.take().unwrap().unwrap(), "next");
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The app's icon, and the badge and progress shown on it in the dock or taskbar.
+//!
+//! Apps change these at runtime with [`DriverCtx::set_icon`],
+//! [`DriverCtx::set_badge_count`] and [`DriverCtx::set_progress`].
+//!
+//! The window's icon is set through winit, which shows it on Windows and X11. Other
+//! platforms don't give windows icons, and the dock icon, badges and progress have
+//! no cross-platform API, so these go to the app's [`TaskbarBackend`], from
+//! [`AppDriver::taskbar`]. The default backend does nothing, so on platforms where
+//! nothing else shows them, setting them is harmless.
+//!
+//! [`DriverCtx::set_icon`]: crate::app_driver::DriverCtx::set_icon
+//! [`DriverCtx::set_badge_count`]: crate::app_driver::DriverCtx::set_badge_count
+//! [`DriverCtx::set_progress`]: crate::app_driver::DriverCtx::set_progress
+//! [`AppDriver::taskbar`]: crate::app_driver::AppDriver::taskbar
+
+use winit::window::{BadIcon, Icon};
+
+/// An icon for the app's window, and its dock or taskbar entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppIcon {
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+/// The progress of a long task, shown on the app's dock or taskbar entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TaskbarProgress {
+    /// No progress is shown.
+    #[default]
+    Hidden,
+    /// A task is running, but its progress is unknown.
+    Indeterminate,
+    /// A task is this far along, from 0.0 to 1.0.
+    Fraction(f64),
+    /// A task is this far along, and has failed.
+    Error(f64),
+}
+
+impl AppIcon {
+    /// An icon from pixels in RGBA order, row by row from the top.
+    ///
+    /// Returns an error if there aren't `width * height` pixels.
+    pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, BadIcon> {
+        // Validated the same way the window's icon will be
+        Icon::from_rgba(rgba.clone(), width, height)?;
+        Ok(AppIcon {
+            rgba,
+            width,
+            height,
+        })
+    }
+
+    /// An icon from a decoded image.
+    pub fn from_image(image: &image::RgbaImage) -> Self {
+        AppIcon {
+            rgba: image.as_raw().clone(),
+            width: image.width(),
+            height: image.height(),
+        }
+    }
+
+    /// The icon's pixels, in RGBA order, row by row from the top.
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub(crate) fn to_window_icon(&self) -> Option<Icon> {
+        Icon::from_rgba(self.rgba.clone(), self.width, self.height).ok()
+    }
+}
+
+impl TaskbarProgress {
+    /// The fraction of the task which is done, clamped to 0.0..=1.0, if known.
+    pub fn fraction(self) -> Option<f64> {
+        match self {
+            TaskbarProgress::Fraction(fraction) | TaskbarProgress::Error(fraction) => {
+                Some(fraction.clamp(0.0, 1.0))
+            }
+            TaskbarProgress::Hidden | TaskbarProgress::Indeterminate => None,
+        }
+    }
+}
+
+/// Shows the app's icon, badge and progress where winit can't, such as in the
+/// macOS dock or the Windows taskbar.
+///
+/// Every method does nothing by default, so a backend only needs to implement
+/// what its platform supports.
+pub trait TaskbarBackend {
+    /// Show `icon` as the app's icon, or the default icon if it is `None`.
+    fn set_icon(&mut self, icon: Option<&AppIcon>) {
+        let _ = icon;
+    }
+
+    /// Show `count` on the app's icon, or no badge if it is `None`.
+    fn set_badge_count(&mut self, count: Option<u32>) {
+        let _ = count;
+    }
+
+    fn set_progress(&mut self, progress: TaskbarProgress) {
+        let _ = progress;
+    }
+}
+
+/// A [`TaskbarBackend`] which doesn't show anything.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoTaskbar;
+
+impl TaskbarBackend for NoTaskbar {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icons_need_every_pixel() {
+        assert!(AppIcon::from_rgba(vec![0; 2 * 3 * 4], 2, 3).is_ok());
+        assert!(AppIcon::from_rgba(vec![0; 5], 2, 3).is_err());
+
+        let image = image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]));
+        let icon = AppIcon::from_image(&image);
+        assert_eq!((icon.width(), icon.height()), (2, 2));
+        assert_eq!(&icon.rgba()[..4], [255, 0, 0, 255]);
+        assert!(icon.to_window_icon().is_some());
+
+        assert_eq!(TaskbarProgress::Error(1.5).fraction(), Some(1.0));
+        assert_eq!(TaskbarProgress::Indeterminate.fraction(), None);
+    }
+}
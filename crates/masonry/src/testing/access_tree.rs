@@ -0,0 +1,198 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Queries over the accessibility tree, for tests.
+
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use accesskit::{Node, NodeId, Role, TreeUpdate};
+
+/// The accessibility tree of a [`TestHarness`](super::TestHarness), as a screen
+/// reader would see it.
+///
+/// Nodes are found by their role and name, like a screen reader user would, or by
+/// the id of their widget, which converts into a [`NodeId`]:
+///
+/// ```ignore
+/// let tree = harness.accessibility_tree();
+/// let button = tree.find(Role::Button, "Save").unwrap();
+/// tree.assert_focused(button.id());
+/// tree.assert_value(textbox_id, "Hello");
+/// ```
+pub struct AccessTree {
+    nodes: HashMap<NodeId, Node>,
+    root: Option<NodeId>,
+    focus: NodeId,
+}
+
+/// A node of an [`AccessTree`], which derefs to its accesskit [`Node`].
+#[derive(Clone, Copy)]
+pub struct AccessNode<'a> {
+    id: NodeId,
+    node: &'a Node,
+    tree: &'a AccessTree,
+}
+
+impl AccessTree {
+    /// The tree made from `update`, which should be of the whole tree.
+    pub(crate) fn new(update: TreeUpdate) -> Self {
+        AccessTree {
+            root: update.tree.map(|tree| tree.root),
+            focus: update.focus,
+            nodes: update.nodes.into_iter().collect(),
+        }
+    }
+
+    pub fn node(&self, id: impl Into<NodeId>) -> Option<AccessNode<'_>> {
+        let id = id.into();
+        let node = self.nodes.get(&id)?;
+        Some(AccessNode {
+            id,
+            node,
+            tree: self,
+        })
+    }
+
+    /// The node with the focus, if it has a node.
+    pub fn focus(&self) -> Option<AccessNode<'_>> {
+        self.node(self.focus)
+    }
+
+    /// All of the nodes, in the order a screen reader reads them, which is the
+    /// order of a depth-first walk from the root.
+    pub fn nodes(&self) -> Vec<AccessNode<'_>> {
+        let mut nodes = Vec::new();
+        let mut stack: Vec<NodeId> = self.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let Some(node) = self.node(id) else {
+                continue;
+            };
+            stack.extend(node.children().iter().rev());
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// The first node with `role` and the [accessible name](AccessNode::accessible_name)
+    /// `name`.
+    pub fn find(&self, role: Role, name: &str) -> Option<AccessNode<'_>> {
+        self.nodes()
+            .into_iter()
+            .find(|node| node.role() == role && node.accessible_name().as_deref() == Some(name))
+    }
+
+    /// The nodes with `role`, in reading order.
+    pub fn find_all(&self, role: Role) -> Vec<AccessNode<'_>> {
+        let nodes = self.nodes().into_iter();
+        nodes.filter(|node| node.role() == role).collect()
+    }
+
+    /// Check that the node `id` has the focus.
+    #[track_caller]
+    pub fn assert_focused(&self, id: impl Into<NodeId>) {
+        let id = id.into();
+        let focus = self.nodes.get(&self.focus);
+        assert_eq!(
+            self.focus,
+            id,
+            "expected {id:?} to be focused, but {:?} is, which is a {:?} named {:?}",
+            self.focus,
+            focus.map(|node| node.role()),
+            focus.and_then(|node| node.name()),
+        );
+    }
+
+    /// Check that the node `id` has `value`, such as the text of a text box.
+    #[track_caller]
+    pub fn assert_value(&self, id: impl Into<NodeId>, value: &str) {
+        let id = id.into();
+        let Some(node) = self.node(id) else {
+            panic!("expected {id:?} to have the value {value:?}, but it has no node");
+        };
+        assert_eq!(node.value(), Some(value), "value of {id:?}");
+    }
+}
+
+impl<'a> AccessNode<'a> {
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// The children of the node which are in the tree.
+    pub fn child_nodes(&self) -> Vec<AccessNode<'a>> {
+        let children = self.node.children().iter();
+        children.filter_map(|id| self.tree.node(*id)).collect()
+    }
+
+    /// The name a screen reader announces for the node, which is its own name, or
+    /// else the names of its descendants, such as the label of a button.
+    pub fn accessible_name(&self) -> Option<String> {
+        if let Some(name) = self.node.name() {
+            return Some(name.to_string());
+        }
+        let names: Vec<String> = self
+            .child_nodes()
+            .iter()
+            .filter_map(|child| child.accessible_name())
+            .collect();
+        (!names.is_empty()).then(|| names.join(" "))
+    }
+}
+
+impl Deref for AccessNode<'_> {
+    type Target = Node;
+
+    fn deref(&self) -> &Node {
+        self.node
+    }
+}
+
+impl std::fmt::Debug for AccessNode<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessNode")
+            .field("id", &self.id)
+            .field("role", &self.role())
+            .field("name", &self.accessible_name())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Button, Flex, Label, Textbox};
+
+    #[test]
+    fn nodes_are_found_by_role_and_name() {
+        let [save_id, textbox_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(Label::new("Title"))
+            .with_child_id(Textbox::new("Hello"), textbox_id)
+            .with_child(Button::new("Cancel"))
+            .with_child_id(Button::new("Save"), save_id);
+        let mut harness = TestHarness::create(widget);
+
+        let tree = harness.accessibility_tree();
+        let buttons = tree.find_all(Role::Button);
+        let names = buttons.iter().map(|node| node.accessible_name());
+        assert_eq!(
+            names.collect::<Vec<_>>(),
+            [Some("Cancel".to_string()), Some("Save".to_string())]
+        );
+        assert_eq!(
+            tree.find(Role::Button, "Save").unwrap().id(),
+            save_id.into()
+        );
+        assert!(tree.find(Role::Button, "Title").is_none());
+        tree.assert_value(textbox_id, "Hello");
+
+        harness.mouse_click_on(textbox_id);
+        harness.accessibility_tree().assert_focused(textbox_id);
+        harness.move_focus(true);
+        let tree = harness.accessibility_tree();
+        let cancel = tree.find(Role::Button, "Cancel").unwrap();
+        tree.assert_focused(cancel.id());
+    }
+}
@@ -3,10 +3,14 @@
 
 //! Tools and infrastructure for testing widgets.
 
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 
+use accesskit::{ActionRequest, TreeUpdate};
 use image::io::Reader as ImageReader;
 use image::{Rgba, RgbaImage};
+use instant::Instant;
 use vello::util::RenderContext;
 use vello::{block_on_wgpu, RendererOptions};
 use wgpu::{
@@ -15,15 +19,28 @@ use wgpu::{
 };
 use winit::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize};
 use winit::event::{Ime, MouseButton};
+use winit::keyboard::ModifiersState;
+use winit::window::CursorIcon;
 
+use super::access_tree::AccessTree;
 use super::screenshots::get_image_diff;
 use super::snapshot_utils::get_cargo_workspace;
 use crate::action::Action;
+use crate::clock::Clock;
+use crate::conventions::PlatformConventions;
+use crate::direction::LayoutDirection;
 use crate::event::{PointerEvent, PointerState, TextEvent, WindowEvent};
 use crate::event_loop_runner::try_init_tracing;
+use crate::feedback::Feedback;
+use crate::interaction::InteractionSettings;
+use crate::paint_debug::PaintDebug;
+use crate::popup::PopupMode;
 use crate::render_root::{RenderRoot, RenderRootSignal, WindowSizePolicy};
+use crate::spellcheck::SpellChecker;
+use crate::wake::WakeHandle;
 use crate::widget::{WidgetMut, WidgetRef};
-use crate::{Color, Handled, Point, Size, Vec2, Widget, WidgetId};
+use crate::widget_timings::{SlowWidget, WidgetCounters};
+use crate::{Color, Handled, IconSet, InputHint, Point, Rect, Size, Vec2, Widget, WidgetId};
 
 // TODO - Get shorter names
 // TODO - Make them associated consts
@@ -62,8 +79,10 @@ pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29
 ///
 /// `TestHarness` tries to act like the normal masonry environment. For instance, it will dispatch every `Command` sent during event handling, handle lifecycle methods, etc.
 ///
-/// The passage of time is simulated with the [`move_timers_forward`](Self::move_timers_forward) methods. **(TODO -
-/// Doesn't move animations forward.)**
+/// Widgets measure time with a [manual clock](crate::clock::Clock::manual), which only moves
+/// forward with the [`animate`](Self::animate) and [`advance_time`](Self::advance_time) methods,
+/// so tests of animations and debounces don't need to sleep. **(TODO - Timers aren't
+/// implemented.)**
 ///
 /// **(TODO - ExtEvents aren't handled.)**
 ///
@@ -120,6 +139,8 @@ pub const HARNESS_DEFAULT_BACKGROUND_COLOR: Color = Color::rgb8(0x29, 0x29, 0x29
 pub struct TestHarness {
     render_root: RenderRoot,
     mouse_state: PointerState,
+    /// When the mouse button was last pressed, to tell double clicks apart.
+    last_press: Option<Instant>,
     window_size: PhysicalSize<u32>,
     background_color: Color,
 }
@@ -184,9 +205,11 @@ impl TestHarness {
         let mut harness = TestHarness {
             render_root: RenderRoot::new(root_widget, WindowSizePolicy::User, 1.0),
             mouse_state,
+            last_press: None,
             window_size,
             background_color,
         };
+        harness.render_root.set_clock(Clock::manual(Instant::now()));
         harness.process_window_event(WindowEvent::Resize(window_size));
 
         harness
@@ -228,8 +251,11 @@ impl TestHarness {
     }
 
     fn process_state_after_event(&mut self) {
-        if self.root_widget().state().needs_layout {
-            self.render_root.root_layout();
+        // As in `RenderRoot::redraw`, a second pass lets popovers settle
+        for _ in 0..2 {
+            if self.root_widget().state().needs_layout {
+                self.render_root.root_layout();
+            }
         }
     }
 
@@ -248,6 +274,9 @@ impl TestHarness {
         let device_handle = &mut context.devices[device_id];
         let device = &device_handle.device;
         let queue = &device_handle.queue;
+        // TODO - Each render uses a new device, and resources are only created on
+        // the first one. They are also created after painting instead of before.
+        self.render_root.prepare_gpu_resources(device, queue);
         let mut renderer = vello::Renderer::new(
             device,
             RendererOptions {
@@ -290,6 +319,8 @@ impl TestHarness {
         renderer
             .render_to_texture(device, queue, &scene, &view, &render_params)
             .expect("Got non-Send/Sync error from rendering");
+        self.render_root
+            .rasterize_cached(&mut renderer, device, queue);
         let padded_byte_width = (width * 4).next_multiple_of(256);
         let buffer_size = padded_byte_width as u64 * height as u64;
         let buffer = device.create_buffer(&BufferDescriptor {
@@ -340,6 +371,10 @@ impl TestHarness {
         // FIXME - Account for scaling
         let pos = pos.into();
         let pos = PhysicalPosition::new(pos.x, pos.y);
+        if pos != self.mouse_state.physical_position {
+            // Clicks only count as a double click if the mouse doesn't move in between
+            self.mouse_state.count = 0;
+        }
         self.mouse_state.physical_position = dbg!(pos);
         // TODO: may want to support testing with non-unity scale factors.
         let scale_factor = 1.0;
@@ -349,8 +384,22 @@ impl TestHarness {
     }
 
     /// Send a MouseDown event to the window.
+    ///
+    /// Presses without a [`mouse_move`](Self::mouse_move) to another position in
+    /// between count as multiple clicks, unless the double click interval passes
+    /// between them.
     pub fn mouse_button_press(&mut self, button: MouseButton) {
         self.mouse_state.buttons.insert(button);
+        let now = self.render_root.clock().now();
+        let interval = self
+            .render_root
+            .interaction_settings()
+            .double_click_interval;
+        if self.last_press.is_some_and(|last| now - last >= interval) {
+            self.mouse_state.count = 0;
+        }
+        self.last_press = Some(now);
+        self.mouse_state.count = self.mouse_state.count.saturating_add(1);
         self.process_pointer_event(PointerEvent::PointerDown(button, self.mouse_state.clone()));
     }
 
@@ -360,6 +409,12 @@ impl TestHarness {
         self.process_pointer_event(PointerEvent::PointerUp(button, self.mouse_state.clone()));
     }
 
+    /// Hold down `mods`, such as for a <kbd>Ctrl</kbd>+click, until they are changed again.
+    pub fn set_modifiers(&mut self, mods: ModifiersState) {
+        self.mouse_state.mods = mods.into();
+        self.process_text_event(TextEvent::ModifierChange(mods));
+    }
+
     /// Send a Wheel event to the window
     pub fn mouse_wheel(&mut self, wheel_delta: Vec2) {
         let pixel_delta = LogicalPosition::new(wheel_delta.x, wheel_delta.y);
@@ -402,6 +457,217 @@ impl TestHarness {
         self.process_state_after_event();
     }
 
+    /// Send a request from an accessibility tool, such as a screen reader, to the
+    /// widget it targets.
+    pub fn process_access_event(&mut self, event: ActionRequest) {
+        self.render_root.root_on_access_event(event);
+        self.process_state_after_event();
+    }
+
+    /// A handle which wakes `widget` up; see [`wake`](crate::wake).
+    pub fn wake_handle(&self, widget: WidgetId) -> WakeHandle {
+        self.render_root.wake_handle(widget)
+    }
+
+    /// Send [`LifeCycle::Wake`](crate::LifeCycle::Wake) to the widgets woken by their
+    /// [`WakeHandle`]s since the last call.
+    ///
+    /// The event loop does this as soon as a widget is woken, but the harness only
+    /// does it when asked, so that tests decide when background work is seen.
+    pub fn process_wakes(&mut self) {
+        self.render_root.handle_wakes();
+        self.process_state_after_event();
+    }
+
+    /// Wait until a widget is woken, for up to `timeout`, then [process the
+    /// wakes](Self::process_wakes). Return whether a widget was woken.
+    pub fn wait_for_wake(&mut self, timeout: Duration) -> bool {
+        let woken = self.render_root.wait_for_wake(timeout);
+        self.process_wakes();
+        woken
+    }
+
+    /// Rebuild the whole accessibility tree, and return its nodes.
+    pub fn access_tree(&mut self) -> TreeUpdate {
+        if self.root_widget().state().needs_layout {
+            self.render_root.root_layout();
+        }
+        self.render_root.rebuild_access_tree = true;
+        self.render_root.root_accessibility()
+    }
+
+    /// Rebuild the whole accessibility tree, and return it with queries to check
+    /// what a screen reader would see, such as which node has the focus.
+    pub fn accessibility_tree(&mut self) -> AccessTree {
+        AccessTree::new(self.access_tree())
+    }
+
+    /// Move the focus to the next widget in the focus chain, or the previous one if
+    /// `forward` is false, as if the user pressed <kbd>Tab</kbd>.
+    pub fn move_focus(&mut self, forward: bool) {
+        self.render_root.move_focus(forward);
+        self.process_state_after_event();
+    }
+
+    /// The rect of the focus ring, if one is shown.
+    ///
+    /// See [`RenderRoot::focus_ring`].
+    pub fn focus_ring(&self) -> Option<Rect> {
+        self.render_root.focus_ring()
+    }
+
+    /// Simulate the loss of the GPU device.
+    ///
+    /// Widgets' GPU resources are released, and widgets receive
+    /// [`LifeCycle::GpuDeviceLost`](crate::LifeCycle::GpuDeviceLost).
+    pub fn lose_gpu_device(&mut self) {
+        self.render_root.handle_device_lost();
+        self.process_state_after_event();
+    }
+
+    /// Replace the timings and distances used by widgets to interpret input.
+    pub fn set_interaction_settings(&mut self, settings: InteractionSettings) {
+        self.render_root.set_interaction_settings(settings);
+    }
+
+    /// Tint the window to show how it was painted, and record the paint order.
+    ///
+    /// See [`paint_debug`](crate::paint_debug) for more.
+    pub fn set_paint_debug(&mut self, mode: PaintDebug) {
+        self.render_root.set_paint_debug(mode);
+        self.process_state_after_event();
+    }
+
+    /// The widgets painted in the last [`render`](Self::render), in paint order,
+    /// while a [`PaintDebug`] mode is on.
+    pub fn paint_order(&self) -> Vec<WidgetId> {
+        self.render_root.paint_order()
+    }
+
+    /// The remembered scroll positions of the portals with a scroll key, by key.
+    pub fn scroll_positions(&self) -> &HashMap<String, Point> {
+        self.render_root.scroll_positions()
+    }
+
+    /// Change where popups are shown.
+    pub fn set_popup_mode(&mut self, mode: PopupMode) {
+        self.render_root.set_popup_mode(mode);
+        self.process_state_after_event();
+    }
+
+    /// Set the part of the screen popups in windows of their own are kept in, in
+    /// window coordinates.
+    pub fn set_screen_rect(&mut self, screen_rect: Option<Rect>) {
+        self.render_root.set_screen_rect(screen_rect);
+        self.process_state_after_event();
+    }
+
+    /// The owners and window rects of the popups shown in windows of their own,
+    /// as of the last paint pass.
+    pub fn native_popups(&self) -> Vec<(WidgetId, Rect)> {
+        self.render_root
+            .native_popups()
+            .into_iter()
+            .map(|popup| (popup.owner, popup.rect))
+            .collect()
+    }
+
+    /// The owners and window rects of the layers painted with
+    /// [`PaintCtx::paint_effect`](crate::PaintCtx::paint_effect), as of the last
+    /// paint pass.
+    #[cfg(feature = "effects")]
+    pub fn effect_layers(&self) -> Vec<(WidgetId, Rect)> {
+        self.render_root
+            .effect_layers()
+            .into_iter()
+            .map(|layer| (layer.owner, layer.rect))
+            .collect()
+    }
+
+    /// Replace the platform conventions followed by widgets, such as to test
+    /// another platform's.
+    pub fn set_conventions(&mut self, conventions: PlatformConventions) {
+        self.render_root.set_conventions(conventions);
+        self.process_state_after_event();
+    }
+
+    /// Replace the spellchecker used by text widgets.
+    pub fn set_spell_checker(&mut self, spell_checker: impl SpellChecker + 'static) {
+        self.render_root.set_spell_checker(Box::new(spell_checker));
+        self.process_state_after_event();
+    }
+
+    /// Replace the icons used by widgets, such as to test another icon pack.
+    pub fn set_icons(&mut self, icons: IconSet) {
+        self.render_root.set_icons(icons);
+        self.process_state_after_event();
+    }
+
+    /// Lay the window out from right to left or from left to right.
+    ///
+    /// See [`RenderRoot::set_layout_direction`].
+    pub fn set_layout_direction(&mut self, direction: LayoutDirection) {
+        self.render_root.set_layout_direction(direction);
+        self.process_state_after_event();
+    }
+
+    /// Report widgets which are slower than `threshold`.
+    ///
+    /// See [`RenderRoot::set_slow_widget_threshold`].
+    pub fn set_slow_widget_threshold(&mut self, threshold: Option<Duration>) {
+        self.render_root.set_slow_widget_threshold(threshold);
+    }
+
+    /// Take the widgets which were slower than the threshold since this was last called.
+    pub fn take_slow_widgets(&mut self) -> Vec<SlowWidget> {
+        self.render_root.take_slow_widgets()
+    }
+
+    /// How often the methods of the widget with the given id have run.
+    ///
+    /// See [`RenderRoot::widget_counters`].
+    pub fn widget_counters(&self, id: WidgetId) -> WidgetCounters {
+        self.render_root.widget_counters(id)
+    }
+
+    /// The widgets which have painted since the counters were last reset.
+    ///
+    /// Paint only runs when the window is rendered, such as with [`render`](Self::render).
+    pub fn painted_widgets(&self) -> Vec<WidgetId> {
+        self.render_root.painted_widgets()
+    }
+
+    /// Set the counters of all widgets back to zero, so that the next ones only
+    /// count what happens from now on.
+    pub fn reset_widget_counters(&mut self) {
+        self.render_root.reset_widget_counters();
+    }
+
+    /// Send an animation frame to the widgets which requested one, as if `duration`
+    /// had passed since the previous frame.
+    ///
+    /// The first frame of an animation always has an interval of zero.
+    pub fn animate(&mut self, duration: Duration) {
+        self.render_root.advance_clock(duration);
+        self.process_window_event(WindowEvent::AnimFrame);
+    }
+
+    /// Move the clock widgets measure time with forward by `duration`, without
+    /// sending an animation frame.
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.render_root.advance_clock(duration);
+    }
+
+    /// Give the widgets which requested idle work a generous budget to do it, as if
+    /// the app had run out of events.
+    ///
+    /// Returns whether any of them requested more.
+    pub fn run_idle_work(&mut self) -> bool {
+        self.render_root.run_idle_work(Duration::from_secs(1));
+        self.process_state_after_event();
+        self.render_root.wants_idle_work()
+    }
+
     #[cfg(FALSE)]
     /// Simulate the passage of time.
     ///
@@ -426,9 +692,39 @@ impl TestHarness {
 
     // --- Getters ---
 
+    #[cfg(test)]
+    pub(crate) fn gpu_resources(&self) -> &crate::gpu::GpuResources {
+        &self.render_root.state.gpu_resources
+    }
+
+    #[cfg(test)]
+    /// Run the paint pass without rendering, so that the content of
+    /// [`CachePaint`](crate::widget::CachePaint) widgets isn't drawn to images.
+    pub(crate) fn redraw(&mut self) {
+        let _ = self.render_root.redraw();
+    }
+
+    #[cfg(test)]
+    /// Draw the content of [`CachePaint`](crate::widget::CachePaint) widgets which
+    /// changed to images with `rasterize`, instead of with the GPU.
+    pub(crate) fn fill_raster_cache(
+        &mut self,
+        rasterize: impl FnMut(&vello::Scene, (u32, u32)) -> Option<vello::peniko::Image>,
+    ) {
+        self.render_root.fill_raster_cache(rasterize);
+        self.process_state_after_event();
+    }
+
+    #[cfg(test)]
+    /// The widgets whose overlays were kept from the last paint pass, in paint order.
+    pub(crate) fn overlay_owners(&self) -> Vec<WidgetId> {
+        let overlays = &self.render_root.state.overlays;
+        overlays.iter().map(|overlay| overlay.owner).collect()
+    }
+
     /// Return the root widget.
     pub fn root_widget(&self) -> WidgetRef<'_, dyn Widget> {
-        self.render_root.root.as_dyn()
+        self.render_root.root_widget()
     }
 
     /// Return the widget with the given id.
@@ -437,14 +733,28 @@ impl TestHarness {
     ///
     /// Panics if no Widget with this id can be found.
     pub fn get_widget(&self, id: WidgetId) -> WidgetRef<'_, dyn Widget> {
-        self.root_widget()
-            .find_widget_by_id(id)
-            .expect("could not find widget")
+        self.try_get_widget(id).expect("could not find widget")
     }
 
     /// Try to return the widget with the given id.
     pub fn try_get_widget(&self, id: WidgetId) -> Option<WidgetRef<'_, dyn Widget>> {
-        self.root_widget().find_widget_by_id(id)
+        let node = self.render_root.widget_arena.find(id)?;
+        Some(WidgetRef::from_node(node))
+    }
+
+    /// Return the id of the parent of the widget with the given id.
+    ///
+    /// Returns `None` for the root widget, and for widgets which aren't in the tree.
+    pub fn get_parent_id(&self, id: WidgetId) -> Option<WidgetId> {
+        self.render_root.widget_arena.parent_of(id)
+    }
+
+    /// Return the ids of the children of the widget with the given id, in order.
+    pub fn get_children_ids(&self, id: WidgetId) -> Vec<WidgetId> {
+        match self.render_root.widget_arena.find(id) {
+            Some(node) => node.children.iter().map(|child| child.id).collect(),
+            None => Vec::new(),
+        }
     }
 
     // TODO - link to focus documentation.
@@ -454,6 +764,71 @@ impl TestHarness {
             .find_widget_by_id(self.render_root.state.focused_widget?)
     }
 
+    /// The cursor shown for the last pointer event.
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.render_root.cursor_icon()
+    }
+
+    /// The kind of text the focused widget takes, as forwarded to the platform.
+    pub fn input_hint(&self) -> Option<InputHint> {
+        self.render_root.input_hint()
+    }
+
+    /// Check that the widget tree and the harness agree on its state, such as after
+    /// a sequence of random events in a fuzz test.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a widget's flags weren't cleared by the passes, if the focused
+    /// widget isn't in the tree, if the children of a widget in the tree aren't the
+    /// ones it holds, or if a hot widget isn't on a path of hot widgets from the
+    /// root, which is where pointer events are sent.
+    pub fn check_invariants(&self) {
+        fn check(harness: &TestHarness, widget: WidgetRef<'_, dyn Widget>) {
+            let children = widget.children();
+            let child_ids = widget.deref().children_ids().to_vec();
+            assert_eq!(
+                harness.get_children_ids(widget.id()),
+                child_ids,
+                "widget '{}' #{} has the wrong recorded children",
+                widget.deref().short_type_name(),
+                widget.id().to_raw(),
+            );
+            for child in children {
+                assert_eq!(
+                    harness.get_parent_id(child.id()),
+                    Some(widget.id()),
+                    "widget '{}' #{} has the wrong parent",
+                    child.deref().short_type_name(),
+                    child.id().to_raw(),
+                );
+                assert!(
+                    !child.state().is_hot || widget.state().is_hot,
+                    "widget '{}' #{} is hot, but its parent isn't",
+                    child.deref().short_type_name(),
+                    child.id().to_raw(),
+                );
+                check(harness, child);
+            }
+        }
+
+        self.root_widget().debug_validate(false);
+        if let Some(focused) = self.render_root.state.focused_widget {
+            assert!(
+                self.try_get_widget(focused).is_some(),
+                "focused widget #{} is not in the tree",
+                focused.to_raw()
+            );
+        }
+        if self.render_root.last_mouse_pos.is_none() {
+            assert!(
+                !self.root_widget().state().is_hot,
+                "the root is hot without a pointer in the window"
+            );
+        }
+        check(self, self.root_widget());
+    }
+
     /// Call the provided visitor on every widget in the widget tree.
     pub fn inspect_widgets(&mut self, f: impl Fn(WidgetRef<'_, dyn Widget>) + 'static) {
         fn inspect(
@@ -461,7 +836,7 @@ impl TestHarness {
             f: &(impl Fn(WidgetRef<'_, dyn Widget>) + 'static),
         ) {
             f(widget);
-            for child in widget.deref().children() {
+            for child in widget.children() {
                 inspect(child, f);
             }
         }
@@ -495,6 +870,18 @@ impl TestHarness {
         }
     }
 
+    /// Pop the oldest feedback requested by a widget, such as a sound.
+    pub fn pop_feedback(&mut self) -> Option<Feedback> {
+        let signal = self
+            .render_root
+            .pop_signal_matching(|signal| matches!(signal, RenderRootSignal::Feedback(..)));
+        match signal {
+            Some(RenderRootSignal::Feedback(feedback)) => Some(feedback),
+            Some(_) => unreachable!(),
+            _ => None,
+        }
+    }
+
     // --- Screenshots ---
 
     /// Method used by [`assert_render_snapshot`]. Use the macro instead.
@@ -567,3 +954,38 @@ impl TestHarness {
         self.render_root.state.debug_logger.write_to_file(path);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::widget_ids;
+    use crate::widget::{Button, Flex, Textbox};
+
+    #[test]
+    fn invariants_hold_after_removing_focus() {
+        let [textbox_id] = widget_ids();
+        let widget = Flex::column().with_child_id(Textbox::new("Focused"), textbox_id);
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_click_on(textbox_id);
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(textbox_id));
+        harness.check_invariants();
+
+        harness.edit_root_widget(|mut root| root.downcast::<Flex>().remove_child(0));
+        harness.check_invariants();
+    }
+
+    #[test]
+    fn invariants_hold_after_removing_hot_widget() {
+        let [button_id] = widget_ids();
+        let widget = Flex::row().with_child_id(Button::new("Hovered"), button_id);
+        let mut harness = TestHarness::create(widget);
+        harness.mouse_move_to(button_id);
+        assert!(harness.get_widget(button_id).state().is_hot);
+        harness.check_invariants();
+
+        harness.edit_root_widget(|mut root| root.downcast::<Flex>().remove_child(0));
+        harness.check_invariants();
+        harness.mouse_move((1000.0, 1000.0));
+        harness.check_invariants();
+    }
+}
@@ -17,7 +17,7 @@ use std::rc::Rc;
 
 use accesskit::Role;
 use accesskit_winit::Event;
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use vello::Scene;
 
 use crate::event::{PointerEvent, TextEvent};
@@ -33,7 +33,7 @@ pub type LayoutFn<S> = dyn FnMut(&mut S, &mut LayoutCtx, &BoxConstraints) -> Siz
 pub type PaintFn<S> = dyn FnMut(&mut S, &mut PaintCtx, &mut Scene);
 pub type RoleFn<S> = dyn Fn(&S) -> Role;
 pub type AccessFn<S> = dyn FnMut(&mut S, &mut AccessCtx);
-pub type ChildrenFn<S> = dyn Fn(&S) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]>;
+pub type ChildrenFn<S> = dyn Fn(&S) -> SmallVec<[WidgetId; 16]>;
 
 #[cfg(FALSE)]
 pub const REPLACE_CHILD: Selector = Selector::new("masonry-test.replace-child");
@@ -95,6 +95,7 @@ pub enum Record {
     AE(AccessEvent),
     SC(StatusChange),
     L(LifeCycle),
+    Removed,
     Layout(Size),
     Paint,
     Access,
@@ -198,7 +199,7 @@ impl<S> ModularWidget<S> {
 
     pub fn children_fn(
         mut self,
-        children: impl Fn(&S) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> + 'static,
+        children: impl Fn(&S) -> SmallVec<[WidgetId; 16]> + 'static,
     ) -> Self {
         self.children = Some(Box::new(children));
         self
@@ -268,7 +269,7 @@ impl<S: 'static> Widget for ModularWidget<S> {
         }
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         if let Some(f) = self.children.as_ref() {
             f(&self.state)
         } else {
@@ -335,8 +336,8 @@ impl Widget for ReplaceChild {
         self.child.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        self.child.widget().children()
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
     }
 }
 
@@ -397,6 +398,11 @@ impl<W: Widget> Widget for Recorder<W> {
         self.child.lifecycle(ctx, event);
     }
 
+    fn on_removed(&mut self, ctx: &mut LifeCycleCtx) {
+        self.recording.push(Record::Removed);
+        self.child.on_removed(ctx);
+    }
+
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         let size = self.child.layout(ctx, bc);
         self.recording.push(Record::Layout(size));
@@ -417,7 +423,7 @@ impl<W: Widget> Widget for Recorder<W> {
         self.child.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        self.child.children()
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.child.children_ids()
     }
 }
@@ -5,6 +5,8 @@
 
 #![cfg(not(tarpaulin_include))]
 
+#[cfg(not(tarpaulin_include))]
+mod access_tree;
 #[cfg(not(tarpaulin_include))]
 mod harness;
 #[cfg(not(tarpaulin_include))]
@@ -14,6 +16,7 @@ mod screenshots;
 #[cfg(not(tarpaulin_include))]
 mod snapshot_utils;
 
+pub use access_tree::{AccessNode, AccessTree};
 pub use harness::{TestHarness, HARNESS_DEFAULT_SIZE};
 pub use helper_widgets::{ModularWidget, Record, Recorder, Recording, ReplaceChild, TestWidgetExt};
 
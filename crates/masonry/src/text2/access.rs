@@ -0,0 +1,213 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Describing laid out text to accessibility tools, with one node per line.
+
+use std::ops::Range;
+
+use accesskit::{NodeBuilder, NodeId, Role, TextDirection, TextPosition, TextSelection};
+use kurbo::{Point, Rect};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::selection::{Affinity, Selection};
+use super::{TextLayout, TextStorage};
+use crate::{AccessCtx, WidgetId};
+
+/// The accessibility nodes of the lines of a text.
+///
+/// Screen readers and voice control read and select text through
+/// [`Role::InlineTextBox`] nodes, which give the position of each character. These
+/// are the children of the text widget's node, and the selection is reported on the
+/// widget's node as positions in them.
+///
+/// Characters are grapheme clusters, which is what the caret moves by.
+#[derive(Default)]
+pub(crate) struct TextAccess {
+    /// The ids of the nodes of each line, kept between passes so that they
+    /// stay the same as long as there are as many lines
+    line_ids: Vec<NodeId>,
+    /// The byte range of each line, from the last accessibility pass
+    line_ranges: Vec<Range<usize>>,
+}
+
+impl TextAccess {
+    /// Add a node for each line of `layout` to the current node, and report
+    /// `selection` on it.
+    ///
+    /// `origin` is where the layout is drawn, in the widget's coordinate space.
+    pub(crate) fn build_nodes<T: TextStorage>(
+        &mut self,
+        ctx: &mut AccessCtx,
+        layout: &TextLayout<T>,
+        origin: Point,
+        selection: Option<Selection>,
+    ) {
+        self.line_ranges.clear();
+        if layout.needs_rebuild() {
+            return;
+        }
+        let text = layout.text().as_str();
+        let scale = ctx.scale_factor;
+        let origin = ctx.widget_state.window_origin() + origin.to_vec2();
+
+        for (index, line) in layout.layout().lines().enumerate() {
+            if index == self.line_ids.len() {
+                self.line_ids.push(WidgetId::next().into());
+            }
+            let range = line.text_range();
+            let metrics = line.metrics();
+            let bottom = (metrics.baseline + metrics.descent) as f64;
+            let bounds = Rect::new(
+                metrics.offset as f64,
+                bottom - metrics.size() as f64,
+                (metrics.offset + metrics.advance) as f64,
+                bottom,
+            ) + origin.to_vec2();
+
+            // Where each cluster is, in the node's coordinates, as runs and their
+            // clusters are in visual order
+            let mut clusters = Vec::new();
+            let mut x = 0.0;
+            let mut rtl = false;
+            for run in line.runs() {
+                rtl |= run.is_rtl();
+                for cluster in run.visual_clusters() {
+                    clusters.push((cluster.text_range(), x, cluster.advance()));
+                    x += cluster.advance();
+                }
+            }
+
+            let line_text = &text[range.clone()];
+            let mut lengths = Vec::new();
+            let mut positions = Vec::new();
+            let mut widths = Vec::new();
+            for (start, grapheme) in line_text.grapheme_indices(true) {
+                let start = range.start + start;
+                let end = start + grapheme.len();
+                let of_grapheme = || {
+                    clusters
+                        .iter()
+                        .filter(|(cluster, _, _)| start <= cluster.start && cluster.start < end)
+                };
+                let position = of_grapheme().map(|(_, x, _)| *x).reduce(f32::min);
+                let width: f32 = of_grapheme().map(|(_, _, advance)| advance).sum();
+                lengths.push(grapheme.len().min(u8::MAX as usize) as u8);
+                positions.push(position.unwrap_or(x) * scale as f32);
+                widths.push(width * scale as f32);
+            }
+
+            let mut node = NodeBuilder::new(Role::InlineTextBox);
+            node.set_bounds(accesskit::Rect {
+                x0: bounds.x0 * scale,
+                y0: bounds.y0 * scale,
+                x1: bounds.x1 * scale,
+                y1: bounds.y1 * scale,
+            });
+            node.set_value(line_text);
+            node.set_text_direction(if rtl {
+                TextDirection::RightToLeft
+            } else {
+                TextDirection::LeftToRight
+            });
+            node.set_word_lengths(word_lengths(line_text));
+            node.set_character_lengths(lengths);
+            node.set_character_positions(positions);
+            node.set_character_widths(widths);
+            ctx.push_child_node(self.line_ids[index], node);
+            self.line_ranges.push(range);
+        }
+        self.line_ids.truncate(self.line_ranges.len());
+
+        if let Some(selection) = selection {
+            let anchor = self.position(text, selection.anchor);
+            let focus = self.position(text, selection.active);
+            if let (Some(anchor), Some(focus)) = (anchor, focus) {
+                ctx.current_node()
+                    .set_text_selection(TextSelection { anchor, focus });
+            }
+        }
+    }
+
+    /// The position of the byte `offset` of `text`, in the nodes of the last pass.
+    fn position(&self, text: &str, offset: usize) -> Option<TextPosition> {
+        let last = self.line_ranges.len().checked_sub(1)?;
+        let line = self
+            .line_ranges
+            .iter()
+            .position(|range| offset < range.end)
+            .unwrap_or(last);
+        let range = &self.line_ranges[line];
+        let before = text.get(range.start..offset.clamp(range.start, range.end))?;
+        Some(TextPosition {
+            node: self.line_ids[line],
+            character_index: before.graphemes(true).count(),
+        })
+    }
+
+    /// The byte offset in `text` of a position in the nodes of the last pass.
+    fn offset(&self, text: &str, position: TextPosition) -> Option<usize> {
+        let line = self.line_ids.iter().position(|id| *id == position.node)?;
+        let range = self.line_ranges.get(line)?;
+        let line_text = text.get(range.clone())?;
+        let offset = line_text
+            .grapheme_indices(true)
+            .nth(position.character_index)
+            .map_or(line_text.len(), |(offset, _)| offset);
+        Some(range.start + offset)
+    }
+
+    /// The selection requested by an accessibility tool, if it is in this text.
+    pub(crate) fn selection(&self, text: &str, selection: &TextSelection) -> Option<Selection> {
+        let anchor = self.offset(text, selection.anchor)?;
+        let active = self.offset(text, selection.focus)?;
+        Some(Selection::new(anchor, active, Affinity::Downstream))
+    }
+}
+
+/// The length of each word of `text` in grapheme clusters, where trailing whitespace
+/// belongs to the word before it.
+fn word_lengths(text: &str) -> Vec<u8> {
+    let mut lengths: Vec<u8> = Vec::new();
+    for segment in text.split_word_bounds() {
+        let length = segment.graphemes(true).count().min(u8::MAX as usize) as u8;
+        match lengths.last_mut() {
+            Some(last) if segment.trim().is_empty() => *last = last.saturating_add(length),
+            _ => lengths.push(length),
+        }
+    }
+    lengths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_map_to_characters_of_lines() {
+        let text = "héllo wörld\nsecond";
+        let access = TextAccess {
+            line_ids: vec![NodeId(100), NodeId(101)],
+            line_ranges: vec![0..14, 14..20],
+        };
+        // "ö" is two bytes, but one character
+        let position = access.position(text, 10).unwrap();
+        assert_eq!((position.node, position.character_index), (NodeId(100), 8));
+        // The caret before the newline is on the newline
+        let position = access.position(text, 13).unwrap();
+        assert_eq!((position.node, position.character_index), (NodeId(100), 11));
+        let end = access.position(text, text.len()).unwrap();
+        assert_eq!((end.node, end.character_index), (NodeId(101), 6));
+
+        let selection = TextSelection {
+            anchor: TextPosition {
+                node: NodeId(100),
+                character_index: 8,
+            },
+            focus: end,
+        };
+        let selection = access.selection(text, &selection).unwrap();
+        assert_eq!((selection.anchor, selection.active), (10, text.len()));
+
+        assert_eq!(word_lengths("héllo  wörld!"), [7, 5, 1]);
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Text being edited, indexed by line, with a record of its recent changes.
+
+use std::collections::VecDeque;
+use std::ops::{Deref, Range};
+
+use super::{EditableText, TextStorage};
+
+/// How many changes a [`Document`] remembers for [`Document::changes_since`].
+const MAX_CHANGES: usize = 256;
+
+/// The text of an editor, such as a [`Textbox`](crate::widget::Textbox).
+///
+/// Besides the text, a document keeps the offsets of the start of each line, so
+/// that finding a line or the line of an offset doesn't need to scan the text,
+/// and a record of its recent changes, so that app code which mirrors the text,
+/// such as a syntax highlighter, can update only what changed.
+///
+/// The text itself is kept in a single string, since it is laid out as a whole.
+/// Edits update the line index in place, rather than rebuilding it.
+#[derive(Clone, Debug)]
+pub struct Document {
+    text: String,
+    /// The byte offset of the start of each line; the first line starts at 0
+    line_starts: Vec<usize>,
+    revision: u64,
+    /// The most recent changes, oldest first
+    changes: VecDeque<DocumentChange>,
+}
+
+/// An edit of a [`Document`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocumentChange {
+    /// The byte range which was replaced, in the text before the change.
+    pub range: Range<usize>,
+    /// The length in bytes of the text which replaced it.
+    pub inserted_len: usize,
+    /// The revision of the document after the change.
+    pub revision: u64,
+}
+
+impl Document {
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let line_starts = std::iter::once(0).chain(newline_ends(&text, 0)).collect();
+        Document {
+            text,
+            line_starts,
+            revision: 0,
+            changes: VecDeque::new(),
+        }
+    }
+
+    /// A number which is incremented by every edit.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// The number of lines, counting the line after a trailing newline.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The byte range of line `line`, without its newline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line` is not less than [`line_count`](Self::line_count).
+    pub fn line_range(&self, line: usize) -> Range<usize> {
+        let start = self.line_starts[line];
+        let end = match self.line_starts.get(line + 1) {
+            Some(next_start) => next_start - 1,
+            None => self.text.len(),
+        };
+        start..end
+    }
+
+    /// The text of line `line`, without its newline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line` is not less than [`line_count`](Self::line_count).
+    pub fn line(&self, line: usize) -> &str {
+        &self.text[self.line_range(line)]
+    }
+
+    /// The line which contains the byte `offset`.
+    ///
+    /// A newline belongs to the line it ends, and offsets past the end of the text
+    /// are on the last line.
+    pub fn line_of_offset(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset) - 1
+    }
+
+    /// Replace the text in `range` with `new`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ends of `range` are not on character boundaries.
+    pub fn replace(&mut self, range: Range<usize>, new: &str) {
+        self.text.replace_range(range.clone(), new);
+
+        // Drop the lines which started inside the replaced text, shift the ones
+        // after it, and add the lines started by the new text
+        let delta = new.len() as isize - range.len() as isize;
+        let first_removed = self.line_starts.partition_point(|s| *s <= range.start);
+        let first_kept = self.line_starts.partition_point(|s| *s <= range.end);
+        for start in &mut self.line_starts[first_kept..] {
+            *start = (*start as isize + delta) as usize;
+        }
+        self.line_starts.splice(
+            first_removed..first_kept,
+            newline_ends(new, range.start).collect::<Vec<_>>(),
+        );
+
+        self.revision += 1;
+        if self.changes.len() == MAX_CHANGES {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(DocumentChange {
+            range,
+            inserted_len: new.len(),
+            revision: self.revision,
+        });
+    }
+
+    /// The changes made after `revision`, oldest first.
+    ///
+    /// Returns `None` if the document doesn't remember all of them, as it only keeps
+    /// the most recent ones; the caller should then start over from the whole text.
+    pub fn changes_since(&self, revision: u64) -> Option<impl Iterator<Item = &DocumentChange>> {
+        if revision > self.revision {
+            return None;
+        }
+        let missing = self.revision - revision;
+        if missing > self.changes.len() as u64 {
+            return None;
+        }
+        Some(
+            self.changes
+                .iter()
+                .skip(self.changes.len() - missing as usize),
+        )
+    }
+}
+
+/// The offsets just after each newline in `text`, which starts at `offset`.
+fn newline_ends(text: &str, offset: usize) -> impl Iterator<Item = usize> + '_ {
+    text.match_indices('\n')
+        .map(move |(index, _)| offset + index + 1)
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Document::new(String::new())
+    }
+}
+
+impl From<String> for Document {
+    fn from(text: String) -> Self {
+        Document::new(text)
+    }
+}
+
+impl From<&str> for Document {
+    fn from(text: &str) -> Self {
+        Document::new(text)
+    }
+}
+
+impl Deref for Document {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl TextStorage for Document {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn maybe_eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl EditableText for Document {
+    fn edit(&mut self, range: Range<usize>, new: impl Into<String>) {
+        self.replace(range, &new.into());
+    }
+
+    fn from_str(s: &str) -> Self {
+        Document::new(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_kept_up_to_date() {
+        let mut document = Document::new("one\ntwo\nthree");
+        assert_eq!(document.line_count(), 3);
+        assert_eq!(document.line(1), "two");
+        assert_eq!(document.line_of_offset(3), 0);
+        assert_eq!(document.line_of_offset(4), 1);
+
+        let edits = [(4..7, "2\nand 2.5"), (0..0, "zero\n"), (3..13, "")];
+        for (range, new) in edits {
+            document.replace(range, new);
+            // Edits give the same index as building it from scratch
+            let expected = Document::new(document.as_str());
+            assert_eq!(document.line_starts, expected.line_starts, "{document:?}");
+        }
+        assert_eq!(document.as_str(), "zerd 2.5\nthree");
+        assert_eq!(document.line(0), "zerd 2.5");
+        assert_eq!(document.line_count(), 2);
+
+        let changes: Vec<_> = document.changes_since(1).unwrap().collect();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].range, 3..13);
+        assert_eq!(changes[1].revision, 3);
+        assert!(document.changes_since(4).is_none());
+        for _ in 0..MAX_CHANGES {
+            document.replace(0..0, "x");
+        }
+        assert!(document.changes_since(0).is_none());
+        assert_eq!(document.changes_since(3).unwrap().count(), MAX_CHANGES);
+    }
+}
@@ -11,7 +11,12 @@ use winit::{
     keyboard::{Key, NamedKey},
 };
 
-use crate::{event::PointerState, Action, EventCtx, Handled, TextEvent};
+use crate::{
+    conventions::PlatformConventions,
+    event::PointerState,
+    keymap::{CaretMovement, EditAction},
+    Action, EventCtx, Handled, TextEvent,
+};
 
 use super::{
     selection::{Affinity, Selection},
@@ -92,169 +97,140 @@ impl<T: EditableText> TextEditor<T> {
         origin: Point,
         state: &PointerState,
         button: MouseButton,
+        conventions: &PlatformConventions,
     ) -> bool {
         // TODO: If we have a selection and we're hovering over it,
         // implement (optional?) click and drag
-        self.inner.pointer_down(origin, state, button)
+        self.inner.pointer_down(origin, state, button, conventions)
+    }
+
+    /// Delete each selection or, where it is a caret, the text between it and the end
+    /// of `movement`.
+    fn delete(&mut self, ctx: &mut EventCtx, movement: CaretMovement) -> Handled {
+        let Some(selection) = self.inner.selection else {
+            return Handled::No;
+        };
+        // TODO: more specific behavior may sometimes be warranted here
+        //       because whole EGCs are more coarse than what people expect
+        //       to be able to delete individual indic grapheme cluster
+        //       components among other things.
+        let ranges = self
+            .inner
+            .selections()
+            .map(|selection| {
+                if selection.is_caret() {
+                    let target = movement.target(self.text(), selection.active);
+                    target.min(selection.active)..target.max(selection.active)
+                } else {
+                    selection.range()
+                }
+            })
+            .collect();
+        if self.replace_ranges(ranges, "", selection.active_affinity) {
+            let contents = self.text().as_str().to_string();
+            ctx.submit_action(Action::TextChanged(contents));
+        }
+        Handled::Yes
+    }
+
+    /// Replace each selection with `new`, or insert it at the start if there is no
+    /// selection, as if it was typed.
+    pub fn insert(&mut self, ctx: &mut EventCtx, new: &str) -> Handled {
+        let mut ranges: Vec<_> = self.inner.selections().map(|s| s.range()).collect();
+        if ranges.is_empty() {
+            ranges.push(0..0);
+        }
+        // We have just added this text, so we are "affined" with it
+        self.replace_ranges(ranges, new, Affinity::Downstream);
+        let contents = self.text().as_str().to_string();
+        ctx.submit_action(Action::TextChanged(contents));
+        Handled::Yes
+    }
+
+    /// Replace each of `ranges` with `new`, and put a caret after each replacement.
+    ///
+    /// The first range is that of the primary selection. Ranges which overlap are
+    /// merged. Returns whether the text changed.
+    fn replace_ranges(
+        &mut self,
+        mut ranges: Vec<Range<usize>>,
+        new: &str,
+        affinity: Affinity,
+    ) -> bool {
+        let Some(primary) = ranges.first().cloned() else {
+            return false;
+        };
+        ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start < last.end || range.start == last.start => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        let changed = !new.is_empty() || merged.iter().any(|range| !range.is_empty());
+        if changed {
+            // Edit from the end, so that the earlier ranges stay valid
+            for range in merged.iter().rev() {
+                self.text_mut().edit(range.clone(), new);
+            }
+        }
+        // Each caret is moved by the edits before it
+        let mut shift = 0_isize;
+        let mut carets = Vec::with_capacity(merged.len());
+        for range in &merged {
+            let start = (range.start as isize + shift) as usize;
+            carets.push(Selection::caret(start + new.len(), affinity));
+            shift += new.len() as isize - range.len() as isize;
+        }
+        let primary_index = merged
+            .iter()
+            .position(|range| range.start <= primary.start && primary.start <= range.end)
+            .unwrap_or(0);
+        let primary = carets.remove(primary_index);
+        self.inner
+            .set_selections(std::iter::once(primary).chain(carets));
+        changed
     }
 
     pub fn text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) -> Handled {
-        let inner_handled = self.inner.text_event(event);
+        let inner_handled = self.inner.text_event(ctx.keymap(), event);
         if inner_handled.is_handled() {
             return inner_handled;
         }
         match event {
             TextEvent::KeyboardKey(event, mods) if event.state.is_pressed() => {
+                let action = self.inner.key_action(ctx.keymap(), event, *mods);
+                if let Some(EditAction::Delete(movement)) = action {
+                    return self.delete(ctx, movement);
+                }
                 // We don't input actual text when these keys are pressed
                 if !(mods.control_key() || mods.alt_key() || mods.super_key()) {
                     match &event.logical_key {
-                        Key::Named(NamedKey::Backspace) => {
-                            if let Some(selection) = self.inner.selection {
-                                if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
-                                    self.inner.selection =
-                                        Some(Selection::caret(selection.min(), Affinity::Upstream));
-                                } else {
-                                    // TODO: more specific behavior may sometimes be warranted here
-                                    //       because whole EGCs are more coarse than what people expect
-                                    //       to be able to delete individual indic grapheme cluster
-                                    //       components among other things.
-                                    let offset = self
-                                        .text()
-                                        .prev_grapheme_offset(selection.active)
-                                        .unwrap_or(0);
-                                    self.text_mut().edit(offset..selection.active, "");
-                                    self.inner.selection =
-                                        Some(Selection::caret(offset, selection.active_affinity));
-                                }
-                                Handled::Yes
-                            } else {
-                                Handled::No
-                            }
-                        }
-                        Key::Named(NamedKey::Delete) => {
-                            if let Some(selection) = self.inner.selection {
-                                if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
-                                    self.inner.selection = Some(Selection::caret(
-                                        selection.min(),
-                                        Affinity::Downstream,
-                                    ));
-                                } else if let Some(offset) =
-                                    self.text().next_grapheme_offset(selection.active)
-                                {
-                                    self.text_mut().edit(selection.min()..offset, "");
-                                    self.inner.selection = Some(Selection::caret(
-                                        selection.min(),
-                                        selection.active_affinity,
-                                    ));
-                                }
-                                Handled::Yes
-                            } else {
-                                Handled::No
-                            }
-                        }
-                        Key::Named(NamedKey::Space) => {
-                            let selection = self.inner.selection.unwrap_or(Selection {
-                                anchor: 0,
-                                active: 0,
-                                active_affinity: Affinity::Downstream,
-                                h_pos: None,
-                            });
-                            let c = ' ';
-                            self.text_mut().edit(selection.range(), c);
-                            self.inner.selection = Some(Selection::caret(
-                                selection.min() + c.len_utf8(),
-                                // We have just added this character, so we are "affined" with it
-                                Affinity::Downstream,
-                            ));
-                            let contents = self.text().as_str().to_string();
-                            ctx.submit_action(Action::TextChanged(contents));
-                            Handled::Yes
-                        }
+                        Key::Named(NamedKey::Space) => self.insert(ctx, " "),
                         Key::Named(NamedKey::Enter) => {
                             let contents = self.text().as_str().to_string();
                             ctx.submit_action(Action::TextEntered(contents));
                             Handled::Yes
                         }
                         Key::Named(_) => Handled::No,
-                        Key::Character(c) => {
-                            let selection = self.inner.selection.unwrap_or(Selection {
-                                anchor: 0,
-                                active: 0,
-                                active_affinity: Affinity::Downstream,
-                                h_pos: None,
-                            });
-                            self.text_mut().edit(selection.range(), &**c);
-                            self.inner.selection = Some(Selection::caret(
-                                selection.min() + c.len(),
-                                // We have just added this character, so we are "affined" with it
-                                Affinity::Downstream,
-                            ));
-                            let contents = self.text().as_str().to_string();
-                            ctx.submit_action(Action::TextChanged(contents));
-                            Handled::Yes
-                        }
+                        Key::Character(c) => self.insert(ctx, c),
                         Key::Unidentified(_) => Handled::No,
                         Key::Dead(d) => {
                             eprintln!("Got dead key {d:?}. Will handle");
                             Handled::No
                         }
                     }
-                } else if mods.control_key() || mods.super_key()
-                // TODO: do things differently on mac, rather than capturing both super and control.
-                {
-                    match &event.logical_key {
-                        Key::Named(NamedKey::Backspace) => {
-                            if let Some(selection) = self.inner.selection {
-                                if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
-                                    self.inner.selection =
-                                        Some(Selection::caret(selection.min(), Affinity::Upstream));
-                                }
-                                let offset =
-                                    self.text().prev_word_offset(selection.active).unwrap_or(0);
-                                self.text_mut().edit(offset..selection.active, "");
-                                self.inner.selection =
-                                    Some(Selection::caret(offset, Affinity::Upstream));
-
-                                let contents = self.text().as_str().to_string();
-                                ctx.submit_action(Action::TextChanged(contents));
-                                Handled::Yes
-                            } else {
-                                Handled::No
-                            }
-                        }
-                        Key::Named(NamedKey::Delete) => {
-                            if let Some(selection) = self.inner.selection {
-                                if !selection.is_caret() {
-                                    self.text_mut().edit(selection.range(), "");
-                                    self.inner.selection = Some(Selection::caret(
-                                        selection.min(),
-                                        Affinity::Downstream,
-                                    ));
-                                } else if let Some(offset) =
-                                    self.text().next_word_offset(selection.active)
-                                {
-                                    self.text_mut().edit(selection.active..offset, "");
-                                    self.inner.selection =
-                                        Some(Selection::caret(selection.min(), Affinity::Upstream));
-                                }
-                                let contents = self.text().as_str().to_string();
-                                ctx.submit_action(Action::TextChanged(contents));
-                                Handled::Yes
-                            } else {
-                                Handled::No
-                            }
-                        }
-                        _ => Handled::No,
-                    }
                 } else {
                     Handled::No
                 }
             }
             TextEvent::KeyboardKey(_, _) => Handled::No,
             TextEvent::Ime(ime) => match ime {
+                Ime::Commit(text) if self.preedit_range.is_none() => self.insert(ctx, text),
                 Ime::Commit(text) => {
                     if let Some(preedit) = self
                         .preedit_range
@@ -287,6 +263,8 @@ impl<T: EditableText> TextEditor<T> {
                             Some(Selection::caret(np.end, Affinity::Upstream))
                         };
                     } else {
+                        // Composition only happens at the primary caret
+                        self.inner.collapse_selections();
                         let sr = self.selection.map(|x| x.range()).unwrap_or(0..0);
                         self.text_mut().edit(sr.clone(), preedit_string);
                         let np = sr.start..(sr.start + preedit_string.len());
@@ -0,0 +1,67 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Searching text for the matches of a find query.
+
+use std::ops::Range;
+
+/// The byte ranges of the matches of `query` in `text`, ignoring case.
+///
+/// Matches don't overlap; each one starts after the end of the previous one.
+pub(crate) fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    let mut search_from = 0;
+    for (start, _) in text.char_indices() {
+        if start < search_from {
+            continue;
+        }
+        if let Some(end) = match_end(text, start, &query) {
+            matches.push(start..end);
+            search_from = end;
+        }
+    }
+    matches
+}
+
+/// The end of the match of `query` starting at `start`, if there is one.
+///
+/// Characters are compared by their lowercase forms, and a match has to end on a
+/// character boundary of `text`.
+fn match_end(text: &str, start: usize, query: &[char]) -> Option<usize> {
+    let mut remaining = query;
+    for (offset, c) in text[start..].char_indices() {
+        for lower in c.to_lowercase() {
+            let (first, rest) = remaining.split_first()?;
+            if *first != lower {
+                return None;
+            }
+            remaining = rest;
+        }
+        if remaining.is_empty() {
+            return Some(start + offset + c.len_utf8());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_ignore_case() {
+        let text = "Cat, cAT and catcat — Ünïcode ünïcode";
+        let matches = find_matches(text, "cat");
+        let found: Vec<_> = matches.iter().map(|range| &text[range.clone()]).collect();
+        assert_eq!(found, ["Cat", "cAT", "cat", "cat"]);
+        assert_eq!(find_matches(text, "ÜNÏ").len(), 2);
+        // Matches don't overlap
+        assert_eq!(find_matches("aaaa", "aa"), [0..2, 2..4]);
+        assert!(find_matches(text, "").is_empty());
+        assert!(find_matches(text, "dog").is_empty());
+    }
+}
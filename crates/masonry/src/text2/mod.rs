@@ -16,12 +16,20 @@ pub use store::{Link, TextStorage};
 mod layout;
 pub use layout::{LayoutMetrics, TextBrush, TextLayout};
 
+mod find;
+
+mod access;
+
 mod selection;
 pub use selection::{
-    len_utf8_from_first_byte, EditableTextCursor, Selectable, StringCursor, TextWithSelection,
+    len_utf8_from_first_byte, Affinity, EditableTextCursor, Selectable, Selection, StringCursor,
+    TextWithSelection,
 };
 
 // mod movement;
 
 mod edit;
 pub use edit::{EditableText, TextEditor};
+
+mod document;
+pub use document::{Document, DocumentChange};
@@ -5,32 +5,59 @@
 
 use std::borrow::Cow;
 use std::ops::{Deref, DerefMut, Range};
+use std::time::Duration;
 
-use kurbo::{Affine, Line, Point, Stroke};
+use kurbo::{Affine, Line, Point, Rect, Stroke};
 use parley::context::RangedBuilder;
 use parley::FontContext;
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 use vello::peniko::{Brush, Color};
 use vello::Scene;
-use winit::event::MouseButton;
-use winit::keyboard::NamedKey;
+use winit::event::{KeyEvent, MouseButton};
+use winit::keyboard::ModifiersState;
 use winit::platform::modifier_supplement::KeyEventExtModifierSupplement;
 
+use crate::conventions::PlatformConventions;
 use crate::event::PointerState;
-use crate::{Handled, TextEvent};
+use crate::keymap::{CaretMovement, EditAction, Keymap};
+use crate::{AccessCtx, Handled, TextEvent};
 
+use super::access::TextAccess;
+use super::find::find_matches;
 use super::{TextBrush, TextLayout, TextStorage};
 
+/// The width of the caret line.
+const CARET_WIDTH: f64 = 2.0;
+
 pub struct TextWithSelection<T: Selectable> {
     pub layout: TextLayout<T>,
     /// The current selection within this widget
-    // TODO: Allow multiple selections (i.e. by holding down control)
+    ///
+    /// When there are several carets, this is the primary one, which the caret
+    /// rect and IME follow.
     pub selection: Option<Selection>,
+    /// The carets added besides the primary one, with Ctrl+click or a column selection
+    extra_selections: Vec<Selection>,
+    /// Where an Alt+drag column selection started, relative to the layout
+    column_anchor: Option<Point>,
     highlight_brush: TextBrush,
     needs_selection_update: bool,
     selecting_with_mouse: bool,
     // TODO: Cache cursor line, selection boxes
     cursor_line: Option<Line>,
+    /// Whether the caret is drawn at all, see [`Self::set_caret_visible`]
+    show_caret: bool,
+    /// Whether the caret is in the hidden half of its blink
+    caret_blinked_out: bool,
+    /// The time since the caret was last shown or hidden by blinking
+    since_caret_blink: Duration,
+    /// Overrides the app's keymap for this text
+    keymap: Option<Keymap>,
+    find_query: Option<String>,
+    find_matches: Vec<Range<usize>>,
+    current_match: Option<usize>,
+    find_brush: TextBrush,
+    access: TextAccess,
 }
 
 impl<T: Selectable> TextWithSelection<T> {
@@ -38,20 +65,247 @@ impl<T: Selectable> TextWithSelection<T> {
         Self {
             layout: TextLayout::new(text, text_size),
             selection: None,
+            extra_selections: Vec::new(),
+            column_anchor: None,
             needs_selection_update: false,
             selecting_with_mouse: false,
             cursor_line: None,
+            show_caret: true,
+            caret_blinked_out: false,
+            since_caret_blink: Duration::ZERO,
+            keymap: None,
             highlight_brush: TextBrush::Highlight {
                 text: Color::WHITE.into(),
                 fill: Color::LIGHT_BLUE.into(),
             },
+            find_query: None,
+            find_matches: Vec::new(),
+            current_match: None,
+            find_brush: TextBrush::Highlight {
+                text: Color::WHITE.into(),
+                fill: crate::theme::FIND_MATCH_COLOR.into(),
+            },
+            access: TextAccess::default(),
         }
     }
 
     pub fn set_text(&mut self, text: T) {
         self.selection = None;
+        self.extra_selections.clear();
         self.needs_selection_update = true;
         self.layout.set_text(text);
+        self.current_match = None;
+        self.update_find_matches();
+    }
+
+    /// Highlight the matches of `query`, ignoring case, and select the first one
+    /// after the selection. Returns the number of matches.
+    ///
+    /// The matches are kept up to date as the text changes, until
+    /// [`clear_find`](Self::clear_find) is called. An empty query clears them.
+    pub fn find(&mut self, query: &str) -> usize {
+        if query.is_empty() {
+            self.clear_find();
+            return 0;
+        }
+        self.find_query = Some(query.to_string());
+        self.update_find_matches();
+        let from = self.selection.map_or(0, |selection| selection.min());
+        let first = self
+            .find_matches
+            .iter()
+            .position(|range| range.start >= from)
+            .or((!self.find_matches.is_empty()).then_some(0));
+        self.select_match(first);
+        self.find_matches.len()
+    }
+
+    /// Select the next match of the find query, or the previous one if `forward` is
+    /// false, wrapping around at the ends. Returns the index of the selected match.
+    pub fn find_next(&mut self, forward: bool) -> Option<usize> {
+        let count = self.find_matches.len();
+        if count == 0 {
+            return None;
+        }
+        let next = match (self.current_match, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(current), true) => (current + 1) % count,
+            (Some(current), false) => (current + count - 1) % count,
+        };
+        self.select_match(Some(next));
+        Some(next)
+    }
+
+    /// Stop highlighting the matches of the find query.
+    pub fn clear_find(&mut self) {
+        self.find_query = None;
+        self.find_matches.clear();
+        self.current_match = None;
+        self.needs_selection_update = true;
+    }
+
+    /// The byte ranges of the matches of the find query in the text.
+    pub fn find_matches(&self) -> &[Range<usize>] {
+        &self.find_matches
+    }
+
+    /// The index of the match which was last selected by [`find`](Self::find) or
+    /// [`find_next`](Self::find_next), if any.
+    pub fn current_match(&self) -> Option<usize> {
+        self.current_match
+    }
+
+    fn select_match(&mut self, index: Option<usize>) {
+        self.current_match = index;
+        if let Some(range) = index.map(|index| self.find_matches[index].clone()) {
+            self.selection = Some(Selection::new(range.start, range.end, Affinity::Upstream));
+            self.extra_selections.clear();
+        }
+        self.needs_selection_update = true;
+    }
+
+    fn update_find_matches(&mut self) {
+        let Some(query) = &self.find_query else {
+            return;
+        };
+        self.find_matches = find_matches(self.layout.text().as_str(), query);
+        if self
+            .current_match
+            .is_some_and(|current| current >= self.find_matches.len())
+        {
+            self.current_match = None;
+        }
+    }
+
+    /// Replace the selection, such as to select a word the user right-clicked.
+    ///
+    /// This drops any extra carets.
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        self.selection = selection;
+        self.extra_selections.clear();
+        self.needs_selection_update = true;
+    }
+
+    /// All the selections, starting with the primary one, and then in text order.
+    pub fn selections(&self) -> impl Iterator<Item = Selection> + '_ {
+        self.selection
+            .into_iter()
+            .chain(self.extra_selections.iter().copied())
+    }
+
+    /// Replace all the selections, where the first one is the primary one.
+    ///
+    /// Selections which overlap are merged.
+    pub fn set_selections(&mut self, selections: impl IntoIterator<Item = Selection>) {
+        let mut selections = selections.into_iter();
+        self.selection = selections.next();
+        self.extra_selections = selections.collect();
+        self.normalize_selections();
+        self.needs_selection_update = true;
+    }
+
+    /// Add a caret or selection besides the current ones, which becomes the primary one.
+    pub fn add_selection(&mut self, selection: Selection) {
+        if let Some(primary) = self.selection {
+            self.extra_selections.push(primary);
+        }
+        self.selection = Some(selection);
+        self.normalize_selections();
+        self.needs_selection_update = true;
+    }
+
+    /// Drop the extra carets, keeping only the primary one.
+    ///
+    /// Returns whether there were any.
+    pub fn collapse_selections(&mut self) -> bool {
+        if self.extra_selections.is_empty() {
+            return false;
+        }
+        self.extra_selections.clear();
+        self.needs_selection_update = true;
+        true
+    }
+
+    /// Sort the extra selections and merge the ones which overlap, or which are
+    /// carets at the same place.
+    fn normalize_selections(&mut self) {
+        let Some(primary) = self.selection else {
+            self.extra_selections.clear();
+            return;
+        };
+        let mut all: Vec<Selection> = std::iter::once(primary)
+            .chain(self.extra_selections.drain(..))
+            .collect();
+        all.sort_by_key(|selection| selection.min());
+        let mut merged: Vec<Selection> = Vec::with_capacity(all.len());
+        for selection in all {
+            match merged.last_mut() {
+                Some(last)
+                    if selection.min() < last.max()
+                        || (selection.min() == last.max()
+                            && (selection.is_caret() || last.is_caret())) =>
+                {
+                    if selection.max() > last.max() {
+                        *last =
+                            Selection::new(last.min(), selection.max(), selection.active_affinity);
+                    }
+                }
+                _ => merged.push(selection),
+            }
+        }
+        let primary_index = merged
+            .iter()
+            .position(|selection| {
+                selection.min() <= primary.active && primary.active <= selection.max()
+            })
+            .unwrap_or(0);
+        self.selection = Some(merged.remove(primary_index));
+        self.extra_selections = merged;
+    }
+
+    /// Update every selection with `f`, which is given the text and the selection.
+    fn map_selections(&mut self, f: impl Fn(&T, Selection) -> Selection) {
+        let Some(primary) = self.selection else {
+            return;
+        };
+        let text = self.layout.text();
+        self.selection = Some(f(text, primary));
+        for selection in &mut self.extra_selections {
+            *selection = f(text, *selection);
+        }
+        self.normalize_selections();
+        self.needs_selection_update = true;
+    }
+
+    /// Select the same columns on each line between `anchor` and `to`, relative to
+    /// the layout, with one selection per line.
+    fn select_columns(&mut self, anchor: Point, to: Point) {
+        let (top, bottom) = (anchor.y.min(to.y), anchor.y.max(to.y));
+        let mut selections = Vec::new();
+        for line in self.layout.layout().lines() {
+            let metrics = line.metrics();
+            let line_bottom = (metrics.baseline + metrics.descent) as f64;
+            let line_top = line_bottom - metrics.size() as f64;
+            if line_bottom <= top || line_top > bottom {
+                continue;
+            }
+            let y = (line_top + line_bottom) / 2.0;
+            let start = self.layout.cursor_for_point(Point::new(anchor.x, y));
+            let end = self.layout.cursor_for_point(Point::new(to.x, y));
+            selections.push(Selection::new(
+                start.insert_point,
+                end.insert_point,
+                Affinity::Downstream,
+            ));
+        }
+        // The line under the pointer is the primary selection
+        if to.y < anchor.y {
+            selections.reverse();
+        }
+        if let Some(primary) = selections.pop() {
+            self.set_selections(std::iter::once(primary).chain(selections));
+        }
     }
 
     pub fn needs_rebuild(&self) -> bool {
@@ -63,6 +317,7 @@ impl<T: Selectable> TextWithSelection<T> {
         origin: Point,
         state: &PointerState,
         button: MouseButton,
+        conventions: &PlatformConventions,
     ) -> bool {
         // TODO: work out which button is the primary button?
         if button == MouseButton::Left {
@@ -70,11 +325,27 @@ impl<T: Selectable> TextWithSelection<T> {
             self.needs_selection_update = true;
             // TODO: Much of this juggling seems unnecessary
             let position = Point::new(state.position.x, state.position.y) - origin;
-            let position = self
-                .layout
-                .cursor_for_point(Point::new(position.x, position.y));
+            let layout_position = Point::new(position.x, position.y);
+            let position = self.layout.cursor_for_point(layout_position);
             tracing::warn!("Got cursor point without getting affinity");
-            if state.mods.state().shift_key() {
+            let mods = state.mods.state();
+            if mods.alt_key() {
+                self.column_anchor = Some(layout_position);
+                self.set_selection(Some(Selection::caret(
+                    position.insert_point,
+                    Affinity::Downstream,
+                )));
+                return true;
+            }
+            if conventions.is_command(mods) {
+                self.add_selection(Selection::caret(
+                    position.insert_point,
+                    Affinity::Downstream,
+                ));
+                return true;
+            }
+            self.extra_selections.clear();
+            if mods.shift_key() {
                 if let Some(selection) = self.selection.as_mut() {
                     selection.active = position.insert_point;
                     selection.active_affinity = Affinity::Downstream;
@@ -94,11 +365,16 @@ impl<T: Selectable> TextWithSelection<T> {
     pub fn pointer_up(&mut self, _origin: Point, _state: &PointerState, button: MouseButton) {
         if button == MouseButton::Left {
             self.selecting_with_mouse = false;
+            self.column_anchor = None;
         }
     }
 
     pub fn pointer_move(&mut self, origin: Point, state: &PointerState) -> bool {
-        if self.selecting_with_mouse {
+        if let Some(anchor) = self.column_anchor {
+            let position = Point::new(state.position.x, state.position.y) - origin;
+            self.select_columns(anchor, Point::new(position.x, position.y));
+            true
+        } else if self.selecting_with_mouse {
             self.needs_selection_update = true;
             let position = Point::new(state.position.x, state.position.y) - origin;
             let position = self
@@ -117,77 +393,78 @@ impl<T: Selectable> TextWithSelection<T> {
         }
     }
 
-    pub fn text_event(&mut self, event: &TextEvent) -> Handled {
+    /// Handle a text event, using `keymap` unless this text has its own.
+    pub fn text_event(&mut self, keymap: &Keymap, event: &TextEvent) -> Handled {
         match event {
             TextEvent::KeyboardKey(key, mods) if key.state.is_pressed() => {
-                match key.key_without_modifiers() {
-                    winit::keyboard::Key::Named(NamedKey::ArrowLeft) => {
-                        if mods.shift_key() {
+                let Some(action) = self.key_action(keymap, key, *mods) else {
+                    return Handled::No;
+                };
+                match action {
+                    EditAction::Move(movement) => {
+                        self.map_selections(|text, selection| {
+                            let offset = match movement {
+                                // Moving by a grapheme collapses a selection to its edge
+                                CaretMovement::PrevGrapheme if !selection.is_caret() => {
+                                    selection.min()
+                                }
+                                CaretMovement::NextGrapheme if !selection.is_caret() => {
+                                    selection.max()
+                                }
+                                _ => movement.target(text, selection.active),
+                            };
+                            Selection::caret(offset, movement_affinity(movement))
+                        });
+                        Handled::Yes
+                    }
+                    EditAction::Select(movement) => {
+                        self.map_selections(|text, selection| {
+                            let offset = movement.target(text, selection.active);
+                            Selection::new(selection.anchor, offset, movement_affinity(movement))
+                        });
+                        Handled::Yes
+                    }
+                    // Deletion is handled by `TextEditor`
+                    EditAction::Delete(_) => Handled::No,
+                    EditAction::SelectAll => {
+                        self.set_selection(Some(Selection::new(
+                            0,
+                            self.text().len(),
+                            Affinity::Downstream,
+                        )));
+                        Handled::Yes
+                    }
+                    // Escape is left to the app when there is only one caret
+                    EditAction::CollapseSelections => {
+                        if self.collapse_selections() {
+                            Handled::Yes
                         } else {
-                            let t = self.text();
-                            if let Some(selection) = self.selection {
-                                if mods.control_key() {
-                                    let offset = t.prev_word_offset(selection.active).unwrap_or(0);
-                                    self.selection =
-                                        Some(Selection::caret(offset, Affinity::Downstream));
-                                } else {
-                                    let offset =
-                                        t.prev_grapheme_offset(selection.active).unwrap_or(0);
-                                    self.selection =
-                                        Some(Selection::caret(offset, Affinity::Downstream));
-                                };
-                            }
+                            Handled::No
                         }
+                    }
+                    EditAction::FindNext | EditAction::FindPrevious
+                        if self.find_query.is_some() =>
+                    {
+                        self.find_next(action == EditAction::FindNext);
                         Handled::Yes
                     }
-                    winit::keyboard::Key::Named(NamedKey::ArrowRight) => {
-                        if mods.shift_key() {
-                            // TODO: Expand selection
+                    EditAction::FindNext | EditAction::FindPrevious => Handled::No,
+                    EditAction::Copy => {
+                        let selection = self.selection.unwrap_or(Selection {
+                            anchor: 0,
+                            active: 0,
+                            active_affinity: Affinity::Downstream,
+                            h_pos: None,
+                        });
+                        // TODO: We know this is not the fullest model of copy-paste, and that we should work with the inner text
+                        // e.g. to put HTML code if supported by the rich text kind
+                        if let Some(text) = self.text().slice(selection.min()..selection.max()) {
+                            println!(r#"Copying "{text}""#);
                         } else {
-                            let t = self.text();
-                            if let Some(selection) = self.selection {
-                                if mods.control_key() {
-                                    if let Some(o) = t.next_word_offset(selection.active) {
-                                        self.selection =
-                                            Some(Selection::caret(o, Affinity::Upstream));
-                                    }
-                                } else if let Some(o) = t.next_grapheme_offset(selection.active) {
-                                    self.selection = Some(Selection::caret(o, Affinity::Upstream));
-                                };
-                            }
+                            debug_panic!("Had invalid selection");
                         }
                         Handled::Yes
                     }
-                    winit::keyboard::Key::Named(_) => Handled::No,
-                    winit::keyboard::Key::Character(chr) => match &*chr {
-                        "a" if mods.control_key() || /* macOS, yes this is a hack */ mods.super_key() =>
-                        {
-                            self.selection =
-                                Some(Selection::new(0, self.text().len(), Affinity::Downstream));
-                            self.needs_selection_update = true;
-                            Handled::Yes
-                        }
-                        "c" if mods.control_key() || mods.super_key() => {
-                            let selection = self.selection.unwrap_or(Selection {
-                                anchor: 0,
-                                active: 0,
-                                active_affinity: Affinity::Downstream,
-                                h_pos: None,
-                            });
-                            // TODO: We know this is not the fullest model of copy-paste, and that we should work with the inner text
-                            // e.g. to put HTML code if supported by the rich text kind
-                            if let Some(text) = self.text().slice(selection.min()..selection.max())
-                            {
-                                println!(r#"Copying "{text}""#);
-                            } else {
-                                debug_panic!("Had invalid selection");
-                            }
-                            Handled::Yes
-                        }
-                        _ => Handled::No,
-                    },
-                    winit::keyboard::Key::Unidentified(_) => todo!(),
-                    winit::keyboard::Key::Dead(_) => todo!(),
                 }
             }
             TextEvent::KeyboardKey(_, _) => Handled::No,
@@ -204,10 +481,118 @@ impl<T: Selectable> TextWithSelection<T> {
         }
     }
 
+    /// The editing action bound to `key`, if any.
+    ///
+    /// This uses the keymap set with [`set_keymap`](Self::set_keymap) if there
+    /// is one, and `keymap` otherwise.
+    pub fn key_action(
+        &self,
+        keymap: &Keymap,
+        key: &KeyEvent,
+        mods: ModifiersState,
+    ) -> Option<EditAction> {
+        let keymap = self.keymap.as_ref().unwrap_or(keymap);
+        keymap.action(&key.key_without_modifiers(), mods)
+    }
+
+    pub fn keymap(&self) -> Option<&Keymap> {
+        self.keymap.as_ref()
+    }
+
+    /// Set the keyboard shortcuts used to select and edit this text, instead of
+    /// the app's keymap.
+    ///
+    /// Passing `None` goes back to using the app's keymap.
+    pub fn set_keymap(&mut self, keymap: Option<Keymap>) {
+        self.keymap = keymap;
+    }
+
+    /// Show or hide the caret.
+    ///
+    /// This is for editors which control when the caret is shown themselves, such
+    /// as to hide it in a read-only mode, or to draw their own caret.
+    pub fn set_caret_visible(&mut self, visible: bool) {
+        self.show_caret = visible;
+    }
+
+    /// Whether the caret is drawn right now, taking blinking into account.
+    pub fn is_caret_visible(&self) -> bool {
+        self.show_caret && !self.caret_blinked_out && self.selection.is_some()
+    }
+
+    /// Show the caret and restart its blink, so that it stays shown for a whole
+    /// interval.
+    ///
+    /// Call this whenever the user types or moves the caret, so that the caret
+    /// doesn't blink out while they're looking for it.
+    pub fn reset_caret_blink(&mut self) {
+        self.caret_blinked_out = false;
+        self.since_caret_blink = Duration::ZERO;
+    }
+
+    /// Advance the caret's blink by `elapsed`, where `interval` is how long it is
+    /// shown and then hidden, usually the app's
+    /// [`caret_blink_interval`](crate::InteractionSettings::caret_blink_interval).
+    ///
+    /// Returns whether the caret was shown or hidden, and so needs to be repainted.
+    pub fn blink_caret(&mut self, elapsed: Duration, interval: Duration) -> bool {
+        let was_blinked_out = self.caret_blinked_out;
+        if interval.is_zero() {
+            self.reset_caret_blink();
+        } else {
+            self.since_caret_blink += elapsed;
+            while self.since_caret_blink >= interval {
+                self.since_caret_blink -= interval;
+                self.caret_blinked_out = !self.caret_blinked_out;
+            }
+        }
+        was_blinked_out != self.caret_blinked_out
+    }
+
+    /// The bounds of the caret, relative to the layout's origin.
+    ///
+    /// This is where IMEs show their candidate window, and where screen readers
+    /// and magnifiers follow the text being edited. It is `None` if there is no
+    /// selection, or if the layout needs to be rebuilt. The caret is at the
+    /// active end of the selection, whether or not it is shown right now.
+    pub fn caret_rect(&self) -> Option<Rect> {
+        let selection = self.selection?;
+        if self.layout.needs_rebuild() {
+            return None;
+        }
+        let line = self.layout.cursor_line_for_text_position(selection.active);
+        let rect = Rect::from_points(line.p0, line.p1);
+        Some(rect.inflate(CARET_WIDTH / 2.0, 0.0))
+    }
+
+    /// Describe the lines of the text and the selection to accessibility tools, as
+    /// children of the current node.
+    ///
+    /// `origin` is where the text is drawn, in the widget's coordinate space.
+    pub fn accessibility(&mut self, ctx: &mut AccessCtx, origin: Point) {
+        self.access
+            .build_nodes(ctx, &self.layout, origin, self.selection);
+    }
+
+    /// Select the text requested by an accessibility tool, in the nodes built by the
+    /// last call to [`accessibility`](Self::accessibility).
+    ///
+    /// Returns whether the selection was in this text.
+    pub fn set_access_selection(&mut self, selection: &accesskit::TextSelection) -> bool {
+        let text = self.layout.text().as_str();
+        let Some(selection) = self.access.selection(text, selection) else {
+            return false;
+        };
+        self.set_selection(Some(selection));
+        true
+    }
+
     /// Call when another widget becomes focused
     pub fn focus_lost(&mut self) {
         self.selection = None;
+        self.extra_selections.clear();
         self.selecting_with_mouse = false;
+        self.column_anchor = None;
         self.needs_selection_update = true;
     }
 
@@ -222,9 +607,18 @@ impl<T: Selectable> TextWithSelection<T> {
         // In theory, we could be clever here and only rebuild the layout if the
         // selected range was previously or currently non-zero size (i.e. there is a selected range)
         if self.needs_selection_update || self.layout.needs_rebuild() {
+            // The text may have been edited
+            self.update_find_matches();
             self.layout.invalidate();
             self.layout.rebuild_with_attributes(fcx, |mut builder| {
-                if let Some(selection) = self.selection {
+                for range in &self.find_matches {
+                    builder.push(
+                        &parley::style::StyleProperty::Brush(self.find_brush.clone()),
+                        range.clone(),
+                    );
+                }
+                // The selections are highlighted over the current match
+                for selection in self.selection.iter().chain(&self.extra_selections) {
                     let range = selection.range();
                     if !range.is_empty() {
                         builder.push(
@@ -251,19 +645,34 @@ impl<T: Selectable> TextWithSelection<T> {
             self.cursor_line = None;
         }
         let point: Point = point.into();
-        if let Some(line) = self.cursor_line {
-            scene.stroke(
-                &Stroke::new(2.),
-                Affine::translate((point.x, point.y)),
-                &Brush::Solid(Color::WHITE),
-                None,
-                &line,
-            );
+        if let Some(line) = self.cursor_line.filter(|_| self.is_caret_visible()) {
+            let extra_lines = self
+                .extra_selections
+                .iter()
+                .map(|selection| self.layout.cursor_line_for_text_position(selection.active));
+            for line in std::iter::once(line).chain(extra_lines) {
+                scene.stroke(
+                    &Stroke::new(CARET_WIDTH),
+                    Affine::translate((point.x, point.y)),
+                    &Brush::Solid(Color::WHITE),
+                    None,
+                    &line,
+                );
+            }
         }
         self.layout.draw(scene, point);
     }
 }
 
+/// The affinity of the caret after `movement`, so that it sticks to the text it moved past.
+fn movement_affinity(movement: CaretMovement) -> Affinity {
+    if movement.is_backward() {
+        Affinity::Downstream
+    } else {
+        Affinity::Upstream
+    }
+}
+
 impl<T: Selectable> Deref for TextWithSelection<T> {
     type Target = TextLayout<T>;
 
@@ -542,7 +951,10 @@ pub trait EditableTextCursor {
 }
 
 impl<Str: Deref<Target = str> + TextStorage> Selectable for Str {
-    type Cursor<'a> = StringCursor<'a> where Self: 'a;
+    type Cursor<'a>
+        = StringCursor<'a>
+    where
+        Self: 'a;
 
     fn cursor<'a>(&self, position: usize) -> Option<StringCursor> {
         let new_cursor = StringCursor {
@@ -893,4 +1305,29 @@ mod tests {
         assert_eq!(b.len(), b.next_line_break(13));
         assert_eq!(b.len(), b.next_line_break(19));
     }
+
+    #[test]
+    fn caret_blinks_unless_reset() {
+        let interval = Duration::from_millis(500);
+        let mut text = TextWithSelection::new(String::from("hello"), 12.0);
+        assert!(!text.is_caret_visible());
+        text.selection = Some(Selection::caret(2, Affinity::Upstream));
+        assert!(text.is_caret_visible());
+
+        assert!(!text.blink_caret(Duration::from_millis(400), interval));
+        assert!(text.blink_caret(Duration::from_millis(200), interval));
+        assert!(!text.is_caret_visible());
+        // Typing shows the caret for a whole interval again
+        text.reset_caret_blink();
+        assert!(text.is_caret_visible());
+        assert!(!text.blink_caret(Duration::from_millis(400), interval));
+        assert!(text.blink_caret(Duration::from_millis(1100), interval));
+        assert!(!text.is_caret_visible());
+
+        // Without blinking, the caret comes back and stays
+        assert!(text.blink_caret(Duration::from_millis(100), Duration::ZERO));
+        assert!(!text.blink_caret(Duration::from_secs(10), Duration::ZERO));
+        text.set_caret_visible(false);
+        assert!(!text.is_caret_visible());
+    }
 }
@@ -6,6 +6,7 @@
 use kurbo::{Line, Rect, Stroke};
 use parley::Layout;
 use vello::{kurbo::Affine, peniko::Fill, Scene};
+use winit::window::ImePurpose;
 
 use crate::{text2::TextBrush, WidgetId};
 
@@ -23,6 +24,77 @@ pub(crate) struct TextFieldRegistration {
     pub widget_id: WidgetId,
 }
 
+/// The kind of text a text input expects.
+///
+/// The focused text input's hint is forwarded to the platform, which can pick a
+/// fitting virtual keyboard on mobile, or an `inputmode` on the web. It also decides
+/// the defaults of the text input, such as whether the text is masked and what
+/// counts as [valid](Self::is_valid).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum InputHint {
+    /// Any text.
+    #[default]
+    Text,
+    /// An email address.
+    Email,
+    /// A number, which may have a sign, a fraction and an exponent.
+    Number,
+    /// A password, which is masked when shown.
+    Password,
+    /// A search query.
+    Search,
+}
+
+impl InputHint {
+    /// The purpose the window's IME is given while a text input with this hint is focused.
+    pub fn ime_purpose(self) -> ImePurpose {
+        match self {
+            InputHint::Password => ImePurpose::Password,
+            _ => ImePurpose::Normal,
+        }
+    }
+
+    /// The value of the HTML `inputmode` attribute matching this hint.
+    pub fn web_input_mode(self) -> &'static str {
+        match self {
+            InputHint::Text | InputHint::Password => "text",
+            InputHint::Email => "email",
+            InputHint::Number => "decimal",
+            InputHint::Search => "search",
+        }
+    }
+
+    /// Whether text inputs with this hint hide their text by default.
+    pub fn is_masked(self) -> bool {
+        self == InputHint::Password
+    }
+
+    /// Whether `text` is a valid value for this hint.
+    ///
+    /// Empty text is always valid, as the input may not have been filled in yet.
+    /// This only checks the shape of the text, so an email address is valid if
+    /// it has a name and a domain with a dot in it.
+    pub fn is_valid(self, text: &str) -> bool {
+        if text.is_empty() {
+            return true;
+        }
+        match self {
+            InputHint::Email => match text.split_once('@') {
+                Some((name, domain)) => {
+                    !name.is_empty()
+                        && !text.contains(char::is_whitespace)
+                        && domain
+                            .split_once('.')
+                            .is_some_and(|(host, tld)| !host.is_empty() && !tld.is_empty())
+                }
+                None => false,
+            },
+            InputHint::Number => text.trim().parse::<f64>().is_ok_and(f64::is_finite),
+            InputHint::Text | InputHint::Password | InputHint::Search => true,
+        }
+    }
+}
+
 // Copy-pasted from druid_shell
 /// An event representing an application-initiated change in [`InputHandler`]
 /// state.
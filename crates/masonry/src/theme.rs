@@ -29,6 +29,8 @@ pub const BUTTON_DARK: Color = Color::BLACK;
 pub const BUTTON_LIGHT: Color = Color::rgb8(0x21, 0x21, 0x21);
 pub const DISABLED_BUTTON_DARK: Color = Color::rgb8(0x28, 0x28, 0x28);
 pub const DISABLED_BUTTON_LIGHT: Color = Color::rgb8(0x38, 0x38, 0x38);
+pub const TOGGLE_PRESSED_LIGHT: Color = Color::rgb8(0x00, 0x6c, 0xaa);
+pub const TOGGLE_PRESSED_DARK: Color = Color::rgb8(0x00, 0x4f, 0x7d);
 pub const BUTTON_BORDER_RADIUS: f64 = 4.;
 pub const BUTTON_BORDER_WIDTH: f64 = 2.;
 pub const BORDER_DARK: Color = Color::rgb8(0x3a, 0x3a, 0x3a);
@@ -39,6 +41,7 @@ pub const SELECTION_TEXT_COLOR: Color = Color::rgb8(0x00, 0x00, 0x00);
 pub const CURSOR_COLOR: Color = Color::WHITE;
 pub const TEXT_SIZE_NORMAL: f64 = 15.0;
 pub const TEXT_SIZE_LARGE: f64 = 24.0;
+pub const ICON_SIZE: f64 = 16.0;
 pub const BASIC_WIDGET_HEIGHT: f64 = 18.0;
 pub const WIDE_WIDGET_WIDTH: f64 = 100.;
 pub const BORDERED_WIDGET_HEIGHT: f64 = 24.0;
@@ -46,9 +49,12 @@ pub const TEXTBOX_BORDER_RADIUS: f64 = 2.;
 pub const TEXTBOX_BORDER_WIDTH: f64 = 1.;
 pub const TEXTBOX_INSETS: Insets = Insets::new(4.0, 4.0, 4.0, 4.0);
 pub const SCROLLBAR_COLOR: Color = Color::rgb8(0xff, 0xff, 0xff);
+pub const SCROLLBAR_HOVER_COLOR: Color = Color::rgb8(0xd8, 0xee, 0xff);
+pub const SCROLLBAR_PRESSED_COLOR: Color = PRIMARY_LIGHT;
 pub const SCROLLBAR_BORDER_COLOR: Color = Color::rgb8(0x77, 0x77, 0x77);
 pub const SCROLLBAR_MAX_OPACITY: f64 = 0.7;
 pub const SCROLLBAR_FADE_DELAY: u64 = 1500u64;
+pub const SCROLLBAR_FADE_DURATION: u64 = 250u64;
 pub const SCROLLBAR_WIDTH: f64 = 8.;
 pub const SCROLLBAR_PAD: f64 = 2.;
 pub const SCROLLBAR_MIN_SIZE: f64 = 45.;
@@ -57,6 +63,23 @@ pub const SCROLLBAR_EDGE_WIDTH: f64 = 1.;
 pub const WIDGET_PADDING_VERTICAL: f64 = 10.0;
 pub const WIDGET_PADDING_HORIZONTAL: f64 = 8.0;
 pub const WIDGET_CONTROL_COMPONENT_PADDING: f64 = 4.0;
+pub const MODAL_SCRIM_COLOR: Color = Color::rgba8(0x00, 0x00, 0x00, 0x80);
+pub const SHADOW_COLOR: Color = Color::rgba8(0x00, 0x00, 0x00, 0x60);
+pub const CARD_PADDING: f64 = 12.0;
+pub const CARD_ELEVATION: f64 = 4.0;
+pub const AVATAR_SIZE: f64 = 32.0;
+pub const SKELETON_COLOR: Color = Color::rgb8(0x3a, 0x3a, 0x3a);
+pub const SKELETON_HIGHLIGHT_COLOR: Color = Color::rgb8(0x5a, 0x5a, 0x5a);
+pub const SKELETON_RADIUS: f64 = 4.0;
+pub const SKELETON_LINE_HEIGHT: f64 = 22.0;
+pub const FOCUS_RING_COLOR: Color = PRIMARY_LIGHT;
+pub const FOCUS_RING_WIDTH: f64 = 2.0;
+pub const FOCUS_RING_OFFSET: f64 = 2.0;
+pub const FOCUS_RING_RADIUS: f64 = 4.0;
+pub const FIND_MATCH_COLOR: Color = Color::rgb8(0x8a, 0x6d, 0x1e);
+pub const SPELLING_ERROR_COLOR: Color = Color::rgb8(0xf0, 0x50, 0x50);
+pub const AUTOCOMPLETE_DEBOUNCE: u64 = 150u64;
+pub const INVALID_INPUT_COLOR: Color = SPELLING_ERROR_COLOR;
 
 static DEBUG_COLOR: &[Color] = &[
     Color::rgb8(230, 25, 75),
@@ -0,0 +1,139 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Waking widgets up from other threads.
+//!
+//! Widgets which wait on work done in the background, such as loading a file,
+//! shouldn't check on it every frame. Instead, they take a [`WakeHandle`] with
+//! [`LifeCycleCtx::wake_handle`] and give it to the work, which calls
+//! [`WakeHandle::wake`] once it is done. The event loop then wakes up, if it was
+//! waiting for events, and sends [`LifeCycle::Wake`] to the widget.
+//!
+//! [`LifeCycleCtx::wake_handle`]: crate::LifeCycleCtx::wake_handle
+//! [`LifeCycle::Wake`]: crate::LifeCycle::Wake
+
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use crate::WidgetId;
+
+type Notify = Box<dyn Fn() + Send>;
+
+/// A handle which wakes a widget up from any thread.
+///
+/// See the [module docs](self).
+#[derive(Clone)]
+pub struct WakeHandle {
+    widget: WidgetId,
+    queue: Arc<WakeQueue>,
+}
+
+/// The widgets woken since the render root last handled wakes, and how to tell the
+/// event loop about new ones.
+#[derive(Default)]
+pub(crate) struct WakeQueue {
+    woken: Mutex<Vec<WidgetId>>,
+    woken_changed: Condvar,
+    /// Wakes up the event loop, if there is one.
+    notify: Mutex<Option<Notify>>,
+}
+
+impl WakeHandle {
+    pub(crate) fn new(widget: WidgetId, queue: Arc<WakeQueue>) -> Self {
+        WakeHandle { widget, queue }
+    }
+
+    /// The widget this handle wakes.
+    pub fn widget_id(&self) -> WidgetId {
+        self.widget
+    }
+
+    /// Send [`LifeCycle::Wake`](crate::LifeCycle::Wake) to the widget.
+    ///
+    /// Wakes which happen before the widget gets the event are merged into one. If
+    /// the widget has been removed, this does nothing.
+    pub fn wake(&self) {
+        let mut woken = self.queue.woken.lock().unwrap();
+        if woken.contains(&self.widget) {
+            return;
+        }
+        woken.push(self.widget);
+        drop(woken);
+        self.queue.woken_changed.notify_all();
+        if let Some(notify) = &*self.queue.notify.lock().unwrap() {
+            notify();
+        }
+    }
+}
+
+impl std::fmt::Debug for WakeHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WakeHandle").field(&self.widget).finish()
+    }
+}
+
+impl WakeQueue {
+    pub(crate) fn set_notify(&self, notify: Option<Notify>) {
+        *self.notify.lock().unwrap() = notify;
+    }
+
+    /// The widgets woken since the last call.
+    pub(crate) fn take(&self) -> Vec<WidgetId> {
+        std::mem::take(&mut self.woken.lock().unwrap())
+    }
+
+    /// Wait until a widget has been woken, for up to `timeout`, and return whether
+    /// one has.
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        let woken = self.woken.lock().unwrap();
+        let (woken, _) = self
+            .woken_changed
+            .wait_timeout_while(woken, timeout, |woken| woken.is_empty())
+            .unwrap();
+        !woken.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
+    use crate::widget::Flex;
+    use crate::LifeCycle;
+
+    #[test]
+    fn only_the_woken_widget_gets_wake() {
+        let [woken_id, other_id] = widget_ids();
+        let wakes = Rc::new(Cell::new(Vec::new()));
+        let counter = |id: WidgetId| {
+            let wakes = wakes.clone();
+            ModularWidget::new(()).lifecycle_fn(move |_, _, event| {
+                if let LifeCycle::Wake = event {
+                    let mut seen = wakes.take();
+                    seen.push(id);
+                    wakes.set(seen);
+                }
+            })
+        };
+        let widget = Flex::row()
+            .with_child_id(counter(woken_id), woken_id)
+            .with_child_id(counter(other_id), other_id);
+        let mut harness = TestHarness::create(widget);
+
+        let handle = harness.wake_handle(woken_id);
+        let thread = std::thread::spawn(move || {
+            // Both wakes are delivered as one
+            handle.wake();
+            handle.wake();
+        });
+        thread.join().unwrap();
+        assert!(harness.wait_for_wake(Duration::from_secs(1)));
+        assert_eq!(wakes.take(), [woken_id]);
+
+        assert!(!harness.wait_for_wake(Duration::ZERO));
+        assert_eq!(wakes.take(), []);
+    }
+}
@@ -15,10 +15,10 @@ use vello::Scene;
 
 use crate::contexts::AccessCtx;
 use crate::paint_scene_helpers::UnitPoint;
-use crate::widget::{WidgetPod, WidgetRef};
+use crate::widget::{WidgetMut, WidgetPod};
 use crate::{
     AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, Rect, Size, StatusChange, TextEvent, Widget,
+    PointerEvent, Rect, Size, StatusChange, TextEvent, Widget, WidgetId,
 };
 
 // TODO - Have child widget type as generic argument
@@ -29,20 +29,28 @@ pub struct Align {
     child: WidgetPod<Box<dyn Widget>>,
     width_factor: Option<f64>,
     height_factor: Option<f64>,
+    /// Whether `align` is mirrored in right-to-left layouts
+    logical: bool,
 }
 
 impl Align {
     /// Create widget with alignment.
     ///
     /// Note that the `align` parameter is specified as a `UnitPoint` in
-    /// terms of left and right. This is inadequate for bidi-aware layout
-    /// and thus the API will change when Masonry gains bidi capability.
+    /// terms of left and right, which stay the same in right-to-left layouts
+    /// unless the alignment is made [`logical`](Self::logical).
     pub fn new(align: UnitPoint, child: impl Widget + 'static) -> Align {
+        Align::new_pod(align, WidgetPod::new(child).boxed())
+    }
+
+    /// Create widget with alignment, from a [`WidgetPod`].
+    pub fn new_pod(align: UnitPoint, child: WidgetPod<Box<dyn Widget>>) -> Align {
         Align {
             align,
-            child: WidgetPod::new(child).boxed(),
+            child,
             width_factor: None,
             height_factor: None,
+            logical: false,
         }
     }
 
@@ -61,6 +69,27 @@ impl Align {
         Align::new(UnitPoint::LEFT, child)
     }
 
+    /// Create a widget aligned to the start of lines, which is the left in
+    /// left-to-right layouts and the right in right-to-left ones.
+    pub fn start(child: impl Widget + 'static) -> Align {
+        Align::left(child).logical()
+    }
+
+    /// Create a widget aligned to the end of lines, which is the right in
+    /// left-to-right layouts and the left in right-to-left ones.
+    pub fn end(child: impl Widget + 'static) -> Align {
+        Align::right(child).logical()
+    }
+
+    /// Builder-style method to treat the alignment as the one for left-to-right
+    /// layouts, and mirror it in right-to-left ones.
+    ///
+    /// See [`direction`](crate::direction) for more.
+    pub fn logical(mut self) -> Self {
+        self.logical = true;
+        self
+    }
+
     /// Align only in the horizontal axis, keeping the child's size in the vertical.
     pub fn horizontal(align: UnitPoint, child: impl Widget + 'static) -> Align {
         Align {
@@ -68,6 +97,7 @@ impl Align {
             child: WidgetPod::new(child).boxed(),
             width_factor: None,
             height_factor: Some(1.0),
+            logical: false,
         }
     }
 
@@ -78,10 +108,22 @@ impl Align {
             child: WidgetPod::new(child).boxed(),
             width_factor: Some(1.0),
             height_factor: None,
+            logical: false,
         }
     }
 }
 
+impl WidgetMut<'_, Align> {
+    pub fn set_alignment(&mut self, align: UnitPoint) {
+        self.widget.align = align;
+        self.ctx.request_layout();
+    }
+
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
 impl Widget for Align {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
         self.child.on_pointer_event(ctx, event);
@@ -124,16 +166,19 @@ impl Widget for Align {
         my_size = bc.constrain(my_size);
         let extra_width = (my_size.width - size.width).max(0.);
         let extra_height = (my_size.height - size.height).max(0.);
-        let origin = self
+        let mut origin = self
             .align
             .resolve(Rect::new(0., 0., extra_width, extra_height))
             .expand();
+        if self.logical && ctx.layout_direction().is_rtl() {
+            origin.x = extra_width - origin.x;
+        }
         ctx.place_child(&mut self.child, origin);
 
-        let my_insets = self.child.compute_parent_paint_insets(my_size);
+        let my_insets = ctx.compute_insets_from_child(&self.child, my_size);
         ctx.set_paint_insets(my_insets);
         if self.height_factor.is_some() {
-            let baseline_offset = self.child.baseline_offset();
+            let baseline_offset = ctx.child_baseline_offset(&self.child);
             if baseline_offset > 0f64 {
                 ctx.set_baseline_offset(baseline_offset + extra_height / 2.0);
             }
@@ -160,8 +205,8 @@ impl Widget for Align {
         self.child.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        smallvec![self.child.as_dyn()]
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
     }
 
     fn make_trace_span(&self) -> Span {
@@ -0,0 +1,646 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A text input which suggests completions.
+
+use std::time::{Duration, Instant};
+
+use accesskit::Role;
+use kurbo::{Insets, Point, Rect, Size};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::paint_scene_helpers::fill_color;
+use crate::popup::PopupRequest;
+use crate::task_pool::Task;
+use crate::widget::{Label, Textbox, WidgetMut, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, InternalLifeCycle, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
+    WidgetPod,
+};
+
+/// A [`Textbox`] with a popup list of suggestions shown below it while the user types.
+///
+/// Once the text has stayed the same for the [debounce](Self::with_debounce) delay,
+/// the widget submits [`Action::SuggestionsRequested`]. The app then provides the
+/// suggestions, either straight away with [`WidgetMut::set_suggestions`], or from
+/// work running in the background with [`WidgetMut::load_suggestions`].
+///
+/// The suggestions can be picked with the pointer or with <kbd>↑</kbd>/<kbd>↓</kbd>
+/// and <kbd>Enter</kbd>. <kbd>Escape</kbd> hides them until the text next changes.
+/// Picking a suggestion replaces the text with it, and emits [`Action::TextChanged`].
+///
+/// The list is placed with [`LayoutCtx::place_popup`] and painted with
+/// [`PaintCtx::paint_popup`], like the panel of a [`Popover`](crate::widget::Popover),
+/// so it is shown above the widgets below the text box.
+pub struct Autocomplete {
+    textbox: WidgetPod<Textbox>,
+    suggestions: Vec<WidgetPod<Label>>,
+    /// The suggestion highlighted with the keyboard or the pointer
+    active: Option<usize>,
+    /// Whether the suggestions should be shown
+    expanded: bool,
+    debounce: Duration,
+    /// When the text last changed, until suggestions are requested for it
+    changed_at: Option<Instant>,
+    /// The suggestions being loaded for the latest request
+    loading: Option<Task<Vec<ArcStr>>>,
+    /// The list of suggestions, in local coordinates, as of the last layout
+    panel: Rect,
+    /// The window origin the list was placed with
+    window_origin: Point,
+}
+
+impl Autocomplete {
+    /// Create a new `Autocomplete` with the given initial text.
+    pub fn new(initial_text: impl Into<String>) -> Self {
+        Self::from_pod(WidgetPod::new(Textbox::new(initial_text)))
+    }
+
+    /// Create a new `Autocomplete` wrapping an existing text box.
+    pub fn from_pod(textbox: WidgetPod<Textbox>) -> Self {
+        Autocomplete {
+            textbox,
+            suggestions: Vec::new(),
+            active: None,
+            expanded: false,
+            debounce: Duration::from_millis(theme::AUTOCOMPLETE_DEBOUNCE),
+            changed_at: None,
+            loading: None,
+            panel: Rect::ZERO,
+            window_origin: Point::ORIGIN,
+        }
+    }
+
+    /// Builder-style method to set the suggestions.
+    pub fn with_suggestions(mut self, suggestions: impl IntoIterator<Item = ArcStr>) -> Self {
+        self.suggestions = suggestions
+            .into_iter()
+            .map(|text| WidgetPod::new(Label::new(text)).with_stashed(true))
+            .collect();
+        self
+    }
+
+    /// Builder-style method to set how long the text has to stay the same before
+    /// suggestions are requested, which is [`theme::AUTOCOMPLETE_DEBOUNCE`]
+    /// milliseconds by default.
+    ///
+    /// With a zero delay, suggestions are requested every time the text changes.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Whether the suggestions are currently shown.
+    pub fn is_expanded(&self) -> bool {
+        self.expanded && !self.suggestions.is_empty()
+    }
+
+    fn set_expanded(&mut self, ctx: &mut EventCtx, expanded: bool) {
+        let was_expanded = self.is_expanded();
+        self.expanded = expanded;
+        self.active = None;
+        if self.is_expanded() != was_expanded {
+            for suggestion in &mut self.suggestions {
+                ctx.set_stashed(suggestion, !self.expanded);
+            }
+        }
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+    }
+
+    /// Replace the suggestions with labels of `suggestions`, returning the old ones
+    /// to be removed.
+    fn replace_suggestions(
+        &mut self,
+        suggestions: impl IntoIterator<Item = ArcStr>,
+    ) -> Vec<WidgetPod<Label>> {
+        let stashed = !self.expanded;
+        let new_suggestions = suggestions
+            .into_iter()
+            .map(|text| WidgetPod::new(Label::new(text)).with_stashed(stashed))
+            .collect();
+        self.active = None;
+        std::mem::replace(&mut self.suggestions, new_suggestions)
+    }
+
+    /// Request suggestions for the text once it has stayed the same for the debounce delay.
+    fn text_changed(&mut self, ctx: &mut EventCtx) {
+        if self.debounce.is_zero() {
+            let text = ctx.get_ref(&self.textbox).text().to_string();
+            ctx.submit_action(Action::SuggestionsRequested(text));
+        } else {
+            self.changed_at = Some(ctx.now());
+            ctx.request_anim_frame();
+        }
+    }
+
+    /// Replace the text with the suggestion at `ix`, and hide the suggestions.
+    fn pick(&mut self, ctx: &mut EventCtx, ix: usize) {
+        let text = ctx.get_ref(&self.suggestions[ix]).text().to_string();
+        trace!("Autocomplete {:?} picked {:?}", ctx.widget_id(), text);
+        ctx.get_mut(&mut self.textbox).replace_text(text.clone());
+        self.set_expanded(ctx, false);
+        // The picked text doesn't need suggestions of its own
+        self.changed_at = None;
+        ctx.submit_action(Action::TextChanged(text));
+    }
+
+    fn paint_list(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        fill_color(scene, &self.panel, theme::BACKGROUND_LIGHT);
+        if let Some(ix) = self.active {
+            let rect = ctx.child_layout_rect(&self.suggestions[ix]);
+            let active_rect = Rect::new(self.panel.x0, rect.y0, self.panel.x1, rect.y1);
+            fill_color(scene, &active_rect, theme::SELECTED_TEXT_BACKGROUND_COLOR);
+        }
+        for suggestion in &mut self.suggestions {
+            suggestion.paint(ctx, scene);
+        }
+    }
+}
+
+impl<'w> WidgetRef<'w, Autocomplete> {
+    /// The text of the suggestion highlighted by the user, if any.
+    pub fn active_suggestion(&self) -> Option<&'w ArcStr> {
+        let autocomplete = self.deref();
+        let ix = autocomplete.active?;
+        Some(self.get_child(&autocomplete.suggestions[ix]).deref().text())
+    }
+}
+
+impl WidgetMut<'_, Autocomplete> {
+    /// Replace the suggestions.
+    ///
+    /// This keeps the suggestions shown if they already were, but clears the
+    /// highlighted suggestion.
+    pub fn set_suggestions(&mut self, suggestions: impl IntoIterator<Item = ArcStr>) {
+        for suggestion in self.widget.replace_suggestions(suggestions) {
+            self.ctx.remove_child(suggestion);
+        }
+        self.ctx.children_changed();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Replace the suggestions with the result of `task` once it is done.
+    ///
+    /// This drops the suggestions being loaded before, if any, so that results for
+    /// older text never replace newer ones. The current suggestions are kept until
+    /// then.
+    pub fn load_suggestions(&mut self, task: Task<Vec<ArcStr>>) {
+        task.wake_when_done(self.ctx.wake_handle());
+        self.widget.loading = Some(task);
+    }
+
+    /// Set how long the text has to stay the same before suggestions are requested.
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.widget.debounce = debounce;
+    }
+
+    pub fn textbox_mut(&mut self) -> WidgetMut<'_, Textbox> {
+        self.ctx.get_mut(&mut self.widget.textbox)
+    }
+}
+
+impl Widget for Autocomplete {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.textbox.on_pointer_event(ctx, event);
+        for suggestion in &mut self.suggestions {
+            suggestion.on_pointer_event(ctx, event);
+        }
+        if !self.is_expanded() {
+            return;
+        }
+
+        let Some(hovered) = self
+            .suggestions
+            .iter()
+            .position(|label| ctx.get_ref(label).state().is_hot)
+        else {
+            return;
+        };
+        match event {
+            PointerEvent::PointerMove(_) if self.active != Some(hovered) => {
+                self.active = Some(hovered);
+                ctx.request_paint();
+                ctx.request_accessibility_update();
+            }
+            PointerEvent::PointerDown(_, _) => {
+                self.pick(ctx, hovered);
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if let TextEvent::KeyboardKey(key, mods) = event {
+            if key.state.is_pressed() && mods.is_empty() && self.is_expanded() {
+                let len = self.suggestions.len();
+                let handled = match &key.logical_key {
+                    Key::Named(NamedKey::ArrowDown) => {
+                        self.active = Some(self.active.map_or(0, |ix| (ix + 1) % len));
+                        true
+                    }
+                    Key::Named(NamedKey::ArrowUp) => {
+                        self.active = Some(self.active.map_or(len - 1, |ix| (ix + len - 1) % len));
+                        true
+                    }
+                    Key::Named(NamedKey::Enter) => match self.active {
+                        Some(ix) => {
+                            self.pick(ctx, ix);
+                            true
+                        }
+                        None => false,
+                    },
+                    Key::Named(NamedKey::Escape) => {
+                        self.set_expanded(ctx, false);
+                        true
+                    }
+                    _ => false,
+                };
+                if handled {
+                    ctx.skip_child(&mut self.textbox);
+                    for suggestion in &mut self.suggestions {
+                        ctx.skip_child(suggestion);
+                    }
+                    ctx.set_handled();
+                    ctx.request_paint();
+                    ctx.request_accessibility_update();
+                    return;
+                }
+            }
+        }
+
+        let previous_text = ctx.get_ref(&self.textbox).text().to_string();
+        self.textbox.on_text_event(ctx, event);
+        for suggestion in &mut self.suggestions {
+            suggestion.on_text_event(ctx, event);
+        }
+        if ctx.get_ref(&self.textbox).text() != previous_text {
+            self.set_expanded(ctx, true);
+            self.text_changed(ctx);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.action == accesskit::Action::Default {
+            if let Some(ix) = self
+                .suggestions
+                .iter()
+                .position(|label| label.id() == event.target)
+            {
+                self.pick(ctx, ix);
+            }
+        }
+        self.textbox.on_access_event(ctx, event);
+        for suggestion in &mut self.suggestions {
+            suggestion.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        // The suggestions are replaced after the old ones have been sent the event
+        self.textbox.lifecycle(ctx, event);
+        for suggestion in &mut self.suggestions {
+            suggestion.lifecycle(ctx, event);
+        }
+        match event {
+            LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin { .. })
+                if self.is_expanded() && ctx.window_origin() != self.window_origin =>
+            {
+                trace!("Autocomplete {:?} moved in the window", ctx.widget_id());
+                ctx.request_layout();
+            }
+            LifeCycle::AnimFrame(_) => {
+                if let Some(changed_at) = self.changed_at {
+                    if ctx.now().saturating_duration_since(changed_at) >= self.debounce {
+                        self.changed_at = None;
+                        let text = ctx.get_ref(&self.textbox).text().to_string();
+                        ctx.submit_action(Action::SuggestionsRequested(text));
+                    } else {
+                        ctx.request_anim_frame();
+                    }
+                }
+            }
+            LifeCycle::Wake if self.loading.as_ref().is_some_and(Task::is_done) => {
+                let task = self.loading.take().unwrap();
+                match task.take() {
+                    Some(Ok(suggestions)) => {
+                        for suggestion in self.replace_suggestions(suggestions) {
+                            ctx.remove_child(suggestion);
+                        }
+                        ctx.children_changed();
+                        ctx.request_accessibility_update();
+                    }
+                    Some(Err(_)) => tracing::error!("Loading autocomplete suggestions panicked"),
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.textbox.layout(ctx, bc);
+        ctx.place_child(&mut self.textbox, Point::ORIGIN);
+
+        if !self.is_expanded() {
+            ctx.set_paint_insets(Insets::ZERO);
+            trace!("Computed layout: size={}", size);
+            return size;
+        }
+
+        // The list is placed relative to the window, so we remember where we were in it
+        self.window_origin = ctx.widget_state.window_origin();
+        let suggestion_bc = BoxConstraints::new(
+            Size::new(size.width, 0.0),
+            Size::new(size.width, f64::INFINITY),
+        );
+        let heights: Vec<f64> = self
+            .suggestions
+            .iter_mut()
+            .map(|suggestion| suggestion.layout(ctx, &suggestion_bc).height)
+            .collect();
+        let list_size = Size::new(size.width, heights.iter().sum());
+        let panel = ctx
+            .place_popup(PopupRequest::new(size.to_rect(), list_size))
+            .rect;
+        let mut y = panel.y0;
+        for (suggestion, height) in self.suggestions.iter_mut().zip(heights) {
+            ctx.place_child(suggestion, Point::new(panel.x0, y));
+            y += height;
+        }
+        // Other popups can move the list without this widget asking for layout
+        if panel != self.panel {
+            ctx.request_paint();
+        }
+        self.panel = panel;
+
+        ctx.set_paint_insets(panel.union(size.to_rect()) - size.to_rect());
+        trace!("Computed layout: size={}, list={}", size, panel);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.textbox.paint(ctx, scene);
+        if self.is_expanded() {
+            // The list is above the rest of the window, and isn't repainted with it
+            ctx.paint_popup(self.panel, |ctx, scene| self.paint_list(ctx, scene));
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ComboBox
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_expanded(self.is_expanded());
+        if let Some(ix) = self.active.filter(|_| self.is_expanded()) {
+            ctx.current_node()
+                .set_active_descendant(self.suggestions[ix].id().into());
+        }
+        self.textbox.accessibility(ctx);
+        for suggestion in &mut self.suggestions {
+            suggestion.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut children = SmallVec::new();
+        children.push(self.textbox.id());
+        children.extend(self.suggestions.iter().map(|label| label.id()));
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Autocomplete")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(format!("{} suggestions", self.suggestions.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::task_pool::TaskPool;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Flex, SizedBox};
+    use crate::WidgetId;
+
+    fn suggestion_ids(harness: &TestHarness, id: WidgetId) -> Vec<WidgetId> {
+        let autocomplete = harness.get_widget(id);
+        autocomplete.children()[1..]
+            .iter()
+            .map(|child| child.id())
+            .collect()
+    }
+
+    fn suggestion_texts(harness: &TestHarness, id: WidgetId) -> Vec<String> {
+        suggestion_ids(harness, id)
+            .into_iter()
+            .map(|id| {
+                let label = harness.get_widget(id);
+                label.downcast::<Label>().unwrap().text().to_string()
+            })
+            .collect()
+    }
+
+    fn edit_autocomplete(harness: &mut TestHarness, f: impl FnOnce(WidgetMut<'_, Autocomplete>)) {
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<SizedBox>();
+            let mut autocomplete = root.child_mut().unwrap();
+            f(autocomplete.downcast::<Autocomplete>());
+        });
+    }
+
+    /// Pop the actions submitted by the autocomplete widget itself.
+    fn autocomplete_actions(harness: &mut TestHarness, id: WidgetId) -> Vec<Action> {
+        std::iter::from_fn(|| harness.pop_action())
+            .filter(|(_, widget_id)| *widget_id == id)
+            .map(|(action, _)| action)
+            .collect()
+    }
+
+    #[test]
+    fn typing_shows_suggestions() {
+        let [autocomplete_id, textbox_id] = widget_ids();
+        let widget = Autocomplete::from_pod(WidgetPod::new_with_id(Textbox::new(""), textbox_id))
+            .with_suggestions(["apple".into(), "apricot".into()]);
+
+        let mut harness = TestHarness::create(SizedBox::new_with_id(widget, autocomplete_id));
+        let is_expanded = |harness: &TestHarness| {
+            harness
+                .get_widget(autocomplete_id)
+                .downcast::<Autocomplete>()
+                .unwrap()
+                .is_expanded()
+        };
+        assert!(!is_expanded(&harness));
+
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("ap");
+        assert!(is_expanded(&harness));
+        while harness.pop_action().is_some() {}
+
+        let apricot = suggestion_ids(&harness, autocomplete_id)[1];
+        harness.mouse_click_on(apricot);
+        assert!(!is_expanded(&harness));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("apricot".into()), autocomplete_id))
+        );
+        let textbox = harness.get_widget(textbox_id);
+        assert_eq!(textbox.downcast::<Textbox>().unwrap().text(), "apricot");
+    }
+
+    #[test]
+    fn suggestions_set_while_closed_stay_hidden() {
+        let [autocomplete_id, textbox_id] = widget_ids();
+        let widget = Autocomplete::from_pod(WidgetPod::new_with_id(Textbox::new(""), textbox_id));
+        let mut harness = TestHarness::create(SizedBox::new_with_id(widget, autocomplete_id));
+        let stashed = |harness: &TestHarness| {
+            suggestion_ids(harness, autocomplete_id)
+                .into_iter()
+                .map(|id| harness.get_widget(id).state().is_stashed)
+                .collect::<Vec<_>>()
+        };
+
+        edit_autocomplete(&mut harness, |mut autocomplete| {
+            autocomplete.set_suggestions(["apple".into(), "apricot".into()]);
+        });
+        assert_eq!(stashed(&harness), [true, true]);
+        harness.check_invariants();
+
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("ap");
+        assert_eq!(stashed(&harness), [false, false]);
+    }
+
+    #[test]
+    fn suggestions_are_shown_in_a_popup() {
+        let [autocomplete_id, textbox_id, below_id] = widget_ids();
+        let widget = Autocomplete::from_pod(WidgetPod::new_with_id(Textbox::new(""), textbox_id))
+            .with_suggestions(["apple".into(), "apricot".into()]);
+        let column = Flex::column()
+            .with_child_id(widget, autocomplete_id)
+            .with_child_id(SizedBox::empty().width(100.0).height(100.0), below_id);
+        let mut harness = TestHarness::create_with_size(column, Size::new(200.0, 400.0));
+        harness.redraw();
+        assert!(harness.overlay_owners().is_empty());
+
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("ap");
+        while harness.pop_action().is_some() {}
+        harness.redraw();
+        assert_eq!(harness.overlay_owners(), [autocomplete_id]);
+
+        // The list covers the widget below instead of pushing it down
+        let textbox = harness.get_widget(textbox_id).state().window_layout_rect();
+        let autocomplete = harness
+            .get_widget(autocomplete_id)
+            .state()
+            .window_layout_rect();
+        let below = harness.get_widget(below_id).state().window_layout_rect();
+        assert_eq!(autocomplete, textbox);
+        assert_eq!(below.y0, textbox.y1);
+        let apple = suggestion_ids(&harness, autocomplete_id)[0];
+        let apple = harness.get_widget(apple).state().window_layout_rect();
+        assert_eq!(apple.y0, textbox.y1);
+        assert!(apple.intersect(below).area() > 0.0);
+
+        // It still gets the pointer, above the widget below
+        harness.mouse_click_on(suggestion_ids(&harness, autocomplete_id)[0]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("apple".into()), autocomplete_id))
+        );
+        harness.redraw();
+        assert!(harness.overlay_owners().is_empty());
+    }
+
+    #[test]
+    fn suggestions_are_requested_after_the_debounce() {
+        let [autocomplete_id, textbox_id] = widget_ids();
+        let widget = Autocomplete::from_pod(WidgetPod::new_with_id(Textbox::new(""), textbox_id))
+            .with_debounce(Duration::from_millis(100));
+        let mut harness = TestHarness::create(SizedBox::new_with_id(widget, autocomplete_id));
+
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("a");
+        harness.animate(Duration::from_millis(60));
+        harness.keyboard_type_chars("p");
+        harness.animate(Duration::from_millis(60));
+        // Typing again restarted the delay
+        assert_eq!(autocomplete_actions(&mut harness, autocomplete_id), []);
+
+        harness.animate(Duration::from_millis(60));
+        assert_eq!(
+            autocomplete_actions(&mut harness, autocomplete_id),
+            [Action::SuggestionsRequested("ap".into())]
+        );
+        harness.animate(Duration::from_millis(200));
+        assert_eq!(autocomplete_actions(&mut harness, autocomplete_id), []);
+
+        edit_autocomplete(&mut harness, |mut autocomplete| {
+            autocomplete.set_debounce(Duration::ZERO);
+        });
+        harness.keyboard_type_chars("r");
+        assert_eq!(
+            autocomplete_actions(&mut harness, autocomplete_id),
+            [Action::SuggestionsRequested("apr".into())]
+        );
+    }
+
+    #[test]
+    fn loaded_suggestions_replace_older_requests() {
+        let [autocomplete_id, textbox_id] = widget_ids();
+        let widget = Autocomplete::from_pod(WidgetPod::new_with_id(Textbox::new(""), textbox_id))
+            .with_suggestions(["apple".into()]);
+        let mut harness = TestHarness::create(SizedBox::new_with_id(widget, autocomplete_id));
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("ap");
+
+        // The first request is still loading when the second one finishes
+        let pool = TaskPool::new(2);
+        let (release, released) = mpsc::channel::<()>();
+        let older = pool.spawn(move || {
+            released.recv().unwrap();
+            vec!["apex".into()]
+        });
+        let newer = pool.spawn(|| vec!["apricot".into(), "apron".into()]);
+        edit_autocomplete(&mut harness, |mut autocomplete| {
+            autocomplete.load_suggestions(older);
+            autocomplete.load_suggestions(newer);
+        });
+        assert_eq!(suggestion_texts(&harness, autocomplete_id), ["apple"]);
+
+        assert!(harness.wait_for_wake(Duration::from_secs(5)));
+        assert_eq!(
+            suggestion_texts(&harness, autocomplete_id),
+            ["apricot", "apron"]
+        );
+        harness.check_invariants();
+        let autocomplete = harness.get_widget(autocomplete_id);
+        assert!(autocomplete
+            .downcast::<Autocomplete>()
+            .unwrap()
+            .is_expanded());
+
+        release.send(()).unwrap();
+        harness.wait_for_wake(Duration::from_millis(100));
+        assert_eq!(
+            suggestion_texts(&harness, autocomplete_id),
+            ["apricot", "apron"]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which blurs what is painted beneath it.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use vello::Scene;
+
+use crate::effects::Blur;
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A widget which blurs whatever is painted beneath its child, and paints the child
+/// over the blur, such as for a frosted glass sidebar or sheet.
+///
+/// The child should have a translucent background for the blur to show through.
+/// The blur fills the whole layout rect of the widget, so rounded panels should be
+/// clipped by their parent. See [`effects`](crate::effects) for how it is rendered.
+pub struct BackdropBlur {
+    child: WidgetPod<Box<dyn Widget>>,
+    blur: Rc<RefCell<Blur>>,
+}
+
+impl BackdropBlur {
+    /// Blur what is beneath `child` by `radius` logical pixels.
+    pub fn new(radius: f64, child: impl Widget + 'static) -> Self {
+        Self::new_pod(radius, WidgetPod::new(child).boxed())
+    }
+
+    pub fn new_pod(radius: f64, child: WidgetPod<Box<dyn Widget>>) -> Self {
+        BackdropBlur {
+            child,
+            blur: Rc::new(RefCell::new(Blur::new(radius))),
+        }
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.blur.borrow().radius()
+    }
+}
+
+impl WidgetMut<'_, BackdropBlur> {
+    pub fn set_radius(&mut self, radius: f64) {
+        self.widget.blur.borrow_mut().set_radius(radius);
+        self.ctx.request_paint();
+    }
+
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl Widget for BackdropBlur {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _scene: &mut Scene) {
+        let child = &mut self.child;
+        ctx.paint_backdrop_effect(self.blur.clone(), |ctx, scene| child.paint(ctx, scene));
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Flex, SizedBox};
+
+    #[test]
+    fn blurs_beneath_its_child() {
+        let panel = SizedBox::empty().width(60.0).height(100.0);
+        let root = Flex::row().with_child(BackdropBlur::new(12.0, panel));
+        let mut harness = TestHarness::create_with_size(root, Size::new(200.0, 100.0));
+        let _ = harness.render();
+        let layers = harness.effect_layers();
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers[0].1.size(), Size::new(60.0, 100.0));
+
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            let mut blur = flex.child_mut(0).unwrap();
+            blur.downcast::<BackdropBlur>().set_radius(4.0);
+        });
+        let blur = harness.get_widget(layers[0].0);
+        assert_eq!(blur.downcast::<BackdropBlur>().unwrap().radius(), 4.0);
+    }
+}
@@ -3,7 +3,7 @@
 
 //! A button widget.
 
-use accesskit::{DefaultActionVerb, Role};
+use accesskit::Role;
 use smallvec::{smallvec, SmallVec};
 use tracing::{trace, trace_span, Span};
 use vello::Scene;
@@ -11,10 +11,11 @@ use vello::Scene;
 use crate::action::Action;
 use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
 use crate::text2::TextStorage;
-use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::widget::{Label, WidgetMut, WidgetPod};
 use crate::{
-    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, Insets, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget,
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, Insets, Interactivity,
+    LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, PointerEvent, Size, StatusChange, TextEvent,
+    Widget, WidgetId,
 };
 
 // the minimum padding added to a button.
@@ -126,7 +127,7 @@ impl Widget for Button {
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
-        let baseline = self.label.baseline_offset();
+        let baseline = ctx.child_baseline_offset(&self.label);
         ctx.set_baseline_offset(baseline + LABEL_INSETS.y1);
 
         let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
@@ -154,12 +155,11 @@ impl Widget for Button {
         let is_active = ctx.is_active() && !ctx.is_disabled();
         let is_hot = ctx.is_hot();
         let size = ctx.size();
-        let stroke_width = theme::BUTTON_BORDER_WIDTH;
-
-        let rounded_rect = size
-            .to_rect()
-            .inset(-stroke_width / 2.0)
-            .to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+        let (border_rect, stroke_width) = ctx.snap_stroke(
+            size.to_rect().inset(-theme::BUTTON_BORDER_WIDTH / 2.0),
+            theme::BUTTON_BORDER_WIDTH,
+        );
+        let rounded_rect = border_rect.to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
 
         let bg_gradient = if ctx.is_disabled() {
             [theme::DISABLED_BUTTON_LIGHT, theme::DISABLED_BUTTON_DARK]
@@ -187,31 +187,28 @@ impl Widget for Button {
         self.label.paint(ctx, scene);
     }
 
+    fn interactivity(&self) -> Interactivity {
+        Interactivity::Clickable
+    }
+
     fn accessibility_role(&self) -> Role {
         Role::Button
     }
 
     fn accessibility(&mut self, ctx: &mut AccessCtx) {
-        let _name = self.label.widget().text().as_str().to_string();
+        let _name = ctx.get_ref(&self.label).text().as_str().to_string();
         // We may want to add a name if it doesn't interfere with the child label
         // ctx.current_node().set_name(name);
-        ctx.current_node()
-            .set_default_action_verb(DefaultActionVerb::Click);
-
         self.label.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        smallvec![self.label.as_dyn()]
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.label.id()]
     }
 
     fn make_trace_span(&self) -> Span {
         trace_span!("Button")
     }
-
-    fn get_debug_text(&self) -> Option<String> {
-        Some(self.label.as_ref().text().as_str().to_string())
-    }
 }
 
 #[cfg(test)]
@@ -222,6 +219,8 @@ mod tests {
     use crate::assert_render_snapshot;
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
     use crate::theme::PRIMARY_LIGHT;
+    use crate::widget::Flex;
+    use crate::WidgetId;
 
     #[test]
     fn simple_button() {
@@ -242,6 +241,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn disabled_button() {
+        let button_id = WidgetId::next();
+        let button = WidgetPod::new_with_id(Button::new("Hello"), button_id).with_disabled(true);
+        let widget = Flex::row().with_child_pod(button.boxed());
+
+        let mut harness = TestHarness::create(widget);
+        assert!(harness.get_widget(button_id).state().is_disabled());
+        harness.mouse_click_on(button_id);
+        assert_eq!(harness.pop_action(), None);
+
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            flex.child_mut(0).unwrap().ctx.set_disabled(false);
+        });
+        assert!(!harness.get_widget(button_id).state().is_disabled());
+        harness.mouse_click_on(button_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, button_id))
+        );
+    }
+
     #[test]
     fn edit_button() {
         let image_1 = {
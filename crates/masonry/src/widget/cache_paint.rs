@@ -0,0 +1,145 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which draws its child from a cached image.
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A widget which draws its child to an image, and draws that image on later
+/// frames until a widget in the child's subtree is painted again.
+///
+/// The renderer draws the whole window on every frame, so this saves drawing
+/// expensive content which rarely changes, such as rendered markdown or a chart,
+/// while something else is animated. Content which changes often shouldn't be
+/// cached, as it is then drawn both to the window and to a new image.
+///
+/// Only what the child paints inside its layout rect is shown, and its overlays
+/// aren't cached.
+pub struct CachePaint {
+    child: WidgetPod<Box<dyn Widget>>,
+}
+
+impl CachePaint {
+    pub fn new(child: impl Widget + 'static) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed())
+    }
+
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
+        CachePaint { child }
+    }
+}
+
+impl WidgetMut<'_, CachePaint> {
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl Widget for CachePaint {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let child = &mut self.child;
+        ctx.paint_cached(scene, |ctx, scene| child.paint(ctx, scene));
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use vello::peniko::{Format, Image};
+
+    use super::*;
+    use crate::paint_scene_helpers::fill_color;
+    use crate::testing::{ModularWidget, TestHarness};
+    use crate::widget::Flex;
+    use crate::Color;
+
+    #[test]
+    fn content_is_rasterized_until_repainted() {
+        let paints = Rc::new(Cell::new(0));
+        let counted_paints = paints.clone();
+        let content = ModularWidget::new(())
+            .layout_fn(|_, _, _| Size::new(100.0, 40.0))
+            .paint_fn(move |_, ctx, scene| {
+                counted_paints.set(counted_paints.get() + 1);
+                fill_color(scene, &ctx.size().to_rect(), Color::WHITE);
+            });
+        let widget = Flex::column().with_child(CachePaint::new(content));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+        harness.redraw();
+
+        let rasterized = Cell::new(Vec::new());
+        let rasterize = |_: &Scene, (width, height): (u32, u32)| {
+            let mut sizes = rasterized.take();
+            sizes.push((width, height));
+            rasterized.set(sizes);
+            let pixels = vec![0; (width * height * 4) as usize];
+            Some(Image::new(pixels.into(), Format::Rgba8, width, height))
+        };
+        harness.fill_raster_cache(rasterize);
+        assert_eq!(rasterized.take(), [(100, 40)]);
+
+        // The cache is painted again to draw the image, without repainting the content
+        harness.redraw();
+        assert_eq!(paints.get(), 1);
+        harness.fill_raster_cache(rasterize);
+        assert!(rasterized.take().is_empty());
+
+        // Once the content is painted again, it's drawn to a new image
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            let mut cache = flex.child_mut(0).unwrap();
+            let mut cache = cache.downcast::<CachePaint>();
+            cache.child_mut().ctx.request_paint();
+        });
+        harness.redraw();
+        assert_eq!(paints.get(), 2);
+        harness.fill_raster_cache(rasterize);
+        assert_eq!(rasterized.take(), [(100, 40)]);
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which paints with a closure.
+
+use accesskit::Role;
+use kurbo::Affine;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+
+use crate::widget::WidgetMut;
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+type PaintFn = Box<dyn Fn(&mut Scene, Size)>;
+
+/// A widget which fills the space it is given by painting it with a closure, for
+/// drawing which no other widget does.
+///
+/// The closure is called with the scene to paint into, in the canvas's own
+/// coordinates, and the canvas's size. What it paints outside of the canvas is
+/// clipped. The canvas is as big as its constraints allow, or as small as they
+/// allow in directions in which they are unbounded.
+pub struct Canvas {
+    paint: PaintFn,
+}
+
+impl Canvas {
+    pub fn new(paint: impl Fn(&mut Scene, Size) + 'static) -> Self {
+        Canvas {
+            paint: Box::new(paint),
+        }
+    }
+}
+
+impl WidgetMut<'_, Canvas> {
+    /// Replace the closure, and paint the canvas again with it.
+    pub fn set_paint(&mut self, paint: impl Fn(&mut Scene, Size) + 'static) {
+        self.widget.paint = Box::new(paint);
+        self.ctx.request_paint();
+    }
+}
+
+impl Widget for Canvas {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            bc.min().width
+        };
+        let height = if bc.is_height_bounded() {
+            bc.max().height
+        } else {
+            bc.min().height
+        };
+        let size = Size::new(width, height);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let clip_rect = ctx.size().to_rect();
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+        (self.paint)(scene, ctx.size());
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Canvas
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Canvas")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn paints_with_its_size() {
+        let painted = Rc::new(Cell::new(None));
+        let canvas = Canvas::new({
+            let painted = painted.clone();
+            move |_, size| painted.set(Some(size))
+        });
+        let mut harness = TestHarness::create_with_size(canvas, Size::new(60.0, 40.0));
+        let _ = harness.render();
+        assert_eq!(painted.get(), Some(Size::new(60.0, 40.0)));
+
+        painted.set(None);
+        harness.edit_root_widget(|mut canvas| {
+            canvas.downcast::<Canvas>().set_paint(|_, _| {});
+        });
+        let _ = harness.render();
+        assert_eq!(painted.get(), None);
+        assert_eq!(Rc::strong_count(&painted), 1);
+    }
+}
@@ -10,18 +10,21 @@ use tracing::{trace, trace_span, Span};
 use vello::Scene;
 
 use crate::action::Action;
+use crate::feedback::{HapticKind, SoundKind};
 use crate::kurbo::{BezPath, Cap, Join, Size};
 use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
 use crate::text2::TextStorage;
-use crate::widget::{Label, WidgetMut, WidgetRef};
+use crate::widget::{Label, WidgetMut};
 use crate::{
-    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetPod,
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, Interactivity, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
+    WidgetPod,
 };
 
 /// A checkbox that can be toggled.
 pub struct Checkbox {
     checked: bool,
+    feedback: bool,
     label: WidgetPod<Label>,
 }
 
@@ -30,6 +33,7 @@ impl Checkbox {
     pub fn new(checked: bool, text: impl Into<ArcStr>) -> Checkbox {
         Checkbox {
             checked,
+            feedback: false,
             label: WidgetPod::new(Label::new(text)),
         }
     }
@@ -38,9 +42,26 @@ impl Checkbox {
     pub fn from_label(checked: bool, label: Label) -> Checkbox {
         Checkbox {
             checked,
+            feedback: false,
             label: WidgetPod::new(label),
         }
     }
+
+    /// Builder-style method to tick and click when the checkbox is toggled, on
+    /// platforms where the app plays [feedback](crate::feedback).
+    pub fn with_feedback(mut self, feedback: bool) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    fn toggle(&mut self, ctx: &mut EventCtx) {
+        self.checked = !self.checked;
+        ctx.submit_action(Action::CheckboxChecked(self.checked));
+        if self.feedback {
+            ctx.trigger_haptic(HapticKind::Tick);
+            ctx.play_system_sound(SoundKind::Click);
+        }
+    }
 }
 
 impl WidgetMut<'_, Checkbox> {
@@ -49,6 +70,10 @@ impl WidgetMut<'_, Checkbox> {
         self.ctx.request_paint();
     }
 
+    pub fn set_feedback(&mut self, feedback: bool) {
+        self.widget.feedback = feedback;
+    }
+
     /// Set the text.
     ///
     /// We enforce this to be an `ArcStr` to make the allocation explicit.
@@ -74,8 +99,7 @@ impl Widget for Checkbox {
             PointerEvent::PointerUp(_, _) => {
                 if ctx.is_active() && !ctx.is_disabled() {
                     if ctx.is_hot() {
-                        self.checked = !self.checked;
-                        ctx.submit_action(Action::CheckboxChecked(self.checked));
+                        self.toggle(ctx);
                         trace!("Checkbox {:?} released", ctx.widget_id());
                     }
                     ctx.request_paint();
@@ -95,8 +119,7 @@ impl Widget for Checkbox {
         if event.target == ctx.widget_id() {
             match event.action {
                 accesskit::Action::Default => {
-                    self.checked = !self.checked;
-                    ctx.submit_action(Action::CheckboxChecked(self.checked));
+                    self.toggle(ctx);
                     ctx.request_paint();
                 }
                 _ => {}
@@ -125,7 +148,8 @@ impl Widget for Checkbox {
             check_size.max(label_size.height),
         );
         let our_size = bc.constrain(desired_size);
-        let baseline = self.label.baseline_offset() + (our_size.height - label_size.height);
+        let baseline =
+            ctx.child_baseline_offset(&self.label) + (our_size.height - label_size.height);
         ctx.set_baseline_offset(baseline);
         trace!("Computed layout: size={}, baseline={}", our_size, baseline);
         our_size
@@ -186,12 +210,16 @@ impl Widget for Checkbox {
         self.label.paint(ctx, scene);
     }
 
+    fn interactivity(&self) -> Interactivity {
+        Interactivity::Clickable
+    }
+
     fn accessibility_role(&self) -> Role {
         Role::CheckBox
     }
 
     fn accessibility(&mut self, ctx: &mut AccessCtx) {
-        let _name = self.label.widget().text().as_str().to_string();
+        let _name = ctx.get_ref(&self.label).text().as_str().to_string();
         // We may want to add a name if it doesn't interfere with the child label
         // ctx.current_node().set_name(name);
         if self.checked {
@@ -207,8 +235,8 @@ impl Widget for Checkbox {
         self.label.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        smallvec![self.label.as_dyn()]
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.label.id()]
     }
 
     fn make_trace_span(&self) -> Span {
@@ -216,11 +244,7 @@ impl Widget for Checkbox {
     }
 
     fn get_debug_text(&self) -> Option<String> {
-        Some(format!(
-            "[{}] {}",
-            if self.checked { "X" } else { " " },
-            self.label.as_ref().text().as_str()
-        ))
+        Some(format!("[{}]", if self.checked { "X" } else { " " }))
     }
 }
 
@@ -261,6 +285,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn checkbox_feedback_is_opt_in() {
+        use crate::feedback::Feedback;
+
+        let [quiet_id, ticking_id] = widget_ids();
+        let widget = crate::widget::Flex::row()
+            .with_child(Checkbox::new(false, "Quiet").with_id(quiet_id))
+            .with_child(
+                Checkbox::new(false, "Ticking")
+                    .with_feedback(true)
+                    .with_id(ticking_id),
+            );
+        let mut harness = TestHarness::create(widget);
+
+        harness.mouse_click_on(quiet_id);
+        assert_eq!(harness.pop_feedback(), None);
+
+        harness.mouse_click_on(ticking_id);
+        assert_eq!(
+            harness.pop_feedback(),
+            Some(Feedback::Haptic(HapticKind::Tick))
+        );
+        assert_eq!(
+            harness.pop_feedback(),
+            Some(Feedback::Sound(SoundKind::Click))
+        );
+        assert_eq!(harness.pop_feedback(), None);
+    }
+
     #[test]
     fn edit_checkbox() {
         let image_1 = {
@@ -0,0 +1,131 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which makes its child clickable.
+
+use accesskit::{DefaultActionVerb, Role};
+use smallvec::{smallvec, SmallVec};
+use vello::Scene;
+
+use crate::action::Action;
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A widget which emits [`Action::ButtonPressed`] when its child is clicked.
+///
+/// Like a [`Button`](crate::widget::Button), a click is a press and a release of
+/// the pointer over the widget, but nothing is painted besides the child, so any
+/// content can be made clickable, such as a card or a row of a list.
+pub struct Clickable {
+    child: WidgetPod<Box<dyn Widget>>,
+}
+
+impl Clickable {
+    pub fn new(child: impl Widget + 'static) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed())
+    }
+
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
+        Clickable { child }
+    }
+}
+
+impl WidgetMut<'_, Clickable> {
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl Widget for Clickable {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+        match event {
+            PointerEvent::PointerDown(_, _) if !ctx.is_disabled() => {
+                ctx.set_active(true);
+            }
+            PointerEvent::PointerUp(_, _) => {
+                if ctx.is_active() && ctx.is_hot() && !ctx.is_disabled() {
+                    ctx.submit_action(Action::ButtonPressed);
+                }
+                ctx.set_active(false);
+            }
+            PointerEvent::PointerLeave(_) => {
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target == ctx.widget_id() && event.action == accesskit::Action::Default {
+            ctx.submit_action(Action::ButtonPressed);
+        }
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node()
+            .set_default_action_verb(DefaultActionVerb::Click);
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::{Flex, Label};
+
+    #[test]
+    fn clicks_on_the_child() {
+        let [clickable_id, label_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child(Clickable::new(Label::new("Open").with_id(label_id)).with_id(clickable_id));
+        let mut harness = TestHarness::create(widget);
+        assert_eq!(harness.pop_action(), None);
+
+        harness.mouse_click_on(label_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, clickable_id))
+        );
+
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            flex.child_mut(0).unwrap().ctx.set_disabled(true);
+        });
+        harness.mouse_click_on(label_id);
+        assert_eq!(harness.pop_action(), None);
+    }
+}
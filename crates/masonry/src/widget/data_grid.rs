@@ -0,0 +1,1197 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A grid of cells with a header row and frozen columns.
+
+use accesskit::Role;
+use instant::Instant;
+use kurbo::{Affine, Line, Rect, Size, Vec2};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+use winit::event::Ime;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::action::Action;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text2::TextStorage;
+use crate::widget::{Label, Textbox, WidgetMut, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+
+type Cell = WidgetPod<Box<dyn Widget>>;
+
+/// A scrollable grid of cells, with a header row which stays at the top and
+/// optionally some columns which stay on the left.
+///
+/// Every row has the same height, and every column has a fixed width. The cells are
+/// laid out and painted only while they are at least partly visible, so large grids
+/// stay cheap to scroll.
+///
+/// Cells can be replaced or edited in place with [`WidgetMut::set_cell`] and
+/// [`WidgetMut::edit_cell`].
+///
+/// ## Editing
+///
+/// If the grid is [editable](Self::with_editable), the user can edit cells which are
+/// [`Label`]s. Clicking a cell makes it the current cell, which can also be moved with
+/// the arrow keys. Double-clicking it or pressing <kbd>F2</kbd> replaces it with a
+/// [`Textbox`] holding its text. Then:
+///
+/// - <kbd>Enter</kbd> commits the edit, setting the text of the label and emitting
+///   [`Action::CellEdited`] if the text changed.
+/// - <kbd>Escape</kbd> cancels the edit, restoring the label.
+/// - <kbd>Tab</kbd> (or <kbd>Shift</kbd>+<kbd>Tab</kbd>) commits the edit and starts
+///   editing the next (or previous) cell.
+/// - Clicking another cell commits the edit and makes that cell current.
+///
+/// ## Type-ahead
+///
+/// While the grid has focus and no cell is being edited, typing makes current the
+/// next row whose label in the current column starts with the typed text, ignoring
+/// case, like a desktop list box. Characters typed within the
+/// [type-ahead timeout](crate::interaction::InteractionSettings::type_ahead_timeout) of each
+/// other are searched for together, and typing the same character again moves to
+/// the next row starting with it.
+pub struct DataGrid {
+    column_widths: Vec<f64>,
+    row_height: f64,
+    frozen_columns: usize,
+    has_header: bool,
+    /// The number of rows in the body, which the body panes hold
+    row_count: usize,
+    scroll_offset: Vec2,
+    editable: bool,
+    /// The row and column of the cell which the keyboard acts on
+    current: Option<(usize, usize)>,
+    /// The row and column of the cell being edited, if any
+    editing: Option<(usize, usize)>,
+    /// The lowercased text typed to jump to a row
+    typed: String,
+    /// When the last character of `typed` was typed
+    last_typed: Option<Instant>,
+    /// The text box which replaces a cell while it is edited, stashed otherwise
+    editor: WidgetPod<Textbox>,
+    // The header and the rows are split between a frozen and a scrolling pane each,
+    // so that each part can be clipped (and hit-tested) on its own.
+    frozen_header: WidgetPod<GridPane>,
+    header: WidgetPod<GridPane>,
+    frozen_body: WidgetPod<GridPane>,
+    body: WidgetPod<GridPane>,
+}
+
+/// A part of a [`DataGrid`], which clips its cells and scrolls them by `offset`.
+struct GridPane {
+    rows: Vec<Vec<Cell>>,
+    column_widths: Vec<f64>,
+    row_height: f64,
+    offset: Vec2,
+    /// The row and column of the cell which is hidden by the grid's editor
+    hidden: Option<(usize, usize)>,
+}
+
+impl DataGrid {
+    /// Create a new, empty grid, with columns of the given widths.
+    pub fn new(column_widths: impl IntoIterator<Item = f64>) -> Self {
+        Self::with_editor_pod(column_widths, WidgetPod::new(Textbox::new("")))
+    }
+
+    /// Create a new, empty grid, using an existing text box to edit cells.
+    pub fn with_editor_pod(
+        column_widths: impl IntoIterator<Item = f64>,
+        editor: WidgetPod<Textbox>,
+    ) -> Self {
+        let editor = editor.with_stashed(true);
+        let column_widths: Vec<f64> = column_widths.into_iter().collect();
+        let row_height = theme::BORDERED_WIDGET_HEIGHT;
+        let pane = |column_widths: &[f64]| {
+            WidgetPod::new(GridPane {
+                rows: Vec::new(),
+                column_widths: column_widths.to_vec(),
+                row_height,
+                offset: Vec2::ZERO,
+                hidden: None,
+            })
+        };
+        DataGrid {
+            frozen_header: pane(&[]),
+            header: pane(&column_widths),
+            frozen_body: pane(&[]),
+            body: pane(&column_widths),
+            column_widths,
+            row_height,
+            frozen_columns: 0,
+            has_header: false,
+            row_count: 0,
+            scroll_offset: Vec2::ZERO,
+            editable: false,
+            current: None,
+            editing: None,
+            typed: String::new(),
+            last_typed: None,
+            editor,
+        }
+    }
+
+    /// Builder-style method to set the header cells, one per column.
+    pub fn with_header(mut self, cells: impl IntoIterator<Item = impl Widget>) -> Self {
+        let (frozen, scrolling) = self.split_row(boxed_cells(cells));
+        self.frozen_header.created_widget_mut().rows = vec![frozen];
+        self.header.created_widget_mut().rows = vec![scrolling];
+        self.has_header = true;
+        self
+    }
+
+    /// Builder-style method to add a row of cells, one per column.
+    pub fn with_row(self, cells: impl IntoIterator<Item = impl Widget>) -> Self {
+        self.with_row_pods(boxed_cells(cells))
+    }
+
+    /// Builder-style variant of `with_row`, that takes the cells' pods.
+    ///
+    /// Useful for unit tests.
+    pub fn with_row_pods(mut self, cells: impl IntoIterator<Item = Cell>) -> Self {
+        let (frozen, scrolling) = self.split_row(cells.into_iter().collect());
+        self.frozen_body.created_widget_mut().rows.push(frozen);
+        self.body.created_widget_mut().rows.push(scrolling);
+        self.row_count += 1;
+        self
+    }
+
+    /// Builder-style method to keep the first `count` columns on the left when
+    /// scrolling horizontally.
+    pub fn with_frozen_columns(mut self, count: usize) -> Self {
+        let count = count.min(self.column_widths.len());
+        let header_rows = self.take_rows(true);
+        let body_rows = self.take_rows(false);
+        self.row_count = 0;
+        self.frozen_columns = count;
+        self.frozen_header.created_widget_mut().column_widths =
+            self.column_widths[..count].to_vec();
+        self.header.created_widget_mut().column_widths = self.column_widths[count..].to_vec();
+        self.frozen_body.created_widget_mut().column_widths = self.column_widths[..count].to_vec();
+        self.body.created_widget_mut().column_widths = self.column_widths[count..].to_vec();
+        for row in header_rows {
+            let (frozen, scrolling) = self.split_row(row);
+            self.frozen_header.created_widget_mut().rows.push(frozen);
+            self.header.created_widget_mut().rows.push(scrolling);
+        }
+        for row in body_rows {
+            self = self.with_row_pods(row);
+        }
+        self
+    }
+
+    /// Builder-style method to set the height of every row, including the header.
+    pub fn with_row_height(mut self, row_height: f64) -> Self {
+        self.row_height = row_height;
+        for pane in self.panes_mut() {
+            pane.created_widget_mut().row_height = row_height;
+        }
+        self
+    }
+
+    /// Builder-style method to let the user edit cells which are [`Label`]s.
+    ///
+    /// See the [type documentation](Self#editing) for how cells are edited.
+    pub fn with_editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Split a row into its frozen and scrolling cells.
+    fn split_row(&self, mut cells: Vec<Cell>) -> (Vec<Cell>, Vec<Cell>) {
+        let scrolling = cells.split_off(self.frozen_columns.min(cells.len()));
+        (cells, scrolling)
+    }
+
+    /// Take the rows of the header or of the body out of their panes, joining the
+    /// frozen and scrolling cells back together.
+    fn take_rows(&mut self, header: bool) -> Vec<Vec<Cell>> {
+        let (frozen, scrolling) = if header {
+            (&mut self.frozen_header, &mut self.header)
+        } else {
+            (&mut self.frozen_body, &mut self.body)
+        };
+        let frozen = std::mem::take(&mut frozen.created_widget_mut().rows);
+        let scrolling = std::mem::take(&mut scrolling.created_widget_mut().rows);
+        frozen
+            .into_iter()
+            .zip(scrolling)
+            .map(|(mut row, rest)| {
+                row.extend(rest);
+                row
+            })
+            .collect()
+    }
+
+    fn panes_mut(&mut self) -> [&mut WidgetPod<GridPane>; 4] {
+        [
+            &mut self.frozen_header,
+            &mut self.header,
+            &mut self.frozen_body,
+            &mut self.body,
+        ]
+    }
+}
+
+fn boxed_cells(cells: impl IntoIterator<Item = impl Widget>) -> Vec<Cell> {
+    cells
+        .into_iter()
+        .map(|cell| WidgetPod::new(Box::new(cell) as Box<dyn Widget>))
+        .collect()
+}
+
+impl DataGrid {
+    /// The number of rows, not counting the header.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// The number of columns.
+    pub fn column_count(&self) -> usize {
+        self.column_widths.len()
+    }
+
+    /// How far the grid is scrolled, in logical pixels.
+    pub fn scroll_offset(&self) -> Vec2 {
+        self.scroll_offset
+    }
+
+    /// Whether the user can edit cells.
+    pub fn is_editable(&self) -> bool {
+        self.editable
+    }
+
+    /// The row and column of the cell which the keyboard acts on, if any.
+    pub fn current_cell(&self) -> Option<(usize, usize)> {
+        self.current
+    }
+
+    /// The row and column of the cell being edited, if any.
+    pub fn editing_cell(&self) -> Option<(usize, usize)> {
+        self.editing
+    }
+
+    /// The body pane holding column `col`, and the column's index in that pane.
+    fn body_pane(&self, col: usize) -> (&WidgetPod<GridPane>, usize) {
+        if col < self.frozen_columns {
+            (&self.frozen_body, col)
+        } else {
+            (&self.body, col - self.frozen_columns)
+        }
+    }
+
+    fn body_pane_mut(&mut self, col: usize) -> (&mut WidgetPod<GridPane>, usize) {
+        if col < self.frozen_columns {
+            (&mut self.frozen_body, col)
+        } else {
+            (&mut self.body, col - self.frozen_columns)
+        }
+    }
+
+    fn header_height(&self) -> f64 {
+        if self.has_header {
+            self.row_height
+        } else {
+            0.0
+        }
+    }
+
+    fn frozen_width(&self) -> f64 {
+        self.column_widths[..self.frozen_columns].iter().sum()
+    }
+
+    fn content_size(&self) -> Size {
+        Size::new(
+            self.column_widths.iter().sum(),
+            self.header_height() + self.row_count() as f64 * self.row_height,
+        )
+    }
+
+    /// The rectangle of the cell at `row` and `col`, in the grid's coordinates.
+    fn cell_rect(&self, row: usize, col: usize) -> Rect {
+        let mut x: f64 = self.column_widths[..col].iter().sum();
+        if col >= self.frozen_columns {
+            x -= self.scroll_offset.x;
+        }
+        let y = self.header_height() + row as f64 * self.row_height - self.scroll_offset.y;
+        let width = self.column_widths[col];
+        Rect::new(x, y, x + width, y + self.row_height)
+    }
+
+    /// Scroll the least possible for the cell at `row` and `col` to be visible.
+    fn scroll_to_cell(&mut self, size: Size, row: usize, col: usize) -> bool {
+        let view = Rect::new(
+            self.frozen_width(),
+            self.header_height(),
+            size.width,
+            size.height,
+        );
+        let rect = self.cell_rect(row, col);
+        let mut offset = self.scroll_offset;
+        if col >= self.frozen_columns {
+            if rect.x0 < view.x0 {
+                offset.x -= view.x0 - rect.x0;
+            } else if rect.x1 > view.x1 {
+                offset.x += (rect.x1 - view.x1).min(rect.x0 - view.x0);
+            }
+        }
+        if rect.y0 < view.y0 {
+            offset.y -= view.y0 - rect.y0;
+        } else if rect.y1 > view.y1 {
+            offset.y += (rect.y1 - view.y1).min(rect.y0 - view.y0);
+        }
+        self.set_scroll_offset_raw(size, offset)
+    }
+
+    /// The cell after (or before) the one at `row` and `col`, going through the
+    /// rows in order.
+    fn next_cell(&self, row: usize, col: usize, backwards: bool) -> Option<(usize, usize)> {
+        let columns = self.column_count();
+        let ix = row * columns + col;
+        let next = if backwards {
+            ix.checked_sub(1)?
+        } else {
+            ix + 1
+        };
+        (next < self.row_count() * columns).then_some((next / columns, next % columns))
+    }
+
+    /// The body cell under the pointer, if any.
+    fn hot_cell(&self, ctx: &EventCtx) -> Option<(usize, usize)> {
+        [(&self.frozen_body, 0), (&self.body, self.frozen_columns)]
+            .into_iter()
+            .find_map(|(pane, first_col)| {
+                let pane = ctx.get_ref(pane);
+                pane.deref()
+                    .rows
+                    .iter()
+                    .enumerate()
+                    .find_map(|(row, cells)| {
+                        let col = cells.iter().position(|cell| {
+                            let state = pane.get_child(cell).state();
+                            state.is_hot && !state.is_stashed
+                        })?;
+                        Some((row, first_col + col))
+                    })
+            })
+    }
+
+    /// The text of the label at `row` and `col`, if that cell is a label.
+    fn label_text(&self, ctx: &EventCtx, row: usize, col: usize) -> Option<String> {
+        let (pane, col) = self.body_pane(col);
+        let cell = pane_cell(ctx.get_ref(pane), row, col)?;
+        let label = cell.downcast::<Label>()?;
+        Some(label.text().as_str().to_string())
+    }
+
+    /// Replace the cell at `row` and `col` with the editor, if the cell is a label.
+    fn start_editing(&mut self, ctx: &mut EventCtx, row: usize, col: usize) -> bool {
+        if !self.editable {
+            return false;
+        }
+        let Some(text) = self.label_text(ctx, row, col) else {
+            return false;
+        };
+        trace!(
+            "DataGrid {:?} editing cell {}, {}",
+            ctx.widget_id(),
+            row,
+            col
+        );
+        self.editing = Some((row, col));
+        self.current = Some((row, col));
+        // Leave the caret at the end of the text, ready to type
+        ctx.get_mut(&mut self.editor).replace_text(text);
+        ctx.set_stashed(&mut self.editor, false);
+        ctx.set_focus(self.editor.id());
+        ctx.request_layout();
+        true
+    }
+
+    /// Hide the editor, and if `commit` is true, set the label to its text.
+    fn finish_editing(&mut self, ctx: &mut EventCtx, commit: bool) {
+        let Some((row, col)) = self.editing.take() else {
+            return;
+        };
+        ctx.set_stashed(&mut self.editor, true);
+        ctx.request_layout();
+        if !commit {
+            return;
+        }
+
+        let text = ctx.get_ref(&self.editor).text().to_string();
+        let (pane, pane_col) = self.body_pane_mut(col);
+        let changed = ctx.get_mut(pane).set_label_text(row, pane_col, &text);
+        if changed {
+            trace!(
+                "DataGrid {:?} edited cell {}, {}",
+                ctx.widget_id(),
+                row,
+                col
+            );
+            ctx.submit_action(Action::CellEdited(row, col, text));
+        }
+    }
+
+    /// Handle a key which moves between cells or edits them, returning whether it was used.
+    fn on_key(&mut self, ctx: &mut EventCtx, key: &Key, mods: ModifiersState) -> bool {
+        let size = ctx.size();
+        let target = match (key, self.editing, self.current) {
+            (Key::Named(NamedKey::Enter), Some(_), _) => {
+                self.finish_editing(ctx, true);
+                ctx.request_focus();
+                return true;
+            }
+            (Key::Named(NamedKey::Escape), Some(_), _) => {
+                self.finish_editing(ctx, false);
+                ctx.request_focus();
+                return true;
+            }
+            (Key::Named(NamedKey::Tab), Some((row, col)), _) => {
+                self.finish_editing(ctx, true);
+                let next = self.next_cell(row, col, mods.shift_key());
+                let Some((row, col)) = next else {
+                    ctx.request_focus();
+                    return true;
+                };
+                if self.scroll_to_cell(size, row, col) {
+                    ctx.request_layout();
+                }
+                if !self.start_editing(ctx, row, col) {
+                    self.current = Some((row, col));
+                    ctx.request_focus();
+                }
+                return true;
+            }
+            (_, Some(_), _) => return false,
+            (Key::Named(NamedKey::F2), None, Some((row, col))) => {
+                if self.scroll_to_cell(size, row, col) {
+                    ctx.request_layout();
+                }
+                return self.start_editing(ctx, row, col);
+            }
+            (Key::Named(NamedKey::ArrowUp), None, Some((row, col))) => {
+                (row.checked_sub(1), Some(col))
+            }
+            (Key::Named(NamedKey::ArrowDown), None, Some((row, col))) => (
+                Some(row + 1).filter(|&row| row < self.row_count()),
+                Some(col),
+            ),
+            (Key::Named(NamedKey::ArrowLeft), None, Some((row, col))) => {
+                (Some(row), col.checked_sub(1))
+            }
+            (Key::Named(NamedKey::ArrowRight), None, Some((row, col))) => (
+                Some(row),
+                Some(col + 1).filter(|&col| col < self.column_count()),
+            ),
+            _ => return false,
+        };
+        if let (Some(row), Some(col)) = target {
+            self.current = Some((row, col));
+            if self.scroll_to_cell(size, row, col) {
+                ctx.request_layout();
+            }
+        }
+        true
+    }
+
+    /// Add `text` to the typed prefix, and make current the next row whose label in
+    /// the current column starts with it.
+    fn type_ahead(&mut self, ctx: &mut EventCtx, text: &str) -> bool {
+        if text.chars().all(char::is_control) {
+            return false;
+        }
+        let now = ctx.now();
+        let timeout = ctx.interaction_settings().type_ahead_timeout;
+        if self
+            .last_typed
+            .is_some_and(|last| now.duration_since(last) > timeout)
+        {
+            self.typed.clear();
+        }
+        self.last_typed = Some(now);
+        self.typed.push_str(&text.to_lowercase());
+
+        let rows = self.row_count();
+        let (current_row, col) = match self.current {
+            Some((row, col)) => (Some(row), col),
+            None => (None, 0),
+        };
+        // Typing the same character again cycles through the rows starting with it,
+        // while a longer prefix can still match the current row
+        let mut chars = self.typed.chars();
+        let first = chars.next().unwrap_or_default();
+        let (prefix, start) = if chars.all(|c| c == first) {
+            (
+                &self.typed[..first.len_utf8()],
+                current_row.map_or(0, |row| row + 1),
+            )
+        } else {
+            (self.typed.as_str(), current_row.unwrap_or(0))
+        };
+        let found = (0..rows).map(|i| (start + i) % rows).find(|&row| {
+            self.label_text(ctx, row, col)
+                .is_some_and(|text| text.to_lowercase().starts_with(prefix))
+        });
+        let Some(row) = found else {
+            return false;
+        };
+        self.current = Some((row, col));
+        if self.scroll_to_cell(ctx.size(), row, col) {
+            ctx.request_layout();
+        }
+        true
+    }
+
+    /// Set the scroll offset, keeping the content within a grid of the given size.
+    fn set_scroll_offset_raw(&mut self, size: Size, offset: Vec2) -> bool {
+        let max = self.content_size() - size;
+        let offset = Vec2::new(
+            offset.x.min(max.width).max(0.0),
+            offset.y.min(max.height).max(0.0),
+        );
+        let changed = offset != self.scroll_offset;
+        self.scroll_offset = offset;
+        changed
+    }
+}
+
+impl<'w> WidgetRef<'w, DataGrid> {
+    /// The cell at `row` and `col`, if there is one.
+    pub fn cell(&self, row: usize, col: usize) -> Option<WidgetRef<'w, dyn Widget>> {
+        let (pane, col) = self.deref().body_pane(col);
+        pane_cell(self.get_child(pane), row, col)
+    }
+}
+
+/// The cell at `row` and `col` of `pane`.
+fn pane_cell(
+    pane: WidgetRef<'_, GridPane>,
+    row: usize,
+    col: usize,
+) -> Option<WidgetRef<'_, dyn Widget>> {
+    let cell = pane.deref().rows.get(row)?.get(col)?;
+    Some(pane.get_child(cell).as_dyn())
+}
+
+impl WidgetMut<'_, DataGrid> {
+    /// Set whether the user can edit cells.
+    ///
+    /// Making the grid read-only cancels the current edit, if any.
+    pub fn set_editable(&mut self, editable: bool) {
+        self.widget.editable = editable;
+        if !editable {
+            self.cancel_editing();
+        }
+    }
+
+    /// Hide the cell editor, if it is shown, without changing the cell.
+    pub fn cancel_editing(&mut self) {
+        if self.widget.editing.take().is_some() {
+            self.ctx.set_stashed(&mut self.widget.editor, true);
+            self.ctx.request_layout();
+        }
+    }
+
+    pub fn editor_mut(&mut self) -> WidgetMut<'_, Textbox> {
+        self.ctx.get_mut(&mut self.widget.editor)
+    }
+
+    /// Replace the header cells, one per column.
+    ///
+    /// Passing no cells removes the header.
+    pub fn set_header(&mut self, cells: impl IntoIterator<Item = impl Widget>) {
+        let cells = boxed_cells(cells);
+        self.widget.has_header = !cells.is_empty();
+        let (frozen, scrolling) = self.widget.split_row(cells);
+        self.ctx
+            .get_mut(&mut self.widget.frozen_header)
+            .set_rows(vec![frozen]);
+        self.ctx
+            .get_mut(&mut self.widget.header)
+            .set_rows(vec![scrolling]);
+        self.ctx.request_layout();
+    }
+
+    /// Set the width of each column.
+    pub fn set_column_widths(&mut self, column_widths: impl IntoIterator<Item = f64>) {
+        let column_widths: Vec<f64> = column_widths.into_iter().collect();
+        let count = self.widget.frozen_columns.min(column_widths.len());
+        let (frozen, scrolling) = column_widths.split_at(count);
+        for (pane, widths) in self
+            .widget
+            .panes_mut()
+            .into_iter()
+            .zip([frozen, scrolling].repeat(2))
+        {
+            self.ctx.get_mut(pane).widget.column_widths = widths.to_vec();
+        }
+        self.widget.column_widths = column_widths;
+        self.widget.frozen_columns = count;
+        self.ctx.request_layout();
+    }
+
+    /// Set the height of every row, including the header.
+    pub fn set_row_height(&mut self, row_height: f64) {
+        self.widget.row_height = row_height;
+        for pane in self.widget.panes_mut() {
+            self.ctx.get_mut(pane).widget.row_height = row_height;
+        }
+        self.ctx.request_layout();
+    }
+
+    /// Add a row of cells, one per column, at the end of the grid.
+    pub fn add_row(&mut self, cells: impl IntoIterator<Item = impl Widget>) {
+        let ix = self.widget.row_count();
+        self.insert_row(ix, cells);
+    }
+
+    /// Insert a row of cells, one per column, before the row at `ix`.
+    ///
+    /// This cancels the current edit, if any.
+    pub fn insert_row(&mut self, ix: usize, cells: impl IntoIterator<Item = impl Widget>) {
+        self.cancel_editing();
+        self.widget.current = None;
+        let (frozen, scrolling) = self.widget.split_row(boxed_cells(cells));
+        self.ctx
+            .get_mut(&mut self.widget.frozen_body)
+            .insert_row(ix, frozen);
+        self.ctx
+            .get_mut(&mut self.widget.body)
+            .insert_row(ix, scrolling);
+        self.widget.row_count += 1;
+        self.ctx.request_layout();
+    }
+
+    /// Remove the row at `ix`.
+    ///
+    /// This cancels the current edit, if any.
+    pub fn remove_row(&mut self, ix: usize) {
+        self.cancel_editing();
+        self.widget.current = None;
+        self.ctx
+            .get_mut(&mut self.widget.frozen_body)
+            .remove_row(ix);
+        self.ctx.get_mut(&mut self.widget.body).remove_row(ix);
+        self.widget.row_count -= 1;
+        self.ctx.request_layout();
+    }
+
+    /// Replace the cell at `row` and `col`.
+    ///
+    /// This cancels editing the cell, if it was being edited.
+    pub fn set_cell(&mut self, row: usize, col: usize, cell: impl Widget) {
+        if self.widget.editing == Some((row, col)) {
+            self.cancel_editing();
+        }
+        let cell = WidgetPod::new(Box::new(cell) as Box<dyn Widget>);
+        let (pane, col) = self.widget.body_pane_mut(col);
+        let mut pane = self.ctx.get_mut(pane);
+        let old_cell = std::mem::replace(&mut pane.widget.rows[row][col], cell);
+        pane.ctx.remove_child(old_cell);
+    }
+
+    /// Run `f` on the cell at `row` and `col`, to change it in place.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if there is no such cell.
+    pub fn edit_cell<R>(
+        &mut self,
+        row: usize,
+        col: usize,
+        f: impl FnOnce(WidgetMut<'_, Box<dyn Widget>>) -> R,
+    ) -> R {
+        let (pane, col) = self.widget.body_pane_mut(col);
+        let mut pane = self.ctx.get_mut(pane);
+        f(pane.ctx.get_mut(&mut pane.widget.rows[row][col]))
+    }
+
+    /// Scroll the grid, so that `offset` is at the top left of the scrolling part.
+    ///
+    /// The offset is clamped so that the grid doesn't scroll past its content.
+    pub fn set_scroll_offset(&mut self, offset: Vec2) {
+        let size = self.ctx.size();
+        if self.widget.set_scroll_offset_raw(size, offset) {
+            self.ctx.request_layout();
+        }
+    }
+}
+
+impl WidgetMut<'_, GridPane> {
+    fn set_rows(&mut self, rows: Vec<Vec<Cell>>) {
+        for cell in std::mem::replace(&mut self.widget.rows, rows)
+            .into_iter()
+            .flatten()
+        {
+            self.ctx.remove_child(cell);
+        }
+        self.ctx.children_changed();
+    }
+
+    fn insert_row(&mut self, ix: usize, cells: Vec<Cell>) {
+        self.widget.rows.insert(ix, cells);
+        self.ctx.children_changed();
+    }
+
+    fn remove_row(&mut self, ix: usize) {
+        for cell in self.widget.rows.remove(ix) {
+            self.ctx.remove_child(cell);
+        }
+    }
+
+    /// Set the text of the label at `row` and `col`, returning whether it changed.
+    fn set_label_text(&mut self, row: usize, col: usize, text: &str) -> bool {
+        let mut cell = self.ctx.get_mut(&mut self.widget.rows[row][col]);
+        let mut label = cell.downcast::<Label>();
+        if label.text().as_str() == text {
+            return false;
+        }
+        label.set_text(text.to_string());
+        true
+    }
+}
+
+impl Widget for DataGrid {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        if let PointerEvent::MouseWheel(delta, _) = event {
+            let offset = self.scroll_offset + Vec2::new(delta.x, delta.y);
+            if self.set_scroll_offset_raw(ctx.size(), offset) {
+                ctx.request_layout();
+            }
+        }
+
+        for pane in self.panes_mut() {
+            pane.on_pointer_event(ctx, event);
+        }
+        self.editor.on_pointer_event(ctx, event);
+
+        if let PointerEvent::PointerDown(_, state) = event {
+            // The cell being edited is hidden by its editor, so it can't be hot
+            if let Some(cell) = self.hot_cell(ctx) {
+                self.finish_editing(ctx, true);
+                self.current = Some(cell);
+                if state.count < 2 || !self.start_editing(ctx, cell.0, cell.1) {
+                    ctx.request_focus();
+                }
+                ctx.request_paint();
+                ctx.request_accessibility_update();
+            }
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let handled = match event {
+            TextEvent::KeyboardKey(key, mods) => {
+                key.state.is_pressed() && self.on_key(ctx, &key.logical_key, *mods)
+            }
+            TextEvent::Ime(Ime::Commit(text)) => {
+                self.editing.is_none() && self.type_ahead(ctx, text)
+            }
+            _ => false,
+        };
+        if handled {
+            for pane in self.panes_mut() {
+                ctx.skip_child(pane);
+            }
+            ctx.skip_child(&mut self.editor);
+            ctx.set_handled();
+            ctx.request_paint();
+            ctx.request_accessibility_update();
+            return;
+        }
+
+        for pane in self.panes_mut() {
+            pane.on_text_event(ctx, event);
+        }
+        self.editor.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for pane in self.panes_mut() {
+            pane.on_access_event(ctx, event);
+        }
+        self.editor.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            if self.editable {
+                ctx.register_for_focus();
+            }
+        }
+        for pane in self.panes_mut() {
+            pane.lifecycle(ctx, event);
+        }
+        self.editor.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = bc.constrain(self.content_size());
+        self.set_scroll_offset_raw(size, self.scroll_offset);
+
+        let frozen_width = self.frozen_width().min(size.width);
+        let header_height = self.header_height().min(size.height);
+        let scroll = self.scroll_offset;
+        let editing = self.editing;
+        let frozen_columns = self.frozen_columns;
+        let panes = [
+            (
+                &mut self.frozen_header,
+                Rect::new(0.0, 0.0, frozen_width, header_height),
+                Vec2::ZERO,
+            ),
+            (
+                &mut self.header,
+                Rect::new(frozen_width, 0.0, size.width, header_height),
+                Vec2::new(scroll.x, 0.0),
+            ),
+            (
+                &mut self.frozen_body,
+                Rect::new(0.0, header_height, frozen_width, size.height),
+                Vec2::new(0.0, scroll.y),
+            ),
+            (
+                &mut self.body,
+                Rect::new(frozen_width, header_height, size.width, size.height),
+                scroll,
+            ),
+        ];
+        for (ix, (pane, rect, offset)) in panes.into_iter().enumerate() {
+            {
+                let pane = ctx.get_mut(pane);
+                pane.widget.offset = offset;
+                // The frozen body pane is the third, and the scrolling one the fourth
+                pane.widget.hidden = editing.and_then(|(row, col)| match ix {
+                    2 if col < frozen_columns => Some((row, col)),
+                    3 if col >= frozen_columns => Some((row, col - frozen_columns)),
+                    _ => None,
+                });
+            }
+            pane.layout(ctx, &BoxConstraints::tight(rect.size()));
+            ctx.place_child(pane, rect.origin());
+        }
+
+        if let Some((row, col)) = self.editing {
+            let rect = self.cell_rect(row, col);
+            self.editor.layout(ctx, &BoxConstraints::tight(rect.size()));
+            ctx.place_child(&mut self.editor, rect.origin());
+        }
+
+        trace!("Computed layout: size={}, offset={:?}", size, scroll);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let header_height = ctx.child_layout_rect(&self.header).height();
+        let frozen_width = ctx.child_layout_rect(&self.frozen_body).width();
+
+        if header_height > 0.0 {
+            let header_rect = Size::new(size.width, header_height).to_rect();
+            fill_color(scene, &header_rect, theme::BACKGROUND_LIGHT);
+        }
+        for pane in self.panes_mut() {
+            pane.paint(ctx, scene);
+        }
+        if let Some((_, col)) = self.editing {
+            // The editor is clipped like the cell it replaces
+            let clip_rect = ctx.child_layout_rect(self.body_pane(col).0);
+            scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+            self.editor.paint(ctx, scene);
+            scene.pop_layer();
+        }
+
+        if let Some((row, col)) = self.current.filter(|_| ctx.has_focus()) {
+            let (pane, _) = self.body_pane(col);
+            let rect = self
+                .cell_rect(row, col)
+                .intersect(ctx.child_layout_rect(pane));
+            if rect.area() > 0.0 {
+                stroke(scene, &rect.inset(-1.0), theme::PRIMARY_LIGHT, 2.0);
+            }
+        }
+
+        // Separate the parts which don't scroll from the rest
+        if header_height > 0.0 {
+            let line = Line::new((0.0, header_height), (size.width, header_height));
+            let (line, width) = ctx.snap_line(line, 1.0);
+            stroke(scene, &line, theme::BORDER_LIGHT, width);
+        }
+        if frozen_width > 0.0 {
+            let line = Line::new((frozen_width, 0.0), (frozen_width, size.height));
+            let (line, width) = ctx.snap_line(line, 1.0);
+            stroke(scene, &line, theme::BORDER_LIGHT, width);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Grid
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_row_count(self.row_count());
+        ctx.current_node().set_column_count(self.column_count());
+        ctx.current_node().set_scroll_x(self.scroll_offset.x);
+        ctx.current_node().set_scroll_y(self.scroll_offset.y);
+        if let Some((row, col)) = self.current {
+            let (pane, pane_col) = self.body_pane(col);
+            if let Some(cell) = pane_cell(ctx.get_ref(pane), row, pane_col) {
+                let id = cell.id();
+                ctx.current_node().set_active_descendant(id.into());
+            }
+        }
+        for pane in self.panes_mut() {
+            pane.accessibility(ctx);
+        }
+        self.editor.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut children: SmallVec<_> = [
+            &self.frozen_header,
+            &self.header,
+            &self.frozen_body,
+            &self.body,
+        ]
+        .into_iter()
+        .map(|pane| pane.id())
+        .collect();
+        children.push(self.editor.id());
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("DataGrid")
+    }
+}
+
+impl Widget for GridPane {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for cell in self.rows.iter_mut().flatten() {
+            cell.on_pointer_event(ctx, event);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for cell in self.rows.iter_mut().flatten() {
+            cell.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for cell in self.rows.iter_mut().flatten() {
+            cell.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::WidgetAdded = event {
+            // Cells are clipped to the pane, so they can't get pointer events outside of it
+            ctx.register_as_portal();
+        }
+        for cell in self.rows.iter_mut().flatten() {
+            cell.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = bc.max();
+        let visible_rect = size.to_rect();
+        for (row_ix, row) in self.rows.iter_mut().enumerate() {
+            let y = row_ix as f64 * self.row_height - self.offset.y;
+            let mut x = -self.offset.x;
+            for (col_ix, cell) in row.iter_mut().enumerate() {
+                let width = self.column_widths.get(col_ix).copied().unwrap_or(0.0);
+                let cell_rect = Rect::new(x, y, x + width, y + self.row_height);
+                x += width;
+                // Cells which can't be seen are stashed, so that they aren't laid out or painted
+                let visible = cell_rect.intersect(visible_rect).area() > 0.0
+                    && self.hidden != Some((row_ix, col_ix));
+                ctx.set_stashed(cell, !visible);
+                if visible {
+                    cell.layout(ctx, &BoxConstraints::tight(cell_rect.size()));
+                    ctx.place_child(cell, cell_rect.origin());
+                }
+            }
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let clip_rect = ctx.size().to_rect();
+        ctx.clip_children(clip_rect);
+
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+        for cell in self.rows.iter_mut().flatten() {
+            if !ctx.get_ref(cell).state().is_stashed {
+                cell.paint(ctx, scene);
+            }
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_clips_children();
+        for cell in self.rows.iter_mut().flatten() {
+            cell.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.rows.iter().flatten().map(|cell| cell.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("GridPane")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+    use std::time::Duration;
+
+    #[test]
+    fn frozen_cells_stay_in_place() {
+        let [frozen_id, scrolling_id] = widget_ids();
+        let mut grid = DataGrid::new([100.0; 5])
+            .with_header((0..5).map(|col| Label::new(format!("Column {col}"))))
+            .with_frozen_columns(1)
+            .with_row_height(20.0)
+            .with_row_pods([
+                WidgetPod::new_with_id(
+                    Box::new(Label::new("frozen")) as Box<dyn Widget>,
+                    frozen_id,
+                ),
+                WidgetPod::new_with_id(Box::new(Label::new("scrolling")), scrolling_id),
+            ]);
+        for row in 1..100 {
+            grid = grid.with_row((0..5).map(|col| Label::new(format!("{row}, {col}"))));
+        }
+        let mut harness = TestHarness::create_with_size(grid, Size::new(300.0, 200.0));
+        let window_rect =
+            |harness: &TestHarness, id| harness.get_widget(id).state().window_layout_rect();
+
+        assert_eq!(
+            window_rect(&harness, frozen_id),
+            Rect::new(0.0, 20.0, 100.0, 40.0)
+        );
+        assert_eq!(
+            window_rect(&harness, scrolling_id),
+            Rect::new(100.0, 20.0, 200.0, 40.0)
+        );
+
+        harness.mouse_move((150.0, 100.0));
+        harness.mouse_wheel(Vec2::new(50.0, 10.0));
+        assert_eq!(
+            window_rect(&harness, frozen_id),
+            Rect::new(0.0, 10.0, 100.0, 30.0)
+        );
+        assert_eq!(
+            window_rect(&harness, scrolling_id),
+            Rect::new(50.0, 10.0, 150.0, 30.0)
+        );
+
+        // Cells which are scrolled out of view are stashed
+        harness.mouse_wheel(Vec2::new(1000.0, 10000.0));
+        let grid = harness.root_widget();
+        let grid = grid.downcast::<DataGrid>().unwrap();
+        assert_eq!(grid.scroll_offset(), Vec2::new(200.0, 1820.0));
+        assert!(harness.get_widget(frozen_id).state().is_stashed);
+        assert!(harness.get_widget(scrolling_id).state().is_stashed);
+    }
+
+    #[test]
+    fn double_click_edits_cell() {
+        let [grid_id, first_id, second_id] = widget_ids();
+        let grid = DataGrid::new([100.0; 2])
+            .with_editable(true)
+            .with_row_pods([
+                WidgetPod::new_with_id(Box::new(Label::new("first")) as Box<dyn Widget>, first_id),
+                WidgetPod::new_with_id(Box::new(Label::new("second")), second_id),
+            ]);
+        let mut harness = TestHarness::create(SizedBox::new_with_id(grid, grid_id));
+        let editing_cell = |harness: &TestHarness| {
+            let grid = harness.get_widget(grid_id);
+            grid.downcast::<DataGrid>().unwrap().editing_cell()
+        };
+
+        harness.mouse_click_on(first_id);
+        assert_eq!(editing_cell(&harness), None);
+        harness.mouse_click_on(first_id);
+        assert_eq!(editing_cell(&harness), Some((0, 0)));
+        let editor = harness.focused_widget().unwrap();
+        assert_eq!(editor.downcast::<Textbox>().unwrap().text(), "first");
+
+        harness.keyboard_type_chars("!");
+        let _ = std::iter::from_fn(|| harness.pop_action()).count();
+
+        // Clicking another cell commits the edit
+        harness.mouse_click_on(second_id);
+        assert_eq!(editing_cell(&harness), None);
+        let first = harness.get_widget(first_id);
+        assert_eq!(first.downcast::<Label>().unwrap().text().as_str(), "first!");
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::CellEdited(0, 0, "first!".into()), grid_id))
+        );
+        let grid = harness.get_widget(grid_id);
+        let grid = grid.downcast::<DataGrid>().unwrap();
+        assert_eq!(grid.current_cell(), Some((0, 1)));
+    }
+
+    #[test]
+    fn typing_jumps_to_matching_row() {
+        let [grid_id, first_id] = widget_ids();
+        let mut grid = DataGrid::new([100.0]).with_row_pods([WidgetPod::new_with_id(
+            Box::new(Label::new("Apple")) as Box<dyn Widget>,
+            first_id,
+        )]);
+        for fruit in ["Banana", "Blueberry", "Cherry", "banana split"] {
+            grid = grid.with_row([Label::new(fruit)]);
+        }
+        let mut harness = TestHarness::create(SizedBox::new_with_id(grid, grid_id));
+        let current_cell = |harness: &TestHarness| {
+            let grid = harness.get_widget(grid_id);
+            grid.downcast::<DataGrid>().unwrap().current_cell()
+        };
+        harness.mouse_click_on(first_id);
+        assert_eq!(current_cell(&harness), Some((0, 0)));
+
+        harness.keyboard_type_chars("c");
+        assert_eq!(current_cell(&harness), Some((3, 0)));
+
+        // Typing the same character again cycles through the rows starting with it
+        harness.advance_time(Duration::from_secs(2));
+        harness.keyboard_type_chars("bb");
+        assert_eq!(current_cell(&harness), Some((1, 0)));
+        harness.keyboard_type_chars("b");
+        assert_eq!(current_cell(&harness), Some((2, 0)));
+
+        // Characters typed quickly are searched for together
+        harness.advance_time(Duration::from_secs(2));
+        harness.keyboard_type_chars("blu");
+        assert_eq!(current_cell(&harness), Some((2, 0)));
+        harness.keyboard_type_chars("c");
+        assert_eq!(current_cell(&harness), Some((2, 0)));
+        harness.advance_time(Duration::from_secs(2));
+        harness.keyboard_type_chars("c");
+        assert_eq!(current_cell(&harness), Some((3, 0)));
+    }
+}
@@ -0,0 +1,458 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A panel asking the user to acknowledge, confirm or answer something.
+
+use accesskit::Role;
+use kurbo::{Point, Size};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::conventions::ButtonOrder;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Button, Label, LineBreaking, Textbox, WidgetMut, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+
+/// The widest a dialog will be.
+const DIALOG_WIDTH: f64 = 320.0;
+/// The space between the edge of a dialog and its contents.
+const DIALOG_PADDING: f64 = 16.0;
+
+/// A panel with a title, a message and buttons, optionally with a text input,
+/// usually shown in a [`ModalLayer`].
+///
+/// The accept button is the default button: clicking it or pressing <kbd>Enter</kbd>
+/// emits [`Action::DialogAccepted`] with the text of the input. Clicking the cancel
+/// button or pressing <kbd>Escape</kbd> emits [`Action::DialogCancelled`]. The
+/// buttons still emit their own [`Action::ButtonPressed`].
+///
+/// The dialog focuses its input when it is added, or itself if it has no input.
+///
+/// [`ModalLayer`]: crate::widget::ModalLayer
+pub struct Dialog {
+    title: WidgetPod<Label>,
+    message: WidgetPod<Label>,
+    input: Option<WidgetPod<Textbox>>,
+    accept: WidgetPod<Button>,
+    cancel: Option<WidgetPod<Button>>,
+}
+
+impl Dialog {
+    /// A dialog which tells the user something, with only an "OK" button.
+    pub fn alert(title: impl Into<ArcStr>, message: impl Into<ArcStr>) -> Self {
+        Self::from_pods(
+            title,
+            message,
+            None,
+            WidgetPod::new(Button::new("OK")),
+            None,
+        )
+    }
+
+    /// A dialog which asks the user to confirm something, with "OK" and "Cancel" buttons.
+    pub fn confirm(title: impl Into<ArcStr>, message: impl Into<ArcStr>) -> Self {
+        Self::from_pods(
+            title,
+            message,
+            None,
+            WidgetPod::new(Button::new("OK")),
+            Some(WidgetPod::new(Button::new("Cancel"))),
+        )
+    }
+
+    /// A dialog which asks the user for some text, starting with `initial_text`.
+    pub fn prompt(
+        title: impl Into<ArcStr>,
+        message: impl Into<ArcStr>,
+        initial_text: impl Into<String>,
+    ) -> Self {
+        Self::from_pods(
+            title,
+            message,
+            Some(WidgetPod::new(Textbox::new(initial_text))),
+            WidgetPod::new(Button::new("OK")),
+            Some(WidgetPod::new(Button::new("Cancel"))),
+        )
+    }
+
+    /// Create a new `Dialog` from existing widgets.
+    ///
+    /// There is no cancel button if `cancel` is `None`, though <kbd>Escape</kbd> still
+    /// cancels the dialog.
+    pub fn from_pods(
+        title: impl Into<ArcStr>,
+        message: impl Into<ArcStr>,
+        input: Option<WidgetPod<Textbox>>,
+        accept: WidgetPod<Button>,
+        cancel: Option<WidgetPod<Button>>,
+    ) -> Self {
+        Dialog {
+            title: WidgetPod::new(
+                Label::new(title)
+                    .with_text_size(theme::TEXT_SIZE_LARGE as f32)
+                    .with_line_break_mode(LineBreaking::WordWrap),
+            ),
+            message: WidgetPod::new(
+                Label::new(message).with_line_break_mode(LineBreaking::WordWrap),
+            ),
+            input,
+            accept,
+            cancel,
+        }
+    }
+
+    fn accept(&self, ctx: &mut EventCtx) {
+        trace!("Dialog {:?} accepted", ctx.widget_id());
+        let text = self
+            .input
+            .as_ref()
+            .map(|input| ctx.get_ref(input).text().to_string())
+            .unwrap_or_default();
+        ctx.submit_action(Action::DialogAccepted(text));
+    }
+
+    fn cancel(&self, ctx: &mut EventCtx) {
+        trace!("Dialog {:?} cancelled", ctx.widget_id());
+        ctx.submit_action(Action::DialogCancelled);
+    }
+}
+
+impl<'w> WidgetRef<'w, Dialog> {
+    /// The text of the title.
+    pub fn title(&self) -> &'w ArcStr {
+        self.get_child(&self.deref().title).deref().text()
+    }
+}
+
+impl WidgetMut<'_, Dialog> {
+    pub fn set_title(&mut self, title: impl Into<ArcStr>) {
+        self.ctx.get_mut(&mut self.widget.title).set_text(title);
+        self.ctx.request_accessibility_update();
+    }
+
+    pub fn set_message(&mut self, message: impl Into<ArcStr>) {
+        self.ctx.get_mut(&mut self.widget.message).set_text(message);
+    }
+
+    pub fn input_mut(&mut self) -> Option<WidgetMut<'_, Textbox>> {
+        let input = self.widget.input.as_mut()?;
+        Some(self.ctx.get_mut(input))
+    }
+
+    /// Replace the text input, or remove it if `input` is `None`.
+    pub fn set_input_pod(&mut self, input: Option<WidgetPod<Textbox>>) {
+        if let Some(old_input) = std::mem::replace(&mut self.widget.input, input) {
+            self.ctx.remove_child(old_input);
+        }
+        self.ctx.children_changed();
+        self.ctx.request_accessibility_update();
+    }
+
+    pub fn accept_button_mut(&mut self) -> WidgetMut<'_, Button> {
+        self.ctx.get_mut(&mut self.widget.accept)
+    }
+
+    pub fn cancel_button_mut(&mut self) -> Option<WidgetMut<'_, Button>> {
+        let cancel = self.widget.cancel.as_mut()?;
+        Some(self.ctx.get_mut(cancel))
+    }
+
+    /// Replace the cancel button, or remove it if `cancel` is `None`.
+    pub fn set_cancel_button_pod(&mut self, cancel: Option<WidgetPod<Button>>) {
+        if let Some(old_cancel) = std::mem::replace(&mut self.widget.cancel, cancel) {
+            self.ctx.remove_child(old_cancel);
+        }
+        self.ctx.children_changed();
+    }
+}
+
+impl Widget for Dialog {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        // A button was clicked if it was pressed, and the pointer is still on it when released
+        let is_active =
+            |ctx: &EventCtx, button: &WidgetPod<Button>| ctx.get_ref(button).state().is_active;
+        let is_hot =
+            |ctx: &EventCtx, button: &WidgetPod<Button>| ctx.get_ref(button).state().is_hot;
+        let accept_was_active = is_active(ctx, &self.accept);
+        let cancel_was_active = (self.cancel.as_ref()).is_some_and(|cancel| is_active(ctx, cancel));
+
+        self.title.on_pointer_event(ctx, event);
+        self.message.on_pointer_event(ctx, event);
+        if let Some(input) = &mut self.input {
+            input.on_pointer_event(ctx, event);
+        }
+        self.accept.on_pointer_event(ctx, event);
+        if let Some(cancel) = &mut self.cancel {
+            cancel.on_pointer_event(ctx, event);
+        }
+
+        if let PointerEvent::PointerUp(_, _) = event {
+            if accept_was_active && is_hot(ctx, &self.accept) {
+                self.accept(ctx);
+            } else if cancel_was_active && (self.cancel.as_ref()).is_some_and(|c| is_hot(ctx, c)) {
+                self.cancel(ctx);
+            }
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if let TextEvent::KeyboardKey(key, mods) = event {
+            let accepted = match &key.logical_key {
+                Key::Named(NamedKey::Enter) => Some(true),
+                Key::Named(NamedKey::Escape) => Some(false),
+                _ => None,
+            };
+            if let Some(accepted) = accepted.filter(|_| key.state.is_pressed() && mods.is_empty()) {
+                ctx.skip_child(&mut self.title);
+                ctx.skip_child(&mut self.message);
+                if let Some(input) = &mut self.input {
+                    ctx.skip_child(input);
+                }
+                ctx.skip_child(&mut self.accept);
+                if let Some(cancel) = &mut self.cancel {
+                    ctx.skip_child(cancel);
+                }
+                if accepted {
+                    self.accept(ctx);
+                } else {
+                    self.cancel(ctx);
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        self.title.on_text_event(ctx, event);
+        self.message.on_text_event(ctx, event);
+        if let Some(input) = &mut self.input {
+            input.on_text_event(ctx, event);
+        }
+        self.accept.on_text_event(ctx, event);
+        if let Some(cancel) = &mut self.cancel {
+            cancel.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.action == accesskit::Action::Default {
+            if event.target == self.accept.id() {
+                self.accept(ctx);
+            } else if self.cancel.as_ref().map(|cancel| cancel.id()) == Some(event.target) {
+                self.cancel(ctx);
+            }
+        }
+
+        self.title.on_access_event(ctx, event);
+        self.message.on_access_event(ctx, event);
+        if let Some(input) = &mut self.input {
+            input.on_access_event(ctx, event);
+        }
+        self.accept.on_access_event(ctx, event);
+        if let Some(cancel) = &mut self.cancel {
+            cancel.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::WidgetAdded = event {
+            match &mut self.input {
+                Some(input) => {
+                    ctx.set_focus(input.id());
+                    // The text box needs a selection to accept what the user types
+                    let text = ctx.get_ref(input).text().to_string();
+                    ctx.get_mut(input).replace_text(text);
+                }
+                None => ctx.request_focus(),
+            }
+        }
+
+        self.title.lifecycle(ctx, event);
+        self.message.lifecycle(ctx, event);
+        if let Some(input) = &mut self.input {
+            input.lifecycle(ctx, event);
+        }
+        self.accept.lifecycle(ctx, event);
+        if let Some(cancel) = &mut self.cancel {
+            cancel.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let width = DIALOG_WIDTH.min(bc.max().width);
+        let content_width = (width - 2.0 * DIALOG_PADDING).max(0.0);
+        let content_bc = BoxConstraints::new(Size::ZERO, Size::new(content_width, f64::INFINITY));
+
+        let mut y = DIALOG_PADDING;
+        for label in [&mut self.title, &mut self.message] {
+            let size = label.layout(ctx, &content_bc);
+            ctx.place_child(label, Point::new(DIALOG_PADDING, y));
+            y += size.height + theme::WIDGET_PADDING_VERTICAL;
+        }
+        if let Some(input) = &mut self.input {
+            let input_bc = BoxConstraints::new(
+                Size::new(content_width, 0.0),
+                Size::new(content_width, f64::INFINITY),
+            );
+            let size = input.layout(ctx, &input_bc);
+            ctx.place_child(input, Point::new(DIALOG_PADDING, y));
+            y += size.height + theme::WIDGET_PADDING_VERTICAL;
+        }
+
+        // The buttons are on the right, in the platform's order, placed from the
+        // rightmost one
+        let mut buttons = vec![&mut self.accept];
+        buttons.extend(&mut self.cancel);
+        if ctx.conventions().dialog_button_order == ButtonOrder::AcceptFirst {
+            buttons.reverse();
+        }
+        let mut x = width - DIALOG_PADDING;
+        let mut row_height: f64 = 0.0;
+        for button in buttons {
+            let button_size = button.layout(ctx, &content_bc);
+            x -= button_size.width;
+            ctx.place_child(button, Point::new(x, y));
+            x -= theme::WIDGET_PADDING_HORIZONTAL;
+            row_height = row_height.max(button_size.height);
+        }
+
+        let size = bc.constrain(Size::new(width, y + row_height + DIALOG_PADDING));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let (rect, border_width) = ctx.snap_stroke(
+            ctx.size()
+                .to_rect()
+                .inset(-theme::BUTTON_BORDER_WIDTH / 2.0),
+            theme::BUTTON_BORDER_WIDTH,
+        );
+        let rect = rect.to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+        fill_color(scene, &rect, theme::BACKGROUND_DARK);
+        stroke(scene, &rect, theme::BORDER_LIGHT, border_width);
+
+        self.title.paint(ctx, scene);
+        self.message.paint(ctx, scene);
+        if let Some(input) = &mut self.input {
+            input.paint(ctx, scene);
+        }
+        self.accept.paint(ctx, scene);
+        if let Some(cancel) = &mut self.cancel {
+            cancel.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        if self.input.is_some() {
+            Role::Dialog
+        } else {
+            Role::AlertDialog
+        }
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let title = ctx.get_ref(&self.title).text().as_ref().to_string();
+        let node = ctx.current_node();
+        node.set_modal();
+        node.set_name(title);
+        node.set_described_by(vec![self.message.id().into()]);
+
+        self.title.accessibility(ctx);
+        self.message.accessibility(ctx);
+        if let Some(input) = &mut self.input {
+            input.accessibility(ctx);
+        }
+        self.accept.accessibility(ctx);
+        if let Some(cancel) = &mut self.cancel {
+            cancel.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut children: SmallVec<_> = SmallVec::new();
+        children.push(self.title.id());
+        children.push(self.message.id());
+        if let Some(input) = &self.input {
+            children.push(input.id());
+        }
+        children.push(self.accept.id());
+        if let Some(cancel) = &self.cancel {
+            children.push(cancel.id());
+        }
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Dialog")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conventions::{Platform, PlatformConventions};
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+
+    #[test]
+    fn prompt_buttons() {
+        let [dialog_id, input_id, ok_id, cancel_id] = widget_ids();
+        let widget = Dialog::from_pods(
+            "Rename",
+            "Enter a new name",
+            Some(WidgetPod::new_with_id(Textbox::new("old"), input_id)),
+            WidgetPod::new_with_id(Button::new("OK"), ok_id),
+            Some(WidgetPod::new_with_id(Button::new("Cancel"), cancel_id)),
+        );
+        let mut harness = TestHarness::create(SizedBox::new_with_id(widget, dialog_id));
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(input_id));
+
+        harness.keyboard_type_chars(" name");
+        harness.mouse_click_on(ok_id);
+        let dialog_actions: Vec<_> = std::iter::from_fn(|| harness.pop_action())
+            .filter(|(_, id)| *id == dialog_id)
+            .map(|(action, _)| action)
+            .collect();
+        assert_eq!(
+            dialog_actions,
+            [Action::DialogAccepted("old name".to_string())]
+        );
+
+        harness.mouse_click_on(cancel_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, cancel_id))
+        );
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DialogCancelled, dialog_id))
+        );
+    }
+
+    #[test]
+    fn buttons_follow_platform_order() {
+        let [ok_id, cancel_id] = widget_ids();
+        let widget = Dialog::from_pods(
+            "Delete",
+            "Delete the file?",
+            None,
+            WidgetPod::new_with_id(Button::new("OK"), ok_id),
+            Some(WidgetPod::new_with_id(Button::new("Cancel"), cancel_id)),
+        );
+        let mut harness = TestHarness::create(widget);
+        let x_of = |harness: &TestHarness, id| harness.get_widget(id).state().layout_rect().x0;
+
+        harness.set_conventions(PlatformConventions::for_platform(Platform::MacOs));
+        assert!(x_of(&harness, cancel_id) < x_of(&harness, ok_id));
+        harness.set_conventions(PlatformConventions::for_platform(Platform::Windows));
+        assert!(x_of(&harness, ok_id) < x_of(&harness, cancel_id));
+    }
+}
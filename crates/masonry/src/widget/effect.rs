@@ -0,0 +1,88 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which runs a custom post-processing pass over its child.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use vello::Scene;
+
+use crate::effects::PostProcess;
+use crate::widget::WidgetPod;
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A widget which renders its child to a texture of its own, and runs a
+/// [`PostProcess`] pass over it, such as to blur or desaturate it.
+///
+/// See [`effects`](crate::effects) for how the layer is rendered and composited.
+pub struct Effect {
+    child: WidgetPod<Box<dyn Widget>>,
+    pass: Rc<RefCell<dyn PostProcess>>,
+}
+
+impl Effect {
+    pub fn new(child: impl Widget + 'static, pass: impl PostProcess + 'static) -> Self {
+        Effect {
+            child: WidgetPod::new(child).boxed(),
+            pass: Rc::new(RefCell::new(pass)),
+        }
+    }
+
+    /// The pass run over the child, such as to change its parameters.
+    ///
+    /// The window isn't redrawn when the pass changes, so the widget should be asked
+    /// to repaint afterwards.
+    pub fn pass(&self) -> &Rc<RefCell<dyn PostProcess>> {
+        &self.pass
+    }
+}
+
+impl Widget for Effect {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _scene: &mut Scene) {
+        let pass = self.pass.clone();
+        let child = &mut self.child;
+        ctx.paint_effect(pass, |ctx, scene| child.paint(ctx, scene));
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which decides which events reach its child.
+
+use std::borrow::Cow;
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, warn, Span};
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// An event on its way to the child of an [`EventFilter`].
+#[derive(Debug, Clone, Copy)]
+pub enum FilteredEvent<'a> {
+    Pointer(&'a PointerEvent),
+    Text(&'a TextEvent),
+    Access(&'a AccessEvent),
+}
+
+/// What an [`EventFilter`] does with an event.
+#[derive(Debug, Clone)]
+pub enum FilterDecision {
+    /// Let the event through unchanged.
+    Pass,
+    /// Stop the event, as if the child had handled it.
+    Swallow,
+    /// Send this pointer event instead.
+    Pointer(PointerEvent),
+    /// Send this text event instead.
+    Text(TextEvent),
+    /// Send this accessibility event instead.
+    Access(AccessEvent),
+}
+
+type FilterFn = Box<dyn FnMut(FilteredEvent<'_>) -> FilterDecision>;
+
+/// A widget which passes each event to a filter before its child, which can let
+/// it through, change it, or stop it from reaching the child.
+///
+/// For example, a filter which swallows every event blocks input to a part of the
+/// app while it shows that it's loading. Events which don't reach the child's
+/// subtree aren't routed through it at all, so the filter is cheap.
+///
+/// Only events are filtered: the child is laid out, painted and shown to
+/// assistive technologies as usual.
+pub struct EventFilter {
+    child: WidgetPod<Box<dyn Widget>>,
+    filter: FilterFn,
+}
+
+impl EventFilter {
+    /// Filter the events of `child` with `filter`.
+    pub fn new(
+        child: impl Widget,
+        filter: impl FnMut(FilteredEvent<'_>) -> FilterDecision + 'static,
+    ) -> Self {
+        Self::new_pod(WidgetPod::new(Box::new(child)), filter)
+    }
+
+    /// Filter the events of the child in `child` with `filter`.
+    pub fn new_pod(
+        child: WidgetPod<Box<dyn Widget>>,
+        filter: impl FnMut(FilteredEvent<'_>) -> FilterDecision + 'static,
+    ) -> Self {
+        EventFilter {
+            child,
+            filter: Box::new(filter),
+        }
+    }
+
+    /// Ask the filter about `event`, returning the event to send to the child.
+    ///
+    /// A swallowed event is marked as handled, so the child's pod skips it while
+    /// still doing its bookkeeping.
+    fn filter<'a, E: Clone>(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &'a E,
+        filtered: FilteredEvent<'a>,
+        replacement: impl FnOnce(FilterDecision) -> Option<E>,
+    ) -> Cow<'a, E> {
+        match (self.filter)(filtered) {
+            FilterDecision::Pass => Cow::Borrowed(event),
+            FilterDecision::Swallow => {
+                trace!("EventFilter {:?} swallowed {:?}", ctx.widget_id(), filtered);
+                ctx.set_handled();
+                Cow::Borrowed(event)
+            }
+            decision => match replacement(decision) {
+                Some(event) => Cow::Owned(event),
+                None => {
+                    warn!("EventFilter replaced {filtered:?} with a different kind of event");
+                    Cow::Borrowed(event)
+                }
+            },
+        }
+    }
+}
+
+impl WidgetMut<'_, EventFilter> {
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+
+    pub fn set_filter(
+        &mut self,
+        filter: impl FnMut(FilteredEvent<'_>) -> FilterDecision + 'static,
+    ) {
+        self.widget.filter = Box::new(filter);
+    }
+}
+
+impl Widget for EventFilter {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let replacement = |decision| match decision {
+            FilterDecision::Pointer(event) => Some(event),
+            _ => None,
+        };
+        let event = self.filter(ctx, event, FilteredEvent::Pointer(event), replacement);
+        self.child.on_pointer_event(ctx, &event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let replacement = |decision| match decision {
+            FilterDecision::Text(event) => Some(event),
+            _ => None,
+        };
+        let event = self.filter(ctx, event, FilteredEvent::Text(event), replacement);
+        self.child.on_text_event(ctx, &event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        let replacement = |decision| match decision {
+            FilterDecision::Access(event) => Some(event),
+            _ => None,
+        };
+        let event = self.filter(ctx, event, FilteredEvent::Access(event), replacement);
+        self.child.on_access_event(ctx, &event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        let insets = ctx.compute_insets_from_child(&self.child, size);
+        ctx.set_paint_insets(insets);
+        ctx.set_baseline_offset(ctx.child_baseline_offset(&self.child));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("EventFilter")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Button, Flex};
+    use crate::Action;
+
+    #[test]
+    fn filter_swallows_events_while_blocked() {
+        let blocked = Rc::new(Cell::new(true));
+        let [filter_id] = widget_ids();
+        let filter = EventFilter::new(Button::new("Blocked"), {
+            let blocked = blocked.clone();
+            move |event| match event {
+                FilteredEvent::Pointer(_) if blocked.get() => FilterDecision::Swallow,
+                _ => FilterDecision::Pass,
+            }
+        });
+        let widget = Flex::column()
+            .with_child_id(filter, filter_id)
+            .with_child(Button::new("Other"));
+        let mut harness = TestHarness::create(widget);
+        let button = harness.get_widget(filter_id).children()[0].id();
+        let other = harness.root_widget().children()[1].id();
+
+        harness.mouse_click_on(button);
+        assert_eq!(harness.pop_action(), None);
+        // Events outside of the filter are unaffected
+        harness.mouse_click_on(other);
+        assert_eq!(harness.pop_action(), Some((Action::ButtonPressed, other)));
+
+        blocked.set(false);
+        harness.mouse_click_on(button);
+        assert_eq!(harness.pop_action(), Some((Action::ButtonPressed, button)));
+    }
+}
@@ -12,7 +12,7 @@ use vello::Scene;
 use crate::kurbo::common::FloatExt;
 use crate::kurbo::Vec2;
 use crate::theme::get_debug_color;
-use crate::widget::{WidgetMut, WidgetRef};
+use crate::widget::{FlexItem, Sticky, WidgetMut};
 use crate::{
     AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
     Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
@@ -48,6 +48,7 @@ pub struct FlexParams {
 /// the direction in which they grow as their number of children increases.
 /// Has some methods for manipulating geometry with respect to the axis.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Axis {
     /// The x axis
     Horizontal,
@@ -60,6 +61,7 @@ pub enum Axis {
 /// If a widget is smaller than the container on the minor axis, this determines
 /// where it is positioned.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CrossAxisAlignment {
     /// Top or leading.
     Start,
@@ -78,6 +80,7 @@ pub enum CrossAxisAlignment {
 /// If there is surplus space on the main axis after laying out children, this
 /// enum represents how children are laid out in this space.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MainAxisAlignment {
     /// Top or leading.
     Start,
@@ -169,24 +172,17 @@ impl Flex {
     }
 
     /// Builder-style method to add a flexible child to the container.
-    pub fn with_flex_child(mut self, child: impl Widget, params: impl Into<FlexParams>) -> Self {
-        // TODO - dedup?
-        let params = params.into();
-        let child = if params.flex > 0.0 {
-            Child::Flex {
-                widget: WidgetPod::new(Box::new(child)),
-                alignment: params.alignment,
-                flex: params.flex,
-            }
-        } else {
-            // TODO
-            tracing::warn!("Flex value should be > 0.0. To add a non-flex child use the add_child or with_child methods.\nSee the docs for masonry::widget::Flex for more information");
-            Child::Fixed {
-                widget: WidgetPod::new(Box::new(child)),
-                alignment: None,
-            }
-        };
-        self.children.push(child);
+    pub fn with_flex_child(self, child: impl Widget, params: impl Into<FlexParams>) -> Self {
+        self.with_flex_child_pod(WidgetPod::new(Box::new(child)), params)
+    }
+
+    /// Builder-style method to add a flexible child that is already in a `WidgetPod`.
+    pub fn with_flex_child_pod(
+        mut self,
+        widget: WidgetPod<Box<dyn Widget>>,
+        params: impl Into<FlexParams>,
+    ) -> Self {
+        self.children.push(Child::new_flex(widget, params.into()));
         self
     }
 
@@ -304,21 +300,7 @@ impl<'a> WidgetMut<'a, Flex> {
 
     /// Add a flexible child widget.
     pub fn add_flex_child(&mut self, child: impl Widget, params: impl Into<FlexParams>) {
-        let params = params.into();
-        let child = if params.flex > 0.0 {
-            Child::Flex {
-                widget: WidgetPod::new(Box::new(child)),
-                alignment: params.alignment,
-                flex: params.flex,
-            }
-        } else {
-            // TODO
-            tracing::warn!("Flex value should be > 0.0. To add a non-flex child use the add_child or with_child methods.\nSee the docs for masonry::widget::Flex for more information");
-            Child::Fixed {
-                widget: WidgetPod::new(Box::new(child)),
-                alignment: None,
-            }
-        };
+        let child = Child::new_flex(WidgetPod::new(Box::new(child)), params.into());
         self.widget.children.push(child);
         self.ctx.children_changed();
     }
@@ -396,21 +378,17 @@ impl<'a> WidgetMut<'a, Flex> {
         child: impl Widget,
         params: impl Into<FlexParams>,
     ) {
-        let params = params.into();
-        let child = if params.flex > 0.0 {
-            Child::Flex {
-                widget: WidgetPod::new(Box::new(child)),
-                alignment: params.alignment,
-                flex: params.flex,
-            }
-        } else {
-            // TODO
-            tracing::warn!("Flex value should be > 0.0. To add a non-flex child use the add_child or with_child methods.\nSee the docs for masonry::widget::Flex for more information");
-            Child::Fixed {
-                widget: WidgetPod::new(Box::new(child)),
-                alignment: None,
-            }
-        };
+        self.insert_flex_child_pod(idx, WidgetPod::new(Box::new(child)), params);
+    }
+
+    /// Add a flexible child widget that is already in a `WidgetPod`.
+    pub fn insert_flex_child_pod(
+        &mut self,
+        idx: usize,
+        widget: WidgetPod<Box<dyn Widget>>,
+        params: impl Into<FlexParams>,
+    ) {
+        let child = Child::new_flex(widget, params.into());
         self.widget.children.insert(idx, child);
         // TODO
         self.ctx.widget_state.children_changed = true;
@@ -465,10 +443,27 @@ impl<'a> WidgetMut<'a, Flex> {
     }
 
     pub fn remove_child(&mut self, idx: usize) {
-        self.widget.children.remove(idx);
+        let child = self.widget.children.remove(idx);
+        if let Some(widget) = child.into_widget() {
+            self.ctx.remove_child(widget);
+        }
         self.ctx.widget_state.needs_layout = true;
     }
 
+    /// Move the child (or spacer) at index `from` to index `to`, shifting the
+    /// children in between.
+    ///
+    /// Unlike removing a child and inserting it again, this keeps the widget
+    /// and its state alive.
+    pub fn move_child(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let child = self.widget.children.remove(from);
+        self.widget.children.insert(to, child);
+        self.ctx.children_changed();
+    }
+
     // FIXME - Remove Box
     pub fn child_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
         let child = match &mut self.widget.children[idx] {
@@ -481,7 +476,11 @@ impl<'a> WidgetMut<'a, Flex> {
     }
 
     pub fn clear(&mut self) {
-        self.widget.children.clear();
+        for child in std::mem::take(&mut self.widget.children) {
+            if let Some(widget) = child.into_widget() {
+                self.ctx.remove_child(widget);
+            }
+        }
         self.ctx.widget_state.needs_layout = true;
     }
 }
@@ -514,6 +513,8 @@ impl Widget for Flex {
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        self.apply_item_params(ctx);
+
         // we loosen our constraints when passing to children.
         let loosened_bc = bc.loosen();
 
@@ -534,7 +535,7 @@ impl Widget for Flex {
 
                     let child_bc = self.direction.constraints(&loosened_bc, 0.0, f64::INFINITY);
                     let child_size = widget.layout(ctx, &child_bc);
-                    let baseline_offset = widget.baseline_offset();
+                    let baseline_offset = ctx.child_baseline_offset(widget);
 
                     if child_size.width.is_infinite() {
                         tracing::warn!("A non-Flex child has an infinite width.");
@@ -578,7 +579,7 @@ impl Widget for Flex {
 
                     let child_bc = self.direction.constraints(&loosened_bc, 0.0, actual_major);
                     let child_size = widget.layout(ctx, &child_bc);
-                    let baseline_offset = widget.baseline_offset();
+                    let baseline_offset = ctx.child_baseline_offset(widget);
 
                     major_flex += self.direction.major(child_size).expand();
                     minor = minor.max(self.direction.minor(child_size).expand());
@@ -618,13 +619,21 @@ impl Widget for Flex {
 
         let mut major = spacing.next().unwrap_or(0.);
 
-        for child in &mut self.children {
+        // When scrolled by a portal, sticky children are placed once the start of
+        // the one after each of them is known
+        let scroll_offset = ctx.scroll_offset();
+        let mut sticky_children = Vec::new();
+        // The children are placed once the size of the flex is known, which
+        // right-to-left layouts need to mirror their positions
+        let mut child_positions = Vec::new();
+
+        for (ix, child) in self.children.iter_mut().enumerate() {
             match child {
                 Child::Fixed { widget, alignment }
                 | Child::Flex {
                     widget, alignment, ..
                 } => {
-                    let child_size = widget.layout_rect().size();
+                    let child_size = ctx.child_layout_rect(widget).size();
                     let alignment = alignment.unwrap_or(self.cross_alignment);
                     let child_minor_offset = match alignment {
                         // This will ignore baseline alignment if it is overridden on children,
@@ -632,7 +641,7 @@ impl Widget for Flex {
                         CrossAxisAlignment::Baseline
                             if matches!(self.direction, Axis::Horizontal) =>
                         {
-                            let child_baseline = widget.baseline_offset();
+                            let child_baseline = ctx.child_baseline_offset(widget);
                             let child_above_baseline = child_size.height - child_baseline;
                             extra_height + (max_above_baseline - child_above_baseline)
                         }
@@ -652,7 +661,13 @@ impl Widget for Flex {
                     };
 
                     let child_pos: Point = self.direction.pack(major, child_minor_offset).into();
-                    ctx.place_child(widget, child_pos);
+                    if scroll_offset.is_some()
+                        && ctx.get_ref(widget).deref().as_any().is::<Sticky>()
+                    {
+                        sticky_children.push((ix, child_pos));
+                    } else {
+                        child_positions.push((ix, child_pos));
+                    }
                     major += self.direction.major(child_size).expand();
                     major += spacing.next().unwrap_or(0.);
                 }
@@ -683,6 +698,30 @@ impl Widget for Flex {
             bc.constrain(my_size)
         };
 
+        if ctx.layout_direction().is_rtl() {
+            // The start of the main axis of a row, or of the cross axis of a column,
+            // is on the right
+            for (ix, pos) in child_positions.iter_mut().chain(&mut sticky_children) {
+                if let Some(widget) = self.children[*ix].widget() {
+                    pos.x = my_size.width - pos.x - ctx.child_layout_rect(widget).width();
+                }
+            }
+        }
+        for (ix, pos) in child_positions {
+            if let Some(widget) = self.children[ix].widget_mut() {
+                ctx.place_child(widget, pos);
+            }
+        }
+
+        if let Some(offset) = scroll_offset {
+            self.place_sticky_children(
+                ctx,
+                &sticky_children,
+                self.direction.major_vec(offset),
+                self.direction.major(my_size),
+            );
+        }
+
         let baseline_offset = match self.direction {
             Axis::Horizontal => max_below_baseline,
             Axis::Vertical => (self.children)
@@ -690,8 +729,8 @@ impl Widget for Flex {
                 .map(|last| {
                     let child = last.widget();
                     if let Some(widget) = child {
-                        let child_bl = widget.baseline_offset();
-                        let child_max_y = widget.layout_rect().max_y();
+                        let child_bl = ctx.child_baseline_offset(widget);
+                        let child_max_y = ctx.child_layout_rect(widget).max_y();
                         let extra_bottom_padding = my_size.height - child_max_y;
                         child_bl + extra_bottom_padding
                     } else {
@@ -711,9 +750,10 @@ impl Widget for Flex {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
-        for child in self.children.iter_mut().filter_map(|x| x.widget_mut()) {
-            child.paint(ctx, scene);
-        }
+        ctx.paint_children(
+            scene,
+            self.children.iter_mut().filter_map(|x| x.widget_mut()),
+        );
 
         // paint the baseline if we're debugging layout
         if ctx.debug_paint && ctx.widget_state.baseline_offset != 0.0 {
@@ -736,11 +776,11 @@ impl Widget for Flex {
         }
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         self.children
             .iter()
             .filter_map(|child| child.widget())
-            .map(|widget_pod| widget_pod.as_dyn())
+            .map(|widget_pod| widget_pod.id())
             .collect()
     }
 
@@ -995,13 +1035,94 @@ enum Child {
     FlexedSpacer(f64, f64),
 }
 
+impl Flex {
+    /// Lay out the [`FlexItem`] children with their own params, which may have
+    /// changed since the last layout.
+    fn apply_item_params(&mut self, ctx: &LayoutCtx) {
+        for child in &mut self.children {
+            let Some(params) = child
+                .widget()
+                .and_then(|widget| ctx.get_ref(widget).downcast::<FlexItem>())
+                .map(|item| item.params)
+            else {
+                continue;
+            };
+            let Some(widget) = std::mem::replace(child, Child::FixedSpacer(0.0, 0.0)).into_widget()
+            else {
+                unreachable!()
+            };
+            *child = if params.flex > 0.0 {
+                Child::Flex {
+                    widget,
+                    alignment: params.alignment,
+                    flex: params.flex,
+                }
+            } else {
+                Child::Fixed {
+                    widget,
+                    alignment: params.alignment,
+                }
+            };
+        }
+    }
+
+    /// Place the [`Sticky`] children at `positions`, or `scroll_offset` along the
+    /// major axis if they were scrolled past, stopping at the start of the next one.
+    fn place_sticky_children(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        positions: &[(usize, Point)],
+        scroll_offset: f64,
+        major_size: f64,
+    ) {
+        for (n, (ix, pos)) in positions.iter().enumerate() {
+            let next_start = positions
+                .get(n + 1)
+                .map_or(major_size, |(_, next)| self.direction.major_pos(*next));
+            let Some(widget) = self.children[*ix].widget_mut() else {
+                continue;
+            };
+            let start = self.direction.major_pos(*pos);
+            let end = next_start - self.direction.major(ctx.child_layout_rect(widget).size());
+            let pinned = scroll_offset.min(end).max(start);
+            let pos = self.direction.pack(pinned, self.direction.minor_pos(*pos));
+            ctx.set_child_z_index(widget, 1);
+            ctx.place_child(widget, pos.into());
+        }
+    }
+}
+
 impl Child {
+    /// A flexible child, or a fixed one if `params` has no flex.
+    fn new_flex(widget: WidgetPod<Box<dyn Widget>>, params: FlexParams) -> Self {
+        if params.flex > 0.0 {
+            Child::Flex {
+                widget,
+                alignment: params.alignment,
+                flex: params.flex,
+            }
+        } else {
+            // TODO
+            tracing::warn!("Flex value should be > 0.0. To add a non-flex child use the add_child or with_child methods.\nSee the docs for masonry::widget::Flex for more information");
+            Child::Fixed {
+                widget,
+                alignment: None,
+            }
+        }
+    }
+
     fn widget_mut(&mut self) -> Option<&mut WidgetPod<Box<dyn Widget>>> {
         match self {
             Child::Fixed { widget, .. } | Child::Flex { widget, .. } => Some(widget),
             _ => None,
         }
     }
+    fn into_widget(self) -> Option<WidgetPod<Box<dyn Widget>>> {
+        match self {
+            Child::Fixed { widget, .. } | Child::Flex { widget, .. } => Some(widget),
+            _ => None,
+        }
+    }
     fn widget(&self) -> Option<&WidgetPod<Box<dyn Widget>>> {
         match self {
             Child::Fixed { widget, .. } | Child::Flex { widget, .. } => Some(widget),
@@ -1014,7 +1135,7 @@ impl Child {
 mod tests {
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::TestHarness;
+    use crate::testing::{widget_ids, TestHarness};
     use crate::widget::Label;
 
     #[test]
@@ -1406,4 +1527,28 @@ mod tests {
 
         // TODO - test out-of-bounds access?
     }
+
+    #[test]
+    fn move_flex_child() {
+        let [hello_id, world_id] = widget_ids();
+        let widget = Flex::column()
+            .with_child_id(Label::new("hello"), hello_id)
+            .with_child_id(Label::new("world"), world_id)
+            .with_spacer(1.0);
+
+        let mut harness = TestHarness::create(widget);
+        let hello_y = harness.get_widget(hello_id).state().layout_rect().y0;
+
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            flex.move_child(1, 0);
+        });
+
+        // The moved widget is not rebuilt, and takes the place of the first child.
+        assert_eq!(
+            harness.get_widget(world_id).state().layout_rect().y0,
+            hello_y
+        );
+        assert!(harness.get_widget(hello_id).state().layout_rect().y0 > hello_y);
+    }
 }
@@ -0,0 +1,137 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A child of a [`Flex`] which carries its own [`FlexParams`].
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::Scene;
+
+use crate::widget::{FlexParams, WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A child of a [`Flex`](crate::widget::Flex) which is laid out with `params`,
+/// as if it had been added with [`Flex::with_flex_child`].
+///
+/// This is for code which only builds the children, and not the container, such
+/// as the views of a [Xilem](https://crates.io/crates/xilem) flex. Other children
+/// ignore the params.
+///
+/// [`Flex::with_flex_child`]: crate::widget::Flex::with_flex_child
+pub struct FlexItem {
+    child: WidgetPod<Box<dyn Widget>>,
+    pub(crate) params: FlexParams,
+}
+
+impl FlexItem {
+    /// Create a new flex item.
+    pub fn new(child: impl Widget, params: impl Into<FlexParams>) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed(), params)
+    }
+
+    /// Create a new flex item from a [`WidgetPod`].
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>, params: impl Into<FlexParams>) -> Self {
+        FlexItem {
+            child,
+            params: params.into(),
+        }
+    }
+}
+
+impl WidgetMut<'_, FlexItem> {
+    pub fn set_params(&mut self, params: impl Into<FlexParams>) {
+        self.widget.params = params.into();
+        // The parent reads the params in its layout
+        self.ctx.request_layout();
+    }
+
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl Widget for FlexItem {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        ctx.set_baseline_offset(ctx.child_baseline_offset(&self.child));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("FlexItem")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{CrossAxisAlignment, Flex, SizedBox};
+
+    #[test]
+    fn items_share_the_space_by_weight() {
+        let [narrow_id, wide_id] = widget_ids();
+        let item = |params: FlexParams| {
+            FlexItem::new(SizedBox::empty().expand_width().height(20.0), params)
+        };
+        let widget = Flex::row()
+            .with_child_id(item(FlexParams::new(1.0, None)), narrow_id)
+            .with_child_id(
+                item(FlexParams::new(3.0, CrossAxisAlignment::Start)),
+                wide_id,
+            );
+        let mut harness = TestHarness::create_with_size(widget, Size::new(400.0, 100.0));
+        let rect = |harness: &TestHarness, id| harness.get_widget(id).state().layout_rect();
+        assert_eq!(rect(&harness, narrow_id).width(), 100.0);
+        assert_eq!(rect(&harness, wide_id).width(), 300.0);
+        assert_eq!(rect(&harness, narrow_id).y0, 40.0);
+        assert_eq!(rect(&harness, wide_id).y0, 0.0);
+
+        harness.edit_root_widget(|mut flex| {
+            let mut flex = flex.downcast::<Flex>();
+            let mut item = flex.child_mut(1).unwrap();
+            item.downcast::<FlexItem>().set_params(1.0);
+        });
+        assert_eq!(rect(&harness, narrow_id).width(), 200.0);
+        assert_eq!(rect(&harness, wide_id).y0, 40.0);
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A panel showing where the time of each frame goes.
+
+use std::time::Duration;
+
+use accesskit::Role;
+use kurbo::{Point, Size};
+use parley::style::{FontFamily, FontStack, GenericFamily};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+
+use crate::frame_stats::FrameStats;
+use crate::paint_scene_helpers::fill_color;
+use crate::text2::{TextLayout, TextStorage};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// How often the statistics are refreshed, so that they can be read.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+const PADDING: f64 = 6.0;
+const TEXT_SIZE: f32 = 12.0;
+const BACKGROUND_COLOR: Color = Color::rgba8(0x00, 0x00, 0x00, 0xc0);
+
+/// A panel showing the frame rate and what the last frame spent its time on, for
+/// performance work.
+///
+/// The panel shows the statistics from [`crate::frame_stats`]. It requests an
+/// animation frame every frame so that the frame rate stays current, which means
+/// the app never stops redrawing while the panel is shown.
+pub struct FrameStatsOverlay {
+    text_layout: TextLayout<ArcStr>,
+    /// Nanoseconds since the text was last refreshed.
+    since_refresh: u64,
+}
+
+impl FrameStatsOverlay {
+    pub fn new() -> Self {
+        let mut text_layout = TextLayout::new(ArcStr::from(""), TEXT_SIZE);
+        text_layout.set_font(FontStack::Single(FontFamily::Generic(
+            GenericFamily::Monospace,
+        )));
+        text_layout.set_brush(theme::TEXT_COLOR);
+        FrameStatsOverlay {
+            text_layout,
+            since_refresh: 0,
+        }
+    }
+
+    fn refresh(&mut self, ctx: &mut LifeCycleCtx) {
+        let text = stats_text(ctx.frame_stats());
+        if *self.text_layout.text().as_str() != text {
+            self.text_layout.set_text(text.into());
+            ctx.request_layout();
+            ctx.request_accessibility_update();
+        }
+        self.since_refresh = 0;
+    }
+}
+
+impl Default for FrameStatsOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The lines shown by the overlay for `stats`.
+fn stats_text(stats: &FrameStats) -> String {
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    format!(
+        "{:.0} fps  {} widgets\n\
+         event {:.1}ms  rebuild {:.1}ms\n\
+         layout {:.1}ms  paint {:.1}ms  render {:.1}ms\n\
+         damage {:.0}×{:.0}\n\
+         latency p50 {:.1}ms  p95 {:.1}ms  p99 {:.1}ms",
+        stats.fps,
+        stats.widget_count,
+        ms(stats.event),
+        ms(stats.rebuild),
+        ms(stats.layout),
+        ms(stats.paint),
+        ms(stats.render),
+        stats.damage.width(),
+        stats.damage.height(),
+        ms(stats.latency.p50),
+        ms(stats.latency.p95),
+        ms(stats.latency.p99),
+    )
+}
+
+impl Widget for FrameStatsOverlay {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.refresh(ctx);
+                ctx.request_anim_frame();
+            }
+            LifeCycle::AnimFrame(interval) => {
+                self.since_refresh += *interval;
+                if self.since_refresh >= REFRESH_INTERVAL.as_nanos() as u64 {
+                    self.refresh(ctx);
+                }
+                ctx.request_anim_frame();
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        if self.text_layout.needs_rebuild() {
+            self.text_layout.rebuild(ctx.font_ctx());
+        }
+        let text_size = self.text_layout.size();
+        let size = bc.constrain(Size::new(
+            text_size.width + 2.0 * PADDING,
+            text_size.height + 2.0 * PADDING,
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let panel = ctx
+            .size()
+            .to_rect()
+            .to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+        fill_color(scene, &panel, BACKGROUND_COLOR);
+        self.text_layout.draw(scene, Point::new(PADDING, PADDING));
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::StaticText
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node()
+            .set_name(self.text_layout.text().as_str().to_string());
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("FrameStatsOverlay")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::Rect;
+
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn overlay_shows_stats() {
+        let stats = FrameStats {
+            fps: 59.6,
+            widget_count: 42,
+            layout: Duration::from_micros(1250),
+            damage: Rect::new(10.0, 10.0, 130.0, 50.0),
+            ..Default::default()
+        };
+        let text = stats_text(&stats);
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], "60 fps  42 widgets");
+        assert!(lines[2].starts_with("layout 1.2ms"), "{text}");
+        assert_eq!(lines[3], "damage 120×40");
+        assert_eq!(lines[4], "latency p50 0.0ms  p95 0.0ms  p99 0.0ms");
+
+        // The overlay keeps animating, refreshing its text every so often
+        let mut harness = TestHarness::create(FrameStatsOverlay::new());
+        harness.animate(Duration::ZERO);
+        harness.animate(REFRESH_INTERVAL / 2);
+        let overlay = harness.root_widget();
+        let overlay = overlay.downcast::<FrameStatsOverlay>().unwrap();
+        assert!(overlay.since_refresh > 0);
+        harness.animate(REFRESH_INTERVAL);
+        let overlay = harness.root_widget();
+        let overlay = overlay.downcast::<FrameStatsOverlay>().unwrap();
+        assert_eq!(overlay.since_refresh, 0);
+    }
+}
@@ -0,0 +1,601 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget that arranges its children in rows and columns.
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A container which places its children in the cells of rows and columns, such
+/// as the labels and fields of a form.
+///
+/// The size of each column and row is given by a [`TrackSize`]. A child is placed
+/// in the cell given by its [`GridParams`], and can span several columns or rows.
+/// Children without a position are placed after the previous child, starting a
+/// new row when the columns run out. Columns and rows which children are placed
+/// in, but which were not given a size, are [`TrackSize::Auto`].
+///
+/// Each child is given at most the size of its cells, and is placed at their top
+/// left corner.
+pub struct Grid {
+    columns: Vec<TrackSize>,
+    rows: Vec<TrackSize>,
+    column_gap: f64,
+    row_gap: f64,
+    children: Vec<GridChild>,
+}
+
+/// How the size of a column or row of a [`Grid`] is decided.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackSize {
+    /// A fixed size, in logical pixels.
+    Fixed(f64),
+    /// The size of the largest child in the track.
+    Auto,
+    /// A share of the space left by the other tracks, in proportion to the
+    /// fractions of all such tracks.
+    ///
+    /// When the grid is unbounded along the track's axis, this is the same as
+    /// [`TrackSize::Auto`].
+    Fraction(f64),
+}
+
+/// Where a child of a [`Grid`] is placed.
+///
+/// The default is to place the child after the previous one, in a single cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridParams {
+    cell: Option<(usize, usize)>,
+    column_span: usize,
+    row_span: usize,
+}
+
+/// A child of a [`Grid`] which carries its own [`GridParams`], which take
+/// precedence over those it was added with.
+///
+/// This is for code which only builds the children, and not the container, such
+/// as the views of a [Xilem](https://crates.io/crates/xilem) grid.
+pub struct GridItem {
+    child: WidgetPod<Box<dyn Widget>>,
+    params: GridParams,
+}
+
+struct GridChild {
+    widget: WidgetPod<Box<dyn Widget>>,
+    params: GridParams,
+}
+
+// --- GridParams impl ---
+
+impl GridParams {
+    /// Place the child in the cell at `column` and `row`, counting from zero.
+    pub fn at(column: usize, row: usize) -> Self {
+        GridParams {
+            cell: Some((column, row)),
+            ..Default::default()
+        }
+    }
+
+    /// Builder-style method to make the child span `columns` columns and `rows`
+    /// rows, from its cell to the right and down.
+    ///
+    /// Spans are at least one.
+    pub fn with_span(mut self, columns: usize, rows: usize) -> Self {
+        self.column_span = columns.max(1);
+        self.row_span = rows.max(1);
+        self
+    }
+}
+
+impl Default for GridParams {
+    fn default() -> Self {
+        GridParams {
+            cell: None,
+            column_span: 1,
+            row_span: 1,
+        }
+    }
+}
+
+impl From<(usize, usize)> for GridParams {
+    fn from((column, row): (usize, usize)) -> Self {
+        GridParams::at(column, row)
+    }
+}
+
+// --- Grid impl ---
+
+impl Grid {
+    /// Create a new grid with the given column sizes.
+    ///
+    /// Rows are [`TrackSize::Auto`] unless set with [`with_rows`](Self::with_rows).
+    pub fn with_columns(columns: impl Into<Vec<TrackSize>>) -> Self {
+        Grid {
+            columns: columns.into(),
+            rows: Vec::new(),
+            column_gap: 0.0,
+            row_gap: 0.0,
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set the row sizes.
+    pub fn with_rows(mut self, rows: impl Into<Vec<TrackSize>>) -> Self {
+        self.rows = rows.into();
+        self
+    }
+
+    /// Builder-style method to set the space between columns and between rows.
+    pub fn with_gap(self, column_gap: f64, row_gap: f64) -> Self {
+        Grid {
+            column_gap: column_gap.max(0.0),
+            row_gap: row_gap.max(0.0),
+            ..self
+        }
+    }
+
+    /// Builder-style method to add a child.
+    pub fn with_child(self, child: impl Widget, params: impl Into<GridParams>) -> Self {
+        self.with_child_pod(WidgetPod::new(Box::new(child)), params)
+    }
+
+    /// Builder-style variant of `with_child`, that takes the id that the child will have.
+    ///
+    /// Useful for unit tests.
+    pub fn with_child_id(
+        self,
+        child: impl Widget,
+        id: WidgetId,
+        params: impl Into<GridParams>,
+    ) -> Self {
+        self.with_child_pod(WidgetPod::new_with_id(Box::new(child), id), params)
+    }
+
+    pub fn with_child_pod(
+        mut self,
+        widget: WidgetPod<Box<dyn Widget>>,
+        params: impl Into<GridParams>,
+    ) -> Self {
+        self.children.push(GridChild {
+            widget,
+            params: params.into(),
+        });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// --- Mutate live Grid - WidgetMut ---
+
+impl WidgetMut<'_, Grid> {
+    pub fn set_columns(&mut self, columns: impl Into<Vec<TrackSize>>) {
+        self.widget.columns = columns.into();
+        self.ctx.request_layout();
+    }
+
+    pub fn set_rows(&mut self, rows: impl Into<Vec<TrackSize>>) {
+        self.widget.rows = rows.into();
+        self.ctx.request_layout();
+    }
+
+    /// Set the space between columns and between rows.
+    pub fn set_gap(&mut self, column_gap: f64, row_gap: f64) {
+        self.widget.column_gap = column_gap.max(0.0);
+        self.widget.row_gap = row_gap.max(0.0);
+        self.ctx.request_layout();
+    }
+
+    pub fn add_child(&mut self, child: impl Widget, params: impl Into<GridParams>) {
+        let idx = self.widget.children.len();
+        self.insert_child_pod(idx, WidgetPod::new(Box::new(child)), params);
+    }
+
+    pub fn insert_child_pod(
+        &mut self,
+        idx: usize,
+        widget: WidgetPod<Box<dyn Widget>>,
+        params: impl Into<GridParams>,
+    ) {
+        let child = GridChild {
+            widget,
+            params: params.into(),
+        };
+        self.widget.children.insert(idx, child);
+        self.ctx.children_changed();
+    }
+
+    pub fn remove_child(&mut self, idx: usize) {
+        let child = self.widget.children.remove(idx);
+        self.ctx.remove_child(child.widget);
+        self.ctx.request_layout();
+    }
+
+    /// Move the child at index `from` to index `to`, shifting the children in
+    /// between, and keeping the widget and its state alive.
+    pub fn move_child(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let child = self.widget.children.remove(from);
+        self.widget.children.insert(to, child);
+        self.ctx.children_changed();
+    }
+
+    pub fn set_child_params(&mut self, idx: usize, params: impl Into<GridParams>) {
+        self.widget.children[idx].params = params.into();
+        self.ctx.request_layout();
+    }
+
+    // FIXME - Remove Box
+    pub fn child_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let child = self.widget.children.get_mut(idx)?;
+        Some(self.ctx.get_mut(&mut child.widget))
+    }
+}
+
+impl Grid {
+    /// The column and row of each child, and the number of columns and rows
+    /// including those only created by the children.
+    fn place_children(&self, ctx: &LayoutCtx) -> (Vec<(usize, usize)>, usize, usize) {
+        let width = self.columns.len().max(1);
+        let mut cells = Vec::with_capacity(self.children.len());
+        let (mut column_count, mut row_count) = (self.columns.len(), self.rows.len());
+        let mut next = (0, 0);
+        for child in &self.children {
+            let params = child_params(ctx, child);
+            let cell = params.cell.unwrap_or_else(|| {
+                let (column, row) = next;
+                if column > 0 && column + params.column_span > width {
+                    (0, row + 1)
+                } else {
+                    (column, row)
+                }
+            });
+            next = (cell.0 + params.column_span, cell.1);
+            column_count = column_count.max(cell.0 + params.column_span);
+            row_count = row_count.max(cell.1 + params.row_span);
+            cells.push(cell);
+        }
+        (cells, column_count, row_count)
+    }
+}
+
+/// The params of `child`, which are its own if it is a [`GridItem`].
+fn child_params(ctx: &LayoutCtx, child: &GridChild) -> GridParams {
+    ctx.get_ref(&child.widget)
+        .downcast::<GridItem>()
+        .map_or(child.params, |item| item.params)
+}
+
+/// The sizes of `count` tracks separated by `gap`, within `available` space, given
+/// the `(start, span, size)` of the children along their axis.
+///
+/// Only the children whose tracks are all fixed or auto are taken into account.
+fn track_sizes(
+    tracks: &[TrackSize],
+    count: usize,
+    gap: f64,
+    available: f64,
+    children: &[(usize, usize, f64)],
+) -> Vec<f64> {
+    let track = |ix: usize| match tracks.get(ix).copied().unwrap_or(TrackSize::Auto) {
+        TrackSize::Fraction(_) if !available.is_finite() => TrackSize::Auto,
+        track => track,
+    };
+    let mut sizes: Vec<f64> = (0..count)
+        .map(|ix| match track(ix) {
+            TrackSize::Fixed(size) => size.max(0.0),
+            _ => 0.0,
+        })
+        .collect();
+
+    // Single tracks first, so that spanning children only add what they still
+    // need, shared between their auto tracks
+    let mut children = children.to_vec();
+    children.sort_by_key(|(_, span, _)| *span);
+    for (start, span, size) in children {
+        let span = start..start + span;
+        if span
+            .clone()
+            .any(|ix| matches!(track(ix), TrackSize::Fraction(_)))
+        {
+            continue;
+        }
+        let auto: Vec<_> = span
+            .clone()
+            .filter(|ix| track(*ix) == TrackSize::Auto)
+            .collect();
+        let current = span.clone().map(|ix| sizes[ix]).sum::<f64>() + gap * (span.len() - 1) as f64;
+        if auto.is_empty() || size <= current {
+            continue;
+        }
+        let extra = (size - current) / auto.len() as f64;
+        for ix in auto {
+            sizes[ix] += extra;
+        }
+    }
+
+    let fractions: f64 = (0..count)
+        .filter_map(|ix| match track(ix) {
+            TrackSize::Fraction(fraction) => Some(fraction.max(0.0)),
+            _ => None,
+        })
+        .sum();
+    if fractions > 0.0 {
+        let used = sizes.iter().sum::<f64>() + gap * count.saturating_sub(1) as f64;
+        let per_fraction = (available - used).max(0.0) / fractions;
+        for (ix, size) in sizes.iter_mut().enumerate() {
+            if let TrackSize::Fraction(fraction) = track(ix) {
+                *size = fraction.max(0.0) * per_fraction;
+            }
+        }
+    }
+    sizes
+}
+
+/// The start of each track, and the total length of the tracks.
+fn track_starts(sizes: &[f64], gap: f64) -> (Vec<f64>, f64) {
+    let mut starts = Vec::with_capacity(sizes.len());
+    let mut pos = 0.0;
+    for size in sizes {
+        starts.push(pos);
+        pos += size + gap;
+    }
+    (starts, (pos - gap).max(0.0))
+}
+
+/// The length of `span` tracks from `start`, including the gaps between them.
+fn span_length(sizes: &[f64], gap: f64, start: usize, span: usize) -> f64 {
+    sizes[start..start + span].iter().sum::<f64>() + gap * (span - 1) as f64
+}
+
+// --- GridItem impl ---
+
+impl GridItem {
+    /// Create a new grid item.
+    pub fn new(child: impl Widget, params: impl Into<GridParams>) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed(), params)
+    }
+
+    /// Create a new grid item from a [`WidgetPod`].
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>, params: impl Into<GridParams>) -> Self {
+        GridItem {
+            child,
+            params: params.into(),
+        }
+    }
+}
+
+impl WidgetMut<'_, GridItem> {
+    pub fn set_params(&mut self, params: impl Into<GridParams>) {
+        self.widget.params = params.into();
+        // The parent reads the params in its layout
+        self.ctx.request_layout();
+    }
+
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+// --- Widget impls ---
+
+impl Widget for Grid {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for child in &mut self.children {
+            child.widget.on_pointer_event(ctx, event);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for child in &mut self.children {
+            child.widget.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for child in &mut self.children {
+            child.widget.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let (cells, column_count, row_count) = self.place_children(ctx);
+        let params: Vec<_> = (self.children.iter())
+            .map(|child| child_params(ctx, child))
+            .collect();
+        let unbounded = BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, f64::INFINITY));
+
+        // Measure the children in auto columns at their natural width
+        let is_auto_column = |ix: usize| match self.columns.get(ix) {
+            None | Some(TrackSize::Auto) => true,
+            Some(TrackSize::Fraction(_)) => !bc.max().width.is_finite(),
+            Some(TrackSize::Fixed(_)) => false,
+        };
+        let mut widths = Vec::new();
+        for ((child, (column, _)), params) in self.children.iter_mut().zip(&cells).zip(&params) {
+            if (*column..column + params.column_span).any(is_auto_column) {
+                let size = child.widget.layout(ctx, &unbounded);
+                widths.push((*column, params.column_span, size.width));
+            }
+        }
+        let column_sizes = track_sizes(
+            &self.columns,
+            column_count,
+            self.column_gap,
+            bc.max().width,
+            &widths,
+        );
+
+        // Measure the heights of the children at the width of their columns
+        let mut heights = Vec::new();
+        for ((child, (column, row)), params) in self.children.iter_mut().zip(&cells).zip(&params) {
+            let width = span_length(&column_sizes, self.column_gap, *column, params.column_span);
+            let child_bc = BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY));
+            let size = child.widget.layout(ctx, &child_bc);
+            heights.push((*row, params.row_span, size.height));
+        }
+        let row_sizes = track_sizes(
+            &self.rows,
+            row_count,
+            self.row_gap,
+            bc.max().height,
+            &heights,
+        );
+
+        let (column_starts, width) = track_starts(&column_sizes, self.column_gap);
+        let (row_starts, height) = track_starts(&row_sizes, self.row_gap);
+        for ((child, (column, row)), params) in self.children.iter_mut().zip(&cells).zip(&params) {
+            let cell = Size::new(
+                span_length(&column_sizes, self.column_gap, *column, params.column_span),
+                span_length(&row_sizes, self.row_gap, *row, params.row_span),
+            );
+            child
+                .widget
+                .layout(ctx, &BoxConstraints::new(Size::ZERO, cell));
+            let origin = Point::new(column_starts[*column], row_starts[*row]);
+            ctx.place_child(&mut child.widget, origin);
+        }
+
+        let size = bc.constrain(Size::new(width, height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        ctx.paint_children(
+            scene,
+            self.children.iter_mut().map(|child| &mut child.widget),
+        );
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for child in &mut self.children {
+            child.widget.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.children
+            .iter()
+            .map(|child| child.widget.id())
+            .collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Grid")
+    }
+}
+
+impl Widget for GridItem {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        ctx.set_baseline_offset(ctx.child_baseline_offset(&self.child));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("GridItem")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+
+    #[test]
+    fn tracks_are_sized_and_children_placed() {
+        let [label_id, field_id, wide_id, next_id] = widget_ids();
+        let block = |width: f64, height: f64| SizedBox::empty().width(width).height(height);
+        let widget = Grid::with_columns([TrackSize::Auto, TrackSize::Fraction(1.0)])
+            .with_rows([TrackSize::Fixed(30.0)])
+            .with_gap(10.0, 5.0)
+            .with_child_id(block(60.0, 20.0), label_id, GridParams::default())
+            .with_child_id(SizedBox::empty().expand(), field_id, GridParams::default())
+            .with_child_id(
+                block(500.0, 40.0),
+                wide_id,
+                GridParams::default().with_span(2, 1),
+            )
+            // Wrapped in an item, the child takes its params from the item
+            .with_child_id(
+                GridItem::new(block(30.0, 30.0), (1, 3)),
+                next_id,
+                GridParams::at(0, 0),
+            );
+        let harness = TestHarness::create_with_size(widget, Size::new(400.0, 400.0));
+        let rect = |id| harness.get_widget(id).state().layout_rect();
+
+        assert_eq!(rect(label_id), kurbo::Rect::new(0.0, 0.0, 60.0, 20.0));
+        // The field fills what the auto column leaves, in the fixed row
+        assert_eq!(rect(field_id), kurbo::Rect::new(70.0, 0.0, 400.0, 30.0));
+        // The wide child wraps to the next row, and is limited to both columns
+        assert_eq!(rect(wide_id), kurbo::Rect::new(0.0, 35.0, 400.0, 75.0));
+        // The empty row 2 is auto, so it has no height
+        assert_eq!(rect(next_id).origin(), Point::new(70.0, 85.0));
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which shows an icon from the app's icon set.
+
+use accesskit::Role;
+use kurbo::Affine;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, warn, Span};
+use vello::peniko::Fill;
+use vello::Scene;
+
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, Color, EventCtx, IconName, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A widget which shows the icon called `name` in the app's [`IconSet`](crate::IconSet).
+///
+/// The icon is drawn in a square of [`theme::ICON_SIZE`] by default, filled with
+/// [`theme::TEXT_COLOR`]. If the icon set doesn't have an icon with this name,
+/// nothing is drawn.
+pub struct Icon {
+    name: IconName,
+    size: f64,
+    color: Color,
+}
+
+impl Icon {
+    pub fn new(name: impl Into<IconName>) -> Self {
+        Icon {
+            name: name.into(),
+            size: theme::ICON_SIZE,
+            color: theme::TEXT_COLOR,
+        }
+    }
+
+    /// Builder-style method to set the length of the sides of the icon's square.
+    pub fn with_size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Builder-style method to set the color the icon is filled with.
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn name(&self) -> &IconName {
+        &self.name
+    }
+}
+
+impl WidgetMut<'_, Icon> {
+    pub fn set_name(&mut self, name: impl Into<IconName>) {
+        self.widget.name = name.into();
+        self.ctx.request_paint();
+    }
+
+    pub fn set_size(&mut self, size: f64) {
+        self.widget.size = size;
+        self.ctx.request_layout();
+    }
+
+    pub fn set_color(&mut self, color: impl Into<Color>) {
+        self.widget.color = color.into();
+        self.ctx.request_paint();
+    }
+}
+
+impl Widget for Icon {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::WidgetAdded = event {
+            if ctx.icons().get(&self.name).is_none() {
+                warn!("No icon called {:?} in the icon set", self.name.as_str());
+            }
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = bc.constrain(Size::new(self.size, self.size));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let Some(icon) = ctx.icons().get(&self.name) else {
+            return;
+        };
+        let view_box = icon.view_box();
+        if view_box.is_empty() {
+            return;
+        }
+        // Fit the view box in the middle of the widget, keeping its aspect ratio
+        let scale = (size.width / view_box.width).min(size.height / view_box.height);
+        let offset = (size.to_vec2() - view_box.to_vec2() * scale) / 2.0;
+        let transform = Affine::translate(offset) * Affine::scale(scale);
+        scene.fill(Fill::NonZero, transform, self.color, None, icon.path());
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Image
+    }
+
+    fn accessibility(&mut self, _ctx: &mut AccessCtx) {}
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Icon")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::Flex;
+    use crate::IconSet;
+
+    #[test]
+    fn icon_takes_its_size() {
+        let widget = Flex::row()
+            .with_child(Icon::new(IconName::CHECK))
+            .with_child(Icon::new(IconName::CLOSE).with_size(24.0));
+        let mut harness = TestHarness::create(widget);
+        let sizes = |harness: &TestHarness| {
+            harness.root_widget().children()[..2]
+                .iter()
+                .map(|icon| icon.state().layout_rect().size())
+                .collect::<Vec<_>>()
+        };
+        let default_size = Size::new(theme::ICON_SIZE, theme::ICON_SIZE);
+        assert_eq!(sizes(&harness), [default_size, Size::new(24.0, 24.0)]);
+
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<Flex>();
+            let mut icon = root.child_mut(0).unwrap();
+            icon.downcast::<Icon>().set_size(32.0);
+        });
+        assert_eq!(sizes(&harness)[0], Size::new(32.0, 32.0));
+
+        // Painting an icon which isn't in the set shouldn't crash
+        harness.set_icons(IconSet::empty());
+        let _ = harness.render();
+    }
+
+    #[test]
+    fn changing_icons_repaints_every_icon() {
+        let [first, second] = widget_ids();
+        let row = Flex::row()
+            .with_child_id(Icon::new(IconName::CHECK), first)
+            .with_child_id(Icon::new(IconName::CLOSE), second);
+        let widget = Flex::column().with_child(row);
+        let mut harness = TestHarness::create(widget);
+        let _ = harness.render();
+        harness.reset_widget_counters();
+
+        harness.set_icons(IconSet::empty());
+        let _ = harness.render();
+        let painted = harness.painted_widgets();
+        assert!(painted.contains(&first), "{painted:?}");
+        assert!(painted.contains(&second), "{painted:?}");
+    }
+}
@@ -5,16 +5,16 @@
 //! Please consider using SVG and the SVG widget as it scales much better.
 
 use accesskit::Role;
-use kurbo::Affine;
+use kurbo::{Affine, Insets, Rect};
 use smallvec::SmallVec;
 use tracing::{trace, trace_span, Span};
 use vello::peniko::{BlendMode, Image as ImageBuf};
 use vello::Scene;
 
-use crate::widget::{FillStrat, WidgetMut, WidgetRef};
+use crate::widget::{FillStrat, WidgetMut};
 use crate::{
     AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, Size, StatusChange, TextEvent, Widget,
+    PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
 };
 
 // TODO - Resolve name collision between masonry::Image and peniko::Image
@@ -25,6 +25,7 @@ use crate::{
 pub struct Image {
     image_data: ImageBuf,
     fill: FillStrat,
+    slices: Option<Insets>,
 }
 
 impl Image {
@@ -37,6 +38,7 @@ impl Image {
         Image {
             image_data,
             fill: FillStrat::default(),
+            slices: None,
         }
     }
 
@@ -46,6 +48,18 @@ impl Image {
         self.fill = mode;
         self
     }
+
+    /// Builder-style method to draw the image as a nine-slice image.
+    ///
+    /// The image is cut into a 3x3 grid by `insets`, measured in image pixels.
+    /// The corners are drawn at their own size, while the edges and the center
+    /// stretch to fill the widget, so panels and buttons drawn from bitmaps keep
+    /// their corners intact. The fill strategy is ignored while slices are set.
+    #[inline]
+    pub fn with_slices(mut self, insets: Insets) -> Self {
+        self.slices = Some(insets);
+        self
+    }
 }
 
 impl<'a> WidgetMut<'a, Image> {
@@ -56,6 +70,16 @@ impl<'a> WidgetMut<'a, Image> {
         self.ctx.request_paint();
     }
 
+    /// Set the insets used to draw the image as a nine-slice image, or `None` to
+    /// draw it normally.
+    ///
+    /// See [`Image::with_slices`].
+    #[inline]
+    pub fn set_slices(&mut self, insets: Option<Insets>) {
+        self.widget.slices = insets;
+        self.ctx.request_paint();
+    }
+
     /// Set new `ImageBuf`.
     #[inline]
     pub fn set_image_data(&mut self, image_data: ImageBuf) {
@@ -96,6 +120,20 @@ impl Widget for Image {
 
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
         let image_size = Size::new(self.image_data.width as f64, self.image_data.height as f64);
+        if let Some(insets) = self.slices {
+            for (source, dest) in nine_slices(image_size, insets, ctx.size()) {
+                let transform = Affine::translate(dest.origin().to_vec2())
+                    * Affine::scale_non_uniform(
+                        dest.width() / source.width(),
+                        dest.height() / source.height(),
+                    )
+                    * Affine::translate(-source.origin().to_vec2());
+                scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &dest);
+                scene.draw_image(&self.image_data, transform);
+                scene.pop_layer();
+            }
+            return;
+        }
         let transform = self.fill.affine_to_fill(ctx.size(), image_size);
 
         let clip_rect = ctx.size().to_rect();
@@ -112,7 +150,7 @@ impl Widget for Image {
         // TODO - Handle alt text and such.
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         SmallVec::new()
     }
 
@@ -121,6 +159,75 @@ impl Widget for Image {
     }
 }
 
+/// The parts of a nine-slice image, as pairs of the rect in the image and the
+/// rect it is drawn to in a widget of `size`.
+///
+/// If the widget is too small for the corners, they are all scaled down by the
+/// same factor. Empty parts are left out.
+fn nine_slices(image_size: Size, insets: Insets, size: Size) -> Vec<(Rect, Rect)> {
+    let insets = Insets::new(
+        insets.x0.clamp(0.0, image_size.width),
+        insets.y0.clamp(0.0, image_size.height),
+        insets.x1.clamp(0.0, image_size.width - insets.x0.max(0.0)),
+        insets.y1.clamp(0.0, image_size.height - insets.y0.max(0.0)),
+    );
+    let corner_scale = [
+        1.0,
+        size.width / insets.x_value(),
+        size.height / insets.y_value(),
+    ]
+    .into_iter()
+    .filter(|scale| scale.is_finite())
+    .fold(1.0_f64, f64::min);
+
+    let source_xs = [
+        0.0,
+        insets.x0,
+        image_size.width - insets.x1,
+        image_size.width,
+    ];
+    let source_ys = [
+        0.0,
+        insets.y0,
+        image_size.height - insets.y1,
+        image_size.height,
+    ];
+    let dest_xs = [
+        0.0,
+        insets.x0 * corner_scale,
+        size.width - insets.x1 * corner_scale,
+        size.width,
+    ];
+    let dest_ys = [
+        0.0,
+        insets.y0 * corner_scale,
+        size.height - insets.y1 * corner_scale,
+        size.height,
+    ];
+
+    let mut slices = Vec::with_capacity(9);
+    for row in 0..3 {
+        for column in 0..3 {
+            let source = Rect::new(
+                source_xs[column],
+                source_ys[row],
+                source_xs[column + 1],
+                source_ys[row + 1],
+            );
+            let dest = Rect::new(
+                dest_xs[column],
+                dest_ys[row],
+                dest_xs[column + 1],
+                dest_ys[row + 1],
+            );
+            if source.area() > 0.0 && dest.area() > 0.0 {
+                slices.push((source, dest));
+            }
+        }
+    }
+    slices
+}
+
 // FIXME - remove cfg?
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
@@ -162,6 +269,39 @@ mod tests {
         assert_render_snapshot!(harness, "tall_paint");
     }
 
+    #[test]
+    fn nine_slices_keep_corners() {
+        let image_size = Size::new(30.0, 30.0);
+        let insets = Insets::uniform(10.0);
+
+        let slices = nine_slices(image_size, insets, Size::new(100.0, 50.0));
+        assert_eq!(slices.len(), 9);
+        // The corners keep their size, and the center stretches
+        assert_eq!(
+            slices[0],
+            (Rect::new(0., 0., 10., 10.), Rect::new(0., 0., 10., 10.))
+        );
+        assert_eq!(
+            slices[4],
+            (Rect::new(10., 10., 20., 20.), Rect::new(10., 10., 90., 40.))
+        );
+        assert_eq!(
+            slices[8],
+            (
+                Rect::new(20., 20., 30., 30.),
+                Rect::new(90., 40., 100., 50.)
+            )
+        );
+
+        // Too narrow for the corners, which are scaled down and leave no middle column
+        let slices = nine_slices(image_size, insets, Size::new(10.0, 40.0));
+        assert_eq!(slices.len(), 6);
+        assert_eq!(
+            slices[0],
+            (Rect::new(0., 0., 10., 10.), Rect::new(0., 0., 5., 5.))
+        );
+    }
+
     #[test]
     fn edit_image() {
         let image_data = ImageBuf::new(vec![255; 4 * 8 * 8].into(), Format::Rgba8, 8, 8);
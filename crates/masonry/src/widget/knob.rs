@@ -0,0 +1,424 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A rotary knob, for adjusting a value like the dials of audio equipment.
+
+use std::f64::consts::PI;
+use std::ops::RangeInclusive;
+
+use accesskit::{ActionData, Role};
+use kurbo::{Affine, Arc, Cap, Circle, Line, Stroke, Vec2};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// The size of a knob, unless its parent constrains it.
+const DIAMETER: f64 = 36.0;
+/// How far the pointer moves up or down to turn a knob over its whole range, when it
+/// is dragged vertically.
+const DRAG_DISTANCE: f64 = 200.0;
+/// How much slower a knob turns while <kbd>Shift</kbd> is held.
+const FINE_FACTOR: f64 = 0.1;
+/// The angle of the minimum value, clockwise from the right, which puts the gap of
+/// the arc at the bottom.
+const START_ANGLE: f64 = 0.75 * PI;
+/// The angle between the minimum and the maximum value.
+const SWEEP_ANGLE: f64 = 1.5 * PI;
+const TRACK_WIDTH: f64 = 3.0;
+
+/// How dragging a [`Knob`] turns it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KnobDrag {
+    /// Dragging up turns the knob up, and dragging down turns it down, wherever
+    /// the pointer is. This is the default, since it is the most precise.
+    #[default]
+    Vertical,
+    /// The knob follows the angle of the pointer around its centre.
+    Circular,
+}
+
+/// A rotary knob, which adjusts a value within a range.
+///
+/// The value is shown by an arc around the knob, and changed by dragging the knob,
+/// or with the arrow keys while it has focus. Holding <kbd>Shift</kbd> turns it
+/// more finely, and double-clicking it resets it to its default value.
+///
+/// Emits [`Action::ValueChanged`] when the user changes the value.
+pub struct Knob {
+    value: f64,
+    min: f64,
+    max: f64,
+    default: f64,
+    step: f64,
+    drag: KnobDrag,
+    /// Where the pointer was at the last move of the drag, if the knob is dragged
+    last_drag_pos: Option<Point>,
+}
+
+impl Knob {
+    /// Create a new knob for a value in `range`, which resets to `value` when it is
+    /// double-clicked.
+    ///
+    /// The arrow keys change the value by a hundredth of the range.
+    pub fn new(value: f64, range: RangeInclusive<f64>) -> Self {
+        let (min, max) = range.into_inner();
+        assert!(min <= max, "the range of a knob must not be empty");
+        let value = value.clamp(min, max);
+        Knob {
+            value,
+            min,
+            max,
+            default: value,
+            step: (max - min) / 100.0,
+            drag: KnobDrag::default(),
+            last_drag_pos: None,
+        }
+    }
+
+    /// Builder-style method to set the value which double-clicking resets to.
+    pub fn with_default_value(mut self, default: f64) -> Self {
+        self.default = default.clamp(self.min, self.max);
+        self
+    }
+
+    /// Builder-style method to set how much the arrow keys change the value.
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Builder-style method to set how dragging turns the knob.
+    pub fn with_drag(mut self, drag: KnobDrag) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    /// The current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// How far the value is through the range, from 0 to 1.
+    fn fraction(&self) -> f64 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    /// Set the value, clamped to the range, and emit an action if it changed.
+    fn change_value(&mut self, ctx: &mut EventCtx, value: f64) {
+        let value = value.clamp(self.min, self.max);
+        if value == self.value {
+            return;
+        }
+        trace!("Knob {:?} turned to {}", ctx.widget_id(), value);
+        self.value = value;
+        ctx.submit_action(Action::ValueChanged(value));
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+    }
+
+    /// How far the pointer moving from `from` to `to` turns a knob whose centre is
+    /// at `centre`, as a fraction of its range.
+    fn drag_turn(&self, centre: Point, from: Point, to: Point) -> f64 {
+        match self.drag {
+            KnobDrag::Vertical => (from.y - to.y) / DRAG_DISTANCE,
+            KnobDrag::Circular => {
+                let angle = (to - centre).atan2() - (from - centre).atan2();
+                // The shortest way round, so that crossing the left doesn't jump
+                ((angle + PI).rem_euclid(2.0 * PI) - PI) / SWEEP_ANGLE
+            }
+        }
+    }
+}
+
+impl WidgetMut<'_, Knob> {
+    /// Set the value, clamped to the range, without emitting an action.
+    pub fn set_value(&mut self, value: f64) {
+        self.widget.value = value.clamp(self.widget.min, self.widget.max);
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the range, clamping the value and the default value to it.
+    pub fn set_range(&mut self, range: RangeInclusive<f64>) {
+        let (min, max) = range.into_inner();
+        assert!(min <= max, "the range of a knob must not be empty");
+        self.widget.min = min;
+        self.widget.max = max;
+        self.widget.default = self.widget.default.clamp(min, max);
+        self.set_value(self.widget.value);
+    }
+
+    /// Set the value which double-clicking resets to.
+    pub fn set_default_value(&mut self, default: f64) {
+        self.widget.default = default.clamp(self.widget.min, self.widget.max);
+    }
+
+    /// Set how much the arrow keys change the value.
+    pub fn set_step(&mut self, step: f64) {
+        self.widget.step = step;
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set how dragging turns the knob.
+    pub fn set_drag(&mut self, drag: KnobDrag) {
+        self.widget.drag = drag;
+    }
+}
+
+impl Widget for Knob {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(MouseButton::Left, state) if !ctx.is_disabled() => {
+                ctx.request_focus();
+                if state.count >= 2 {
+                    self.last_drag_pos = None;
+                    self.change_value(ctx, self.default);
+                } else {
+                    ctx.set_active(true);
+                    self.last_drag_pos = Some(Point::new(state.position.x, state.position.y));
+                }
+                ctx.request_paint();
+            }
+            PointerEvent::PointerMove(state) => {
+                let Some(last) = self.last_drag_pos else {
+                    return;
+                };
+                let pos = Point::new(state.position.x, state.position.y);
+                let centre = ctx.window_origin() + ctx.size().to_vec2() / 2.0;
+                let mut turn = self.drag_turn(centre, last, pos);
+                if state.mods.state().shift_key() {
+                    turn *= FINE_FACTOR;
+                }
+                self.last_drag_pos = Some(pos);
+                self.change_value(ctx, self.value + turn * (self.max - self.min));
+            }
+            PointerEvent::PointerUp(_, _) => {
+                self.last_drag_pos = None;
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key, mods) = event else {
+            return;
+        };
+        if !key.state.is_pressed() || !ctx.is_focused() || ctx.is_disabled() {
+            return;
+        }
+        let step = if mods.shift_key() {
+            self.step * FINE_FACTOR
+        } else {
+            self.step
+        };
+        let value = match &key.logical_key {
+            Key::Named(NamedKey::ArrowUp | NamedKey::ArrowRight) => self.value + step,
+            Key::Named(NamedKey::ArrowDown | NamedKey::ArrowLeft) => self.value - step,
+            Key::Named(NamedKey::Home) => self.min,
+            Key::Named(NamedKey::End) => self.max,
+            _ => return,
+        };
+        self.change_value(ctx, value);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target != ctx.widget_id() || ctx.is_disabled() {
+            return;
+        }
+        match (event.action, &event.data) {
+            (accesskit::Action::Increment, _) => self.change_value(ctx, self.value + self.step),
+            (accesskit::Action::Decrement, _) => self.change_value(ctx, self.value - self.step),
+            (accesskit::Action::SetValue, Some(ActionData::NumericValue(value))) => {
+                self.change_value(ctx, *value);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        bc.constrain(Size::new(DIAMETER, DIAMETER))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let centre = size.to_rect().center();
+        let radius = (size.min_side() - TRACK_WIDTH) / 2.0;
+        let body_radius = radius - TRACK_WIDTH * 2.0;
+
+        let body = Circle::new(centre, body_radius.max(0.0));
+        fill_color(scene, &body, theme::BACKGROUND_LIGHT);
+        let border_color = if ctx.is_hot() && !ctx.is_disabled() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+        stroke(scene, &body, border_color, 1.0);
+
+        let track_style = Stroke::new(TRACK_WIDTH).with_caps(Cap::Round);
+        let arc = |sweep_angle| Arc {
+            center: centre,
+            radii: Vec2::new(radius, radius),
+            start_angle: START_ANGLE,
+            sweep_angle,
+            x_rotation: 0.0,
+        };
+        scene.stroke(
+            &track_style,
+            Affine::IDENTITY,
+            theme::BACKGROUND_DARK,
+            None,
+            &arc(SWEEP_ANGLE),
+        );
+        let (value_color, pointer_color) = if ctx.is_disabled() {
+            (theme::DISABLED_FOREGROUND_DARK, theme::DISABLED_TEXT_COLOR)
+        } else {
+            (theme::PRIMARY_LIGHT, theme::TEXT_COLOR)
+        };
+        let value_angle = self.fraction() * SWEEP_ANGLE;
+        if value_angle > 0.0 {
+            scene.stroke(
+                &track_style,
+                Affine::IDENTITY,
+                value_color,
+                None,
+                &arc(value_angle),
+            );
+        }
+
+        // The pointer of the knob, from near its centre to its edge
+        let direction = Vec2::from_angle(START_ANGLE + value_angle);
+        let pointer = Line::new(
+            centre + direction * body_radius * 0.4,
+            centre + direction * body_radius,
+        );
+        scene.stroke(
+            &Stroke::new(2.0).with_caps(Cap::Round),
+            Affine::IDENTITY,
+            pointer_color,
+            None,
+            &pointer,
+        );
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Slider
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_numeric_value(self.value);
+        node.set_min_numeric_value(self.min);
+        node.set_max_numeric_value(self.max);
+        node.set_numeric_value_step(self.step);
+        node.add_action(accesskit::Action::Focus);
+        node.add_action(accesskit::Action::Increment);
+        node.add_action(accesskit::Action::Decrement);
+        node.add_action(accesskit::Action::SetValue);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Knob")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::ModifiersState;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::Flex;
+
+    #[test]
+    fn drag_turns_and_double_click_resets() {
+        let [knob_id] = widget_ids();
+        let widget = Flex::column().with_child(Knob::new(0.5, 0.0..=1.0).with_id(knob_id));
+        let mut harness = TestHarness::create(widget);
+        let value = |harness: &TestHarness| {
+            let knob = harness.get_widget(knob_id);
+            knob.downcast::<Knob>().unwrap().value()
+        };
+        let centre = harness
+            .get_widget(knob_id)
+            .state()
+            .window_layout_rect()
+            .center();
+
+        harness.mouse_move(centre);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move(centre - Vec2::new(0.0, 50.0));
+        assert_eq!(value(&harness), 0.75);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ValueChanged(0.75), knob_id))
+        );
+
+        // Shift turns it more finely, and the value stops at the end of the range
+        harness.set_modifiers(ModifiersState::SHIFT);
+        harness.mouse_move(centre - Vec2::new(0.0, 150.0));
+        assert!((value(&harness) - 0.8).abs() < 1e-9);
+        harness.set_modifiers(ModifiersState::empty());
+        harness.mouse_move(centre - Vec2::new(0.0, 1000.0));
+        assert_eq!(value(&harness), 1.0);
+        harness.mouse_button_release(MouseButton::Left);
+
+        harness.mouse_move(centre);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        assert_eq!(value(&harness), 0.5);
+    }
+
+    #[test]
+    fn circular_drag_follows_the_pointer() {
+        let [knob_id] = widget_ids();
+        let knob = Knob::new(0.5, 0.0..=1.0).with_drag(KnobDrag::Circular);
+        let widget = Flex::column().with_child(knob.with_id(knob_id));
+        let mut harness = TestHarness::create(widget);
+        let centre = harness
+            .get_widget(knob_id)
+            .state()
+            .window_layout_rect()
+            .center();
+
+        // From the top of the knob, a quarter turn clockwise is a third of the range
+        harness.mouse_move(centre - Vec2::new(0.0, 10.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move(centre + Vec2::new(10.0, 0.0));
+        let knob = harness.get_widget(knob_id);
+        let value = knob.downcast::<Knob>().unwrap().value();
+        assert!((value - (0.5 + 1.0 / 3.0)).abs() < 1e-9);
+    }
+}
@@ -3,20 +3,23 @@
 
 //! A label widget.
 
+use std::ops::Range;
+
 use accesskit::Role;
 use kurbo::{Affine, Point, Size};
+use parley::fontique::Weight;
 use parley::layout::Alignment;
-use parley::style::{FontFamily, FontStack};
+use parley::style::{FontFamily, FontStack, StyleProperty};
 use smallvec::SmallVec;
 use tracing::trace;
 use vello::peniko::BlendMode;
 use vello::Scene;
 
 use crate::text2::{TextBrush, TextLayout, TextStorage};
-use crate::widget::{WidgetMut, WidgetRef};
+use crate::widget::WidgetMut;
 use crate::{
     AccessCtx, AccessEvent, ArcStr, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
 };
 
 // added padding between the edges of the widget and the text.
@@ -33,6 +36,22 @@ pub enum LineBreaking {
     Overflow,
 }
 
+/// A style for part of a label's text, such as the parts of a search result which
+/// matched the query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpanStyle {
+    Brush(TextBrush),
+    Weight(Weight),
+    Underline,
+}
+
+/// A style applied to a byte range of a label's text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub range: Range<usize>,
+    pub style: SpanStyle,
+}
+
 /// A widget displaying non-editable text.
 pub struct Label {
     // We hardcode the underlying storage type as `ArcStr` for `Label`
@@ -43,6 +62,9 @@ pub struct Label {
     line_break_mode: LineBreaking,
     show_disabled: bool,
     brush: TextBrush,
+    spans: Vec<TextSpan>,
+    /// Whether the text is drawn with the disabled color
+    shown_disabled: bool,
 }
 
 impl Label {
@@ -53,6 +75,8 @@ impl Label {
             line_break_mode: LineBreaking::Overflow,
             show_disabled: true,
             brush: crate::theme::TEXT_COLOR.into(),
+            spans: Vec::new(),
+            shown_disabled: false,
         }
     }
 
@@ -89,6 +113,18 @@ impl Label {
         self
     }
 
+    /// Style parts of the text differently from the rest.
+    ///
+    /// Brushes of spans aren't used while the label is shown as disabled.
+    pub fn with_spans(mut self, spans: Vec<TextSpan>) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
     /// Create a label with empty text.
     pub fn empty() -> Self {
         Self::new("")
@@ -138,6 +174,12 @@ impl WidgetMut<'_, Label> {
         self.widget.line_break_mode = line_break_mode;
         self.ctx.request_paint();
     }
+
+    /// Replace the styled parts of the text; see [`Label::with_spans`].
+    pub fn set_spans(&mut self, spans: Vec<TextSpan>) {
+        self.widget.spans = spans;
+        self.set_text_properties(TextLayout::invalidate);
+    }
 }
 
 impl Widget for Label {
@@ -179,6 +221,7 @@ impl Widget for Label {
         match event {
             LifeCycle::DisabledChanged(disabled) => {
                 if self.show_disabled {
+                    self.shown_disabled = *disabled;
                     if *disabled {
                         self.text_layout
                             .set_brush(crate::theme::DISABLED_TEXT_COLOR);
@@ -211,7 +254,23 @@ impl Widget for Label {
         };
         self.text_layout.set_max_advance(max_advance);
         if self.text_layout.needs_rebuild() {
-            self.text_layout.rebuild(ctx.font_ctx());
+            let spans = &self.spans;
+            let show_brushes = !self.shown_disabled;
+            self.text_layout
+                .rebuild_with_attributes(ctx.font_ctx(), |mut builder| {
+                    for span in spans {
+                        let property = match &span.style {
+                            SpanStyle::Brush(brush) if show_brushes => {
+                                StyleProperty::Brush(brush.clone())
+                            }
+                            SpanStyle::Brush(_) => continue,
+                            SpanStyle::Weight(weight) => StyleProperty::FontWeight(*weight),
+                            SpanStyle::Underline => StyleProperty::Underline(true),
+                        };
+                        builder.push(&property, span.range.clone());
+                    }
+                    builder
+                });
         }
         // We ignore trailing whitespace for a label
         let text_size = self.text_layout.size();
@@ -254,7 +313,7 @@ impl Widget for Label {
             .set_name(self.text().as_str().to_string());
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         SmallVec::new()
     }
 
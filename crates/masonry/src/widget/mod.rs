@@ -14,42 +14,95 @@ mod widget_state;
 mod tests;
 
 mod align;
+mod autocomplete;
+#[cfg(feature = "effects")]
+mod backdrop_blur;
 mod button;
+mod cache_paint;
+mod canvas;
 mod checkbox;
+mod clickable;
+mod data_grid;
+mod dialog;
+#[cfg(feature = "effects")]
+mod effect;
+mod event_filter;
 mod flex;
+mod flex_item;
+mod frame_stats_overlay;
+mod grid;
+mod icon;
 mod image;
+mod knob;
 mod label;
+mod modal_layer;
+mod pager;
+mod popover;
 mod portal;
 mod prose;
 mod root_widget;
 mod scroll_bar;
+mod segmented_control;
 mod sized_box;
+mod skeleton;
+mod slider;
 mod spinner;
 mod split;
+mod sticky;
 mod textbox;
+mod toggle_group;
+mod token_field;
+mod virtual_list;
 
 pub use self::image::Image;
 pub use align::Align;
+pub use autocomplete::Autocomplete;
+#[cfg(feature = "effects")]
+pub use backdrop_blur::BackdropBlur;
 pub use button::Button;
+pub use cache_paint::CachePaint;
+pub use canvas::Canvas;
 pub use checkbox::Checkbox;
+pub use clickable::Clickable;
+pub use data_grid::DataGrid;
+pub use dialog::Dialog;
+#[cfg(feature = "effects")]
+pub use effect::Effect;
+pub use event_filter::{EventFilter, FilterDecision, FilteredEvent};
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
-pub use label::{Label, LineBreaking};
-pub use portal::Portal;
+pub use flex_item::FlexItem;
+pub use frame_stats_overlay::FrameStatsOverlay;
+pub use grid::{Grid, GridItem, GridParams, TrackSize};
+pub use icon::Icon;
+pub use knob::{Knob, KnobDrag};
+pub use label::{Label, LineBreaking, SpanStyle, TextSpan};
+pub use modal_layer::ModalLayer;
+pub use pager::Pager;
+pub use popover::Popover;
+pub use portal::{Portal, ScrollSyncGroup, SyncAxes};
 pub use prose::Prose;
 pub use root_widget::RootWidget;
-pub use scroll_bar::ScrollBar;
+pub use scroll_bar::{ScrollBar, ScrollBarStyle, ScrollBarVisibility};
+pub use segmented_control::SegmentedControl;
 pub use sized_box::SizedBox;
+pub use skeleton::Skeleton;
+pub use slider::Slider;
 pub use spinner::Spinner;
 pub use split::Split;
+pub use sticky::Sticky;
 pub use textbox::Textbox;
+pub use toggle_group::{ToggleGroup, ToggleMode};
+pub use token_field::TokenField;
+pub use virtual_list::VirtualList;
 pub use widget_mut::WidgetMut;
+pub(crate) use widget_pod::update_hot_state;
 pub use widget_pod::WidgetPod;
 pub use widget_ref::WidgetRef;
 pub use widget_state::WidgetState;
 
 pub use sized_box::BackgroundBrush;
 #[doc(hidden)]
-pub use widget::{Widget, WidgetId};
+pub use widget::{Interactivity, Widget, WidgetId};
 use winit::window::CursorIcon;
 
 /// The possible cursor states for a widget.
@@ -0,0 +1,292 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A container which can show a modal widget above its content.
+
+use accesskit::Role;
+use kurbo::{Point, Size};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+use crate::paint_scene_helpers::fill_color;
+use crate::widget::{WidgetId, WidgetMut};
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetPod,
+};
+
+/// A container which can show a modal widget, such as a [`Dialog`], centered above
+/// its content.
+///
+/// While the modal is shown, the content is covered by a scrim and gets no pointer,
+/// text or accessibility events. The modal is focused when it is shown, and
+/// <kbd>Tab</kbd> only moves focus between the widgets in the modal. When the modal
+/// is removed, focus goes back to the widget which had it before.
+///
+/// [`Dialog`]: crate::widget::Dialog
+pub struct ModalLayer {
+    child: WidgetPod<Box<dyn Widget>>,
+    modal: Option<WidgetPod<Box<dyn Widget>>>,
+    /// The widget which was focused before the modal was shown
+    previous_focus: Option<WidgetId>,
+}
+
+impl ModalLayer {
+    /// Create a new `ModalLayer` around `child`, with no modal shown.
+    pub fn new(child: impl Widget) -> Self {
+        Self::new_pod(WidgetPod::new(child).boxed())
+    }
+
+    /// Create a new `ModalLayer` around a child that is already in a `WidgetPod`.
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
+        ModalLayer {
+            child,
+            modal: None,
+            previous_focus: None,
+        }
+    }
+
+    /// Builder-style method to show `modal` above the content.
+    pub fn with_modal(self, modal: impl Widget) -> Self {
+        self.with_modal_pod(WidgetPod::new(modal).boxed())
+    }
+
+    /// Builder-style method to show a modal that is already in a `WidgetPod`.
+    pub fn with_modal_pod(mut self, modal: WidgetPod<Box<dyn Widget>>) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+
+    /// Whether a modal is shown.
+    pub fn has_modal(&self) -> bool {
+        self.modal.is_some()
+    }
+
+    /// Move focus to the next or previous widget in the modal's focus chain.
+    fn cycle_focus(&self, ctx: &mut EventCtx, forward: bool) {
+        let Some(modal) = &self.modal else {
+            return;
+        };
+        let chain = &ctx.get_ref(modal).state().focus_chain;
+        if chain.is_empty() {
+            return;
+        }
+        let focused = ctx.global_state.focused_widget;
+        let next = match chain.iter().position(|id| Some(*id) == focused) {
+            Some(ix) if forward => (ix + 1) % chain.len(),
+            Some(ix) => (ix + chain.len() - 1) % chain.len(),
+            None if forward => 0,
+            None => chain.len() - 1,
+        };
+        let next = chain[next];
+        ctx.set_focus(next);
+    }
+}
+
+impl WidgetMut<'_, ModalLayer> {
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+
+    /// Replace the content, keeping the modal if there is one.
+    pub fn set_child_pod(&mut self, child: WidgetPod<Box<dyn Widget>>) {
+        let old_child = std::mem::replace(&mut self.widget.child, child);
+        self.ctx.remove_child(old_child);
+        self.ctx.children_changed();
+    }
+
+    pub fn modal_mut(&mut self) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let modal = self.widget.modal.as_mut()?;
+        Some(self.ctx.get_mut(modal))
+    }
+
+    /// Show `modal` above the content, replacing the current modal if there is one.
+    pub fn set_modal(&mut self, modal: impl Widget) {
+        self.set_modal_pod(WidgetPod::new(modal).boxed());
+    }
+
+    /// Show a modal that is already in a `WidgetPod`, replacing the current modal if
+    /// there is one.
+    pub fn set_modal_pod(&mut self, modal: WidgetPod<Box<dyn Widget>>) {
+        if let Some(old_modal) = self.widget.modal.take() {
+            self.ctx.remove_child(old_modal);
+        } else {
+            self.widget.previous_focus = self.ctx.global_state.focused_widget;
+        }
+        self.ctx.set_focus(modal.id());
+        self.widget.modal = Some(modal);
+        self.ctx.children_changed();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Remove the modal, if there is one, and give focus back to the content.
+    pub fn remove_modal(&mut self) {
+        if let Some(modal) = self.widget.modal.take() {
+            self.ctx.remove_child(modal);
+            self.ctx.global_state.next_focused_widget = self.widget.previous_focus.take();
+            self.ctx.request_accessibility_update();
+        }
+    }
+}
+
+impl Widget for ModalLayer {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match &mut self.modal {
+            Some(modal) => {
+                ctx.skip_child(&mut self.child);
+                modal.on_pointer_event(ctx, event);
+                // The scrim covers the content, so the event doesn't go any further
+                ctx.set_handled();
+            }
+            None => self.child.on_pointer_event(ctx, event),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let Some(modal) = &mut self.modal else {
+            self.child.on_text_event(ctx, event);
+            return;
+        };
+        ctx.skip_child(&mut self.child);
+        modal.on_text_event(ctx, event);
+
+        // Keep focus in the modal, rather than letting it move to the content
+        if let TextEvent::KeyboardKey(key, mods) = event {
+            if !ctx.is_handled()
+                && key.state.is_pressed()
+                && key.physical_key == PhysicalKey::Code(KeyCode::Tab)
+            {
+                trace!("ModalLayer {:?} trapped Tab", ctx.widget_id());
+                self.cycle_focus(ctx, !mods.shift_key());
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        match &mut self.modal {
+            Some(modal) => {
+                ctx.skip_child(&mut self.child);
+                modal.on_access_event(ctx, event);
+            }
+            None => self.child.on_access_event(ctx, event),
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let (LifeCycle::WidgetAdded, Some(modal)) = (event, &self.modal) {
+            ctx.set_focus(modal.id());
+        }
+        self.child.lifecycle(ctx, event);
+        if let Some(modal) = &mut self.modal {
+            modal.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+
+        if let Some(modal) = &mut self.modal {
+            let modal_size = modal.layout(ctx, &BoxConstraints::new(Size::ZERO, size));
+            let origin = Point::new(
+                ((size.width - modal_size.width) / 2.0).max(0.0),
+                ((size.height - modal_size.height) / 2.0).max(0.0),
+            );
+            ctx.place_child(modal, origin);
+        }
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+        if let Some(modal) = &mut self.modal {
+            fill_color(scene, &ctx.size().to_rect(), theme::MODAL_SCRIM_COLOR);
+            modal.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+        if let Some(modal) = &mut self.modal {
+            modal.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut children = smallvec![self.child.id()];
+        if let Some(modal) = &self.modal {
+            children.push(modal.id());
+        }
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ModalLayer")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Action;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Button, Dialog, SizedBox};
+
+    #[test]
+    fn modal_blocks_content() {
+        let [button_id, dialog_id, ok_id] = widget_ids();
+        let widget = ModalLayer::new(SizedBox::new_with_id(Button::new("Behind"), button_id));
+        let mut harness = TestHarness::create(widget);
+
+        harness.mouse_click_on(button_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, button_id))
+        );
+
+        harness.edit_root_widget(|mut layer| {
+            let dialog = Dialog::from_pods(
+                "Title",
+                "Message",
+                None,
+                WidgetPod::new_with_id(Button::new("OK"), ok_id),
+                None,
+            );
+            let mut layer = layer.downcast::<ModalLayer>();
+            layer.set_modal(SizedBox::new_with_id(dialog, dialog_id));
+        });
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(dialog_id));
+
+        // The button is under the scrim, so clicking it does nothing
+        harness.mouse_click_on(button_id);
+        assert_eq!(harness.pop_action(), None);
+
+        harness.mouse_click_on(ok_id);
+        assert_eq!(harness.pop_action(), Some((Action::ButtonPressed, ok_id)));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::DialogAccepted(String::new()), dialog_id))
+        );
+
+        harness.edit_root_widget(|mut layer| {
+            layer.downcast::<ModalLayer>().remove_modal();
+        });
+        assert_eq!(harness.focused_widget().map(|w| w.id()), None);
+        harness.mouse_click_on(button_id);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ButtonPressed, button_id))
+        );
+    }
+}
@@ -0,0 +1,681 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which shows one of its pages at a time.
+
+use std::time::{Duration, Instant};
+
+use accesskit::Role;
+use kurbo::{Affine, Circle, Point, Rect};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::peniko::BlendMode;
+use vello::Scene;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::paint_scene_helpers::fill_color;
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, Action, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// The height of the row of page indicator dots below the pages.
+const INDICATOR_HEIGHT: f64 = 20.0;
+const DOT_RADIUS: f64 = 3.0;
+const DOT_SPACING: f64 = 12.0;
+
+/// How far ahead the velocity of a swipe is projected when choosing the page to
+/// snap to, in seconds. A quick flick turns the page even if it didn't move far.
+const VELOCITY_PROJECTION: f64 = 0.2;
+
+/// How long the pointer must stay still before it's released for a swipe to have
+/// no velocity.
+const RELEASE_STOP_TIME: Duration = Duration::from_millis(50);
+
+/// The spring which pulls the pages to the current page, in pages and seconds.
+const SPRING: Spring = Spring {
+    stiffness: 170.0,
+    damping: 26.0,
+};
+
+/// A damped spring, which moves a position towards a target.
+#[derive(Clone, Copy, Debug)]
+struct Spring {
+    stiffness: f64,
+    damping: f64,
+}
+
+impl Spring {
+    /// The time step of the simulation, in seconds.
+    const STEP: f64 = 1.0 / 240.0;
+
+    /// Move `position` and `velocity` forward by `duration` seconds.
+    fn advance(&self, position: &mut f64, velocity: &mut f64, target: f64, duration: f64) {
+        let mut remaining = duration;
+        while remaining > 0.0 {
+            let dt = remaining.min(Self::STEP);
+            let acceleration = -self.stiffness * (*position - target) - self.damping * *velocity;
+            *velocity += acceleration * dt;
+            *position += *velocity * dt;
+            remaining -= dt;
+        }
+    }
+
+    fn is_at_rest(&self, position: f64, velocity: f64, target: f64) -> bool {
+        (position - target).abs() < 1e-3 && velocity.abs() < 1e-3
+    }
+}
+
+/// A pointer dragging the pages.
+struct Drag {
+    start_x: f64,
+    start_page: usize,
+    start_offset: f64,
+    last_x: f64,
+    last_time: Instant,
+    /// The velocity of the pointer, in pixels per second.
+    velocity: f64,
+    /// Whether the pointer has moved far enough for the pages to follow it.
+    started: bool,
+}
+
+/// A widget which shows one of its pages at a time, side by side in a row.
+///
+/// The user can turn the page by swiping or dragging, by clicking the dots below
+/// the pages, or with the left and right arrow keys when the pager is focused. The
+/// pages then slide to the new page, and [`Action::PageChanged`] is submitted.
+///
+/// By default every page is laid out, however far it is from the current one. With
+/// [`with_prewarm`](Self::with_prewarm), only the pages on screen are, and the
+/// pages next to the current one are laid out ahead of time when the app is idle,
+/// so that they are ready when the user turns to them.
+pub struct Pager {
+    pages: Vec<WidgetPod<Box<dyn Widget>>>,
+    current: usize,
+    prewarm: bool,
+    /// Which pages have been laid out ahead of time, when `prewarm` is set.
+    prewarmed: Vec<bool>,
+    /// Whether the last turn was forwards, which is where the user will probably
+    /// turn next.
+    forwards: bool,
+    /// The page shown at the left edge, which is between pages while they slide.
+    offset: f64,
+    /// The speed at which the pages slide, in pages per second.
+    velocity: f64,
+    drag: Option<Drag>,
+}
+
+// --- METHODS ---
+
+impl Pager {
+    pub fn new() -> Self {
+        Pager {
+            pages: Vec::new(),
+            current: 0,
+            prewarm: false,
+            prewarmed: Vec::new(),
+            forwards: true,
+            offset: 0.0,
+            velocity: 0.0,
+            drag: None,
+        }
+    }
+
+    /// Builder-style method to add a page.
+    pub fn with_page(self, page: impl Widget) -> Self {
+        self.with_page_pod(WidgetPod::new(Box::new(page)))
+    }
+
+    pub fn with_page_pod(mut self, page: WidgetPod<Box<dyn Widget>>) -> Self {
+        self.pages.push(page);
+        self.prewarmed.push(false);
+        self
+    }
+
+    /// Builder-style method to set the page shown first.
+    pub fn with_current_page(mut self, page: usize) -> Self {
+        self.current = page;
+        self.offset = page as f64;
+        self
+    }
+
+    /// Builder-style method to only lay out the pages on screen, and the pages next
+    /// to the current one when the app is idle.
+    pub fn with_prewarm(mut self, prewarm: bool) -> Self {
+        self.prewarm = prewarm;
+        self
+    }
+
+    pub fn current_page(&self) -> usize {
+        self.current
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    fn page_size(size: Size) -> Size {
+        Size::new(size.width, (size.height - INDICATOR_HEIGHT).max(0.0))
+    }
+
+    fn dot_center(&self, size: Size, page: usize) -> Point {
+        let row_width = (self.pages.len().max(1) - 1) as f64 * DOT_SPACING;
+        let x = (size.width - row_width) / 2.0 + page as f64 * DOT_SPACING;
+        Point::new(x, size.height - INDICATOR_HEIGHT / 2.0)
+    }
+
+    fn dot_at(&self, size: Size, pos: Point) -> Option<usize> {
+        (0..self.pages.len()).find(|&page| {
+            let center = self.dot_center(size, page);
+            (pos - center).hypot() <= DOT_SPACING / 2.0
+        })
+    }
+
+    /// The page which `key` turns to, if it turns the page.
+    fn page_for_key(&self, key: &Key) -> Option<usize> {
+        let last = self.pages.len().saturating_sub(1);
+        match key {
+            Key::Named(NamedKey::ArrowLeft) => Some(self.current.saturating_sub(1)),
+            Key::Named(NamedKey::ArrowRight) => Some((self.current + 1).min(last)),
+            Key::Named(NamedKey::Home) => Some(0),
+            Key::Named(NamedKey::End) => Some(last),
+            _ => None,
+        }
+    }
+
+    /// Whether page `ix` is laid out, because it's on screen or was laid out ahead
+    /// of time.
+    fn is_laid_out(&self, ix: usize) -> bool {
+        !self.prewarm || (ix as f64 - self.offset).abs() < 1.0 || self.prewarmed[ix]
+    }
+
+    /// The page to lay out next when the app is idle: the one the user will
+    /// probably turn to, then the one on the other side.
+    fn next_to_prewarm(&self) -> Option<usize> {
+        if !self.prewarm {
+            return None;
+        }
+        let next = self.current + 1;
+        let previous = self.current.checked_sub(1);
+        let (likely, other) = if self.forwards {
+            (Some(next), previous)
+        } else {
+            (previous, Some(next))
+        };
+        [likely, other]
+            .into_iter()
+            .flatten()
+            .find(|&ix| ix < self.pages.len() && !self.is_laid_out(ix))
+    }
+
+    /// Slide to `page`, and tell the app if it changed.
+    fn turn_to(&mut self, ctx: &mut EventCtx, page: usize) {
+        let page = page.min(self.pages.len().saturating_sub(1));
+        if page != self.current {
+            self.forwards = page > self.current;
+            self.current = page;
+            trace!("Pager {:?} turned to page {}", ctx.widget_id(), page);
+            ctx.submit_action(Action::PageChanged(page));
+            ctx.request_accessibility_update();
+        }
+        ctx.request_anim_frame();
+    }
+}
+
+impl Default for Pager {
+    fn default() -> Self {
+        Pager::new()
+    }
+}
+
+impl WidgetMut<'_, Pager> {
+    /// Slide to `page`, without submitting an action.
+    pub fn set_current_page(&mut self, page: usize) {
+        let page = page.min(self.widget.pages.len().saturating_sub(1));
+        if page != self.widget.current {
+            self.widget.forwards = page > self.widget.current;
+            self.widget.current = page;
+            self.ctx.request_anim_frame();
+            self.ctx.request_accessibility_update();
+        }
+    }
+
+    /// Set whether to only lay out the pages on screen, and the pages next to the
+    /// current one when the app is idle.
+    pub fn set_prewarm(&mut self, prewarm: bool) {
+        self.widget.prewarm = prewarm;
+        if prewarm {
+            self.ctx.request_idle_work();
+        }
+        self.ctx.request_layout();
+    }
+
+    pub fn add_page(&mut self, page: impl Widget) {
+        let ix = self.widget.pages.len();
+        self.insert_page_pod(ix, WidgetPod::new(Box::new(page)));
+    }
+
+    pub fn insert_page_pod(&mut self, idx: usize, page: WidgetPod<Box<dyn Widget>>) {
+        self.widget.pages.insert(idx, page);
+        self.widget.prewarmed.insert(idx, false);
+        self.ctx.children_changed();
+    }
+
+    pub fn remove_page(&mut self, idx: usize) {
+        let page = self.widget.pages.remove(idx);
+        self.widget.prewarmed.remove(idx);
+        self.ctx.remove_child(page);
+        let last = self.widget.pages.len().saturating_sub(1);
+        self.widget.current = self.widget.current.min(last);
+        self.widget.offset = self.widget.offset.min(last as f64);
+        self.ctx.request_layout();
+    }
+
+    /// Move the page at index `from` to index `to`, keeping its state.
+    pub fn move_page(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let page = self.widget.pages.remove(from);
+        self.widget.pages.insert(to, page);
+        let prewarmed = self.widget.prewarmed.remove(from);
+        self.widget.prewarmed.insert(to, prewarmed);
+        self.ctx.children_changed();
+    }
+
+    pub fn page_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let page = self.widget.pages.get_mut(idx)?;
+        Some(self.ctx.get_mut(page))
+    }
+}
+
+// --- TRAIT IMPLS ---
+
+impl Widget for Pager {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        let size = ctx.size();
+        let window_origin = ctx.window_origin();
+        let local_pos = |pos: winit::dpi::LogicalPosition<f64>| {
+            Point::new(pos.x, pos.y) - window_origin.to_vec2()
+        };
+        match event {
+            PointerEvent::PointerDown(_, state)
+                if size.to_rect().contains(local_pos(state.position)) =>
+            {
+                let pos = local_pos(state.position);
+                ctx.request_focus();
+                if let Some(page) = self.dot_at(size, pos) {
+                    self.turn_to(ctx, page);
+                } else if pos.y < Self::page_size(size).height {
+                    ctx.set_active(true);
+                    self.drag = Some(Drag {
+                        start_x: pos.x,
+                        start_page: self.current,
+                        start_offset: self.offset,
+                        last_x: pos.x,
+                        last_time: ctx.now(),
+                        velocity: 0.0,
+                        started: false,
+                    });
+                }
+            }
+            PointerEvent::PointerMove(state) => {
+                if let Some(drag) = &mut self.drag {
+                    let x = local_pos(state.position).x;
+                    let now = ctx.now();
+                    // Moves which arrive together are treated as a millisecond apart
+                    let dt = (now - drag.last_time).as_secs_f64().max(1e-3);
+                    // Smooth the velocity, as pointer moves arrive unevenly
+                    drag.velocity = 0.8 * (x - drag.last_x) / dt + 0.2 * drag.velocity;
+                    drag.last_x = x;
+                    drag.last_time = now;
+                    drag.started |= ctx.interaction_settings().is_drag(x - drag.start_x);
+                    if drag.started && size.width > 0.0 {
+                        let max_offset = self.pages.len().saturating_sub(1) as f64;
+                        self.offset = (drag.start_offset - (x - drag.start_x) / size.width)
+                            .clamp(0.0, max_offset);
+                    }
+                    self.velocity = 0.0;
+                    ctx.request_layout();
+                }
+            }
+            PointerEvent::PointerUp(_, _) | PointerEvent::PointerLeave(_) => {
+                if let Some(drag) = self.drag.take() {
+                    ctx.set_active(false);
+                    // A pointer which stopped before it was released doesn't flick, nor
+                    // does one which never moved far enough to start dragging
+                    let stopped = ctx.now() - drag.last_time > RELEASE_STOP_TIME;
+                    let velocity = if size.width > 0.0 && drag.started && !stopped {
+                        -drag.velocity / size.width
+                    } else {
+                        0.0
+                    };
+                    // Swipes turn at most one page, however fast they are
+                    let projected = (self.offset + velocity * VELOCITY_PROJECTION).round();
+                    let page = projected
+                        .clamp(
+                            drag.start_page.saturating_sub(1) as f64,
+                            (drag.start_page + 1) as f64,
+                        )
+                        .max(0.0) as usize;
+                    self.velocity = velocity;
+                    self.turn_to(ctx, page);
+                }
+            }
+            _ => (),
+        }
+
+        for page in &mut self.pages {
+            page.on_pointer_event(ctx, event);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for page in &mut self.pages {
+            page.on_text_event(ctx, event);
+        }
+        if ctx.is_handled() || !ctx.is_focused() {
+            return;
+        }
+        if let TextEvent::KeyboardKey(key, _) = event {
+            if !key.state.is_pressed() {
+                return;
+            }
+            if let Some(page) = self.page_for_key(&key.logical_key) {
+                self.turn_to(ctx, page);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for page in &mut self.pages {
+            page.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain if !self.pages.is_empty() => {
+                ctx.register_for_focus();
+            }
+            LifeCycle::WidgetAdded if self.prewarm => {
+                ctx.request_idle_work();
+            }
+            LifeCycle::AnimFrame(interval) if self.drag.is_none() => {
+                let target = self.current as f64;
+                SPRING.advance(
+                    &mut self.offset,
+                    &mut self.velocity,
+                    target,
+                    *interval as f64 * 1e-9,
+                );
+                if SPRING.is_at_rest(self.offset, self.velocity, target) {
+                    self.offset = target;
+                    self.velocity = 0.0;
+                    // Pages the user has left behind go back to not being laid out,
+                    // and the new neighbours are prepared
+                    for (ix, prewarmed) in self.prewarmed.iter_mut().enumerate() {
+                        *prewarmed &= ix.abs_diff(self.current) <= 1;
+                    }
+                    if self.next_to_prewarm().is_some() {
+                        ctx.request_idle_work();
+                    }
+                } else {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_layout();
+            }
+            LifeCycle::Idle(deadline) => {
+                // Each idle event prepares one page, since the layout happens after it
+                if Instant::now() < *deadline {
+                    if let Some(ix) = self.next_to_prewarm() {
+                        trace!("Pager {:?} laying out page {} ahead", ctx.widget_id(), ix);
+                        self.prewarmed[ix] = true;
+                        ctx.request_layout();
+                    }
+                }
+                if self.next_to_prewarm().is_some() {
+                    ctx.request_idle_work();
+                }
+            }
+            _ => (),
+        }
+        for page in &mut self.pages {
+            page.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let max = bc.max();
+        let width = if bc.is_width_bounded() {
+            max.width
+        } else {
+            theme::WIDE_WIDGET_WIDTH
+        };
+        let max_page_height = (max.height - INDICATOR_HEIGHT).max(0.0);
+        let min_page_height = (bc.min().height - INDICATOR_HEIGHT).max(0.0);
+        // Pages are as wide as the pager, and as tall as the tallest page which is
+        // laid out
+        let page_bc = BoxConstraints::new(
+            Size::new(width, min_page_height),
+            Size::new(width, max_page_height),
+        );
+        let mut page_height = min_page_height;
+        for ix in 0..self.pages.len() {
+            let laid_out = self.is_laid_out(ix);
+            let page = &mut self.pages[ix];
+            // Pages which aren't laid out are stashed, so that they aren't painted
+            ctx.set_stashed(page, !laid_out);
+            if laid_out {
+                let page_size = page.layout(ctx, &page_bc);
+                page_height = page_height.max(page_size.height);
+            }
+        }
+        for (ix, page) in self.pages.iter_mut().enumerate() {
+            if !ctx.get_ref(page).state().is_stashed {
+                let x = (ix as f64 - self.offset) * width;
+                ctx.place_child(page, Point::new(x, 0.0));
+            }
+        }
+
+        let size = bc.constrain(Size::new(width, page_height + INDICATOR_HEIGHT));
+        trace!("Computed layout: size={}, offset={}", size, self.offset);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let clip_rect = Self::page_size(size).to_rect();
+        ctx.clip_children(clip_rect);
+
+        scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
+        for page in &mut self.pages {
+            let page_state = ctx.get_ref(page).state();
+            if page_state.is_stashed {
+                continue;
+            }
+            let visible = (page_state.layout_rect().min_x() - clip_rect.max_x()) < 0.0
+                && (page_state.layout_rect().max_x() - clip_rect.min_x()) > 0.0;
+            if visible {
+                page.paint(ctx, scene);
+            } else {
+                ctx.skip_child(page);
+            }
+        }
+        scene.pop_layer();
+
+        for page in 0..self.pages.len() {
+            // The dots of the pages on screen light up as much as they are shown
+            let shown = (1.0 - (page as f64 - self.offset).abs()).clamp(0.0, 1.0);
+            let color = theme::BORDER_LIGHT.with_alpha_factor(0.4 + 0.6 * shown as f32);
+            let dot = Circle::new(self.dot_center(size, page), DOT_RADIUS);
+            fill_color(scene, &dot, color);
+        }
+        if ctx.is_focused() {
+            let focus_rect = Rect::from_center_size(
+                self.dot_center(size, self.current),
+                Size::new(DOT_SPACING, DOT_SPACING),
+            );
+            crate::paint_scene_helpers::stroke(
+                scene,
+                &focus_rect.to_rounded_rect(DOT_SPACING / 2.0),
+                theme::PRIMARY_LIGHT,
+                1.0,
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::TabPanel
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_clips_children();
+        ctx.current_node()
+            .set_value(format!("Page {} of {}", self.current + 1, self.pages.len()));
+        for page in &mut self.pages {
+            page.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.pages.iter().map(|page| page.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Pager")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::Label;
+
+    fn pager() -> Pager {
+        Pager::new()
+            .with_page(Label::new("One"))
+            .with_page(Label::new("Two"))
+            .with_page(Label::new("Three"))
+    }
+
+    fn settle(harness: &mut TestHarness) {
+        // The first frame starts the animation
+        harness.animate(Duration::ZERO);
+        for _ in 0..120 {
+            harness.animate(Duration::from_millis(16));
+        }
+    }
+
+    fn offset(harness: &TestHarness) -> f64 {
+        harness.root_widget().downcast::<Pager>().unwrap().offset
+    }
+
+    #[test]
+    fn pages_snap_after_swipe() {
+        let mut harness = TestHarness::create_with_size(pager(), Size::new(200.0, 100.0));
+        let pager_id = harness.root_widget().id();
+
+        // A short drag snaps back to the same page
+        harness.mouse_move((100.0, 40.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move((90.0, 40.0));
+        assert!(offset(&harness) > 0.0);
+        harness.advance_time(RELEASE_STOP_TIME * 2);
+        harness.mouse_button_release(MouseButton::Left);
+        assert_eq!(harness.pop_action(), None);
+        settle(&mut harness);
+        assert_eq!(offset(&harness), 0.0);
+
+        // A long drag turns the page, however far it goes
+        harness.mouse_move((190.0, 40.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move((10.0, 40.0));
+        harness.mouse_move((-300.0, 40.0));
+        harness.mouse_button_release(MouseButton::Left);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::PageChanged(1), pager_id))
+        );
+        settle(&mut harness);
+        assert_eq!(offset(&harness), 1.0);
+        let second_page = harness.root_widget().children()[1];
+        assert_eq!(second_page.state().layout_rect().origin(), Point::ORIGIN);
+    }
+
+    #[test]
+    fn dots_and_keys_turn_pages() {
+        let size = Size::new(200.0, 100.0);
+        let mut harness = TestHarness::create_with_size(pager(), size);
+        let pager_id = harness.root_widget().id();
+
+        let root = harness.root_widget();
+        let dot = root.downcast::<Pager>().unwrap().dot_center(size, 2);
+        harness.mouse_move(dot);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::PageChanged(2), pager_id))
+        );
+        assert_eq!(harness.focused_widget().map(|w| w.id()), Some(pager_id));
+
+        let root = harness.root_widget();
+        let pager = root.downcast::<Pager>().unwrap();
+        let key = |key| pager.page_for_key(&Key::Named(key));
+        assert_eq!(key(NamedKey::ArrowLeft), Some(1));
+        assert_eq!(key(NamedKey::ArrowRight), Some(2));
+        assert_eq!(key(NamedKey::Home), Some(0));
+        assert_eq!(key(NamedKey::Tab), None);
+
+        harness.edit_root_widget(|mut root| {
+            let mut pager = root.downcast::<Pager>();
+            pager.remove_page(2);
+        });
+        let root = harness.root_widget();
+        assert_eq!(root.downcast::<Pager>().unwrap().current_page(), 1);
+    }
+
+    #[test]
+    fn neighbours_are_laid_out_when_idle() {
+        let pager = pager()
+            .with_page(Label::new("Four"))
+            .with_page(Label::new("Five"))
+            .with_prewarm(true);
+        let mut harness = TestHarness::create_with_size(pager, Size::new(200.0, 100.0));
+        let stashed = |harness: &TestHarness| -> Vec<bool> {
+            let root = harness.root_widget();
+            root.children()
+                .iter()
+                .map(|page| page.state().is_stashed)
+                .collect()
+        };
+
+        // Only the page on screen is laid out until the app is idle
+        assert_eq!(stashed(&harness), [false, true, true, true, true]);
+        assert!(!harness.run_idle_work());
+        assert_eq!(stashed(&harness), [false, false, true, true, true]);
+
+        // Turning forwards prepares the next page first, then the one left behind
+        harness.edit_root_widget(|mut root| {
+            root.downcast::<Pager>().set_current_page(3);
+        });
+        settle(&mut harness);
+        assert_eq!(stashed(&harness), [true, true, true, false, true]);
+        assert!(harness.run_idle_work());
+        assert_eq!(stashed(&harness), [true, true, true, false, false]);
+        assert!(!harness.run_idle_work());
+        assert_eq!(stashed(&harness), [true, true, false, false, false]);
+    }
+}
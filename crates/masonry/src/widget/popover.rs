@@ -0,0 +1,398 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A widget which shows some content next to an anchor widget.
+
+use accesskit::Role;
+use kurbo::{BezPath, Insets, Point, Rect, Size};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::popup::{Placement, PopupRequest};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, InternalLifeCycle, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
+    WidgetPod,
+};
+
+/// The length of the arrow pointing from a popover to its anchor.
+const ARROW_SIZE: f64 = 8.0;
+/// The space between the edge of a popover and its content.
+const POPOVER_PADDING: f64 = 8.0;
+
+/// A widget which shows its content in a panel next to its anchor, with an arrow
+/// pointing at the anchor, while it is open.
+///
+/// The popover takes the size of its anchor. The panel is placed with
+/// [`LayoutCtx::place_popup`], so it is shown on the preferred side of the anchor
+/// unless there isn't enough room in the window there, and it is moved to stay
+/// inside the window.
+///
+/// The panel is painted with [`PaintCtx::paint_popup`], above the rest of the
+/// window, so opening and closing it doesn't repaint the content below it. With
+/// [`PopupMode::NativeWindow`](crate::popup::PopupMode::NativeWindow), it is shown
+/// in a window of its own.
+///
+/// The panel is placed using the popover's position in the window from the
+/// previous layout pass, so it may take an extra pass to settle after the popover
+/// moves.
+pub struct Popover {
+    anchor: WidgetPod<Box<dyn Widget>>,
+    content: WidgetPod<Box<dyn Widget>>,
+    is_open: bool,
+    placement: Placement,
+    /// The panel around the content, in local coordinates, as of the last layout
+    panel: Rect,
+    /// The side of the anchor the panel ended up on, after flipping
+    side: Placement,
+    /// The window origin the panel was placed with
+    window_origin: Point,
+}
+
+impl Popover {
+    /// Create a new closed `Popover`, showing `content` next to `anchor` when opened.
+    pub fn new(anchor: impl Widget, content: impl Widget) -> Self {
+        Self::new_pod(
+            WidgetPod::new(anchor).boxed(),
+            WidgetPod::new(content).boxed(),
+        )
+    }
+
+    /// Create a new closed `Popover` from widgets that are already in `WidgetPod`s.
+    pub fn new_pod(
+        anchor: WidgetPod<Box<dyn Widget>>,
+        content: WidgetPod<Box<dyn Widget>>,
+    ) -> Self {
+        Popover {
+            anchor,
+            content: content.with_stashed(true),
+            is_open: false,
+            placement: Placement::default(),
+            panel: Rect::ZERO,
+            side: Placement::default(),
+            window_origin: Point::ORIGIN,
+        }
+    }
+
+    /// Builder-style method to set whether the content is shown.
+    pub fn with_open(mut self, is_open: bool) -> Self {
+        self.is_open = is_open;
+        self.content.created_state_mut().is_stashed = !is_open;
+        self
+    }
+
+    /// Builder-style method to set the side of the anchor the content is shown on,
+    /// if there is room.
+    pub fn with_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Whether the content is shown.
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// The side of the anchor the content was shown on in the last layout pass.
+    pub fn side(&self) -> Placement {
+        self.side
+    }
+
+    fn paint_panel(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let panel = self.panel.to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+        fill_color(scene, &panel, theme::BACKGROUND_DARK);
+        stroke(scene, &panel, theme::BORDER_LIGHT, 1.0);
+
+        // The arrow points at the middle of the anchor, as far as the panel's corners allow
+        let anchor = ctx.child_layout_rect(&self.anchor).center();
+        let margin = theme::BUTTON_BORDER_RADIUS + ARROW_SIZE;
+        let along = |target: f64, start: f64, end: f64| {
+            if end - start < 2.0 * margin {
+                (start + end) / 2.0
+            } else {
+                target.clamp(start + margin, end - margin)
+            }
+        };
+        // The base of the arrow overlaps the panel, to cover its border
+        let (base_start, base_end, tip) = match self.side {
+            Placement::Below => {
+                let x = along(anchor.x, self.panel.x0, self.panel.x1);
+                let y = self.panel.y0 + 1.0;
+                (
+                    (x - ARROW_SIZE, y),
+                    (x + ARROW_SIZE, y),
+                    (x, y - ARROW_SIZE - 1.0),
+                )
+            }
+            Placement::Above => {
+                let x = along(anchor.x, self.panel.x0, self.panel.x1);
+                let y = self.panel.y1 - 1.0;
+                (
+                    (x - ARROW_SIZE, y),
+                    (x + ARROW_SIZE, y),
+                    (x, y + ARROW_SIZE + 1.0),
+                )
+            }
+            Placement::Right => {
+                let y = along(anchor.y, self.panel.y0, self.panel.y1);
+                let x = self.panel.x0 + 1.0;
+                (
+                    (x, y - ARROW_SIZE),
+                    (x, y + ARROW_SIZE),
+                    (x - ARROW_SIZE - 1.0, y),
+                )
+            }
+            Placement::Left => {
+                let y = along(anchor.y, self.panel.y0, self.panel.y1);
+                let x = self.panel.x1 - 1.0;
+                (
+                    (x, y - ARROW_SIZE),
+                    (x, y + ARROW_SIZE),
+                    (x + ARROW_SIZE + 1.0, y),
+                )
+            }
+        };
+        let mut edges = BezPath::new();
+        edges.move_to(base_start);
+        edges.line_to(tip);
+        edges.line_to(base_end);
+        let mut arrow = edges.clone();
+        arrow.close_path();
+        fill_color(scene, &arrow, theme::BACKGROUND_DARK);
+        stroke(scene, &edges, theme::BORDER_LIGHT, 1.0);
+
+        self.content.paint(ctx, scene);
+    }
+}
+
+impl WidgetMut<'_, Popover> {
+    pub fn anchor_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.anchor)
+    }
+
+    pub fn content_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.content)
+    }
+
+    /// Set whether the content is shown.
+    pub fn set_open(&mut self, is_open: bool) {
+        if self.widget.is_open != is_open {
+            self.widget.is_open = is_open;
+            self.ctx.set_stashed(&mut self.widget.content, !is_open);
+            self.ctx.request_accessibility_update();
+        }
+    }
+
+    /// Set the side of the anchor the content is shown on, if there is room.
+    pub fn set_placement(&mut self, placement: Placement) {
+        self.widget.placement = placement;
+        self.ctx.request_layout();
+    }
+}
+
+impl Widget for Popover {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        // The content is above the anchor, so it gets the event first
+        self.content.on_pointer_event(ctx, event);
+        self.anchor.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.content.on_text_event(ctx, event);
+        self.anchor.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.content.on_access_event(ctx, event);
+        self.anchor.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::Internal(InternalLifeCycle::ParentWindowOrigin { .. }) = event {
+            if self.is_open && ctx.window_origin() != self.window_origin {
+                trace!("Popover {:?} moved in the window", ctx.widget_id());
+                ctx.request_layout();
+            }
+        }
+        self.anchor.lifecycle(ctx, event);
+        self.content.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.anchor.layout(ctx, bc);
+        ctx.place_child(&mut self.anchor, Point::ORIGIN);
+
+        if !self.is_open {
+            ctx.set_paint_insets(Insets::ZERO);
+            trace!("Computed layout: size={}", size);
+            return size;
+        }
+
+        // The panel is placed relative to the window, so we remember where we were in it
+        self.window_origin = ctx.widget_state.window_origin();
+        let padding = Size::new(2.0 * POPOVER_PADDING, 2.0 * POPOVER_PADDING);
+        let window_size = ctx.window_size();
+        let max_content_size = Size::new(
+            (window_size.width - padding.width).max(0.0),
+            (window_size.height - padding.height).max(0.0),
+        );
+        let content_size = self
+            .content
+            .layout(ctx, &BoxConstraints::new(Size::ZERO, max_content_size));
+        let request = PopupRequest::new(size.to_rect(), content_size + padding)
+            .with_placement(self.placement)
+            .with_gap(ARROW_SIZE);
+        let placement = ctx.place_popup(request);
+        let panel = placement.rect;
+        ctx.place_child(
+            &mut self.content,
+            panel.origin() + (POPOVER_PADDING, POPOVER_PADDING),
+        );
+        // Other popups can move the panel without this popover asking for layout
+        if (panel, placement.side) != (self.panel, self.side) {
+            ctx.request_paint();
+        }
+        self.panel = panel;
+        self.side = placement.side;
+
+        let painted = panel.inflate(ARROW_SIZE, ARROW_SIZE).union(size.to_rect());
+        ctx.set_paint_insets(painted - size.to_rect());
+        trace!("Computed layout: size={}, panel={}", size, panel);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.anchor.paint(ctx, scene);
+        if self.is_open {
+            // The panel is above the rest of the window, and isn't repainted with it
+            let popup = self.panel.inflate(ARROW_SIZE, ARROW_SIZE);
+            ctx.paint_popup(popup, |ctx, scene| self.paint_panel(ctx, scene));
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_expanded(self.is_open);
+        self.anchor.accessibility(ctx);
+        self.content.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.anchor.id(), self.content.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Popover")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::paint_scene_helpers::UnitPoint;
+    use crate::popup::PopupMode;
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
+    use crate::widget::{Align, Flex, SizedBox};
+
+    #[test]
+    fn open_popover_stays_in_window() {
+        let [content_id] = widget_ids();
+        let popover = Popover::new_pod(
+            WidgetPod::new(SizedBox::empty().width(40.0).height(20.0)).boxed(),
+            WidgetPod::new_with_id(SizedBox::empty().width(100.0).height(50.0), content_id).boxed(),
+        )
+        .with_open(true);
+        let widget = Align::new(UnitPoint::BOTTOM_RIGHT, popover);
+        let harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+
+        // The anchor is in the bottom right corner, so the content goes above it
+        let content = harness.get_widget(content_id).state().window_layout_rect();
+        let bottom = 200.0 - 20.0 - ARROW_SIZE - POPOVER_PADDING;
+        assert_eq!(
+            content,
+            Rect::new(
+                200.0 - POPOVER_PADDING - 100.0,
+                bottom - 50.0,
+                200.0 - POPOVER_PADDING,
+                bottom,
+            )
+        );
+    }
+
+    #[test]
+    fn panel_is_painted_apart_from_content() {
+        let [popover_id] = widget_ids();
+        let paints = Rc::new(Cell::new(0));
+        let counted_paints = paints.clone();
+        let content = ModularWidget::new(())
+            .layout_fn(|_, _, _| Size::new(100.0, 40.0))
+            .paint_fn(move |_, _, _| counted_paints.set(counted_paints.get() + 1));
+        let popover = Popover::new(
+            SizedBox::empty().width(40.0).height(20.0),
+            SizedBox::empty().width(60.0).height(30.0),
+        );
+        let widget = Flex::column()
+            .with_child(SizedBox::new(content))
+            .with_child_id(popover, popover_id);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+        let _ = harness.render();
+        assert_eq!(paints.get(), 1);
+        assert!(harness.overlay_owners().is_empty());
+
+        let set_open = |harness: &mut TestHarness, is_open: bool| {
+            harness.edit_root_widget(|mut root| {
+                let mut flex = root.downcast::<Flex>();
+                let mut popover = flex.child_mut(1).unwrap();
+                popover.downcast::<Popover>().set_open(is_open);
+            });
+            let _ = harness.render();
+        };
+
+        // Opening and closing the popover doesn't repaint the content next to it
+        set_open(&mut harness, true);
+        assert_eq!(harness.overlay_owners(), [popover_id]);
+        set_open(&mut harness, false);
+        assert!(harness.overlay_owners().is_empty());
+        assert_eq!(paints.get(), 1);
+    }
+
+    #[test]
+    fn native_popups_can_leave_the_window() {
+        let [content_id] = widget_ids();
+        let popover = Popover::new_pod(
+            WidgetPod::new(SizedBox::empty().width(40.0).height(20.0)).boxed(),
+            WidgetPod::new_with_id(SizedBox::empty().width(100.0).height(50.0), content_id).boxed(),
+        )
+        .with_open(true);
+        let widget = Align::new(UnitPoint::BOTTOM_RIGHT, popover);
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+        harness.set_popup_mode(PopupMode::NativeWindow);
+        harness.set_screen_rect(Some(Rect::new(-500.0, -500.0, 700.0, 700.0)));
+        let _ = harness.render();
+
+        // The content goes below the anchor, past the bottom of the window
+        let content = harness.get_widget(content_id).state().window_layout_rect();
+        assert_eq!(content.y0, 200.0 + ARROW_SIZE + POPOVER_PADDING);
+        let popups = harness.native_popups();
+        assert_eq!(popups.len(), 1);
+        assert!(popups[0].1.contains(content.center()));
+
+        // Back in the window, the popup is an overlay again
+        harness.set_popup_mode(PopupMode::InWindow);
+        let _ = harness.render();
+        assert!(harness.native_popups().is_empty());
+        let content = harness.get_widget(content_id).state().window_layout_rect();
+        assert!(content.y1 <= 200.0);
+    }
+}
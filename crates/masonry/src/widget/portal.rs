@@ -3,22 +3,38 @@
 
 #![allow(missing_docs)]
 
+use std::cell::Cell;
 use std::ops::Range;
+use std::rc::Rc;
 
 use accesskit::Role;
 use kurbo::Affine;
 use smallvec::{smallvec, SmallVec};
-use tracing::{trace_span, Span};
+use tracing::{trace_span, warn, Span};
 use vello::peniko::BlendMode;
 use vello::Scene;
 
 use crate::kurbo::{Point, Rect, Size, Vec2};
-use crate::widget::{Axis, ScrollBar, WidgetMut, WidgetRef};
+use crate::widget::{
+    Axis, ScrollBar, ScrollBarStyle, ScrollBarVisibility, Spinner, WidgetMut, WidgetRef,
+};
 use crate::{
-    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, StatusChange, TextEvent, Widget, WidgetPod,
+    AccessCtx, AccessEvent, Action, BoxConstraints, EventCtx, Interactivity, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
 };
 
+/// How far a portal must be pulled down past its top to request a refresh.
+///
+/// This is also the height of the band the refresh spinner is shown in.
+const PULL_TO_REFRESH_DISTANCE: f64 = 60.0;
+const REFRESH_SPINNER_SIZE: f64 = 24.0;
+/// How fast a portal scrolls while a drag is past its edge, in logical pixels per
+/// second for every pixel the pointer is past the edge.
+const AUTOSCROLL_RATE: f64 = 10.0;
+/// The fastest a portal scrolls while a drag is past its edge, in logical pixels
+/// per second.
+const MAX_AUTOSCROLL_SPEED: f64 = 3000.0;
+
 // TODO - refactor - see issue #15
 // TODO - rename "Portal" to "ScrollPortal"?
 // Conceptually, a Portal is a Widget giving a restricted view of a child widget
@@ -38,12 +54,105 @@ pub struct Portal<W: Widget> {
     scrollbar_horizontal_visible: bool,
     scrollbar_vertical: WidgetPod<ScrollBar>,
     scrollbar_vertical_visible: bool,
+    scroll_sync: Option<(ScrollSyncGroup, SyncAxes)>,
+    end_threshold: Option<f64>,
+    /// Whether the viewport was within the end threshold in the last layout
+    reached_end: bool,
+    pull_to_refresh: bool,
+    /// The pointer height a pull started at, if the portal is being pulled
+    pull_start: Option<f64>,
+    pull_distance: f64,
+    /// Only exists while refreshing, so that it doesn't animate otherwise
+    refresh_spinner: Option<WidgetPod<Spinner>>,
+    /// How far past the edges the pointer is, while dragging from the content,
+    /// such as to select text, which scrolls towards the pointer
+    autoscroll_overshoot: Vec2,
+    /// The key the scroll position is remembered under in the window
+    scroll_key: Option<String>,
+    /// Whether the remembered position still has to be restored
+    restore_scroll: bool,
+    /// Where to scroll to on the next layout pass, once the content is laid out
+    scroll_request: Option<ScrollRequest>,
+}
+
+/// A scroll position requested through a [`WidgetMut`].
+#[derive(Clone, Copy, Debug)]
+enum ScrollRequest {
+    Position(Point),
+    Widget(WidgetId),
+}
+
+/// A shared scroll offset, which keeps several [`Portal`]s scrolled together.
+///
+/// Clones of a group are the same group. When a portal in the group is scrolled,
+/// the other portals in it take the same offset on the axes they are synchronized
+/// on, on the next layout pass. For example, the header and body of a table can
+/// be kept aligned by synchronizing them horizontally.
+#[derive(Clone, Debug, Default)]
+pub struct ScrollSyncGroup {
+    offset: Rc<Cell<Point>>,
+}
+
+/// The axes on which a [`Portal`] follows its [`ScrollSyncGroup`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyncAxes {
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl ScrollSyncGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The offset of the portal in the group which was scrolled last.
+    pub fn offset(&self) -> Point {
+        self.offset.get()
+    }
+
+    /// Whether both groups are clones of the same group.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.offset, &other.offset)
+    }
+}
+
+impl SyncAxes {
+    fn includes(self, axis: Axis) -> bool {
+        matches!(
+            (self, axis),
+            (SyncAxes::Both, _)
+                | (SyncAxes::Horizontal, Axis::Horizontal)
+                | (SyncAxes::Vertical, Axis::Vertical)
+        )
+    }
+
+    /// Take the coordinates of `pos` on these axes from `synced`.
+    fn merge(self, pos: Point, synced: Point) -> Point {
+        Point::new(
+            if self.includes(Axis::Horizontal) {
+                synced.x
+            } else {
+                pos.x
+            },
+            if self.includes(Axis::Vertical) {
+                synced.y
+            } else {
+                pos.y
+            },
+        )
+    }
 }
 
 impl<W: Widget> Portal<W> {
     pub fn new(child: W) -> Self {
+        Self::new_pod(WidgetPod::new(child))
+    }
+
+    pub fn new_pod(child: WidgetPod<W>) -> Self {
         Portal {
-            child: WidgetPod::new(child),
+            child,
             viewport_pos: Point::ORIGIN,
             constrain_horizontal: false,
             constrain_vertical: false,
@@ -53,6 +162,17 @@ impl<W: Widget> Portal<W> {
             scrollbar_horizontal_visible: false,
             scrollbar_vertical: WidgetPod::new(ScrollBar::new(Axis::Vertical, 1.0, 1.0)),
             scrollbar_vertical_visible: false,
+            scroll_sync: None,
+            end_threshold: None,
+            reached_end: false,
+            pull_to_refresh: false,
+            pull_start: None,
+            pull_distance: 0.0,
+            refresh_spinner: None,
+            autoscroll_overshoot: Vec2::ZERO,
+            scroll_key: None,
+            restore_scroll: false,
+            scroll_request: None,
         }
     }
 
@@ -60,10 +180,6 @@ impl<W: Widget> Portal<W> {
         self.viewport_pos
     }
 
-    pub fn child(&self) -> WidgetRef<'_, W> {
-        self.child.as_ref()
-    }
-
     // TODO - rewrite doc
     /// Builder-style method for deciding whether to constrain the child vertically.
     ///
@@ -101,6 +217,116 @@ impl<W: Widget> Portal<W> {
         self.must_fill = must_fill;
         self
     }
+
+    /// Builder-style method to keep this portal's offset synchronized with the other
+    /// portals in `group`, on the given `axes`.
+    pub fn with_scroll_sync(mut self, group: ScrollSyncGroup, axes: SyncAxes) -> Self {
+        self.scroll_sync = Some((group, axes));
+        self
+    }
+
+    /// Builder-style method to submit [`Action::ScrollReachedEnd`] when the bottom
+    /// of the viewport comes within `threshold` of the bottom of the content.
+    ///
+    /// The action is submitted once each time the viewport reaches that area,
+    /// including when the content is too short to fill the portal. It is meant for
+    /// loading more content as the user scrolls down.
+    pub fn with_end_threshold(mut self, threshold: f64) -> Self {
+        self.end_threshold = Some(threshold);
+        self
+    }
+
+    /// Builder-style method to set whether pulling the portal down past its top
+    /// submits [`Action::RefreshRequested`].
+    ///
+    /// Once a refresh is requested, the portal shows a spinner above its content
+    /// until it is told the refresh is complete with [`set_refreshing`].
+    ///
+    /// [`set_refreshing`]: WidgetMut::set_refreshing
+    pub fn with_pull_to_refresh(mut self, pull_to_refresh: bool) -> Self {
+        self.pull_to_refresh = pull_to_refresh;
+        self
+    }
+
+    /// Builder-style method to show the refresh spinner above the content.
+    pub fn with_refreshing(mut self, refreshing: bool) -> Self {
+        self.refresh_spinner = refreshing.then(|| WidgetPod::new(Spinner::new()));
+        self
+    }
+
+    /// Whether the portal is showing its refresh spinner.
+    pub fn is_refreshing(&self) -> bool {
+        self.refresh_spinner.is_some()
+    }
+
+    /// Remember the scroll position under `key`, so that a portal created later
+    /// with the same key, such as when the user navigates back to a list, is
+    /// scrolled to where this one was.
+    ///
+    /// The positions are kept by the window; see
+    /// [`RenderRoot::scroll_positions`](crate::render_root::RenderRoot::scroll_positions).
+    pub fn with_scroll_key(mut self, key: impl Into<String>) -> Self {
+        self.scroll_key = Some(key.into());
+        self.restore_scroll = true;
+        self
+    }
+
+    /// Builder-style method to set the size, shape and colors of both scrollbars.
+    pub fn with_scrollbar_style(mut self, style: ScrollBarStyle) -> Self {
+        self.scrollbar_horizontal.created_widget_mut().style = style;
+        self.scrollbar_vertical.created_widget_mut().style = style;
+        self
+    }
+
+    /// Builder-style method to set when both scrollbars are shown.
+    ///
+    /// The default depends on the platform; see [`ScrollBarVisibility`].
+    pub fn with_scrollbar_visibility(mut self, visibility: ScrollBarVisibility) -> Self {
+        self.scrollbar_horizontal.created_widget_mut().visibility = visibility;
+        self.scrollbar_vertical.created_widget_mut().visibility = visibility;
+        self
+    }
+
+    /// Builder-style method to scroll to `position` in the first layout pass.
+    ///
+    /// See [`WidgetMut::scroll_to`](WidgetMut#method.scroll_to).
+    pub fn with_scroll_to(mut self, position: Point) -> Self {
+        self.scroll_request = Some(ScrollRequest::Position(position));
+        self
+    }
+
+    /// Builder-style method to show the descendant `id` in the first layout pass.
+    pub fn with_scroll_to_widget(mut self, id: WidgetId) -> Self {
+        self.scroll_request = Some(ScrollRequest::Widget(id));
+        self
+    }
+}
+
+/// The viewport position which shows `target` with the least panning from
+/// `viewport`, both in child coordinates.
+fn pan_target(viewport: Rect, target: Rect) -> Point {
+    let x = compute_pan_range(
+        viewport.min_x()..viewport.max_x(),
+        target.min_x()..target.max_x(),
+    )
+    .start;
+    let y = compute_pan_range(
+        viewport.min_y()..viewport.max_y(),
+        target.min_y()..target.max_y(),
+    )
+    .start;
+    Point::new(x, y)
+}
+
+/// The layout rect of the descendant `id` of `widget`, in `widget`'s coordinates.
+fn rect_in(widget: WidgetRef<'_, dyn Widget>, id: WidgetId) -> Option<Rect> {
+    if widget.id() == id {
+        return Some(widget.state().layout_rect().size().to_rect());
+    }
+    widget.children().into_iter().find_map(|child| {
+        let origin = child.state().layout_rect().origin().to_vec2();
+        rect_in(child, id).map(|rect| rect + origin)
+    })
 }
 
 fn compute_pan_range(mut viewport: Range<f64>, target: Range<f64>) -> Range<f64> {
@@ -146,6 +372,55 @@ impl<W: Widget> Portal<W> {
             false
         }
     }
+
+    /// Share the viewport position with the other portals in our sync group.
+    ///
+    /// This is called when the position changes because of user input or a
+    /// [`WidgetMut`] method, but not when layout recomputes it.
+    fn publish_viewport_pos(&self) {
+        if let Some((group, axes)) = &self.scroll_sync {
+            group
+                .offset
+                .set(axes.merge(group.offset.get(), self.viewport_pos));
+        }
+    }
+
+    /// How far `pointer`, in window coordinates, is past the edges of the viewport
+    /// on the axes which can scroll, negative for the top and left edges.
+    fn overshoot(ctx: &EventCtx, pointer: Point, content_size: Size) -> Vec2 {
+        let portal_size = ctx.size();
+        let pointer = pointer - ctx.window_origin().to_vec2();
+        let past = |pos: f64, size: f64, content: f64| {
+            if content <= size {
+                0.0
+            } else if pos < 0.0 {
+                pos
+            } else {
+                (pos - size).max(0.0)
+            }
+        };
+        Vec2::new(
+            past(pointer.x, portal_size.width, content_size.width),
+            past(pointer.y, portal_size.height, content_size.height),
+        )
+    }
+
+    /// The progress of the horizontal and vertical scrollbars matching the
+    /// viewport position.
+    fn scrollbar_progress(&self, portal_size: Size, content_size: Size) -> (f64, f64) {
+        let max_pos = content_size - portal_size;
+        let progress = |pos: f64, max: f64| if max > 0.0 { pos / max } else { 0.0 };
+        (
+            progress(self.viewport_pos.x, max_pos.width),
+            progress(self.viewport_pos.y, max_pos.height),
+        )
+    }
+}
+
+impl<'w, W: Widget> WidgetRef<'w, Portal<W>> {
+    pub fn child(&self) -> WidgetRef<'w, W> {
+        self.get_child(&self.deref().child)
+    }
 }
 
 impl<W: Widget> WidgetMut<'_, Portal<W>> {
@@ -193,20 +468,98 @@ impl<W: Widget> WidgetMut<'_, Portal<W>> {
         self.ctx.request_layout();
     }
 
+    /// Keep this portal's offset synchronized with the other portals in `group`, on
+    /// the given `axes`, or stop synchronizing it if `sync` is `None`.
+    pub fn set_scroll_sync(&mut self, sync: Option<(ScrollSyncGroup, SyncAxes)>) {
+        self.widget.scroll_sync = sync;
+        self.ctx.request_layout();
+    }
+
+    /// Set how close to the end of the content the viewport must come to submit
+    /// [`Action::ScrollReachedEnd`], or stop submitting it if `threshold` is `None`.
+    ///
+    /// See [`Portal::with_end_threshold`] for details.
+    pub fn set_end_threshold(&mut self, threshold: Option<f64>) {
+        self.widget.end_threshold = threshold;
+        self.widget.reached_end = false;
+        self.ctx.request_layout();
+    }
+
+    /// Set whether pulling the portal down past its top submits
+    /// [`Action::RefreshRequested`].
+    pub fn set_pull_to_refresh(&mut self, pull_to_refresh: bool) {
+        self.widget.pull_to_refresh = pull_to_refresh;
+        if !pull_to_refresh {
+            self.widget.pull_start = None;
+            self.widget.pull_distance = 0.0;
+            self.ctx.request_layout();
+        }
+    }
+
+    /// Show or hide the refresh spinner above the content.
+    ///
+    /// The portal starts refreshing on its own when it is pulled to refresh, and
+    /// should be told when the refresh is complete.
+    pub fn set_refreshing(&mut self, refreshing: bool) {
+        if refreshing == self.widget.is_refreshing() {
+            return;
+        }
+        if refreshing {
+            self.widget.refresh_spinner = Some(WidgetPod::new(Spinner::new()));
+            self.ctx.children_changed();
+        } else if let Some(spinner) = self.widget.refresh_spinner.take() {
+            self.ctx.remove_child(spinner);
+        }
+        self.ctx.request_layout();
+    }
+
+    /// Remember the scroll position under `key`, or stop remembering it if `key`
+    /// is `None`, and scroll to the position remembered under the new key, if any.
+    ///
+    /// See [`Portal::with_scroll_key`] for more.
+    pub fn set_scroll_key(&mut self, key: Option<String>) {
+        self.widget.restore_scroll = key.is_some();
+        self.widget.scroll_key = key;
+        self.ctx.request_layout();
+    }
+
+    /// Set the size, shape and colors of both scrollbars.
+    pub fn set_scrollbar_style(&mut self, style: ScrollBarStyle) {
+        self.horizontal_scrollbar_mut().set_style(style);
+        self.vertical_scrollbar_mut().set_style(style);
+    }
+
+    /// Set when both scrollbars are shown.
+    pub fn set_scrollbar_visibility(&mut self, visibility: ScrollBarVisibility) {
+        self.horizontal_scrollbar_mut().set_visibility(visibility);
+        self.vertical_scrollbar_mut().set_visibility(visibility);
+    }
+
     pub fn set_viewport_pos(&mut self, position: Point) -> bool {
         let portal_size = self.ctx.widget_state.layout_rect().size();
-        let content_size = self.widget.child.layout_rect().size();
+        let content_size = self
+            .ctx
+            .get_ref(&self.widget.child)
+            .state()
+            .layout_rect()
+            .size();
 
         let pos_changed = self
             .widget
             .set_viewport_pos_raw(portal_size, content_size, position);
         if pos_changed {
-            let progress_x = self.widget.viewport_pos.x / (content_size - portal_size).width;
-            self.horizontal_scrollbar_mut()
-                .set_cursor_progress(progress_x);
-            let progress_y = self.widget.viewport_pos.y / (content_size - portal_size).height;
-            self.vertical_scrollbar_mut()
-                .set_cursor_progress(progress_y);
+            let max_pos = content_size - portal_size;
+            if max_pos.width > 0.0 {
+                let progress_x = self.widget.viewport_pos.x / max_pos.width;
+                self.horizontal_scrollbar_mut()
+                    .set_cursor_progress(progress_x);
+            }
+            if max_pos.height > 0.0 {
+                let progress_y = self.widget.viewport_pos.y / max_pos.height;
+                self.vertical_scrollbar_mut()
+                    .set_cursor_progress(progress_y);
+            }
+            self.widget.publish_viewport_pos();
             self.ctx.request_layout();
         }
         pos_changed
@@ -219,37 +572,96 @@ impl<W: Widget> WidgetMut<'_, Portal<W>> {
     // Note - Rect is in child coordinates
     pub fn pan_viewport_to(&mut self, target: Rect) -> bool {
         let viewport = Rect::from_origin_size(self.widget.viewport_pos, self.ctx.widget_state.size);
+        self.set_viewport_pos(pan_target(viewport, target))
+    }
 
-        let new_pos_x = compute_pan_range(
-            viewport.min_x()..viewport.max_x(),
-            target.min_x()..target.max_x(),
-        )
-        .start;
-        let new_pos_y = compute_pan_range(
-            viewport.min_y()..viewport.max_y(),
-            target.min_y()..target.max_y(),
-        )
-        .start;
+    /// Scroll so that `position`, in child coordinates, is at the top left of the
+    /// viewport, or as close as the content allows.
+    ///
+    /// Unlike [`set_viewport_pos`](Self::set_viewport_pos), this happens in the next
+    /// layout pass, so it can be called before the content is laid out, such as
+    /// right after it was changed.
+    pub fn scroll_to(&mut self, position: Point) {
+        self.widget.scroll_request = Some(ScrollRequest::Position(position));
+        self.ctx.request_layout();
+    }
 
-        self.set_viewport_pos(Point::new(new_pos_x, new_pos_y))
+    /// Scroll as little as possible to show the descendant `id`, in the next layout
+    /// pass.
+    ///
+    /// Nothing happens if `id` isn't in the content of this portal.
+    pub fn scroll_to_widget(&mut self, id: WidgetId) {
+        self.widget.scroll_request = Some(ScrollRequest::Widget(id));
+        self.ctx.request_layout();
     }
 }
 
 impl<W: Widget> Widget for Portal<W> {
     fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
         let portal_size = ctx.size();
-        let content_size = self.child.layout_rect().size();
+        let content_size = ctx.child_layout_rect(&self.child).size();
+        let child_has_active = ctx.get_ref(&self.child).state().has_active;
+
+        // A drag from the content past our edges scrolls towards the pointer, see
+        // the `AnimFrame` handling in `lifecycle`
+        self.autoscroll_overshoot = match event {
+            PointerEvent::PointerMove(state) if child_has_active && self.pull_start.is_none() => {
+                let pointer = Point::new(state.position.x, state.position.y);
+                Self::overshoot(ctx, pointer, content_size)
+            }
+            _ => Vec2::ZERO,
+        };
+        if self.autoscroll_overshoot != Vec2::ZERO {
+            ctx.request_anim_frame();
+        }
 
         match event {
-            PointerEvent::MouseWheel(delta, _) => {
-                self.set_viewport_pos_raw(
-                    portal_size,
-                    content_size,
-                    self.viewport_pos + Vec2::new(delta.x, delta.y),
-                );
-                // TODO - horizontal scrolling?
-                ctx.get_mut(&mut self.scrollbar_vertical)
-                    .set_cursor_progress(self.viewport_pos.y / (content_size - portal_size).height);
+            PointerEvent::MouseWheel(delta, state) => {
+                let max_pos = content_size - portal_size;
+                // Mice without a horizontal wheel scroll horizontally with Shift held
+                let delta = if state.mods.state().shift_key() && delta.x == 0.0 {
+                    Vec2::new(delta.y, 0.0)
+                } else {
+                    Vec2::new(delta.x, delta.y)
+                };
+                let changed =
+                    self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos + delta);
+                if changed {
+                    if max_pos.width > 0.0 {
+                        ctx.get_mut(&mut self.scrollbar_horizontal)
+                            .set_cursor_progress(self.viewport_pos.x / max_pos.width);
+                    }
+                    if max_pos.height > 0.0 {
+                        ctx.get_mut(&mut self.scrollbar_vertical)
+                            .set_cursor_progress(self.viewport_pos.y / max_pos.height);
+                    }
+                    self.publish_viewport_pos();
+                    ctx.request_layout();
+                }
+            }
+            PointerEvent::PointerDown(_, state)
+                if self.pull_to_refresh && !self.is_refreshing() && self.viewport_pos.y <= 0.0 =>
+            {
+                self.pull_start = Some(state.position.y);
+            }
+            PointerEvent::PointerMove(state) => {
+                if let Some(pull_start) = self.pull_start {
+                    // The content follows the pointer, up to the refresh distance
+                    self.pull_distance =
+                        (state.position.y - pull_start).clamp(0.0, PULL_TO_REFRESH_DISTANCE);
+                    ctx.request_layout();
+                }
+            }
+            PointerEvent::PointerUp(_, _) | PointerEvent::PointerLeave(_)
+                if self.pull_start.is_some() =>
+            {
+                self.pull_start = None;
+                if self.pull_distance >= PULL_TO_REFRESH_DISTANCE {
+                    self.refresh_spinner = Some(WidgetPod::new(Spinner::new()));
+                    ctx.children_changed();
+                    ctx.submit_action(Action::RefreshRequested);
+                }
+                self.pull_distance = 0.0;
                 ctx.request_layout();
             }
             _ => (),
@@ -259,26 +671,34 @@ impl<W: Widget> Widget for Portal<W> {
         self.scrollbar_horizontal.on_pointer_event(ctx, event);
         self.scrollbar_vertical.on_pointer_event(ctx, event);
 
-        if self.scrollbar_horizontal.widget().moved {
-            let progress = self.scrollbar_horizontal.widget().cursor_progress;
-            self.scrollbar_horizontal.widget_mut().moved = false;
+        let (moved, progress) = {
+            let scrollbar = ctx.get_mut(&mut self.scrollbar_horizontal);
+            let moved = std::mem::take(&mut scrollbar.widget.moved);
+            (moved, scrollbar.widget.cursor_progress)
+        };
+        if moved {
             self.viewport_pos = Axis::Horizontal
                 .pack(
                     progress * Axis::Horizontal.major(content_size - portal_size),
                     Axis::Horizontal.minor_pos(self.viewport_pos),
                 )
                 .into();
+            self.publish_viewport_pos();
             ctx.request_layout();
         }
-        if self.scrollbar_vertical.widget().moved {
-            let progress = self.scrollbar_vertical.widget().cursor_progress;
-            self.scrollbar_vertical.widget_mut().moved = false;
+        let (moved, progress) = {
+            let scrollbar = ctx.get_mut(&mut self.scrollbar_vertical);
+            let moved = std::mem::take(&mut scrollbar.widget.moved);
+            (moved, scrollbar.widget.cursor_progress)
+        };
+        if moved {
             self.viewport_pos = Axis::Vertical
                 .pack(
                     progress * Axis::Vertical.major(content_size - portal_size),
                     Axis::Vertical.minor_pos(self.viewport_pos),
                 )
                 .into();
+            self.publish_viewport_pos();
             ctx.request_layout();
         }
     }
@@ -305,12 +725,45 @@ impl<W: Widget> Widget for Portal<W> {
             LifeCycle::WidgetAdded => {
                 ctx.register_as_portal();
             }
+            LifeCycle::AnimFrame(interval) if self.autoscroll_overshoot != Vec2::ZERO => {
+                if ctx.get_ref(&self.child).state().has_active {
+                    let overshoot = self.autoscroll_overshoot;
+                    let speed = (overshoot.hypot() * AUTOSCROLL_RATE).min(MAX_AUTOSCROLL_SPEED);
+                    let seconds = *interval as f64 * 1e-9;
+                    let delta = overshoot.normalize() * speed * seconds;
+                    let portal_size = ctx.size();
+                    let content_size = ctx.child_layout_rect(&self.child).size();
+                    if self.set_viewport_pos_raw(
+                        portal_size,
+                        content_size,
+                        self.viewport_pos + delta,
+                    ) {
+                        let (x, y) = self.scrollbar_progress(portal_size, content_size);
+                        ctx.get_mut(&mut self.scrollbar_horizontal)
+                            .widget
+                            .cursor_progress = x;
+                        ctx.get_mut(&mut self.scrollbar_vertical)
+                            .widget
+                            .cursor_progress = y;
+                        self.publish_viewport_pos();
+                        ctx.request_layout();
+                        // So that the dragged content follows what's now under the pointer
+                        ctx.request_pointer_move();
+                    }
+                    ctx.request_anim_frame();
+                } else {
+                    self.autoscroll_overshoot = Vec2::ZERO;
+                }
+            }
             //TODO
             //LifeCycle::RequestPanToChild(target_rect) => {}
             _ => {}
         }
 
         self.child.lifecycle(ctx, event);
+        if let Some(spinner) = &mut self.refresh_spinner {
+            spinner.lifecycle(ctx, event);
+        }
         self.scrollbar_horizontal.lifecycle(ctx, event);
         self.scrollbar_vertical.lifecycle(ctx, event);
     }
@@ -327,25 +780,126 @@ impl<W: Widget> Widget for Portal<W> {
 
         let child_bc = BoxConstraints::new(min_child_size, max_child_size);
 
-        let content_size = self.child.layout(ctx, &child_bc);
-        let portal_size = bc.constrain(content_size);
+        if let Some(key) = &self.scroll_key {
+            if std::mem::take(&mut self.restore_scroll) {
+                if let Some(pos) = ctx.global_state.scroll_positions.get(key) {
+                    self.viewport_pos = *pos;
+                }
+            }
+        }
 
         // TODO - document better
-        // Recompute the portal offset for the new layout
-        self.set_viewport_pos_raw(portal_size, content_size, self.viewport_pos);
-        // TODO - recompute portal progress
+        // Recompute the portal offset for the new layout, following our sync group
+        // if another portal in it was scrolled
+        let viewport = |pos: Point| Rect::from_origin_size(pos, bc.max());
+        let viewport_pos = match &self.scroll_sync {
+            Some((group, axes)) => axes.merge(self.viewport_pos, group.offset.get()),
+            None => self.viewport_pos,
+        };
+        let mut content_size =
+            ctx.layout_scrolled_child(&mut self.child, &child_bc, viewport(viewport_pos));
+        let portal_size = bc.constrain(content_size);
+        let requested_pos = match self.scroll_request.take() {
+            Some(ScrollRequest::Position(position)) => position,
+            Some(ScrollRequest::Widget(id)) => match rect_in(ctx.get_ref(&self.child).as_dyn(), id)
+            {
+                Some(target) => {
+                    pan_target(Rect::from_origin_size(viewport_pos, portal_size), target)
+                }
+                None => {
+                    warn!("Portal can't scroll to {id:?}, which isn't in its content");
+                    viewport_pos
+                }
+            },
+            None => viewport_pos,
+        };
+        self.set_viewport_pos_raw(portal_size, content_size, requested_pos);
+        if requested_pos != viewport_pos {
+            self.publish_viewport_pos();
+        }
+        if self.viewport_pos != viewport_pos {
+            // The content has shrunk, so sticky children have to follow the new position
+            content_size =
+                ctx.layout_scrolled_child(&mut self.child, &child_bc, viewport(self.viewport_pos));
+        }
+        let (x, y) = self.scrollbar_progress(portal_size, content_size);
+        ctx.get_mut(&mut self.scrollbar_horizontal)
+            .widget
+            .cursor_progress = x;
+        ctx.get_mut(&mut self.scrollbar_vertical)
+            .widget
+            .cursor_progress = y;
+        if let Some(key) = &self.scroll_key {
+            let positions = &mut ctx.global_state.scroll_positions;
+            if positions.get(key) != Some(&self.viewport_pos) {
+                positions.insert(key.clone(), self.viewport_pos);
+            }
+        }
+
+        if let Some(threshold) = self.end_threshold {
+            let remaining = content_size.height - portal_size.height - self.viewport_pos.y;
+            let reached_end = remaining <= threshold;
+            if reached_end && !self.reached_end {
+                ctx.submit_action(Action::ScrollReachedEnd);
+            }
+            self.reached_end = reached_end;
+        }
 
-        ctx.place_child(&mut self.child, Point::new(0.0, -self.viewport_pos.y));
+        // While the portal is pulled or refreshing, the content is pushed down
+        let indicator_height = if let Some(spinner) = &mut self.refresh_spinner {
+            let spinner_bc =
+                BoxConstraints::tight(Size::new(REFRESH_SPINNER_SIZE, REFRESH_SPINNER_SIZE));
+            spinner.layout(ctx, &spinner_bc);
+            ctx.place_child(
+                spinner,
+                Point::new(
+                    (portal_size.width - REFRESH_SPINNER_SIZE) / 2.0,
+                    (PULL_TO_REFRESH_DISTANCE - REFRESH_SPINNER_SIZE) / 2.0,
+                ),
+            );
+            PULL_TO_REFRESH_DISTANCE
+        } else {
+            self.pull_distance
+        };
+        ctx.place_child(
+            &mut self.child,
+            Point::new(-self.viewport_pos.x, indicator_height - self.viewport_pos.y),
+        );
 
         self.scrollbar_horizontal_visible =
             !self.constrain_horizontal && portal_size.width < content_size.width;
         self.scrollbar_vertical_visible =
             !self.constrain_vertical && portal_size.height < content_size.height;
 
+        // When both bars are shown, each one stops short of the other
+        let corner = |scrollbar: &WidgetPod<ScrollBar>, visible: bool| {
+            let style = &ctx.get_ref(scrollbar).deref().style;
+            if visible {
+                style.width + style.padding * 2.0
+            } else {
+                0.0
+            }
+        };
+        let corner_width = corner(&self.scrollbar_vertical, self.scrollbar_vertical_visible);
+        let corner_height = corner(
+            &self.scrollbar_horizontal,
+            self.scrollbar_horizontal_visible,
+        );
+
         if self.scrollbar_horizontal_visible {
-            self.scrollbar_horizontal.widget_mut().portal_size = portal_size.width;
-            self.scrollbar_horizontal.widget_mut().content_size = content_size.width;
-            let scrollbar_size = self.scrollbar_horizontal.layout(ctx, bc);
+            {
+                let scrollbar = ctx.get_mut(&mut self.scrollbar_horizontal);
+                scrollbar.widget.portal_size = portal_size.width;
+                scrollbar.widget.content_size = content_size.width;
+            }
+            let bar_bc = BoxConstraints::new(
+                Size::ZERO,
+                Size::new(
+                    (portal_size.width - corner_width).max(0.0),
+                    portal_size.height,
+                ),
+            );
+            let scrollbar_size = self.scrollbar_horizontal.layout(ctx, &bar_bc);
             ctx.place_child(
                 &mut self.scrollbar_horizontal,
                 Point::new(0.0, portal_size.height - scrollbar_size.height),
@@ -354,9 +908,19 @@ impl<W: Widget> Widget for Portal<W> {
             ctx.skip_child(&mut self.scrollbar_horizontal);
         }
         if self.scrollbar_vertical_visible {
-            self.scrollbar_vertical.widget_mut().portal_size = portal_size.height;
-            self.scrollbar_vertical.widget_mut().content_size = content_size.height;
-            let scrollbar_size = self.scrollbar_vertical.layout(ctx, bc);
+            {
+                let scrollbar = ctx.get_mut(&mut self.scrollbar_vertical);
+                scrollbar.widget.portal_size = portal_size.height;
+                scrollbar.widget.content_size = content_size.height;
+            }
+            let bar_bc = BoxConstraints::new(
+                Size::ZERO,
+                Size::new(
+                    portal_size.width,
+                    (portal_size.height - corner_height).max(0.0),
+                ),
+            );
+            let scrollbar_size = self.scrollbar_vertical.layout(ctx, &bar_bc);
             ctx.place_child(
                 &mut self.scrollbar_vertical,
                 Point::new(portal_size.width - scrollbar_size.width, 0.0),
@@ -371,11 +935,16 @@ impl<W: Widget> Widget for Portal<W> {
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
         // TODO - also clip the invalidated region
         let clip_rect = ctx.size().to_rect();
+        ctx.clip_children(clip_rect);
 
         scene.push_layer(BlendMode::default(), 1., Affine::IDENTITY, &clip_rect);
         self.child.paint(ctx, scene);
         scene.pop_layer();
 
+        if let Some(spinner) = &mut self.refresh_spinner {
+            spinner.paint(ctx, scene);
+        }
+
         if self.scrollbar_horizontal_visible {
             self.scrollbar_horizontal.paint(ctx, scene);
         } else {
@@ -388,6 +957,10 @@ impl<W: Widget> Widget for Portal<W> {
         }
     }
 
+    fn interactivity(&self) -> Interactivity {
+        Interactivity::Scrollable
+    }
+
     fn accessibility_role(&self) -> Role {
         Role::GenericContainer
     }
@@ -396,25 +969,34 @@ impl<W: Widget> Widget for Portal<W> {
         // TODO - Double check this code
         // Not sure about these values
         if false {
+            let x_max = ctx.get_ref(&self.scrollbar_horizontal).portal_size;
+            let y_max = ctx.get_ref(&self.scrollbar_vertical).portal_size;
             ctx.current_node().set_scroll_x(self.viewport_pos.x);
             ctx.current_node().set_scroll_y(self.viewport_pos.y);
             ctx.current_node().set_scroll_x_min(0.0);
-            ctx.current_node()
-                .set_scroll_x_max(self.scrollbar_horizontal.widget().portal_size);
+            ctx.current_node().set_scroll_x_max(x_max);
             ctx.current_node().set_scroll_y_min(0.0);
-            ctx.current_node()
-                .set_scroll_y_max(self.scrollbar_vertical.widget().portal_size);
+            ctx.current_node().set_scroll_y_max(y_max);
         }
 
         ctx.current_node().set_clips_children();
 
         self.child.accessibility(ctx);
+        if let Some(spinner) = &mut self.refresh_spinner {
+            spinner.accessibility(ctx);
+        }
         self.scrollbar_horizontal.accessibility(ctx);
         self.scrollbar_vertical.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        smallvec![self.child.as_dyn()]
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut children: SmallVec<[WidgetId; 16]> = smallvec![self.child.id()];
+        if let Some(spinner) = &self.refresh_spinner {
+            children.push(spinner.id());
+        }
+        children.push(self.scrollbar_horizontal.id());
+        children.push(self.scrollbar_vertical.id());
+        children
     }
 
     fn make_trace_span(&self) -> Span {
@@ -425,10 +1007,14 @@ impl<W: Widget> Widget for Portal<W> {
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
+    use winit::event::MouseButton;
+    use winit::keyboard::ModifiersState;
+
+    use std::time::Duration;
 
     use super::*;
     use crate::assert_render_snapshot;
-    use crate::testing::{widget_ids, TestHarness};
+    use crate::testing::{widget_ids, Record, Recording, TestHarness, TestWidgetExt as _};
     use crate::widget::{Button, Flex, SizedBox};
 
     fn button(text: &'static str) -> impl Widget {
@@ -502,6 +1088,245 @@ mod tests {
         assert_render_snapshot!(harness, "button_list_scroll_to_item_13");
     }
 
+    #[test]
+    fn offscreen_children_not_painted() {
+        let visible = Recording::default();
+        let offscreen = Recording::default();
+
+        let widget = Portal::new(
+            Flex::column()
+                .with_child(
+                    SizedBox::empty()
+                        .width(100.0)
+                        .height(100.0)
+                        .record(&visible),
+                )
+                .with_spacer(500.0)
+                .with_child(
+                    SizedBox::empty()
+                        .width(100.0)
+                        .height(100.0)
+                        .record(&offscreen),
+                ),
+        );
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+        harness.render();
+
+        let was_painted = |record: &Recording| {
+            record
+                .drain()
+                .iter()
+                .any(|event| matches!(event, Record::Paint))
+        };
+        assert!(was_painted(&visible));
+        assert!(!was_painted(&offscreen));
+
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            portal.set_viewport_pos(Point::new(0.0, 550.0))
+        });
+        harness.render();
+
+        assert!(was_painted(&offscreen));
+    }
+
+    #[test]
+    fn scroll_both_axes() {
+        let widget = Portal::new(SizedBox::empty().width(400.0).height(400.0));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+        harness.mouse_move(Point::new(100.0, 100.0));
+        harness.mouse_wheel(Vec2::new(30.0, 50.0));
+
+        let portal = harness.root_widget();
+        let portal = portal.downcast::<Portal<SizedBox>>().unwrap();
+        let content = portal.child().state().layout_rect();
+        assert_eq!(content.origin(), Point::new(-30.0, -50.0));
+
+        // Both bars are shown, and they don't overlap in the corner
+        let horizontal = (portal.get_child(&portal.scrollbar_horizontal).state()).layout_rect();
+        let vertical = (portal.get_child(&portal.scrollbar_vertical).state()).layout_rect();
+        assert!(horizontal.intersect(vertical).area() == 0.0);
+    }
+
+    #[test]
+    fn synced_portals_scroll_together() {
+        let [header_id, body_id] = widget_ids();
+        let group = ScrollSyncGroup::new();
+        let portal = |id| {
+            Portal::new(SizedBox::empty().width(400.0).height(400.0))
+                .with_scroll_sync(group.clone(), SyncAxes::Horizontal)
+                .with_id(id)
+        };
+        let widget = Flex::column()
+            .with_child(SizedBox::new(portal(header_id)).width(200.0).height(50.0))
+            .with_child(SizedBox::new(portal(body_id)).width(200.0).height(150.0));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+        harness.mouse_move_to(body_id);
+        harness.mouse_wheel(Vec2::new(30.0, 50.0));
+
+        let viewport_pos = |harness: &TestHarness, id| {
+            let portal = harness.get_widget(id);
+            portal
+                .downcast::<Portal<SizedBox>>()
+                .unwrap()
+                .get_viewport_pos()
+        };
+        assert_eq!(viewport_pos(&harness, body_id), Point::new(30.0, 50.0));
+        // The header only follows the body horizontally
+        assert_eq!(viewport_pos(&harness, header_id), Point::new(30.0, 0.0));
+        assert_eq!(group.offset(), Point::new(30.0, 0.0));
+    }
+
+    #[test]
+    fn synced_portals_repaint_together() {
+        let [header_id, body_id] = widget_ids();
+        let group = ScrollSyncGroup::new();
+        let portal = |id| {
+            Portal::new(SizedBox::empty().width(400.0).height(400.0))
+                .with_scroll_sync(group.clone(), SyncAxes::Horizontal)
+                .with_id(id)
+        };
+        let widget = Flex::column()
+            .with_child(SizedBox::new(portal(header_id)).width(200.0).height(50.0))
+            .with_child(SizedBox::new(portal(body_id)).width(200.0).height(150.0));
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+        let _ = harness.render();
+        harness.reset_widget_counters();
+        harness.mouse_move_to(body_id);
+        harness.mouse_wheel(Vec2::new(30.0, 0.0));
+        let _ = harness.render();
+
+        // The header's content moved, so its cached paint is out of date
+        let painted = harness.painted_widgets();
+        assert!(painted.contains(&header_id), "{painted:?}");
+        assert!(painted.contains(&body_id), "{painted:?}");
+    }
+
+    #[test]
+    fn reach_end_and_pull_to_refresh() {
+        let widget = Portal::new(SizedBox::empty().width(200.0).height(400.0))
+            .with_end_threshold(50.0)
+            .with_pull_to_refresh(true);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+        assert_eq!(harness.pop_action(), None);
+
+        // Pulling the content down past the top requests a refresh
+        harness.mouse_move(Point::new(100.0, 20.0));
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_move(Point::new(100.0, 20.0 + PULL_TO_REFRESH_DISTANCE + 10.0));
+        harness.mouse_button_release(MouseButton::Left);
+        assert_eq!(harness.pop_action().unwrap().0, Action::RefreshRequested);
+
+        let content_origin = |harness: &TestHarness| {
+            let portal = harness.root_widget();
+            let portal = portal.downcast::<Portal<SizedBox>>().unwrap();
+            portal.child().state().layout_rect().origin()
+        };
+        assert_eq!(
+            content_origin(&harness),
+            Point::new(0.0, PULL_TO_REFRESH_DISTANCE)
+        );
+
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<SizedBox>>();
+            portal.set_refreshing(false);
+        });
+        assert_eq!(content_origin(&harness), Point::ORIGIN);
+
+        // The end action is only submitted once per visit to the end
+        harness.mouse_wheel(Vec2::new(0.0, 160.0));
+        assert_eq!(harness.pop_action().unwrap().0, Action::ScrollReachedEnd);
+        harness.mouse_wheel(Vec2::new(0.0, 10.0));
+        assert_eq!(harness.pop_action(), None);
+    }
+
+    #[test]
+    fn dragging_past_the_edge_scrolls() {
+        let dragged = Recording::default();
+        let widget = Portal::new(
+            Flex::column()
+                .with_child(button("Item 1").record(&dragged))
+                .with_spacer(400.0),
+        );
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+        // Frame intervals are measured with the wall clock, so they're a bit longer
+        let scrolled = |harness: &TestHarness| {
+            let portal = harness.root_widget();
+            let pos = portal
+                .downcast::<Portal<Flex>>()
+                .unwrap()
+                .get_viewport_pos();
+            pos.y.round()
+        };
+
+        harness.mouse_move(Point::new(30.0, 20.0));
+        harness.mouse_button_press(MouseButton::Left);
+        // The first frame of an animation has no interval
+        harness.mouse_move(Point::new(30.0, 220.0));
+        harness.animate(Duration::ZERO);
+        dragged.clear();
+
+        // The portal scrolls in proportion to how far past its edge the pointer is,
+        // and the dragged widget is told that the pointer is now elsewhere on it
+        harness.animate(Duration::from_millis(100));
+        assert_eq!(scrolled(&harness), 20.0);
+        assert!(dragged
+            .drain()
+            .iter()
+            .any(|event| matches!(event, Record::PE(PointerEvent::PointerMove(_)))));
+        harness.mouse_move(Point::new(30.0, 240.0));
+        harness.animate(Duration::from_millis(100));
+        assert_eq!(scrolled(&harness), 60.0);
+
+        // Releasing the button stops the scrolling
+        harness.mouse_button_release(MouseButton::Left);
+        harness.animate(Duration::from_millis(100));
+        assert_eq!(scrolled(&harness), 60.0);
+    }
+
+    #[test]
+    fn shift_wheel_and_scroll_to_widget() {
+        let [target_id] = widget_ids();
+        let widget = Portal::new(
+            Flex::row().with_spacer(300.0).with_child(
+                Flex::column()
+                    .with_spacer(500.0)
+                    .with_child_id(SizedBox::empty().width(50.0).height(50.0), target_id),
+            ),
+        );
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200., 200.));
+        let viewport_pos = |harness: &TestHarness| {
+            let portal = harness.root_widget();
+            let portal = portal.downcast::<Portal<Flex>>().unwrap();
+            portal.get_viewport_pos()
+        };
+
+        // A vertical wheel scrolls horizontally while Shift is held
+        harness.mouse_move(Point::new(100.0, 100.0));
+        harness.set_modifiers(ModifiersState::SHIFT);
+        harness.mouse_wheel(Vec2::new(0.0, 40.0));
+        assert_eq!(viewport_pos(&harness), Point::new(40.0, 0.0));
+        harness.set_modifiers(ModifiersState::empty());
+
+        // The target is inside two containers, and the portal scrolls just enough
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            portal.scroll_to_widget(target_id);
+        });
+        assert_eq!(viewport_pos(&harness), Point::new(150.0, 350.0));
+
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            portal.scroll_to(Point::new(10.0, 1000.0));
+        });
+        assert_eq!(viewport_pos(&harness), Point::new(10.0, 350.0));
+    }
+
     // Helper function for panning tests
     fn make_range(repr: &str) -> Range<f64> {
         let repr = &repr[repr.find('_').unwrap()..];
@@ -612,4 +1437,41 @@ mod tests {
 
         assert_eq!(compute_pan_range(initial_range, target_range), result_range);
     }
+
+    #[test]
+    fn scroll_position_is_restored_by_key() {
+        let list = || {
+            let mut list = Flex::column();
+            for _ in 0..10 {
+                list = list.with_child(SizedBox::empty().height(50.0));
+            }
+            SizedBox::new(Portal::new(list).with_scroll_key("list")).height(100.0)
+        };
+        let portal_pos = |harness: &TestHarness| {
+            let root = harness.root_widget();
+            let portal = root.children()[0].children()[0];
+            portal
+                .downcast::<Portal<Flex>>()
+                .unwrap()
+                .get_viewport_pos()
+        };
+        let mut harness = TestHarness::create(Flex::column().with_child(list()));
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<Flex>();
+            let mut sized_box = root.child_mut(0).unwrap();
+            let mut sized_box = sized_box.downcast::<SizedBox>();
+            let mut portal = sized_box.child_mut().unwrap();
+            let mut portal = portal.downcast::<Portal<Flex>>();
+            portal.set_viewport_pos(Point::new(0.0, 120.0));
+        });
+        assert_eq!(harness.scroll_positions()["list"], Point::new(0.0, 120.0));
+
+        // The user navigates away and back
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<Flex>();
+            root.remove_child(0);
+            root.add_child(list());
+        });
+        assert_eq!(portal_pos(&harness), Point::new(0.0, 120.0));
+    }
 }
@@ -14,11 +14,11 @@ use vello::{peniko::BlendMode, Scene};
 use crate::{
     text2::{TextBrush, TextStorage, TextWithSelection},
     widget::label::LABEL_X_PADDING,
-    AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
-    PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+    AccessCtx, AccessEvent, Action, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
 };
 
-use super::{LineBreaking, WidgetMut, WidgetRef};
+use super::{LineBreaking, WidgetMut};
 
 /// The prose widget is a widget which displays text which can be
 /// selected with keyboard and mouse, and which can be copied from,
@@ -92,6 +92,7 @@ impl WidgetMut<'_, Prose> {
         let ret = f(&mut self.widget.text_layout);
         if self.widget.text_layout.needs_rebuild() {
             self.ctx.request_layout();
+            self.ctx.request_accessibility_update();
         }
         ret
     }
@@ -133,6 +134,28 @@ impl WidgetMut<'_, Prose> {
         self.widget.line_break_mode = line_break_mode;
         self.ctx.request_paint();
     }
+
+    /// Highlight the matches of `query`, ignoring case, and select the first one.
+    /// Returns the number of matches.
+    ///
+    /// The user can then move between the matches with the keymap's
+    /// [`FindNext`](crate::EditAction::FindNext) and
+    /// [`FindPrevious`](crate::EditAction::FindPrevious) shortcuts while the text
+    /// is focused, which submits [`Action::FindMatchChanged`](crate::Action::FindMatchChanged).
+    pub fn find(&mut self, query: &str) -> usize {
+        self.set_text_properties(|layout| layout.find(query))
+    }
+
+    /// Select the next match of the find query, or the previous one if `forward`
+    /// is false. Returns the index of the selected match.
+    pub fn find_next(&mut self, forward: bool) -> Option<usize> {
+        self.set_text_properties(|layout| layout.find_next(forward))
+    }
+
+    /// Stop highlighting the matches of the find query.
+    pub fn clear_find(&mut self) {
+        self.set_text_properties(|layout| layout.clear_find());
+    }
 }
 
 impl Widget for Prose {
@@ -143,10 +166,16 @@ impl Widget for Prose {
             PointerEvent::PointerDown(button, state) => {
                 if !ctx.is_disabled() {
                     // TODO: Start tracking currently pressed link?
-                    let made_change = self.text_layout.pointer_down(inner_origin, state, *button);
+                    let made_change = self.text_layout.pointer_down(
+                        inner_origin,
+                        state,
+                        *button,
+                        ctx.conventions(),
+                    );
                     if made_change {
                         ctx.request_layout();
                         ctx.request_paint();
+                        ctx.request_accessibility_update();
                         ctx.request_focus();
                         ctx.set_active(true);
                     }
@@ -179,17 +208,39 @@ impl Widget for Prose {
 
     fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
         // If focused on a link and enter pressed, follow it?
-        let result = self.text_layout.text_event(event);
+        let previous_match = self.text_layout.current_match();
+        let result = self.text_layout.text_event(ctx.keymap(), event);
         if result.is_handled() {
             ctx.set_handled();
+            if let Some(current) = self.text_layout.current_match() {
+                if previous_match != Some(current) {
+                    let count = self.text_layout.find_matches().len();
+                    ctx.submit_action(Action::FindMatchChanged(current, count));
+                }
+            }
             // TODO: only some handlers need this repaint
             ctx.request_layout();
             ctx.request_paint();
+            ctx.request_accessibility_update();
         }
     }
 
-    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {
-        // TODO - Handle accesskit::Action::SetTextSelection
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target != ctx.widget_id() {
+            return;
+        }
+        if let (
+            accesskit::Action::SetTextSelection,
+            Some(accesskit::ActionData::SetTextSelection(selection)),
+        ) = (event.action, &event.data)
+        {
+            if self.text_layout.set_access_selection(selection) {
+                ctx.set_handled();
+                ctx.request_layout();
+                ctx.request_paint();
+                ctx.request_accessibility_update();
+            }
+        }
     }
 
     #[allow(missing_docs)]
@@ -284,11 +335,15 @@ impl Widget for Prose {
     }
 
     fn accessibility(&mut self, ctx: &mut AccessCtx) {
-        ctx.current_node()
-            .set_name(self.text().as_str().to_string());
+        let node = ctx.current_node();
+        node.set_name(self.text().as_str().to_string());
+        node.set_read_only();
+        node.add_action(accesskit::Action::SetTextSelection);
+        self.text_layout
+            .accessibility(ctx, Point::new(LABEL_X_PADDING, 0.0));
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         SmallVec::new()
     }
 
@@ -296,3 +351,36 @@ impl Widget for Prose {
         Some(self.text_layout.text().as_str().chars().take(100).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn find_selects_matches_in_turn() {
+        let mut harness = TestHarness::create(Prose::new("One two one, ONE"));
+        let count = harness.edit_root_widget(|mut root| root.downcast::<Prose>().find("one"));
+        assert_eq!(count, 3);
+
+        let current = |harness: &TestHarness| {
+            let prose = harness.root_widget();
+            let layout = &prose.downcast::<Prose>().unwrap().text_layout;
+            (layout.current_match(), layout.selection.map(|s| s.range()))
+        };
+        assert_eq!(current(&harness), (Some(0), Some(0..3)));
+        harness.edit_root_widget(|mut root| root.downcast::<Prose>().find_next(false));
+        assert_eq!(current(&harness), (Some(2), Some(13..16)));
+        harness.edit_root_widget(|mut root| root.downcast::<Prose>().find_next(true));
+        assert_eq!(current(&harness).0, Some(0));
+
+        harness.edit_root_widget(|mut root| root.downcast::<Prose>().clear_find());
+        let prose = harness.root_widget();
+        assert!(prose
+            .downcast::<Prose>()
+            .unwrap()
+            .text_layout
+            .find_matches()
+            .is_empty());
+    }
+}
@@ -6,10 +6,10 @@ use kurbo::Point;
 use smallvec::SmallVec;
 use vello::Scene;
 
-use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::widget::{WidgetMut, WidgetPod};
 use crate::{
     AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, Size, StatusChange, TextEvent, Widget,
+    PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
 };
 
 // TODO: This is a hack to provide an accessibility node with a Window type.
@@ -72,9 +72,9 @@ impl<W: Widget> Widget for RootWidget<W> {
         self.pod.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         let mut vec = SmallVec::new();
-        vec.push(self.pod.as_dyn());
+        vec.push(self.pod.id());
         vec
     }
 }
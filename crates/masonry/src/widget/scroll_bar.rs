@@ -11,10 +11,11 @@ use vello::Scene;
 use super::Axis;
 use crate::kurbo::Rect;
 use crate::paint_scene_helpers::{fill_color, stroke};
-use crate::widget::{WidgetMut, WidgetRef};
+use crate::widget::WidgetMut;
+use crate::Color;
 use crate::{
     theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
-    PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget,
+    PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
 };
 
 // RULES
@@ -26,7 +27,69 @@ use crate::{
 // - _z
 // - _length
 
-// TODO - Fade scrollbars? Find out how Linux/MacOS/Windows do it
+/// The size, shape and colors of a [`ScrollBar`].
+///
+/// The default style uses the `SCROLLBAR_*` values of the [`theme`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollBarStyle {
+    /// The thickness of the bar.
+    pub width: f64,
+    /// The space on each side of the bar, across its axis.
+    pub padding: f64,
+    /// The shortest the bar gets, however large the content is.
+    pub min_length: f64,
+    /// The radius of the bar's corners.
+    pub radius: f64,
+    /// The width of the bar's border.
+    pub edge_width: f64,
+    pub color: Color,
+    /// The color of the bar while the pointer is over it.
+    pub hover_color: Color,
+    /// The color of the bar while it's dragged.
+    pub pressed_color: Color,
+    pub border_color: Color,
+}
+
+impl Default for ScrollBarStyle {
+    fn default() -> Self {
+        ScrollBarStyle {
+            width: theme::SCROLLBAR_WIDTH,
+            padding: theme::SCROLLBAR_PAD,
+            min_length: theme::SCROLLBAR_MIN_SIZE,
+            radius: theme::SCROLLBAR_RADIUS,
+            edge_width: theme::SCROLLBAR_EDGE_WIDTH,
+            color: theme::SCROLLBAR_COLOR,
+            hover_color: theme::SCROLLBAR_HOVER_COLOR,
+            pressed_color: theme::SCROLLBAR_PRESSED_COLOR,
+            border_color: theme::SCROLLBAR_BORDER_COLOR,
+        }
+    }
+}
+
+/// When a [`ScrollBar`] is shown.
+///
+/// In both modes, the bar is painted over the edge of the scrolled content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollBarVisibility {
+    /// The bar is always shown. This is the default, except on macOS.
+    Always,
+    /// The bar fades in when the content is scrolled or the pointer is over it, and
+    /// fades out again after [`SCROLLBAR_FADE_DELAY`](theme::SCROLLBAR_FADE_DELAY)
+    /// milliseconds without either. This is the default on macOS.
+    Overlay,
+}
+
+impl Default for ScrollBarVisibility {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            ScrollBarVisibility::Overlay
+        } else {
+            ScrollBarVisibility::Always
+        }
+    }
+}
+
 // TODO - Rename cursor to oval/rect/bar/grabber/grabbybar
 // TODO - Rename progress to ???
 #[allow(dead_code)]
@@ -38,6 +101,12 @@ pub struct ScrollBar {
     pub(crate) content_size: f64,
     hovered: bool,
     grab_anchor: Option<f64>,
+    pub(crate) style: ScrollBarStyle,
+    pub(crate) visibility: ScrollBarVisibility,
+    /// How visible an overlay bar is, from 0.0 (hidden) to 1.0
+    opacity: f64,
+    /// Nanoseconds since an overlay bar was last scrolled or hovered
+    idle_time: u64,
 }
 
 impl ScrollBar {
@@ -50,6 +119,38 @@ impl ScrollBar {
             content_size,
             hovered: false,
             grab_anchor: None,
+            style: ScrollBarStyle::default(),
+            visibility: ScrollBarVisibility::default(),
+            opacity: 0.0,
+            idle_time: 0,
+        }
+    }
+
+    /// Builder-style method to set the size, shape and colors of the bar.
+    pub fn with_style(mut self, style: ScrollBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Builder-style method to set when the bar is shown.
+    pub fn with_visibility(mut self, visibility: ScrollBarVisibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    pub fn style(&self) -> &ScrollBarStyle {
+        &self.style
+    }
+
+    pub fn visibility(&self) -> ScrollBarVisibility {
+        self.visibility
+    }
+
+    /// How much of the bar's color is painted, from 0.0 to 1.0.
+    pub fn opacity(&self) -> f64 {
+        match self.visibility {
+            ScrollBarVisibility::Always => 1.0,
+            ScrollBarVisibility::Overlay => self.opacity * theme::SCROLLBAR_MAX_OPACITY,
         }
     }
 
@@ -62,6 +163,18 @@ impl ScrollBar {
 }
 
 impl ScrollBar {
+    /// Show an overlay bar and restart its fade out delay.
+    ///
+    /// Returns `true` if the bar needs animation frames to fade out.
+    fn reveal(&mut self) -> bool {
+        if self.visibility != ScrollBarVisibility::Overlay {
+            return false;
+        }
+        self.opacity = 1.0;
+        self.idle_time = 0;
+        true
+    }
+
     fn get_cursor_rect(&self, layout_size: Size, min_length: f64) -> Rect {
         // TODO - handle invalid sizes
         let size_ratio = self.portal_size / self.content_size;
@@ -118,10 +231,28 @@ impl WidgetMut<'_, ScrollBar> {
         self.ctx.request_paint();
     }
 
+    /// Set how far the bar is from its initial point, and show it if it is an
+    /// overlay bar.
     pub fn set_cursor_progress(&mut self, cursor_progress: f64) {
+        if self.widget.cursor_progress != cursor_progress && self.widget.reveal() {
+            self.ctx.request_anim_frame();
+        }
         self.widget.cursor_progress = cursor_progress;
         self.ctx.request_paint();
     }
+
+    /// Set the size, shape and colors of the bar.
+    pub fn set_style(&mut self, style: ScrollBarStyle) {
+        self.widget.style = style;
+        self.ctx.request_layout();
+    }
+
+    /// Set when the bar is shown.
+    pub fn set_visibility(&mut self, visibility: ScrollBarVisibility) {
+        self.widget.visibility = visibility;
+        self.widget.opacity = 0.0;
+        self.ctx.request_paint();
+    }
 }
 
 // --- TRAIT IMPLS ---
@@ -132,7 +263,7 @@ impl Widget for ScrollBar {
             PointerEvent::PointerDown(_, state) => {
                 ctx.set_active(true);
 
-                let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
+                let cursor_min_length = self.style.min_length;
                 let cursor_rect = self.get_cursor_rect(ctx.size(), cursor_min_length);
 
                 let mouse_pos = Point::new(state.position.x, state.position.y);
@@ -146,12 +277,13 @@ impl Widget for ScrollBar {
                     self.moved = true;
                     self.grab_anchor = Some(0.5);
                 };
+                self.reveal();
                 ctx.request_paint();
             }
             PointerEvent::PointerMove(state) => {
                 let mouse_pos = Point::new(state.position.x, state.position.y);
                 if let Some(grab_anchor) = self.grab_anchor {
-                    let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
+                    let cursor_min_length = self.style.min_length;
                     self.cursor_progress = self.progress_from_mouse_pos(
                         ctx.size(),
                         cursor_min_length,
@@ -160,13 +292,29 @@ impl Widget for ScrollBar {
                     );
                     self.moved = true;
                 }
+                // The bar stays shown while the pointer is over it, and starts fading
+                // out once the pointer leaves
+                let was_hovered = self.hovered;
+                self.hovered = ctx.is_hot();
+                if (self.hovered || was_hovered) && self.reveal() {
+                    ctx.request_anim_frame();
+                }
                 ctx.request_paint();
             }
             PointerEvent::PointerUp(_, _) => {
                 self.grab_anchor = None;
                 ctx.set_active(false);
+                if self.reveal() {
+                    ctx.request_anim_frame();
+                }
                 ctx.request_paint();
             }
+            PointerEvent::PointerLeave(_) => {
+                if self.hovered && self.reveal() {
+                    ctx.request_anim_frame();
+                }
+                self.hovered = false;
+            }
             _ => {}
         }
     }
@@ -179,13 +327,36 @@ impl Widget for ScrollBar {
 
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
 
-    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle) {}
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        let LifeCycle::AnimFrame(interval) = event else {
+            return;
+        };
+        if self.visibility != ScrollBarVisibility::Overlay || self.opacity == 0.0 {
+            return;
+        }
+        // We stop animating while the bar is in use, and start again once it isn't
+        if self.hovered || self.grab_anchor.is_some() {
+            return;
+        }
+
+        self.idle_time += interval;
+        let fade_delay = theme::SCROLLBAR_FADE_DELAY * 1_000_000;
+        let fade_duration = theme::SCROLLBAR_FADE_DURATION * 1_000_000;
+        if self.idle_time > fade_delay {
+            let faded = (self.idle_time - fade_delay) as f64 / fade_duration as f64;
+            self.opacity = (1.0 - faded).max(0.0);
+            ctx.request_paint();
+        }
+        if self.opacity > 0.0 {
+            ctx.request_anim_frame();
+        }
+    }
 
     fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         // TODO - handle resize
 
-        let scrollbar_width = theme::SCROLLBAR_WIDTH;
-        let cursor_padding = theme::SCROLLBAR_PAD;
+        let scrollbar_width = self.style.width;
+        let cursor_padding = self.style.padding;
         self.axis
             .pack(
                 self.axis.major(bc.max()),
@@ -195,10 +366,15 @@ impl Widget for ScrollBar {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
-        let radius = theme::SCROLLBAR_RADIUS;
-        let edge_width = theme::SCROLLBAR_EDGE_WIDTH;
-        let cursor_padding = theme::SCROLLBAR_PAD;
-        let cursor_min_length = theme::SCROLLBAR_MIN_SIZE;
+        let opacity = self.opacity();
+        if opacity == 0.0 {
+            return;
+        }
+        let style = &self.style;
+        let radius = style.radius;
+        let edge_width = style.edge_width;
+        let cursor_padding = style.padding;
+        let cursor_min_length = style.min_length;
 
         let (inset_x, inset_y) = self.axis.pack(0.0, cursor_padding);
         let cursor_rect = self
@@ -206,11 +382,19 @@ impl Widget for ScrollBar {
             .inset((-inset_x, -inset_y))
             .to_rounded_rect(radius);
 
-        fill_color(scene, &cursor_rect, theme::SCROLLBAR_COLOR);
+        let opacity = opacity as f32;
+        let color = if self.grab_anchor.is_some() {
+            style.pressed_color
+        } else if self.hovered {
+            style.hover_color
+        } else {
+            style.color
+        };
+        fill_color(scene, &cursor_rect, color.with_alpha_factor(opacity));
         stroke(
             scene,
             &cursor_rect,
-            theme::SCROLLBAR_BORDER_COLOR,
+            style.border_color.with_alpha_factor(opacity),
             edge_width,
         );
     }
@@ -224,7 +408,7 @@ impl Widget for ScrollBar {
         // Use set_scroll_x/y_min/max?
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         SmallVec::new()
     }
 
@@ -235,6 +419,8 @@ impl Widget for ScrollBar {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use insta::assert_debug_snapshot;
     use winit::event::MouseButton;
 
@@ -288,6 +474,38 @@ mod tests {
         assert_render_snapshot!(harness, "scrollbar_horizontal_middle");
     }
 
+    #[test]
+    fn overlay_scrollbar_fades_out() {
+        let widget = ScrollBar::new(Axis::Vertical, 200.0, 600.0)
+            .with_visibility(ScrollBarVisibility::Overlay);
+
+        let mut harness = TestHarness::create_with_size(widget, Size::new(50.0, 200.0));
+        let opacity = |harness: &TestHarness| {
+            let scrollbar = harness.root_widget();
+            scrollbar.downcast::<ScrollBar>().unwrap().opacity()
+        };
+        assert_eq!(opacity(&harness), 0.0);
+
+        harness.edit_root_widget(|mut scrollbar| {
+            let mut scrollbar = scrollbar.downcast::<ScrollBar>();
+            scrollbar.set_cursor_progress(0.5);
+        });
+        assert_eq!(opacity(&harness), theme::SCROLLBAR_MAX_OPACITY);
+
+        let fade_delay = Duration::from_millis(theme::SCROLLBAR_FADE_DELAY);
+        let fade_duration = Duration::from_millis(theme::SCROLLBAR_FADE_DURATION);
+        harness.animate(Duration::ZERO);
+        harness.animate(fade_delay / 2);
+        assert_eq!(opacity(&harness), theme::SCROLLBAR_MAX_OPACITY);
+
+        harness.animate(fade_delay / 2 + fade_duration / 2);
+        let halfway = opacity(&harness);
+        assert!(halfway > 0.0 && halfway < theme::SCROLLBAR_MAX_OPACITY);
+
+        harness.animate(fade_duration);
+        assert_eq!(opacity(&harness), 0.0);
+    }
+
     // TODO - portal larger than content
 
     #[cfg(FALSE)]
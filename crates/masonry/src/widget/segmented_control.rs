@@ -0,0 +1,496 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A row of mutually exclusive options, drawn as joined buttons.
+
+use std::time::Duration;
+
+use accesskit::{DefaultActionVerb, Role, Toggled};
+use kurbo::{Line, Point, Rect, Size};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::{Label, WidgetMut, WidgetPod};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// The space on each side of the text of a segment.
+const SEGMENT_PADDING: f64 = 12.0;
+/// How long the selection indicator takes to slide to another segment.
+const SLIDE_DURATION: Duration = Duration::from_millis(150);
+
+/// A row of mutually exclusive options, drawn as joined buttons, like a group of
+/// radio buttons.
+///
+/// Clicking a segment selects it, and the selection indicator slides to it. While
+/// the control has focus, the arrow keys select the previous or next segment, and
+/// <kbd>Home</kbd> and <kbd>End</kbd> the first or last. Each segment is as wide as
+/// its text.
+///
+/// Emits [`Action::SegmentSelected`] when the user selects another segment.
+pub struct SegmentedControl {
+    segments: Vec<WidgetPod<Segment>>,
+    selected: usize,
+    /// The segment which the pointer was pressed on
+    pressed: Option<usize>,
+    /// The left edge and width of each segment, from the last layout
+    spans: Vec<(f64, f64)>,
+    /// The left edge and width of the indicator when it started sliding
+    slide_from: (f64, f64),
+    /// How far the indicator has slid to the selected segment, from 0 to 1
+    slide_progress: f64,
+}
+
+/// One option of a [`SegmentedControl`].
+struct Segment {
+    label: WidgetPod<Label>,
+    selected: bool,
+}
+
+impl SegmentedControl {
+    /// Create a new segmented control with a segment for each text, the first of
+    /// which is selected.
+    pub fn new(segments: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        SegmentedControl {
+            segments: segments.into_iter().map(Segment::pod).collect(),
+            selected: 0,
+            pressed: None,
+            spans: Vec::new(),
+            slide_from: (0.0, 0.0),
+            slide_progress: 1.0,
+        }
+        .with_selected(0)
+    }
+
+    /// Builder-style method to set the selected segment.
+    pub fn with_selected(mut self, selected: usize) -> Self {
+        self.selected = selected;
+        for (ix, segment) in self.segments.iter_mut().enumerate() {
+            segment.created_widget_mut().selected = ix == selected;
+        }
+        self
+    }
+
+    /// The index of the selected segment.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The left edge and width of the selection indicator, which is between
+    /// segments while it slides.
+    fn indicator_span(&self) -> (f64, f64) {
+        let Some(&(x, width)) = self.spans.get(self.selected) else {
+            return self.slide_from;
+        };
+        let eased = 1.0 - (1.0 - self.slide_progress).powi(3);
+        let (from_x, from_width) = self.slide_from;
+        (
+            from_x + (x - from_x) * eased,
+            from_width + (width - from_width) * eased,
+        )
+    }
+
+    /// Select segment `ix`, and start sliding the indicator to it.
+    ///
+    /// Returns the previously selected segment if the selection changed. The
+    /// caller must then update both segments with [`set_segment_selected`].
+    fn set_selected_raw(&mut self, ix: usize) -> Option<usize> {
+        if ix == self.selected || ix >= self.segments.len() {
+            return None;
+        }
+        self.slide_from = self.indicator_span();
+        self.slide_progress = 0.0;
+        Some(std::mem::replace(&mut self.selected, ix))
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, ix: usize) {
+        if let Some(previous) = self.set_selected_raw(ix) {
+            for (segment, selected) in [(previous, false), (ix, true)] {
+                if let Some(segment) = self.segments.get_mut(segment) {
+                    set_segment_selected(ctx.get_mut(segment), selected);
+                }
+            }
+            trace!("SegmentedControl {:?} selected {}", ctx.widget_id(), ix);
+            ctx.submit_action(Action::SegmentSelected(ix));
+            ctx.request_anim_frame();
+            ctx.request_paint();
+            ctx.request_accessibility_update();
+        }
+    }
+
+    fn segment_for_key(&self, key: &Key) -> Option<usize> {
+        let count = self.segments.len();
+        if count == 0 {
+            return None;
+        }
+        // Like radio buttons, the arrow keys wrap around
+        match key {
+            Key::Named(NamedKey::ArrowLeft | NamedKey::ArrowUp) => {
+                Some((self.selected + count - 1) % count)
+            }
+            Key::Named(NamedKey::ArrowRight | NamedKey::ArrowDown) => {
+                Some((self.selected + 1) % count)
+            }
+            Key::Named(NamedKey::Home) => Some(0),
+            Key::Named(NamedKey::End) => Some(count - 1),
+            _ => None,
+        }
+    }
+
+    fn hot_segment(&self, ctx: &EventCtx) -> Option<usize> {
+        (self.segments.iter()).position(|segment| ctx.get_ref(segment).state().is_hot)
+    }
+}
+
+/// Mark whether `segment` is selected, and update its accessibility node.
+fn set_segment_selected(mut segment: WidgetMut<'_, Segment>, selected: bool) {
+    segment.widget.selected = selected;
+    segment.ctx.request_accessibility_update();
+}
+
+impl Segment {
+    fn pod(text: impl Into<ArcStr>) -> WidgetPod<Segment> {
+        WidgetPod::new(Segment {
+            label: WidgetPod::new(Label::new(text)),
+            selected: false,
+        })
+    }
+}
+
+impl WidgetMut<'_, SegmentedControl> {
+    /// Select segment `ix`, without submitting an action.
+    pub fn set_selected(&mut self, ix: usize) {
+        if let Some(previous) = self.widget.set_selected_raw(ix) {
+            for (segment, selected) in [(previous, false), (ix, true)] {
+                if let Some(segment) = self.widget.segments.get_mut(segment) {
+                    set_segment_selected(self.ctx.get_mut(segment), selected);
+                }
+            }
+            self.ctx.request_anim_frame();
+            self.ctx.request_paint();
+            self.ctx.request_accessibility_update();
+        }
+    }
+
+    /// Set the text of segment `ix`.
+    pub fn set_segment_text(&mut self, ix: usize, text: impl Into<ArcStr>) {
+        let mut segment = self.ctx.get_mut(&mut self.widget.segments[ix]);
+        segment
+            .ctx
+            .get_mut(&mut segment.widget.label)
+            .set_text(text.into());
+    }
+
+    /// Add a segment after the others.
+    pub fn add_segment(&mut self, text: impl Into<ArcStr>) {
+        self.widget.segments.push(Segment::pod(text));
+        self.ctx.children_changed();
+    }
+
+    /// Remove segment `ix`, selecting the one before it if it was selected.
+    pub fn remove_segment(&mut self, ix: usize) {
+        let segment = self.widget.segments.remove(ix);
+        self.ctx.remove_child(segment);
+        self.widget.pressed = None;
+        if self.widget.selected >= ix && self.widget.selected > 0 {
+            self.widget.selected -= 1;
+        }
+        if let Some(segment) = self.widget.segments.get_mut(self.widget.selected) {
+            set_segment_selected(self.ctx.get_mut(segment), true);
+        }
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+}
+
+impl Widget for SegmentedControl {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for segment in &mut self.segments {
+            segment.on_pointer_event(ctx, event);
+        }
+        match event {
+            PointerEvent::PointerDown(MouseButton::Left, _) if !ctx.is_disabled() => {
+                self.pressed = self.hot_segment(ctx);
+                if self.pressed.is_some() {
+                    ctx.set_active(true);
+                    ctx.request_focus();
+                }
+                ctx.request_paint();
+            }
+            PointerEvent::PointerUp(_, _) => {
+                if let Some(ix) = self.pressed.take() {
+                    if self.hot_segment(ctx) == Some(ix) && !ctx.is_disabled() {
+                        self.select(ctx, ix);
+                    }
+                }
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            PointerEvent::PointerMove(_) | PointerEvent::PointerLeave(_) => {
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key, _) = event else {
+            return;
+        };
+        if !key.state.is_pressed() || !ctx.is_focused() || ctx.is_disabled() {
+            return;
+        }
+        if let Some(ix) = self.segment_for_key(&key.logical_key) {
+            self.select(ctx, ix);
+            ctx.set_handled();
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        let target = self
+            .segments
+            .iter()
+            .position(|segment| segment.id() == event.target);
+        if let Some(ix) = target {
+            if event.action == accesskit::Action::Default && !ctx.is_disabled() {
+                self.select(ctx, ix);
+            }
+        }
+        for segment in &mut self.segments {
+            segment.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::BuildFocusChain if !self.segments.is_empty() => {
+                ctx.register_for_focus();
+            }
+            LifeCycle::AnimFrame(interval) if self.slide_progress < 1.0 => {
+                let step = *interval as f64 / SLIDE_DURATION.as_nanos() as f64;
+                self.slide_progress = (self.slide_progress + step).min(1.0);
+                if self.slide_progress < 1.0 {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        for segment in &mut self.segments {
+            segment.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let height = theme::BORDERED_WIDGET_HEIGHT.max(bc.min().height);
+        let max_width = bc.max().width / self.segments.len().max(1) as f64;
+        let segment_bc = BoxConstraints::new(
+            Size::new(0.0, height),
+            Size::new(max_width, bc.max().height.max(height)),
+        );
+        self.spans.clear();
+        let mut x = 0.0;
+        let mut max_height = height;
+        for segment in &mut self.segments {
+            let size = segment.layout(ctx, &segment_bc);
+            ctx.place_child(segment, Point::new(x, 0.0));
+            self.spans.push((x, size.width));
+            x += size.width;
+            max_height = max_height.max(size.height);
+        }
+        if self.slide_progress >= 1.0 {
+            self.slide_from = self.indicator_span();
+        }
+        bc.constrain(Size::new(x, max_height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let radius = theme::BUTTON_BORDER_RADIUS;
+        let background = size.to_rect().inset(-0.5).to_rounded_rect(radius);
+        fill_color(scene, &background, theme::BACKGROUND_DARK);
+
+        let segment_rect = |(x, width): (f64, f64)| Rect::new(x, 0.0, x + width, size.height);
+        if !ctx.is_disabled() {
+            let hot_segment =
+                (self.segments.iter()).position(|segment| ctx.get_ref(segment).state().is_hot);
+            if let Some(&span) = hot_segment.and_then(|ix| self.spans.get(ix)) {
+                let hot = segment_rect(span).inset(-2.0).to_rounded_rect(radius - 1.0);
+                fill_color(scene, &hot, theme::BACKGROUND_LIGHT);
+            }
+        }
+        for &(x, _) in self.spans.iter().skip(1) {
+            let separator = Line::new((x, 4.0), (x, size.height - 4.0));
+            stroke(scene, &separator, theme::BORDER_DARK, 1.0);
+        }
+        if !self.spans.is_empty() {
+            let indicator = segment_rect(self.indicator_span())
+                .inset(-2.0)
+                .to_rounded_rect(radius - 1.0);
+            let color = if ctx.is_disabled() {
+                theme::DISABLED_BUTTON_LIGHT
+            } else {
+                theme::PRIMARY_DARK
+            };
+            fill_color(scene, &indicator, color);
+        }
+        stroke(scene, &background, theme::BORDER_DARK, 1.0);
+
+        for segment in &mut self.segments {
+            segment.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::RadioGroup
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for segment in &mut self.segments {
+            segment.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.segments.iter().map(|segment| segment.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("SegmentedControl")
+    }
+}
+
+impl Widget for Segment {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let label_bc = bc.shrink((SEGMENT_PADDING * 2.0, 0.0)).loosen();
+        let label_size = self.label.layout(ctx, &label_bc);
+        let size = bc.constrain(Size::new(
+            label_size.width + SEGMENT_PADDING * 2.0,
+            label_size.height,
+        ));
+        let origin = Point::new(
+            (size.width - label_size.width) / 2.0,
+            (size.height - label_size.height) / 2.0,
+        );
+        ctx.place_child(&mut self.label, origin);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::RadioButton
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let toggled = if self.selected {
+            Toggled::True
+        } else {
+            Toggled::False
+        };
+        ctx.current_node().set_toggled(toggled);
+        ctx.current_node()
+            .set_default_action_verb(DefaultActionVerb::Click);
+        self.label.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.label.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Segment")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::Flex;
+
+    #[test]
+    fn select_with_pointer_and_access() {
+        let [control_id] = widget_ids();
+        let control = SegmentedControl::new(["Day", "Week", "Month"]).with_id(control_id);
+        let mut harness = TestHarness::create(Flex::column().with_child(control));
+        let segment_ids: Vec<_> = harness
+            .get_widget(control_id)
+            .children()
+            .iter()
+            .map(|segment| segment.id())
+            .collect();
+        let selected = |harness: &TestHarness| {
+            let control = harness.get_widget(control_id);
+            control.downcast::<SegmentedControl>().unwrap().selected()
+        };
+
+        harness.mouse_click_on(segment_ids[2]);
+        assert_eq!(selected(&harness), 2);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::SegmentSelected(2), control_id))
+        );
+        // The indicator slides to the segment
+        let indicator = |harness: &TestHarness| {
+            let control = harness.get_widget(control_id);
+            control
+                .downcast::<SegmentedControl>()
+                .unwrap()
+                .indicator_span()
+        };
+        let target = harness.get_widget(segment_ids[2]).state().layout_rect();
+        assert_ne!(indicator(&harness).0, target.x0);
+        harness.animate(Duration::ZERO);
+        harness.animate(SLIDE_DURATION);
+        assert_eq!(indicator(&harness), (target.x0, target.width()));
+
+        // Assistive technologies select segments as radio buttons
+        assert_eq!(harness.focused_widget().unwrap().id(), control_id);
+        harness.process_access_event(accesskit::ActionRequest {
+            action: accesskit::Action::Default,
+            target: segment_ids[0].into(),
+            data: None,
+        });
+        assert_eq!(selected(&harness), 0);
+        let tree = harness.access_tree();
+        let (_, node) = tree
+            .nodes
+            .iter()
+            .find(|(id, _)| *id == segment_ids[0].into())
+            .unwrap();
+        assert_eq!(node.role(), Role::RadioButton);
+        assert_eq!(node.toggled(), Some(Toggled::True));
+    }
+}
@@ -10,16 +10,20 @@ use tracing::{trace, trace_span, warn, Span};
 use vello::peniko::{BlendMode, Color, Fill, Gradient};
 use vello::Scene;
 
+use crate::direction::EdgeInsets;
 use crate::kurbo::RoundedRectRadii;
 use crate::paint_scene_helpers::{fill_color, stroke};
-use crate::widget::{WidgetId, WidgetMut, WidgetPod, WidgetRef};
+use crate::widget::{WidgetId, WidgetMut, WidgetPod};
 use crate::{
-    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    Point, PointerEvent, Size, StatusChange, TextEvent, Widget,
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, Insets, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Vec2, Widget,
 };
 
 // FIXME - Improve all doc in this module ASAP.
 
+/// The number of layers the shadow of an elevated [`SizedBox`] is painted with.
+const ELEVATION_SHADOW_LAYERS: usize = 4;
+
 /// Something that can be used as the background for a widget.
 #[non_exhaustive]
 #[allow(missing_docs)]
@@ -37,7 +41,6 @@ struct BorderStyle {
 }
 
 // TODO - Have Widget type as generic argument
-// TODO - Add Padding
 
 /// A widget with predefined size.
 ///
@@ -55,6 +58,8 @@ pub struct SizedBox {
     background: Option<BackgroundBrush>,
     border: Option<BorderStyle>,
     corner_radius: RoundedRectRadii,
+    padding: EdgeInsets,
+    elevation: f64,
 }
 
 impl SizedBox {
@@ -67,18 +72,27 @@ impl SizedBox {
             background: None,
             border: None,
             corner_radius: RoundedRectRadii::from_single_radius(0.0),
+            padding: EdgeInsets::ZERO,
+            elevation: 0.0,
         }
     }
 
     /// Construct container with child, and both width and height not set.
     pub fn new_with_id(child: impl Widget, id: WidgetId) -> Self {
+        Self::new_pod(WidgetPod::new_with_id(child, id).boxed())
+    }
+
+    /// Construct container with a child that is already in a `WidgetPod`.
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
         Self {
-            child: Some(WidgetPod::new_with_id(child, id).boxed()),
+            child: Some(child),
             width: None,
             height: None,
             background: None,
             border: None,
             corner_radius: RoundedRectRadii::from_single_radius(0.0),
+            padding: EdgeInsets::ZERO,
+            elevation: 0.0,
         }
     }
 
@@ -95,6 +109,8 @@ impl SizedBox {
             background: None,
             border: None,
             corner_radius: RoundedRectRadii::from_single_radius(0.0),
+            padding: EdgeInsets::ZERO,
+            elevation: 0.0,
         }
     }
 
@@ -164,18 +180,40 @@ impl SizedBox {
         self
     }
 
+    /// Builder-style method for setting the space between the border and the child.
+    ///
+    /// The padding can be given with [logical edges](crate::direction::LogicalInsets),
+    /// which are flipped in right-to-left layouts.
+    pub fn padding(mut self, padding: impl Into<EdgeInsets>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// Builder-style method for raising the container above what is behind it,
+    /// by painting a shadow below it.
+    ///
+    /// The elevation is roughly how far the shadow extends, in logical pixels.
+    pub fn elevation(mut self, elevation: f64) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
     // TODO - child()
 }
 
 impl WidgetMut<'_, SizedBox> {
     pub fn set_child(&mut self, child: impl Widget) {
-        self.widget.child = Some(WidgetPod::new(child).boxed());
+        if let Some(old_child) = self.widget.child.replace(WidgetPod::new(child).boxed()) {
+            self.ctx.remove_child(old_child);
+        }
         self.ctx.children_changed();
         self.ctx.request_layout();
     }
 
     pub fn remove_child(&mut self) {
-        self.widget.child = None;
+        if let Some(old_child) = self.widget.child.take() {
+            self.ctx.remove_child(old_child);
+        }
         self.ctx.children_changed();
         self.ctx.request_layout();
     }
@@ -240,6 +278,21 @@ impl WidgetMut<'_, SizedBox> {
         self.ctx.request_paint();
     }
 
+    /// Set the space between the border and the child.
+    pub fn set_padding(&mut self, padding: impl Into<EdgeInsets>) {
+        self.widget.padding = padding.into();
+        self.ctx.request_layout();
+    }
+
+    /// Set how far the container is raised above what is behind it.
+    ///
+    /// See [`SizedBox::elevation`].
+    pub fn set_elevation(&mut self, elevation: f64) {
+        self.widget.elevation = elevation;
+        // The paint insets depend on the elevation
+        self.ctx.request_layout();
+    }
+
     // TODO - Doc
     pub fn child_mut(&mut self) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
         let child = self.widget.child.as_mut()?;
@@ -273,6 +326,12 @@ impl SizedBox {
         )
     }
 
+    /// How far the shadow is moved down, and how far it extends past the container.
+    fn shadow(&self) -> (f64, f64) {
+        let elevation = self.elevation.max(0.0);
+        (elevation / 2.0, elevation)
+    }
+
     #[allow(dead_code)]
     pub(crate) fn width_and_height(&self) -> (Option<f64>, Option<f64>) {
         (self.width, self.height)
@@ -292,7 +351,11 @@ impl Widget for SizedBox {
         }
     }
 
-    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if let Some(ref mut child) = self.child {
+            child.on_access_event(ctx, event);
+        }
+    }
 
     fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
 
@@ -309,23 +372,36 @@ impl Widget for SizedBox {
             None => 0.0,
         };
 
+        let padding = self.padding.resolve(ctx.layout_direction());
+        let insets = Insets::new(
+            padding.x0 + border_width,
+            padding.y0 + border_width,
+            padding.x1 + border_width,
+            padding.y1 + border_width,
+        );
+
         let child_bc = self.child_constraints(bc);
-        let child_bc = child_bc.shrink((2.0 * border_width, 2.0 * border_width));
-        let origin = Point::new(border_width, border_width);
+        let child_bc = child_bc.shrink(insets.size());
+        let origin = Point::new(insets.x0, insets.y0);
 
         let mut size;
         match self.child.as_mut() {
             Some(child) => {
                 size = child.layout(ctx, &child_bc);
                 ctx.place_child(child, origin);
-                size = Size::new(
-                    size.width + 2.0 * border_width,
-                    size.height + 2.0 * border_width,
-                );
+                size += insets.size();
             }
             None => size = bc.constrain((self.width.unwrap_or(0.0), self.height.unwrap_or(0.0))),
         };
 
+        let (shadow_offset, shadow_spread) = self.shadow();
+        ctx.set_paint_insets(Insets::new(
+            shadow_spread,
+            shadow_spread - shadow_offset,
+            shadow_spread,
+            shadow_spread + shadow_offset,
+        ));
+
         // TODO - figure out paint insets
         // TODO - figure out baseline offset
 
@@ -344,6 +420,27 @@ impl Widget for SizedBox {
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
         let corner_radius = self.corner_radius;
 
+        if self.elevation > 0.0 {
+            // Vello can't blur yet, so the shadow is a stack of translucent layers
+            // which get darker towards the container.
+            let (offset, spread) = self.shadow();
+            let layer_color =
+                theme::SHADOW_COLOR.with_alpha_factor(1.0 / ELEVATION_SHADOW_LAYERS as f32);
+            for layer in 1..=ELEVATION_SHADOW_LAYERS {
+                let grow = spread * layer as f64 / ELEVATION_SHADOW_LAYERS as f64;
+                let shadow = ctx.size().to_rect().inflate(grow, grow) + Vec2::new(0.0, offset);
+                let radii = corner_radius;
+                let radii = RoundedRectRadii::new(
+                    radii.top_left + grow,
+                    radii.top_right + grow,
+                    radii.bottom_right + grow,
+                    radii.bottom_left + grow,
+                );
+                fill_color(scene, &shadow.to_rounded_rect(radii), layer_color);
+                ctx.set_painted_bounds(shadow);
+            }
+        }
+
         if let Some(background) = self.background.as_mut() {
             let panel = ctx.size().to_rounded_rect(corner_radius);
 
@@ -355,12 +452,11 @@ impl Widget for SizedBox {
         }
 
         if let Some(border) = &self.border {
-            let border_width = border.width;
-            let border_rect = ctx
-                .size()
-                .to_rect()
-                .inset(border_width / -2.0)
-                .to_rounded_rect(corner_radius);
+            let (border_rect, border_width) = ctx.snap_stroke(
+                ctx.size().to_rect().inset(border.width / -2.0),
+                border.width,
+            );
+            let border_rect = border_rect.to_rounded_rect(corner_radius);
             stroke(scene, &border_rect, border.color, border_width);
         };
 
@@ -379,9 +475,9 @@ impl Widget for SizedBox {
         }
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         if let Some(child) = &self.child {
-            smallvec![child.as_dyn()]
+            smallvec![child.id()]
         } else {
             smallvec![]
         }
@@ -439,7 +535,7 @@ mod tests {
     use super::*;
     use crate::assert_render_snapshot;
     use crate::testing::TestHarness;
-    use crate::widget::Label;
+    use crate::widget::{Flex, Label};
 
     #[test]
     fn expand() {
@@ -498,5 +594,31 @@ mod tests {
         assert_render_snapshot!(harness, "label_box_no_size");
     }
 
+    #[test]
+    fn padding_and_elevation() {
+        let [box_id, child_id] = crate::testing::widget_ids();
+        let sized_box = SizedBox::new_pod(
+            WidgetPod::new_with_id(SizedBox::empty().width(20.0).height(10.0), child_id).boxed(),
+        )
+        .border(Color::BLUE, 1.0)
+        .padding(Insets::new(2.0, 3.0, 4.0, 5.0))
+        .elevation(4.0);
+        let widget = Flex::row().with_child_pod(WidgetPod::new_with_id(sized_box, box_id).boxed());
+
+        let harness = TestHarness::create(widget);
+        let sized_box = harness.get_widget(box_id);
+        assert_eq!(
+            sized_box.state().layout_rect().size(),
+            Size::new(28.0, 20.0)
+        );
+        let child = harness.get_widget(child_id);
+        assert_eq!(child.state().layout_rect().origin(), Point::new(3.0, 4.0));
+        // The shadow is outside the box, and further below it than above
+        assert_eq!(
+            sized_box.state().paint_insets,
+            Insets::new(4.0, 2.0, 4.0, 6.0)
+        );
+    }
+
     // TODO - add screenshot tests for different brush types
 }
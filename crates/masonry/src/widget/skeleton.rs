@@ -0,0 +1,205 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A placeholder widget shown while content loads.
+
+use accesskit::Role;
+use kurbo::{Affine, Point, RoundedRect};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::peniko::{Fill, Gradient};
+use vello::Scene;
+
+use crate::color::linear_light_stops;
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// The time a shimmer takes to sweep across a skeleton, in nanoseconds.
+const SHIMMER_PERIOD: f64 = 1.5e9;
+
+/// The width of the last line of a text skeleton, relative to the other lines.
+const LAST_LINE_FRACTION: f64 = 0.6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Shape {
+    Block(Size),
+    Text { lines: usize },
+}
+
+/// A grey shape with a shimmer sweeping across it, which stands in for content
+/// that is still loading.
+///
+/// A skeleton is either a block of a given size, or some lines of text which fill
+/// the available width.
+pub struct Skeleton {
+    shape: Shape,
+    /// How far the shimmer has swept across, from 0 to 1.
+    phase: f64,
+}
+
+impl Skeleton {
+    /// A block of `width` by `height`.
+    pub fn new(width: f64, height: f64) -> Self {
+        Skeleton {
+            shape: Shape::Block(Size::new(width, height)),
+            phase: 0.0,
+        }
+    }
+
+    /// Some lines of text, the last of which is shorter than the others.
+    pub fn text(lines: usize) -> Self {
+        Skeleton {
+            shape: Shape::Text { lines },
+            phase: 0.0,
+        }
+    }
+
+    fn bars(&self, size: Size) -> Vec<RoundedRect> {
+        match self.shape {
+            Shape::Block(_) => {
+                vec![size.to_rect().to_rounded_rect(theme::SKELETON_RADIUS)]
+            }
+            Shape::Text { lines } => (0..lines)
+                .map(|line| {
+                    let line_width = if line + 1 == lines && lines > 1 {
+                        size.width * LAST_LINE_FRACTION
+                    } else {
+                        size.width
+                    };
+                    // Each bar is as tall as the text, with the leading left empty
+                    let y = line as f64 * theme::SKELETON_LINE_HEIGHT;
+                    Size::new(line_width, theme::TEXT_SIZE_NORMAL)
+                        .to_rect()
+                        .with_origin((0.0, y))
+                        .to_rounded_rect(theme::SKELETON_RADIUS)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl WidgetMut<'_, Skeleton> {
+    pub fn set_size(&mut self, width: f64, height: f64) {
+        self.widget.shape = Shape::Block(Size::new(width, height));
+        self.ctx.request_layout();
+    }
+
+    pub fn set_lines(&mut self, lines: usize) {
+        self.widget.shape = Shape::Text { lines };
+        self.ctx.request_layout();
+    }
+}
+
+impl Widget for Skeleton {
+    fn on_pointer_event(&mut self, _ctx: &mut EventCtx, _event: &PointerEvent) {}
+
+    fn on_text_event(&mut self, _ctx: &mut EventCtx, _event: &TextEvent) {}
+
+    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {}
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.request_anim_frame();
+            }
+            LifeCycle::AnimFrame(interval) => {
+                self.phase = (self.phase + *interval as f64 / SHIMMER_PERIOD).fract();
+                ctx.request_anim_frame();
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = match self.shape {
+            Shape::Block(size) => bc.constrain(size),
+            Shape::Text { lines } => {
+                let width = if bc.is_width_bounded() {
+                    bc.max().width
+                } else {
+                    theme::WIDE_WIDGET_WIDTH
+                };
+                let height = lines as f64 * theme::SKELETON_LINE_HEIGHT;
+                bc.constrain(Size::new(width, height))
+            }
+        };
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        // The highlight is a band as wide as the widget, which starts just off its
+        // left edge and ends just off its right edge
+        let x = (2.0 * self.phase - 1.0) * size.width;
+        let shimmer = Gradient::new_linear(Point::new(x, 0.0), Point::new(x + size.width, 0.0))
+            .with_stops(
+                linear_light_stops([
+                    theme::SKELETON_COLOR,
+                    theme::SKELETON_HIGHLIGHT_COLOR,
+                    theme::SKELETON_COLOR,
+                ])
+                .as_slice(),
+            );
+        for bar in self.bars(size) {
+            scene.fill(Fill::NonZero, Affine::IDENTITY, &shimmer, None, &bar);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ProgressIndicator
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        ctx.current_node().set_busy();
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Skeleton")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::Flex;
+
+    #[test]
+    fn skeleton_shimmers_while_shown() {
+        let widget = Flex::column()
+            .with_child(Skeleton::new(40.0, 40.0))
+            .with_child(Skeleton::text(3));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 200.0));
+
+        let block = harness.root_widget().children()[0];
+        assert_eq!(block.state().layout_rect().size(), Size::new(40.0, 40.0));
+        let text = harness.root_widget().children()[1];
+        let text_size = text.state().layout_rect().size();
+        assert_eq!(text_size.height, 3.0 * theme::SKELETON_LINE_HEIGHT);
+
+        let text = text.downcast::<Skeleton>().unwrap();
+        let bars = text.bars(text_size);
+        assert_eq!(bars.len(), 3);
+        assert!(bars[2].rect().width() < bars[0].rect().width());
+
+        // The first frame starts the animation
+        harness.animate(Duration::ZERO);
+        harness.animate(Duration::from_millis(750));
+        let block = harness.root_widget().children()[0];
+        let phase = block.downcast::<Skeleton>().unwrap().phase;
+        assert!((phase - 0.5).abs() < 0.05, "{phase}");
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A horizontal slider, for picking a value within a range.
+
+use std::ops::RangeInclusive;
+
+use accesskit::{ActionData, Role};
+use kurbo::{Circle, Point, RoundedRect};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::widget::WidgetMut;
+use crate::{
+    theme, AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+const THUMB_RADIUS: f64 = 7.0;
+const TRACK_HEIGHT: f64 = 4.0;
+/// How many steps <kbd>Page Up</kbd> and <kbd>Page Down</kbd> move the value.
+const PAGE_STEPS: f64 = 10.0;
+
+/// A horizontal slider, which picks a value within a range.
+///
+/// The value is changed by pressing on the track or dragging the thumb, or with the
+/// arrow keys while the slider has focus.
+///
+/// Emits [`Action::ValueChanged`] when the user changes the value.
+pub struct Slider {
+    value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+}
+
+impl Slider {
+    /// Create a new slider for a value in `range`.
+    ///
+    /// The arrow keys change the value by a hundredth of the range.
+    pub fn new(value: f64, range: RangeInclusive<f64>) -> Self {
+        let (min, max) = range.into_inner();
+        assert!(min <= max, "the range of a slider must not be empty");
+        Slider {
+            value: value.clamp(min, max),
+            min,
+            max,
+            step: (max - min) / 100.0,
+        }
+    }
+
+    /// Builder-style method to set how much the arrow keys change the value.
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// The current value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// How far the value is through the range, from 0 to 1.
+    fn fraction(&self) -> f64 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    /// Set the value, clamped to the range, and emit an action if it changed.
+    fn change_value(&mut self, ctx: &mut EventCtx, value: f64) {
+        let value = value.clamp(self.min, self.max);
+        if value == self.value {
+            return;
+        }
+        trace!("Slider {:?} moved to {}", ctx.widget_id(), value);
+        self.value = value;
+        ctx.submit_action(Action::ValueChanged(value));
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+    }
+
+    /// The value under the pointer at window position `pos`, when the thumb is
+    /// dragged there.
+    fn value_at(&self, ctx: &EventCtx, pos: Point) -> f64 {
+        let x = pos.x - ctx.window_origin().x - THUMB_RADIUS;
+        let track_width = ctx.size().width - THUMB_RADIUS * 2.0;
+        if track_width <= 0.0 {
+            return self.value;
+        }
+        self.min + (x / track_width).clamp(0.0, 1.0) * (self.max - self.min)
+    }
+}
+
+impl WidgetMut<'_, Slider> {
+    /// Set the value, clamped to the range, without emitting an action.
+    pub fn set_value(&mut self, value: f64) {
+        self.widget.value = value.clamp(self.widget.min, self.widget.max);
+        self.ctx.request_paint();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the range, clamping the value to it.
+    pub fn set_range(&mut self, range: RangeInclusive<f64>) {
+        let (min, max) = range.into_inner();
+        assert!(min <= max, "the range of a slider must not be empty");
+        self.widget.min = min;
+        self.widget.max = max;
+        self.set_value(self.widget.value);
+    }
+
+    /// Set how much the arrow keys change the value.
+    pub fn set_step(&mut self, step: f64) {
+        self.widget.step = step;
+        self.ctx.request_accessibility_update();
+    }
+}
+
+impl Widget for Slider {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(MouseButton::Left, state) if !ctx.is_disabled() => {
+                ctx.request_focus();
+                ctx.set_active(true);
+                let pos = Point::new(state.position.x, state.position.y);
+                self.change_value(ctx, self.value_at(ctx, pos));
+                ctx.request_paint();
+            }
+            PointerEvent::PointerMove(state) if ctx.is_active() => {
+                let pos = Point::new(state.position.x, state.position.y);
+                self.change_value(ctx, self.value_at(ctx, pos));
+            }
+            PointerEvent::PointerUp(_, _) => {
+                ctx.set_active(false);
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let TextEvent::KeyboardKey(key, _) = event else {
+            return;
+        };
+        if !key.state.is_pressed() || !ctx.is_focused() || ctx.is_disabled() {
+            return;
+        }
+        let value = match &key.logical_key {
+            Key::Named(NamedKey::ArrowUp | NamedKey::ArrowRight) => self.value + self.step,
+            Key::Named(NamedKey::ArrowDown | NamedKey::ArrowLeft) => self.value - self.step,
+            Key::Named(NamedKey::PageUp) => self.value + self.step * PAGE_STEPS,
+            Key::Named(NamedKey::PageDown) => self.value - self.step * PAGE_STEPS,
+            Key::Named(NamedKey::Home) => self.min,
+            Key::Named(NamedKey::End) => self.max,
+            _ => return,
+        };
+        self.change_value(ctx, value);
+        ctx.set_handled();
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target != ctx.widget_id() || ctx.is_disabled() {
+            return;
+        }
+        match (event.action, &event.data) {
+            (accesskit::Action::Increment, _) => self.change_value(ctx, self.value + self.step),
+            (accesskit::Action::Decrement, _) => self.change_value(ctx, self.value - self.step),
+            (accesskit::Action::SetValue, Some(ActionData::NumericValue(value))) => {
+                self.change_value(ctx, *value);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::BuildFocusChain = event {
+            ctx.register_for_focus();
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        bc.constrain(Size::new(
+            theme::WIDE_WIDGET_WIDTH,
+            theme::BASIC_WIDGET_HEIGHT,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let centre_y = size.height / 2.0;
+        let track = RoundedRect::new(
+            THUMB_RADIUS,
+            centre_y - TRACK_HEIGHT / 2.0,
+            (size.width - THUMB_RADIUS).max(THUMB_RADIUS),
+            centre_y + TRACK_HEIGHT / 2.0,
+            TRACK_HEIGHT / 2.0,
+        );
+        fill_color(scene, &track, theme::BACKGROUND_DARK);
+
+        let thumb_x = THUMB_RADIUS + self.fraction() * track.width();
+        let (value_color, thumb_color) = if ctx.is_disabled() {
+            (
+                theme::DISABLED_FOREGROUND_DARK,
+                theme::DISABLED_FOREGROUND_LIGHT,
+            )
+        } else {
+            (theme::PRIMARY_LIGHT, theme::FOREGROUND_LIGHT)
+        };
+        let filled = RoundedRect::new(
+            track.rect().x0,
+            track.rect().y0,
+            thumb_x,
+            track.rect().y1,
+            TRACK_HEIGHT / 2.0,
+        );
+        fill_color(scene, &filled, value_color);
+
+        let thumb = Circle::new(Point::new(thumb_x, centre_y), THUMB_RADIUS - 1.0);
+        fill_color(scene, &thumb, thumb_color);
+        let border_color = if (ctx.is_hot() || ctx.is_active()) && !ctx.is_disabled() {
+            theme::PRIMARY_DARK
+        } else {
+            theme::BORDER_LIGHT
+        };
+        stroke(scene, &thumb, border_color, 1.0);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Slider
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let node = ctx.current_node();
+        node.set_numeric_value(self.value);
+        node.set_min_numeric_value(self.min);
+        node.set_max_numeric_value(self.max);
+        node.set_numeric_value_step(self.step);
+        node.add_action(accesskit::Action::Focus);
+        node.add_action(accesskit::Action::Increment);
+        node.add_action(accesskit::Action::Decrement);
+        node.add_action(accesskit::Action::SetValue);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        SmallVec::new()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Slider")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kurbo::Vec2;
+
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness, TestWidgetExt};
+    use crate::widget::Flex;
+
+    #[test]
+    fn press_and_drag_move_the_thumb() {
+        let [slider_id] = widget_ids();
+        let slider = Slider::new(0.0, 0.0..=10.0).with_id(slider_id);
+        let mut harness = TestHarness::create(Flex::column().with_child(slider));
+        let value = |harness: &TestHarness| {
+            let slider = harness.get_widget(slider_id);
+            slider.downcast::<Slider>().unwrap().value()
+        };
+        let rect = harness.get_widget(slider_id).state().window_layout_rect();
+        let track_width = rect.width() - THUMB_RADIUS * 2.0;
+        let at = |fraction: f64| {
+            Point::new(
+                rect.x0 + THUMB_RADIUS + track_width * fraction,
+                rect.center().y,
+            )
+        };
+
+        harness.mouse_move(at(0.5));
+        harness.mouse_button_press(MouseButton::Left);
+        assert_eq!(value(&harness), 5.0);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::ValueChanged(5.0), slider_id))
+        );
+        harness.mouse_move(at(0.8));
+        assert!((value(&harness) - 8.0).abs() < 1e-9);
+        // The value stops at the end of the track
+        harness.mouse_move(at(1.0) + Vec2::new(50.0, 0.0));
+        assert_eq!(value(&harness), 10.0);
+        harness.mouse_button_release(MouseButton::Left);
+
+        harness.mouse_move(at(0.2));
+        assert_eq!(value(&harness), 10.0);
+
+        harness.process_access_event(accesskit::ActionRequest {
+            action: accesskit::Action::Decrement,
+            target: slider_id.into(),
+            data: None,
+        });
+        assert!((value(&harness) - 9.9).abs() < 1e-9);
+    }
+}
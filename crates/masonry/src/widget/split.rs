@@ -14,10 +14,11 @@ use winit::window::CursorIcon;
 use crate::kurbo::Line;
 use crate::paint_scene_helpers::{fill_color, stroke};
 use crate::widget::flex::Axis;
-use crate::widget::{WidgetMut, WidgetPod, WidgetRef};
+use crate::widget::{WidgetMut, WidgetPod};
 use crate::{
     theme, AccessCtx, AccessEvent, BoxConstraints, Color, EventCtx, LayoutCtx, LifeCycle,
     LifeCycleCtx, PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget,
+    WidgetId,
 };
 
 // TODO - Have child widget type as generic argument
@@ -252,7 +253,7 @@ impl Split {
             ),
         };
         let splitter_color = self.bar_color();
-        fill_color(scene, &rect, splitter_color);
+        fill_color(scene, &ctx.snap_rect(rect), splitter_color);
     }
 
     fn paint_stroked_bar(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
@@ -286,8 +287,10 @@ impl Split {
             ),
         };
         let splitter_color = self.bar_color();
-        stroke(scene, &line1, splitter_color, line_width);
-        stroke(scene, &line2, splitter_color, line_width);
+        for line in [line1, line2] {
+            let (line, line_width) = ctx.snap_line(line, line_width);
+            stroke(scene, &line, splitter_color, line_width);
+        }
     }
 }
 
@@ -431,10 +434,10 @@ impl Widget for Split {
             }
         }
 
-        if !self.child1.is_active() {
+        if !ctx.get_ref(&self.child1).state().is_active {
             self.child1.on_pointer_event(ctx, event);
         }
-        if !self.child2.is_active() {
+        if !ctx.get_ref(&self.child2).state().is_active {
             self.child2.on_pointer_event(ctx, event);
         }
     }
@@ -545,7 +548,9 @@ impl Widget for Split {
         ctx.place_child(&mut self.child1, child1_pos);
         ctx.place_child(&mut self.child2, child2_pos);
 
-        let paint_rect = self.child1.paint_rect().union(self.child2.paint_rect());
+        let paint_rect = ctx
+            .child_paint_rect(&self.child1)
+            .union(ctx.child_paint_rect(&self.child2));
         let insets = paint_rect - my_size.to_rect();
         ctx.set_paint_insets(insets);
 
@@ -573,8 +578,8 @@ impl Widget for Split {
         self.child2.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        smallvec![self.child1.as_dyn(), self.child2.as_dyn()]
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child1.id(), self.child2.id()]
     }
 
     fn make_trace_span(&self) -> Span {
@@ -0,0 +1,128 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A marker for children which stay in view while their section is scrolled.
+
+use accesskit::Role;
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace_span, Span};
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// A child of a [`Flex`](crate::widget::Flex) which stays at the start of a
+/// [`Portal`](crate::widget::Portal) while the children after it are scrolled past,
+/// such as the header of a section in a long list.
+///
+/// The child stays in view until the next `Sticky` sibling reaches it and pushes it
+/// out, or until the end of the `Flex`. This only works when the `Flex` is the
+/// content of the portal itself, and the flex scrolls along its major axis.
+/// The child is painted above its siblings, so it should paint its own background.
+pub struct Sticky {
+    child: WidgetPod<Box<dyn Widget>>,
+}
+
+impl Sticky {
+    /// Create a new sticky child.
+    pub fn new(child: impl Widget) -> Self {
+        Sticky {
+            child: WidgetPod::new(child).boxed(),
+        }
+    }
+
+    /// Create a new sticky child from a [`WidgetPod`].
+    pub fn new_pod(child: WidgetPod<Box<dyn Widget>>) -> Self {
+        Sticky { child }
+    }
+}
+
+impl WidgetMut<'_, Sticky> {
+    pub fn child_mut(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        self.ctx.get_mut(&mut self.widget.child)
+    }
+}
+
+impl Widget for Sticky {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        ctx.set_baseline_offset(ctx.child_baseline_offset(&self.child));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.child.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("Sticky")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::{Flex, Portal, SizedBox};
+
+    #[test]
+    fn headers_pin_and_push_each_other_out() {
+        let [first_id, second_id] = widget_ids();
+        let header = || Sticky::new(SizedBox::empty().height(20.0));
+        let items = || SizedBox::empty().height(100.0);
+        let list = Flex::column()
+            .with_child_id(header(), first_id)
+            .with_child(items())
+            .with_child_id(header(), second_id)
+            .with_child(items());
+        let mut harness = TestHarness::create_with_size(Portal::new(list), Size::new(100.0, 60.0));
+        let header_y = |harness: &TestHarness, id| harness.get_widget(id).state().layout_rect().y0;
+        let mut scroll_to = |y| {
+            harness.edit_root_widget(|mut portal| {
+                let mut portal = portal.downcast::<Portal<Flex>>();
+                portal.set_viewport_pos(Point::new(0.0, y));
+            });
+            (header_y(&harness, first_id), header_y(&harness, second_id))
+        };
+
+        assert_eq!(scroll_to(0.0), (0.0, 120.0));
+        assert_eq!(scroll_to(50.0), (50.0, 120.0));
+        // The second header pushes the first one out of view
+        assert_eq!(scroll_to(110.0), (100.0, 120.0));
+        assert_eq!(scroll_to(150.0), (100.0, 150.0));
+    }
+}
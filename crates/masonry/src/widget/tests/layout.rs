@@ -3,9 +3,12 @@
 
 //! Tests related to layout.
 
-use crate::kurbo::{Insets, Size};
+use smallvec::smallvec;
+
+use crate::kurbo::{Insets, Rect, Size};
 use crate::testing::{widget_ids, ModularWidget, TestHarness, TestWidgetExt};
-use crate::widget::{Flex, SizedBox};
+use crate::widget::{Button, Flex, SizedBox};
+use crate::{Action, Point, WidgetPod};
 
 #[test]
 fn layout_simple() {
@@ -68,6 +71,136 @@ fn layout_insets() {
     assert_eq!(parent_paint_rect.y1, BOX_WIDTH + 20.0);
 }
 
+#[test]
+fn painted_bounds_grow_paint_rect() {
+    const BOX_WIDTH: f64 = 50.;
+
+    let [child_id, parent_id] = widget_ids();
+
+    // A shadow the widget didn't ask for room for in layout
+    let shadow = Rect::new(-10., 0., BOX_WIDTH + 10., BOX_WIDTH + 20.);
+    let child_widget = ModularWidget::new(())
+        .layout_fn(|_, _, _| Size::new(BOX_WIDTH, BOX_WIDTH))
+        .paint_fn(move |_, ctx, _| ctx.set_painted_bounds(shadow));
+    let parent_widget = SizedBox::new_with_id(child_widget, child_id).with_id(parent_id);
+
+    let mut harness = TestHarness::create(parent_widget);
+    assert_eq!(
+        harness.get_widget(child_id).state().paint_rect(),
+        Rect::new(0., 0., BOX_WIDTH, BOX_WIDTH)
+    );
+
+    // The next layout includes what was painted, in the parent's paint rect too
+    let _ = harness.render();
+    assert!(harness.root_widget().state().needs_layout);
+    let _ = harness.render();
+    let child_state = harness.get_widget(child_id).state();
+    assert_eq!(child_state.painted_bounds(), shadow);
+    assert_eq!(child_state.paint_rect(), shadow);
+    assert_eq!(harness.get_widget(parent_id).state().paint_rect(), shadow);
+}
+
+#[test]
+fn z_index_hit_testing() {
+    const BOX_WIDTH: f64 = 50.;
+
+    let [id_1, id_2] = widget_ids();
+
+    let children = (
+        WidgetPod::new_with_id(SizedBox::empty().width(BOX_WIDTH).height(BOX_WIDTH), id_1),
+        WidgetPod::new_with_id(SizedBox::empty().width(BOX_WIDTH).height(BOX_WIDTH), id_2),
+    );
+    // Both children overlap exactly; the second one is raised above the first.
+    let parent_widget = ModularWidget::new(children)
+        .lifecycle_fn(|children, ctx, event| {
+            children.0.lifecycle(ctx, event);
+            children.1.lifecycle(ctx, event);
+        })
+        .layout_fn(|children, ctx, bc| {
+            children.0.layout(ctx, bc);
+            children.1.layout(ctx, bc);
+            ctx.place_child(&mut children.0, Point::ORIGIN);
+            ctx.place_child(&mut children.1, Point::ORIGIN);
+            ctx.set_child_z_index(&mut children.1, 1);
+            Size::new(BOX_WIDTH, BOX_WIDTH)
+        })
+        .paint_fn(|children, ctx, scene| {
+            ctx.paint_children(scene, [&mut children.0, &mut children.1]);
+        })
+        .children_fn(|children| smallvec![children.0.id(), children.1.id()]);
+
+    let harness = TestHarness::create(parent_widget);
+
+    assert_eq!(harness.get_widget(id_1).state().z_index, 0);
+    assert_eq!(harness.get_widget(id_2).state().z_index, 1);
+
+    let hit = harness
+        .root_widget()
+        .find_widget_at_pos(Point::new(BOX_WIDTH / 2., BOX_WIDTH / 2.))
+        .unwrap();
+    assert_eq!(hit.state().id, id_2);
+}
+
+#[test]
+fn bounding_rect_includes_overflowing_children() {
+    const BOX_WIDTH: f64 = 50.;
+
+    let [child_id, parent_id] = widget_ids();
+
+    let child = WidgetPod::new_with_id(
+        SizedBox::empty().width(BOX_WIDTH).height(BOX_WIDTH),
+        child_id,
+    );
+    let parent_widget = ModularWidget::new(child)
+        .lifecycle_fn(|child, ctx, event| child.lifecycle(ctx, event))
+        .layout_fn(|child, ctx, bc| {
+            child.layout(ctx, bc);
+            // The child is placed outside of its parent's layout rect
+            ctx.place_child(child, Point::new(2. * BOX_WIDTH, 0.));
+            Size::new(BOX_WIDTH, BOX_WIDTH)
+        })
+        .children_fn(|child| smallvec![child.id()]);
+    let widget = SizedBox::new_with_id(parent_widget, parent_id);
+
+    let harness = TestHarness::create(widget);
+
+    let parent_state = harness.get_widget(parent_id).state();
+    let child_state = harness.get_widget(child_id).state();
+    assert_eq!(parent_state.window_layout_rect().width(), BOX_WIDTH);
+    assert_eq!(
+        parent_state.window_bounding_rect(),
+        parent_state
+            .window_layout_rect()
+            .union(child_state.window_layout_rect())
+    );
+}
+
+#[test]
+fn overflowing_children_get_pointer_events() {
+    const BOX_WIDTH: f64 = 50.;
+
+    let [button_id] = widget_ids();
+
+    let child = WidgetPod::new_with_id(Button::new("Outside"), button_id);
+    let parent_widget = ModularWidget::new(child)
+        .pointer_event_fn(|child, ctx, event| child.on_pointer_event(ctx, event))
+        .lifecycle_fn(|child, ctx, event| child.lifecycle(ctx, event))
+        .layout_fn(|child, ctx, bc| {
+            child.layout(ctx, bc);
+            // The button is placed below its parent's layout rect, like a popup
+            ctx.place_child(child, Point::new(0., 2. * BOX_WIDTH));
+            Size::new(BOX_WIDTH, BOX_WIDTH)
+        })
+        .children_fn(|child| smallvec![child.id()]);
+
+    let mut harness = TestHarness::create(parent_widget);
+    harness.mouse_click_on(button_id);
+    assert_eq!(
+        harness.pop_action(),
+        Some((Action::ButtonPressed, button_id))
+    );
+}
+
 // TODO - insets + flex
 // TODO - viewport
 // TODO - insets + viewport
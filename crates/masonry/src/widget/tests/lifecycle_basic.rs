@@ -6,7 +6,7 @@
 use insta::assert_debug_snapshot;
 
 use crate::testing::{
-    widget_ids, Record, Recording, ReplaceChild, TestHarness, TestWidgetExt as _,
+    widget_ids, ModularWidget, Record, Recording, ReplaceChild, TestHarness, TestWidgetExt as _,
 };
 use crate::widget::{Flex, Label, SizedBox};
 use crate::*;
@@ -85,6 +85,133 @@ fn child_tracking() {
     assert_eq!(child_state.children.entry_count(), 2);
 }
 
+#[test]
+fn parent_tracking() {
+    let [id_1, id_2, id_3, id_4] = widget_ids();
+
+    let widget = SizedBox::new_with_id(
+        Flex::row()
+            .with_child_id(SizedBox::empty(), id_1)
+            .with_child_id(SizedBox::new_with_id(SizedBox::empty(), id_2), id_3),
+        id_4,
+    );
+
+    let harness = TestHarness::create(widget);
+
+    let root_id = harness.root_widget().id();
+    assert_eq!(harness.get_parent_id(id_1), Some(id_4));
+    assert_eq!(harness.get_parent_id(id_2), Some(id_3));
+    assert_eq!(harness.get_parent_id(id_3), Some(id_4));
+    assert_eq!(harness.get_parent_id(id_4), Some(root_id));
+    assert_eq!(harness.get_parent_id(root_id), None);
+}
+
+#[test]
+fn children_are_tracked_in_order() {
+    let [id_1, id_2, id_3] = widget_ids();
+
+    let widget = Flex::row()
+        .with_child_id(SizedBox::empty(), id_1)
+        .with_child_id(SizedBox::empty(), id_2);
+
+    let mut harness = TestHarness::create(widget);
+    let flex_id = harness.get_parent_id(id_1).unwrap();
+    assert_eq!(harness.get_children_ids(flex_id), [id_1, id_2]);
+
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        flex.insert_child_pod(1, WidgetPod::new_with_id(SizedBox::empty(), id_3).boxed());
+    });
+    assert_eq!(harness.get_children_ids(flex_id), [id_1, id_3, id_2]);
+
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        flex.move_child(0, 2);
+        flex.remove_child(0);
+    });
+    assert_eq!(harness.get_children_ids(flex_id), [id_2, id_1]);
+    assert_eq!(harness.get_parent_id(id_3), None);
+    harness.check_invariants();
+}
+
+#[test]
+fn removed_child_is_notified() {
+    let [child_id] = widget_ids();
+    let record = Recording::default();
+
+    let widget = Flex::row().with_child_id(SizedBox::empty().record(&record), child_id);
+
+    let mut harness = TestHarness::create(widget);
+    record.clear();
+
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        flex.remove_child(0);
+    });
+
+    let record = record.drain();
+    assert!(record
+        .iter()
+        .any(|event| matches!(event, Record::L(LifeCycle::WidgetRemoved))));
+    assert_eq!(harness.get_parent_id(child_id), None);
+}
+
+#[test]
+fn removed_subtree_is_torn_down() {
+    struct Buffer;
+
+    impl crate::gpu::GpuResource for Buffer {
+        fn create(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue) {}
+        fn release(&mut self) {}
+    }
+
+    let [outer_id, inner_id, leaf_id] = widget_ids();
+    let [outer, inner, leaf] = [(); 3].map(|_| Recording::default());
+
+    // The deepest widget holds a GPU resource
+    let leaf_widget = ModularWidget::new(()).lifecycle_fn(|_, ctx, event| {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.register_gpu_resource(Buffer);
+        }
+    });
+    let subtree = SizedBox::new_with_id(
+        Flex::column()
+            .with_child_id(leaf_widget.record(&leaf), leaf_id)
+            .with_child(Label::new("Sibling"))
+            .record(&inner),
+        inner_id,
+    )
+    .record(&outer);
+    let widget = Flex::row().with_child_id(subtree, outer_id);
+
+    let mut harness = TestHarness::create(widget);
+    assert_eq!(harness.gpu_resources().len(), 1);
+    for record in [&outer, &inner, &leaf] {
+        record.clear();
+    }
+
+    harness.edit_root_widget(|mut root| {
+        let mut flex = root.downcast::<Flex>();
+        flex.remove_child(0);
+    });
+
+    // Each widget is torn down once, after it handled WidgetRemoved
+    for record in [&outer, &inner, &leaf] {
+        let record = record.drain();
+        let removals = record
+            .iter()
+            .filter(|event| matches!(event, Record::Removed))
+            .count();
+        assert_eq!(removals, 1);
+        assert!(matches!(record.last(), Some(Record::Removed)));
+    }
+    assert_eq!(harness.gpu_resources().len(), 0);
+    for id in [outer_id, inner_id, leaf_id] {
+        assert_eq!(harness.get_parent_id(id), None);
+        assert!(harness.try_get_widget(id).is_none());
+    }
+}
+
 /// Test that all children are registered correctly after a child is replaced.
 #[test]
 #[cfg(FALSE)]
@@ -182,7 +182,7 @@ fn resign_focus_on_disable() {
                 ctx.place_child(child, Point::ZERO);
                 layout
             })
-            .children_fn(|child| smallvec![child.as_dyn()])
+            .children_fn(|child| smallvec![child.id()])
     }
 
     let [group_0, group_1, sub_group, focus_1, focus_2] = widget_ids();
@@ -238,3 +238,72 @@ fn resign_focus_on_disable() {
     assert_eq!(harness.window().focus_chain(), &[focus_2]);
     assert_eq!(harness.window().focus, None);
 }
+
+/// Check that the focus ring is only shown for focus reached with the keyboard.
+#[test]
+fn focus_ring_after_keyboard_navigation() {
+    fn focusable() -> impl Widget {
+        ModularWidget::new(())
+            .pointer_event_fn(|_, ctx, event| {
+                if let PointerEvent::PointerDown(..) = event {
+                    ctx.request_focus();
+                }
+            })
+            .lifecycle_fn(|_, ctx, event| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .layout_fn(|_, _, _| Size::new(40.0, 20.0))
+    }
+
+    let [id_1, id_2] = widget_ids();
+    let widget = Flex::column()
+        .with_child_id(focusable(), id_1)
+        .with_child_id(focusable(), id_2);
+    let mut harness = TestHarness::create(widget);
+    let focused = |harness: &TestHarness| harness.focused_widget().map(|widget| widget.id());
+
+    harness.mouse_click_on(id_1);
+    assert_eq!(focused(&harness), Some(id_1));
+    assert_eq!(harness.focus_ring(), None);
+
+    harness.move_focus(true);
+    assert_eq!(focused(&harness), Some(id_2));
+    let offset = theme::FOCUS_RING_OFFSET;
+    let rect = harness.get_widget(id_2).state().window_layout_rect();
+    assert_eq!(harness.focus_ring(), Some(rect.inflate(offset, offset)));
+
+    harness.mouse_click_on(id_1);
+    assert_eq!(focused(&harness), Some(id_1));
+    assert_eq!(harness.focus_ring(), None);
+}
+
+/// Check that widgets are focusable and get a cursor from their interactivity.
+#[test]
+fn interactivity_sets_focus_and_cursor() {
+    use crate::widget::{Button, Label, Textbox};
+
+    let [button_id, label_id, textbox_id] = widget_ids();
+    let widget = Flex::column()
+        .with_child_id(Button::new("Button"), button_id)
+        .with_child_id(Label::new("Label"), label_id)
+        .with_child_id(Textbox::new(""), textbox_id);
+    let mut harness = TestHarness::create(widget);
+    let focused = |harness: &TestHarness| harness.focused_widget().map(|widget| widget.id());
+
+    harness.mouse_click_on(textbox_id);
+    assert_eq!(focused(&harness), Some(textbox_id));
+    harness.move_focus(true);
+    assert_eq!(focused(&harness), Some(button_id));
+    // The label isn't focusable, so it's skipped
+    harness.move_focus(true);
+    assert_eq!(focused(&harness), Some(textbox_id));
+
+    harness.mouse_move_to(button_id);
+    assert_eq!(harness.cursor_icon(), winit::window::CursorIcon::Pointer);
+    harness.mouse_move_to(label_id);
+    assert_eq!(harness.cursor_icon(), winit::window::CursorIcon::Default);
+    harness.mouse_move_to(textbox_id);
+    assert_eq!(harness.cursor_icon(), winit::window::CursorIcon::Text);
+}
@@ -25,7 +25,7 @@ fn make_parent_widget<W: Widget>(child: W) -> ModularWidget<WidgetPod<W>> {
         .paint_fn(move |child, ctx, scene| {
             child.paint(ctx, scene);
         })
-        .children_fn(|child| smallvec![child.as_dyn()])
+        .children_fn(|child| smallvec![child.id()])
 }
 
 #[should_panic(expected = "not visited in method on_pointer_event")]
@@ -243,7 +243,7 @@ fn check_forget_children_changed() {
         })
         .children_fn(|child| {
             if let Some(child) = child {
-                smallvec![child.as_dyn()]
+                smallvec![child.id()]
             } else {
                 smallvec![]
             }
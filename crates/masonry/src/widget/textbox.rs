@@ -1,8 +1,11 @@
 // Copyright 2018 the Xilem Authors and the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
+use std::ops::Range;
+use std::time::Duration;
+
 use accesskit::Role;
-use kurbo::{Affine, Point, Size, Stroke};
+use kurbo::{Affine, BezPath, Circle, Point, Size, Stroke};
 use parley::{
     layout::Alignment,
     style::{FontFamily, FontStack},
@@ -10,17 +13,19 @@ use parley::{
 use smallvec::SmallVec;
 use tracing::trace;
 use vello::{
-    peniko::{BlendMode, Color},
+    peniko::{BlendMode, Color, Fill},
     Scene,
 };
+use winit::event::MouseButton;
 
 use crate::{
-    text2::{TextBrush, TextEditor, TextStorage, TextWithSelection},
-    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    PointerEvent, StatusChange, TextEvent, Widget,
+    text2::{Affinity, Document, Selection, TextBrush, TextEditor, TextStorage, TextWithSelection},
+    AccessCtx, AccessEvent, Action, BoxConstraints, EventCtx, InputHint, Interactivity, Keymap,
+    LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget,
+    WidgetId,
 };
 
-use super::{LineBreaking, WidgetMut, WidgetRef};
+use super::{LineBreaking, WidgetMut};
 
 const TEXTBOX_PADDING: f64 = 3.0;
 /// HACK: A "margin" which is placed around the outside of all textboxes, ensuring that
@@ -32,29 +37,52 @@ const TEXTBOX_PADDING: f64 = 3.0;
 /// In theory, this should be proper margin/padding in the parent widget, but that hasn't been
 /// designed.
 const TEXTBOX_MARGIN: f64 = 8.0;
+/// The width of each wave of the squiggle under misspelled words.
+const SQUIGGLE_WAVELENGTH: f64 = 4.0;
+const SQUIGGLE_HEIGHT: f64 = 2.0;
+/// The radius of the bullets shown for masked text, relative to the line height.
+const BULLET_RADIUS: f64 = 0.15;
 
 /// The textbox widget is a widget which shows text which can be edited by the user
 ///
 /// For immutable text [`Prose`](super::Prose) should be preferred
+///
+/// With [`with_spellcheck`](Self::with_spellcheck), misspelled words are underlined,
+/// as decided by the app's [spellchecker](crate::spellcheck). Right-clicking one
+/// selects it and submits [`Action::SpellingSuggestions`](crate::Action::SpellingSuggestions).
+///
+/// The [`InputHint`] tells the platform what kind of text to expect, and sets the
+/// defaults: the text of a [password](InputHint::Password) is shown as bullets and
+/// hidden from accessibility tools, and text which isn't [valid](InputHint::is_valid)
+/// for the hint gets a red outline.
 // TODO: RichTextBox 👀
 pub struct Textbox {
-    // We hardcode the underlying storage type as `String`.
-    // We might change this to a rope based structure at some point.
+    // We hardcode the underlying storage type as `Document`.
     // If you need a text box which uses a different text type, you should
     // create a custom widget
-    editor: TextEditor<String>,
+    editor: TextEditor<Document>,
     line_break_mode: LineBreaking,
     show_disabled: bool,
     brush: TextBrush,
+    spellcheck: bool,
+    input_hint: InputHint,
+    /// The byte ranges of the words flagged in the last layout pass
+    misspellings: Vec<Range<usize>>,
 }
 
 impl Textbox {
     pub fn new(initial_text: impl Into<String>) -> Self {
         Textbox {
-            editor: TextEditor::new(initial_text.into(), crate::theme::TEXT_SIZE_NORMAL as f32),
+            editor: TextEditor::new(
+                Document::new(initial_text),
+                crate::theme::TEXT_SIZE_NORMAL as f32,
+            ),
             line_break_mode: LineBreaking::WordWrap,
             show_disabled: true,
             brush: crate::theme::TEXT_COLOR.into(),
+            spellcheck: false,
+            input_hint: InputHint::Text,
+            misspellings: Vec::new(),
         }
     }
 
@@ -63,10 +91,17 @@ impl Textbox {
         self.editor.text()
     }
 
+    /// The document being edited, which indexes the text by line and records
+    /// its recent changes.
+    pub fn document(&self) -> &Document {
+        self.editor.text()
+    }
+
     #[doc(alias = "with_text_color")]
     pub fn with_text_brush(mut self, brush: impl Into<TextBrush>) -> Self {
         self.brush = brush.into();
-        self.editor.set_brush(self.brush.clone());
+        let brush = self.layout_brush(false);
+        self.editor.set_brush(brush);
         self
     }
 
@@ -92,6 +127,116 @@ impl Textbox {
         self.line_break_mode = line_break_mode;
         self
     }
+
+    /// Builder-style method to turn spellchecking on or off. It is off by default.
+    pub fn with_spellcheck(mut self, spellcheck: bool) -> Self {
+        self.spellcheck = spellcheck;
+        self
+    }
+
+    /// Builder-style method to set the kind of text the textbox takes. It is
+    /// [`InputHint::Text`] by default.
+    pub fn with_input_hint(mut self, input_hint: InputHint) -> Self {
+        self.input_hint = input_hint;
+        let brush = self.layout_brush(false);
+        self.editor.set_brush(brush);
+        self
+    }
+
+    /// Whether the text is valid for the textbox's [`InputHint`].
+    pub fn is_valid(&self) -> bool {
+        self.input_hint.is_valid(self.text())
+    }
+
+    /// The brush the text is laid out with.
+    ///
+    /// Masked text is laid out with a transparent brush, so that the caret and the
+    /// selection still line up with it, and bullets are painted over it.
+    fn layout_brush(&self, disabled: bool) -> TextBrush {
+        if self.input_hint.is_masked() {
+            Color::TRANSPARENT.into()
+        } else if disabled && self.show_disabled {
+            crate::theme::DISABLED_TEXT_COLOR.into()
+        } else {
+            self.brush.clone()
+        }
+    }
+
+    /// Builder-style method to set the keyboard shortcuts used to edit the text,
+    /// instead of the app's [`Keymap`].
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.editor.set_keymap(Some(keymap));
+        self
+    }
+
+    /// The misspelled word at `index` in the text, if any.
+    fn misspelling_at(&self, index: usize) -> Option<Range<usize>> {
+        self.misspellings
+            .iter()
+            .find(|range| range.start <= index && index <= range.end)
+            .cloned()
+    }
+
+    /// Paint a bullet over each character of masked text.
+    fn paint_bullets(&self, scene: &mut Scene, disabled: bool) {
+        let brush = if disabled && self.show_disabled {
+            crate::theme::DISABLED_TEXT_COLOR.into()
+        } else {
+            match &self.brush {
+                TextBrush::Normal(brush) | TextBrush::Highlight { text: brush, .. } => {
+                    brush.clone()
+                }
+            }
+        };
+        for (index, ch) in self.text().char_indices() {
+            let start = self.editor.cursor_line_for_text_position(index);
+            let end = self
+                .editor
+                .cursor_line_for_text_position(index + ch.len_utf8());
+            // Line breaks have no bullet
+            if ch == '\n' || start.p0.y != end.p0.y {
+                continue;
+            }
+            let height = start.p0.y - start.p1.y;
+            let center = Point::new((start.p0.x + end.p0.x) / 2.0, start.p0.y - height / 2.0);
+            scene.fill(
+                Fill::NonZero,
+                Affine::translate((TEXTBOX_PADDING, TEXTBOX_PADDING)),
+                &brush,
+                None,
+                &Circle::new(center, height * BULLET_RADIUS),
+            );
+        }
+    }
+
+    fn paint_squiggles(&self, scene: &mut Scene) {
+        for range in &self.misspellings {
+            let start = self.editor.cursor_line_for_text_position(range.start);
+            let end = self.editor.cursor_line_for_text_position(range.end);
+            // TODO - Underline words which wrap onto the next line
+            if start.p0.y != end.p0.y {
+                continue;
+            }
+            let y = start.p0.y - SQUIGGLE_HEIGHT;
+            let mut squiggle = BezPath::new();
+            squiggle.move_to((start.p0.x, y));
+            let mut x = start.p0.x;
+            let mut up = true;
+            while x < end.p0.x {
+                x = (x + SQUIGGLE_WAVELENGTH / 2.0).min(end.p0.x);
+                let wave_y = if up { y - SQUIGGLE_HEIGHT / 2.0 } else { y };
+                squiggle.line_to((x, wave_y));
+                up = !up;
+            }
+            scene.stroke(
+                &Stroke::new(1.0),
+                Affine::translate((TEXTBOX_PADDING, TEXTBOX_PADDING)),
+                crate::theme::SPELLING_ERROR_COLOR,
+                None,
+                &squiggle,
+            );
+        }
+    }
 }
 
 impl WidgetMut<'_, Textbox> {
@@ -99,13 +244,49 @@ impl WidgetMut<'_, Textbox> {
         self.widget.editor.text()
     }
 
+    pub fn document(&self) -> &Document {
+        self.widget.editor.text()
+    }
+
+    /// Edit the document programmatically, such as to indent the selected lines.
+    ///
+    /// The selection is kept, but moved back inside the text if it got shorter.
+    /// Unlike edits by the user, this doesn't submit
+    /// [`Action::TextChanged`](crate::Action::TextChanged).
+    pub fn edit_document<R>(&mut self, f: impl FnOnce(&mut Document) -> R) -> R {
+        self.widget.editor.reset_preedit();
+        self.set_text_properties(|layout| {
+            let ret = f(layout.text_mut());
+            let text = layout.text().as_str();
+            let selection = layout
+                .selection
+                .map(|selection| selection.constrained(text));
+            layout.set_selection(selection);
+            ret
+        })
+    }
+
+    /// Replace the whole text with `new_text` as a single edit of the document,
+    /// then select `selection`.
+    fn replace_document(&mut self, new_text: &str, selection: Option<Selection>) {
+        self.widget.editor.reset_preedit();
+        self.set_text_properties(|layout| {
+            if layout.text().as_str() != new_text {
+                let len = layout.text().len();
+                layout.text_mut().replace(0..len, new_text);
+            }
+            layout.set_selection(selection);
+        });
+    }
+
     pub fn set_text_properties<R>(
         &mut self,
-        f: impl FnOnce(&mut TextWithSelection<String>) -> R,
+        f: impl FnOnce(&mut TextWithSelection<Document>) -> R,
     ) -> R {
         let ret = f(&mut self.widget.editor);
         if self.widget.editor.needs_rebuild() {
             self.ctx.request_layout();
+            self.ctx.request_accessibility_update();
         }
         ret
     }
@@ -123,18 +304,71 @@ impl WidgetMut<'_, Textbox> {
                 "Called reset_text on a focused `Textbox`. This will lose the user's current selection and cursor"
             );
         }
+        self.replace_document(&new_text, None);
+    }
+
+    /// Replace the contents of the text box, and move the caret to the end.
+    ///
+    /// Unlike [`reset_text`](Self::reset_text), this leaves the text box ready to
+    /// be typed into, so it can be used while it is focused, for instance to
+    /// complete what the user is typing.
+    pub fn replace_text(&mut self, new_text: String) {
+        let caret = Selection::caret(new_text.len(), Affinity::Upstream);
+        self.replace_document(&new_text, Some(caret));
+    }
+
+    /// Replace the selected text with `text`, and move the caret after it.
+    ///
+    /// This is how apps apply the suggestion the user picked after
+    /// [`Action::SpellingSuggestions`](crate::Action::SpellingSuggestions). It does
+    /// nothing if nothing is selected.
+    pub fn replace_selection(&mut self, text: &str) {
+        let Some(selection) = self.widget.editor.selection else {
+            return;
+        };
+        let range = selection.range();
         self.widget.editor.reset_preedit();
-        self.set_text_properties(|layout| layout.set_text(new_text));
+        self.set_text_properties(|layout| {
+            layout.text_mut().replace(range.clone(), text);
+            let caret = range.start + text.len();
+            layout.set_selection(Some(Selection::caret(caret, Affinity::Upstream)));
+        });
+    }
+
+    /// Highlight the matches of `query`, ignoring case, and select the first one.
+    /// Returns the number of matches.
+    ///
+    /// The user can then move between the matches with the keymap's
+    /// [`FindNext`](crate::EditAction::FindNext) and
+    /// [`FindPrevious`](crate::EditAction::FindPrevious) shortcuts while the text box
+    /// is focused, which submits [`Action::FindMatchChanged`](crate::Action::FindMatchChanged).
+    pub fn find(&mut self, query: &str) -> usize {
+        self.set_text_properties(|layout| layout.find(query))
+    }
+
+    /// Select the next match of the find query, or the previous one if `forward`
+    /// is false. Returns the index of the selected match.
+    pub fn find_next(&mut self, forward: bool) -> Option<usize> {
+        self.set_text_properties(|layout| layout.find_next(forward))
+    }
+
+    /// Stop highlighting the matches of the find query.
+    pub fn clear_find(&mut self) {
+        self.set_text_properties(|layout| layout.clear_find());
+    }
+
+    /// Turn spellchecking on or off.
+    pub fn set_spellcheck(&mut self, spellcheck: bool) {
+        self.widget.spellcheck = spellcheck;
+        self.ctx.request_layout();
     }
 
     #[doc(alias = "set_text_color")]
     pub fn set_text_brush(&mut self, brush: impl Into<TextBrush>) {
         let brush = brush.into();
         self.widget.brush = brush;
-        if !self.ctx.is_disabled() {
-            let brush = self.widget.brush.clone();
-            self.set_text_properties(|layout| layout.set_brush(brush));
-        }
+        let brush = self.widget.layout_brush(self.ctx.is_disabled());
+        self.set_text_properties(|layout| layout.set_brush(brush));
     }
     pub fn set_text_size(&mut self, size: f32) {
         self.set_text_properties(|layout| layout.set_text_size(size));
@@ -152,6 +386,31 @@ impl WidgetMut<'_, Textbox> {
         self.widget.line_break_mode = line_break_mode;
         self.ctx.request_paint();
     }
+
+    /// Set the kind of text the textbox takes.
+    pub fn set_input_hint(&mut self, input_hint: InputHint) {
+        self.widget.input_hint = input_hint;
+        let brush = self.widget.layout_brush(self.ctx.is_disabled());
+        self.set_text_properties(|layout| layout.set_brush(brush));
+        self.ctx.request_layout();
+        self.ctx.request_accessibility_update();
+    }
+
+    /// Set the keyboard shortcuts used to edit the text.
+    ///
+    /// Passing `None` goes back to using the app's [`Keymap`].
+    pub fn set_keymap(&mut self, keymap: Option<Keymap>) {
+        self.widget.editor.set_keymap(keymap);
+    }
+
+    /// Show or hide the caret while the text box is focused.
+    ///
+    /// The caret is shown by default, and blinks at the app's
+    /// [`caret_blink_interval`](crate::InteractionSettings::caret_blink_interval).
+    pub fn set_caret_visible(&mut self, visible: bool) {
+        self.widget.editor.set_caret_visible(visible);
+        self.ctx.request_paint();
+    }
 }
 
 impl Widget for Textbox {
@@ -162,13 +421,34 @@ impl Widget for Textbox {
             window_origin.y + TEXTBOX_PADDING,
         );
         match event {
+            PointerEvent::PointerDown(MouseButton::Right, state) if !ctx.is_disabled() => {
+                let position = Point::new(state.position.x, state.position.y) - inner_origin;
+                let index = self
+                    .editor
+                    .cursor_for_point(position.to_point())
+                    .insert_point;
+                if let Some(range) = self.misspelling_at(index) {
+                    let word = &self.editor.text()[range.clone()];
+                    let suggestions = ctx.spell_checker().suggestions(word);
+                    let selection = Selection::new(range.start, range.end, Affinity::Upstream);
+                    self.editor.set_selection(Some(selection));
+                    ctx.submit_action(Action::SpellingSuggestions(suggestions));
+                    ctx.request_layout();
+                    ctx.request_focus();
+                    ctx.set_handled();
+                }
+            }
             PointerEvent::PointerDown(button, state) => {
                 if !ctx.is_disabled() {
                     // TODO: Start tracking currently pressed link?
-                    let made_change = self.editor.pointer_down(inner_origin, state, *button);
+                    let made_change =
+                        self.editor
+                            .pointer_down(inner_origin, state, *button, ctx.conventions());
                     if made_change {
+                        self.editor.reset_caret_blink();
                         ctx.request_layout();
                         ctx.request_paint();
+                        ctx.request_accessibility_update();
                         ctx.request_focus();
                         ctx.set_active(true);
                     }
@@ -200,20 +480,58 @@ impl Widget for Textbox {
     }
 
     fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        let previous_match = self.editor.current_match();
         let result = self.editor.text_event(ctx, event);
         // If focused on a link and enter pressed, follow it?
         if result.is_handled() {
             ctx.set_handled();
+            if let Some(current) = self.editor.current_match() {
+                if previous_match != Some(current) {
+                    let count = self.editor.find_matches().len();
+                    ctx.submit_action(Action::FindMatchChanged(current, count));
+                }
+            }
+            // Keep the caret shown while typing
+            self.editor.reset_caret_blink();
             // TODO: only some handlers need this repaint
             ctx.request_layout();
             ctx.request_paint();
+            ctx.request_accessibility_update();
         }
     }
 
-    fn on_access_event(&mut self, _ctx: &mut EventCtx, _event: &AccessEvent) {
-        // TODO - Handle accesskit::Action::SetTextSelection
-        // TODO - Handle accesskit::Action::ReplaceSelectedText
-        // TODO - Handle accesskit::Action::SetValue
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        if event.target != ctx.widget_id() || ctx.is_disabled() {
+            return;
+        }
+        match (event.action, &event.data) {
+            (
+                accesskit::Action::SetTextSelection,
+                Some(accesskit::ActionData::SetTextSelection(selection)),
+            ) => {
+                if self.editor.set_access_selection(selection) {
+                    self.editor.reset_preedit();
+                    ctx.request_focus();
+                }
+            }
+            (accesskit::Action::ReplaceSelectedText, Some(accesskit::ActionData::Value(text))) => {
+                self.editor.reset_preedit();
+                self.editor.insert(ctx, text);
+            }
+            (accesskit::Action::SetValue, Some(accesskit::ActionData::Value(text))) => {
+                self.editor.reset_preedit();
+                let len = self.editor.text().as_str().len();
+                self.editor
+                    .set_selection(Some(Selection::new(0, len, Affinity::Downstream)));
+                self.editor.insert(ctx, text);
+            }
+            _ => return,
+        }
+        self.editor.reset_caret_blink();
+        ctx.set_handled();
+        ctx.request_layout();
+        ctx.request_paint();
+        ctx.request_accessibility_update();
     }
 
     #[allow(missing_docs)]
@@ -225,6 +543,8 @@ impl Widget for Textbox {
                 // TODO: Stop focusing on any links
             }
             StatusChange::FocusChanged(true) => {
+                self.editor.reset_caret_blink();
+                ctx.request_anim_frame();
                 // TODO: Focus on first link
             }
             _ => {}
@@ -235,15 +555,23 @@ impl Widget for Textbox {
         match event {
             LifeCycle::DisabledChanged(disabled) => {
                 if self.show_disabled {
-                    if *disabled {
-                        self.editor.set_brush(crate::theme::DISABLED_TEXT_COLOR);
-                    } else {
-                        self.editor.set_brush(self.brush.clone());
-                    }
+                    let brush = self.layout_brush(*disabled);
+                    self.editor.set_brush(brush);
                 }
                 // TODO: Parley seems to require a relayout when colours change
                 ctx.request_layout();
             }
+            LifeCycle::AnimFrame(interval) if ctx.is_focused() => {
+                let blink_interval = ctx.interaction_settings().caret_blink_interval;
+                let elapsed = Duration::from_nanos(*interval);
+                if self.editor.blink_caret(elapsed, blink_interval) {
+                    ctx.request_paint();
+                }
+                // Only keep animating while the caret blinks
+                if !blink_interval.is_zero() {
+                    ctx.request_anim_frame();
+                }
+            }
             LifeCycle::BuildFocusChain => {
                 // TODO: This will always be empty
                 if !self.editor.text().links().is_empty() {
@@ -277,6 +605,21 @@ impl Widget for Textbox {
             width: bc.max().width - 2. * TEXTBOX_MARGIN,
         };
         let size = bc.constrain(label_size);
+        ctx.set_input_hint(Some(self.input_hint));
+        // Masked text isn't checked, as that would show where the words are
+        if self.spellcheck && !self.input_hint.is_masked() {
+            // The word being typed isn't flagged until the caret leaves it
+            let typing_at = self.editor.selection.filter(|s| s.is_caret());
+            self.misspellings = ctx.spell_checker().misspellings(self.editor.text());
+            self.misspellings.retain(|range| {
+                !typing_at
+                    .is_some_and(|caret| range.start <= caret.active && caret.active <= range.end)
+            });
+        } else {
+            self.misspellings.clear();
+        }
+        let padding = Point::new(TEXTBOX_PADDING, TEXTBOX_PADDING).to_vec2();
+        ctx.set_caret_rect(self.editor.caret_rect().map(|rect| rect + padding));
         trace!(
             "Computed layout: max={:?}. w={}, h={}",
             max_advance,
@@ -297,12 +640,21 @@ impl Widget for Textbox {
 
         self.editor
             .draw(scene, Point::new(TEXTBOX_PADDING, TEXTBOX_PADDING));
+        if self.input_hint.is_masked() {
+            self.paint_bullets(scene, ctx.is_disabled());
+        }
+        self.paint_squiggles(scene);
 
         let outline_rect = ctx.size().to_rect().inset(1.0);
+        let outline_color = if self.is_valid() {
+            Color::WHITE
+        } else {
+            crate::theme::INVALID_INPUT_COLOR
+        };
         scene.stroke(
             &Stroke::new(1.0),
             Affine::IDENTITY,
-            Color::WHITE,
+            outline_color,
             None,
             &outline_rect,
         );
@@ -311,15 +663,40 @@ impl Widget for Textbox {
         }
     }
 
+    fn interactivity(&self) -> Interactivity {
+        Interactivity::TextInput
+    }
+
     fn accessibility_role(&self) -> Role {
-        Role::TextInput
+        match self.input_hint {
+            InputHint::Email => Role::EmailInput,
+            InputHint::Number => Role::NumberInput,
+            InputHint::Password => Role::PasswordInput,
+            InputHint::Search => Role::SearchInput,
+            InputHint::Text => Role::TextInput,
+        }
     }
 
-    fn accessibility(&mut self, _ctx: &mut AccessCtx) {
-        // TODO
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        let masked = self.input_hint.is_masked();
+        let node = ctx.current_node();
+        if !self.is_valid() {
+            node.set_invalid(accesskit::Invalid::True);
+        }
+        node.add_action(accesskit::Action::ReplaceSelectedText);
+        node.add_action(accesskit::Action::SetValue);
+        // Masked text is only described by the bullets
+        if masked {
+            node.set_value("\u{2022}".repeat(self.editor.text().as_str().chars().count()));
+            return;
+        }
+        node.set_value(self.editor.text().as_str());
+        node.add_action(accesskit::Action::SetTextSelection);
+        self.editor
+            .accessibility(ctx, Point::new(TEXTBOX_PADDING, TEXTBOX_PADDING));
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         SmallVec::new()
     }
 
@@ -327,3 +704,256 @@ impl Widget for Textbox {
         Some(self.editor.text().as_str().chars().take(100).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conventions::PlatformConventions;
+    use crate::spellcheck::SpellChecker;
+    use crate::testing::TestHarness;
+    use crate::Vec2;
+    use winit::keyboard::ModifiersState;
+
+    struct Cats;
+
+    impl SpellChecker for Cats {
+        fn is_correct(&self, word: &str) -> bool {
+            word != "cta"
+        }
+
+        fn suggestions(&self, _word: &str) -> Vec<String> {
+            vec!["cat".into(), "act".into()]
+        }
+    }
+
+    #[test]
+    fn misspelled_words_can_be_replaced() {
+        let textbox = Textbox::new("The cta sat").with_spellcheck(true);
+        let mut harness = TestHarness::create(textbox);
+        let textbox_id = harness.root_widget().id();
+        assert!(harness
+            .root_widget()
+            .downcast::<Textbox>()
+            .unwrap()
+            .misspellings
+            .is_empty());
+
+        harness.set_spell_checker(Cats);
+        let textbox = harness.get_widget(textbox_id);
+        let textbox_origin = textbox.state().window_origin();
+        let textbox = textbox.downcast::<Textbox>().unwrap();
+        assert_eq!(textbox.misspellings, vec![4..7]);
+        let word = textbox.editor.cursor_line_for_text_position(5);
+        let word_center = textbox_origin + word.p0.midpoint(word.p1).to_vec2();
+
+        // Right-clicking the word selects it and offers the suggestions
+        harness.mouse_move(word_center + Vec2::new(TEXTBOX_PADDING, TEXTBOX_PADDING));
+        harness.mouse_button_press(MouseButton::Right);
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::SpellingSuggestions(vec!["cat".into(), "act".into()]),
+                textbox_id
+            ))
+        );
+        harness.edit_root_widget(|mut root| {
+            let mut textbox = root.downcast::<Textbox>();
+            textbox.replace_selection("cat");
+        });
+        let textbox = harness.get_widget(textbox_id);
+        let textbox = textbox.downcast::<Textbox>().unwrap();
+        assert_eq!(textbox.text(), "The cat sat");
+        assert!(textbox.misspellings.is_empty());
+    }
+
+    #[test]
+    fn edits_are_recorded_in_the_document() {
+        let mut harness = TestHarness::create(Textbox::new("first\nsecond"));
+        harness.edit_root_widget(|mut root| {
+            let mut textbox = root.downcast::<Textbox>();
+            textbox.edit_document(|document| {
+                let second = document.line_range(1);
+                document.replace(second, "2nd\nthird");
+            });
+            textbox.replace_text("first\n2nd\nthird".into());
+        });
+
+        let root = harness.root_widget();
+        let textbox = root.downcast::<Textbox>().unwrap();
+        let document = textbox.document();
+        assert_eq!(document.line(2), "third");
+        // Replacing the text with the same text isn't an edit
+        assert_eq!(document.revision(), 1);
+        let change = document.changes_since(0).unwrap().next().unwrap();
+        assert_eq!((change.range.clone(), change.inserted_len), (6..12, 9));
+    }
+
+    #[test]
+    fn screen_readers_can_select_and_replace_text() {
+        let mut harness = TestHarness::create(Textbox::new("one two"));
+        let textbox_id = harness.root_widget().id();
+        let tree = harness.access_tree();
+        let node = |id: accesskit::NodeId| &tree.nodes.iter().find(|(n, _)| *n == id).unwrap().1;
+        let textbox = node(textbox_id.into());
+        assert_eq!(textbox.value(), Some("one two"));
+        let [line_id] = textbox.children() else {
+            panic!("Expected one line, got {:?}", textbox.children());
+        };
+        let line = node(*line_id);
+        assert_eq!(line.role(), Role::InlineTextBox);
+        assert_eq!(line.character_lengths().len(), 7);
+        assert_eq!(line.word_lengths(), [4, 3]);
+
+        let position = |character_index| accesskit::TextPosition {
+            node: *line_id,
+            character_index,
+        };
+        harness.process_access_event(accesskit::ActionRequest {
+            action: accesskit::Action::SetTextSelection,
+            target: textbox_id.into(),
+            data: Some(accesskit::ActionData::SetTextSelection(
+                accesskit::TextSelection {
+                    anchor: position(4),
+                    focus: position(7),
+                },
+            )),
+        });
+        let root = harness.root_widget();
+        let selection = root
+            .downcast::<Textbox>()
+            .unwrap()
+            .editor
+            .selection
+            .unwrap();
+        assert_eq!(selection.range(), 4..7);
+
+        harness.process_access_event(accesskit::ActionRequest {
+            action: accesskit::Action::ReplaceSelectedText,
+            target: textbox_id.into(),
+            data: Some(accesskit::ActionData::Value("2".into())),
+        });
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("one 2".into()), textbox_id))
+        );
+        let tree = harness.access_tree();
+        let textbox = &tree
+            .nodes
+            .iter()
+            .find(|(n, _)| *n == textbox_id.into())
+            .unwrap()
+            .1;
+        let selection = textbox.text_selection().unwrap();
+        assert_eq!(
+            selection.focus,
+            accesskit::TextPosition {
+                node: *line_id,
+                character_index: 5
+            }
+        );
+    }
+
+    #[test]
+    fn typing_goes_to_every_caret() {
+        let mut harness = TestHarness::create(Textbox::new("ab ab"));
+        let textbox = harness.root_widget();
+        let origin = textbox.state().window_origin() + Vec2::new(TEXTBOX_PADDING, TEXTBOX_PADDING);
+        let textbox = textbox.downcast::<Textbox>().unwrap();
+        let caret_at = |index| {
+            origin
+                + textbox
+                    .editor
+                    .cursor_line_for_text_position(index)
+                    .p0
+                    .to_vec2()
+        };
+        let (first, second) = (caret_at(1), caret_at(4));
+
+        harness.mouse_move(first);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        harness.set_modifiers(PlatformConventions::default().command_modifier);
+        harness.mouse_move(second);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        harness.set_modifiers(ModifiersState::empty());
+
+        harness.keyboard_type_chars("xy");
+        let root = harness.root_widget();
+        let textbox = root.downcast::<Textbox>().unwrap();
+        assert_eq!(textbox.text(), "axyb axyb");
+        let carets: Vec<_> = textbox.editor.selections().map(|s| s.active).collect();
+        assert_eq!(carets, [8, 3]);
+
+        harness.edit_root_widget(|mut root| {
+            let textbox = root.downcast::<Textbox>();
+            assert!(textbox.widget.editor.collapse_selections());
+        });
+        harness.keyboard_type_chars("z");
+        let root = harness.root_widget();
+        assert_eq!(root.downcast::<Textbox>().unwrap().text(), "axyb axyzb");
+    }
+
+    #[test]
+    fn set_value_replaces_the_text() {
+        let mut harness = TestHarness::create(Textbox::new("draft"));
+        let textbox_id = harness.root_widget().id();
+        harness.process_access_event(accesskit::ActionRequest {
+            action: accesskit::Action::SetValue,
+            target: textbox_id.into(),
+            data: Some(accesskit::ActionData::Value("final".into())),
+        });
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TextChanged("final".into()), textbox_id))
+        );
+        let root = harness.root_widget();
+        let textbox = root.downcast::<Textbox>().unwrap();
+        assert_eq!(textbox.text(), "final");
+        assert_eq!(
+            textbox.editor.selection.unwrap(),
+            Selection::caret(5, Affinity::Downstream)
+        );
+    }
+
+    #[test]
+    fn input_hints_mask_and_validate_the_text() {
+        let textbox = Textbox::new("hunter2").with_input_hint(InputHint::Password);
+        let mut harness = TestHarness::create(textbox);
+        let textbox_id = harness.root_widget().id();
+        let tree = harness.access_tree();
+        let (_, node) = tree
+            .nodes
+            .iter()
+            .find(|(n, _)| *n == textbox_id.into())
+            .unwrap();
+        assert_eq!(node.role(), Role::PasswordInput);
+        assert_eq!(node.value(), Some("\u{2022}".repeat(7).as_str()));
+        assert!(node.children().is_empty());
+
+        // The hint of the focused textbox is forwarded to the platform
+        assert_eq!(harness.input_hint(), None);
+        let center = harness.root_widget().state().window_layout_rect().center();
+        harness.mouse_move(center);
+        harness.mouse_button_press(MouseButton::Left);
+        harness.mouse_button_release(MouseButton::Left);
+        assert_eq!(harness.input_hint(), Some(InputHint::Password));
+
+        harness.edit_root_widget(|mut root| {
+            let mut textbox = root.downcast::<Textbox>();
+            textbox.set_input_hint(InputHint::Email);
+            textbox.reset_text("someone@example".into());
+        });
+        assert_eq!(harness.input_hint(), Some(InputHint::Email));
+        let tree = harness.access_tree();
+        let (_, node) = tree
+            .nodes
+            .iter()
+            .find(|(n, _)| *n == textbox_id.into())
+            .unwrap();
+        assert_eq!(node.value(), Some("someone@example"));
+        assert_eq!(node.invalid(), Some(accesskit::Invalid::True));
+        assert!(InputHint::Email.is_valid("someone@example.com"));
+        assert!(!InputHint::Number.is_valid("1.5x"));
+    }
+}
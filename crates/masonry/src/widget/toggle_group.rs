@@ -0,0 +1,445 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A group of toggle buttons, such as the bold, italic and underline buttons of a
+//! toolbar.
+
+use accesskit::{DefaultActionVerb, Role, Toggled};
+use kurbo::{Insets, Point, Size};
+use smallvec::{smallvec, SmallVec};
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::event::MouseButton;
+
+use crate::action::Action;
+use crate::paint_scene_helpers::{fill_lin_gradient, stroke, UnitPoint};
+use crate::widget::{Label, WidgetMut, WidgetPod, WidgetRef};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
+/// The space between the buttons of a group.
+const BUTTON_SPACING: f64 = 2.0;
+
+/// Which buttons of a [`ToggleGroup`] can be pressed together.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToggleMode {
+    /// Any of the buttons can be pressed, such as for bold, italic and underline.
+    #[default]
+    Multiple,
+    /// Pressing a button releases the others, such as for the alignment of text.
+    /// Pressing the button which is already pressed keeps it pressed.
+    Exclusive,
+}
+
+/// A row of toggle buttons, each of which is pressed or released.
+///
+/// Clicking a button toggles it, or in [`ToggleMode::Exclusive`] presses it and
+/// releases the others. Pressed buttons are painted with
+/// [`theme::TOGGLE_PRESSED_LIGHT`] and [`theme::TOGGLE_PRESSED_DARK`].
+///
+/// Emits [`Action::TogglesChanged`] with whether each button is pressed, when the
+/// user changes them.
+pub struct ToggleGroup {
+    buttons: Vec<WidgetPod<ToggleButton>>,
+    mode: ToggleMode,
+    /// The button which the pointer was pressed on
+    held: Option<usize>,
+}
+
+/// One button of a [`ToggleGroup`].
+struct ToggleButton {
+    label: WidgetPod<Label>,
+    pressed: bool,
+    /// Whether the pointer is held down on the button
+    held: bool,
+}
+
+impl ToggleGroup {
+    /// Create a new group with a released button for each text.
+    pub fn new(buttons: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        ToggleGroup {
+            buttons: buttons.into_iter().map(ToggleButton::pod).collect(),
+            mode: ToggleMode::default(),
+            held: None,
+        }
+    }
+
+    /// Builder-style method to set which buttons are pressed, in order.
+    ///
+    /// Buttons past the end of `pressed` are released.
+    pub fn with_pressed(mut self, pressed: impl IntoIterator<Item = bool>) -> Self {
+        let mut pressed = pressed.into_iter();
+        for button in &mut self.buttons {
+            button.created_widget_mut().pressed = pressed.next().unwrap_or(false);
+        }
+        self
+    }
+
+    /// Builder-style method to set which buttons can be pressed together.
+    pub fn with_mode(mut self, mode: ToggleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Whether each button is pressed, in order.
+    fn pressed_buttons(&self, ctx: &EventCtx) -> Vec<bool> {
+        (self.buttons.iter())
+            .map(|button| ctx.get_ref(button).pressed)
+            .collect()
+    }
+
+    /// Toggle button `ix` as the user would, and submit the new state.
+    fn toggle(&mut self, ctx: &mut EventCtx, ix: usize) {
+        let before = self.pressed_buttons(ctx);
+        let mode = self.mode;
+        for (button_ix, button) in self.buttons.iter_mut().enumerate() {
+            let pressed = match mode {
+                ToggleMode::Multiple if button_ix == ix => !before[ix],
+                ToggleMode::Multiple => before[button_ix],
+                ToggleMode::Exclusive => button_ix == ix,
+            };
+            set_button_state(ctx.get_mut(button), pressed, false);
+        }
+        let after = self.pressed_buttons(ctx);
+        if after != before {
+            trace!("ToggleGroup {:?} toggled {}", ctx.widget_id(), ix);
+            ctx.submit_action(Action::TogglesChanged(after));
+        }
+        ctx.request_paint();
+        ctx.request_accessibility_update();
+    }
+
+    fn hot_button(&self, ctx: &EventCtx) -> Option<usize> {
+        (self.buttons.iter()).position(|button| ctx.get_ref(button).state().is_hot)
+    }
+}
+
+impl<'w> WidgetRef<'w, ToggleGroup> {
+    /// Whether each button is pressed, in order.
+    pub fn pressed(&self) -> Vec<bool> {
+        (self.deref().buttons.iter())
+            .map(|button| self.get_child(button).pressed)
+            .collect()
+    }
+}
+
+/// Set whether `button` is pressed and held, repainting it if either changed.
+///
+/// The caller must also request a paint and an accessibility update of the group,
+/// for the button to be repainted and updated.
+fn set_button_state(mut button: WidgetMut<'_, ToggleButton>, pressed: bool, held: bool) {
+    if button.widget.pressed != pressed || button.widget.held != held {
+        button.widget.pressed = pressed;
+        button.widget.held = held;
+        button.ctx.request_paint();
+        button.ctx.request_accessibility_update();
+    }
+}
+
+impl ToggleButton {
+    fn pod(text: impl Into<ArcStr>) -> WidgetPod<ToggleButton> {
+        WidgetPod::new(ToggleButton {
+            label: WidgetPod::new(Label::new(text)),
+            pressed: false,
+            held: false,
+        })
+    }
+}
+
+impl WidgetMut<'_, ToggleGroup> {
+    /// Set which buttons are pressed, in order, without submitting an action.
+    ///
+    /// Buttons past the end of `pressed` are released.
+    pub fn set_pressed(&mut self, pressed: impl IntoIterator<Item = bool>) {
+        let mut pressed = pressed.into_iter();
+        for button in &mut self.widget.buttons {
+            let mut button = self.ctx.get_mut(button);
+            let pressed = pressed.next().unwrap_or(false);
+            if button.widget.pressed != pressed {
+                button.widget.pressed = pressed;
+                button.ctx.request_paint();
+                button.ctx.request_accessibility_update();
+            }
+        }
+    }
+
+    /// Set which buttons can be pressed together.
+    ///
+    /// This doesn't change which buttons are pressed.
+    pub fn set_mode(&mut self, mode: ToggleMode) {
+        self.widget.mode = mode;
+    }
+
+    /// Set the text of button `ix`.
+    pub fn set_button_text(&mut self, ix: usize, text: impl Into<ArcStr>) {
+        let mut button = self.ctx.get_mut(&mut self.widget.buttons[ix]);
+        button
+            .ctx
+            .get_mut(&mut button.widget.label)
+            .set_text(text.into());
+    }
+
+    /// Add a released button after the others.
+    pub fn add_button(&mut self, text: impl Into<ArcStr>) {
+        self.widget.buttons.push(ToggleButton::pod(text));
+        self.ctx.children_changed();
+    }
+
+    /// Remove button `ix`.
+    pub fn remove_button(&mut self, ix: usize) {
+        let button = self.widget.buttons.remove(ix);
+        self.ctx.remove_child(button);
+        self.widget.held = None;
+        self.ctx.request_layout();
+    }
+}
+
+impl Widget for ToggleGroup {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for button in &mut self.buttons {
+            button.on_pointer_event(ctx, event);
+        }
+        match event {
+            PointerEvent::PointerDown(MouseButton::Left, _) if !ctx.is_disabled() => {
+                self.held = self.hot_button(ctx);
+                if let Some(ix) = self.held {
+                    ctx.set_active(true);
+                    let button = ctx.get_mut(&mut self.buttons[ix]);
+                    let pressed = button.widget.pressed;
+                    set_button_state(button, pressed, true);
+                    ctx.request_paint();
+                }
+            }
+            PointerEvent::PointerUp(_, _) => {
+                if let Some(ix) = self.held.take() {
+                    let button = ctx.get_mut(&mut self.buttons[ix]);
+                    let pressed = button.widget.pressed;
+                    set_button_state(button, pressed, false);
+                    if self.hot_button(ctx) == Some(ix) && !ctx.is_disabled() {
+                        self.toggle(ctx, ix);
+                    }
+                    ctx.request_paint();
+                }
+                ctx.set_active(false);
+            }
+            _ => (),
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for button in &mut self.buttons {
+            button.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        let target = self
+            .buttons
+            .iter()
+            .position(|button| button.id() == event.target);
+        if let Some(ix) = target {
+            if event.action == accesskit::Action::Default && !ctx.is_disabled() {
+                self.toggle(ctx, ix);
+            }
+        }
+        for button in &mut self.buttons {
+            button.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for button in &mut self.buttons {
+            button.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let button_bc = bc.loosen();
+        let mut x = 0.0;
+        let mut height: f64 = 0.0;
+        for (ix, button) in self.buttons.iter_mut().enumerate() {
+            if ix > 0 {
+                x += BUTTON_SPACING;
+            }
+            let size = button.layout(ctx, &button_bc);
+            ctx.place_child(button, Point::new(x, 0.0));
+            x += size.width;
+            height = height.max(size.height);
+        }
+        bc.constrain(Size::new(x, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        for button in &mut self.buttons {
+            button.paint(ctx, scene);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for button in &mut self.buttons {
+            button.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.buttons.iter().map(|button| button.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ToggleGroup")
+    }
+}
+
+impl Widget for ToggleButton {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.label.on_pointer_event(ctx, event);
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.label.on_text_event(ctx, event);
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.label.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, ctx: &mut LifeCycleCtx, _event: &StatusChange) {
+        ctx.request_paint();
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.label.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
+        let label_size = self.label.layout(ctx, &bc.shrink(padding).loosen());
+        let size = bc.constrain(Size::new(
+            label_size.width + padding.width,
+            (label_size.height + padding.height).max(theme::BORDERED_WIDGET_HEIGHT),
+        ));
+        let label_offset = (size.to_vec2() - label_size.to_vec2()) / 2.0;
+        ctx.place_child(&mut self.label, label_offset.to_point());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let size = ctx.size();
+        let (border_rect, stroke_width) = ctx.snap_stroke(
+            size.to_rect().inset(-theme::BUTTON_BORDER_WIDTH / 2.0),
+            theme::BUTTON_BORDER_WIDTH,
+        );
+        let rounded_rect = border_rect.to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+
+        let bg_gradient = if ctx.is_disabled() {
+            [theme::DISABLED_BUTTON_LIGHT, theme::DISABLED_BUTTON_DARK]
+        } else if self.held {
+            [theme::BUTTON_DARK, theme::BUTTON_LIGHT]
+        } else if self.pressed {
+            [theme::TOGGLE_PRESSED_LIGHT, theme::TOGGLE_PRESSED_DARK]
+        } else {
+            [theme::BUTTON_LIGHT, theme::BUTTON_DARK]
+        };
+        let border_color = if ctx.is_hot() && !ctx.is_disabled() {
+            theme::BORDER_LIGHT
+        } else {
+            theme::BORDER_DARK
+        };
+
+        stroke(scene, &rounded_rect, border_color, stroke_width);
+        fill_lin_gradient(
+            scene,
+            &rounded_rect,
+            bg_gradient,
+            UnitPoint::TOP,
+            UnitPoint::BOTTOM,
+        );
+
+        self.label.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Button
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        // A button with a toggled state is a toggle button
+        let toggled = if self.pressed {
+            Toggled::True
+        } else {
+            Toggled::False
+        };
+        ctx.current_node().set_toggled(toggled);
+        ctx.current_node()
+            .set_default_action_verb(DefaultActionVerb::Click);
+        self.label.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        smallvec![self.label.id()]
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("ToggleButton")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    #[test]
+    fn multiple_and_exclusive_modes() {
+        let group = ToggleGroup::new(["B", "I", "U"]).with_pressed([false, true]);
+        let mut harness = TestHarness::create(group);
+        let group_id = harness.root_widget().id();
+        let button_ids: Vec<_> = harness
+            .root_widget()
+            .children()
+            .iter()
+            .map(|button| button.id())
+            .collect();
+
+        harness.mouse_click_on(button_ids[0]);
+        harness.mouse_click_on(button_ids[1]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TogglesChanged(vec![true, true, false]), group_id))
+        );
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TogglesChanged(vec![true, false, false]), group_id))
+        );
+
+        harness.edit_root_widget(|mut group| {
+            let mut group = group.downcast::<ToggleGroup>();
+            group.set_mode(ToggleMode::Exclusive);
+        });
+        harness.mouse_click_on(button_ids[2]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::TogglesChanged(vec![false, false, true]), group_id))
+        );
+        // The pressed button stays pressed
+        harness.mouse_click_on(button_ids[2]);
+        assert_eq!(harness.pop_action(), None);
+
+        let tree = harness.access_tree();
+        let (_, node) = tree
+            .nodes
+            .iter()
+            .find(|(id, _)| *id == button_ids[2].into())
+            .unwrap();
+        assert_eq!(node.toggled(), Some(Toggled::True));
+    }
+}
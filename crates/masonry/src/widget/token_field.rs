@@ -0,0 +1,392 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A text input which turns what the user types into a list of tokens.
+
+use accesskit::Role;
+use kurbo::{Point, Size};
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::action::Action;
+use crate::paint_scene_helpers::{fill_color, stroke};
+use crate::text2::TextStorage;
+use crate::widget::{Label, Textbox, WidgetMut};
+use crate::{
+    theme, AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, PointerEvent, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+
+/// The space between the text of a token and its outline.
+const TOKEN_PADDING: f64 = 4.0;
+/// The space between tokens.
+const TOKEN_SPACING: f64 = 4.0;
+
+/// A [`Textbox`] which turns what the user types into removable tokens, as used
+/// for email recipients or search filters.
+///
+/// The text becomes a token when the user presses <kbd>Enter</kbd> or types a
+/// comma. <kbd>Backspace</kbd> in the empty text box removes the last token.
+/// Tokens can be selected with <kbd>←</kbd> and <kbd>→</kbd> and removed with
+/// <kbd>Backspace</kbd> or <kbd>Delete</kbd>, or removed by clicking them.
+///
+/// Every change to the tokens emits [`Action::TokensChanged`].
+pub struct TokenField {
+    tokens: Vec<Token>,
+    textbox: WidgetPod<Textbox>,
+    /// The token selected with the keyboard
+    selected: Option<usize>,
+}
+
+/// A token, and the label showing it.
+struct Token {
+    text: ArcStr,
+    label: WidgetPod<Label>,
+}
+
+impl TokenField {
+    /// Create a new `TokenField` with the given tokens.
+    pub fn new(tokens: impl IntoIterator<Item = impl Into<ArcStr>>) -> Self {
+        Self::from_pod(WidgetPod::new(Textbox::new("")), tokens)
+    }
+
+    /// Create a new `TokenField` with the given tokens, using an existing text box.
+    pub fn from_pod(
+        textbox: WidgetPod<Textbox>,
+        tokens: impl IntoIterator<Item = impl Into<ArcStr>>,
+    ) -> Self {
+        TokenField {
+            tokens: tokens.into_iter().map(Token::new).collect(),
+            textbox,
+            selected: None,
+        }
+    }
+
+    /// The text of each token.
+    pub fn tokens(&self) -> impl Iterator<Item = &str> {
+        self.tokens.iter().map(|token| token.text.as_str())
+    }
+
+    /// The index of the token selected with the keyboard, if any.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    fn submit_tokens(&self, ctx: &mut EventCtx) {
+        let tokens = self.tokens().map(str::to_string).collect();
+        ctx.submit_action(Action::TokensChanged(tokens));
+    }
+
+    fn remove_token(&mut self, ctx: &mut EventCtx, ix: usize) {
+        trace!("TokenField {:?} removed token {}", ctx.widget_id(), ix);
+        let token = self.tokens.remove(ix);
+        ctx.remove_child(token.label);
+        self.selected = None;
+        self.submit_tokens(ctx);
+    }
+
+    /// Turn the finished parts of the text into tokens.
+    ///
+    /// If `all` is false, only the text before the last comma is finished.
+    fn take_tokens(&mut self, ctx: &mut EventCtx, all: bool) {
+        let text = ctx.get_ref(&self.textbox).text().to_string();
+        let (finished, rest) = match text.rfind(',') {
+            _ if all => (text.as_str(), ""),
+            Some(ix) => (&text[..ix], &text[ix + 1..]),
+            None => return,
+        };
+        let new_tokens: Vec<_> = finished
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(Token::new)
+            .collect();
+        let rest = rest.trim_start().to_string();
+
+        ctx.get_mut(&mut self.textbox).replace_text(rest);
+        if !new_tokens.is_empty() {
+            self.tokens.extend(new_tokens);
+            ctx.children_changed();
+            self.submit_tokens(ctx);
+        }
+    }
+
+    /// Handle a key which edits the tokens rather than the text, returning whether it was used.
+    fn on_key(&mut self, ctx: &mut EventCtx, key: &Key) -> bool {
+        let text_is_empty = ctx.get_ref(&self.textbox).text().is_empty();
+        let last = self.tokens.len().checked_sub(1);
+        match (key, self.selected) {
+            (Key::Named(NamedKey::Enter), _) if !text_is_empty => {
+                self.take_tokens(ctx, true);
+                true
+            }
+            (Key::Named(NamedKey::Backspace | NamedKey::Delete), Some(ix)) => {
+                self.remove_token(ctx, ix);
+                true
+            }
+            (Key::Named(NamedKey::Backspace), None) if text_is_empty => match last {
+                Some(ix) => {
+                    self.remove_token(ctx, ix);
+                    true
+                }
+                None => false,
+            },
+            (Key::Named(NamedKey::ArrowLeft), Some(ix)) => {
+                self.selected = Some(ix.saturating_sub(1));
+                true
+            }
+            (Key::Named(NamedKey::ArrowLeft), None) if text_is_empty && last.is_some() => {
+                self.selected = last;
+                true
+            }
+            (Key::Named(NamedKey::ArrowRight), Some(ix)) => {
+                self.selected = Some(ix + 1).filter(|&next| next < self.tokens.len());
+                true
+            }
+            (Key::Named(NamedKey::Escape), Some(_)) => {
+                self.selected = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Token {
+    fn new(text: impl Into<ArcStr>) -> Self {
+        let text = text.into();
+        Token {
+            label: WidgetPod::new(Label::new(text.clone())),
+            text,
+        }
+    }
+}
+
+impl WidgetMut<'_, TokenField> {
+    /// Replace the tokens.
+    pub fn set_tokens(&mut self, tokens: impl IntoIterator<Item = impl Into<ArcStr>>) {
+        for token in std::mem::take(&mut self.widget.tokens) {
+            self.ctx.remove_child(token.label);
+        }
+        self.widget.tokens = tokens.into_iter().map(Token::new).collect();
+        self.widget.selected = None;
+        self.ctx.children_changed();
+    }
+
+    pub fn textbox_mut(&mut self) -> WidgetMut<'_, Textbox> {
+        self.ctx.get_mut(&mut self.widget.textbox)
+    }
+}
+
+impl Widget for TokenField {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for token in &mut self.tokens {
+            token.label.on_pointer_event(ctx, event);
+        }
+        self.textbox.on_pointer_event(ctx, event);
+
+        if let PointerEvent::PointerDown(_, _) = event {
+            let hot_token =
+                (self.tokens.iter()).position(|token| ctx.get_ref(&token.label).state().is_hot);
+            if let Some(ix) = hot_token {
+                self.remove_token(ctx, ix);
+                ctx.set_handled();
+            }
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        if let TextEvent::KeyboardKey(key, mods) = event {
+            if key.state.is_pressed() && mods.is_empty() && self.on_key(ctx, &key.logical_key) {
+                for token in &mut self.tokens {
+                    ctx.skip_child(&mut token.label);
+                }
+                ctx.skip_child(&mut self.textbox);
+                ctx.set_handled();
+                ctx.request_paint();
+                ctx.request_accessibility_update();
+                return;
+            }
+        }
+
+        for token in &mut self.tokens {
+            token.label.on_text_event(ctx, event);
+        }
+        let previous_text = ctx.get_ref(&self.textbox).text().to_string();
+        self.textbox.on_text_event(ctx, event);
+        if ctx.get_ref(&self.textbox).text() != previous_text {
+            if self.selected.take().is_some() {
+                ctx.request_paint();
+            }
+            self.take_tokens(ctx, false);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for token in &mut self.tokens {
+            token.label.on_access_event(ctx, event);
+        }
+        self.textbox.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for token in &mut self.tokens {
+            token.label.lifecycle(ctx, event);
+        }
+        self.textbox.lifecycle(ctx, event);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let max_width = if bc.max().width.is_finite() {
+            bc.max().width
+        } else {
+            // Put everything on one line
+            f64::INFINITY
+        };
+
+        // Lay out the tokens in rows, wrapping when they don't fit
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut row_height: f64 = 0.0;
+        let token_bc = BoxConstraints::new(
+            Size::ZERO,
+            Size::new(max_width - 2. * TOKEN_PADDING, f64::INFINITY),
+        );
+        for token in &mut self.tokens {
+            let size = token.label.layout(ctx, &token_bc);
+            let outer_width = size.width + 2. * TOKEN_PADDING;
+            if x > 0.0 && x + outer_width > max_width {
+                x = 0.0;
+                y += row_height + TOKEN_SPACING;
+                row_height = 0.0;
+            }
+            ctx.place_child(
+                &mut token.label,
+                Point::new(x + TOKEN_PADDING, y + TOKEN_PADDING),
+            );
+            x += outer_width + TOKEN_SPACING;
+            row_height = row_height.max(size.height + 2. * TOKEN_PADDING);
+        }
+
+        // The text box takes the rest of the last row, or a row of its own
+        if max_width - x < theme::WIDE_WIDGET_WIDTH {
+            x = 0.0;
+            y += row_height + TOKEN_SPACING;
+            row_height = 0.0;
+        }
+        let textbox_bc = BoxConstraints::new(Size::ZERO, Size::new(max_width - x, bc.max().height));
+        let textbox_size = self.textbox.layout(ctx, &textbox_bc);
+        ctx.place_child(&mut self.textbox, Point::new(x, y));
+        row_height = row_height.max(textbox_size.height);
+
+        let width = if max_width.is_finite() {
+            max_width
+        } else {
+            x + textbox_size.width
+        };
+        let size = bc.constrain(Size::new(width, y + row_height));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        for (ix, token) in self.tokens.iter_mut().enumerate() {
+            let rect = ctx
+                .child_layout_rect(&token.label)
+                .inset(TOKEN_PADDING)
+                .to_rounded_rect(theme::BUTTON_BORDER_RADIUS);
+            let (background, border) = if self.selected == Some(ix) {
+                (theme::SELECTED_TEXT_BACKGROUND_COLOR, theme::PRIMARY_LIGHT)
+            } else {
+                (theme::BACKGROUND_LIGHT, theme::BORDER_LIGHT)
+            };
+            fill_color(scene, &rect, background);
+            stroke(scene, &rect, border, 1.0);
+            token.label.paint(ctx, scene);
+        }
+        self.textbox.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Group
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        if let Some(ix) = self.selected {
+            ctx.current_node()
+                .set_active_descendant(self.tokens[ix].label.id().into());
+        }
+        for token in &mut self.tokens {
+            token.label.accessibility(ctx);
+        }
+        self.textbox.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut children: SmallVec<_> = self.tokens.iter().map(|token| token.label.id()).collect();
+        children.push(self.textbox.id());
+        children
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("TokenField")
+    }
+
+    fn get_debug_text(&self) -> Option<String> {
+        Some(self.tokens().collect::<Vec<_>>().join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, TestHarness};
+    use crate::widget::SizedBox;
+
+    #[test]
+    fn commas_create_tokens() {
+        let [field_id, textbox_id] = widget_ids();
+        let widget = TokenField::from_pod(
+            WidgetPod::new_with_id(Textbox::new(""), textbox_id),
+            ["first"],
+        );
+        let mut harness = TestHarness::create(SizedBox::new_with_id(widget, field_id));
+        let tokens = |harness: &TestHarness| -> Vec<String> {
+            let field = harness.get_widget(field_id);
+            let field = field.downcast::<TokenField>().unwrap();
+            field.tokens().map(str::to_string).collect()
+        };
+
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("a, b,c");
+        assert_eq!(tokens(&harness), ["first", "a", "b"]);
+        let textbox = harness.get_widget(textbox_id);
+        assert_eq!(textbox.downcast::<Textbox>().unwrap().text(), "c");
+        let field_actions: Vec<_> = std::iter::from_fn(|| harness.pop_action())
+            .filter(|(_, id)| *id == field_id)
+            .map(|(action, _)| action)
+            .collect();
+        assert_eq!(
+            field_actions,
+            [
+                Action::TokensChanged(vec!["first".into(), "a".into()]),
+                Action::TokensChanged(vec!["first".into(), "a".into(), "b".into()]),
+            ]
+        );
+
+        let first = harness.get_widget(field_id).children()[0].id();
+        harness.mouse_click_on(first);
+        assert_eq!(tokens(&harness), ["a", "b"]);
+        assert_eq!(
+            harness.pop_action(),
+            Some((
+                Action::TokensChanged(vec!["a".into(), "b".into()]),
+                field_id
+            ))
+        );
+    }
+}
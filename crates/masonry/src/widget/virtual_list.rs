@@ -0,0 +1,255 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A list which only has children for the items which are scrolled into view.
+
+use std::ops::Range;
+
+use accesskit::Role;
+use smallvec::SmallVec;
+use tracing::{trace, trace_span, Span};
+use vello::Scene;
+
+use crate::widget::{WidgetMut, WidgetPod};
+use crate::{
+    AccessCtx, AccessEvent, Action, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, PointerEvent, Rect, Size, StatusChange, TextEvent, Widget, WidgetId,
+};
+
+/// How many items above and below the viewport also get children, so that they
+/// are ready before they are scrolled into view.
+const OVERSCAN_ITEMS: usize = 2;
+
+/// A column of items of the same height, such as the rows of a long list, which
+/// only has children for the items which are shown.
+///
+/// The list should be the child of a [`Portal`](crate::widget::Portal), which
+/// scrolls it. The list is as tall as all of its items, but its children are
+/// only the items from [`first_item`](WidgetMut::first_item) on. When the
+/// items which are shown change, it submits [`Action::VisibleItemsChanged`] with
+/// the items it needs children for, and its owner should then replace its
+/// children with those items; the children can be reused for the new items.
+///
+/// Without a portal above it, the list needs children for all of its items.
+pub struct VirtualList {
+    item_count: usize,
+    item_height: f64,
+    /// The index of the item shown by the first child
+    first_item: usize,
+    children: Vec<WidgetPod<Box<dyn Widget>>>,
+    /// The items last asked for with an action, to only submit it when they change
+    requested: Option<Range<usize>>,
+}
+
+impl VirtualList {
+    /// Create a list of `item_count` items, each `item_height` tall, without
+    /// children.
+    pub fn new(item_count: usize, item_height: f64) -> Self {
+        VirtualList {
+            item_count,
+            item_height: item_height.max(0.0),
+            first_item: 0,
+            children: Vec::new(),
+            requested: None,
+        }
+    }
+
+    /// Builder-style method to add a child for the next item.
+    pub fn with_child_pod(mut self, child: WidgetPod<Box<dyn Widget>>) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// The items which are shown in `viewport`, and the ones around them.
+    fn visible_items(&self, viewport: Option<Rect>) -> Range<usize> {
+        let Some(viewport) = viewport.filter(|_| self.item_height > 0.0) else {
+            return 0..self.item_count;
+        };
+        let item = |y: f64| (y / self.item_height).max(0.0);
+        let start = (item(viewport.min_y()).floor() as usize).saturating_sub(OVERSCAN_ITEMS);
+        let end = (item(viewport.max_y()).ceil() as usize).saturating_add(OVERSCAN_ITEMS);
+        start.min(self.item_count)..end.min(self.item_count)
+    }
+
+    /// The items which the children are for.
+    fn child_items(&self) -> Range<usize> {
+        self.first_item..self.first_item + self.children.len()
+    }
+}
+
+impl WidgetMut<'_, VirtualList> {
+    pub fn set_item_count(&mut self, item_count: usize) {
+        self.widget.item_count = item_count;
+        self.ctx.request_layout();
+    }
+
+    pub fn set_item_height(&mut self, item_height: f64) {
+        self.widget.item_height = item_height.max(0.0);
+        self.ctx.request_layout();
+    }
+
+    /// The index of the item shown by the first child.
+    pub fn first_item(&self) -> usize {
+        self.widget.first_item
+    }
+
+    /// Set the item shown by the first child, with the next children showing the
+    /// items after it.
+    pub fn set_first_item(&mut self, first_item: usize) {
+        self.widget.first_item = first_item;
+        self.ctx.request_layout();
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.widget.children.len()
+    }
+
+    pub fn insert_child_pod(&mut self, idx: usize, child: WidgetPod<Box<dyn Widget>>) {
+        self.widget.children.insert(idx, child);
+        self.ctx.children_changed();
+    }
+
+    pub fn remove_child(&mut self, idx: usize) {
+        let child = self.widget.children.remove(idx);
+        self.ctx.remove_child(child);
+        self.ctx.request_layout();
+    }
+
+    /// Move the child at index `from` to index `to`, shifting the children in
+    /// between, and keeping the widget and its state alive.
+    pub fn move_child(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+        let child = self.widget.children.remove(from);
+        self.widget.children.insert(to, child);
+        self.ctx.children_changed();
+    }
+
+    // FIXME - Remove Box
+    pub fn child_mut(&mut self, idx: usize) -> Option<WidgetMut<'_, Box<dyn Widget>>> {
+        let child = self.widget.children.get_mut(idx)?;
+        Some(self.ctx.get_mut(child))
+    }
+}
+
+impl Widget for VirtualList {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        for child in &mut self.children {
+            child.on_pointer_event(ctx, event);
+        }
+    }
+
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        for child in &mut self.children {
+            child.on_text_event(ctx, event);
+        }
+    }
+
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        for child in &mut self.children {
+            child.on_access_event(ctx, event);
+        }
+    }
+
+    fn on_status_change(&mut self, _ctx: &mut LifeCycleCtx, _event: &StatusChange) {}
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        for child in &mut self.children {
+            child.lifecycle(ctx, event);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let visible = self.visible_items(ctx.scroll_viewport());
+        if visible != self.child_items() && self.requested.as_ref() != Some(&visible) {
+            ctx.submit_action(Action::VisibleItemsChanged(visible.clone()));
+        }
+        self.requested = Some(visible);
+
+        // The items fill the width of the list, if it is bounded
+        let max_width = bc.max().width;
+        let min_width = if max_width.is_finite() {
+            max_width
+        } else {
+            0.0
+        };
+        let child_bc = BoxConstraints::new(
+            Size::new(min_width, self.item_height),
+            Size::new(max_width, self.item_height),
+        );
+        let mut width: f64 = 0.0;
+        for (item, child) in (self.first_item..).zip(&mut self.children) {
+            let size = child.layout(ctx, &child_bc);
+            width = width.max(size.width);
+            ctx.place_child(child, Point::new(0.0, item as f64 * self.item_height));
+        }
+
+        let size = bc.constrain(Size::new(width, self.item_count as f64 * self.item_height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        ctx.paint_children(scene, self.children.iter_mut());
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::List
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        for child in &mut self.children {
+            child.accessibility(ctx);
+        }
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.children.iter().map(|child| child.id()).collect()
+    }
+
+    fn make_trace_span(&self) -> Span {
+        trace_span!("VirtualList")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+    use crate::widget::{Portal, SizedBox};
+    use crate::Vec2;
+
+    #[test]
+    fn only_asks_for_the_items_in_view() {
+        let widget = Portal::new(VirtualList::new(1000, 20.0));
+        let mut harness = TestHarness::create_with_size(widget, Size::new(200.0, 100.0));
+        let list_id = harness.root_widget().children()[0].id();
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::VisibleItemsChanged(0..7), list_id))
+        );
+
+        harness.mouse_move(Point::new(100.0, 50.0));
+        harness.mouse_wheel(Vec2::new(0.0, 400.0));
+        assert_eq!(
+            harness.pop_action(),
+            Some((Action::VisibleItemsChanged(18..27), list_id))
+        );
+
+        // Once the children are the items in view, the list doesn't ask again
+        harness.edit_root_widget(|mut portal| {
+            let mut portal = portal.downcast::<Portal<VirtualList>>();
+            let mut list = portal.child_mut();
+            list.set_first_item(18);
+            for idx in 0..9 {
+                list.insert_child_pod(idx, WidgetPod::new(Box::new(SizedBox::empty())));
+            }
+        });
+        assert_eq!(harness.pop_action(), None);
+        let list = harness.root_widget().children()[0];
+        assert_eq!(list.state().layout_rect().height(), 20_000.0);
+        let first = list.children()[0].state().layout_rect();
+        assert_eq!((first.min_y(), first.height()), (360.0, 20.0));
+    }
+}
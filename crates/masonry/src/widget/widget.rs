@@ -10,14 +10,53 @@ use accesskit::Role;
 use smallvec::SmallVec;
 use tracing::{trace_span, Span};
 use vello::Scene;
+use winit::window::CursorIcon;
 
 use crate::event::{AccessEvent, PointerEvent, StatusChange, TextEvent};
-use crate::widget::WidgetRef;
 use crate::{
-    AccessCtx, AsAny, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    Point, Size,
+    AccessCtx, AsAny, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
 };
 
+/// How the user interacts with a widget, as reported by [`Widget::interactivity`].
+///
+/// Unless the widget does otherwise:
+/// - [`Clickable`](Self::Clickable) and [`TextInput`](Self::TextInput) widgets are
+///   registered for focus, so the user can reach them with <kbd>Tab</kbd>, and their
+///   accessibility nodes offer the `Focus` action.
+/// - The cursor is the pointing hand over `Clickable` widgets and the text cursor over
+///   `TextInput` ones, which the widgets can replace with [`EventCtx::set_cursor`].
+/// - The accessibility nodes of `Clickable` widgets offer the `Default` action, as a
+///   click, which the widget should handle in [`Widget::on_access_event`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interactivity {
+    /// The widget doesn't respond to the user itself, like a label or a container.
+    #[default]
+    None,
+    /// The widget does something when clicked, like a button.
+    Clickable,
+    /// The widget takes text typed by the user, like a text box.
+    TextInput,
+    /// The widget scrolls its content, like a portal. Scrollable widgets aren't focused
+    /// themselves, so that <kbd>Tab</kbd> goes to their content.
+    Scrollable,
+}
+
+impl Interactivity {
+    /// Whether the widget is registered for focus by default.
+    pub fn is_focusable(self) -> bool {
+        matches!(self, Interactivity::Clickable | Interactivity::TextInput)
+    }
+
+    /// The cursor shown over the widget by default, if any.
+    pub fn cursor(self) -> Option<CursorIcon> {
+        match self {
+            Interactivity::Clickable => Some(CursorIcon::Pointer),
+            Interactivity::TextInput => Some(CursorIcon::Text),
+            Interactivity::None | Interactivity::Scrollable => None,
+        }
+    }
+}
+
 /// A unique identifier for a single [`Widget`].
 ///
 /// `WidgetId`s are generated automatically for all widgets in the widget tree.
@@ -55,8 +94,8 @@ pub struct WidgetId(pub(crate) NonZeroU64);
 /// These trait methods are provided with a corresponding context. The widget can
 /// request things and cause actions by calling methods on that context.
 ///
-/// Widgets also have a [`children`](Self::children) method. Leaf widgets return an empty array,
-/// whereas container widgets return an array of [`WidgetRef`]. Container widgets
+/// Widgets also have a [`children_ids`](Self::children_ids) method. Leaf widgets return an empty
+/// array, whereas container widgets return the ids of their children. Container widgets
 /// have some validity invariants to maintain regarding their children. See TUTORIAL_2
 /// for details **(TODO)**.
 ///
@@ -87,6 +126,19 @@ pub trait Widget: AsAny {
     /// changes in the widget graph or in the state of your specific widget.
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle);
 
+    /// Release everything the widget holds outside of itself, as it's removed from
+    /// the widget tree.
+    ///
+    /// This is called exactly once, after [`LifeCycle::WidgetRemoved`] has been
+    /// handled by the widget and all of its descendants, whose `on_removed` has
+    /// already been called. Afterwards the framework releases what it tracks for the
+    /// widget itself, such as its GPU resources and its accessibility node.
+    ///
+    /// Widgets which were never added to the tree are not called.
+    fn on_removed(&mut self, ctx: &mut LifeCycleCtx) {
+        let _ = ctx;
+    }
+
     /// Compute layout.
     ///
     /// A leaf widget should determine its size (subject to the provided
@@ -114,21 +166,30 @@ pub trait Widget: AsAny {
     /// the render context, which is especially useful for scrolling.
     fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene);
 
+    /// How the user interacts with the widget, from which the framework derives
+    /// some of its behaviour; see [`Interactivity`].
+    ///
+    /// This is read when the widget is added to the tree, and when its accessibility
+    /// node and the focus chain are built.
+    fn interactivity(&self) -> Interactivity {
+        Interactivity::None
+    }
+
     fn accessibility_role(&self) -> Role;
 
     fn accessibility(&mut self, ctx: &mut AccessCtx);
 
-    /// Return references to this widget's children.
+    /// Return the ids of this widget's children, in order.
     ///
-    /// Leaf widgets return an empty array. Container widgets return references to
-    /// their children.
+    /// Leaf widgets return an empty array. Container widgets return the ids of
+    /// their children, which the framework uses to find them in the widget tree.
     ///
     /// This methods has some validity invariants. A widget's children list must be
     /// consistent. If children are added or removed, the parent widget should call
     /// `children_changed` on one of the Ctx parameters. Container widgets are also
     /// responsible for calling the main methods (on_event, lifecycle, layout, paint)
     /// on their children.
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]>;
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]>;
 
     /// Return a span for tracing.
     ///
@@ -141,8 +202,8 @@ pub trait Widget: AsAny {
 
     /// Return a small string representing important info about this widget instance.
     ///
-    /// When using [`WidgetRef`]'s [`Debug`](std::fmt::Debug) implementation, widgets
-    /// will be displayed as a tree of values. Widgets which return a non-null value in
+    /// When using [`WidgetRef`](crate::widget::WidgetRef)'s [`Debug`](std::fmt::Debug)
+    /// implementation, widgets will be displayed as a tree of values. Widgets which return a non-null value in
     /// `get_debug_text` will appear with that text next to their type name. This can
     /// be eg a label's text, or whether a checkbox is checked.
     fn get_debug_text(&self) -> Option<String> {
@@ -151,20 +212,6 @@ pub trait Widget: AsAny {
 
     // --- Auto-generated implementations ---
 
-    /// Return which child, if any, has the given `pos` in its layout rect.
-    ///
-    /// The child return is a direct child, not eg a grand-child. The position is in
-    /// relative coordinates. (Eg `(0,0)` is the top-left corner of `self`).
-    ///
-    /// Has a default implementation, that can be overridden to search children more
-    /// efficiently.
-    fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'_, dyn Widget>> {
-        // layout_rect() is in parent coordinate space
-        self.children()
-            .into_iter()
-            .find(|child| child.state().layout_rect().contains(pos))
-    }
-
     /// Get the (verbose) type name of the widget for debugging purposes.
     /// You should not override this method.
     #[doc(hidden)]
@@ -269,6 +316,10 @@ impl Widget for Box<dyn Widget> {
         self.deref_mut().lifecycle(ctx, event);
     }
 
+    fn on_removed(&mut self, ctx: &mut LifeCycleCtx) {
+        self.deref_mut().on_removed(ctx);
+    }
+
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
         self.deref_mut().layout(ctx, bc)
     }
@@ -277,6 +328,10 @@ impl Widget for Box<dyn Widget> {
         self.deref_mut().paint(ctx, scene);
     }
 
+    fn interactivity(&self) -> Interactivity {
+        self.deref().interactivity()
+    }
+
     fn accessibility_role(&self) -> Role {
         self.deref().accessibility_role()
     }
@@ -289,8 +344,8 @@ impl Widget for Box<dyn Widget> {
         self.deref().type_name()
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
-        self.deref().children()
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        self.deref().children_ids()
     }
 
     fn make_trace_span(&self) -> Span {
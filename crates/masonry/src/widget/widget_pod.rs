@@ -1,17 +1,21 @@
 // Copyright 2018 the Xilem Authors and the Druid Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use accesskit::{NodeBuilder, NodeId};
+use std::any::Any;
+
+use accesskit::{DefaultActionVerb, NodeBuilder, NodeId};
 use tracing::{info_span, trace, warn};
 use vello::Scene;
 use winit::dpi::LogicalPosition;
 
 use crate::event::{AccessEvent, PointerEvent, TextEvent};
-use crate::kurbo::{Affine, Insets, Point, Rect, Shape, Size};
+use crate::kurbo::{Affine, Point, Rect, Shape, Size};
+use crate::paint_debug::PaintDebug;
 use crate::paint_scene_helpers::stroke;
 use crate::render_root::RenderRootState;
 use crate::theme::get_debug_color;
-use crate::widget::{WidgetRef, WidgetState};
+use crate::widget::{Interactivity, WidgetRef, WidgetState};
+use crate::widget_tree::{ArenaMut, ArenaMutChildren, ArenaRefChildren, WidgetNode};
 use crate::{
     AccessCtx, BoxConstraints, EventCtx, InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx,
     PaintCtx, StatusChange, Widget, WidgetId,
@@ -25,12 +29,26 @@ use crate::{
 /// but rather contain a `WidgetPod`, which has additional state needed
 /// for layout and for the widget to participate in event flow.
 ///
+/// Once the pod's parent has passed anything down to it, the widget and its
+/// state are stored in the widget tree, keyed by the pod's id, and the pod is
+/// only a handle to them. Parents access them through their context, eg with
+/// [`LayoutCtx::get_ref`] or [`EventCtx::get_mut`].
+///
 /// `WidgetPod` will translate internal Masonry events to regular events,
 /// synthesize additional events of interest, and stop propagation when it makes sense.
 pub struct WidgetPod<W> {
-    pub(crate) state: WidgetState,
-    pub(crate) inner: W,
-    pub(crate) fragment: Scene,
+    id: WidgetId,
+    inner: WidgetPodInner<W>,
+}
+
+// Pods only hold their widget until it's added to the tree, so the size of the
+// `Created` variant isn't worth an extra allocation.
+#[allow(clippy::large_enum_variant)]
+enum WidgetPodInner<W> {
+    /// The widget hasn't been added to the tree yet.
+    Created { widget: W, state: WidgetState },
+    /// The widget is in the tree, as a child of the pod's parent.
+    Inserted,
 }
 
 // ---
@@ -51,272 +69,321 @@ impl<W: Widget> WidgetPod<W> {
         state.children_changed = true;
         state.needs_layout = true;
         WidgetPod {
-            state,
-            inner,
-            fragment: Scene::new(),
+            id,
+            inner: WidgetPodInner::Created {
+                widget: inner,
+                state,
+            },
         }
     }
 
-    /// Read-only access to state. We don't mark the field as `pub` because
-    /// we want to control mutation.
-    pub(crate) fn state(&self) -> &WidgetState {
-        &self.state
-    }
-
-    // TODO - remove
-    /// Return a reference to the inner widget.
-    pub fn widget(&self) -> &W {
-        &self.inner
-    }
-
-    /// Return a [`WidgetRef`] to the inner widget.
-    pub fn as_ref(&self) -> WidgetRef<'_, W> {
-        WidgetRef::new(&self.state, &self.inner)
-    }
-
-    /// Return a type-erased [`WidgetRef`] to the inner widget.
-    pub fn as_dyn(&self) -> WidgetRef<'_, dyn Widget> {
-        WidgetRef::new(&self.state, &self.inner)
-    }
-
-    /// Return `true` if the widget has received [`LifeCycle::WidgetAdded`].
-    ///
-    /// [`LifeCycle::WidgetAdded`]: ./enum.LifeCycle.html#variant.WidgetAdded
-    pub fn is_initialized(&self) -> bool {
-        !self.state.is_new
-    }
-
-    /// Return `true` if widget or any descendent is focused
-    pub fn has_focus(&self) -> bool {
-        self.state.has_focus
-    }
-
-    /// Query the "active" state of the widget.
-    pub fn is_active(&self) -> bool {
-        self.state.is_active
-    }
-
-    /// Return `true` if any descendant is active.
-    pub fn has_active(&self) -> bool {
-        self.state.has_active
-    }
-
-    /// Query the "hot" state of the widget.
-    ///
-    /// See [`EventCtx::is_hot`] for additional information.
-    pub fn is_hot(&self) -> bool {
-        self.state.is_hot
+    /// Builder-style method to disable the widget before it is added, as if it
+    /// called [`set_disabled`](crate::EventCtx::set_disabled) on itself.
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        match &mut self.inner {
+            WidgetPodInner::Created { state, .. } => state.is_explicitly_disabled_new = disabled,
+            WidgetPodInner::Inserted => debug_panic!(
+                "Error in WidgetPod #{}: with_disabled called after the widget was added",
+                self.id.to_raw(),
+            ),
+        }
+        self
     }
 
     /// Get the identity of the widget.
     pub fn id(&self) -> WidgetId {
-        self.state.id
+        self.id
     }
 
-    /// Return the layout rectangle.
-    ///
-    /// This will be a [`Rect`] with a [`Size`] determined by the child's [`layout`]
-    /// method, and the origin that was set by [`place_child`].
+    /// Builder-style method to stash the widget before it is added, as if its
+    /// parent called [`set_stashed`](crate::EventCtx::set_stashed) on it.
+    pub(crate) fn with_stashed(mut self, stashed: bool) -> Self {
+        self.created_state_mut().is_stashed = stashed;
+        self
+    }
+
+    /// The inner widget, which mustn't have been added to the tree yet.
     ///
-    /// Two sibling widgets' layout rects will almost never intersect.
+    /// This is for builder methods of containers, which set up their children
+    /// before they are added. Once they are, use [`EventCtx::get_mut`] instead.
     ///
-    /// This rect will also be used to detect whether any given pointer event (eg clicks)
-    /// intersects with the rectangle.
+    /// # Panics
     ///
-    /// [`layout`]: trait.Widget.html#tymethod.layout
-    /// [`place_child`]: LayoutCtx::place_child
-    pub fn layout_rect(&self) -> Rect {
-        self.state.layout_rect()
+    /// If the widget was added to the tree.
+    pub(crate) fn created_widget_mut(&mut self) -> &mut W {
+        match &mut self.inner {
+            WidgetPodInner::Created { widget, .. } => widget,
+            WidgetPodInner::Inserted => {
+                panic!("Widget #{} was already added to the tree", self.id.to_raw())
+            }
+        }
     }
 
-    /// Get the widget's paint rectangle.
+    /// The state of the widget, which mustn't have been added to the tree yet.
     ///
-    /// This is the [`Rect`] that widget has indicated it needs to paint in.
-    /// This is the same as the [`layout_rect`] with the [`paint_insets`] applied;
-    /// in the general case it is the same as the [`layout_rect`].
+    /// # Panics
     ///
-    /// [`layout_rect`]: Self::layout_rect
-    /// [`paint_insets`]: Self::paint_insets
-    pub fn paint_rect(&self) -> Rect {
-        self.state.paint_rect()
+    /// If the widget was added to the tree.
+    pub(crate) fn created_state_mut(&mut self) -> &mut WidgetState {
+        match &mut self.inner {
+            WidgetPodInner::Created { state, .. } => state,
+            WidgetPodInner::Inserted => {
+                panic!("Widget #{} was already added to the tree", self.id.to_raw())
+            }
+        }
     }
 
-    /// Return the paint [`Insets`] for this widget.
-    ///
-    /// If these [`Insets`] are nonzero, they describe the area beyond a widget's
-    /// layout rect where it needs to paint.
-    ///
-    /// These are generally zero; exceptions are widgets that do things like
-    /// paint a drop shadow.
-    ///
-    /// A widget can set its insets by calling [`set_paint_insets`] during its
-    /// [`layout`] method.
-    ///
-    /// [`Insets`]: struct.Insets.html
-    /// [`set_paint_insets`]: struct.LayoutCtx.html#method.set_paint_insets
-    /// [`layout`]: trait.Widget.html#tymethod.layout
-    pub fn paint_insets(&self) -> Insets {
-        self.state.paint_insets
+    /// Return `true` if the widget has been moved into the widget tree.
+    pub(crate) fn is_inserted(&self) -> bool {
+        matches!(self.inner, WidgetPodInner::Inserted)
     }
 
-    /// Given a parents layout size, determine the appropriate paint `Insets`
-    /// for the parent.
-    ///
-    /// This is a convenience method to be used from the [`layout`] method
-    /// of a `Widget` that manages a child; it allows the parent to correctly
-    /// propagate a child's desired paint rect, if it extends beyond the bounds
-    /// of the parent's layout rect.
-    ///
-    /// [`layout`]: trait.Widget.html#tymethod.layout
-    /// [`Insets`]: struct.Insets.html
-    pub fn compute_parent_paint_insets(&self, parent_size: Size) -> Insets {
-        let parent_bounds = Rect::ZERO.with_size(parent_size);
-        let union_pant_rect = self.paint_rect().union(parent_bounds);
-        union_pant_rect - parent_bounds
+    /// Return a [`WidgetRef`] to the inner widget, which is a child of the widget
+    /// whose children are `siblings`.
+    pub(crate) fn as_ref_in<'a>(
+        &'a self,
+        siblings: ArenaRefChildren<'a, WidgetNode>,
+    ) -> WidgetRef<'a, W> {
+        match &self.inner {
+            WidgetPodInner::Created { widget, state } => {
+                WidgetRef::new(state, widget, ArenaRefChildren::empty())
+            }
+            WidgetPodInner::Inserted => {
+                let Some(node) = siblings.get_child(self.id) else {
+                    panic!(
+                        "Widget #{} isn't a child of widget {:?}",
+                        self.id.to_raw(),
+                        siblings.parent_id().map(WidgetId::to_raw),
+                    );
+                };
+                WidgetRef::new(&node.item.state, node.item.downcast::<W>(), node.children)
+            }
+        }
     }
 
-    /// The distance from the bottom of this widget to the baseline.
-    pub fn baseline_offset(&self) -> f64 {
-        self.state.baseline_offset
+    /// Return the node of the inner widget, which is a child of the widget whose
+    /// children are `siblings`, moving the widget into the tree if it isn't yet.
+    pub(crate) fn node_mut<'a>(
+        &mut self,
+        mut siblings: ArenaMutChildren<'a, WidgetNode>,
+    ) -> ArenaMut<'a, WidgetNode> {
+        if let WidgetPodInner::Created { .. } = self.inner {
+            let WidgetPodInner::Created { widget, state } =
+                std::mem::replace(&mut self.inner, WidgetPodInner::Inserted)
+            else {
+                unreachable!()
+            };
+            let node = WidgetNode {
+                widget: into_boxed_widget(widget),
+                state,
+                fragment: Scene::new(),
+            };
+            siblings.insert_child(self.id, node);
+        }
+        let parent_id = siblings.parent_id();
+        match siblings.into_child_mut(self.id) {
+            Some(node) => node,
+            None => panic!(
+                "Widget #{} isn't a child of widget {:?}",
+                self.id.to_raw(),
+                parent_id.map(WidgetId::to_raw),
+            ),
+        }
     }
+}
 
-    // FIXME - Remove
-    /// Return a mutable reference to the inner widget.
-    pub(crate) fn widget_mut(&mut self) -> &mut W {
-        &mut self.inner
+impl WidgetPod<Box<dyn Widget>> {
+    /// A pod for a widget which is already in the tree.
+    pub(crate) fn inserted(id: WidgetId) -> Self {
+        WidgetPod {
+            id,
+            inner: WidgetPodInner::Inserted,
+        }
     }
 }
 
-impl<W: Widget> WidgetPod<W> {
-    // TODO - this is confusing
-    #[inline(always)]
-    pub(crate) fn mark_as_visited(&mut self) {
-        self.state.mark_as_visited(true);
+/// Box `widget`, unless it already is a boxed widget.
+fn into_boxed_widget<W: Widget>(widget: W) -> Box<dyn Widget> {
+    let widget: Box<dyn Any> = Box::new(widget);
+    match widget.downcast::<Box<dyn Widget>>() {
+        Ok(widget) => *widget,
+        Err(widget) => match widget.downcast::<W>() {
+            Ok(widget) => widget,
+            Err(_) => unreachable!(),
+        },
     }
+}
 
-    // Notes about hot state:
-    //
-    // Hot state (the thing that changes when your mouse hovers over a button) is annoying to implement, because it breaks the convenient abstraction of multiple static passes over the widget tree.
-    //
-    // Ideally, what you'd want is "first handle events, then update widget states, then compute layout, then paint", where each 'then' is an indestructible wall that only be crossed in one direction.
-    //
-    // Hot state breaks that abstraction, because a change in a widget's layout (eg a button gets bigger) can lead to a change in hot state.
-    //
-    // To give an extreme example: suppose you have a button which becomes very small when you hover over it (and forget all the reasons this would be terrible UX). How should its hot state be handled? When the mouse moves over the button, the hot state will get changed, and the button will become smaller. But becoming smaller make it so the mouse no longer hovers over the button, so the hot state will get changed again.
-    //
-    // Ideally, this is a UX trap I'd like to warn against; in any case, the fact that it's possible shows we have to account for cases where layout has an influence on previous stages.
-    //
-    // In actual Masonry code, that means:
-    // - `Widget::lifecycle` can be called within `Widget::layout`.
-    // - `Widget::set_position` can call `Widget::lifecycle` and thus needs to be passed context types, which gives the method a surprising prototype.
-    //
-    // We could have `set_position` set a `hot_state_needs_update` flag, but then we'd need to add in another UpdateHotState pass (probably as a variant to the Lifecycle enum).
-    //
-    // Another problem is that hot state handling is counter-intuitive for someone writing a Widget implementation. Developers who want to implement "This widget turns red when the mouse is over it" will usually assume they should use the MouseMove event or something similar; when what they actually need is a Lifecycle variant.
-    //
-    // Other things hot state is missing:
-    // - A concept of "cursor moved to inner widget" (though I think that's not super useful outside the browser).
-    // - Multiple pointers handling.
-
-    /// Determines if the provided `mouse_pos` is inside `rect`
-    /// and if so updates the hot state and sends `LifeCycle::HotChanged`.
-    ///
-    /// Return `true` if the hot state changed.
-    ///
-    /// The provided `child_state` should be merged up if this returns `true`.
-    pub(crate) fn update_hot_state(
-        inner: &mut W,
-        inner_state: &mut WidgetState,
-        global_state: &mut RenderRootState,
-        mouse_pos: Option<LogicalPosition<f64>>,
-    ) -> bool {
-        let rect = inner_state.layout_rect() + inner_state.parent_window_origin.to_vec2();
-        let had_hot = inner_state.is_hot;
-        inner_state.is_hot = match mouse_pos {
-            Some(pos) => rect.winding(Point::new(pos.x, pos.y)) != 0,
-            None => false,
+// TODO - this is confusing
+#[inline(always)]
+fn mark_as_visited(node: &ArenaMut<'_, WidgetNode>) {
+    node.item.state.mark_as_visited(true);
+}
+
+// Notes about hot state:
+//
+// Hot state (the thing that changes when your mouse hovers over a button) is annoying to implement, because it breaks the convenient abstraction of multiple static passes over the widget tree.
+//
+// Ideally, what you'd want is "first handle events, then update widget states, then compute layout, then paint", where each 'then' is an indestructible wall that only be crossed in one direction.
+//
+// Hot state breaks that abstraction, because a change in a widget's layout (eg a button gets bigger) can lead to a change in hot state.
+//
+// To give an extreme example: suppose you have a button which becomes very small when you hover over it (and forget all the reasons this would be terrible UX). How should its hot state be handled? When the mouse moves over the button, the hot state will get changed, and the button will become smaller. But becoming smaller make it so the mouse no longer hovers over the button, so the hot state will get changed again.
+//
+// Ideally, this is a UX trap I'd like to warn against; in any case, the fact that it's possible shows we have to account for cases where layout has an influence on previous stages.
+//
+// In actual Masonry code, that means:
+// - `Widget::lifecycle` can be called within `Widget::layout`.
+// - `Widget::set_position` can call `Widget::lifecycle` and thus needs to be passed context types, which gives the method a surprising prototype.
+//
+// We could have `set_position` set a `hot_state_needs_update` flag, but then we'd need to add in another UpdateHotState pass (probably as a variant to the Lifecycle enum).
+//
+// Another problem is that hot state handling is counter-intuitive for someone writing a Widget implementation. Developers who want to implement "This widget turns red when the mouse is over it" will usually assume they should use the MouseMove event or something similar; when what they actually need is a Lifecycle variant.
+//
+// Other things hot state is missing:
+// - A concept of "cursor moved to inner widget" (though I think that's not super useful outside the browser).
+// - Multiple pointers handling.
+
+/// Determines if the provided `mouse_pos` is inside `rect`
+/// and if so updates the hot state and sends `LifeCycle::HotChanged`.
+///
+/// Return `true` if the hot state changed.
+///
+/// The state of the provided `node` should be merged up if this returns `true`.
+pub(crate) fn update_hot_state(
+    node: &mut ArenaMut<'_, WidgetNode>,
+    global_state: &mut RenderRootState,
+    mouse_pos: Option<LogicalPosition<f64>>,
+) -> bool {
+    let WidgetNode { widget, state, .. } = &mut *node.item;
+    let rect = state.layout_rect() + state.parent_window_origin.to_vec2();
+    let had_hot = state.is_hot;
+    state.is_hot = match mouse_pos {
+        Some(pos) => rect.winding(Point::new(pos.x, pos.y)) != 0,
+        None => false,
+    };
+    // FIXME - don't send event, update flags instead
+    if had_hot != state.is_hot {
+        trace!(
+            "Widget '{}' #{}: set hot state to {}",
+            widget.short_type_name(),
+            state.id.to_raw(),
+            state.is_hot
+        );
+
+        let hot_changed_event = StatusChange::HotChanged(state.is_hot);
+        let mut inner_ctx = LifeCycleCtx {
+            global_state,
+            widget_state: state,
+            widget_children: node.children.reborrow_mut(),
         };
-        // FIXME - don't send event, update flags instead
-        if had_hot != inner_state.is_hot {
-            trace!(
-                "Widget '{}' #{}: set hot state to {}",
-                inner.short_type_name(),
-                inner_state.id.to_raw(),
-                inner_state.is_hot
-            );
 
-            let hot_changed_event = StatusChange::HotChanged(inner_state.is_hot);
-            let mut inner_ctx = LifeCycleCtx {
-                global_state,
-                widget_state: inner_state,
-            };
+        let _span = info_span!("on_status_change").entered();
+        widget.on_status_change(&mut inner_ctx, &hot_changed_event);
 
-            let _span = info_span!("on_status_change").entered();
-            inner.on_status_change(&mut inner_ctx, &hot_changed_event);
+        return true;
+    }
+    false
+}
 
-            return true;
-        }
-        false
+// TODO - document
+// TODO - This method should take a 'can_skip: Fn(WidgetRef) -> bool'
+// predicate and only panic if can_skip returns false.
+#[inline(always)]
+fn call_widget_method_with_checks<Ret>(
+    node: &mut ArenaMut<'_, WidgetNode>,
+    method_name: &str,
+    visit: impl FnOnce(&mut ArenaMut<'_, WidgetNode>) -> Ret,
+) -> Ret {
+    if cfg!(not(debug_assertions)) {
+        return visit(node);
     }
 
-    // TODO - document
-    // TODO - This method should take a 'can_skip: Fn(WidgetRef) -> bool'
-    // predicate and only panic if can_skip returns false.
-    #[inline(always)]
-    fn call_widget_method_with_checks<Ret>(
-        &mut self,
-        method_name: &str,
-        visit: impl FnOnce(&mut Self) -> Ret,
-    ) -> Ret {
-        if cfg!(not(debug_assertions)) {
-            return visit(self);
-        }
+    for child in node.children.as_ref().iter() {
+        child.item.state.mark_as_visited(false);
+    }
+    let children_ids = node.item.widget.children_ids();
 
-        for child in self.inner.children() {
-            child.state().mark_as_visited(false);
-        }
-        let children_ids: Vec<_> = self.inner.children().iter().map(|w| w.id()).collect();
+    let return_value = visit(node);
 
-        let return_value = visit(self);
+    let new_children_ids = node.item.widget.children_ids();
+    if children_ids != new_children_ids && !node.item.state.children_changed {
+        debug_panic!(
+            "Error in '{}' #{}: children changed in method {} but ctx.children_changed() wasn't called",
+            node.item.widget.short_type_name(),
+            node.id.to_raw(),
+            method_name,
+        );
+    }
 
-        let new_children_ids: Vec<_> = self.inner.children().iter().map(|w| w.id()).collect();
-        if children_ids != new_children_ids && !self.state.children_changed {
+    // Children which were never passed to a method aren't in the tree yet.
+    #[cfg(debug_assertions)]
+    for id in &children_ids {
+        if new_children_ids.contains(id) && node.children.as_ref().get_child(*id).is_none() {
             debug_panic!(
-                "Error in '{}' #{}: children changed in method {} but ctx.children_changed() wasn't called",
-                self.inner.short_type_name(),
-                self.state().id.to_raw(),
+                "Error in '{}' #{}: child widget #{} not visited in method {}",
+                node.item.widget.short_type_name(),
+                node.id.to_raw(),
+                id.to_raw(),
                 method_name,
             );
         }
-
-        #[cfg(debug_assertions)]
-        for child in self.inner.children() {
-            // FIXME - use can_skip callback instead
-            if child.state().needs_visit() && !child.state().is_stashed {
-                debug_panic!(
-                    "Error in '{}' #{}: child widget '{}' #{} not visited in method {}",
-                    self.inner.short_type_name(),
-                    self.state().id.to_raw(),
-                    child.deref().short_type_name(),
-                    child.state().id.to_raw(),
-                    method_name,
-                );
-            }
-        }
-
-        return_value
     }
 
-    fn check_initialized(&self, method_name: &str) {
-        if !self.is_initialized() {
+    #[cfg(debug_assertions)]
+    for child in node.children.as_ref().iter() {
+        // Children which were dropped are removed from the tree once the
+        // parent's children are updated.
+        if !new_children_ids.contains(&child.id) {
+            continue;
+        }
+        // FIXME - use can_skip callback instead
+        let child_state = &child.item.state;
+        if child_state.needs_visit() && !child_state.is_stashed {
             debug_panic!(
-                "Error in '{}' #{}: method '{}' called before receiving WidgetAdded.",
-                self.inner.short_type_name(),
-                self.state.id.to_raw(),
+                "Error in '{}' #{}: child widget '{}' #{} not visited in method {}",
+                node.item.widget.short_type_name(),
+                node.id.to_raw(),
+                child.item.widget.short_type_name(),
+                child.id.to_raw(),
                 method_name,
             );
         }
     }
+
+    return_value
+}
+
+/// Stop timing `method`, which was started with `WidgetTimings::start`.
+fn finish_timing(
+    node: &ArenaMut<'_, WidgetNode>,
+    global_state: &mut RenderRootState,
+    method: &'static str,
+) {
+    global_state
+        .widget_timings
+        .finish(node.id, node.item.widget.short_type_name(), method, || {
+            node.children.id_path(node.id)
+        });
+}
+
+fn check_initialized(node: &ArenaMut<'_, WidgetNode>, method_name: &str) {
+    if node.item.state.is_new {
+        debug_panic!(
+            "Error in '{}' #{}: method '{}' called before receiving WidgetAdded.",
+            node.item.widget.short_type_name(),
+            node.id.to_raw(),
+            method_name,
+        );
+    }
+}
+
+/// Log the state of `node` to the debug logger, once a pass is done with it.
+fn log_widget_state(node: &ArenaMut<'_, WidgetNode>, global_state: &mut RenderRootState) {
+    global_state
+        .debug_logger
+        .update_widget_state(WidgetRef::from_node(node.reborrow()));
+    global_state.debug_logger.push_log(false, "updated state");
 }
 
 impl<W: Widget + 'static> WidgetPod<W> {
@@ -325,7 +392,15 @@ impl<W: Widget + 'static> WidgetPod<W> {
     /// Convert a `WidgetPod` containing a widget of a specific concrete type
     /// into a dynamically boxed widget.
     pub fn boxed(self) -> WidgetPod<Box<dyn Widget>> {
-        WidgetPod::new_with_id(Box::new(self.inner), self.state.id)
+        match self.inner {
+            WidgetPodInner::Created { widget, state } => {
+                let widget: Box<dyn Widget> = Box::new(widget);
+                WidgetPod::new_with_id(widget, self.id)
+                    .with_disabled(state.is_explicitly_disabled_new)
+            }
+            // The tree stores every widget boxed already.
+            WidgetPodInner::Inserted => WidgetPod::inserted(self.id),
+        }
     }
 }
 
@@ -340,21 +415,38 @@ impl<W: Widget> WidgetPod<W> {
     // - If a Widget has focus, then none of its parents is hidden
 
     pub fn on_pointer_event(&mut self, parent_ctx: &mut EventCtx, event: &PointerEvent) {
-        let _span = self.inner.make_trace_span().entered();
+        let mut node = self.node_mut(parent_ctx.widget_children.reborrow_mut());
+        let hot_pos = pointer_position(event);
+
+        // A subtree that isn't hot or active and doesn't contain the pointer can't
+        // react to the event, so we skip it early, before any logging.
+        let state = &node.item.state;
+        let contains_pointer = hot_pos.is_some_and(|pos| {
+            state
+                .window_bounding_rect()
+                .contains(Point::new(pos.x, pos.y))
+        });
+        if !state.is_hot && !state.has_active && !contains_pointer {
+            mark_as_visited(&node);
+            check_initialized(&node, "on_pointer_event");
+            return;
+        }
+
+        let _span = node.item.widget.make_trace_span().entered();
         // TODO #11
         parent_ctx
             .global_state
             .debug_logger
-            .push_span(self.inner.short_type_name());
+            .push_span(node.item.widget.short_type_name());
 
         // TODO - explain this
-        self.mark_as_visited();
-        self.check_initialized("on_pointer_event");
+        mark_as_visited(&node);
+        check_initialized(&node, "on_pointer_event");
 
         trace!(
             "Widget '{}' #{} visited",
-            self.inner.short_type_name(),
-            self.state.id.to_raw(),
+            node.item.widget.short_type_name(),
+            node.id.to_raw(),
         );
 
         if parent_ctx.is_handled {
@@ -363,83 +455,64 @@ impl<W: Widget> WidgetPod<W> {
             return;
         }
 
-        let had_active = self.state.has_active;
-
-        // TODO - This doesn't handle the case where multiple cursors
-        // are over the same widget
-        let hot_pos = match event {
-            PointerEvent::PointerDown(_, pointer_state) => Some(pointer_state.position),
-            PointerEvent::PointerUp(_, pointer_state) => Some(pointer_state.position),
-            PointerEvent::PointerMove(pointer_state) => Some(pointer_state.position),
-            PointerEvent::PointerEnter(pointer_state) => Some(pointer_state.position),
-            PointerEvent::PointerLeave(_) => None,
-            PointerEvent::MouseWheel(_, pointer_state) => Some(pointer_state.position),
-            PointerEvent::HoverFile(_, _) => None,
-            PointerEvent::DropFile(_, _) => None,
-            PointerEvent::HoverFileCancel(_) => None,
-        };
-        let hot_changed = WidgetPod::update_hot_state(
-            &mut self.inner,
-            &mut self.state,
-            parent_ctx.global_state,
-            hot_pos,
-        );
-        let call_inner = (had_active || self.state.is_hot || hot_changed) && !self.state.is_stashed;
+        let had_active = node.item.state.has_active;
+
+        let hot_changed = update_hot_state(&mut node, parent_ctx.global_state, hot_pos);
+        // Children placed outside of the widget, such as popups, get the event through it
+        let call_inner = (had_active || node.item.state.is_hot || hot_changed || contains_pointer)
+            && !node.item.state.is_stashed;
         //let call_inner = true;
 
         if call_inner {
-            self.call_widget_method_with_checks("on_pointer_event", |widget_pod| {
-                // widget_pod is a reborrow of `self`
+            parent_ctx.global_state.widget_timings.start();
+            call_widget_method_with_checks(&mut node, "on_pointer_event", |node| {
+                let WidgetNode { widget, state, .. } = &mut *node.item;
                 let mut inner_ctx = EventCtx {
                     global_state: parent_ctx.global_state,
-                    widget_state: &mut widget_pod.state,
+                    widget_state: state,
+                    widget_children: node.children.reborrow_mut(),
                     is_handled: false,
                     request_pan_to_child: None,
                 };
                 inner_ctx.widget_state.has_active = false;
 
-                widget_pod.inner.on_pointer_event(&mut inner_ctx, event);
+                widget.on_pointer_event(&mut inner_ctx, event);
 
                 inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                 parent_ctx.is_handled |= inner_ctx.is_handled;
 
                 // TODO - there's some dubious logic here
                 if let Some(target_rect) = inner_ctx.request_pan_to_child {
-                    widget_pod.pan_to_child(parent_ctx, target_rect);
+                    pan_to_child(node, parent_ctx.global_state, target_rect);
                     let new_rect = target_rect
-                        .with_origin(target_rect.origin() + widget_pod.state.origin.to_vec2());
+                        .with_origin(target_rect.origin() + node.item.state.origin.to_vec2());
                     parent_ctx.request_pan_to_child = Some(new_rect);
                 }
             });
+            finish_timing(&node, parent_ctx.global_state, "on_pointer_event");
         }
 
         // Always merge even if not needed, because merging is idempotent and gives us simpler code.
         // Doing this conditionally only makes sense when there's a measurable performance boost.
-        parent_ctx.widget_state.merge_up(&mut self.state);
+        parent_ctx.widget_state.merge_up(&mut node.item.state);
 
-        parent_ctx
-            .global_state
-            .debug_logger
-            .update_widget_state(self.as_dyn());
-        parent_ctx
-            .global_state
-            .debug_logger
-            .push_log(false, "updated state");
+        log_widget_state(&node, parent_ctx.global_state);
 
         parent_ctx.global_state.debug_logger.pop_span();
     }
 
     pub fn on_text_event(&mut self, parent_ctx: &mut EventCtx, event: &TextEvent) {
-        let _span = self.inner.make_trace_span().entered();
+        let mut node = self.node_mut(parent_ctx.widget_children.reborrow_mut());
+        let _span = node.item.widget.make_trace_span().entered();
         // TODO #11
         parent_ctx
             .global_state
             .debug_logger
-            .push_span(self.inner.short_type_name());
+            .push_span(node.item.widget.short_type_name());
 
         // TODO - explain this
-        self.mark_as_visited();
-        self.check_initialized("on_text_event");
+        mark_as_visited(&node);
+        check_initialized(&node, "on_text_event");
 
         if parent_ctx.is_handled {
             parent_ctx.global_state.debug_logger.pop_span();
@@ -447,68 +520,55 @@ impl<W: Widget> WidgetPod<W> {
             return;
         }
 
-        if self.state.has_focus {
-            self.call_widget_method_with_checks("on_text_event", |widget_pod| {
-                // widget_pod is a reborrow of `self`
+        if node.item.state.has_focus {
+            parent_ctx.global_state.widget_timings.start();
+            call_widget_method_with_checks(&mut node, "on_text_event", |node| {
+                let WidgetNode { widget, state, .. } = &mut *node.item;
                 let mut inner_ctx = EventCtx {
                     global_state: parent_ctx.global_state,
-                    widget_state: &mut widget_pod.state,
+                    widget_state: state,
+                    widget_children: node.children.reborrow_mut(),
                     is_handled: false,
                     request_pan_to_child: None,
                 };
 
-                widget_pod.inner.on_text_event(&mut inner_ctx, event);
+                widget.on_text_event(&mut inner_ctx, event);
 
                 inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                 parent_ctx.is_handled |= inner_ctx.is_handled;
 
                 // TODO - there's some dubious logic here
                 if let Some(target_rect) = inner_ctx.request_pan_to_child {
-                    widget_pod.pan_to_child(parent_ctx, target_rect);
+                    pan_to_child(node, parent_ctx.global_state, target_rect);
                     let new_rect = target_rect
-                        .with_origin(target_rect.origin() + widget_pod.state.origin.to_vec2());
+                        .with_origin(target_rect.origin() + node.item.state.origin.to_vec2());
                     parent_ctx.request_pan_to_child = Some(new_rect);
                 }
             });
+            finish_timing(&node, parent_ctx.global_state, "on_text_event");
         }
 
         // Always merge even if not needed, because merging is idempotent and gives us simpler code.
         // Doing this conditionally only makes sense when there's a measurable performance boost.
-        parent_ctx.widget_state.merge_up(&mut self.state);
+        parent_ctx.widget_state.merge_up(&mut node.item.state);
 
-        parent_ctx
-            .global_state
-            .debug_logger
-            .update_widget_state(self.as_dyn());
-        parent_ctx
-            .global_state
-            .debug_logger
-            .push_log(false, "updated state");
+        log_widget_state(&node, parent_ctx.global_state);
 
         parent_ctx.global_state.debug_logger.pop_span();
     }
 
-    fn pan_to_child(&mut self, parent_ctx: &mut EventCtx, rect: Rect) {
-        let mut inner_ctx = LifeCycleCtx {
-            global_state: parent_ctx.global_state,
-            widget_state: &mut self.state,
-        };
-        let event = LifeCycle::RequestPanToChild(rect);
-
-        self.inner.lifecycle(&mut inner_ctx, &event);
-    }
-
     pub fn on_access_event(&mut self, parent_ctx: &mut EventCtx, event: &AccessEvent) {
-        let _span = self.inner.make_trace_span().entered();
+        let mut node = self.node_mut(parent_ctx.widget_children.reborrow_mut());
+        let _span = node.item.widget.make_trace_span().entered();
         // TODO #11
         parent_ctx
             .global_state
             .debug_logger
-            .push_span(self.inner.short_type_name());
+            .push_span(node.item.widget.short_type_name());
 
         // TODO - explain this
-        self.mark_as_visited();
-        self.check_initialized("on_text_event");
+        mark_as_visited(&node);
+        check_initialized(&node, "on_text_event");
 
         if parent_ctx.is_handled {
             parent_ctx.global_state.debug_logger.pop_span();
@@ -516,37 +576,33 @@ impl<W: Widget> WidgetPod<W> {
             return;
         }
 
-        if self.id() == event.target || self.state.children.may_contain(&event.target) {
-            self.call_widget_method_with_checks("on_access_event", |widget_pod| {
-                // widget_pod is a reborrow of `self`
+        if node.id == event.target || node.item.state.children.may_contain(&event.target) {
+            parent_ctx.global_state.widget_timings.start();
+            call_widget_method_with_checks(&mut node, "on_access_event", |node| {
+                let WidgetNode { widget, state, .. } = &mut *node.item;
                 let mut inner_ctx = EventCtx {
                     global_state: parent_ctx.global_state,
-                    widget_state: &mut widget_pod.state,
+                    widget_state: state,
+                    widget_children: node.children.reborrow_mut(),
                     is_handled: false,
                     request_pan_to_child: None,
                 };
 
-                widget_pod.inner.on_access_event(&mut inner_ctx, event);
+                widget.on_access_event(&mut inner_ctx, event);
 
                 inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                 parent_ctx.is_handled |= inner_ctx.is_handled;
 
                 // TODO - request_pan_to_child
             });
+            finish_timing(&node, parent_ctx.global_state, "on_access_event");
         }
 
         // Always merge even if not needed, because merging is idempotent and gives us simpler code.
         // Doing this conditionally only makes sense when there's a measurable performance boost.
-        parent_ctx.widget_state.merge_up(&mut self.state);
+        parent_ctx.widget_state.merge_up(&mut node.item.state);
 
-        parent_ctx
-            .global_state
-            .debug_logger
-            .update_widget_state(self.as_dyn());
-        parent_ctx
-            .global_state
-            .debug_logger
-            .push_log(false, "updated state");
+        log_widget_state(&node, parent_ctx.global_state);
 
         parent_ctx.global_state.debug_logger.pop_span();
     }
@@ -560,259 +616,429 @@ impl<W: Widget> WidgetPod<W> {
     ///
     /// [`LifeCycle`]: enum.LifeCycle.html
     pub fn lifecycle(&mut self, parent_ctx: &mut LifeCycleCtx, event: &LifeCycle) {
-        let _span = self.inner.make_trace_span().entered();
+        match event {
+            // Widgets which were never added have nothing to clean up
+            LifeCycle::WidgetRemoved if !self.is_inserted() => return,
+            // if this is called either we were just created, in
+            // which case we need to change lifecycle event to
+            // WidgetAdded or in case we were already created
+            // we just pass this event down
+            LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded)
+                if self
+                    .node_mut(parent_ctx.widget_children.reborrow_mut())
+                    .item
+                    .state
+                    .is_new =>
+            {
+                self.lifecycle(parent_ctx, &LifeCycle::WidgetAdded);
+                return;
+            }
+            _ => (),
+        }
+        let node = self.node_mut(parent_ctx.widget_children.reborrow_mut());
+        lifecycle_node(
+            node,
+            parent_ctx.global_state,
+            parent_ctx.widget_state,
+            event,
+        );
+    }
+}
 
-        // TODO #11
-        parent_ctx
-            .global_state
-            .debug_logger
-            .push_span(self.inner.short_type_name());
+fn pan_to_child(
+    node: &mut ArenaMut<'_, WidgetNode>,
+    global_state: &mut RenderRootState,
+    rect: Rect,
+) {
+    let WidgetNode { widget, state, .. } = &mut *node.item;
+    let mut inner_ctx = LifeCycleCtx {
+        global_state,
+        widget_state: state,
+        widget_children: node.children.reborrow_mut(),
+    };
+    let event = LifeCycle::RequestPanToChild(rect);
+
+    widget.lifecycle(&mut inner_ctx, &event);
+}
 
-        // TODO - explain this
-        self.mark_as_visited();
-
-        // when routing a status change event, if we are at our target
-        // we may send an extra event after the actual event
-        let mut extra_event = None;
-
-        let had_focus = self.state.has_focus;
-
-        let call_inner = match event {
-            LifeCycle::Internal(internal) => match internal {
-                InternalLifeCycle::RouteWidgetAdded => {
-                    // if this is called either we were just created, in
-                    // which case we need to change lifecycle event to
-                    // WidgetAdded or in case we were already created
-                    // we just pass this event down
-                    if self.state.is_new {
-                        self.lifecycle(parent_ctx, &LifeCycle::WidgetAdded);
-                        parent_ctx
-                            .global_state
-                            .debug_logger
-                            .update_widget_state(self.as_dyn());
-                        parent_ctx
-                            .global_state
-                            .debug_logger
-                            .push_log(false, "updated state");
-                        parent_ctx.global_state.debug_logger.pop_span();
-                        return;
-                    } else {
-                        if self.state.children_changed {
-                            // TODO - Separate "widget removed" case.
-                            self.state.children.clear();
-                        }
-                        self.state.children_changed
-                    }
+/// Propagate a [`LifeCycle`] event to a node, whose parent has the state `parent_state`.
+fn lifecycle_node(
+    mut node: ArenaMut<'_, WidgetNode>,
+    global_state: &mut RenderRootState,
+    parent_state: &mut WidgetState,
+    event: &LifeCycle,
+) {
+    let _span = node.item.widget.make_trace_span().entered();
+
+    // TODO #11
+    global_state
+        .debug_logger
+        .push_span(node.item.widget.short_type_name());
+
+    // TODO - explain this
+    mark_as_visited(&node);
+
+    // when routing a status change event, if we are at our target
+    // we may send an extra event after the actual event
+    let mut extra_event = None;
+
+    let had_focus = node.item.state.has_focus;
+
+    let call_inner = match event {
+        LifeCycle::Internal(internal) => match internal {
+            InternalLifeCycle::RouteWidgetAdded => {
+                let state = &mut node.item.state;
+                if state.children_changed {
+                    // TODO - Separate "widget removed" case.
+                    state.children.clear();
                 }
-                InternalLifeCycle::RouteDisabledChanged => {
-                    self.state.update_focus_chain = true;
-
-                    let was_disabled = self.state.is_disabled();
-
-                    self.state.is_explicitly_disabled = self.state.is_explicitly_disabled_new;
-
-                    if was_disabled != self.state.is_disabled() {
-                        // TODO
-                        let disabled = self.state.is_disabled();
-                        self.call_widget_method_with_checks("lifecycle", |widget_pod| {
-                            let mut inner_ctx = LifeCycleCtx {
-                                global_state: parent_ctx.global_state,
-                                widget_state: &mut widget_pod.state,
-                            };
-
-                            widget_pod
-                                .inner
-                                .lifecycle(&mut inner_ctx, &LifeCycle::DisabledChanged(disabled));
-                        });
-                        //Each widget needs only one of DisabledChanged and RouteDisabledChanged
-                        false
-                    } else {
-                        self.state.children_disabled_changed
-                    }
+                state.children_changed
+            }
+            InternalLifeCycle::RouteDisabledChanged => {
+                let state = &mut node.item.state;
+                state.update_focus_chain = true;
+
+                let was_disabled = state.is_disabled();
+
+                state.is_explicitly_disabled = state.is_explicitly_disabled_new;
+
+                if was_disabled != state.is_disabled() {
+                    // TODO
+                    let disabled = state.is_disabled();
+                    call_widget_method_with_checks(&mut node, "lifecycle", |node| {
+                        let WidgetNode { widget, state, .. } = &mut *node.item;
+                        let mut inner_ctx = LifeCycleCtx {
+                            global_state,
+                            widget_state: state,
+                            widget_children: node.children.reborrow_mut(),
+                        };
+
+                        widget.lifecycle(&mut inner_ctx, &LifeCycle::DisabledChanged(disabled));
+                    });
+                    //Each widget needs only one of DisabledChanged and RouteDisabledChanged
+                    false
+                } else {
+                    state.children_disabled_changed
                 }
-                InternalLifeCycle::RouteFocusChanged { old, new } => {
-                    let this_changed = if *old == Some(self.state.id) {
-                        Some(false)
-                    } else if *new == Some(self.state.id) {
-                        Some(true)
-                    } else {
-                        None
-                    };
-
-                    if let Some(change) = this_changed {
-                        self.state.has_focus = change;
-                        extra_event = Some(StatusChange::FocusChanged(change));
-                    } else {
-                        self.state.has_focus = false;
-                    }
-
-                    // Recurse when the target widgets could be our descendants.
-                    // The bloom filter we're checking can return false positives.
-                    match (old, new) {
-                        (Some(old), _) if self.state.children.may_contain(old) => true,
-                        (_, Some(new)) if self.state.children.may_contain(new) => true,
-                        _ => false,
-                    }
+            }
+            InternalLifeCycle::RouteFocusChanged { old, new } => {
+                let state = &mut node.item.state;
+                let this_changed = if *old == Some(state.id) {
+                    Some(false)
+                } else if *new == Some(state.id) {
+                    Some(true)
+                } else {
+                    None
+                };
+
+                if let Some(change) = this_changed {
+                    state.has_focus = change;
+                    extra_event = Some(StatusChange::FocusChanged(change));
+                } else {
+                    state.has_focus = false;
                 }
-                InternalLifeCycle::ParentWindowOrigin { mouse_pos } => {
-                    self.state.parent_window_origin = parent_ctx.widget_state.window_origin();
-                    self.state.needs_window_origin = false;
-                    let mouse_pos = mouse_pos.map(|pos| LogicalPosition::new(pos.x, pos.y));
-                    WidgetPod::update_hot_state(
-                        &mut self.inner,
-                        &mut self.state,
-                        parent_ctx.global_state,
-                        mouse_pos,
-                    );
-                    // TODO - self.state.is_hidden
-                    true
+
+                // Recurse when the target widgets could be our descendants.
+                // The bloom filter we're checking can return false positives.
+                match (old, new) {
+                    (Some(old), _) if state.children.may_contain(old) => true,
+                    (_, Some(new)) if state.children.may_contain(new) => true,
+                    _ => false,
                 }
-            },
-            LifeCycle::WidgetAdded => {
-                if !self.state.is_new {
-                    // TODO - better warning.
-                    warn!("Already initialized.");
+            }
+            InternalLifeCycle::RouteRequestPaint { widgets } => {
+                let state = &mut node.item.state;
+                if widgets.contains(&state.id) {
+                    state.needs_paint = true;
                 }
-                trace!(
-                    "{} Received LifeCycle::WidgetAdded",
-                    self.inner.short_type_name()
-                );
-
-                self.state.is_new = false;
-                self.state.update_focus_chain = true;
-                self.state.needs_layout = true;
-                self.state.needs_paint = true;
-                self.state.needs_window_origin = true;
-                self.state.needs_accessibility_update = true;
-                self.state.request_accessibility_update = true;
-
+                widgets.iter().any(|id| state.children.may_contain(id))
+            }
+            InternalLifeCycle::RouteRequestPaintAll => {
+                // Stashed widgets are repainted too, so that they don't show stale
+                // fragments once they're shown again.
+                node.item.state.needs_paint = true;
                 true
             }
-            _ if !self.is_initialized() => {
-                debug_panic!(
-                    "Error in '{}' #{}: received LifeCycle::{:?} before receiving WidgetAdded.",
-                    self.inner.short_type_name(),
-                    self.state.id.to_raw(),
-                    event
-                );
-                return;
+            InternalLifeCycle::RouteWake { widgets } => {
+                if widgets.contains(&node.id) {
+                    call_widget_method_with_checks(&mut node, "lifecycle", |node| {
+                        let WidgetNode { widget, state, .. } = &mut *node.item;
+                        let mut inner_ctx = LifeCycleCtx {
+                            global_state,
+                            widget_state: state,
+                            widget_children: node.children.reborrow_mut(),
+                        };
+                        widget.lifecycle(&mut inner_ctx, &LifeCycle::Wake);
+                    });
+                }
+                let state = &node.item.state;
+                widgets.iter().any(|id| state.children.may_contain(id))
             }
-            LifeCycle::AnimFrame(_) => true,
-            LifeCycle::DisabledChanged(ancestors_disabled) => {
-                self.state.update_focus_chain = true;
-
-                let was_disabled = self.state.is_disabled();
-
-                self.state.is_explicitly_disabled = self.state.is_explicitly_disabled_new;
-                self.state.ancestor_disabled = *ancestors_disabled;
-
-                // the change direction (true -> false or false -> true) of our parent and ourself
-                // is always the same, or we dont change at all, because we stay disabled if either
-                // we or our parent are disabled.
-                was_disabled != self.state.is_disabled()
+            InternalLifeCycle::ParentWindowOrigin { mouse_pos } => {
+                let state = &mut node.item.state;
+                state.parent_window_origin = parent_state.window_origin();
+                state.needs_window_origin = false;
+                let mouse_pos = mouse_pos.map(|pos| LogicalPosition::new(pos.x, pos.y));
+                update_hot_state(&mut node, global_state, mouse_pos);
+                // TODO - self.state.is_hidden
+                true
             }
-            LifeCycle::BuildFocusChain => {
-                if self.state.update_focus_chain {
-                    // Replace has_focus to check if the value changed in the meantime
-                    let is_focused = parent_ctx.global_state.focused_widget == Some(self.state.id);
-                    self.state.has_focus = is_focused;
-
-                    self.state.focus_chain.clear();
-                    true
-                } else {
-                    false
-                }
+        },
+        LifeCycle::WidgetAdded => {
+            let WidgetNode { widget, state, .. } = &mut *node.item;
+            if !state.is_new {
+                // TODO - better warning.
+                warn!("Already initialized.");
             }
-            // This is called by children when going up the widget tree.
-            LifeCycle::RequestPanToChild(_) => false,
-        };
+            trace!(
+                "{} Received LifeCycle::WidgetAdded",
+                widget.short_type_name()
+            );
+            state.default_cursor = widget.interactivity().cursor();
 
-        // widget_pod is a reborrow of `self`
-        if call_inner {
-            self.call_widget_method_with_checks("lifecycle", |widget_pod| {
-                let mut inner_ctx = LifeCycleCtx {
-                    global_state: parent_ctx.global_state,
-                    widget_state: &mut widget_pod.state,
-                };
+            state.is_new = false;
+            state.update_focus_chain = true;
+            state.needs_layout = true;
+            state.needs_paint = true;
+            state.needs_window_origin = true;
+            state.needs_accessibility_update = true;
+            state.request_accessibility_update = true;
 
-                widget_pod.inner.lifecycle(&mut inner_ctx, event);
-            });
+            true
         }
-
-        if let Some(event) = extra_event.as_ref() {
+        LifeCycle::WidgetRemoved => {
+            let state = &node.item.state;
+            if state.is_removed {
+                trace!(
+                    "{} #{} was already removed",
+                    node.item.widget.short_type_name(),
+                    state.id.to_raw()
+                );
+                false
+            } else {
+                // Widgets which were never added have nothing to clean up
+                !state.is_new
+            }
+        }
+        _ if node.item.state.is_new => {
+            debug_panic!(
+                "Error in '{}' #{}: received LifeCycle::{:?} before receiving WidgetAdded.",
+                node.item.widget.short_type_name(),
+                node.id.to_raw(),
+                event
+            );
+            return;
+        }
+        LifeCycle::AnimFrame(_) => true,
+        // Only widgets which asked for idle work get it, and they ask again if
+        // they have more
+        LifeCycle::Idle(_) => std::mem::take(&mut node.item.state.request_idle),
+        LifeCycle::GpuDeviceLost | LifeCycle::GpuDeviceRecreated => true,
+        LifeCycle::DisabledChanged(ancestors_disabled) => {
+            let state = &mut node.item.state;
+            state.update_focus_chain = true;
+
+            let was_disabled = state.is_disabled();
+
+            state.is_explicitly_disabled = state.is_explicitly_disabled_new;
+            state.ancestor_disabled = *ancestors_disabled;
+
+            // the change direction (true -> false or false -> true) of our parent and ourself
+            // is always the same, or we dont change at all, because we stay disabled if either
+            // we or our parent are disabled.
+            was_disabled != state.is_disabled()
+        }
+        LifeCycle::BuildFocusChain => {
+            let state = &mut node.item.state;
+            if state.update_focus_chain {
+                // Replace has_focus to check if the value changed in the meantime
+                let is_focused = global_state.focused_widget == Some(state.id);
+                state.has_focus = is_focused;
+
+                state.focus_chain.clear();
+                true
+            } else {
+                false
+            }
+        }
+        // This is called by children when going up the widget tree.
+        LifeCycle::RequestPanToChild(_) => false,
+        // This is sent to its target by `RouteWake`, and isn't forwarded.
+        LifeCycle::Wake => false,
+    };
+
+    if call_inner {
+        call_widget_method_with_checks(&mut node, "lifecycle", |node| {
+            let WidgetNode { widget, state, .. } = &mut *node.item;
             let mut inner_ctx = LifeCycleCtx {
-                global_state: parent_ctx.global_state,
-                widget_state: &mut self.state,
+                global_state,
+                widget_state: state,
+                widget_children: node.children.reborrow_mut(),
             };
 
-            // We add a span so that inner logs are marked as being in an on_status_change pass
-            let _span = info_span!("on_status_change").entered();
-            self.inner.on_status_change(&mut inner_ctx, event);
+            widget.lifecycle(&mut inner_ctx, event);
+        });
+        if let LifeCycle::BuildFocusChain = event {
+            let WidgetNode { widget, state, .. } = &mut *node.item;
+            let id = state.id;
+            if widget.interactivity().is_focusable() && !state.focus_chain.contains(&id) {
+                state.focus_chain.insert(0, id);
+            }
         }
+        if let LifeCycle::WidgetAdded | LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded) =
+            event
+        {
+            update_children(&mut node, global_state);
+        }
+    }
 
-        // Sync our state with our parent's state after the event!
+    if let Some(event) = extra_event.as_ref() {
+        let WidgetNode { widget, state, .. } = &mut *node.item;
+        let mut inner_ctx = LifeCycleCtx {
+            global_state,
+            widget_state: state,
+            widget_children: node.children.reborrow_mut(),
+        };
 
-        match event {
-            // we need to (re)register children in case of one of the following events
-            LifeCycle::WidgetAdded | LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded) => {
-                self.state.children_changed = false;
-                parent_ctx.widget_state.children =
-                    parent_ctx.widget_state.children.union(self.state.children);
-                parent_ctx.register_child(self.id());
+        // We add a span so that inner logs are marked as being in an on_status_change pass
+        let _span = info_span!("on_status_change").entered();
+        widget.on_status_change(&mut inner_ctx, event);
+    }
+
+    // Sync our state with our parent's state after the event!
+
+    match event {
+        LifeCycle::WidgetRemoved if !node.item.state.is_removed => {
+            finish_removal(&mut node, global_state);
+        }
+        // we need to (re)register children in case of one of the following events
+        LifeCycle::WidgetAdded | LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded) => {
+            let state = &mut node.item.state;
+            state.children_changed = false;
+            parent_state.children = parent_state.children.union(state.children);
+            parent_state.children.add(&state.id);
+        }
+        LifeCycle::DisabledChanged(_)
+        | LifeCycle::Internal(InternalLifeCycle::RouteDisabledChanged) => {
+            let state = &mut node.item.state;
+            state.children_disabled_changed = false;
+
+            if state.is_disabled() && state.has_focus {
+                // This may gets overwritten. This is ok because it still ensures that a
+                // FocusChange is routed after we updated the focus-chain.
+                global_state.next_focused_widget = None;
             }
-            LifeCycle::DisabledChanged(_)
-            | LifeCycle::Internal(InternalLifeCycle::RouteDisabledChanged) => {
-                self.state.children_disabled_changed = false;
-
-                if self.state.is_disabled() && self.state.has_focus {
-                    // This may gets overwritten. This is ok because it still ensures that a
-                    // FocusChange is routed after we updated the focus-chain.
-                    parent_ctx.global_state.next_focused_widget = None;
-                }
 
-                // Delete changes of disabled state that happened during DisabledChanged to avoid
-                // recursions.
-                self.state.is_explicitly_disabled_new = self.state.is_explicitly_disabled;
+            // Delete changes of disabled state that happened during DisabledChanged to avoid
+            // recursions.
+            state.is_explicitly_disabled_new = state.is_explicitly_disabled;
+        }
+        // Update focus-chain of our parent
+        LifeCycle::BuildFocusChain => {
+            let state = &mut node.item.state;
+            state.update_focus_chain = false;
+
+            // had_focus is the old focus value. state.has_focus was replaced with parent_ctx.is_focused().
+            // Therefore if had_focus is true but state.has_focus is false then the widget which is
+            // currently focused is not part of the functional tree anymore
+            // (Lifecycle::BuildFocusChain.should_propagate_to_hidden() is false!) and should
+            // resign the focus.
+            if had_focus && !state.has_focus {
+                // Not sure about this logic, might remove
+                global_state.next_focused_widget = None;
             }
-            // Update focus-chain of our parent
-            LifeCycle::BuildFocusChain => {
-                self.state.update_focus_chain = false;
-
-                // had_focus is the old focus value. state.has_focus was replaced with parent_ctx.is_focused().
-                // Therefore if had_focus is true but state.has_focus is false then the widget which is
-                // currently focused is not part of the functional tree anymore
-                // (Lifecycle::BuildFocusChain.should_propagate_to_hidden() is false!) and should
-                // resign the focus.
-                if had_focus && !self.state.has_focus {
-                    // Not sure about this logic, might remove
-                    parent_ctx.global_state.next_focused_widget = None;
-                }
-                self.state.has_focus = had_focus;
+            state.has_focus = had_focus;
 
-                if !self.state.is_disabled() {
-                    parent_ctx
-                        .widget_state
-                        .focus_chain
-                        .extend(&self.state.focus_chain);
-                }
+            if !state.is_disabled() {
+                parent_state.focus_chain.extend(&state.focus_chain);
             }
-            _ => (),
         }
+        _ => (),
+    }
 
-        parent_ctx.widget_state.merge_up(&mut self.state);
+    parent_state.merge_up(&mut node.item.state);
 
-        parent_ctx
-            .global_state
-            .debug_logger
-            .update_widget_state(self.as_dyn());
-        parent_ctx
-            .global_state
-            .debug_logger
-            .push_log(false, "updated state");
+    log_widget_state(&node, global_state);
 
-        parent_ctx.global_state.debug_logger.pop_span();
+    global_state.debug_logger.pop_span();
+}
+
+/// Bring the children of `node` in the tree in line with the widget's children,
+/// once they've all been added.
+///
+/// Children which the widget dropped without removing them are removed, and the
+/// rest are put in the order the widget holds them in.
+fn update_children(node: &mut ArenaMut<'_, WidgetNode>, global_state: &mut RenderRootState) {
+    let WidgetNode { widget, state, .. } = &mut *node.item;
+    let children_ids = widget.children_ids();
+    let dropped: Vec<_> = node
+        .children
+        .ids()
+        .filter(|id| !children_ids.contains(id))
+        .collect();
+    for id in dropped {
+        trace!(
+            "Widget '{}' #{} dropped child #{} without removing it",
+            widget.short_type_name(),
+            state.id.to_raw(),
+            id.to_raw(),
+        );
+        let mut ctx = LifeCycleCtx {
+            global_state,
+            widget_state: state,
+            widget_children: node.children.reborrow_mut(),
+        };
+        WidgetPod::inserted(id).lifecycle(&mut ctx, &LifeCycle::WidgetRemoved);
+        node.children.remove_child(id);
     }
+    node.children.reorder(&children_ids);
+}
 
+/// Tear down the widget once it and its descendants have handled
+/// [`LifeCycle::WidgetRemoved`], releasing everything the framework tracks for it.
+///
+/// The widget is removed from the tree by its parent.
+fn finish_removal(node: &mut ArenaMut<'_, WidgetNode>, global_state: &mut RenderRootState) {
+    let WidgetNode { widget, state, .. } = &mut *node.item;
+    if !state.is_new {
+        // Descendants must be torn down first, so that they can still reach
+        // their parents' resources
+        for child in node.children.as_ref().iter() {
+            if !child.item.state.is_removed {
+                debug_panic!(
+                    "Error in '{}' #{}: child widget '{}' #{} wasn't sent LifeCycle::WidgetRemoved",
+                    widget.short_type_name(),
+                    state.id.to_raw(),
+                    child.item.widget.short_type_name(),
+                    child.id.to_raw(),
+                );
+            }
+        }
+
+        let mut ctx = LifeCycleCtx {
+            global_state,
+            widget_state: state,
+            widget_children: node.children.reborrow_mut(),
+        };
+        widget.on_removed(&mut ctx);
+    }
+
+    let id = state.id;
+    global_state.widget_timings.remove(id);
+    global_state.gpu_resources.remove_owned_by(id);
+    global_state.raster_cache.remove(id);
+    global_state.remove_overlays(id);
+    if state.has_focus {
+        global_state.next_focused_widget = None;
+    }
+    // The widget is about to be dropped, so it mustn't keep asking for frames
+    // after it has been merged into its parent
+    state.request_anim = false;
+    state.request_idle = false;
+    state.is_removed = true;
+}
+
+impl<W: Widget> WidgetPod<W> {
     // --- LAYOUT ---
 
     /// Compute layout of a widget.
@@ -822,80 +1048,101 @@ impl<W: Widget> WidgetPod<W> {
     ///
     /// [`layout`]: trait.Widget.html#tymethod.layout
     pub fn layout(&mut self, parent_ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
-        let _span = self.inner.make_trace_span().entered();
+        let mut node = self.node_mut(parent_ctx.widget_children.reborrow_mut());
+        let _span = node.item.widget.make_trace_span().entered();
 
         // TODO #11
         parent_ctx
             .global_state
             .debug_logger
-            .push_span(self.inner.short_type_name());
+            .push_span(node.item.widget.short_type_name());
 
-        if self.state.is_stashed {
+        if node.item.state.is_stashed {
             debug_panic!(
                 "Error in '{}' #{}: trying to compute layout of stashed widget.",
-                self.inner.short_type_name(),
-                self.state().id.to_raw(),
+                node.item.widget.short_type_name(),
+                node.id.to_raw(),
             );
             return Size::ZERO;
         }
 
         // TODO - explain this
-        self.mark_as_visited();
-        self.check_initialized("layout");
+        mark_as_visited(&node);
+        check_initialized(&node, "layout");
 
-        self.state.needs_layout = false;
-        self.state.is_expecting_place_child_call = true;
-        // TODO - Not everything that has been re-laid out needs to be repainted.
-        self.state.needs_paint = true;
-        self.state.request_accessibility_update = true;
-        self.state.needs_accessibility_update = true;
+        let state = &mut node.item.state;
+        state.layout_requested = state.needs_layout;
+        state.needs_layout = false;
+        state.is_expecting_place_child_call = true;
+        state.request_accessibility_update = true;
+        state.needs_accessibility_update = true;
 
-        bc.debug_check(self.inner.short_type_name());
+        bc.debug_check(node.item.widget.short_type_name());
 
-        self.state.local_paint_rect = Rect::ZERO;
-
-        let new_size = self.call_widget_method_with_checks("layout", |widget_pod| {
-            // widget_pod is a reborrow of `self`
+        node.item.state.local_paint_rect = Rect::ZERO;
 
+        parent_ctx.global_state.widget_timings.start();
+        let new_size = call_widget_method_with_checks(&mut node, "layout", |node| {
+            let WidgetNode { widget, state, .. } = &mut *node.item;
             let mut inner_ctx = LayoutCtx {
-                widget_state: &mut widget_pod.state,
+                widget_state: state,
+                widget_children: node.children.reborrow_mut(),
                 global_state: parent_ctx.global_state,
                 mouse_pos: parent_ctx.mouse_pos,
             };
 
-            widget_pod.inner.layout(&mut inner_ctx, bc)
+            widget.layout(&mut inner_ctx, bc)
         });
+        finish_timing(&node, parent_ctx.global_state, "layout");
 
-        self.state.local_paint_rect = self
-            .state
+        let WidgetNode { widget, state, .. } = &mut *node.item;
+        state.local_paint_rect = state
             .local_paint_rect
-            .union(new_size.to_rect() + self.state.paint_insets);
+            .union(new_size.to_rect() + state.paint_insets);
+        if !state.painted_bounds.is_empty() {
+            state.local_paint_rect = state.local_paint_rect.union(state.painted_bounds);
+        }
+
+        // Portals clip their content, so it can't receive events outside of them.
+        state.local_bounding_rect = new_size.to_rect();
+        if !state.is_portal {
+            for child in node.children.as_ref().iter() {
+                let child_state = &child.item.state;
+                if !child_state.is_stashed {
+                    let child_rect = child_state.local_bounding_rect + child_state.origin.to_vec2();
+                    state.local_bounding_rect = state.local_bounding_rect.union(child_rect);
+                }
+            }
+        }
 
         if cfg!(debug_assertions) {
-            for child in self.inner.children() {
-                if child.state().is_expecting_place_child_call {
+            for child in node.children.as_ref().iter() {
+                let child_state = &child.item.state;
+                if child_state.is_expecting_place_child_call {
                     debug_panic!(
                         "Error in '{}' #{}: missing call to place_child method for child widget '{}' #{}. During layout pass, if a widget calls WidgetPod::layout() on its child, it then needs to call LayoutCtx::place_child() on the same child.",
-                        self.inner.short_type_name(),
-                        self.state().id.to_raw(),
-                        child.deref().short_type_name(),
-                        child.state().id.to_raw(),
+                        widget.short_type_name(),
+                        state.id.to_raw(),
+                        child.item.widget.short_type_name(),
+                        child_state.id.to_raw(),
                     );
                 }
 
                 // TODO - This check might be redundant with the code updating local_paint_rect
-                let child_rect = child.state().paint_rect();
-                if !rect_contains(&self.state.local_paint_rect, &child_rect)
-                    && !self.state.is_portal
+                let child_rect = child_state.paint_rect();
+                // Stashed children keep the rect of wherever they were last placed
+                if !rect_contains(&state.local_paint_rect, &child_rect)
+                    && !state.is_portal
+                    && !child_state.is_stashed
                 {
                     debug_panic!(
                         "Error in '{}' #{}: paint_rect {:?} doesn't contain paint_rect {:?} of child widget '{}' #{}",
-                        self.inner.short_type_name(),
-                        self.state().id.to_raw(),
-                        self.state.local_paint_rect,
+                        widget.short_type_name(),
+                        state.id.to_raw(),
+                        state.local_paint_rect,
                         child_rect,
-                        child.deref().short_type_name(),
-                        child.state().id.to_raw(),
+                        child.item.widget.short_type_name(),
+                        child_state.id.to_raw(),
                     );
                 }
             }
@@ -911,130 +1158,170 @@ impl<W: Widget> WidgetPod<W> {
         // size is (0,0)
         // See issue #4
 
-        parent_ctx.widget_state.merge_up(&mut self.state);
-        self.state.size = new_size;
-        self.log_layout_issues(new_size);
+        // Widgets which were only laid out because an ancestor was keep their cached
+        // fragments, so that eg opening a popup doesn't repaint the whole window
+        if state.layout_requested
+            || parent_ctx.widget_state.layout_requested
+            || new_size != state.size
+        {
+            state.needs_paint = true;
+        }
 
-        parent_ctx
-            .global_state
-            .debug_logger
-            .update_widget_state(self.as_dyn());
-        parent_ctx
-            .global_state
-            .debug_logger
-            .push_log(false, "updated state");
+        parent_ctx.widget_state.merge_up(state);
+        state.size = new_size;
+        log_layout_issues(&**widget, new_size);
+
+        log_widget_state(&node, parent_ctx.global_state);
 
         parent_ctx.global_state.debug_logger.pop_span();
 
         new_size
     }
 
-    fn log_layout_issues(&self, size: Size) {
-        if size.width.is_infinite() {
-            let name = self.inner.type_name();
-            warn!("Widget `{}` has an infinite width.", name);
-        }
-        if size.height.is_infinite() {
-            let name = self.inner.type_name();
-            warn!("Widget `{}` has an infinite height.", name);
-        }
-    }
-
     // --- PAINT ---
 
     /// Paint the widget, translating it by the origin of its layout rectangle.
     ///
-    /// This will recursively paint widgets, stopping if a widget's layout
-    /// rect is outside of the currently visible region.
+    /// The widget's transform, if any, is applied on top of that translation.
+    /// See [`set_child_transform`](crate::LayoutCtx::set_child_transform).
+    ///
+    /// This will recursively paint widgets, skipping those whose paint rect is
+    /// outside of the currently visible region (see [`PaintCtx::clip`]).
     pub fn paint(&mut self, parent_ctx: &mut PaintCtx, scene: &mut Scene) {
-        let _span = self.inner.make_trace_span().entered();
+        let mut node = self.node_mut(parent_ctx.widget_children.reborrow_mut());
+        let _span = node.item.widget.make_trace_span().entered();
 
-        if self.state.is_stashed {
+        if node.item.state.is_stashed {
             debug_panic!(
                 "Error in '{}' #{}: trying to paint stashed widget.",
-                self.inner.short_type_name(),
-                self.state().id.to_raw(),
+                node.item.widget.short_type_name(),
+                node.id.to_raw(),
             );
             return;
         }
 
         trace!(
             "Painting widget '{}' #{}",
-            self.inner.short_type_name(),
-            self.state.id.to_raw()
+            node.item.widget.short_type_name(),
+            node.id.to_raw()
         );
 
         // TODO - explain this
-        self.mark_as_visited();
-        self.check_initialized("paint");
+        mark_as_visited(&node);
+        check_initialized(&node, "paint");
+
+        let state = &mut node.item.state;
+        let transform = Affine::translate(state.origin.to_vec2()) * state.transform;
+        let clip = transform.inverse().transform_rect_bbox(parent_ctx.clip);
+        let is_visible = rect_overlaps(&state.local_paint_rect, &clip);
+        let visible_paint_rect = if is_visible {
+            state.local_paint_rect.intersect(clip)
+        } else {
+            Rect::ZERO
+        };
+        if visible_paint_rect != state.visible_paint_rect {
+            // Cached fragments of this widget and its children may be missing
+            // the parts which just became visible.
+            state.visible_paint_rect = visible_paint_rect;
+            state.needs_paint = true;
+        }
+        if !is_visible {
+            trace!(
+                "Skipping widget '{}' #{} outside of the visible region",
+                node.item.widget.short_type_name(),
+                node.id.to_raw()
+            );
+            return;
+        }
+
+        if parent_ctx.global_state.paint_debug != PaintDebug::Off {
+            let window_rect = Rect::from_origin_size(state.window_origin(), state.size);
+            parent_ctx
+                .global_state
+                .paint_order
+                .push((node.id, window_rect));
+        }
 
-        if self.state.needs_paint {
-            self.state.needs_paint = false;
-            self.call_widget_method_with_checks("paint", |widget_pod| {
+        // While debugging paint, the whole tree is painted so that every widget is
+        // recorded, not only those whose cached paint was out of date.
+        if node.item.state.needs_paint || parent_ctx.global_state.paint_debug != PaintDebug::Off {
+            node.item.state.needs_paint = false;
+            parent_ctx.global_state.remove_overlays(node.id);
+            let painted_before = parent_ctx.global_state.frame_stats.painted_widgets();
+            parent_ctx.global_state.widget_timings.start();
+            call_widget_method_with_checks(&mut node, "paint", |node| {
+                let WidgetNode {
+                    widget,
+                    state,
+                    fragment,
+                } = &mut *node.item;
                 // TODO - Handle invalidation regions
                 let mut inner_ctx = PaintCtx {
                     global_state: parent_ctx.global_state,
-                    widget_state: &widget_pod.state,
+                    widget_state: state,
+                    widget_children: node.children.reborrow_mut(),
+                    clip,
                     depth: parent_ctx.depth + 1,
                     debug_paint: parent_ctx.debug_paint,
                     debug_widget: parent_ctx.debug_widget,
+                    painted_bounds: None,
                 };
 
-                widget_pod.fragment.reset();
-                widget_pod
-                    .inner
-                    .paint(&mut inner_ctx, &mut widget_pod.fragment);
+                fragment.reset();
+                widget.paint(&mut inner_ctx, fragment);
+                let painted_bounds = inner_ctx.painted_bounds.unwrap_or(Rect::ZERO);
+                record_painted_bounds(&**widget, state, parent_ctx.global_state, painted_bounds);
 
                 if parent_ctx.debug_paint {
-                    widget_pod.debug_paint_layout_bounds(widget_pod.state.size);
+                    debug_paint_layout_bounds(fragment, state);
                 }
             });
+            finish_timing(&node, parent_ctx.global_state, "paint");
+            let state = &node.item.state;
+            let frame_stats = &mut parent_ctx.global_state.frame_stats;
+            let painted_children = frame_stats.painted_widgets() != painted_before;
+            let window_paint_rect = state.local_paint_rect.union(state.painted_bounds)
+                + state.window_origin().to_vec2();
+            frame_stats.add_painted_widget(window_paint_rect, painted_children);
         }
 
-        let transform = Affine::translate(self.state.origin.to_vec2());
-        scene.append(&self.fragment, Some(transform));
-    }
-
-    fn debug_paint_layout_bounds(&mut self, size: Size) {
-        const BORDER_WIDTH: f64 = 1.0;
-        let rect = size.to_rect().inset(BORDER_WIDTH / -2.0);
-        let id = self.id().to_raw();
-        let color = get_debug_color(id);
-        let scene = &mut self.fragment;
-        stroke(scene, &rect, color, BORDER_WIDTH);
+        scene.append(&node.item.fragment, Some(transform));
     }
 
     pub fn accessibility(&mut self, parent_ctx: &mut AccessCtx) {
-        let _span = self.inner.make_trace_span().entered();
+        let mut node = self.node_mut(parent_ctx.widget_children.reborrow_mut());
+        let _span = node.item.widget.make_trace_span().entered();
 
         // TODO
         // if self.state.is_stashed {}
 
         // TODO - explain this
-        self.mark_as_visited();
-        self.check_initialized("accessibility");
+        mark_as_visited(&node);
+        check_initialized(&node, "accessibility");
 
         // If this widget or a child has requested an accessibility update,
         // or if AccessKit has requested a full rebuild,
         // we call the accessibility method on this widget.
-        if parent_ctx.rebuild_all || self.state.request_accessibility_update {
+        if parent_ctx.rebuild_all || node.item.state.request_accessibility_update {
             trace!(
                 "Building accessibility node for widget '{}' #{}",
-                self.inner.short_type_name(),
-                self.state.id.to_raw()
+                node.item.widget.short_type_name(),
+                node.id.to_raw()
             );
 
-            self.call_widget_method_with_checks("accessibility", |widget_pod| {
-                let current_node = widget_pod.build_access_node(parent_ctx.scale_factor);
+            call_widget_method_with_checks(&mut node, "accessibility", |node| {
+                let WidgetNode { widget, state, .. } = &mut *node.item;
+                let current_node = build_access_node(&**widget, state, parent_ctx.scale_factor);
                 let mut inner_ctx = AccessCtx {
                     global_state: parent_ctx.global_state,
-                    widget_state: &mut widget_pod.state,
+                    widget_state: state,
+                    widget_children: node.children.reborrow_mut(),
                     tree_update: parent_ctx.tree_update,
                     current_node,
                     rebuild_all: parent_ctx.rebuild_all,
                     scale_factor: parent_ctx.scale_factor,
                 };
-                widget_pod.inner.accessibility(&mut inner_ctx);
+                widget.accessibility(&mut inner_ctx);
 
                 let id: NodeId = inner_ctx.widget_state.id.into();
                 trace!(
@@ -1050,37 +1337,82 @@ impl<W: Widget> WidgetPod<W> {
             });
         }
 
-        self.state.request_accessibility_update = false;
-        self.state.needs_accessibility_update = false;
+        let state = &mut node.item.state;
+        state.request_accessibility_update = false;
+        state.needs_accessibility_update = false;
     }
+}
 
-    fn build_access_node(&mut self, scale_factor: f64) -> NodeBuilder {
-        let mut node = NodeBuilder::new(self.inner.accessibility_role());
-        node.set_bounds(to_accesskit_rect(
-            self.state.window_layout_rect(),
-            scale_factor,
-        ));
-
-        node.set_children(
-            self.inner
-                .children()
-                .iter()
-                .map(|pod| pod.id().into())
-                .collect::<Vec<NodeId>>(),
+fn log_layout_issues(widget: &dyn Widget, size: Size) {
+    if size.width.is_infinite() {
+        let name = widget.type_name();
+        warn!("Widget `{}` has an infinite width.", name);
+    }
+    if size.height.is_infinite() {
+        let name = widget.type_name();
+        warn!("Widget `{}` has an infinite height.", name);
+    }
+}
+
+/// Store the bounds the widget reported drawing in, and check that they are
+/// within the paint rect used to cull and repaint it.
+fn record_painted_bounds(
+    widget: &dyn Widget,
+    state: &mut WidgetState,
+    global_state: &mut RenderRootState,
+    bounds: Rect,
+) {
+    if !bounds.is_empty() && !rect_contains(&state.local_paint_rect, &bounds) {
+        trace!(
+            "'{}' #{} painted {:?} outside of its paint rect {:?}",
+            widget.short_type_name(),
+            state.id.to_raw(),
+            bounds,
+            state.local_paint_rect,
         );
+        global_state.paint_rect_outgrown = true;
+    }
+    state.painted_bounds = bounds;
+}
 
-        if self.state.is_hot {
-            node.set_hovered();
-        }
-        if self.state.is_disabled() {
-            node.set_disabled();
-        }
-        if self.state.is_stashed {
-            node.set_hidden();
-        }
+fn debug_paint_layout_bounds(scene: &mut Scene, state: &WidgetState) {
+    const BORDER_WIDTH: f64 = 1.0;
+    let rect = state.size.to_rect().inset(BORDER_WIDTH / -2.0);
+    let color = get_debug_color(state.id.to_raw());
+    stroke(scene, &rect, color, BORDER_WIDTH);
+}
 
-        node
+fn build_access_node(widget: &dyn Widget, state: &WidgetState, scale_factor: f64) -> NodeBuilder {
+    let mut node = NodeBuilder::new(widget.accessibility_role());
+    node.set_bounds(to_accesskit_rect(state.window_layout_rect(), scale_factor));
+
+    node.set_children(
+        widget
+            .children_ids()
+            .iter()
+            .map(|id| (*id).into())
+            .collect::<Vec<NodeId>>(),
+    );
+
+    if state.is_hot {
+        node.set_hovered();
+    }
+    if state.is_disabled() {
+        node.set_disabled();
+    }
+    if state.is_stashed {
+        node.set_hidden();
     }
+    let interactivity = widget.interactivity();
+    if interactivity.is_focusable() {
+        node.add_action(accesskit::Action::Focus);
+    }
+    if interactivity == Interactivity::Clickable {
+        node.add_action(accesskit::Action::Default);
+        node.set_default_action_verb(DefaultActionVerb::Click);
+    }
+
+    node
 }
 
 fn to_accesskit_rect(r: Rect, scale_factor: f64) -> accesskit::Rect {
@@ -1088,6 +1420,23 @@ fn to_accesskit_rect(r: Rect, scale_factor: f64) -> accesskit::Rect {
     accesskit::Rect::new(s * r.x0, s * r.y0, s * r.x1, s * r.y1)
 }
 
+// TODO - This doesn't handle the case where multiple cursors
+// are over the same widget
+/// The position of the pointer which may make widgets hot, if any.
+fn pointer_position(event: &PointerEvent) -> Option<LogicalPosition<f64>> {
+    match event {
+        PointerEvent::PointerDown(_, pointer_state) => Some(pointer_state.position),
+        PointerEvent::PointerUp(_, pointer_state) => Some(pointer_state.position),
+        PointerEvent::PointerMove(pointer_state) => Some(pointer_state.position),
+        PointerEvent::PointerEnter(pointer_state) => Some(pointer_state.position),
+        PointerEvent::PointerLeave(_) => None,
+        PointerEvent::MouseWheel(_, pointer_state) => Some(pointer_state.position),
+        PointerEvent::HoverFile(_, _) => None,
+        PointerEvent::DropFile(_, _) => None,
+        PointerEvent::HoverFileCancel(_) => None,
+    }
+}
+
 // TODO - negative rects?
 /// Return `true` if all of `smaller` is within `larger`.
 fn rect_contains(larger: &Rect, smaller: &Rect) -> bool {
@@ -1096,3 +1445,11 @@ fn rect_contains(larger: &Rect, smaller: &Rect) -> bool {
         && smaller.y0 >= larger.y0
         && smaller.y1 <= larger.y1
 }
+
+/// Return `true` if `a` and `b` overlap or touch.
+///
+/// Unlike checking that their intersection is non-empty, this is also `true`
+/// for zero-sized rects within the other rect.
+fn rect_overlaps(a: &Rect, b: &Rect) -> bool {
+    a.x0 <= b.x1 && b.x0 <= a.x1 && a.y0 <= b.y1 && b.y0 <= a.y1
+}
@@ -6,7 +6,8 @@ use std::ops::Deref;
 use smallvec::SmallVec;
 
 use crate::kurbo::Point;
-use crate::{Widget, WidgetId, WidgetState};
+use crate::widget_tree::{ArenaRef, ArenaRefChildren, WidgetNode};
+use crate::{Widget, WidgetId, WidgetPod, WidgetState};
 
 /// A rich reference to a [`Widget`].
 ///
@@ -24,6 +25,7 @@ use crate::{Widget, WidgetId, WidgetState};
 pub struct WidgetRef<'w, W: Widget + ?Sized> {
     widget_state: &'w WidgetState,
     widget: &'w W,
+    children: ArenaRefChildren<'w, WidgetNode>,
 }
 
 // --- TRAIT IMPLS ---
@@ -34,6 +36,7 @@ impl<'w, W: Widget + ?Sized> Clone for WidgetRef<'w, W> {
         Self {
             widget_state: self.widget_state,
             widget: self.widget,
+            children: self.children,
         }
     }
 }
@@ -49,7 +52,7 @@ impl<'w, W: Widget + ?Sized> std::fmt::Debug for WidgetRef<'w, W> {
             std::borrow::Cow::Borrowed(widget_name)
         };
 
-        let children = self.widget.children();
+        let children = self.children();
 
         if children.is_empty() {
             f.write_str(&display_name)
@@ -74,10 +77,15 @@ impl<'w, W: Widget + ?Sized> Deref for WidgetRef<'w, W> {
 // --- IMPLS ---
 
 impl<'w, W: Widget + ?Sized> WidgetRef<'w, W> {
-    pub(crate) fn new(widget_state: &'w WidgetState, widget: &'w W) -> Self {
+    pub(crate) fn new(
+        widget_state: &'w WidgetState,
+        widget: &'w W,
+        children: ArenaRefChildren<'w, WidgetNode>,
+    ) -> Self {
         WidgetRef {
             widget_state,
             widget,
+            children,
         }
     }
 
@@ -101,8 +109,28 @@ impl<'w, W: Widget + ?Sized> WidgetRef<'w, W> {
         Some(WidgetRef {
             widget_state: self.widget_state,
             widget: self.widget.as_any().downcast_ref()?,
+            children: self.children,
         })
     }
+
+    /// Return widget's children.
+    pub fn children(&self) -> SmallVec<[WidgetRef<'w, dyn Widget>; 16]> {
+        self.children.iter().map(WidgetRef::from_node).collect()
+    }
+
+    /// Return which child, if any, has the given `pos` in its layout rect.
+    ///
+    /// The child return is a direct child, not eg a grand-child. The position is in
+    /// relative coordinates. (Eg `(0,0)` is the top-left corner of the widget).
+    pub fn get_child_at_pos(&self, pos: Point) -> Option<WidgetRef<'w, dyn Widget>> {
+        // layout_rect() is in parent coordinate space
+        // Reversing means the first of several children with the same z-index wins.
+        self.children()
+            .into_iter()
+            .filter(|child| child.state().layout_rect().contains(pos))
+            .rev()
+            .max_by_key(|child| child.state().z_index)
+    }
 }
 
 impl<'w, W: Widget> WidgetRef<'w, W> {
@@ -111,14 +139,27 @@ impl<'w, W: Widget> WidgetRef<'w, W> {
         WidgetRef {
             widget_state: self.widget_state,
             widget: self.widget,
+            children: self.children,
         }
     }
+
+    /// Return a `WidgetRef` to the child in `child`, which must be a pod held by
+    /// this widget.
+    pub(crate) fn get_child<Child: Widget>(
+        &self,
+        child: &'w WidgetPod<Child>,
+    ) -> WidgetRef<'w, Child> {
+        child.as_ref_in(self.children)
+    }
 }
 
 impl<'w> WidgetRef<'w, dyn Widget> {
-    /// Return widget's children.
-    pub fn children(&self) -> SmallVec<[WidgetRef<'w, dyn Widget>; 16]> {
-        self.widget.children()
+    pub(crate) fn from_node(node: ArenaRef<'w, WidgetNode>) -> Self {
+        WidgetRef {
+            widget_state: &node.item.state,
+            widget: &*node.item.widget,
+            children: node.children,
+        }
     }
 
     /// Recursively find child widget with given id.
@@ -145,7 +186,7 @@ impl<'w> WidgetRef<'w, dyn Widget> {
         }
 
         loop {
-            if let Some(child) = innermost_widget.deref().get_child_at_pos(pos) {
+            if let Some(child) = innermost_widget.get_child_at_pos(pos) {
                 pos -= innermost_widget.state().layout_rect().origin().to_vec2();
                 innermost_widget = child;
             } else {
@@ -186,7 +227,7 @@ impl<'w> WidgetRef<'w, dyn Widget> {
             );
         }
 
-        for child in self.widget.children() {
+        for child in self.children() {
             child.debug_validate(after_layout);
 
             if !self.state().children.may_contain(&child.state().id) {
@@ -209,12 +250,12 @@ mod tests {
     use super::*;
     use crate::testing::{widget_ids, TestHarness, TestWidgetExt as _};
     use crate::widget::{Button, Label};
-    use crate::{Widget, WidgetPod};
+    use crate::Widget;
 
     #[test]
     fn downcast_ref() {
-        let label = WidgetPod::new(Label::new("Hello"));
-        let dyn_widget: WidgetRef<dyn Widget> = label.as_dyn();
+        let harness = TestHarness::create(Label::new("Hello"));
+        let dyn_widget: WidgetRef<dyn Widget> = harness.root_widget();
 
         let label = dyn_widget.downcast::<Label>();
         assert_matches!(label, Some(_));
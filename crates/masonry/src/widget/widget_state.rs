@@ -8,8 +8,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use winit::window::CursorIcon;
 
 use crate::bloom::Bloom;
-use crate::kurbo::{Insets, Point, Rect, Size};
-use crate::text_helpers::TextFieldRegistration;
+use crate::kurbo::{Affine, Insets, Point, Rect, Size};
+use crate::text_helpers::{InputHint, TextFieldRegistration};
 use crate::widget::CursorChange;
 use crate::WidgetId;
 
@@ -55,6 +55,22 @@ pub struct WidgetState {
     // TODO - Document
     // The computed paint rect, in local coordinates.
     pub(crate) local_paint_rect: Rect,
+    /// The area the widget itself drew in when it was last painted, if it reported
+    /// it with `PaintCtx::set_painted_bounds`, in local coordinates.
+    ///
+    /// The next layout pass includes it in the paint rect.
+    pub(crate) painted_bounds: Rect,
+    /// Where the widget's text caret is, if it has one, in local coordinates.
+    ///
+    /// Set with `LayoutCtx::set_caret_rect`.
+    pub(crate) caret_rect: Option<Rect>,
+    /// The kind of text the widget takes, if it's a text input.
+    ///
+    /// Set with `LayoutCtx::set_input_hint`.
+    pub(crate) input_hint: Option<InputHint>,
+    /// The union of the layout rects of the widget and its descendants, in local
+    /// coordinates. Pointer events outside of it can't reach the widget's subtree.
+    pub(crate) local_bounding_rect: Rect,
     /// The offset of the baseline relative to the bottom of the widget.
     ///
     /// In general, this will be zero; the bottom of the widget will be considered
@@ -63,6 +79,17 @@ pub struct WidgetState {
     pub(crate) baseline_offset: f64,
     // TODO - Document
     pub(crate) is_portal: bool,
+    /// An extra transform applied to the widget when painting, on top of the
+    /// translation to `origin`. It does not affect layout.
+    pub(crate) transform: Affine,
+    /// The paint order of the widget relative to its siblings, for containers
+    /// which paint their children with [`PaintCtx::paint_children`].
+    ///
+    /// [`PaintCtx::paint_children`]: crate::PaintCtx::paint_children
+    pub(crate) z_index: i32,
+    /// The part of the paint rect which was visible when the widget was last
+    /// painted, in local coordinates. Empty if the widget was culled.
+    pub(crate) visible_paint_rect: Rect,
 
     // --- PASSES ---
 
@@ -73,6 +100,10 @@ pub struct WidgetState {
     // True until a WidgetAdded event is received.
     pub(crate) is_new: bool,
 
+    /// `true` once the widget has been removed from the tree, so that it's only torn
+    /// down once.
+    pub(crate) is_removed: bool,
+
     // `true` if a descendent of this widget changed its disabled state and should receive
     // LifeCycle::DisabledChanged or InternalLifeCycle::RouteDisabledChanged
     pub(crate) children_disabled_changed: bool,
@@ -82,6 +113,12 @@ pub struct WidgetState {
     pub(crate) is_explicitly_disabled_new: bool,
 
     pub(crate) needs_layout: bool,
+    /// Whether the last layout pass was requested by the widget or a descendant,
+    /// rather than only reaching it through its parent.
+    ///
+    /// Children of such a widget are repainted, since their parent may have changed
+    /// them directly. Other widgets are only repainted if their size changed.
+    pub(crate) layout_requested: bool,
     pub(crate) needs_paint: bool,
     pub(crate) needs_accessibility_update: bool,
 
@@ -91,6 +128,9 @@ pub struct WidgetState {
     /// Any descendant has requested an animation frame.
     pub(crate) request_anim: bool,
 
+    /// Any descendant has requested idle work.
+    pub(crate) request_idle: bool,
+
     /// Any descendant has requested an accessibility update.
     pub(crate) request_accessibility_update: bool,
 
@@ -106,6 +146,9 @@ pub struct WidgetState {
     /// `cursor_change`, which is persistent).
     // TODO - Remove and handle in WidgetRoot instead
     pub(crate) cursor: Option<CursorIcon>,
+    /// The cursor of the widget's [`Interactivity`](crate::widget::Interactivity),
+    /// shown when neither it nor a child sets one.
+    pub(crate) default_cursor: Option<CursorIcon>,
 
     pub(crate) text_registrations: Vec<TextFieldRegistration>,
 
@@ -156,7 +199,14 @@ impl WidgetState {
             is_expecting_place_child_call: false,
             paint_insets: Insets::ZERO,
             local_paint_rect: Rect::ZERO,
+            painted_bounds: Rect::ZERO,
+            caret_rect: None,
+            input_hint: None,
+            local_bounding_rect: Rect::ZERO,
             is_portal: false,
+            transform: Affine::IDENTITY,
+            z_index: 0,
+            visible_paint_rect: Rect::ZERO,
             is_new: true,
             children_disabled_changed: false,
             ancestor_disabled: false,
@@ -164,6 +214,7 @@ impl WidgetState {
             baseline_offset: 0.0,
             is_hot: false,
             needs_layout: false,
+            layout_requested: false,
             needs_paint: false,
             needs_accessibility_update: false,
             needs_window_origin: false,
@@ -171,12 +222,15 @@ impl WidgetState {
             has_active: false,
             has_focus: false,
             request_anim: false,
+            request_idle: false,
+            is_removed: false,
             request_accessibility_update: false,
             focus_chain: Vec::new(),
             children: Bloom::new(),
             children_changed: false,
             cursor_change: CursorChange::Default,
             cursor: None,
+            default_cursor: None,
             is_explicitly_disabled_new: false,
             text_registrations: Vec::new(),
             update_focus_chain: false,
@@ -220,6 +274,7 @@ impl WidgetState {
         self.needs_paint |= child_state.needs_paint;
         self.needs_window_origin |= child_state.needs_window_origin;
         self.request_anim |= child_state.request_anim;
+        self.request_idle |= child_state.request_idle;
         self.request_accessibility_update |= child_state.request_accessibility_update;
         self.children_disabled_changed |= child_state.children_disabled_changed;
         self.children_disabled_changed |=
@@ -251,7 +306,10 @@ impl WidgetState {
     /// in that case there will be nothing in the `cursor` field (as `merge_up`
     /// is never called) and so we need to also check the `cursor_change` field.
     fn take_cursor(&mut self) -> Option<CursorIcon> {
-        self.cursor.take().or_else(|| self.cursor_change.cursor())
+        self.cursor
+            .take()
+            .or_else(|| self.cursor_change.cursor())
+            .or(self.default_cursor)
     }
 
     #[inline]
@@ -266,6 +324,22 @@ impl WidgetState {
         self.local_paint_rect + self.origin.to_vec2()
     }
 
+    /// The area the widget itself drew in when it was last painted, in its parent's
+    /// coordinate space, or an empty rect if it didn't report it.
+    ///
+    /// For more information, see [`PaintCtx::set_painted_bounds`](crate::PaintCtx::set_painted_bounds).
+    pub fn painted_bounds(&self) -> Rect {
+        self.painted_bounds + self.origin.to_vec2()
+    }
+
+    /// Where the widget's text caret is, in window coordinates.
+    ///
+    /// For more information, see [`LayoutCtx::set_caret_rect`](crate::LayoutCtx::set_caret_rect).
+    pub fn window_caret_rect(&self) -> Option<Rect> {
+        self.caret_rect
+            .map(|rect| rect + self.window_origin().to_vec2())
+    }
+
     /// The rectangle used when calculating layout with other widgets
     ///
     /// For more information, see [`WidgetPod::layout_rect`](crate::WidgetPod::layout_rect).
@@ -281,6 +355,12 @@ impl WidgetState {
         Rect::from_origin_size(self.window_origin(), self.size)
     }
 
+    /// The region containing the layout rects of this widget and all its descendants,
+    /// in window coordinates.
+    pub fn window_bounding_rect(&self) -> Rect {
+        self.local_bounding_rect + self.window_origin().to_vec2()
+    }
+
     pub(crate) fn window_origin(&self) -> Point {
         self.parent_window_origin + self.origin.to_vec2()
     }
@@ -0,0 +1,227 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Finding the widgets which take too long to handle events, lay out or paint.
+//!
+//! When a threshold is set, with [`AppDriver::slow_widget_threshold`] or
+//! [`RenderRoot::set_slow_widget_threshold`], each widget's event handlers,
+//! layout and paint are timed. A widget which spends longer than the threshold
+//! in one of them, not counting the time spent in its children, is logged as a
+//! warning and reported as a [`SlowWidget`].
+//!
+//! Whether or not a threshold is set, each widget also has [`WidgetCounters`],
+//! which count how often its methods have run. Tests can use them to check that a
+//! change only repaints or lays out the widgets it should, with
+//! [`TestHarness::widget_counters`].
+//!
+//! [`TestHarness::widget_counters`]: crate::testing::TestHarness::widget_counters
+//! [`AppDriver::slow_widget_threshold`]: crate::app_driver::AppDriver::slow_widget_threshold
+//! [`RenderRoot::set_slow_widget_threshold`]: crate::render_root::RenderRoot::set_slow_widget_threshold
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use instant::Instant;
+use tracing::warn;
+
+use crate::WidgetId;
+
+/// The most reports kept until they're taken, after which the oldest are dropped.
+const MAX_REPORTS: usize = 64;
+
+/// A widget which took longer than the threshold in one of its methods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlowWidget {
+    pub id: WidgetId,
+    /// The ids of the widget's ancestors, starting from the root and ending with
+    /// the widget's own.
+    pub id_path: Vec<WidgetId>,
+    pub type_name: &'static str,
+    /// The method which was slow, such as `"layout"`.
+    pub method: &'static str,
+    /// The time spent in the method, not counting the time spent in its children.
+    pub duration: Duration,
+}
+
+/// How often a widget's methods have run since it was added, or since the
+/// counters were last reset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WidgetCounters {
+    pub paint_count: u64,
+    pub layout_count: u64,
+    /// The pointer, text and accessibility events the widget has handled.
+    pub event_count: u64,
+    /// The time spent in the last paint, not counting the time spent in its
+    /// children.
+    pub last_paint_duration: Duration,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct WidgetTimings {
+    threshold: Option<Duration>,
+    /// When each method being timed started, and how long its children have taken.
+    stack: Vec<(Instant, Duration)>,
+    reports: VecDeque<SlowWidget>,
+    counters: HashMap<WidgetId, WidgetCounters>,
+}
+
+impl WidgetTimings {
+    pub(crate) fn set_threshold(&mut self, threshold: Option<Duration>) {
+        self.threshold = threshold;
+    }
+
+    /// Start timing a widget method, which must be followed by a call to [`finish`].
+    ///
+    /// [`finish`]: Self::finish
+    pub(crate) fn start(&mut self) {
+        self.stack.push((Instant::now(), Duration::ZERO));
+    }
+
+    /// Stop timing the widget method started last, count it, and report the widget
+    /// if it was slow.
+    pub(crate) fn finish(
+        &mut self,
+        id: WidgetId,
+        type_name: &'static str,
+        method: &'static str,
+        id_path: impl FnOnce() -> Option<Vec<WidgetId>>,
+    ) {
+        let Some((start, children)) = self.stack.pop() else {
+            return;
+        };
+        let total = start.elapsed();
+        if let Some((_, parent_children)) = self.stack.last_mut() {
+            *parent_children += total;
+        }
+        let duration = total.saturating_sub(children);
+
+        let counters = self.counters.entry(id).or_default();
+        match method {
+            "paint" => {
+                counters.paint_count += 1;
+                counters.last_paint_duration = duration;
+            }
+            "layout" => counters.layout_count += 1,
+            _ => counters.event_count += 1,
+        }
+
+        let Some(threshold) = self.threshold else {
+            return;
+        };
+        if duration <= threshold {
+            return;
+        }
+
+        let id_path = id_path().unwrap_or_else(|| vec![id]);
+        warn!(
+            "Widget '{}' #{} took {:?} in {}, longer than {:?} (id path {:?})",
+            type_name,
+            id.to_raw(),
+            duration,
+            method,
+            threshold,
+            id_path.iter().map(|id| id.to_raw()).collect::<Vec<_>>(),
+        );
+        if self.reports.len() == MAX_REPORTS {
+            self.reports.pop_front();
+        }
+        self.reports.push_back(SlowWidget {
+            id,
+            id_path,
+            type_name,
+            method,
+            duration,
+        });
+    }
+
+    pub(crate) fn take_reports(&mut self) -> Vec<SlowWidget> {
+        self.reports.drain(..).collect()
+    }
+
+    pub(crate) fn counters(&self, id: WidgetId) -> WidgetCounters {
+        self.counters.get(&id).copied().unwrap_or_default()
+    }
+
+    pub(crate) fn painted_widgets(&self) -> Vec<WidgetId> {
+        let painted = self
+            .counters
+            .iter()
+            .filter(|(_, counters)| counters.paint_count > 0);
+        painted.map(|(id, _)| *id).collect()
+    }
+
+    pub(crate) fn reset_counters(&mut self) {
+        self.counters.clear();
+    }
+
+    /// Forget the counters of a widget which has been removed.
+    pub(crate) fn remove(&mut self, id: WidgetId) {
+        self.counters.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{widget_ids, ModularWidget, TestHarness};
+    use crate::widget::{Flex, SizedBox};
+    use crate::{Color, Point, Size, WidgetPod};
+
+    #[test]
+    fn slow_layout_is_reported_without_ancestors() {
+        let slow = ModularWidget::new(()).layout_fn(|_, _, _| {
+            std::thread::sleep(Duration::from_millis(30));
+            Size::new(10.0, 10.0)
+        });
+        let [slow_id, parent_id] = widget_ids();
+        let parent = ModularWidget::new(WidgetPod::new_with_id(slow, slow_id))
+            .lifecycle_fn(|child, ctx, event| child.lifecycle(ctx, event))
+            .layout_fn(|child, ctx, bc| {
+                let size = child.layout(ctx, bc);
+                ctx.place_child(child, Point::ORIGIN);
+                size
+            })
+            .children_fn(|child| smallvec::smallvec![child.id()]);
+        let widget = Flex::row().with_child_id(parent, parent_id);
+
+        let mut harness = TestHarness::create(widget);
+        harness.set_slow_widget_threshold(Some(Duration::from_millis(10)));
+        harness.edit_root_widget(|mut root| root.ctx.request_layout());
+        let root_id = harness.root_widget().id();
+
+        let reports = harness.take_slow_widgets();
+        assert_eq!(reports.len(), 1, "{reports:?}");
+        let report = &reports[0];
+        assert_eq!(report.id, slow_id);
+        assert_eq!(report.id_path, [root_id, parent_id, slow_id]);
+        assert_eq!(report.method, "layout");
+        assert!(report.duration >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn changing_a_background_only_repaints_its_box_and_ancestors() {
+        let [changed_id, other_id] = widget_ids();
+        let square = || SizedBox::empty().width(20.0).height(20.0);
+        let widget = Flex::row()
+            .with_child_id(square(), changed_id)
+            .with_child_id(square(), other_id);
+
+        let mut harness = TestHarness::create(widget);
+        let _ = harness.render();
+        let root_id = harness.root_widget().id();
+        assert_eq!(harness.widget_counters(other_id).paint_count, 1);
+
+        harness.reset_widget_counters();
+        harness.edit_root_widget(|mut root| {
+            let mut flex = root.downcast::<Flex>();
+            let mut square = flex.child_mut(0).unwrap();
+            square.downcast::<SizedBox>().set_background(Color::RED);
+        });
+        let _ = harness.render();
+        let painted = harness.painted_widgets();
+        assert_eq!(painted.len(), 2, "{painted:?}");
+        assert!(painted.contains(&root_id) && painted.contains(&changed_id));
+        assert_eq!(harness.widget_counters(other_id), WidgetCounters::default());
+        assert_eq!(harness.widget_counters(changed_id).layout_count, 0);
+    }
+}
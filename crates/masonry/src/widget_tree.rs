@@ -0,0 +1,404 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The storage of the widget tree, keyed by widget id.
+
+use std::any::Any;
+use std::collections::HashMap;
+
+use vello::Scene;
+
+use crate::{Widget, WidgetId, WidgetState};
+
+/// A widget which has been added to the tree, along with its state and the scene
+/// fragment it last painted.
+pub(crate) struct WidgetNode {
+    pub(crate) widget: Box<dyn Widget>,
+    pub(crate) state: WidgetState,
+    pub(crate) fragment: Scene,
+}
+
+impl WidgetNode {
+    /// The widget, as its concrete type.
+    ///
+    /// # Panics
+    ///
+    /// If the widget isn't a `W`.
+    pub(crate) fn downcast<W: Widget>(&self) -> &W {
+        // Pods of boxed widgets ask for the box itself.
+        if let Some(widget) = (&self.widget as &dyn Any).downcast_ref::<W>() {
+            return widget;
+        }
+        self.widget
+            .as_any()
+            .downcast_ref()
+            .expect("widget doesn't have the type of its pod")
+    }
+}
+
+/// The widget, as its concrete type.
+///
+/// # Panics
+///
+/// If the widget isn't a `W`.
+pub(crate) fn downcast_widget_mut<W: Widget>(widget: &mut Box<dyn Widget>) -> &mut W {
+    // Pods of boxed widgets ask for the box itself.
+    if (&*widget as &dyn Any).is::<W>() {
+        return (widget as &mut dyn Any).downcast_mut().unwrap();
+    }
+    widget
+        .as_mut_any()
+        .downcast_mut()
+        .expect("widget doesn't have the type of its pod")
+}
+
+/// An arena of items arranged in a tree, keyed by widget id.
+///
+/// Each node owns its children, in the order their parent holds them, and the arena
+/// keeps a map from every id to the id of its parent. This means the ancestors of a
+/// node can be found from its id alone, and a node can be found without walking
+/// down every branch from the roots.
+///
+/// Items are accessed through [`ArenaRef`] and [`ArenaMut`], which borrow a node's
+/// item and its children separately, so that a node's children can be modified
+/// while its item is borrowed.
+pub(crate) struct TreeArena<T> {
+    roots: Vec<TreeNode<T>>,
+    parents: HashMap<WidgetId, Option<WidgetId>>,
+}
+
+struct TreeNode<T> {
+    id: WidgetId,
+    item: T,
+    children: Vec<TreeNode<T>>,
+}
+
+/// A shared reference to a node of a [`TreeArena`].
+pub(crate) struct ArenaRef<'a, T> {
+    pub(crate) id: WidgetId,
+    pub(crate) item: &'a T,
+    pub(crate) children: ArenaRefChildren<'a, T>,
+}
+
+/// A shared reference to the children of a node of a [`TreeArena`], or to its roots.
+pub(crate) struct ArenaRefChildren<'a, T> {
+    id: Option<WidgetId>,
+    children: &'a [TreeNode<T>],
+}
+
+/// A mutable reference to a node of a [`TreeArena`].
+pub(crate) struct ArenaMut<'a, T> {
+    pub(crate) id: WidgetId,
+    pub(crate) item: &'a mut T,
+    pub(crate) children: ArenaMutChildren<'a, T>,
+}
+
+/// A mutable reference to the children of a node of a [`TreeArena`], or to its roots.
+pub(crate) struct ArenaMutChildren<'a, T> {
+    id: Option<WidgetId>,
+    children: &'a mut Vec<TreeNode<T>>,
+    parents: &'a mut HashMap<WidgetId, Option<WidgetId>>,
+}
+
+impl<T> Default for TreeArena<T> {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            parents: HashMap::new(),
+        }
+    }
+}
+
+impl<T> TreeArena<T> {
+    pub(crate) fn roots(&self) -> ArenaRefChildren<'_, T> {
+        ArenaRefChildren {
+            id: None,
+            children: &self.roots,
+        }
+    }
+
+    pub(crate) fn roots_mut(&mut self) -> ArenaMutChildren<'_, T> {
+        ArenaMutChildren {
+            id: None,
+            children: &mut self.roots,
+            parents: &mut self.parents,
+        }
+    }
+
+    /// Find the node with the given id anywhere in the tree.
+    pub(crate) fn find(&self, id: WidgetId) -> Option<ArenaRef<'_, T>> {
+        let path = self.id_path(id)?;
+        let mut children = &self.roots;
+        let mut node = None;
+        for id in path {
+            let child = children.iter().find(|child| child.id == id)?;
+            children = &child.children;
+            node = Some(child);
+        }
+        node.map(TreeNode::as_ref)
+    }
+
+    /// The parent of the node with the given id, or `None` if it is a root or isn't
+    /// in the tree.
+    pub(crate) fn parent_of(&self, id: WidgetId) -> Option<WidgetId> {
+        self.parents.get(&id).copied().flatten()
+    }
+
+    /// The ids from the root of the tree to the node with the given id, ending with
+    /// that id.
+    pub(crate) fn id_path(&self, id: WidgetId) -> Option<Vec<WidgetId>> {
+        id_path(&self.parents, id)
+    }
+
+    /// The number of nodes in the tree.
+    pub(crate) fn len(&self) -> usize {
+        self.parents.len()
+    }
+}
+
+fn id_path(parents: &HashMap<WidgetId, Option<WidgetId>>, id: WidgetId) -> Option<Vec<WidgetId>> {
+    let mut path = vec![id];
+    let mut current = *parents.get(&id)?;
+    while let Some(parent) = current {
+        path.push(parent);
+        current = parents.get(&parent).copied().flatten();
+    }
+    path.reverse();
+    Some(path)
+}
+
+impl<T> TreeNode<T> {
+    fn as_ref(&self) -> ArenaRef<'_, T> {
+        ArenaRef {
+            id: self.id,
+            item: &self.item,
+            children: ArenaRefChildren {
+                id: Some(self.id),
+                children: &self.children,
+            },
+        }
+    }
+
+    fn as_mut<'a>(
+        &'a mut self,
+        parents: &'a mut HashMap<WidgetId, Option<WidgetId>>,
+    ) -> ArenaMut<'a, T> {
+        ArenaMut {
+            id: self.id,
+            item: &mut self.item,
+            children: ArenaMutChildren {
+                id: Some(self.id),
+                children: &mut self.children,
+                parents,
+            },
+        }
+    }
+
+    fn forget_subtree(&self, parents: &mut HashMap<WidgetId, Option<WidgetId>>) {
+        parents.remove(&self.id);
+        for child in &self.children {
+            child.forget_subtree(parents);
+        }
+    }
+}
+
+impl<T> Clone for ArenaRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaRef<'_, T> {}
+
+impl<T> Clone for ArenaRefChildren<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaRefChildren<'_, T> {}
+
+impl<'a, T> ArenaRefChildren<'a, T> {
+    /// An empty list of children, for items which aren't in an arena yet.
+    pub(crate) fn empty() -> Self {
+        Self {
+            id: None,
+            children: &[],
+        }
+    }
+
+    /// The id of the node these are the children of, or `None` for the roots.
+    pub(crate) fn parent_id(&self) -> Option<WidgetId> {
+        self.id
+    }
+
+    pub(crate) fn get_child(&self, id: WidgetId) -> Option<ArenaRef<'a, T>> {
+        let children = self.children;
+        children
+            .iter()
+            .find(|child| child.id == id)
+            .map(TreeNode::as_ref)
+    }
+
+    /// The children, in order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = ArenaRef<'a, T>> {
+        let children = self.children;
+        children.iter().map(TreeNode::as_ref)
+    }
+}
+
+impl<'a, T> ArenaMutChildren<'a, T> {
+    /// The id of the node these are the children of, or `None` for the roots.
+    pub(crate) fn parent_id(&self) -> Option<WidgetId> {
+        self.id
+    }
+
+    /// The child with the given id, borrowed for the whole lifetime of the children.
+    pub(crate) fn into_child_mut(self, id: WidgetId) -> Option<ArenaMut<'a, T>> {
+        let parents = self.parents;
+        self.children
+            .iter_mut()
+            .find(|child| child.id == id)
+            .map(|child| child.as_mut(parents))
+    }
+
+    /// Add `item` as the last child, with the given id.
+    ///
+    /// # Panics
+    ///
+    /// If a node with the same id is already in the arena.
+    pub(crate) fn insert_child(&mut self, id: WidgetId, item: T) {
+        if self.parents.contains_key(&id) {
+            panic!("A node with id #{} is already in the tree", id.to_raw());
+        }
+        self.parents.insert(id, self.id);
+        self.children.push(TreeNode {
+            id,
+            item,
+            children: Vec::new(),
+        });
+    }
+
+    /// Remove the child with the given id and all of its descendants, and return
+    /// its item.
+    pub(crate) fn remove_child(&mut self, id: WidgetId) -> Option<T> {
+        let idx = self.children.iter().position(|child| child.id == id)?;
+        let node = self.children.remove(idx);
+        node.forget_subtree(self.parents);
+        Some(node.item)
+    }
+
+    /// Move the children whose ids are in `order` to the front, in that order.
+    ///
+    /// Other children are kept after them, in the order they were in.
+    pub(crate) fn reorder(&mut self, order: &[WidgetId]) {
+        let rank = |id: WidgetId| {
+            order
+                .iter()
+                .position(|ordered| *ordered == id)
+                .unwrap_or(order.len())
+        };
+        // sort_by_key is stable, which preserves the order of unlisted children.
+        self.children.sort_by_key(|child| rank(child.id));
+    }
+
+    /// The ids from the root of the tree to the node with the given id, ending with
+    /// that id.
+    ///
+    /// The node can be anywhere in the tree, not just among these children.
+    pub(crate) fn id_path(&self, id: WidgetId) -> Option<Vec<WidgetId>> {
+        id_path(self.parents, id)
+    }
+
+    /// The ids of the children, in order.
+    pub(crate) fn ids(&self) -> impl Iterator<Item = WidgetId> + '_ {
+        self.children.iter().map(|child| child.id)
+    }
+
+    pub(crate) fn as_ref(&self) -> ArenaRefChildren<'_, T> {
+        ArenaRefChildren {
+            id: self.id,
+            children: self.children,
+        }
+    }
+
+    pub(crate) fn reborrow_mut(&mut self) -> ArenaMutChildren<'_, T> {
+        ArenaMutChildren {
+            id: self.id,
+            children: self.children,
+            parents: self.parents,
+        }
+    }
+}
+
+impl<T> ArenaMut<'_, T> {
+    pub(crate) fn reborrow(&self) -> ArenaRef<'_, T> {
+        ArenaRef {
+            id: self.id,
+            item: self.item,
+            children: self.children.as_ref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_and_parent_lookups() {
+        let [root, child, grandchild] = [(); 3].map(|()| WidgetId::next());
+        let mut arena = TreeArena::default();
+        let mut roots = arena.roots_mut();
+        roots.insert_child(root, "root");
+        let mut children = roots.into_child_mut(root).unwrap().children;
+        children.insert_child(child, "child");
+        let mut grandchildren = children.into_child_mut(child).unwrap().children;
+        grandchildren.insert_child(grandchild, "grandchild");
+
+        assert_eq!(arena.len(), 3);
+        assert_eq!(*arena.find(grandchild).unwrap().item, "grandchild");
+        assert_eq!(arena.parent_of(grandchild), Some(child));
+        assert_eq!(arena.parent_of(root), None);
+        assert_eq!(
+            arena.id_path(grandchild),
+            Some(vec![root, child, grandchild])
+        );
+    }
+
+    #[test]
+    fn removing_a_child_forgets_its_subtree() {
+        let [root, child, grandchild] = [(); 3].map(|()| WidgetId::next());
+        let mut arena = TreeArena::default();
+        let mut roots = arena.roots_mut();
+        roots.insert_child(root, "root");
+        let mut children = roots.into_child_mut(root).unwrap().children;
+        children.insert_child(child, "child");
+        children
+            .reborrow_mut()
+            .into_child_mut(child)
+            .unwrap()
+            .children
+            .insert_child(grandchild, "grandchild");
+
+        assert_eq!(children.remove_child(child), Some("child"));
+        assert_eq!(arena.len(), 1);
+        assert!(arena.find(grandchild).is_none());
+        assert_eq!(arena.id_path(grandchild), None);
+    }
+
+    #[test]
+    fn reorder_keeps_unlisted_children_last() {
+        let [root, a, b, c] = [(); 4].map(|()| WidgetId::next());
+        let mut arena = TreeArena::default();
+        let mut roots = arena.roots_mut();
+        roots.insert_child(root, ());
+        let mut children = roots.into_child_mut(root).unwrap().children;
+        for id in [a, b, c] {
+            children.insert_child(id, ());
+        }
+
+        children.reorder(&[c, a]);
+        assert_eq!(children.ids().collect::<Vec<_>>(), vec![c, a, b]);
+    }
+}
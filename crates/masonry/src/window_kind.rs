@@ -0,0 +1,127 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! How the app's window sits among the other windows on the screen.
+//!
+//! A [`WindowKind`] is applied to the window's attributes before it is created with
+//! [`WindowKind::apply`], or changed at runtime with [`DriverCtx::set_window_kind`].
+//!
+//! Masonry apps have a single window, so popups such as menus and tooltips are
+//! drawn inside it with [`LayoutCtx::place_popup`]. A [`WindowKind::Popup`] window
+//! is for apps which are themselves a popup, such as a launcher or a tray menu.
+//!
+//! [`DriverCtx::set_window_kind`]: crate::app_driver::DriverCtx::set_window_kind
+//! [`LayoutCtx::place_popup`]: crate::LayoutCtx::place_popup
+
+use winit::window::{Window, WindowAttributes, WindowLevel};
+
+/// How a window sits among the other windows on the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindowKind {
+    /// A regular window, with the platform's title bar and borders.
+    #[default]
+    Normal,
+    /// A regular window which is kept above the other windows.
+    AlwaysOnTop,
+    /// A small palette of tools, kept above the other windows and left out of the
+    /// taskbar where the platform allows it.
+    Tool,
+    /// A borderless window which is kept above the other windows and left out of the
+    /// taskbar, such as a menu.
+    Popup,
+}
+
+impl WindowKind {
+    /// Set up the attributes of a window which hasn't been created yet as this kind.
+    pub fn apply(self, attributes: WindowAttributes) -> WindowAttributes {
+        let attributes = attributes
+            .with_window_level(self.level())
+            .with_decorations(self.has_decorations())
+            .with_resizable(self.is_resizable());
+
+        #[cfg(target_os = "windows")]
+        let attributes = {
+            use winit::platform::windows::WindowAttributesExtWindows;
+            attributes.with_skip_taskbar(self.skips_taskbar())
+        };
+        #[cfg(all(
+            unix,
+            not(any(
+                target_os = "macos",
+                target_os = "ios",
+                target_os = "android",
+                target_os = "redox"
+            ))
+        ))]
+        let attributes = {
+            use winit::platform::x11::{WindowAttributesExtX11, WindowType};
+            let window_type = match self {
+                WindowKind::Normal | WindowKind::AlwaysOnTop => WindowType::Normal,
+                WindowKind::Tool => WindowType::Utility,
+                WindowKind::Popup => WindowType::PopupMenu,
+            };
+            attributes.with_x11_window_type(vec![window_type])
+        };
+
+        attributes
+    }
+
+    /// Change a window which has already been created to this kind.
+    ///
+    /// The platform may not let a window change whether it is in the taskbar, or its
+    /// X11 window type, once it's shown.
+    pub(crate) fn apply_to_window(self, window: &Window) {
+        window.set_window_level(self.level());
+        window.set_decorations(self.has_decorations());
+        window.set_resizable(self.is_resizable());
+        #[cfg(target_os = "windows")]
+        {
+            use winit::platform::windows::WindowExtWindows;
+            window.set_skip_taskbar(self.skips_taskbar());
+        }
+    }
+
+    fn level(self) -> WindowLevel {
+        match self {
+            WindowKind::Normal => WindowLevel::Normal,
+            WindowKind::AlwaysOnTop | WindowKind::Tool | WindowKind::Popup => {
+                WindowLevel::AlwaysOnTop
+            }
+        }
+    }
+
+    fn has_decorations(self) -> bool {
+        self != WindowKind::Popup
+    }
+
+    fn is_resizable(self) -> bool {
+        self != WindowKind::Popup
+    }
+
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    fn skips_taskbar(self) -> bool {
+        matches!(self, WindowKind::Tool | WindowKind::Popup)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn popups_are_borderless_and_on_top() {
+        let popup = WindowKind::Popup.apply(WindowAttributes::default());
+        assert_eq!(popup.window_level, WindowLevel::AlwaysOnTop);
+        assert!(!popup.decorations);
+        assert!(!popup.resizable);
+
+        let tool = WindowKind::Tool.apply(popup);
+        assert_eq!(tool.window_level, WindowLevel::AlwaysOnTop);
+        assert!(tool.decorations);
+
+        let normal = WindowKind::Normal.apply(tool);
+        assert_eq!(normal.window_level, WindowLevel::Normal);
+        assert!(normal.resizable);
+    }
+}
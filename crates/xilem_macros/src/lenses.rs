@@ -0,0 +1,109 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The implementation of `#[derive(Lenses)]`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Field, Fields, Ident, LitStr};
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(not_a_struct(&input)),
+        },
+        _ => return Err(not_a_struct(&input)),
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut accessors = Vec::new();
+    for field in fields {
+        let Some(accessor) = accessor_name(field)? else {
+            continue;
+        };
+        let vis = &field.vis;
+        let field_name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let doc = format!("The `{field_name}` field of [`{name}`].");
+        accessors.push(quote! {
+            #[doc = #doc]
+            #vis fn #accessor(this: &mut Self) -> &mut #ty {
+                &mut this.#field_name
+            }
+        });
+    }
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    })
+}
+
+/// The name of the accessor of `field`, or `None` if it has `#[lens(ignore)]`.
+fn accessor_name(field: &Field) -> syn::Result<Option<Ident>> {
+    let mut name = field.ident.clone();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("lens"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ignore") {
+                name = None;
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(format_ident!("{}", value.value(), span = value.span()));
+                Ok(())
+            } else {
+                Err(meta.error("expected `ignore` or `name = \"..\"`"))
+            }
+        })?;
+    }
+    Ok(name)
+}
+
+fn not_a_struct(input: &DeriveInput) -> syn::Error {
+    syn::Error::new_spanned(
+        &input.ident,
+        "`Lenses` can only be derived for structs with named fields",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accessors_for_each_field() {
+        let input = syn::parse_quote! {
+            struct AppState<T: Clone> {
+                pub count: i32,
+                #[lens(ignore)]
+                cache: Vec<T>,
+                #[lens(name = "name_lens")]
+                pub(crate) name: String,
+            }
+        };
+        let expected = quote! {
+            impl<T: Clone> AppState<T> {
+                #[doc = "The `count` field of [`AppState`]."]
+                pub fn count(this: &mut Self) -> &mut i32 {
+                    &mut this.count
+                }
+                #[doc = "The `name` field of [`AppState`]."]
+                pub(crate) fn name_lens(this: &mut Self) -> &mut String {
+                    &mut this.name
+                }
+            }
+        };
+        assert_eq!(expand(input).unwrap().to_string(), expected.to_string());
+
+        let input = syn::parse_quote!(
+            struct Counter(i32);
+        );
+        assert!(expand(input).is_err());
+    }
+}
@@ -0,0 +1,590 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Macros for Xilem apps: [`view!`], which builds a view tree from nested blocks
+//! instead of nested calls, and [`Lenses`], which derives accessors for the fields
+//! of the app state. They are normally used through `xilem`, with its `macros`
+//! feature.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::parse::discouraged::Speculative;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    braced, parenthesized, token, AngleBracketedGenericArguments, DeriveInput, Expr, Ident, Pat,
+    Path, Token,
+};
+
+mod lenses;
+
+/// The largest tuple which is a `ViewSequence`. Longer sequences are split into
+/// nested tuples.
+const MAX_TUPLE_LEN: usize = 16;
+
+/// Build a view tree from nested blocks.
+///
+/// A view followed by a block of comma separated children is called with the
+/// children as its last argument, so `flex { a, b }` is `flex((a, b))` and
+/// `sized_box { a }.width(20.)` is `sized_box(a).width(20.)`. Children can be:
+///
+/// - Any expression, including other views with children.
+/// - `if` and `if let`, which become an [`Either`] of the branches, or an
+///   `Option` if there is no `else` branch.
+/// - `match`, which becomes an [`Either`] of the arms.
+/// - `for pattern in iterator { .. }`, which becomes a `Vec` of the views built for
+///   each item.
+///
+/// Each branch of an `if` with an `else`, and each arm of a `match`, has to be a
+/// single view. An `if` without an `else` and a `for` loop only make sequences, so
+/// they can't be the root of the tree, or the child of a view which takes a single
+/// view, such as `sized_box`.
+///
+/// ```ignore
+/// use xilem::view::{button, flex, label};
+/// use xilem::{view, Axis, MasonryView};
+///
+/// fn app_logic(items: &mut Vec<String>) -> impl MasonryView<Vec<String>> {
+///     view! {
+///         flex {
+///             button("Add", |items: &mut Vec<String>| items.push("item".into())),
+///             if items.is_empty() {
+///                 label("Nothing yet")
+///             },
+///             for item in items.iter() {
+///                 label(item.clone())
+///             },
+///         }
+///         .direction(Axis::Vertical)
+///     }
+/// }
+/// ```
+///
+/// [`Either`]: https://docs.rs/xilem/latest/xilem/view/enum.Either.html
+#[proc_macro]
+pub fn view(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive an accessor for each field of a struct, which gives the field of a
+/// mutable reference to the struct.
+///
+/// The accessors are associated functions named after their fields, such as
+/// `AppState::count` for a `count: i32` field, of type
+/// `fn(&mut AppState) -> &mut i32`. They can be given to views which update a
+/// field of the app state, such as with `xilem::assign`, so that binding a field
+/// doesn't need a closure. Each accessor has the visibility of its field.
+///
+/// A field can be skipped with `#[lens(ignore)]`, and its accessor renamed, such
+/// as to avoid a method with the same name, with `#[lens(name = "count_lens")]`.
+///
+/// ```ignore
+/// use xilem::view::{checkbox, flex, textbox};
+/// use xilem::{assign, Lenses, MasonryView};
+///
+/// #[derive(Lenses)]
+/// struct AppState {
+///     name: String,
+///     subscribed: bool,
+/// }
+///
+/// fn app_logic(state: &mut AppState) -> impl MasonryView<AppState> {
+///     flex((
+///         textbox(state.name.clone(), assign(AppState::name)),
+///         checkbox("Subscribe", state.subscribed, assign(AppState::subscribed)),
+///     ))
+/// }
+/// ```
+#[proc_macro_derive(Lenses, attributes(lens))]
+pub fn derive_lenses(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    syn::parse::<DeriveInput>(input)
+        .and_then(lenses::expand)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let Root(children) = syn::parse2(input)?;
+    children.single_view("expected a single view at the root")
+}
+
+struct Root(Children);
+
+impl Parse for Root {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        parse_children(input, Span::call_site()).map(Root)
+    }
+}
+
+/// Whether a child has to be a view, or can be any sequence of views.
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    View,
+    Sequence,
+}
+
+/// The children in a block, which also make up the body of a branch or loop.
+struct Children {
+    children: Vec<Child>,
+    span: Span,
+}
+
+enum Child {
+    Node(Node),
+    Expr(Expr),
+    If(If),
+    For(For),
+    Match(Match),
+}
+
+/// A path, such as to a view function, with its arguments, children and the
+/// methods called on the result.
+struct Node {
+    path: Path,
+    args: Option<Punctuated<Expr, Token![,]>>,
+    children: Option<Children>,
+    methods: TokenStream,
+}
+
+struct If {
+    if_token: Token![if],
+    /// The tokens of each condition, which may be an `if let`, and its branch.
+    branches: Vec<(TokenStream, Children)>,
+    otherwise: Option<Children>,
+}
+
+struct For {
+    for_token: Token![for],
+    pat: Pat,
+    expr: Expr,
+    body: Children,
+}
+
+struct Match {
+    expr: Expr,
+    arms: Vec<Arm>,
+}
+
+struct Arm {
+    pat: Pat,
+    guard: Option<Expr>,
+    body: Children,
+}
+
+fn parse_children(input: ParseStream, span: Span) -> syn::Result<Children> {
+    let mut children = Vec::new();
+    while !input.is_empty() {
+        let child = parse_child(input)?;
+        let ends_with_block = matches!(child, Child::If(_) | Child::For(_) | Child::Match(_));
+        children.push(child);
+        if input.is_empty() {
+            break;
+        }
+        if ends_with_block {
+            input.parse::<Option<Token![,]>>()?;
+        } else {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    Ok(Children { children, span })
+}
+
+fn parse_block(input: ParseStream) -> syn::Result<Children> {
+    let content;
+    let brace = braced!(content in input);
+    parse_children(&content, brace.span.join())
+}
+
+fn parse_child(input: ParseStream) -> syn::Result<Child> {
+    if input.peek(Token![if]) {
+        return parse_if(input).map(Child::If);
+    }
+    if input.peek(Token![for]) {
+        return parse_for(input).map(Child::For);
+    }
+    if input.peek(Token![match]) {
+        return parse_match(input).map(Child::Match);
+    }
+    // Anything which doesn't look like a view with children is a plain expression
+    let fork = input.fork();
+    if let Ok(node) = parse_node(&fork) {
+        input.advance_to(&fork);
+        return Ok(Child::Node(node));
+    }
+    input.parse().map(Child::Expr)
+}
+
+fn parse_node(input: ParseStream) -> syn::Result<Node> {
+    let path = input.call(Path::parse_mod_style)?;
+    let args = if input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        Some(content.parse_terminated(Expr::parse, Token![,])?)
+    } else {
+        None
+    };
+    let children = if input.peek(token::Brace) {
+        Some(parse_block(input)?)
+    } else {
+        None
+    };
+    let mut methods = TokenStream::new();
+    while input.peek(Token![.]) {
+        input.parse::<Token![.]>()?.to_tokens(&mut methods);
+        input.parse::<Ident>()?.to_tokens(&mut methods);
+        if input.peek(Token![::]) {
+            AngleBracketedGenericArguments::parse_turbofish(input)?.to_tokens(&mut methods);
+        }
+        if input.peek(token::Paren) {
+            let content;
+            let paren = parenthesized!(content in input);
+            let args = content.parse_terminated(Expr::parse, Token![,])?;
+            paren.surround(&mut methods, |tokens| args.to_tokens(tokens));
+        }
+    }
+    if !input.is_empty() && !input.peek(Token![,]) {
+        return Err(input.error("expected `,`"));
+    }
+    Ok(Node {
+        path,
+        args,
+        children,
+        methods,
+    })
+}
+
+fn parse_if(input: ParseStream) -> syn::Result<If> {
+    let if_token = input.parse()?;
+    let mut branches = Vec::new();
+    let mut otherwise = None;
+    loop {
+        let mut condition = TokenStream::new();
+        if input.peek(Token![let]) {
+            input.parse::<Token![let]>()?.to_tokens(&mut condition);
+            Pat::parse_multi_with_leading_vert(input)?.to_tokens(&mut condition);
+            input.parse::<Token![=]>()?.to_tokens(&mut condition);
+        }
+        Expr::parse_without_eager_brace(input)?.to_tokens(&mut condition);
+        branches.push((condition, parse_block(input)?));
+
+        if input.parse::<Option<Token![else]>>()?.is_none() {
+            break;
+        }
+        if input.parse::<Option<Token![if]>>()?.is_none() {
+            otherwise = Some(parse_block(input)?);
+            break;
+        }
+    }
+    Ok(If {
+        if_token,
+        branches,
+        otherwise,
+    })
+}
+
+fn parse_for(input: ParseStream) -> syn::Result<For> {
+    let for_token = input.parse()?;
+    let pat = Pat::parse_multi_with_leading_vert(input)?;
+    input.parse::<Token![in]>()?;
+    let expr = Expr::parse_without_eager_brace(input)?;
+    let body = parse_block(input)?;
+    Ok(For {
+        for_token,
+        pat,
+        expr,
+        body,
+    })
+}
+
+fn parse_match(input: ParseStream) -> syn::Result<Match> {
+    input.parse::<Token![match]>()?;
+    let expr = Expr::parse_without_eager_brace(input)?;
+    let content;
+    braced!(content in input);
+    let mut arms = Vec::new();
+    while !content.is_empty() {
+        let pat = Pat::parse_multi_with_leading_vert(&content)?;
+        let guard = match content.parse::<Option<Token![if]>>()? {
+            Some(_) => Some(content.parse()?),
+            None => None,
+        };
+        let arrow = content.parse::<Token![=>]>()?;
+        let body = if content.peek(token::Brace) {
+            let body = parse_block(&content)?;
+            content.parse::<Option<Token![,]>>()?;
+            body
+        } else {
+            let body = parse_children_of_arm(&content, arrow.span())?;
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+            body
+        };
+        arms.push(Arm { pat, guard, body });
+    }
+    Ok(Match { expr, arms })
+}
+
+fn parse_children_of_arm(input: ParseStream, span: Span) -> syn::Result<Children> {
+    Ok(Children {
+        children: vec![parse_child(input)?],
+        span,
+    })
+}
+
+impl Children {
+    /// The children as a sequence, which is a tuple unless there is exactly one.
+    fn sequence(self) -> syn::Result<TokenStream> {
+        let mut children = self
+            .children
+            .into_iter()
+            .map(|child| child.expand(Position::Sequence))
+            .collect::<syn::Result<Vec<_>>>()?;
+        if children.len() == 1 {
+            return Ok(children.remove(0));
+        }
+        while children.len() > MAX_TUPLE_LEN {
+            children = children
+                .chunks(MAX_TUPLE_LEN)
+                .map(|chunk| quote!((#(#chunk,)*)))
+                .collect();
+        }
+        Ok(quote!((#(#children,)*)))
+    }
+
+    fn single_view(mut self, message: &str) -> syn::Result<TokenStream> {
+        if self.children.len() != 1 {
+            return Err(syn::Error::new(self.span, message));
+        }
+        self.children.remove(0).expand(Position::View)
+    }
+}
+
+impl Child {
+    fn expand(self, position: Position) -> syn::Result<TokenStream> {
+        match self {
+            Child::Node(node) => node.expand(),
+            Child::Expr(expr) => Ok(expr.into_token_stream()),
+            Child::If(if_) => if_.expand(position),
+            Child::For(for_) => for_.expand(position),
+            Child::Match(match_) => match_.expand(),
+        }
+    }
+}
+
+impl Node {
+    fn expand(self) -> syn::Result<TokenStream> {
+        let Node {
+            path,
+            args,
+            children,
+            methods,
+        } = self;
+        let call = match (args, children) {
+            (args, Some(children)) => {
+                let args = args.into_iter().flatten();
+                let children = children.sequence()?;
+                quote!(#path(#(#args,)* #children))
+            }
+            (Some(args), None) => quote!(#path(#args)),
+            (None, None) => path.into_token_stream(),
+        };
+        Ok(quote!(#call #methods))
+    }
+}
+
+impl If {
+    fn expand(self, position: Position) -> syn::Result<TokenStream> {
+        let mut tokens = TokenStream::new();
+        match self.otherwise {
+            // A single optional branch can be any sequence
+            None if self.branches.len() == 1 => {
+                if position == Position::View {
+                    return Err(missing_else(self.if_token));
+                }
+                let (condition, body) = self.branches.into_iter().next().unwrap();
+                let body = body.sequence()?;
+                tokens.extend(quote! {
+                    if #condition {
+                        ::core::option::Option::Some(#body)
+                    } else {
+                        ::core::option::Option::None
+                    }
+                });
+            }
+            None => {
+                if position == Position::View {
+                    return Err(missing_else(self.if_token));
+                }
+                let count = self.branches.len();
+                for (index, (condition, body)) in self.branches.into_iter().enumerate() {
+                    let view = either(index, count, body.single_view(BRANCH_MESSAGE)?);
+                    let else_token = (index > 0).then(<Token![else]>::default);
+                    tokens.extend(quote! {
+                        #else_token if #condition {
+                            ::core::option::Option::Some(#view)
+                        }
+                    });
+                }
+                tokens.extend(quote!(else { ::core::option::Option::None }));
+            }
+            Some(otherwise) => {
+                let count = self.branches.len() + 1;
+                for (index, (condition, body)) in self.branches.into_iter().enumerate() {
+                    let view = either(index, count, body.single_view(BRANCH_MESSAGE)?);
+                    let else_token = (index > 0).then(<Token![else]>::default);
+                    tokens.extend(quote!(#else_token if #condition { #view }));
+                }
+                let view = either(count - 1, count, otherwise.single_view(BRANCH_MESSAGE)?);
+                tokens.extend(quote!(else { #view }));
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+impl For {
+    fn expand(self, position: Position) -> syn::Result<TokenStream> {
+        if position == Position::View {
+            return Err(syn::Error::new(
+                self.for_token.span(),
+                "a `for` loop makes a sequence of views, so it has to be in the children of a view such as `flex`",
+            ));
+        }
+        let For {
+            pat, expr, body, ..
+        } = self;
+        let body = body.sequence()?;
+        Ok(quote! {
+            ::core::iter::IntoIterator::into_iter(#expr)
+                .map(|#pat| #body)
+                .collect::<::std::vec::Vec<_>>()
+        })
+    }
+}
+
+impl Match {
+    fn expand(self) -> syn::Result<TokenStream> {
+        let Match { expr, arms } = self;
+        let count = arms.len();
+        let arms = arms
+            .into_iter()
+            .enumerate()
+            .map(|(index, Arm { pat, guard, body })| {
+                let guard = guard.map(|guard| quote!(if #guard));
+                let view = either(index, count, body.single_view(ARM_MESSAGE)?);
+                Ok(quote!(#pat #guard => #view,))
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        Ok(quote!(match #expr { #(#arms)* }))
+    }
+}
+
+const BRANCH_MESSAGE: &str = "each branch of an `if` with several branches has to be a single view";
+const ARM_MESSAGE: &str = "each arm of a `match` has to be a single view";
+
+fn missing_else(if_token: Token![if]) -> syn::Error {
+    syn::Error::new(
+        if_token.span(),
+        "an `if` without an `else` makes an optional sequence, so it has to be in the children of a view such as `flex`",
+    )
+}
+
+/// Wrap `view`, the one at `index` of `count` alternatives, in the nested
+/// `Either` which has a variant for each of them.
+fn either(index: usize, count: usize, view: TokenStream) -> TokenStream {
+    let mut tokens = if index + 1 == count {
+        view
+    } else {
+        quote!(::xilem::view::Either::A(#view))
+    };
+    for _ in 0..index {
+        tokens = quote!(::xilem::view::Either::B(#tokens));
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_expands(input: TokenStream, expected: TokenStream) {
+        assert_eq!(expand(input).unwrap().to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn blocks_become_the_last_argument() {
+        assert_expands(
+            quote! {
+                flex {
+                    button("-", |data| *data -= 1),
+                    sized_box { label(format!("{data}")) }.width(40.),
+                    my_view.clone(),
+                }
+                .direction(Axis::Horizontal)
+            },
+            quote! {
+                flex((
+                    button("-", |data| *data -= 1),
+                    sized_box(label(format!("{data}"))).width(40.),
+                    my_view.clone(),
+                ))
+                .direction(Axis::Horizontal)
+            },
+        );
+        let children = (0..17).map(|i| quote!(label(#i)));
+        let (first, last) = (0..16, 16);
+        assert_expands(
+            quote!(flex { #(#children),* }),
+            quote!(flex(((#(label(#first),)*), (label(#last),),))),
+        );
+    }
+
+    #[test]
+    fn control_flow_becomes_combinators() {
+        assert_expands(
+            quote! {
+                flex {
+                    if a { label("a") }
+                    if let Some(b) = b { label(b) } else if c { label("c") } else { label("d") }
+                    for item in items { label(item) }
+                    match e { 0 => label("zero"), _ => { label("many") } }
+                }
+            },
+            quote! {
+                flex((
+                    if a {
+                        ::core::option::Option::Some(label("a"))
+                    } else {
+                        ::core::option::Option::None
+                    },
+                    if let Some(b) = b {
+                        ::xilem::view::Either::A(label(b))
+                    } else if c {
+                        ::xilem::view::Either::B(::xilem::view::Either::A(label("c")))
+                    } else {
+                        ::xilem::view::Either::B(::xilem::view::Either::B(label("d")))
+                    },
+                    ::core::iter::IntoIterator::into_iter(items)
+                        .map(|item| label(item))
+                        .collect::<::std::vec::Vec<_>>(),
+                    match e {
+                        0 => ::xilem::view::Either::A(label("zero")),
+                        _ => ::xilem::view::Either::B(label("many")),
+                    },
+                ))
+            },
+        );
+        assert!(expand(quote!(if a {
+            label("a")
+        }))
+        .is_err());
+        assert!(expand(quote!(for item in items {
+            label(item)
+        }))
+        .is_err());
+    }
+}
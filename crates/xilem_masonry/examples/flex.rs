@@ -1,12 +1,7 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use masonry::widget::{CrossAxisAlignment, MainAxisAlignment};
-use winit::error::EventLoopError;
-use xilem::{
-    view::{button, flex, label},
-    MasonryView, Xilem,
-};
+use xilem::prelude::*;
 
 fn app_logic(data: &mut i32) -> impl MasonryView<i32> {
     flex((
@@ -18,13 +13,14 @@ fn app_logic(data: &mut i32) -> impl MasonryView<i32> {
             *data += 1;
         }),
     ))
-    .direction(xilem::Axis::Horizontal)
+    .direction(Axis::Horizontal)
     .cross_axis_alignment(CrossAxisAlignment::Center)
     .main_axis_alignment(MainAxisAlignment::Center)
 }
 
-fn main() -> Result<(), EventLoopError> {
-    let app = Xilem::new(0, app_logic);
-    app.run_windowed("Centered Flex".into())?;
+fn main() -> Result<(), RunError> {
+    Xilem::new(0, app_logic)
+        .with_window(|window| window.with_title("Centered Flex"))
+        .run()?;
     Ok(())
 }
@@ -36,7 +36,7 @@ fn app_logic(data: &mut AppData) -> impl MasonryView<AppData> {
             label("Label")
                 .color(Color::REBECCA_PURPLE)
                 .alignment(TextAlignment::Start),
-            label("Disabled label").disabled(),
+            label("Disabled label").disabled(true),
         ))
         .direction(Axis::Horizontal),
         textbox(
@@ -90,5 +90,7 @@ fn main() {
     };
 
     let app = Xilem::new(data, app_logic);
-    app.run_windowed("First Example".into()).unwrap();
+    app.with_window(|window| window.with_title("First Example"))
+        .run()
+        .unwrap();
 }
@@ -89,5 +89,7 @@ fn main() {
     };
 
     let app = Xilem::new(data, app_logic);
-    app.run_windowed("First Example".into()).unwrap();
+    app.with_window(|window| window.with_title("First Example"))
+        .run()
+        .unwrap();
 }
@@ -4,10 +4,10 @@
 use std::{any::Any, ops::Deref};
 
 use accesskit::Role;
-use masonry::widget::{WidgetMut, WidgetRef};
+use masonry::widget::WidgetMut;
 use masonry::{
     AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetPod,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
 };
 use smallvec::SmallVec;
 use vello::Scene;
@@ -20,8 +20,8 @@ use crate::{MasonryView, MessageResult, ViewCx, ViewId};
 /// or used to implement conditional display and switching of views.
 ///
 /// Note that `Option` can also be used for conditionally displaying
-/// views in a [`ViewSequence`](crate::ViewSequence).
-// TODO: Mention `Either` when we have implemented that?
+/// views in a [`ViewSequence`](crate::ViewSequence), and [`Either`](crate::view::Either)
+/// for switching between two views known statically.
 pub type BoxedMasonryView<T, A = ()> = Box<dyn AnyMasonryView<T, A>>;
 
 impl<T: 'static, A: 'static> MasonryView<T, A> for BoxedMasonryView<T, A> {
@@ -61,9 +61,13 @@ pub struct AnyViewState {
 }
 
 /// A trait enabling type erasure of views.
-pub trait AnyMasonryView<T, A = ()>: Send {
+pub trait AnyMasonryView<T, A = ()> {
     fn as_any(&self) -> &dyn std::any::Any;
 
+    /// The id of this view within the type-erased view, once it has been replaced
+    /// `generation` times.
+    fn dyn_view_id(&self, generation: u64) -> ViewId;
+
     fn dyn_build(&self, cx: &mut ViewCx) -> (WidgetPod<DynWidget>, AnyViewState);
 
     fn dyn_rebuild(
@@ -91,6 +95,10 @@ where
         self
     }
 
+    fn dyn_view_id(&self, generation: u64) -> ViewId {
+        ViewId::for_type::<V>(generation)
+    }
+
     fn dyn_build(&self, cx: &mut ViewCx) -> (masonry::WidgetPod<DynWidget>, AnyViewState) {
         let generation = 0;
         let (element, view_state) =
@@ -130,6 +138,7 @@ where
             });
         } else {
             // Otherwise, replace the element.
+            cx.teardown(prev.dyn_view_id(dyn_state.generation));
 
             // Increase the generation, because the underlying widget has been swapped out.
             // Overflow condition: Impossible to overflow, as u64 only ever incremented by 1
@@ -152,17 +161,18 @@ where
         message: Box<dyn std::any::Any>,
         app_state: &mut T,
     ) -> MessageResult<A> {
-        let (start, rest) = id_path
-            .split_first()
-            .expect("Id path has elements for AnyView");
+        let Some((start, rest)) = id_path.split_first() else {
+            tracing::error!("Got empty id path in AnyView");
+            return MessageResult::Stale(message);
+        };
         if start.routing_id() != dyn_state.generation {
             return MessageResult::Stale(message);
         }
         if let Some(view_state) = dyn_state.inner_state.downcast_mut() {
             self.message(view_state, rest, message, app_state)
         } else {
-            // Possibly softer failure?
-            panic!("downcast error in dyn_message");
+            tracing::error!("Wrong view state type in AnyView::dyn_message");
+            MessageResult::Stale(message)
         }
     }
 }
@@ -175,12 +185,16 @@ pub struct DynWidget {
 }
 
 impl DynWidget {
+    pub(crate) fn new(inner: WidgetPod<Box<dyn Widget>>) -> Self {
+        DynWidget { inner }
+    }
+
     pub(crate) fn replace_inner(
         this: &mut WidgetMut<'_, Self>,
         widget: WidgetPod<Box<dyn Widget>>,
     ) {
-        this.widget.inner = widget;
-        this.ctx.children_changed();
+        let old_widget = std::mem::replace(&mut this.widget.inner, widget);
+        this.ctx.remove_child(old_widget);
     }
 
     pub(crate) fn downcast<W: Widget, R>(
@@ -230,9 +244,77 @@ impl Widget for DynWidget {
         self.inner.accessibility(ctx);
     }
 
-    fn children(&self) -> SmallVec<[WidgetRef<'_, dyn Widget>; 16]> {
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
         let mut vec = SmallVec::new();
-        vec.push(self.inner.as_dyn());
+        vec.push(self.inner.id());
         vec
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use masonry::testing::TestHarness;
+    use masonry::widget::RootWidget;
+
+    use super::*;
+    use crate::view::{button, label};
+
+    #[test]
+    fn message_to_replaced_view() {
+        fn press(count: &mut u32) {
+            *count += 1;
+        }
+
+        let mut cx = ViewCx::new();
+        let first_view: BoxedMasonryView<u32> = Box::new(button("Press", press as fn(&mut u32)));
+        let (pod, mut view_state) = first_view.build(&mut cx);
+        let button_path = *cx.widget_map.values().next().unwrap();
+        let mut button_path_buffer = Vec::new();
+        cx.id_paths
+            .resolve_into(button_path, &mut button_path_buffer);
+
+        let mut harness = TestHarness::create(RootWidget::from_pod(pod));
+        let next_view: BoxedMasonryView<u32> = Box::new(label("Not a button"));
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<RootWidget<DynWidget>>();
+            next_view.rebuild(&mut view_state, &mut cx, &first_view, root.get_element());
+        });
+
+        let mut count = 0;
+        let result = next_view.message(
+            &mut view_state,
+            &button_path_buffer,
+            Box::new(masonry::Action::ButtonPressed),
+            &mut count,
+        );
+        assert!(matches!(result, MessageResult::Stale(_)));
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn boxed_view_with_shared_state() {
+        let presses = Rc::new(Cell::new(0));
+        let counted_presses = presses.clone();
+        let view: BoxedMasonryView<()> = Box::new(button("Press", move |_: &mut ()| {
+            counted_presses.set(counted_presses.get() + 1);
+        }));
+        let mut cx = ViewCx::new();
+        let (_, mut view_state) = view.build(&mut cx);
+        let button_path = *cx.widget_map.values().next().unwrap();
+        let mut button_path_buffer = Vec::new();
+        cx.id_paths
+            .resolve_into(button_path, &mut button_path_buffer);
+
+        let result = view.message(
+            &mut view_state,
+            &button_path_buffer,
+            Box::new(masonry::Action::ButtonPressed),
+            &mut (),
+        );
+        assert!(matches!(result, MessageResult::Action(())));
+        assert_eq!(presses.get(), 1);
+    }
+}
@@ -0,0 +1,316 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use masonry::widget::WidgetMut;
+use masonry::WidgetPod;
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A typed key for a value which views [`provide`] to all of the views inside them.
+///
+/// Keys are usually constants:
+///
+/// ```ignore
+/// const ACCENT: Key<Color> = Key::new("my_app::ACCENT", || Color::BLUE);
+/// ```
+///
+/// Keys are told apart by their name, which should be unique in the app, such as
+/// by starting with the module path. The default is used when the key is read
+/// with no [`provide`] above the reader, which is a bug: it panics in debug
+/// builds, and is logged in release builds.
+pub struct Key<T> {
+    name: &'static str,
+    default: fn() -> T,
+}
+
+impl<T> Key<T> {
+    pub const fn new(name: &'static str, default: fn() -> T) -> Self {
+        Key { name, default }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Key<T> {}
+
+impl<T> std::fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Key").field(&self.name).finish()
+    }
+}
+
+/// The values provided to the view currently being built or rebuilt.
+#[derive(Default)]
+pub(crate) struct Environment {
+    values: HashMap<&'static str, Rc<dyn Any>>,
+}
+
+impl Environment {
+    /// The value of `key`, or the key's default if it isn't provided.
+    fn get<T: 'static>(&self, key: Key<T>) -> Rc<T> {
+        let Some(provided) = self.values.get(key.name) else {
+            missing_key(format_args!("{} was read, but isn't provided", key.name));
+            return Rc::new((key.default)());
+        };
+        match provided.clone().downcast::<T>() {
+            Ok(value) => value,
+            Err(_) => {
+                missing_key(format_args!(
+                    "{} was read as a {}, but a value of another type is provided",
+                    key.name,
+                    std::any::type_name::<T>()
+                ));
+                Rc::new((key.default)())
+            }
+        }
+    }
+}
+
+fn missing_key(message: std::fmt::Arguments<'_>) {
+    if cfg!(debug_assertions) {
+        panic!("{message}");
+    } else {
+        tracing::error!("{message}");
+    }
+}
+
+/// Run `f` with `key` set to `provided`, then set it back to its previous value.
+fn with_provided<R>(
+    cx: &mut ViewCx,
+    key: &'static str,
+    provided: Rc<dyn Any>,
+    f: impl FnOnce(&mut ViewCx) -> R,
+) -> R {
+    let previous = cx.environment.values.insert(key, provided);
+    let result = f(cx);
+    match previous {
+        Some(previous) => cx.environment.values.insert(key, previous),
+        None => cx.environment.values.remove(key),
+    };
+    result
+}
+
+/// Provide `value` for `key` to `child` and all of the views inside it, which can
+/// read it with [`with_env`].
+///
+/// A `provide` inside `child` overrides the value for the views inside it.
+pub fn provide<T, V>(key: Key<T>, value: T, child: V) -> Provide<T, V> {
+    Provide {
+        key,
+        value: Rc::new(value),
+        child,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Provide<T, V> {
+    key: Key<T>,
+    value: Rc<T>,
+    child: V,
+}
+
+impl<State, Action, T, V> MasonryView<State, Action> for Provide<T, V>
+where
+    T: 'static,
+    V: MasonryView<State, Action>,
+{
+    type Element = V::Element;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        with_provided(cx, self.key.name, self.value.clone(), |cx| {
+            self.child.build(cx)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        element: WidgetMut<Self::Element>,
+    ) {
+        with_provided(cx, self.key.name, self.value.clone(), |cx| {
+            self.child.rebuild(view_state, cx, &prev.child, element);
+        });
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(view_state, id_path, message, app_state)
+    }
+}
+
+/// The view returned by `view` for the value of `key`, as [provided](provide) by a
+/// view around this one.
+///
+/// Like other views made by closures, `view` is called again on every rebuild, so
+/// it can also depend on the app state it captures.
+pub fn with_env<T, F>(key: Key<T>, view: F) -> WithEnv<T, F> {
+    WithEnv { key, view }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct WithEnv<T, F> {
+    key: Key<T>,
+    view: F,
+}
+
+pub struct WithEnvState<V, S> {
+    view: V,
+    child: S,
+}
+
+impl<State, Action, T, F, V> MasonryView<State, Action> for WithEnv<T, F>
+where
+    T: 'static,
+    F: Fn(&T) -> V + 'static,
+    V: MasonryView<State, Action>,
+{
+    type Element = V::Element;
+    type ViewState = WithEnvState<V, V::ViewState>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let value = cx.environment.get(self.key);
+        let view = (self.view)(&value);
+        let (element, child) = view.build(cx);
+        (element, WithEnvState { view, child })
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        _prev: &Self,
+        element: WidgetMut<Self::Element>,
+    ) {
+        let value = cx.environment.get(self.key);
+        let view = (self.view)(&value);
+        view.rebuild(&mut view_state.child, cx, &view_state.view, element);
+        view_state.view = view;
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        view_state
+            .view
+            .message(&mut view_state.child, id_path, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget;
+
+    use super::*;
+    use crate::view::{flex, label, Label};
+
+    const GREETING: Key<&'static str> = Key::new("tests::GREETING", || "Hello");
+    const NAME: Key<&'static str> = Key::new("tests::NAME", || "");
+
+    /// Build `first`, whose widget must be a flex of labels, returning a function which
+    /// rebuilds it to another view and returns the texts of its labels.
+    fn harness<V: MasonryView<(), ()>>(first: V) -> impl FnMut(V) -> Vec<String> {
+        let mut cx = ViewCx::new();
+        let (pod, mut view_state) = MasonryView::<(), ()>::build(&first, &mut cx);
+        let mut harness = TestHarness::create(widget::SizedBox::new_pod(pod.boxed()));
+        let mut prev = first;
+        move |next| {
+            harness.edit_root_widget(|mut root| {
+                let mut root = root.downcast::<widget::SizedBox>();
+                let mut child = root.child_mut().unwrap();
+                let child = child.downcast();
+                MasonryView::<(), ()>::rebuild(&next, &mut view_state, &mut cx, &prev, child);
+            });
+            prev = next;
+            let flex = harness.root_widget().children()[0];
+            flex.children()
+                .iter()
+                .map(|label| {
+                    let label = label.downcast::<widget::Label>().unwrap();
+                    label.text().to_string()
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn readers_see_the_provided_value() {
+        let view = |greeting: &'static str| {
+            provide(
+                GREETING,
+                greeting,
+                with_env(GREETING, |greeting: &&str| flex((label(*greeting),))),
+            )
+        };
+        let mut rebuild = harness(view("Hello"));
+        assert_eq!(rebuild(view("Hello")), ["Hello"]);
+        assert_eq!(rebuild(view("Bonjour")), ["Bonjour"]);
+    }
+
+    #[test]
+    fn readers_rebuild_with_the_app_state_they_capture() {
+        let view = |count: u32| {
+            provide(
+                GREETING,
+                "Hello",
+                with_env(GREETING, move |greeting: &&str| {
+                    flex((label(format!("{greeting} {count}")),))
+                }),
+            )
+        };
+        let mut rebuild = harness(view(0));
+        // The key's value is the same, but the count isn't
+        assert_eq!(rebuild(view(1)), ["Hello 1"]);
+    }
+
+    #[test]
+    fn nested_readers_of_other_keys_are_rebuilt() {
+        let view = |name: &'static str| {
+            provide(
+                GREETING,
+                "Hello",
+                provide(
+                    NAME,
+                    name,
+                    with_env(GREETING, |greeting: &&str| {
+                        flex((label(*greeting), with_env(NAME, |name: &&str| label(*name))))
+                    }),
+                ),
+            )
+        };
+        let mut rebuild = harness(view("Ada"));
+        assert_eq!(rebuild(view("Grace")), ["Hello", "Grace"]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "tests::GREETING was read, but isn't provided")]
+    fn missing_keys_are_detected() {
+        let view = with_env(GREETING, |greeting: &&str| -> Label { label(*greeting) });
+        MasonryView::<(), ()>::build(&view, &mut ViewCx::new());
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fetching images over HTTP, with [`ureq`].
+
+use std::io::{self, Read};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// The largest response body which is read, so that a broken or hostile server
+/// can't use up all of the memory.
+const MAX_BODY_LEN: u64 = 32 * 1024 * 1024;
+const MAX_REDIRECTS: u32 = 5;
+
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) etag: Option<String>,
+    pub(crate) body: Vec<u8>,
+}
+
+/// The agent shared by all requests, so that connections are reused.
+fn agent() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        ureq::AgentBuilder::new()
+            .timeout(TIMEOUT)
+            .redirects(MAX_REDIRECTS)
+            .user_agent("xilem")
+            .build()
+    })
+}
+
+/// Send a `GET` request for `url`, revalidating with `etag` if there is one, and
+/// following redirects.
+///
+/// This blocks until the whole body has been read, so it should be called from the
+/// [task pool](masonry::task_pool::TaskPool).
+pub(crate) fn get(url: &str, etag: Option<&str>) -> io::Result<Response> {
+    let mut request = agent().get(url);
+    if let Some(etag) = etag {
+        request = request.set("If-None-Match", etag);
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        // Error statuses are reported to the caller like any other
+        Err(ureq::Error::Status(_, response)) => response,
+        Err(ureq::Error::Transport(err)) => return Err(io::Error::other(err)),
+    };
+    let status = response.status();
+    let etag = response.header("ETag").map(str::to_string);
+    if response
+        .header("Content-Length")
+        .and_then(|length| length.parse::<u64>().ok())
+        .is_some_and(|length| length > MAX_BODY_LEN)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_BODY_LEN + 1)
+        .read_to_end(&mut body)?;
+    if body.len() as u64 > MAX_BODY_LEN {
+        return Err(too_large());
+    }
+    Ok(Response { status, etag, body })
+}
+
+fn too_large() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("response body is larger than the limit of {MAX_BODY_LEN} bytes"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    use super::*;
+
+    /// Answer one request with each of `responses`, returning the server's address.
+    fn serve(responses: Vec<Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                // The client may hang up early on purpose
+                let _ = stream.write_all(&response);
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn redirects_are_followed() {
+        let address = serve(vec![
+            b"HTTP/1.1 302 Found\r\nLocation: /moved/image.png\r\nContent-Length: 0\r\n\r\n"
+                .to_vec(),
+            b"HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 2\r\n\r\nok".to_vec(),
+        ]);
+        let response = get(&format!("http://{address}/image.png"), None).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(response.body, b"ok");
+    }
+
+    #[test]
+    fn error_statuses_are_returned() {
+        let address = serve(vec![
+            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n".to_vec(),
+        ]);
+        let response = get(&format!("http://{address}/"), None).unwrap();
+        assert_eq!(response.status, 503);
+    }
+
+    #[test]
+    fn oversized_bodies_are_rejected() {
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_LEN + 1
+        );
+        let address = serve(vec![head.into_bytes()]);
+        let err = get(&format!("http://{address}/"), None).err().unwrap();
+        assert!(err.to_string().contains("larger than the limit"));
+    }
+}
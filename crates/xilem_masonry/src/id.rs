@@ -1,9 +1,17 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use std::fmt::Debug;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
 
-#[derive(Copy, Clone)]
+/// The id of a view within its parent.
+///
+/// Ids are chosen by the parent from the structure of the view tree, such as the
+/// index of a child in a tuple, its key, or how many times it has been replaced.
+/// This means that the same views build the same ids on every run, and paths of
+/// them can be used to refer to a view in logs and tests, with [`ViewPath`].
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ViewId {
     // TODO: This used to be NonZeroU64, but that wasn't really being used
     routing_id: u64,
@@ -28,3 +36,389 @@ impl Debug for ViewId {
         write!(f, "{}@[{}]", self.routing_id, self.debug)
     }
 }
+
+impl Display for ViewId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.routing_id)
+    }
+}
+
+/// The routing ids of a path of [`ViewId`]s, from the root down.
+///
+/// This is written as the ids separated by slashes, such as `/0/2/1`, with `/` being
+/// the root. Unlike the path given to [`MasonryView::message`], it doesn't include
+/// the type names of the views, so it can be read back from a string and compared
+/// against the paths of a run of the app with [`matches`](Self::matches).
+///
+/// [`MasonryView::message`]: crate::MasonryView::message
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ViewPath(Vec<u64>);
+
+impl ViewPath {
+    /// The path to the root of the view tree.
+    pub fn root() -> Self {
+        ViewPath(Vec::new())
+    }
+
+    pub fn from_routing_ids(ids: impl IntoIterator<Item = u64>) -> Self {
+        ViewPath(ids.into_iter().collect())
+    }
+
+    pub fn routing_ids(&self) -> &[u64] {
+        &self.0
+    }
+
+    /// The number of ids in the path.
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The path to the parent of this view, or `None` for the root.
+    pub fn parent(&self) -> Option<ViewPath> {
+        let (_, parent) = self.0.split_last()?;
+        Some(ViewPath(parent.to_vec()))
+    }
+
+    /// Whether the view at `self` is `other` or one of its descendants.
+    pub fn starts_with(&self, other: &ViewPath) -> bool {
+        self.0.starts_with(&other.0)
+    }
+
+    /// Whether `ids` is this path, ignoring the type names of the views.
+    pub fn matches(&self, ids: &[ViewId]) -> bool {
+        self.0.len() == ids.len()
+            && self
+                .0
+                .iter()
+                .zip(ids)
+                .all(|(routing_id, id)| *routing_id == id.routing_id)
+    }
+}
+
+impl From<&[ViewId]> for ViewPath {
+    fn from(ids: &[ViewId]) -> Self {
+        ViewPath(ids.iter().map(|id| id.routing_id).collect())
+    }
+}
+
+impl Display for ViewPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "/");
+        }
+        for routing_id in &self.0 {
+            write!(f, "/{routing_id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ViewPath {
+    type Err = ParseViewPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(rest) = s.strip_prefix('/') else {
+            return Err(ParseViewPathError(s.to_string()));
+        };
+        if rest.is_empty() {
+            return Ok(ViewPath::root());
+        }
+        rest.split('/')
+            .map(|id| id.parse())
+            .collect::<Result<_, _>>()
+            .map(ViewPath)
+            .map_err(|_| ParseViewPathError(s.to_string()))
+    }
+}
+
+/// The error returned when a string isn't a valid [`ViewPath`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseViewPathError(String);
+
+impl Display for ParseViewPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid view path '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseViewPathError {}
+
+/// An interned path of [`ViewId`]s, which is stored in an [`IdPathTable`].
+///
+/// Once its view is [removed](IdPathTable::remove_child), its slot in the table is reused,
+/// and the generation tells the old path apart from the new one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct IdPath {
+    index: u32,
+    generation: u32,
+}
+
+impl IdPath {
+    /// The empty path, i.e. the root of the view tree.
+    pub(crate) const ROOT: IdPath = IdPath {
+        index: 0,
+        generation: 0,
+    };
+}
+
+struct IdPathNode {
+    parent: IdPath,
+    id: ViewId,
+    depth: usize,
+    /// The paths which have this one as their parent
+    children: Vec<IdPath>,
+}
+
+struct IdPathSlot {
+    generation: u32,
+    node: Option<IdPathNode>,
+}
+
+/// The storage for interned [`IdPath`]s.
+///
+/// Paths are stored as a tree, where each node only holds its last id and a link
+/// to its parent. This means that sharing a prefix doesn't cost anything, and a path
+/// can be resolved by walking up the tree, without allocating.
+///
+/// When a view is removed from the view tree, its parent removes its path and those
+/// of its descendants, so the table only grows with the size of the view tree.
+#[derive(Default)]
+pub(crate) struct IdPathTable {
+    slots: Vec<IdPathSlot>,
+    /// The indices of the slots without a node
+    free: Vec<u32>,
+    root_children: Vec<IdPath>,
+    // The type name is compared by address; equal names at distinct addresses only
+    // lead to duplicated nodes, not to wrong routing
+    lookup: HashMap<(IdPath, u64, usize), IdPath>,
+}
+
+impl IdPathTable {
+    /// Get the path made of `parent` followed by `id`, interning it if needed.
+    pub(crate) fn child(&mut self, parent: IdPath, id: ViewId) -> IdPath {
+        let key = Self::key(parent, id);
+        if let Some(path) = self.lookup.get(&key) {
+            return *path;
+        }
+        let node = IdPathNode {
+            parent,
+            id,
+            depth: self.depth(parent) + 1,
+            children: Vec::new(),
+        };
+        let path = match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize - 1];
+                slot.node = Some(node);
+                IdPath {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(IdPathSlot {
+                    generation: 0,
+                    node: Some(node),
+                });
+                IdPath {
+                    index: self
+                        .slots
+                        .len()
+                        .try_into()
+                        .expect("Can't have more than u32::MAX distinct id paths"),
+                    generation: 0,
+                }
+            }
+        };
+        self.children_mut(parent).push(path);
+        self.lookup.insert(key, path);
+        path
+    }
+
+    /// Remove the path made of `parent` followed by `id`, and every path below it.
+    ///
+    /// Returns whether there was such a path.
+    pub(crate) fn remove_child(&mut self, parent: IdPath, id: ViewId) -> bool {
+        let Some(path) = self.lookup.remove(&Self::key(parent, id)) else {
+            return false;
+        };
+        let siblings = self.children_mut(parent);
+        if let Some(ix) = siblings.iter().position(|sibling| *sibling == path) {
+            siblings.swap_remove(ix);
+        }
+        let mut removed = vec![path];
+        while let Some(path) = removed.pop() {
+            let slot = &mut self.slots[path.index as usize - 1];
+            let node = slot.node.take().expect("Removed paths are live");
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free.push(path.index);
+            self.lookup.remove(&Self::key(node.parent, node.id));
+            removed.extend(node.children);
+        }
+        true
+    }
+
+    /// Whether `path` is the root, or hasn't been removed.
+    pub(crate) fn contains(&self, path: IdPath) -> bool {
+        path == IdPath::ROOT || self.node(path).is_some()
+    }
+
+    /// The number of paths in the table, not counting the root.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// The number of ids in `path`.
+    pub(crate) fn depth(&self, path: IdPath) -> usize {
+        match self.node(path) {
+            Some(node) => node.depth,
+            None => 0,
+        }
+    }
+
+    /// Write the ids of `path` to `buffer`, from the root down.
+    ///
+    /// The buffer is cleared first, and is only reallocated if it is too small for the path.
+    pub(crate) fn resolve_into(&self, path: IdPath, buffer: &mut Vec<ViewId>) {
+        debug_assert!(self.contains(path), "Can't resolve a removed path");
+        buffer.clear();
+        buffer.reserve(self.depth(path));
+        let mut current = path;
+        while let Some(node) = self.node(current) {
+            buffer.push(node.id);
+            current = node.parent;
+        }
+        buffer.reverse();
+    }
+
+    fn key(parent: IdPath, id: ViewId) -> (IdPath, u64, usize) {
+        (parent, id.routing_id, id.debug.as_ptr() as usize)
+    }
+
+    fn node(&self, path: IdPath) -> Option<&IdPathNode> {
+        if path == IdPath::ROOT {
+            return None;
+        }
+        let slot = &self.slots[path.index as usize - 1];
+        if slot.generation != path.generation {
+            return None;
+        }
+        slot.node.as_ref()
+    }
+
+    fn children_mut(&mut self, path: IdPath) -> &mut Vec<IdPath> {
+        if path == IdPath::ROOT {
+            &mut self.root_children
+        } else {
+            &mut self.slots[path.index as usize - 1]
+                .node
+                .as_mut()
+                .expect("The parent of a path is live")
+                .children
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intern_paths() {
+        let mut table = IdPathTable::default();
+        let a = table.child(IdPath::ROOT, ViewId::for_type::<u8>(0));
+        let ab = table.child(a, ViewId::for_type::<u8>(1));
+        let ac = table.child(a, ViewId::for_type::<u16>(1));
+
+        assert_eq!(table.child(IdPath::ROOT, ViewId::for_type::<u8>(0)), a);
+        assert_eq!(table.child(a, ViewId::for_type::<u8>(1)), ab);
+        assert_ne!(ab, ac);
+
+        let mut buffer = Vec::new();
+        table.resolve_into(IdPath::ROOT, &mut buffer);
+        assert!(buffer.is_empty());
+
+        table.resolve_into(ab, &mut buffer);
+        let ids: Vec<u64> = buffer.iter().map(|id| id.routing_id()).collect();
+        assert_eq!(ids, [0, 1]);
+        assert_eq!(table.depth(ab), 2);
+    }
+
+    #[test]
+    fn remove_subtrees() {
+        let mut table = IdPathTable::default();
+        let a = table.child(IdPath::ROOT, ViewId::for_type::<u8>(0));
+        let ab = table.child(a, ViewId::for_type::<u8>(1));
+        let c = table.child(IdPath::ROOT, ViewId::for_type::<u8>(2));
+        assert_eq!(table.len(), 3);
+
+        assert!(table.remove_child(IdPath::ROOT, ViewId::for_type::<u8>(0)));
+        assert!(!table.remove_child(IdPath::ROOT, ViewId::for_type::<u8>(0)));
+        assert_eq!(table.len(), 1);
+        assert!(!table.contains(a));
+        assert!(!table.contains(ab));
+        assert!(table.contains(c));
+
+        // The slots are reused, but the old paths stay removed
+        let new_a = table.child(IdPath::ROOT, ViewId::for_type::<u8>(0));
+        let new_ab = table.child(new_a, ViewId::for_type::<u8>(1));
+        assert_eq!(table.len(), 3);
+        assert_ne!(new_a, a);
+        assert_ne!(new_ab, ab);
+        assert!(!table.contains(a));
+        let mut buffer = Vec::new();
+        table.resolve_into(new_ab, &mut buffer);
+        let ids: Vec<u64> = buffer.iter().map(|id| id.routing_id()).collect();
+        assert_eq!(ids, [0, 1]);
+    }
+
+    #[test]
+    fn format_and_parse_paths() {
+        let ids = [ViewId::for_type::<u8>(0), ViewId::for_type::<u16>(12)];
+        let path = ViewPath::from(&ids[..]);
+        assert_eq!(path.to_string(), "/0/12");
+        assert_eq!("/0/12".parse::<ViewPath>(), Ok(path.clone()));
+        assert!(path.matches(&ids));
+        assert!(!path.matches(&ids[..1]));
+
+        assert_eq!(ViewPath::root().to_string(), "/");
+        assert_eq!("/".parse::<ViewPath>(), Ok(ViewPath::root()));
+        assert_eq!(path.parent().unwrap().parent(), Some(ViewPath::root()));
+        assert!(path.starts_with(&"/0".parse().unwrap()));
+        assert!("0/12".parse::<ViewPath>().is_err());
+        assert!("/0//12".parse::<ViewPath>().is_err());
+        assert!("/a".parse::<ViewPath>().is_err());
+    }
+
+    #[test]
+    fn same_views_build_same_paths() {
+        use crate::view::{button, flex, label};
+        use crate::{MasonryView, ViewCx};
+
+        let view = || {
+            flex((
+                label("Count"),
+                button("+", |count: &mut u32| *count += 1),
+                button("-", |count: &mut u32| *count -= 1),
+            ))
+        };
+        let paths = || {
+            let mut cx = ViewCx::new();
+            let _ = MasonryView::<u32>::build(&view(), &mut cx);
+            let mut paths: Vec<String> = cx
+                .widget_map
+                .keys()
+                .map(|id| cx.widget_view_path(*id).unwrap().to_string())
+                .collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(paths(), ["/1", "/2"]);
+        assert_eq!(paths(), paths());
+    }
+}
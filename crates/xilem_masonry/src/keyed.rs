@@ -0,0 +1,359 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::sequence::WasASequence;
+use crate::{ElementSplice, MessageResult, ViewCx, ViewId, ViewSequence};
+
+/// A sequence of views, each identified by a key.
+///
+/// Unlike a `Vec` of views, which is diffed by index, items are matched with
+/// their previous version by key. This means that inserting, removing or
+/// reordering items only inserts, removes or moves the corresponding widgets,
+/// instead of rebuilding every item after the change.
+///
+/// Keys should be unique within the sequence. If a key is repeated, only its
+/// first occurrence is matched with the previous sequence.
+pub fn keyed<K, VT>(items: impl IntoIterator<Item = (K, VT)>) -> Keyed<K, VT> {
+    Keyed {
+        items: items.into_iter().collect(),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Keyed<K, VT> {
+    items: Vec<(K, VT)>,
+}
+
+impl<K, VT> FromIterator<(K, VT)> for Keyed<K, VT> {
+    fn from_iter<I: IntoIterator<Item = (K, VT)>>(iter: I) -> Self {
+        keyed(iter)
+    }
+}
+
+pub struct KeyedSeqState<InnerState> {
+    /// The routing id and state of each item, in the same order as the items
+    inner_with_ids: Vec<(u64, InnerState)>,
+    next_id: u64,
+}
+
+impl<State, Action, Marker, K, VT> ViewSequence<State, Action, (WasASequence, Marker)>
+    for Keyed<K, VT>
+where
+    K: Hash + Eq + 'static,
+    VT: ViewSequence<State, Action, Marker>,
+{
+    type SeqState = KeyedSeqState<VT::SeqState>;
+
+    fn build(&self, cx: &mut ViewCx, elements: &mut dyn ElementSplice) -> Self::SeqState {
+        let inner_with_ids = self
+            .items
+            .iter()
+            .zip(0..)
+            .map(|((_, child), id)| {
+                let state = cx.with_id(ViewId::for_type::<VT>(id), |cx| child.build(cx, elements));
+                (id, state)
+            })
+            .collect::<Vec<_>>();
+        KeyedSeqState {
+            next_id: inner_with_ids.len() as u64,
+            inner_with_ids,
+        }
+    }
+
+    fn rebuild(
+        &self,
+        seq_state: &mut Self::SeqState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        elements: &mut dyn ElementSplice,
+    ) {
+        // Collecting in reverse means the first occurrence of a repeated key wins
+        let prev_indices: HashMap<&K, usize> = prev
+            .items
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(ix, (key, _))| (key, ix))
+            .collect();
+        // For each item, the index of the previous item it replaces, if any
+        let mut reused = vec![false; prev.items.len()];
+        let sources: Vec<Option<usize>> = self
+            .items
+            .iter()
+            .map(|(key, _)| {
+                let ix = *prev_indices.get(key)?;
+                if reused[ix] {
+                    return None;
+                }
+                reused[ix] = true;
+                Some(ix)
+            })
+            .collect();
+
+        let mut prev_states: Vec<_> = std::mem::take(&mut seq_state.inner_with_ids)
+            .into_iter()
+            .map(Some)
+            .collect();
+        // The previous items whose elements are still after the splice's current index, in order
+        let mut pending: VecDeque<usize> = (0..prev.items.len()).collect();
+        let mut inner_with_ids = Vec::with_capacity(self.items.len());
+
+        for ((_, child), source) in self.items.iter().zip(sources) {
+            // Delete removed items as we reach them, so that they don't have to be moved past
+            while let Some(&head) = pending.front() {
+                if reused[head] {
+                    break;
+                }
+                elements.delete(prev.items[head].1.count());
+                pending.pop_front();
+                if let Some((id, _)) = prev_states[head].take() {
+                    cx.teardown(ViewId::for_type::<VT>(id));
+                }
+                cx.mark_changed();
+            }
+            match source {
+                Some(ix) => {
+                    let (id, mut child_state) = prev_states[ix]
+                        .take()
+                        .expect("Each previous item is reused at most once");
+                    let position = pending
+                        .iter()
+                        .position(|&pending_ix| pending_ix == ix)
+                        .expect("Reused items are pending until they are reached");
+                    if position != 0 {
+                        let offset = pending
+                            .iter()
+                            .take(position)
+                            .map(|&pending_ix| prev.items[pending_ix].1.count())
+                            .sum();
+                        elements.move_from(offset, prev.items[ix].1.count());
+                        cx.mark_changed();
+                    }
+                    pending.remove(position);
+                    cx.with_id(ViewId::for_type::<VT>(id), |cx| {
+                        child.rebuild(&mut child_state, cx, &prev.items[ix].1, elements);
+                    });
+                    inner_with_ids.push((id, child_state));
+                }
+                None => {
+                    let id = seq_state.next_id;
+                    seq_state.next_id += 1;
+                    let child_state =
+                        cx.with_id(ViewId::for_type::<VT>(id), |cx| child.build(cx, elements));
+                    inner_with_ids.push((id, child_state));
+                    cx.mark_changed();
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            let n_delete = pending.iter().map(|&ix| prev.items[ix].1.count()).sum();
+            elements.delete(n_delete);
+            for ix in pending {
+                if let Some((id, _)) = prev_states[ix].take() {
+                    cx.teardown(ViewId::for_type::<VT>(id));
+                }
+            }
+            cx.mark_changed();
+        }
+        seq_state.inner_with_ids = inner_with_ids;
+    }
+
+    fn message(
+        &self,
+        seq_state: &mut Self::SeqState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        let Some((start, rest)) = id_path.split_first() else {
+            tracing::error!("Got empty id path in keyed sequence");
+            return MessageResult::Stale(message);
+        };
+        let Some(ix) = seq_state
+            .inner_with_ids
+            .iter()
+            .position(|(id, _)| *id == start.routing_id())
+        else {
+            return MessageResult::Stale(message);
+        };
+        let (_, child_state) = &mut seq_state.inner_with_ids[ix];
+        self.items[ix]
+            .1
+            .message(child_state, rest, message, app_state)
+    }
+
+    fn count(&self) -> usize {
+        self.items.iter().map(|(_, child)| child.count()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget::{self, Label};
+    use masonry::Size;
+
+    use super::*;
+    use crate::view::{button, flex_row, label, sized_box, Either};
+    use crate::MasonryView;
+
+    #[test]
+    fn keyed_changes_keep_each_widget_with_its_gap() {
+        let view = |keys: &[&'static str]| {
+            let items = keys
+                .iter()
+                .map(|&key| (key, sized_box(label(key)).width(20.0)));
+            flex_row(keyed(items)).gap(10.0)
+        };
+        let mut cx = ViewCx::new();
+        let mut prev = view(&["a", "b"]);
+        let (pod, mut seq_state) = MasonryView::<(), ()>::build(&prev, &mut cx);
+        let mut harness = TestHarness::create_with_size(
+            widget::Flex::column().with_child_pod(pod.boxed()),
+            Size::new(200.0, 50.0),
+        );
+        let mut rebuild_to = |harness: &mut TestHarness, keys: &[&'static str]| {
+            let next = view(keys);
+            harness.edit_root_widget(|mut root| {
+                let mut root = root.downcast::<widget::Flex>();
+                let mut flex = root.child_mut(0).unwrap();
+                let flex = flex.downcast();
+                MasonryView::<(), ()>::rebuild(&next, &mut seq_state, &mut cx, &prev, flex);
+            });
+            prev = next;
+        };
+        // The labels in order, with where they start
+        let children = |harness: &TestHarness| {
+            let flex = harness.root_widget().children()[0];
+            flex.children()
+                .iter()
+                .map(|sized_box| {
+                    let label = sized_box.children()[0];
+                    let text = label.downcast::<Label>().unwrap().text().to_string();
+                    (text, sized_box.state().layout_rect().x0)
+                })
+                .collect::<Vec<_>>()
+        };
+        let expected = |keys: &[&str]| {
+            let offsets = (0..).map(|ix| ix as f64 * 30.0);
+            keys.iter()
+                .map(|key| key.to_string())
+                .zip(offsets)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(children(&harness), expected(&["a", "b"]));
+
+        rebuild_to(&mut harness, &["new", "a", "b"]);
+        assert_eq!(children(&harness), expected(&["new", "a", "b"]));
+
+        rebuild_to(&mut harness, &["b", "new", "a"]);
+        assert_eq!(children(&harness), expected(&["b", "new", "a"]));
+
+        rebuild_to(&mut harness, &["a", "b"]);
+        assert_eq!(children(&harness), expected(&["a", "b"]));
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<widget::Flex>();
+            let mut flex = root.child_mut(0).unwrap();
+            let mut flex = flex.downcast::<widget::Flex>();
+            // Each of the two widgets is followed by its spacer
+            let spacers = (0..flex.widget.len())
+                .map(|ix| flex.child_mut(ix).is_none())
+                .collect::<Vec<_>>();
+            assert_eq!(spacers, [false, true, false, true]);
+        });
+    }
+
+    #[test]
+    fn random_changes_keep_the_widget_tree_consistent() {
+        // Even keys are shown as labels and odd ones as buttons
+        let view = |keys: &[u32]| {
+            flex_row(keyed(keys.iter().map(|&key| {
+                let item = if key % 2 == 0 {
+                    Either::A(label(format!("{key}")))
+                } else {
+                    Either::B(button(format!("{key}"), |_: &mut ()| ()))
+                };
+                (key, item)
+            })))
+        };
+        let mut keys = vec![0, 1, 2];
+        let mut next_key = 3;
+        let mut cx = ViewCx::new();
+        let mut prev = view(&keys);
+        let (pod, mut seq_state) = MasonryView::<(), ()>::build(&prev, &mut cx);
+        let mut harness = TestHarness::create_with_size(
+            widget::Flex::column().with_child_pod(pod.boxed()),
+            Size::new(400.0, 50.0),
+        );
+
+        // A fixed xorshift sequence, so that failures can be reproduced
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move |below: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % below.max(1) as u64) as usize
+        };
+        for _ in 0..500 {
+            match random(5) {
+                0 | 1 => {
+                    keys.insert(random(keys.len() + 1), next_key);
+                    next_key += 1;
+                }
+                2 if !keys.is_empty() => {
+                    keys.remove(random(keys.len()));
+                }
+                3 if !keys.is_empty() => {
+                    let from = keys.remove(random(keys.len()));
+                    keys.insert(random(keys.len() + 1), from);
+                }
+                _ => harness.mouse_move((random(400) as f64, random(50) as f64)),
+            }
+            let next = view(&keys);
+            harness.edit_root_widget(|mut root| {
+                let mut root = root.downcast::<widget::Flex>();
+                let mut flex = root.child_mut(0).unwrap();
+                let flex = flex.downcast();
+                MasonryView::<(), ()>::rebuild(&next, &mut seq_state, &mut cx, &prev, flex);
+            });
+            prev = next;
+            harness.check_invariants();
+            let items = harness.root_widget().children()[0].children().len();
+            assert_eq!(items, keys.len());
+        }
+    }
+
+    #[test]
+    fn removed_items_are_forgotten() {
+        // Every rebuild replaces all of the items with new ones
+        let view = |round: u32| {
+            flex_row(keyed((0..10).map(|ix| {
+                let key = round * 10 + ix;
+                (key, button(format!("{key}"), |_: &mut ()| ()))
+            })))
+        };
+        let mut cx = ViewCx::new();
+        let mut prev = view(0);
+        let (pod, mut seq_state) = MasonryView::<(), ()>::build(&prev, &mut cx);
+        let mut harness = TestHarness::create(widget::Flex::column().with_child_pod(pod.boxed()));
+        let paths = cx.id_paths.len();
+        let widgets = cx.widget_map.len();
+
+        for round in 1..100 {
+            let next = view(round);
+            harness.edit_root_widget(|mut root| {
+                let mut root = root.downcast::<widget::Flex>();
+                let mut flex = root.child_mut(0).unwrap();
+                let flex = flex.downcast();
+                MasonryView::<(), ()>::rebuild(&next, &mut seq_state, &mut cx, &prev, flex);
+            });
+            prev = next;
+            assert_eq!(cx.id_paths.len(), paths);
+            assert_eq!(cx.widget_map.len(), widgets);
+        }
+    }
+}
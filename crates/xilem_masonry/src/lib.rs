@@ -2,51 +2,131 @@
 // SPDX-License-Identifier: Apache-2.0
 
 #![allow(clippy::comparison_chain)]
-use std::{any::Any, collections::HashMap};
+use std::{any::Any, collections::HashMap, future::Future, marker::PhantomData, time::Duration};
 
+pub use masonry::direction::{LayoutDirection, LogicalInsets};
+pub use masonry::event_loop_runner::RunError;
+pub use masonry::widget::{
+    Axis, CrossAxisAlignment, FlexParams, GridParams, MainAxisAlignment, SpanStyle, TextSpan,
+    TrackSize,
+};
 use masonry::{
     app_driver::AppDriver,
+    color::ColorSpace,
     event_loop_runner,
-    widget::{RootWidget, WidgetMut},
-    Widget, WidgetId, WidgetPod,
+    feedback::{FeedbackBackend, NoFeedback},
+    headless::{self, HeadlessTheme},
+    idle::ActivityEvent,
+    interaction::InteractionSettings,
+    popup::PopupMode,
+    render_diagnostics::RenderError,
+    task_pool::TaskPool,
+    widget::{ModalLayer, RootWidget, WidgetMut},
+    window_kind::WindowKind,
+    Keymap, Widget, WidgetId, WidgetPod,
+};
+pub use masonry::{
+    paint_scene_helpers::UnitPoint, Affine, Color, IconName, IconSet, IconShape, InputHint, Insets,
+    Point, Rect, Size, TextAlignment, Vec2,
 };
-pub use masonry::{widget::Axis, Color, TextAlignment};
+// The crates in Xilem's API, so that apps don't need to depend on matching versions of them
+pub use masonry::{self, kurbo, parley, vello};
+pub use winit::{self, event::KeyEvent};
 use winit::{
     dpi::LogicalSize,
-    error::EventLoopError,
     window::{Window, WindowAttributes},
 };
 
 mod any_view;
+mod environment;
+mod http;
 mod id;
+mod keyed;
+mod recovery;
 mod sequence;
+mod signal;
+mod startup;
+mod time_travel;
+mod topic;
+mod tracked;
 mod vec_splice;
+mod view_ext;
 pub use any_view::{AnyMasonryView, BoxedMasonryView};
+pub mod prelude;
 pub mod view;
-pub use id::ViewId;
+use environment::Environment;
+pub use environment::{provide, with_env, Key, Provide, WithEnv, WithEnvState};
+use id::{IdPath, IdPathTable};
+pub use id::{ParseViewPathError, ViewId, ViewPath};
+pub use keyed::{keyed, Keyed};
+pub use recovery::PanicReport;
+use recovery::{catch_panic, PanicRecovery};
 pub use sequence::{ElementSplice, ViewSequence};
+pub use signal::{bind, Bind, Bound, Signal};
+pub use startup::{Pending, Ready, Startup, StartupState, StartupView, StartupWidget};
+use time_travel::TimeTravel;
+pub use topic::{publish, subscribe, Subscribe, Topic};
+pub use tracked::Tracked;
 pub use vec_splice::VecSplice;
+pub use view_ext::ViewExt;
+#[cfg(feature = "macros")]
+pub use xilem_macros::{view, Lenses};
 
-pub struct Xilem<State, Logic, View>
+pub struct Xilem<State, Logic, View, Action = (), Reducer = fn(&mut State, Action)>
 where
-    View: MasonryView<State>,
+    View: MasonryView<State, Action>,
 {
-    root_widget: RootWidget<View::Element>,
-    driver: MasonryDriver<State, Logic, View, View::ViewState>,
+    /// The widget of the first view tree, which is put in the root widget when the
+    /// app is run
+    app_widget: WidgetPod<Box<dyn Widget>>,
+    driver: MasonryDriver<State, Logic, View, View::ViewState, Action, Reducer>,
+    window_attributes: WindowAttributes,
 }
 
-pub struct MasonryDriver<State, Logic, View, ViewState> {
+type ActivityCallback<State, Action> = Box<dyn FnMut(&mut State, ActivityEvent) -> Action>;
+
+pub struct MasonryDriver<
+    State,
+    Logic,
+    View,
+    ViewState,
+    Action = (),
+    Reducer = fn(&mut State, Action),
+> {
     state: State,
     logic: Logic,
+    reducer: Reducer,
+    /// Whether the state has changed since the last rebuild, which also resets the tracking
+    take_changed: fn(&mut State) -> bool,
     current_view: View,
     view_cx: ViewCx,
     view_state: ViewState,
+    /// Reused between messages, so that routing them doesn't allocate
+    id_path_buffer: Vec<ViewId>,
+    keymap: Keymap,
+    icons: IconSet,
+    interaction: InteractionSettings,
+    popup_mode: PopupMode,
+    color_space: ColorSpace,
+    layout_direction: LayoutDirection,
+    feedback: Option<Box<dyn FeedbackBackend>>,
+    on_activity: Option<(Duration, ActivityCallback<State, Action>)>,
+    slow_widget_threshold: Option<Duration>,
+    software_fallback: bool,
+    window_geometry_key: Option<String>,
+    recovery: Option<PanicRecovery<State>>,
+    /// The error screen shown after a panic, if there is one.
+    panic_dialog: Option<WidgetId>,
+    time_travel: Option<TimeTravel<State>>,
+    phantom: PhantomData<fn(Action)>,
 }
 
-impl<State, Logic, View> AppDriver for MasonryDriver<State, Logic, View, View::ViewState>
+impl<State, Logic, View, Action, Reducer> AppDriver
+    for MasonryDriver<State, Logic, View, View::ViewState, Action, Reducer>
 where
     Logic: FnMut(&mut State) -> View,
-    View: MasonryView<State>,
+    View: MasonryView<State, Action>,
+    Reducer: FnMut(&mut State, Action),
 {
     fn on_action(
         &mut self,
@@ -54,99 +134,704 @@ where
         widget_id: masonry::WidgetId,
         action: masonry::Action,
     ) {
+        if self.panic_dialog.is_some() {
+            // The error screen covers the app, so only its own actions are expected
+            if self.panic_dialog == Some(widget_id) {
+                match action {
+                    masonry::Action::DialogAccepted(_) => self.recover(ctx, false),
+                    masonry::Action::DialogCancelled => self.recover(ctx, true),
+                    _ => {}
+                }
+            }
+            return;
+        }
+        if self.recovery.is_some() {
+            if let Err(report) = catch_panic(|| self.handle_action(ctx, widget_id, action)) {
+                self.show_panic(ctx, report);
+            }
+        } else {
+            self.handle_action(ctx, widget_id, action);
+        }
+    }
+
+    fn keymap(&self) -> Keymap {
+        self.keymap.clone()
+    }
+
+    fn icons(&self) -> IconSet {
+        self.icons.clone()
+    }
+
+    fn interaction_settings(&self) -> InteractionSettings {
+        self.interaction
+    }
+
+    fn popup_mode(&self) -> PopupMode {
+        self.popup_mode
+    }
+
+    fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    fn layout_direction(&self) -> LayoutDirection {
+        self.layout_direction
+    }
+
+    fn feedback(&mut self) -> Box<dyn FeedbackBackend> {
+        self.feedback.take().unwrap_or_else(|| Box::new(NoFeedback))
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        self.on_activity.as_ref().map(|(timeout, _)| *timeout)
+    }
+
+    fn slow_widget_threshold(&self) -> Option<Duration> {
+        self.slow_widget_threshold
+    }
+
+    fn software_fallback(&self) -> bool {
+        self.software_fallback
+    }
+
+    fn window_geometry_key(&self) -> Option<String> {
+        self.window_geometry_key.clone()
+    }
+
+    fn on_activity(&mut self, ctx: &mut masonry::app_driver::DriverCtx<'_>, event: ActivityEvent) {
+        if self.panic_dialog.is_some() {
+            return;
+        }
+        if self.recovery.is_some() {
+            if let Err(report) = catch_panic(|| self.handle_activity(ctx, event)) {
+                self.show_panic(ctx, report);
+            }
+        } else {
+            self.handle_activity(ctx, event);
+        }
+    }
+}
+
+impl<State, Logic, View, Action, Reducer>
+    MasonryDriver<State, Logic, View, View::ViewState, Action, Reducer>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: MasonryView<State, Action>,
+    Reducer: FnMut(&mut State, Action),
+{
+    fn handle_action(
+        &mut self,
+        ctx: &mut masonry::app_driver::DriverCtx<'_>,
+        widget_id: masonry::WidgetId,
+        action: masonry::Action,
+    ) {
+        if let Some(time_travel) = &mut self.time_travel {
+            if time_travel.is_panel_button(widget_id) {
+                if let Some(state) = time_travel.step(widget_id) {
+                    self.state = state;
+                    self.rebuild(ctx);
+                    self.update_time_travel_panel(ctx);
+                }
+                return;
+            }
+        }
         if let Some(id_path) = self.view_cx.widget_map.get(&widget_id) {
+            self.view_cx
+                .id_paths
+                .resolve_into(*id_path, &mut self.id_path_buffer);
             let message_result = self.current_view.message(
                 &mut self.view_state,
-                id_path.as_slice(),
+                self.id_path_buffer.as_slice(),
                 Box::new(action),
                 &mut self.state,
             );
             let rebuild = match message_result {
-                MessageResult::Action(()) => {
-                    // It's not entirely clear what to do here
-                    true
+                MessageResult::Action(action) => {
+                    (self.reducer)(&mut self.state, action);
+                    (self.take_changed)(&mut self.state)
                 }
                 MessageResult::RequestRebuild => true,
                 MessageResult::Nop => false,
                 MessageResult::Stale(_) => {
-                    tracing::info!("Discarding message");
+                    if cfg!(debug_assertions) {
+                        tracing::debug!(
+                            id_path = ?self.id_path_buffer,
+                            "Discarding message to a view which no longer exists"
+                        );
+                    }
                     false
                 }
             };
+            let rebuild = self.deliver_published() || rebuild;
             if rebuild {
-                let next_view = (self.logic)(&mut self.state);
-                let mut root = ctx.get_root::<RootWidget<View::Element>>();
+                self.rebuild(ctx);
+                self.record_snapshot(ctx);
+            }
+        } else {
+            tracing::error!("Got action {action:?} for unknown widget. Did you forget to use `with_action_widget`?");
+        }
+    }
+
+    fn handle_activity(
+        &mut self,
+        ctx: &mut masonry::app_driver::DriverCtx<'_>,
+        event: ActivityEvent,
+    ) {
+        if let Some((_, callback)) = &mut self.on_activity {
+            let action = callback(&mut self.state, event);
+            (self.reducer)(&mut self.state, action);
+            let rebuild = (self.take_changed)(&mut self.state);
+            if self.deliver_published() || rebuild {
+                self.rebuild(ctx);
+                self.record_snapshot(ctx);
+            }
+        }
+    }
+
+    /// Deliver the messages published by the callbacks which just ran, returning
+    /// whether the view tree should be rebuilt.
+    fn deliver_published(&mut self) -> bool {
+        topic::deliver_published(
+            &self.current_view,
+            &mut self.view_state,
+            &mut self.view_cx,
+            &mut self.id_path_buffer,
+            &mut self.state,
+            &mut self.reducer,
+            self.take_changed,
+        )
+    }
+
+    /// Take a snapshot of the state after it changed, if time travel is on.
+    fn record_snapshot(&mut self, ctx: &mut masonry::app_driver::DriverCtx<'_>) {
+        if let Some(time_travel) = &mut self.time_travel {
+            time_travel.record(&self.state);
+        }
+        self.update_time_travel_panel(ctx);
+    }
 
-                self.view_cx.view_tree_changed = false;
+    fn update_time_travel_panel(&mut self, ctx: &mut masonry::app_driver::DriverCtx<'_>) {
+        if let Some(time_travel) = &self.time_travel {
+            let mut root = ctx.get_root::<AppRoot>();
+            with_root_content(&mut root, self.recovery.is_some(), |mut content| {
+                time_travel.update_panel(&mut content);
+            });
+        }
+    }
+
+    /// Report a panic, and cover the app with an error screen.
+    fn show_panic(&mut self, ctx: &mut masonry::app_driver::DriverCtx<'_>, report: PanicReport) {
+        tracing::error!(
+            message = report.message,
+            "The app panicked, showing the error screen"
+        );
+        let Some(recovery) = &mut self.recovery else {
+            return;
+        };
+        if let Some(on_panic) = &mut recovery.on_panic {
+            on_panic(&report);
+        }
+        let dialog = WidgetPod::new(report.dialog(recovery.reset.is_some()));
+        self.panic_dialog = Some(dialog.id());
+        let mut root = ctx.get_root::<AppRoot>();
+        let mut layer = root.get_element();
+        layer.downcast::<ModalLayer>().set_modal_pod(dialog.boxed());
+    }
+
+    /// Hide the error screen, and build the view tree again from scratch, since the
+    /// panic may have left it half rebuilt. The state is reset first if `reset` is
+    /// true and the app can reset it.
+    fn recover(&mut self, ctx: &mut masonry::app_driver::DriverCtx<'_>, reset: bool) {
+        self.panic_dialog = None;
+        ctx.get_root::<AppRoot>()
+            .get_element()
+            .downcast::<ModalLayer>()
+            .remove_modal();
+        let reset = self
+            .recovery
+            .as_mut()
+            .and_then(|recovery| recovery.reset.as_mut())
+            .filter(|_| reset);
+        if let Some(reset) = reset {
+            self.state = reset();
+        }
+        if let Err(report) = catch_panic(|| self.rebuild_from_scratch(ctx)) {
+            self.show_panic(ctx, report);
+        }
+    }
+
+    fn rebuild_from_scratch(&mut self, ctx: &mut masonry::app_driver::DriverCtx<'_>) {
+        let view = (self.logic)(&mut self.state);
+        let task_pool = self.view_cx.task_pool.clone();
+        self.view_cx = ViewCx {
+            task_pool,
+            ..ViewCx::new()
+        };
+        let (pod, view_state) = view.build(&mut self.view_cx);
+        let mut root = ctx.get_root::<AppRoot>();
+        let mut layer = root.get_element();
+        let docked = self.time_travel.is_some();
+        time_travel::set_app_pod(&mut layer.downcast(), docked, pod.boxed());
+        self.current_view = view;
+        self.view_state = view_state;
+        (self.take_changed)(&mut self.state);
+    }
+}
+
+impl<State, Logic, View, Action, Reducer>
+    MasonryDriver<State, Logic, View, View::ViewState, Action, Reducer>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: MasonryView<State, Action>,
+{
+    /// Run the app logic on the current state, and update the widgets to match.
+    fn rebuild(&mut self, ctx: &mut masonry::app_driver::DriverCtx<'_>) {
+        let next_view = (self.logic)(&mut self.state);
+        let mut root = ctx.get_root::<AppRoot>();
+
+        self.view_cx.view_tree_changed = false;
+        let docked = self.time_travel.is_some();
+        with_root_content(&mut root, self.recovery.is_some(), |content| {
+            time_travel::with_app_element(content, docked, |mut element| {
                 next_view.rebuild(
                     &mut self.view_state,
                     &mut self.view_cx,
                     &self.current_view,
-                    root.get_element(),
+                    element.downcast(),
                 );
-                if cfg!(debug_assertions) && !self.view_cx.view_tree_changed {
-                    tracing::debug!("Nothing changed as result of action");
-                }
-                self.current_view = next_view;
-            }
-        } else {
-            eprintln!("Got action {action:?} for unknown widget. Did you forget to use `with_action_widget`?");
+            });
+        });
+        if cfg!(debug_assertions) && !self.view_cx.view_tree_changed {
+            tracing::debug!("Nothing changed as result of action");
         }
+        self.current_view = next_view;
+        // The app logic may access the state mutably, which shouldn't cause another rebuild
+        (self.take_changed)(&mut self.state);
     }
 }
 
+/// The widget at the root of the window.
+///
+/// Its child is the app, in a [`ModalLayer`] if panic recovery is on, so that the
+/// error screen can be shown above it.
+type AppRoot = RootWidget<Box<dyn Widget>>;
+
+/// Call `f` with the content of the root, which is in a [`ModalLayer`] if `layered`.
+fn with_root_content<R>(
+    root: &mut WidgetMut<AppRoot>,
+    layered: bool,
+    f: impl FnOnce(WidgetMut<Box<dyn Widget>>) -> R,
+) -> R {
+    let mut child = root.get_element();
+    if !layered {
+        return f(child);
+    }
+    let mut layer = child.downcast::<ModalLayer>();
+    f(layer.child_mut())
+}
+
 impl<State, Logic, View> Xilem<State, Logic, View>
 where
     Logic: FnMut(&mut State) -> View,
     View: MasonryView<State>,
 {
-    pub fn new(mut state: State, mut logic: Logic) -> Self {
+    /// Create an app from its initial state and the function which builds the view tree.
+    ///
+    /// The app is run with [`run`](Self::run), in a window which can be configured with
+    /// [`with_window`](Self::with_window).
+    pub fn new(state: State, logic: Logic) -> Self {
+        Self::new_with_reducer(state, logic, |_, ()| {})
+    }
+}
+
+/// The app logic of an app started with [`Xilem::new_with_init`].
+pub type StartupLogic<T, S, V> = Box<dyn FnMut(&mut Startup<T>) -> StartupView<S, V>>;
+
+impl<T, S, V> Xilem<Startup<T>, StartupLogic<T, S, V>, StartupView<S, V>>
+where
+    T: Send + 'static,
+    S: MasonryView<Startup<T>>,
+    V: MasonryView<T>,
+{
+    /// Create an app whose initial state is computed by `init`, such as by loading
+    /// its settings or connecting to a server, while the view from `splash` is shown.
+    ///
+    /// `init` runs on a thread of its own. Once it is done, the view tree built by
+    /// `logic` from its result replaces the splash screen, fading in if the app has
+    /// a [`with_startup_fade`](Self::with_startup_fade). If `init` panics, an error
+    /// is shown instead.
+    ///
+    /// ```ignore
+    /// Xilem::new_with_init(load_settings(), || label("Loading…"), app_logic).run()
+    /// ```
+    ///
+    /// The app's state is a [`Startup`], which callbacks such as
+    /// [`with_idle_timeout`](Self::with_idle_timeout) are given.
+    pub fn new_with_init(
+        init: impl Future<Output = T> + Send + 'static,
+        splash: impl FnMut() -> S + 'static,
+        logic: impl FnMut(&mut T) -> V + 'static,
+    ) -> Self {
+        let state = Startup::Loading(Pending::spawn(init));
+        Self::new(state, Box::new(startup::startup_logic(splash, logic)))
+    }
+
+    /// Fade the app in over `duration` once it has been initialized, rather than
+    /// replacing the splash screen straight away.
+    pub fn with_startup_fade(mut self, duration: Duration) -> Self {
+        self.driver.view_state.fade = duration;
+        self
+    }
+}
+
+impl<State, Logic, View, Action, Reducer> Xilem<State, Logic, View, Action, Reducer>
+where
+    Logic: FnMut(&mut State) -> View,
+    View: MasonryView<State, Action>,
+    Reducer: FnMut(&mut State, Action),
+{
+    /// Create an app whose view tree produces actions, which are handled by `reducer`.
+    ///
+    /// Whenever a message to a view results in [`MessageResult::Action`], the action
+    /// is passed to `reducer` along with the app state, before the view tree is rebuilt.
+    /// Views can convert the actions of their children with [`map_action`](view::map_action).
+    pub fn new_with_reducer(mut state: State, mut logic: Logic, reducer: Reducer) -> Self {
         let first_view = logic(&mut state);
-        let mut view_cx = ViewCx {
-            id_path: vec![],
-            widget_map: HashMap::new(),
-            view_tree_changed: false,
-        };
+        let mut view_cx = ViewCx::new();
         let (pod, view_state) = first_view.build(&mut view_cx);
-        let root_widget = RootWidget::from_pod(pod);
+        let window_size = LogicalSize::new(600., 800.);
+        let window_attributes = Window::default_attributes()
+            .with_title("Xilem")
+            .with_resizable(true)
+            .with_min_inner_size(window_size);
         Xilem {
             driver: MasonryDriver {
                 current_view: first_view,
                 logic,
+                reducer,
+                take_changed: |_| true,
                 state,
                 view_cx,
                 view_state,
+                id_path_buffer: Vec::new(),
+                keymap: Keymap::default(),
+                icons: IconSet::default(),
+                interaction: InteractionSettings::from_env(),
+                popup_mode: PopupMode::InWindow,
+                color_space: ColorSpace::Srgb,
+                layout_direction: LayoutDirection::LeftToRight,
+                feedback: None,
+                on_activity: None,
+                slow_widget_threshold: None,
+                software_fallback: true,
+                window_geometry_key: None,
+                recovery: None,
+                panic_dialog: None,
+                time_travel: None,
+                phantom: PhantomData,
             },
-            root_widget,
+            app_widget: pod.boxed(),
+            window_attributes,
         }
     }
 
+    /// Configure the window the app will run in.
+    ///
+    /// The closure is passed the current attributes, which default to a resizable
+    /// window titled "Xilem", and returns the attributes to use.
+    ///
+    /// ```ignore
+    /// Xilem::new(state, app_logic)
+    ///     .with_window(|window| window.with_title("Counter"))
+    ///     .run()
+    /// ```
+    pub fn with_window(mut self, f: impl FnOnce(WindowAttributes) -> WindowAttributes) -> Self {
+        self.window_attributes = f(self.window_attributes);
+        self
+    }
+
+    /// Set how the window sits among the other windows, such as to keep it above
+    /// them, or to make it a borderless popup.
+    pub fn with_window_kind(mut self, kind: WindowKind) -> Self {
+        self.window_attributes = kind.apply(self.window_attributes);
+        self
+    }
+
+    /// Set the keyboard shortcuts used by widgets in the app.
+    ///
+    /// This defaults to the conventions of the current platform. For example,
+    /// to move to the start of the line with <kbd>Ctrl</kbd>+<kbd>a</kbd>:
+    ///
+    /// ```ignore
+    /// let keymap = Keymap::default().with_binding(
+    ///     Key::Character("a".into()),
+    ///     ModifiersState::CONTROL,
+    ///     EditAction::Move(CaretMovement::LineStart),
+    /// );
+    /// Xilem::new(state, app_logic).with_keymap(keymap).run()
+    /// ```
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.driver.keymap = keymap;
+        self
+    }
+
+    /// Set the icons shown by [`icon`](view::icon) views, such as with an icon pack.
+    ///
+    /// This defaults to a small set of common icons. To add icons to them:
+    ///
+    /// ```ignore
+    /// let arrow = IconShape::from_svg_path("M4 11h12l-5-5 1.4-1.4L20 12l-7.6 7.4L11 18l5-5H4z", Size::new(24.0, 24.0))?;
+    /// let icons = IconSet::default().with_icon("arrow_right", arrow);
+    /// Xilem::new(state, app_logic).with_icons(icons).run()
+    /// ```
+    pub fn with_icons(mut self, icons: IconSet) -> Self {
+        self.driver.icons = icons;
+        self
+    }
+
+    /// Set the timings and distances which decide how widgets interpret input, such
+    /// as the double click interval and how far the pointer must move to start a drag.
+    ///
+    /// This defaults to [`InteractionSettings::from_env`]. To give users more time
+    /// to double click:
+    ///
+    /// ```ignore
+    /// let settings = InteractionSettings::from_env()
+    ///     .with_double_click_interval(Duration::from_millis(800));
+    /// Xilem::new(state, app_logic).with_interaction_settings(settings).run()
+    /// ```
+    pub fn with_interaction_settings(mut self, settings: InteractionSettings) -> Self {
+        self.driver.interaction = settings;
+        self
+    }
+
+    /// Set where popups, such as menus and dropdowns, are shown.
+    ///
+    /// By default, they are drawn in the window. With [`PopupMode::NativeWindow`],
+    /// they get windows of their own, so they aren't cut off by a small window.
+    pub fn with_popup_mode(mut self, mode: PopupMode) -> Self {
+        self.driver.popup_mode = mode;
+        self
+    }
+
+    /// Set the color space the app renders in, such as [`ColorSpace::DisplayP3`]
+    /// for the wider gamut of recent displays.
+    ///
+    /// Where the platform can't show it, the app renders in sRGB. Widgets draw
+    /// colors from other spaces with [`PaintCtx::resolve_color`](masonry::PaintCtx::resolve_color).
+    pub fn with_color_space(mut self, space: ColorSpace) -> Self {
+        self.driver.color_space = space;
+        self
+    }
+
+    /// Lay the app out from right to left, such as for Arabic or Hebrew, or from
+    /// left to right, the default.
+    ///
+    /// Rows then start on the right, as do padding and alignments given with
+    /// logical edges, such as [`LogicalInsets`]. See [`masonry::direction`] for more.
+    pub fn with_layout_direction(mut self, direction: LayoutDirection) -> Self {
+        self.driver.layout_direction = direction;
+        self
+    }
+
+    /// Set the backend which plays the sounds and haptic effects requested by
+    /// widgets, such as a [`checkbox`](view::checkbox) with
+    /// [`feedback`](view::Checkbox::feedback) turned on.
+    ///
+    /// By default, no feedback is played. See [`masonry::feedback`] for more.
+    pub fn with_feedback(mut self, feedback: impl FeedbackBackend + 'static) -> Self {
+        self.driver.feedback = Some(Box::new(feedback));
+        self
+    }
+
+    /// Call `on_activity` once the user hasn't used the mouse or the keyboard for
+    /// `timeout`, and again when they use either after that.
+    ///
+    /// This can be used to autosave, or to pause expensive animations while nobody
+    /// is looking. See [`masonry::idle`] for more.
+    pub fn with_idle_timeout(
+        mut self,
+        timeout: Duration,
+        on_activity: impl FnMut(&mut State, ActivityEvent) -> Action + 'static,
+    ) -> Self {
+        self.driver.on_activity = Some((timeout, Box::new(on_activity)));
+        self
+    }
+
+    /// Log widgets which spend longer than `threshold` handling an event, laying out
+    /// or painting, to find the ones which make frames late.
+    ///
+    /// See [`masonry::widget_timings`] for more.
+    pub fn with_slow_widget_threshold(mut self, threshold: Duration) -> Self {
+        self.driver.slow_widget_threshold = Some(threshold);
+        self
+    }
+
+    /// Set whether to render in software if the GPU can't be used, which is on by
+    /// default.
+    ///
+    /// Without it, [`run`](Self::run) returns an error instead. See
+    /// [`masonry::render_diagnostics`] for more.
+    pub fn with_software_fallback(mut self, enabled: bool) -> Self {
+        self.driver.software_fallback = enabled;
+        self
+    }
+
+    /// Save the window's position and size when it is closed, and restore them when
+    /// the app is next launched, as long as the window would still be on a monitor.
+    ///
+    /// `key` should be unique to the app, such as its name. See
+    /// [`masonry::persistence`] for more.
+    pub fn with_saved_window_geometry(mut self, key: impl Into<String>) -> Self {
+        self.driver.window_geometry_key = Some(key.into());
+        self
+    }
+
+    /// Catch panics in the app logic, in event handlers and while rebuilding, and
+    /// show an error screen instead of closing the window.
+    ///
+    /// The error screen shows the panic message, and its backtrace in debug builds.
+    /// The user can continue with the current state, or `reset` it, such as to the
+    /// initial state. Either way the view tree is built again from scratch.
+    ///
+    /// Panics in widgets, such as during layout or paint, are not caught.
+    pub fn with_panic_recovery(mut self, reset: impl FnMut() -> State + 'static) -> Self {
+        self.driver
+            .recovery
+            .get_or_insert_with(Default::default)
+            .reset = Some(Box::new(reset));
+        self
+    }
+
+    /// Call `on_panic` with each panic caught by panic recovery, such as to write a
+    /// crash report.
+    ///
+    /// This turns on panic recovery if it isn't already, without the option to
+    /// reset the state. See [`with_panic_recovery`](Self::with_panic_recovery).
+    pub fn with_panic_callback(mut self, on_panic: impl FnMut(&PanicReport) + 'static) -> Self {
+        self.driver
+            .recovery
+            .get_or_insert_with(Default::default)
+            .on_panic = Some(Box::new(on_panic));
+        self
+    }
+
+    /// Keep the last `max_snapshots` states of the app, taken after each message
+    /// which changed it, and show a panel below the app to step back and forth
+    /// through them.
+    ///
+    /// Stepping to a snapshot sets the state to a clone of it and rebuilds the view
+    /// tree, so the app can be used from there on; the snapshots after it are then
+    /// replaced by the new ones. This is meant for debugging and demos, since it
+    /// clones the state after every change.
+    pub fn with_time_travel(mut self, max_snapshots: usize) -> Self
+    where
+        State: Clone,
+    {
+        let time_travel = TimeTravel::new(&self.driver.state, State::clone, max_snapshots);
+        self.driver.time_travel = Some(time_travel);
+        self
+    }
+
+    /// Run the app, blocking until its window is closed.
     // TODO: Make windows a specific view
-    pub fn run_windowed(self, window_title: String) -> Result<(), EventLoopError>
+    pub fn run(self) -> Result<(), RunError>
     where
         State: 'static,
         Logic: 'static,
         View: 'static,
+        Action: 'static,
+        Reducer: 'static,
     {
-        let window_size = LogicalSize::new(600., 800.);
-        let window_attributes = Window::default_attributes()
-            .with_title(window_title)
-            .with_resizable(true)
-            .with_min_inner_size(window_size);
-        self.run_windowed_in(window_attributes)
+        let mut content = match &self.driver.time_travel {
+            Some(time_travel) => WidgetPod::new(time_travel.dock(self.app_widget)).boxed(),
+            None => self.app_widget,
+        };
+        if self.driver.recovery.is_some() {
+            content = WidgetPod::new(ModalLayer::new_pod(content)).boxed();
+        }
+        let root_widget: AppRoot = RootWidget::from_pod(content);
+        event_loop_runner::run(self.window_attributes, root_widget, self.driver)
+    }
+
+    #[deprecated = "use `with_window` and `run` instead"]
+    pub fn run_windowed(self, window_title: String) -> Result<(), RunError>
+    where
+        State: 'static,
+        Logic: 'static,
+        View: 'static,
+        Action: 'static,
+        Reducer: 'static,
+    {
+        self.with_window(|window| window.with_title(window_title))
+            .run()
     }
 
-    // TODO: Make windows into a custom view
-    pub fn run_windowed_in(self, window_attributes: WindowAttributes) -> Result<(), EventLoopError>
+    #[deprecated = "use `with_window` and `run` instead"]
+    pub fn run_windowed_in(self, window_attributes: WindowAttributes) -> Result<(), RunError>
     where
         State: 'static,
         Logic: 'static,
         View: 'static,
+        Action: 'static,
+        Reducer: 'static,
     {
-        event_loop_runner::run(window_attributes, self.root_widget, self.driver)
+        self.with_window(|_| window_attributes).run()
+    }
+}
+impl<T, Logic, View, Action, Reducer> Xilem<Tracked<T>, Logic, View, Action, Reducer>
+where
+    Logic: FnMut(&mut Tracked<T>) -> View,
+    View: MasonryView<Tracked<T>, Action>,
+    Reducer: FnMut(&mut Tracked<T>, Action),
+{
+    /// Don't rebuild the view tree after an action which didn't mutate the state.
+    ///
+    /// This avoids running the app logic for event handlers which turn out to be
+    /// no-ops, such as ones reacting to frequent pointer events.
+    /// See [`Tracked`] for how mutations are detected.
+    pub fn skip_unchanged_rebuilds(mut self) -> Self {
+        self.driver.take_changed = Tracked::take_changed;
+        // Building the first view tree may have accessed the state mutably
+        self.driver.state.take_changed();
+        self
     }
 }
-pub trait MasonryView<State, Action = ()>: Send + 'static {
+
+/// Build `view` and render it to an image of `size` logical pixels, without a
+/// window or an event loop, such as for thumbnails or screenshots in CI.
+///
+/// The view's actions are never handled, so the image shows the view as it is
+/// first built. See [`masonry::headless`] for what is needed to render.
+pub fn render_to_image<State, Action>(
+    view: &impl MasonryView<State, Action>,
+    size: Size,
+    theme: &HeadlessTheme,
+) -> Result<image::RgbaImage, RenderError> {
+    let (pod, _) = view.build(&mut ViewCx::new());
+    let root_widget = RootWidget::from_pod(pod.boxed());
+    headless::render_to_image(root_widget, size, theme)
+}
+
+/// A callback which stores the new value of a view, such as a [`textbox`](view::textbox)
+/// or [`checkbox`](view::checkbox), in the place of the app state given by
+/// `accessor`.
+///
+/// The accessor can be a closure, or one derived with `#[derive(Lenses)]`, such
+/// as `assign(AppState::name)`.
+pub fn assign<State, T>(
+    accessor: impl Fn(&mut State) -> &mut T + 'static,
+) -> impl Fn(&mut State, T) + 'static {
+    move |state, value| *accessor(state) = value
+}
+
+/// A description of a part of the UI, which is built into widgets and rebuilt
+/// each time the app logic is run.
+///
+/// Views, their callbacks and the app state stay on the thread running the event
+/// loop, so they don't need to be `Send`, and callbacks may capture an `Rc` or a
+/// `RefCell` of data shared with the rest of the app.
+pub trait MasonryView<State, Action = ()>: 'static {
     type Element: Widget;
     type ViewState;
 
@@ -173,13 +858,37 @@ pub struct ViewCx {
     /// The map from a widgets id to its position in the View tree.
     ///
     /// This includes only the widgets which might send actions
-    /// This is currently never cleaned up
-    widget_map: HashMap<WidgetId, Vec<ViewId>>,
-    id_path: Vec<ViewId>,
+    widget_map: HashMap<WidgetId, IdPath>,
+    id_paths: IdPathTable,
+    id_path: IdPath,
     view_tree_changed: bool,
+    environment: Environment,
+    /// The paths of the views which subscribe to each topic.
+    ///
+    /// The views which have been removed are dropped when they are [torn down](Self::teardown)
+    subscriptions: HashMap<&'static str, Vec<IdPath>>,
+    task_pool: TaskPool,
 }
 
 impl ViewCx {
+    fn new() -> Self {
+        ViewCx {
+            widget_map: HashMap::new(),
+            id_paths: IdPathTable::default(),
+            id_path: IdPath::ROOT,
+            view_tree_changed: false,
+            environment: Environment::default(),
+            subscriptions: HashMap::new(),
+            task_pool: TaskPool::default(),
+        }
+    }
+
+    /// The threads on which views do work which would block the UI, such as
+    /// fetching images.
+    pub fn task_pool(&self) -> &TaskPool {
+        &self.task_pool
+    }
+
     pub fn mark_changed(&mut self) {
         if cfg!(debug_assertions) {
             self.view_tree_changed = true;
@@ -199,17 +908,59 @@ impl ViewCx {
     ) -> WidgetPod<E> {
         let value = f(self);
         let id = value.id();
-        let path = self.id_path.clone();
-        self.widget_map.insert(id, path);
+        self.widget_map.insert(id, self.id_path);
         value
     }
 
+    /// The path to the view currently being built or rebuilt.
+    pub fn view_path(&self) -> ViewPath {
+        self.resolve(self.id_path)
+    }
+
+    /// The path to the view which created the widget `id`, if it sends actions.
+    pub fn widget_view_path(&self, id: WidgetId) -> Option<ViewPath> {
+        Some(self.resolve(*self.widget_map.get(&id)?))
+    }
+
+    /// The widget of the view at `path`, or of its shallowest descendant, among the
+    /// widgets which send actions.
+    pub fn widget_at_view_path(&self, path: &ViewPath) -> Option<WidgetId> {
+        self.widget_map
+            .iter()
+            .map(|(id, id_path)| (*id, self.resolve(*id_path)))
+            .filter(|(_, view_path)| view_path.starts_with(path))
+            .min_by_key(|(_, view_path)| view_path.depth())
+            .map(|(id, _)| id)
+    }
+
+    fn resolve(&self, path: IdPath) -> ViewPath {
+        let mut ids = Vec::new();
+        self.id_paths.resolve_into(path, &mut ids);
+        ViewPath::from(&ids[..])
+    }
+
     pub fn with_id<R>(&mut self, id: ViewId, f: impl FnOnce(&mut Self) -> R) -> R {
-        self.id_path.push(id);
+        let parent = self.id_path;
+        self.id_path = self.id_paths.child(parent, id);
         let res = f(self);
-        self.id_path.pop();
+        self.id_path = parent;
         res
     }
+
+    /// Forget the child `id` of the current view, and all of its descendants, after
+    /// it has been removed from the view tree.
+    ///
+    /// Views which remove or replace their children must call this, so that the paths
+    /// and widgets of removed views don't pile up.
+    pub fn teardown(&mut self, id: ViewId) {
+        if self.id_paths.remove_child(self.id_path, id) {
+            let id_paths = &self.id_paths;
+            self.widget_map.retain(|_, path| id_paths.contains(*path));
+            for subscribers in self.subscriptions.values_mut() {
+                subscribers.retain(|path| id_paths.contains(*path));
+            }
+        }
+    }
 }
 
 /// A result wrapper type for event handlers.
@@ -219,5 +970,10 @@ pub enum MessageResult<A> {
     RequestRebuild,
     #[default]
     Nop,
+    /// The message was addressed to a view which is no longer in the tree.
+    ///
+    /// This is expected when a message is sent by a widget which has since been removed
+    /// or replaced, for example after a list item was deleted. Views return this
+    /// (handing back the message) rather than panicking, and the message is discarded.
     Stale(Box<dyn Any>),
 }
@@ -0,0 +1,20 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The views, traits and types most apps use, to be imported with
+//! `use xilem::prelude::*;`.
+
+pub use crate::view::{
+    align, button, canvas, checkbox, flex, flex_column, flex_row, grid, label, map_action,
+    on_click, padding, portal, prose, sized_box, textbox, Either,
+};
+pub use crate::{
+    assign, bind, keyed, provide, publish, subscribe, with_env, Key, MasonryView, RunError, Signal,
+    Topic, ViewExt, ViewSequence, Xilem,
+};
+pub use crate::{
+    Axis, Color, CrossAxisAlignment, FlexParams, GridParams, InputHint, Insets, MainAxisAlignment,
+    Point, Rect, Size, TextAlignment, TrackSize, UnitPoint, Vec2,
+};
+#[cfg(feature = "macros")]
+pub use xilem_macros::{view, Lenses};
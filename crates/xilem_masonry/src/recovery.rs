@@ -0,0 +1,169 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recovering from panics in the app logic, instead of closing the window.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use masonry::widget::{Button, Dialog};
+use masonry::WidgetPod;
+
+/// The most lines of the backtrace shown in the error screen, which has no room
+/// for all of it.
+const MAX_BACKTRACE_LINES: usize = 16;
+
+/// A panic which was caught while handling an action or rebuilding the view tree.
+///
+/// See [`Xilem::with_panic_recovery`](crate::Xilem::with_panic_recovery).
+#[derive(Clone, Debug)]
+pub struct PanicReport {
+    pub message: String,
+    /// Where the panic happened, which is only captured in debug builds.
+    pub backtrace: Option<String>,
+}
+
+type ResetCallback<State> = Box<dyn FnMut() -> State>;
+type PanicCallback = Box<dyn FnMut(&PanicReport)>;
+
+pub(crate) struct PanicRecovery<State> {
+    pub(crate) reset: Option<ResetCallback<State>>,
+    pub(crate) on_panic: Option<PanicCallback>,
+}
+
+impl<State> Default for PanicRecovery<State> {
+    fn default() -> Self {
+        PanicRecovery {
+            reset: None,
+            on_panic: None,
+        }
+    }
+}
+
+thread_local! {
+    /// Whether this thread is running code in [`catch_panic`], whose panics are
+    /// the only ones the hook captures a backtrace for.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static LAST_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, returning a report instead of unwinding if it panics.
+///
+/// The default panic hook still runs, so the panic is printed as usual.
+pub(crate) fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, PanicReport> {
+    install_hook();
+    LAST_BACKTRACE.with(|backtrace| backtrace.borrow_mut().take());
+    let was_catching = CATCHING.replace(true);
+    // The driver's state may be left inconsistent by the panic, which is why it
+    // rebuilds the view tree from scratch afterwards
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(was_catching);
+    result.map_err(|payload| PanicReport {
+        message: panic_message(&*payload),
+        backtrace: LAST_BACKTRACE.with(|backtrace| backtrace.borrow_mut().take()),
+    })
+}
+
+/// Install a panic hook which records the backtrace of panics in [`catch_panic`],
+/// since it can't be captured once the stack has been unwound.
+///
+/// The hook which was installed before is still called for every panic, and the
+/// panics of other threads and code outside of `catch_panic` are left to it.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if cfg!(debug_assertions) && CATCHING.get() {
+                let backtrace = Backtrace::force_capture().to_string();
+                LAST_BACKTRACE.with(|last| *last.borrow_mut() = Some(backtrace));
+            }
+            previous(info);
+        }));
+    });
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+impl PanicReport {
+    /// The error screen for this panic, whose accept button continues with the
+    /// current state, and whose cancel button, if `can_reset`, resets it.
+    pub(crate) fn dialog(&self, can_reset: bool) -> Dialog {
+        let mut message = self.message.clone();
+        if let Some(backtrace) = &self.backtrace {
+            message.push_str("\n\n");
+            let lines: Vec<_> = backtrace.lines().take(MAX_BACKTRACE_LINES).collect();
+            message.push_str(&lines.join("\n"));
+        }
+        Dialog::from_pods(
+            "The app ran into a problem",
+            message,
+            None,
+            WidgetPod::new(Button::new("Continue")),
+            can_reset.then(|| WidgetPod::new(Button::new("Reset state"))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget::Label;
+
+    use super::*;
+
+    #[test]
+    fn panics_become_reports() {
+        assert_eq!(catch_panic(|| 1 + 1).unwrap(), 2);
+
+        let report = catch_panic(|| panic!("Out of {}", "cheese")).unwrap_err();
+        assert_eq!(report.message, "Out of cheese");
+        assert_eq!(report.backtrace.is_some(), cfg!(debug_assertions));
+
+        let harness = TestHarness::create(report.dialog(true));
+        let dialog = harness.root_widget();
+        let labels: Vec<String> = dialog
+            .children()
+            .into_iter()
+            .filter(|child| child.downcast::<Button>().is_some())
+            .filter_map(|button| {
+                let label = button.children()[0];
+                Some(label.downcast::<Label>()?.text().to_string())
+            })
+            .collect();
+        assert_eq!(labels, ["Continue", "Reset state"]);
+    }
+
+    #[test]
+    fn only_caught_panics_are_captured() {
+        install_hook();
+        // A panic on another thread, like in a task, doesn't replace the backtrace
+        // of this thread's panic, and isn't captured on its own thread
+        let report = catch_panic(|| {
+            let other = std::thread::spawn(|| {
+                let result = panic::catch_unwind(|| panic!("In a task"));
+                assert!(result.is_err());
+                LAST_BACKTRACE.with(|backtrace| backtrace.borrow().is_none())
+            });
+            assert!(other.join().unwrap());
+            panic!("In the app logic")
+        })
+        .unwrap_err();
+        assert_eq!(report.backtrace.is_some(), cfg!(debug_assertions));
+
+        // Panics outside of `catch_panic` are left to the previous hook
+        assert!(panic::catch_unwind(|| panic!("Not caught")).is_err());
+        assert!(LAST_BACKTRACE.with(|backtrace| backtrace.borrow().is_none()));
+    }
+}
@@ -14,6 +14,9 @@ pub trait ElementSplice {
     fn mutate(&mut self) -> WidgetMut<Box<dyn Widget>>;
     /// Delete the next n existing elements (this doesn't change the index)
     fn delete(&mut self, n: usize);
+    /// Move the n existing elements starting `offset` elements after the current index
+    /// to the current index, without rebuilding them (this doesn't change the index)
+    fn move_from(&mut self, offset: usize, n: usize);
     /// Current length of the elements collection
     // TODO: Is `len` needed?
     fn len(&self) -> usize;
@@ -22,7 +25,7 @@ pub trait ElementSplice {
 /// This trait represents a (possibly empty) sequence of views.
 ///
 /// It is up to the parent view how to lay out and display them.
-pub trait ViewSequence<State, Action, Marker>: Send + 'static {
+pub trait ViewSequence<State, Action, Marker>: 'static {
     type SeqState;
     // TODO: Rename to not overlap with MasonryView?
     /// Build the associated widgets and initialize all states.
@@ -160,6 +163,7 @@ impl<State, Action, Marker, VT: ViewSequence<State, Action, Marker>>
                 let count = prev.count();
                 elements.delete(count);
                 seq_state.inner = None;
+                cx.teardown(ViewId::for_type::<VT>(seq_state.generation));
                 cx.mark_changed();
             }
             (Some(this), None) => {
@@ -181,9 +185,10 @@ impl<State, Action, Marker, VT: ViewSequence<State, Action, Marker>>
         message: Box<dyn std::any::Any>,
         app_state: &mut State,
     ) -> MessageResult<Action> {
-        let (start, rest) = id_path
-            .split_first()
-            .expect("Id path has elements for Option<ViewSequence>");
+        let Some((start, rest)) = id_path.split_first() else {
+            tracing::error!("Got empty id path in Option<ViewSequence>");
+            return MessageResult::Stale(message);
+        };
         if start.routing_id() != seq_state.generation {
             return MessageResult::Stale(message);
         }
@@ -248,7 +253,12 @@ impl<T, A, Marker, VT: ViewSequence<T, A, Marker>> ViewSequence<T, A, (WasASeque
         let n = self.len();
         if n < prev.len() {
             let n_delete = prev[n..].iter().map(ViewSequence::count).sum();
-            seq_state.inner_with_generations.drain(n..);
+            for (ix, (_, generation)) in seq_state.inner_with_generations.drain(n..).enumerate() {
+                cx.teardown(ViewId::for_type::<VT>(create_vector_view_id(
+                    n + ix,
+                    generation,
+                )));
+            }
             elements.delete(n_delete);
             cx.mark_changed();
         } else if n > prev.len() {
@@ -298,11 +308,16 @@ impl<T, A, Marker, VT: ViewSequence<T, A, Marker>> ViewSequence<T, A, (WasASeque
         message: Box<dyn std::any::Any>,
         app_state: &mut T,
     ) -> MessageResult<A> {
-        let (start, rest) = id_path
-            .split_first()
-            .expect("Id path has elements for vector");
+        let Some((start, rest)) = id_path.split_first() else {
+            tracing::error!("Got empty id path in vector");
+            return MessageResult::Stale(message);
+        };
         let (index, generation) = view_id_to_index_generation(start.routing_id());
-        let (seq_state, stored_generation) = &mut seq_state.inner_with_generations[index];
+        // The item may have been removed since the message was sent
+        let Some((seq_state, stored_generation)) = seq_state.inner_with_generations.get_mut(index)
+        else {
+            return MessageResult::Stale(message);
+        };
         if *stored_generation != generation {
             return MessageResult::Stale(message);
         }
@@ -439,16 +454,20 @@ macro_rules! impl_view_tuple {
                 message: Box<dyn std::any::Any>,
                 app_state: &mut State,
             ) -> MessageResult<Action> {
-                let (start, rest) = id_path
-                    .split_first()
-                    .expect("Id path has elements for tuple");
+                let Some((start, rest)) = id_path.split_first() else {
+                    tracing::error!("Got empty id path in tuple");
+                    return MessageResult::Stale(message);
+                };
                 match start.routing_id() {
                     $(
                         $idx => self.$idx.message(&mut seq_state.$idx, rest, message, app_state),
                     )+
                     // If we have received a message, our parent is (mostly) certain that we requested it
                     // The only time that wouldn't be the case is when a generational index has overflowed?
-                    _ => unreachable!("Unexpected id path {start:?} in tuple (wants to be routed via {rest:?})"),
+                    _ => {
+                        tracing::error!("Unexpected id path {start:?} in tuple (wants to be routed via {rest:?})");
+                        MessageResult::Stale(message)
+                    }
                 }
             }
 
@@ -476,3 +495,70 @@ impl_view_tuple!(M0, Seq0, 0; M1, Seq1, 1; M2, Seq2, 2; M3, Seq3, 3; M4, Seq4, 4
 impl_view_tuple!(M0, Seq0, 0; M1, Seq1, 1; M2, Seq2, 2; M3, Seq3, 3; M4, Seq4, 4; M5, Seq5, 5; M6, Seq6, 6; M7, Seq7, 7; M8, Seq8, 8; M9, Seq9, 9; M10, Seq10, 10; M11, Seq11, 11; M12, Seq12, 12; M13, Seq13, 13);
 impl_view_tuple!(M0, Seq0, 0; M1, Seq1, 1; M2, Seq2, 2; M3, Seq3, 3; M4, Seq4, 4; M5, Seq5, 5; M6, Seq6, 6; M7, Seq7, 7; M8, Seq8, 8; M9, Seq9, 9; M10, Seq10, 10; M11, Seq11, 11; M12, Seq12, 12; M13, Seq13, 13; M14, Seq14, 14);
 impl_view_tuple!(M0, Seq0, 0; M1, Seq1, 1; M2, Seq2, 2; M3, Seq3, 3; M4, Seq4, 4; M5, Seq5, 5; M6, Seq6, 6; M7, Seq7, 7; M8, Seq8, 8; M9, Seq9, 9; M10, Seq10, 10; M11, Seq11, 11; M12, Seq12, 12; M13, Seq13, 13; M14, Seq14, 14; M15, Seq15, 15);
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget::{self, RootWidget};
+
+    use crate::view::{button, flex};
+    use crate::{MasonryView, MessageResult, ViewCx};
+
+    fn press(count: &mut u32) {
+        *count += 1;
+    }
+
+    #[test]
+    fn message_to_removed_vector_item() {
+        let view = |n| {
+            flex(
+                (0..n)
+                    .map(|_| button("Press", press as fn(&mut u32)))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let mut cx = ViewCx::new();
+        let first_view = view(2);
+        let (pod, mut view_state) = MasonryView::<u32>::build(&first_view, &mut cx);
+
+        let mut paths: Vec<_> = cx
+            .widget_map
+            .values()
+            .map(|path| {
+                let mut buffer = Vec::new();
+                cx.id_paths.resolve_into(*path, &mut buffer);
+                buffer
+            })
+            .collect();
+        paths.sort_by_key(|path| path[0].routing_id());
+        let [first_path, second_path] = &paths[..] else {
+            panic!("Expected one path per button, got {paths:?}");
+        };
+
+        let mut harness = TestHarness::create(RootWidget::from_pod(pod));
+        let next_view = view(1);
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<RootWidget<widget::Flex>>();
+            next_view.rebuild(&mut view_state, &mut cx, &first_view, root.get_element());
+        });
+
+        let mut count = 0;
+        let result = next_view.message(
+            &mut view_state,
+            second_path,
+            Box::new(masonry::Action::ButtonPressed),
+            &mut count,
+        );
+        assert!(matches!(result, MessageResult::Stale(_)));
+        assert_eq!(count, 0);
+
+        let result = next_view.message(
+            &mut view_state,
+            first_path,
+            Box::new(masonry::Action::ButtonPressed),
+            &mut count,
+        );
+        assert!(matches!(result, MessageResult::Action(())));
+        assert_eq!(count, 1);
+    }
+}
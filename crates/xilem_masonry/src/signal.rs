@@ -0,0 +1,355 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use accesskit::Role;
+use masonry::wake::WakeHandle;
+use masonry::widget::WidgetMut;
+use masonry::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+use smallvec::SmallVec;
+use vello::Scene;
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A shared value which can be bound directly to a widget property.
+///
+/// Updating a signal doesn't rebuild the view tree. Instead, it wakes the widgets
+/// bound to it with [`bind`], which apply the new value. This is meant for
+/// values which change much more often than the rest of the app state, such as
+/// audio meters or frame counters.
+///
+/// Signals are cheap to clone, and all clones share the same value. They can be
+/// set from any thread.
+pub struct Signal<T> {
+    inner: Arc<SignalInner<T>>,
+}
+
+struct SignalInner<T> {
+    value: Mutex<T>,
+    /// Incremented every time the value is set
+    version: AtomicU64,
+    /// The bound widgets, which are woken when the value is set
+    subscribers: Mutex<Vec<WakeHandle>>,
+}
+
+impl<T> Signal<T> {
+    pub fn new(value: T) -> Self {
+        Signal {
+            inner: Arc::new(SignalInner {
+                value: Mutex::new(value),
+                version: AtomicU64::new(0),
+                subscribers: Mutex::default(),
+            }),
+        }
+    }
+
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Modify the value in place.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.lock().unwrap());
+        self.inner.version.fetch_add(1, Ordering::Release);
+        for subscriber in self.inner.subscribers.lock().unwrap().iter() {
+            subscriber.wake();
+        }
+    }
+
+    /// Run `f` with the current value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.value.lock().unwrap())
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// The number of times the value has been set.
+    pub fn version(&self) -> u64 {
+        self.inner.version.load(Ordering::Acquire)
+    }
+
+    /// Whether both signals are clones of the same signal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn subscribe(&self, handle: WakeHandle) {
+        self.inner.subscribers.lock().unwrap().push(handle);
+    }
+
+    fn unsubscribe(&self, widget: WidgetId) {
+        let mut subscribers = self.inner.subscribers.lock().unwrap();
+        subscribers.retain(|handle| handle.widget_id() != widget);
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Signal {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Default> Default for Signal<T> {
+    fn default() -> Self {
+        Signal::new(T::default())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|value| f.debug_tuple("Signal").field(value).finish())
+    }
+}
+
+/// Keep a property of `view`'s widget in sync with `signal`.
+///
+/// `apply` is called with the widget whenever the value of `signal` changes,
+/// without rebuilding the view tree. For example, to display a counter:
+///
+/// ```ignore
+/// bind(label(""), fps.clone(), |mut label, fps: &u32| {
+///     label.set_text(format!("{fps} FPS"));
+/// })
+/// ```
+///
+/// `view` itself shouldn't also set the bound property, as its rebuilds would
+/// overwrite the value from the signal until the signal next changes.
+pub fn bind<State, Action, V, T, F>(
+    view: V,
+    signal: Signal<T>,
+    apply: F,
+) -> Bind<V, T, F, State, Action>
+where
+    V: MasonryView<State, Action>,
+    T: 'static,
+    F: Fn(WidgetMut<'_, V::Element>, &T) + Clone + 'static,
+{
+    Bind {
+        view,
+        signal,
+        apply,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Bind<V, T, F, State, Action> {
+    view: V,
+    signal: Signal<T>,
+    apply: F,
+    phantom: PhantomData<fn(&mut State) -> Action>,
+}
+
+impl<V, T, F, State, Action> Bind<V, T, F, State, Action> {
+    fn binding<W>(&self) -> Box<dyn Binding<W>>
+    where
+        T: 'static,
+        F: Fn(WidgetMut<'_, W>, &T) + Clone + 'static,
+        W: Widget,
+    {
+        Box::new(SignalBinding {
+            signal: self.signal.clone(),
+            seen_version: None,
+            apply: self.apply.clone(),
+        })
+    }
+}
+
+impl<State, Action, V, T, F> MasonryView<State, Action> for Bind<V, T, F, State, Action>
+where
+    State: 'static,
+    Action: 'static,
+    V: MasonryView<State, Action>,
+    T: 'static,
+    F: Fn(WidgetMut<'_, V::Element>, &T) + Clone + 'static,
+{
+    type Element = Bound<V::Element>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, view_state) = self.view.build(cx);
+        let widget = Bound {
+            child,
+            binding: self.binding(),
+        };
+        (WidgetPod::new(widget), view_state)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        let child = element.ctx.get_mut(&mut element.widget.child);
+        self.view.rebuild(view_state, cx, &prev.view, child);
+        if !self.signal.ptr_eq(&prev.signal) {
+            prev.signal.unsubscribe(element.ctx.widget_id());
+            self.signal.subscribe(element.ctx.wake_handle());
+            element.widget.binding = self.binding();
+            let child = element.ctx.get_mut(&mut element.widget.child);
+            element.widget.binding.poll(child);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.view.message(view_state, id_path, message, app_state)
+    }
+}
+
+/// A signal and the function which applies its value to a widget.
+trait Binding<W: Widget> {
+    /// Apply the value of the signal to `widget`, if it changed since the last poll.
+    fn poll(&mut self, widget: WidgetMut<'_, W>);
+
+    /// Wake `handle` whenever the signal is set.
+    fn subscribe(&self, handle: WakeHandle);
+
+    fn unsubscribe(&self, widget: WidgetId);
+}
+
+struct SignalBinding<T, F> {
+    signal: Signal<T>,
+    seen_version: Option<u64>,
+    apply: F,
+}
+
+impl<W, T, F> Binding<W> for SignalBinding<T, F>
+where
+    W: Widget,
+    F: Fn(WidgetMut<'_, W>, &T),
+{
+    fn poll(&mut self, widget: WidgetMut<'_, W>) {
+        let version = self.signal.version();
+        if self.seen_version != Some(version) {
+            self.seen_version = Some(version);
+            self.signal.with(|value| (self.apply)(widget, value));
+        }
+    }
+
+    fn subscribe(&self, handle: WakeHandle) {
+        self.signal.subscribe(handle);
+    }
+
+    fn unsubscribe(&self, widget: WidgetId) {
+        self.signal.unsubscribe(widget);
+    }
+}
+
+/// The widget created by [`bind`], which updates its child when the signal changes.
+pub struct Bound<W: Widget> {
+    child: WidgetPod<W>,
+    binding: Box<dyn Binding<W>>,
+}
+
+/// Forward all events to the child widget.
+impl<W: Widget> Widget for Bound<W> {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _: &mut LifeCycleCtx, _: &StatusChange) {
+        // Intentionally do nothing
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.binding.subscribe(ctx.wake_handle());
+                self.binding.poll(ctx.get_mut(&mut self.child));
+            }
+            LifeCycle::Wake => self.binding.poll(ctx.get_mut(&mut self.child)),
+            LifeCycle::WidgetRemoved => self.binding.unsubscribe(ctx.widget_id()),
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut vec = SmallVec::new();
+        vec.push(self.child.id());
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use masonry::testing::TestHarness;
+    use masonry::widget::{Label, RootWidget};
+
+    use super::*;
+    use crate::view::label;
+
+    #[test]
+    fn signal_updates_bound_label() {
+        let level = Signal::new(0_u32);
+        let view = bind(label("unset"), level.clone(), |mut label, level: &u32| {
+            label.set_text(level.to_string());
+        });
+
+        let mut cx = ViewCx::new();
+        let (pod, _) = MasonryView::<()>::build(&view, &mut cx);
+        let mut harness = TestHarness::create(RootWidget::from_pod(pod));
+        let label_text = |harness: &TestHarness| {
+            let root = harness.root_widget();
+            let bound = root.children()[0];
+            let label = bound.children()[0].downcast::<Label>().unwrap();
+            label.text().to_string()
+        };
+        assert_eq!(label_text(&harness), "0");
+
+        // Setting the signal on another thread wakes the bound widget
+        let setter = level.clone();
+        std::thread::spawn(move || setter.set(42)).join().unwrap();
+        assert_eq!(level.version(), 1);
+        assert!(harness.wait_for_wake(Duration::from_secs(1)));
+        assert_eq!(label_text(&harness), "42");
+    }
+}
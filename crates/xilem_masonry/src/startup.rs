@@ -0,0 +1,473 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Initializing an app in the background, showing a splash screen until it's done.
+//!
+//! See [`Xilem::new_with_init`](crate::Xilem::new_with_init).
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+use std::time::Duration;
+
+use accesskit::Role;
+use masonry::task_pool::Task;
+use masonry::wake::WakeHandle;
+use masonry::widget::WidgetMut;
+use masonry::{
+    AccessCtx, AccessEvent, Affine, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+use smallvec::SmallVec;
+use vello::peniko::BlendMode;
+use vello::Scene;
+
+use crate::any_view::DynWidget;
+use crate::recovery::panic_message;
+use crate::view::{label, Either, Label};
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// The state of an app started with [`Xilem::new_with_init`](crate::Xilem::new_with_init).
+pub enum Startup<T> {
+    /// The app is still being initialized, and its splash screen is shown.
+    Loading(Pending<T>),
+    /// The app has been initialized, with this state.
+    Ready(T),
+    /// Initializing the app panicked with this message, which is shown instead of
+    /// the app.
+    Failed(String),
+}
+
+/// The initial state of an app, which is being computed on another thread.
+pub struct Pending<T> {
+    task: Arc<Task<T>>,
+}
+
+impl<T: Send + 'static> Pending<T> {
+    /// Run `init` to completion on a new thread.
+    ///
+    /// If `init` panics, the app moves to [`Startup::Failed`].
+    pub(crate) fn spawn(init: impl Future<Output = T> + Send + 'static) -> Self {
+        Pending {
+            task: Arc::new(Task::spawn_thread(move || block_on(init))),
+        }
+    }
+}
+
+/// An initialization which the splash screen waits for, whatever its result is.
+trait Waiting {
+    fn wake_when_done(&self, handle: WakeHandle);
+    fn is_done(&self) -> bool;
+}
+
+impl<T> Waiting for Task<T> {
+    fn wake_when_done(&self, handle: WakeHandle) {
+        Task::wake_when_done(self, handle);
+    }
+
+    fn is_done(&self) -> bool {
+        Task::is_done(self)
+    }
+}
+
+impl<T> Startup<T> {
+    /// The app's state, if it has been initialized.
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            Startup::Ready(state) => Some(state),
+            Startup::Loading(_) | Startup::Failed(_) => None,
+        }
+    }
+
+    /// The app's state, if it has been initialized.
+    pub fn ready_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Startup::Ready(state) => Some(state),
+            Startup::Loading(_) | Startup::Failed(_) => None,
+        }
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Poll `future` on the current thread until it is done, sleeping while it waits.
+///
+/// Futures which need a particular runtime, such as to do IO, should be spawned on
+/// that runtime by the init future, which waits for them.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// The app logic of an app with a splash screen, which shows `splash` until the
+/// state is ready and `logic` after.
+pub(crate) fn startup_logic<T: 'static, S, V>(
+    mut splash: impl FnMut() -> S,
+    mut logic: impl FnMut(&mut T) -> V,
+) -> impl FnMut(&mut Startup<T>) -> StartupView<S, V> {
+    move |state| match state {
+        Startup::Loading(pending) => StartupView {
+            content: Either::A(splash()),
+            waiting: Some(pending.task.clone()),
+        },
+        Startup::Ready(state) => StartupView {
+            content: Either::B(Either::B(Ready(logic(state)))),
+            waiting: None,
+        },
+        Startup::Failed(message) => StartupView {
+            content: Either::B(Either::A(label(format!(
+                "The app failed to start: {message}"
+            )))),
+            waiting: None,
+        },
+    }
+}
+
+/// The root view of an app with a splash screen, which is either the splash screen,
+/// the app's view tree once it has been initialized, or an error if that failed.
+pub struct StartupView<S, V> {
+    content: Either<S, Either<Label, Ready<V>>>,
+    /// The initialization, while the splash screen is shown
+    waiting: Option<Arc<dyn Waiting>>,
+}
+
+/// The view tree of an app which has been initialized.
+pub struct Ready<V>(V);
+
+pub struct StartupState<Inner> {
+    inner: Inner,
+    /// How long the app takes to fade in once it's ready
+    pub(crate) fade: Duration,
+}
+
+/// The routing id of the content.
+const CONTENT_ID: u64 = 0;
+
+impl<T, S, V> MasonryView<Startup<T>> for StartupView<S, V>
+where
+    S: MasonryView<Startup<T>>,
+    V: MasonryView<T>,
+{
+    type Element = StartupWidget;
+    type ViewState =
+        StartupState<<Either<S, Either<Label, Ready<V>>> as MasonryView<Startup<T>>>::ViewState>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, inner) = cx.with_id(ViewId::for_type::<Self>(CONTENT_ID), |cx| {
+            self.content.build(cx)
+        });
+        let pod = cx.with_action_widget(|_| {
+            WidgetPod::new(StartupWidget {
+                child,
+                waiting: self.waiting.clone(),
+                fade: None,
+            })
+        });
+        let view_state = StartupState {
+            inner,
+            fade: Duration::ZERO,
+        };
+        (pod, view_state)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        cx.with_id(ViewId::for_type::<Self>(CONTENT_ID), |cx| {
+            let child = element.ctx.get_mut(&mut element.widget.child);
+            self.content
+                .rebuild(&mut view_state.inner, cx, &prev.content, child);
+        });
+        match (&prev.waiting, &self.waiting) {
+            (Some(_), None) => {
+                element.widget.waiting = None;
+                if !view_state.fade.is_zero() {
+                    element.widget.fade = Some(Fade {
+                        elapsed: Duration::ZERO,
+                        duration: view_state.fade,
+                    });
+                    element.ctx.request_anim_frame();
+                }
+                cx.mark_changed();
+            }
+            (_, Some(waiting)) => {
+                // The state may have been reset to a new initialization, such as
+                // by panic recovery
+                if !prev
+                    .waiting
+                    .as_ref()
+                    .is_some_and(|prev| Arc::ptr_eq(prev, waiting))
+                {
+                    waiting.wake_when_done(element.ctx.wake_handle());
+                    element.widget.waiting = Some(waiting.clone());
+                    cx.mark_changed();
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut Startup<T>,
+    ) -> MessageResult<()> {
+        if let Some((start, rest)) = id_path.split_first() {
+            if start.routing_id() == CONTENT_ID {
+                return self
+                    .content
+                    .message(&mut view_state.inner, rest, message, app_state);
+            }
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::Other(payload) if payload.is::<StartupFinished>() => {
+                    let Startup::Loading(pending) = app_state else {
+                        return MessageResult::Nop;
+                    };
+                    let Some(result) = pending.task.take() else {
+                        return MessageResult::Nop;
+                    };
+                    *app_state = match result {
+                        Ok(state) => Startup::Ready(state),
+                        Err(payload) => {
+                            let message = panic_message(&*payload);
+                            tracing::error!(message, "Initializing the app panicked");
+                            Startup::Failed(message)
+                        }
+                    };
+                    MessageResult::RequestRebuild
+                }
+                action => {
+                    tracing::error!("Wrong action type in StartupView::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in StartupView::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+impl<T, V> MasonryView<Startup<T>> for Ready<V>
+where
+    V: MasonryView<T>,
+{
+    type Element = V::Element;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        self.0.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        element: WidgetMut<Self::Element>,
+    ) {
+        self.0.rebuild(view_state, cx, &prev.0, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut Startup<T>,
+    ) -> MessageResult<()> {
+        match app_state {
+            Startup::Ready(state) => self.0.message(view_state, id_path, message, state),
+            Startup::Loading(_) | Startup::Failed(_) => MessageResult::Stale(message),
+        }
+    }
+}
+
+/// The action sent by a [`StartupWidget`] when the app has been initialized.
+struct StartupFinished;
+
+struct Fade {
+    elapsed: Duration,
+    duration: Duration,
+}
+
+/// The widget created by [`StartupView`], which waits for the app to be initialized
+/// and fades it in.
+pub struct StartupWidget {
+    child: WidgetPod<DynWidget>,
+    waiting: Option<Arc<dyn Waiting>>,
+    fade: Option<Fade>,
+}
+
+/// Forward all events to the child widget.
+impl Widget for StartupWidget {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _: &mut LifeCycleCtx, _: &StatusChange) {
+        // Intentionally do nothing
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+        match (event, &self.waiting) {
+            (LifeCycle::WidgetAdded, Some(waiting)) => waiting.wake_when_done(ctx.wake_handle()),
+            (LifeCycle::Wake, Some(waiting)) if waiting.is_done() => {
+                self.waiting = None;
+                ctx.submit_action(masonry::Action::Other(Arc::new(StartupFinished)));
+            }
+            (LifeCycle::AnimFrame(interval), _) => {
+                if let Some(fade) = &mut self.fade {
+                    fade.elapsed += Duration::from_nanos(*interval);
+                    if fade.elapsed >= fade.duration {
+                        self.fade = None;
+                    } else {
+                        ctx.request_anim_frame();
+                    }
+                    ctx.request_paint();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        let Some(fade) = &self.fade else {
+            self.child.paint(ctx, scene);
+            return;
+        };
+        let alpha = fade.elapsed.as_secs_f64() / fade.duration.as_secs_f64();
+        let rect = ctx.size().to_rect();
+        scene.push_layer(BlendMode::default(), alpha as f32, Affine::IDENTITY, &rect);
+        self.child.paint(ctx, scene);
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut vec = SmallVec::new();
+        vec.push(self.child.id());
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget::RootWidget;
+
+    use super::*;
+
+    type TestLogic = Box<dyn FnMut(&mut Startup<i32>) -> StartupView<Label, Label>>;
+
+    fn logic() -> TestLogic {
+        Box::new(startup_logic(
+            || label("Loading"),
+            |n: &mut i32| label(n.to_string()),
+        ))
+    }
+
+    /// Build the view of `state`, wait for the app to be initialized, and rebuild.
+    fn start(state: &mut Startup<i32>, fade: Duration) -> (TestHarness, StartupState<impl Sized>) {
+        let mut logic = logic();
+        let view = logic(state);
+        let mut cx = ViewCx::new();
+        let (pod, mut view_state) = MasonryView::<Startup<i32>>::build(&view, &mut cx);
+        view_state.fade = fade;
+        let mut harness = TestHarness::create(RootWidget::from_pod(pod));
+        assert!(harness.wait_for_wake(Duration::from_secs(5)));
+        let (action, _) = harness.pop_action().expect("init didn't finish");
+        let result = MasonryView::<Startup<i32>>::message(
+            &view,
+            &mut view_state,
+            &[],
+            Box::new(action),
+            state,
+        );
+        assert!(matches!(result, MessageResult::RequestRebuild));
+
+        let next = logic(state);
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<RootWidget<StartupWidget>>();
+            MasonryView::<Startup<i32>>::rebuild(
+                &next,
+                &mut view_state,
+                &mut cx,
+                &view,
+                root.get_element(),
+            );
+        });
+        (harness, view_state)
+    }
+
+    fn content_text(harness: &TestHarness) -> String {
+        // The startup widget, then the widgets of both `Either`s
+        let startup = harness.root_widget().children()[0];
+        let content = startup.children()[0].children()[0].children()[0];
+        let label = content.downcast::<masonry::widget::Label>().unwrap();
+        label.text().to_string()
+    }
+
+    #[test]
+    fn splash_is_replaced_once_ready() {
+        let mut state = Startup::Loading(Pending::spawn(async { 42 }));
+        let (harness, _) = start(&mut state, Duration::from_millis(200));
+        assert_eq!(state.ready(), Some(&42));
+
+        let startup = harness.root_widget().children()[0];
+        assert!(startup.downcast::<StartupWidget>().unwrap().fade.is_some());
+        assert_eq!(content_text(&harness), "42");
+    }
+
+    #[test]
+    fn panicking_init_shows_error() {
+        let mut state = Startup::Loading(Pending::spawn(async { panic!("no config") }));
+        let (harness, _) = start(&mut state, Duration::ZERO);
+        assert!(matches!(&state, Startup::Failed(message) if message == "no config"));
+        assert_eq!(content_text(&harness), "The app failed to start: no config");
+    }
+}
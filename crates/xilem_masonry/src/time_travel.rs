@@ -0,0 +1,163 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Stepping back and forth through the states an app has been in, such as to
+//! debug a state machine or to replay a demo.
+
+use std::collections::VecDeque;
+
+use masonry::widget::{
+    Button, CrossAxisAlignment, Flex, Label, MainAxisAlignment, ModalLayer, WidgetMut,
+};
+use masonry::{Widget, WidgetId, WidgetPod};
+
+/// The index of the label in the panel, between its buttons.
+const LABEL_IX: usize = 1;
+
+/// The snapshots of the app state, taken after each message which changed it.
+///
+/// See [`Xilem::with_time_travel`](crate::Xilem::with_time_travel).
+pub(crate) struct TimeTravel<State> {
+    snapshots: VecDeque<State>,
+    /// The snapshot the app is showing, which is the last one unless the user
+    /// stepped back.
+    position: usize,
+    max_snapshots: usize,
+    clone: fn(&State) -> State,
+    back_button: WidgetId,
+    forward_button: WidgetId,
+}
+
+impl<State> TimeTravel<State> {
+    pub(crate) fn new(state: &State, clone: fn(&State) -> State, max_snapshots: usize) -> Self {
+        TimeTravel {
+            snapshots: VecDeque::from([clone(state)]),
+            position: 0,
+            max_snapshots: max_snapshots.max(1),
+            clone,
+            back_button: WidgetId::next(),
+            forward_button: WidgetId::next(),
+        }
+    }
+
+    /// Take a snapshot of `state`.
+    ///
+    /// If the user had stepped back, the snapshots after the one shown are
+    /// replaced by this one.
+    pub(crate) fn record(&mut self, state: &State) {
+        self.snapshots.truncate(self.position + 1);
+        self.snapshots.push_back((self.clone)(state));
+        if self.snapshots.len() > self.max_snapshots {
+            self.snapshots.pop_front();
+        }
+        self.position = self.snapshots.len() - 1;
+    }
+
+    /// Whether `widget_id` is one of the panel's buttons.
+    pub(crate) fn is_panel_button(&self, widget_id: WidgetId) -> bool {
+        widget_id == self.back_button || widget_id == self.forward_button
+    }
+
+    /// Step back or forward for a press of the panel button `widget_id`, returning
+    /// the state to show, or `None` if there is no snapshot in that direction.
+    pub(crate) fn step(&mut self, widget_id: WidgetId) -> Option<State> {
+        if widget_id == self.back_button && self.position > 0 {
+            self.position -= 1;
+        } else if widget_id == self.forward_button && self.position + 1 < self.snapshots.len() {
+            self.position += 1;
+        } else {
+            return None;
+        }
+        Some((self.clone)(&self.snapshots[self.position]))
+    }
+
+    fn describe(&self) -> String {
+        format!("State {} of {}", self.position + 1, self.snapshots.len())
+    }
+
+    /// Put `app` above the panel, in the widget which becomes the content of the
+    /// root.
+    pub(crate) fn dock(&self, app: WidgetPod<Box<dyn Widget>>) -> Flex {
+        let panel = Flex::row()
+            .main_axis_alignment(MainAxisAlignment::Center)
+            .with_child_id(Button::new("◀ Back"), self.back_button)
+            .with_child(Label::new(self.describe()))
+            .with_child_id(Button::new("Forward ▶"), self.forward_button);
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Fill)
+            .must_fill_main_axis(true)
+            .with_flex_child_pod(app, 1.0)
+            .with_child(panel)
+    }
+
+    /// Show the current position in the panel.
+    pub(crate) fn update_panel(&self, content: &mut WidgetMut<Box<dyn Widget>>) {
+        let mut dock = content.downcast::<Flex>();
+        let Some(mut panel) = dock.child_mut(1) else {
+            return;
+        };
+        let mut panel = panel.downcast::<Flex>();
+        let Some(mut label) = panel.child_mut(LABEL_IX) else {
+            return;
+        };
+        label.downcast::<Label>().set_text(self.describe());
+    }
+}
+
+/// Call `f` with the widget of the app's view tree, which is the content of the
+/// root, or above the panel if it is [docked](TimeTravel::dock).
+pub(crate) fn with_app_element<R>(
+    mut content: WidgetMut<Box<dyn Widget>>,
+    docked: bool,
+    f: impl FnOnce(WidgetMut<Box<dyn Widget>>) -> R,
+) -> R {
+    if !docked {
+        return f(content);
+    }
+    let mut dock = content.downcast::<Flex>();
+    f(dock.child_mut(0).expect("the app should be in the dock"))
+}
+
+/// Replace the widget of the app's view tree; see [`with_app_element`].
+///
+/// This is only done by panic recovery, so the root's content is in `layer`.
+pub(crate) fn set_app_pod(
+    layer: &mut WidgetMut<ModalLayer>,
+    docked: bool,
+    pod: WidgetPod<Box<dyn Widget>>,
+) {
+    if !docked {
+        layer.set_child_pod(pod);
+        return;
+    }
+    let mut content = layer.child_mut();
+    let mut dock = content.downcast::<Flex>();
+    dock.remove_child(0);
+    dock.insert_flex_child_pod(0, pod, 1.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepping_back_then_recording_drops_the_later_snapshots() {
+        let mut history = TimeTravel::new(&0, Clone::clone, 3);
+        for state in 1..=3 {
+            history.record(&state);
+        }
+        // The oldest snapshot is dropped once there are too many
+        assert_eq!(history.snapshots, [1, 2, 3]);
+
+        let (back, forward) = (history.back_button, history.forward_button);
+        assert_eq!(history.step(forward), None);
+        assert_eq!(history.step(back), Some(2));
+        assert_eq!(history.step(back), Some(1));
+        assert_eq!(history.step(back), None);
+        assert_eq!(history.step(forward), Some(2));
+
+        history.record(&10);
+        assert_eq!(history.snapshots, [1, 2, 10]);
+        assert_eq!(history.describe(), "State 3 of 3");
+    }
+}
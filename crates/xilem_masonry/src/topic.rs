@@ -0,0 +1,265 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use masonry::widget::WidgetMut;
+use masonry::WidgetPod;
+
+use crate::id::IdPath;
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A typed channel for messages between views which are far apart in the view
+/// tree, such as a "collapse all" button and the panels it collapses.
+///
+/// Any callback can [`publish`] a message, and it is delivered to every view which
+/// [`subscribe`]s to the topic, through the same path as the messages from
+/// widgets. Topics are usually constants:
+///
+/// ```ignore
+/// const COLLAPSE_ALL: Topic<bool> = Topic::new("my_app::COLLAPSE_ALL");
+/// ```
+///
+/// Topics are told apart by their name, which should be unique in the app.
+pub struct Topic<T> {
+    name: &'static str,
+    phantom: PhantomData<fn(T)>,
+}
+
+impl<T> Topic<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Topic {
+            name,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for Topic<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Topic<T> {}
+
+impl<T> std::fmt::Debug for Topic<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Topic").field(&self.name).finish()
+    }
+}
+
+/// A message published to a topic, as it is sent to a subscriber.
+struct Published {
+    topic: &'static str,
+    payload: Rc<dyn Any>,
+}
+
+thread_local! {
+    /// The messages published since they were last delivered
+    static OUTBOX: RefCell<Vec<(&'static str, Rc<dyn Any>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Send `payload` to all of the views which [`subscribe`] to `topic`.
+///
+/// This must be called on the thread running the event loop, such as in a
+/// callback. The message is delivered once the callback returns, before the view
+/// tree is rebuilt.
+pub fn publish<T: 'static>(topic: Topic<T>, payload: T) {
+    OUTBOX.with_borrow_mut(|outbox| outbox.push((topic.name, Rc::new(payload))));
+}
+
+/// Deliver the published messages to their subscribers, and reduce the actions
+/// they return.
+///
+/// Returns whether the view tree should be rebuilt, like the result of a message
+/// from a widget.
+pub(crate) fn deliver_published<State, Action, V: MasonryView<State, Action>>(
+    view: &V,
+    view_state: &mut V::ViewState,
+    cx: &mut ViewCx,
+    id_path_buffer: &mut Vec<ViewId>,
+    app_state: &mut State,
+    reducer: &mut impl FnMut(&mut State, Action),
+    take_changed: fn(&mut State) -> bool,
+) -> bool {
+    let mut rebuild = false;
+    // Subscribers can publish messages of their own, which are delivered in turn
+    while let Some(published) =
+        OUTBOX.with_borrow_mut(|outbox| (!outbox.is_empty()).then(|| std::mem::take(outbox)))
+    {
+        for (topic, payload) in published {
+            let subscribers = cx.subscriptions.get(topic).cloned().unwrap_or_default();
+            for path in subscribers {
+                cx.id_paths.resolve_into(path, id_path_buffer);
+                let message = Box::new(Published {
+                    topic,
+                    payload: payload.clone(),
+                });
+                match view.message(view_state, id_path_buffer, message, app_state) {
+                    MessageResult::Action(action) => {
+                        reducer(app_state, action);
+                        rebuild |= take_changed(app_state);
+                    }
+                    MessageResult::RequestRebuild => rebuild = true,
+                    MessageResult::Nop => {}
+                    // The subscriber has been removed from the view tree
+                    MessageResult::Stale(_) => {
+                        if let Some(subscribers) = cx.subscriptions.get_mut(topic) {
+                            subscribers.retain(|subscriber| *subscriber != path);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    rebuild
+}
+
+fn add_subscriber(cx: &mut ViewCx, topic: &'static str) {
+    let path: IdPath = cx.id_path;
+    let subscribers = cx.subscriptions.entry(topic).or_default();
+    if !subscribers.contains(&path) {
+        subscribers.push(path);
+    }
+}
+
+/// Call `callback` with the messages [published](publish) to `topic`, for as long
+/// as this view is in the view tree.
+///
+/// The view itself looks like `child`. The action returned by `callback` is
+/// handled like the actions of `child`.
+pub fn subscribe<State, Action, T, V, F>(
+    topic: Topic<T>,
+    child: V,
+    callback: F,
+) -> Subscribe<T, V, F>
+where
+    V: MasonryView<State, Action>,
+    F: Fn(&mut State, &T) -> Action + 'static,
+{
+    Subscribe {
+        topic,
+        child,
+        callback,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Subscribe<T, V, F> {
+    topic: Topic<T>,
+    child: V,
+    callback: F,
+}
+
+/// The routing id of the child.
+const CHILD_ID: u64 = 0;
+
+impl<State, Action, T, V, F> MasonryView<State, Action> for Subscribe<T, V, F>
+where
+    T: 'static,
+    V: MasonryView<State, Action>,
+    F: Fn(&mut State, &T) -> Action + 'static,
+{
+    type Element = V::Element;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        add_subscriber(cx, self.topic.name);
+        cx.with_id(ViewId::for_type::<V>(CHILD_ID), |cx| self.child.build(cx))
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        element: WidgetMut<Self::Element>,
+    ) {
+        // The subscription to the previous topic is dropped on its next message
+        if prev.topic.name != self.topic.name {
+            add_subscriber(cx, self.topic.name);
+        }
+        cx.with_id(ViewId::for_type::<V>(CHILD_ID), |cx| {
+            self.child.rebuild(view_state, cx, &prev.child, element);
+        });
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((start, rest)) = id_path.split_first() {
+            if start.routing_id() == CHILD_ID {
+                return self.child.message(view_state, rest, message, app_state);
+            }
+            return MessageResult::Stale(message);
+        }
+        let published = match message.downcast::<Published>() {
+            Ok(published) => published,
+            Err(message) => return MessageResult::Stale(message),
+        };
+        match published.payload.downcast_ref::<T>() {
+            Some(payload) if published.topic == self.topic.name => {
+                MessageResult::Action((self.callback)(app_state, payload))
+            }
+            _ => MessageResult::Stale(published),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::{flex, label};
+
+    const COLLAPSE_ALL: Topic<bool> = Topic::new("tests::COLLAPSE_ALL");
+
+    #[test]
+    fn published_messages_reach_all_subscribers() {
+        let panel = |index: usize| {
+            subscribe(
+                COLLAPSE_ALL,
+                label(""),
+                move |state: &mut Vec<(usize, bool)>, collapsed: &bool| {
+                    state.push((index, *collapsed));
+                },
+            )
+        };
+        let view = flex((panel(0), flex((panel(1),))));
+        let mut cx = ViewCx::new();
+        let (_, mut view_state) = view.build(&mut cx);
+        let mut state = Vec::new();
+        let mut deliver = |state: &mut Vec<(usize, bool)>, cx: &mut ViewCx| {
+            deliver_published(
+                &view,
+                &mut view_state,
+                cx,
+                &mut Vec::new(),
+                state,
+                &mut |_, ()| {},
+                |_| true,
+            )
+        };
+        assert!(!deliver(&mut state, &mut cx));
+
+        publish(COLLAPSE_ALL, true);
+        assert!(deliver(&mut state, &mut cx));
+        assert_eq!(state, [(0, true), (1, true)]);
+
+        // Messages to other topics are ignored
+        publish(Topic::new("tests::OTHER"), true);
+        assert!(!deliver(&mut state, &mut cx));
+        assert_eq!(state.len(), 2);
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::{Deref, DerefMut};
+
+/// App state which records whether it has been mutated.
+///
+/// Any mutable access through [`DerefMut`] marks the state as changed, while
+/// reading it through [`Deref`] doesn't. When an app's state is wrapped in
+/// `Tracked` and [`Xilem::skip_unchanged_rebuilds`](crate::Xilem::skip_unchanged_rebuilds)
+/// is used, the view tree is not rebuilt after an event handler which didn't
+/// mutate the state.
+///
+/// Note that this is conservative: getting a mutable reference marks the state
+/// as changed, even if nothing is written through it.
+#[derive(Debug, Default)]
+pub struct Tracked<T> {
+    value: T,
+    changed: bool,
+}
+
+impl<T> Tracked<T> {
+    pub fn new(value: T) -> Self {
+        Tracked {
+            value,
+            changed: false,
+        }
+    }
+
+    /// Whether the state has been mutably accessed since the flag was last cleared.
+    pub fn has_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Mark the state as changed, without accessing it.
+    pub fn mark_changed(&mut self) {
+        self.changed = true;
+    }
+
+    /// Return whether the state has changed, and clear the flag.
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Tracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Tracked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        &mut self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutable_access_marks_changed() {
+        let mut state = Tracked::new(vec![1, 2]);
+        assert_eq!(state.len(), 2);
+        assert!(!state.has_changed());
+
+        state.push(3);
+        assert!(state.take_changed());
+        assert!(!state.has_changed());
+
+        state.mark_changed();
+        assert!(state.take_changed());
+        assert_eq!(state.into_inner(), [1, 2, 3]);
+    }
+}
@@ -105,6 +105,10 @@ impl ElementSplice for VecSplice<'_, '_, masonry::WidgetPod<Box<dyn masonry::Wid
         self.delete(n);
     }
 
+    fn move_from(&mut self, _offset: usize, _n: usize) {
+        unreachable!("VecSplice can only be used for `build`, not rebuild")
+    }
+
     fn len(&self) -> usize {
         self.len()
     }
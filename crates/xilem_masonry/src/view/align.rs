@@ -0,0 +1,101 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::paint_scene_helpers::UnitPoint;
+use masonry::widget::{self, WidgetMut};
+use masonry::WidgetPod;
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Place `child` at `alignment` in the space its parent gives it, such as
+/// [`UnitPoint::CENTER`] to center it.
+///
+/// The child keeps its own size, and the aligned view takes all of the space
+/// it's given.
+pub fn align<V>(child: V, alignment: UnitPoint) -> Align<V> {
+    Align { child, alignment }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Align<V> {
+    child: V,
+    alignment: UnitPoint,
+}
+
+impl<State, Action, V> MasonryView<State, Action> for Align<V>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::Align;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let widget = widget::Align::new_pod(self.alignment, child.boxed());
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.alignment != self.alignment {
+            element.set_alignment(self.alignment);
+            cx.mark_changed();
+        }
+        let mut child = element.child_mut();
+        let child = child.downcast();
+        self.child.rebuild(child_state, cx, &prev.child, child);
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::{Point, Size};
+
+    use super::*;
+    use crate::view::{label, padding};
+    use crate::ViewExt;
+
+    #[test]
+    fn padded_child_moves_with_the_alignment() {
+        // The fixed size and the padding are separate boxes
+        let view = |alignment| padding(label("").width(20.0).height(10.0), 5.0).align(alignment);
+        let first = view(UnitPoint::TOP_LEFT);
+        let mut cx = ViewCx::new();
+        let (pod, mut view_state) = MasonryView::<(), ()>::build(&first, &mut cx);
+        let mut harness = TestHarness::create_with_size(
+            widget::SizedBox::new_pod(pod.boxed()).expand(),
+            Size::new(100.0, 50.0),
+        );
+        let child_rect = |harness: &TestHarness| {
+            let align = harness.root_widget().children()[0];
+            align.children()[0].state().layout_rect()
+        };
+        assert_eq!(child_rect(&harness).size(), Size::new(30.0, 20.0));
+        assert_eq!(child_rect(&harness).origin(), Point::ORIGIN);
+
+        let second = view(UnitPoint::BOTTOM_RIGHT);
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<widget::SizedBox>();
+            let mut align = root.child_mut().unwrap();
+            let align = align.downcast();
+            MasonryView::<(), ()>::rebuild(&second, &mut view_state, &mut cx, &first, align);
+        });
+        assert_eq!(child_rect(&harness).origin(), Point::new(70.0, 30.0));
+    }
+}
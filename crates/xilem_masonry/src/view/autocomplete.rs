@@ -0,0 +1,255 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use masonry::{theme, widget::WidgetMut, ArcStr, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+type Callback<State, Action> = Box<dyn Fn(&mut State, String) -> Action + 'static>;
+
+type Provider = Arc<dyn Fn(&str) -> Vec<ArcStr> + Send + Sync>;
+
+/// A text input which shows `suggestions` in a popup below it while the user types.
+///
+/// The suggestions are usually computed from `contents` by the app logic, so they
+/// follow the text as it changes. Suggestions which are slow to compute, such as
+/// the results of a search, can be loaded in the background with a
+/// [`provider`](Autocomplete::provider) instead. Picking a suggestion, with the
+/// pointer or with the arrow keys and <kbd>Enter</kbd>, replaces the text and calls
+/// `on_changed`.
+pub fn autocomplete<F, State, Action>(
+    contents: String,
+    suggestions: impl IntoIterator<Item = impl Into<ArcStr>>,
+    on_changed: F,
+) -> Autocomplete<State, Action>
+where
+    F: Fn(&mut State, String) -> Action + 'static,
+{
+    Autocomplete {
+        contents,
+        suggestions: suggestions.into_iter().map(Into::into).collect(),
+        on_changed: Box::new(on_changed),
+        on_enter: None,
+        provider: None,
+        debounce: Duration::from_millis(theme::AUTOCOMPLETE_DEBOUNCE),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Autocomplete<State, Action> {
+    contents: String,
+    suggestions: Vec<ArcStr>,
+    on_changed: Callback<State, Action>,
+    on_enter: Option<Callback<State, Action>>,
+    provider: Option<Provider>,
+    debounce: Duration,
+}
+
+impl<State, Action> Autocomplete<State, Action> {
+    /// Called when <kbd>Enter</kbd> is pressed without a highlighted suggestion.
+    pub fn on_enter<F>(mut self, on_enter: F) -> Self
+    where
+        F: Fn(&mut State, String) -> Action + 'static,
+    {
+        self.on_enter = Some(Box::new(on_enter));
+        self
+    }
+
+    /// Load the suggestions for the text with `provider`, on the
+    /// [task pool](ViewCx::task_pool).
+    ///
+    /// The provider is called once the user has stopped typing for the
+    /// [debounce](Self::debounce) delay. Its results replace the suggestions, until
+    /// the `suggestions` passed to [`autocomplete`] change. Results for text which
+    /// has changed since are dropped.
+    pub fn provider<F>(mut self, provider: F) -> Self
+    where
+        F: Fn(&str) -> Vec<ArcStr> + Send + Sync + 'static,
+    {
+        self.provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// How long the text has to stay the same before the provider is called, which
+    /// is [`theme::AUTOCOMPLETE_DEBOUNCE`] milliseconds by default.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+}
+
+impl<State: 'static, Action: 'static> MasonryView<State, Action> for Autocomplete<State, Action> {
+    type Element = masonry::widget::Autocomplete;
+    // The text suggestions were requested for, until the provider is started
+    type ViewState = Option<String>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        // Both the text box and the autocomplete widget itself send actions
+        let textbox = cx.with_action_widget(|_| {
+            WidgetPod::new(masonry::widget::Textbox::new(self.contents.clone()))
+        });
+        let pod = cx.with_action_widget(|_| {
+            WidgetPod::new(
+                masonry::widget::Autocomplete::from_pod(textbox)
+                    .with_suggestions(self.suggestions.iter().cloned())
+                    .with_debounce(self.debounce),
+            )
+        });
+        (pod, None)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        // As in `Textbox`, we compare to the element's text rather than the previous contents
+        {
+            let mut textbox = element.textbox_mut();
+            if self.contents != textbox.text() {
+                textbox.reset_text(self.contents.clone());
+                cx.mark_changed();
+            }
+        }
+
+        if prev.suggestions != self.suggestions {
+            element.set_suggestions(self.suggestions.iter().cloned());
+            cx.mark_changed();
+        }
+        if prev.debounce != self.debounce {
+            element.set_debounce(self.debounce);
+        }
+
+        if let (Some(query), Some(provider)) = (view_state.take(), &self.provider) {
+            let provider = provider.clone();
+            let task = cx.task_pool().spawn(move || provider(&query));
+            element.load_suggestions(task);
+        }
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> crate::MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Autocomplete::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::TextChanged(text) => {
+                    MessageResult::Action((self.on_changed)(app_state, text))
+                }
+                masonry::Action::TextEntered(text) if self.on_enter.is_some() => {
+                    MessageResult::Action((self.on_enter.as_ref().unwrap())(app_state, text))
+                }
+                masonry::Action::TextEntered(_) => MessageResult::Nop,
+                masonry::Action::SuggestionsRequested(query) if self.provider.is_some() => {
+                    // The provider is started when rebuilding, where the task pool is
+                    *view_state = Some(query);
+                    MessageResult::RequestRebuild
+                }
+                masonry::Action::SuggestionsRequested(_) => MessageResult::Nop,
+                _ => {
+                    tracing::error!("Wrong action type in Autocomplete::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Autocomplete::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget::{Label, SizedBox};
+
+    use super::*;
+
+    const FRUITS: [&str; 4] = ["apple", "apricot", "banana", "cherry"];
+
+    fn view(text: &str) -> Autocomplete<String, ()> {
+        autocomplete(text.to_string(), Vec::<ArcStr>::new(), |text, new_text| {
+            *text = new_text;
+        })
+        .provider(|query| {
+            FRUITS
+                .iter()
+                .filter(|fruit| fruit.contains(query))
+                .map(|fruit| ArcStr::from(*fruit))
+                .collect()
+        })
+        .debounce(Duration::ZERO)
+    }
+
+    fn suggestions(harness: &TestHarness) -> Vec<String> {
+        let autocomplete = harness.root_widget().children()[0];
+        autocomplete.children()[1..]
+            .iter()
+            .map(|child| child.downcast::<Label>().unwrap().text().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn provider_suggestions_are_loaded() {
+        let mut text = String::new();
+        let mut prev = view(&text);
+        let mut cx = ViewCx::new();
+        let (pod, mut view_state) = MasonryView::<String>::build(&prev, &mut cx);
+        let mut harness = TestHarness::create(SizedBox::new_pod(pod.boxed()));
+
+        let textbox_id = harness.root_widget().children()[0].children()[0].id();
+        harness.mouse_click_on(textbox_id);
+        harness.keyboard_type_chars("ap");
+
+        // Suggestions are requested for "a" and then "ap", and only the last request counts
+        while let Some((action, id)) = harness.pop_action() {
+            let mut id_path = Vec::new();
+            cx.id_paths.resolve_into(cx.widget_map[&id], &mut id_path);
+            let _ = MasonryView::<String>::message(
+                &prev,
+                &mut view_state,
+                &id_path,
+                Box::new(action),
+                &mut text,
+            );
+            let next = view(&text);
+            harness.edit_root_widget(|mut root| {
+                let mut root = root.downcast::<SizedBox>();
+                let mut child = root.child_mut().unwrap();
+                MasonryView::<String>::rebuild(
+                    &next,
+                    &mut view_state,
+                    &mut cx,
+                    &prev,
+                    child.downcast(),
+                );
+            });
+            prev = next;
+        }
+        assert_eq!(text, "ap");
+
+        for _ in 0..10 {
+            if suggestions(&harness) == ["apple", "apricot"] {
+                break;
+            }
+            harness.wait_for_wake(Duration::from_millis(500));
+        }
+        assert_eq!(suggestions(&harness), ["apple", "apricot"]);
+        let autocomplete = harness.root_widget().children()[0];
+        let autocomplete = autocomplete.downcast::<masonry::widget::Autocomplete>();
+        assert!(autocomplete.unwrap().is_expanded());
+    }
+}
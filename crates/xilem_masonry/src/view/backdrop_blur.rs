@@ -0,0 +1,65 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Blur whatever is painted beneath `child` by `radius` logical pixels, and paint
+/// `child` over it, such as for a frosted glass sidebar.
+///
+/// `child` should have a translucent background for the blur to show through.
+pub fn backdrop_blur<V>(radius: f64, child: V) -> BackdropBlur<V> {
+    BackdropBlur { radius, child }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct BackdropBlur<V> {
+    radius: f64,
+    child: V,
+}
+
+impl<State, Action, V> MasonryView<State, Action> for BackdropBlur<V>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::BackdropBlur;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let widget = widget::BackdropBlur::new_pod(self.radius, child.boxed());
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        {
+            let mut child = element.child_mut();
+            let child = child.downcast();
+            self.child.rebuild(child_state, cx, &prev.child, child);
+        }
+        if prev.radius != self.radius {
+            element.set_radius(self.radius);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
@@ -7,22 +7,33 @@ use crate::{MasonryView, MessageResult, ViewCx, ViewId};
 
 pub fn button<F, State, Action>(label: impl Into<ArcStr>, callback: F) -> Button<F>
 where
-    F: Fn(&mut State) -> Action + Send + 'static,
+    F: Fn(&mut State) -> Action + 'static,
 {
     Button {
         label: label.into(),
         callback,
+        disabled: false,
     }
 }
 
+#[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Button<F> {
     label: ArcStr,
     callback: F,
+    disabled: bool,
+}
+
+impl<F> Button<F> {
+    /// Disable the button, so that it can't be pressed, such as while the app state doesn't allow using it.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
 }
 
 impl<F, State, Action> MasonryView<State, Action> for Button<F>
 where
-    F: Fn(&mut State) -> Action + Send + 'static,
+    F: Fn(&mut State) -> Action + 'static,
 {
     type Element = masonry::widget::Button;
     type ViewState = ();
@@ -30,6 +41,7 @@ where
     fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
         cx.with_leaf_action_widget(|_| {
             WidgetPod::new(masonry::widget::Button::new(self.label.clone()))
+                .with_disabled(self.disabled)
         })
     }
 
@@ -44,6 +56,10 @@ where
             element.set_text(self.label.clone());
             cx.mark_changed();
         }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
     }
 
     fn message(
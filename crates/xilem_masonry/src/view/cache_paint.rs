@@ -0,0 +1,58 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Draw `child` from an image, which is drawn again only once `child` changes.
+///
+/// This is for expensive content which rarely changes, such as rendered markdown
+/// or a chart below an animation.
+pub fn cache_paint<V>(child: V) -> CachePaint<V> {
+    CachePaint { child }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct CachePaint<V> {
+    child: V,
+}
+
+impl<State, Action, V> MasonryView<State, Action> for CachePaint<V>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::CachePaint;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let widget = widget::CachePaint::new_pod(child.boxed());
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        let mut child = element.child_mut();
+        let child = child.downcast();
+        self.child.rebuild(child_state, cx, &prev.child, child);
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::rc::Rc;
+
+use masonry::vello::Scene;
+use masonry::{widget::WidgetMut, Size, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A view which paints the space it is given with `paint`, for drawing which no
+/// other view does, such as a chart.
+///
+/// `paint` is called with the scene to paint into, in the canvas's coordinates,
+/// and the canvas's size:
+///
+/// ```ignore
+/// canvas(move |scene, size| {
+///     let circle = Circle::new(size.to_rect().center(), 20.0);
+///     scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &circle);
+/// })
+/// ```
+///
+/// The canvas is painted again whenever the view tree is rebuilt, since the values
+/// captured by `paint` may have changed. Wrap it in a
+/// [`sized_box`](crate::view::sized_box) to give it a size.
+pub fn canvas<F>(paint: F) -> Canvas<F>
+where
+    F: Fn(&mut Scene, Size) + 'static,
+{
+    Canvas {
+        paint: Rc::new(paint),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Canvas<F> {
+    paint: Rc<F>,
+}
+
+impl<F> Canvas<F>
+where
+    F: Fn(&mut Scene, Size) + 'static,
+{
+    fn paint_fn(&self) -> impl Fn(&mut Scene, Size) + 'static {
+        let paint = self.paint.clone();
+        move |scene, size| paint(scene, size)
+    }
+}
+
+impl<State, Action, F> MasonryView<State, Action> for Canvas<F>
+where
+    F: Fn(&mut Scene, Size) + 'static,
+{
+    type Element = masonry::widget::Canvas;
+    type ViewState = ();
+
+    fn build(&self, _cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let widget = masonry::widget::Canvas::new(self.paint_fn());
+        (WidgetPod::new(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if !Rc::ptr_eq(&prev.paint, &self.paint) {
+            element.set_paint(self.paint_fn());
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        _id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in Canvas::message, but Canvas doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
@@ -11,34 +11,55 @@ pub fn checkbox<F, State, Action>(
     callback: F,
 ) -> Checkbox<F>
 where
-    F: Fn(&mut State, bool) -> Action + Send + 'static,
+    F: Fn(&mut State, bool) -> Action + 'static,
 {
     Checkbox {
         label: label.into(),
         callback,
         checked,
+        feedback: false,
+        disabled: false,
     }
 }
 
+#[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Checkbox<F> {
     label: ArcStr,
     checked: bool,
+    feedback: bool,
+    disabled: bool,
     callback: F,
 }
 
+impl<F> Checkbox<F> {
+    /// Tick and click when the checkbox is toggled, if the app plays feedback with
+    /// [`Xilem::with_feedback`](crate::Xilem::with_feedback).
+    pub fn feedback(mut self, feedback: bool) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// Disable the checkbox, so that it can't be toggled, such as while the app state doesn't allow using it.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
 impl<F, State, Action> MasonryView<State, Action> for Checkbox<F>
 where
-    F: Fn(&mut State, bool) -> Action + Send + 'static,
+    F: Fn(&mut State, bool) -> Action + 'static,
 {
     type Element = masonry::widget::Checkbox;
     type ViewState = ();
 
     fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
         cx.with_leaf_action_widget(|_| {
-            WidgetPod::new(masonry::widget::Checkbox::new(
-                self.checked,
-                self.label.clone(),
-            ))
+            WidgetPod::new(
+                masonry::widget::Checkbox::new(self.checked, self.label.clone())
+                    .with_feedback(self.feedback),
+            )
+            .with_disabled(self.disabled)
         })
     }
 
@@ -57,6 +78,14 @@ where
             element.set_checked(self.checked);
             cx.mark_changed();
         }
+        if prev.feedback != self.feedback {
+            element.set_feedback(self.feedback);
+            cx.mark_changed();
+        }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
     }
 
     fn message(
@@ -0,0 +1,121 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Components built by composing other views.
+//!
+//! These don't have widgets of their own: each one is a function which arranges
+//! existing views, so they also show how to build reusable components in an app.
+
+use masonry::{theme, widget::FillStrat, Insets};
+
+use crate::view::{button, flex, image_url, label, sized_box, ImageUrl, Label, SizedBox};
+use crate::{Axis, MasonryView, TextAlignment};
+
+/// A raised panel around `child`, for grouping related content.
+///
+/// The card is a [`sized_box`], so its padding and elevation can be changed with
+/// [`padding`](SizedBox::padding) and [`elevation`](SizedBox::elevation).
+pub fn card<V>(child: V) -> SizedBox<V> {
+    sized_box(child)
+        .padding(Insets::uniform(theme::CARD_PADDING))
+        .background(theme::BACKGROUND_LIGHT)
+        .rounded(theme::BUTTON_BORDER_RADIUS)
+        .elevation(theme::CARD_ELEVATION)
+}
+
+/// A small rounded label, such as for a tag or a filter.
+pub fn chip(text: impl Into<masonry::ArcStr>) -> SizedBox<Label> {
+    chip_box(label(text))
+}
+
+/// A [`chip`] with a button which calls `on_close`, such as to remove a tag.
+pub fn closable_chip<State, Action, F>(
+    text: impl Into<masonry::ArcStr>,
+    on_close: F,
+) -> SizedBox<impl MasonryView<State, Action>>
+where
+    F: Fn(&mut State) -> Action + 'static,
+{
+    chip_box(flex((label(text), button("×", on_close))).direction(Axis::Horizontal))
+}
+
+fn chip_box<V>(content: V) -> SizedBox<V> {
+    sized_box(content)
+        .padding(Insets::uniform_xy(theme::WIDGET_PADDING_HORIZONTAL, 2.0))
+        .border(theme::BORDER_LIGHT, 1.0)
+        .rounded(theme::BORDERED_WIDGET_HEIGHT / 2.0)
+}
+
+/// A round badge showing the initials of `name`, standing in for a person.
+pub fn avatar(name: &str) -> SizedBox<Label> {
+    // The label is padded to make the avatar about as tall as it is wide
+    let vertical_padding = ((theme::AVATAR_SIZE - theme::BASIC_WIDGET_HEIGHT) / 2.0).max(0.0);
+    sized_box(label(initials(name)).alignment(TextAlignment::Middle))
+        .width(theme::AVATAR_SIZE)
+        .padding(Insets::uniform_xy(0.0, vertical_padding))
+        .background(theme::PRIMARY_DARK)
+        .rounded(theme::AVATAR_SIZE / 2.0)
+}
+
+/// An [`avatar`] showing the picture at `url`, with the initials of `name` until
+/// the picture is fetched or if it can't be.
+pub fn avatar_image(
+    name: &str,
+    url: impl Into<String>,
+) -> SizedBox<ImageUrl<SizedBox<Label>, SizedBox<Label>>> {
+    let picture = image_url(url)
+        .placeholder(avatar(name))
+        .error(avatar(name))
+        .fill(FillStrat::Cover);
+    sized_box(picture)
+        .width(theme::AVATAR_SIZE)
+        .height(theme::AVATAR_SIZE)
+}
+
+/// The first letters of the first two words of `name`, in upper case.
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget;
+    use masonry::Size;
+
+    use super::*;
+    use crate::ViewCx;
+
+    #[test]
+    fn initials_of_names() {
+        assert_eq!(initials("ada lovelace"), "AL");
+        assert_eq!(initials("  Grace  Brewster Hopper "), "GB");
+        assert_eq!(initials("Ω"), "Ω");
+        assert_eq!(initials(""), "");
+    }
+
+    #[test]
+    fn card_pads_its_content() {
+        let view = card(avatar("Ada Lovelace"));
+        let mut cx = ViewCx::new();
+        let (pod, _) = MasonryView::<()>::build(&view, &mut cx);
+        let harness = TestHarness::create(widget::Flex::row().with_child_pod(pod.boxed()));
+
+        let card = harness.root_widget().children()[0];
+        let avatar = card.children()[0];
+        assert!(card.downcast::<widget::SizedBox>().is_some());
+        assert_eq!(
+            avatar.state().layout_rect().origin(),
+            (theme::CARD_PADDING, theme::CARD_PADDING).into()
+        );
+        let padding = Size::new(2.0 * theme::CARD_PADDING, 2.0 * theme::CARD_PADDING);
+        assert_eq!(
+            card.state().layout_rect().size(),
+            avatar.state().layout_rect().size() + padding
+        );
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    ArcStr, WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+type Callback<State, Action> = Box<dyn Fn(&mut State, usize, usize, String) -> Action + 'static>;
+
+/// A scrollable grid of text cells, with columns of the given widths.
+///
+/// The cells are only editable if [`on_commit`](DataGrid::on_commit) is set.
+pub fn data_grid<State, Action>(
+    column_widths: impl IntoIterator<Item = f64>,
+    rows: Vec<Vec<String>>,
+) -> DataGrid<State, Action> {
+    DataGrid {
+        column_widths: column_widths.into_iter().collect(),
+        header: None,
+        rows,
+        frozen_columns: 0,
+        row_height: None,
+        on_commit: None,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct DataGrid<State, Action> {
+    column_widths: Vec<f64>,
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    frozen_columns: usize,
+    row_height: Option<f64>,
+    on_commit: Option<Callback<State, Action>>,
+}
+
+impl<State, Action> DataGrid<State, Action> {
+    /// A header row, which stays at the top when the grid is scrolled.
+    pub fn header(mut self, header: Vec<String>) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Keep the first `count` columns on the left when the grid is scrolled.
+    ///
+    /// This is only used when the grid is first built.
+    // TODO: Support changing the frozen columns of an existing grid
+    pub fn frozen_columns(mut self, count: usize) -> Self {
+        self.frozen_columns = count;
+        self
+    }
+
+    /// The height of every row, including the header.
+    pub fn row_height(mut self, row_height: f64) -> Self {
+        self.row_height = Some(row_height);
+        self
+    }
+
+    /// Let the user edit cells, calling `on_commit` with the row, column and new
+    /// text of each cell they change.
+    ///
+    /// The grid shows the edited text until it is rebuilt, so `on_commit` should
+    /// update the rows in the app state if the edit is accepted.
+    pub fn on_commit<F>(mut self, on_commit: F) -> Self
+    where
+        F: Fn(&mut State, usize, usize, String) -> Action + 'static,
+    {
+        self.on_commit = Some(Box::new(on_commit));
+        self
+    }
+}
+
+fn text_cells(texts: &[String]) -> impl Iterator<Item = widget::Label> + '_ {
+    texts
+        .iter()
+        .map(|text| widget::Label::new(ArcStr::from(text.as_str())))
+}
+
+impl<State: 'static, Action: 'static> MasonryView<State, Action> for DataGrid<State, Action> {
+    type Element = widget::DataGrid;
+    type ViewState = ();
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        // The editor's actions are routed here too, so that they aren't reported as unknown
+        let editor = cx.with_action_widget(|_| WidgetPod::new(widget::Textbox::new("")));
+        cx.with_leaf_action_widget(|_| {
+            let mut grid =
+                widget::DataGrid::with_editor_pod(self.column_widths.iter().copied(), editor)
+                    .with_editable(self.on_commit.is_some())
+                    .with_frozen_columns(self.frozen_columns);
+            if let Some(header) = &self.header {
+                grid = grid.with_header(text_cells(header));
+            }
+            if let Some(row_height) = self.row_height {
+                grid = grid.with_row_height(row_height);
+            }
+            for row in &self.rows {
+                grid = grid.with_row(text_cells(row));
+            }
+            WidgetPod::new(grid)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.column_widths != self.column_widths {
+            element.set_column_widths(self.column_widths.iter().copied());
+            cx.mark_changed();
+        }
+        if prev.header != self.header {
+            element.set_header(text_cells(self.header.as_deref().unwrap_or_default()));
+            cx.mark_changed();
+        }
+        if prev.row_height != self.row_height {
+            element.set_row_height(
+                self.row_height
+                    .unwrap_or(masonry::theme::BORDERED_WIDGET_HEIGHT),
+            );
+            cx.mark_changed();
+        }
+        if prev.on_commit.is_some() != self.on_commit.is_some() {
+            element.set_editable(self.on_commit.is_some());
+            cx.mark_changed();
+        }
+
+        // As in `Textbox`, we compare to the element's text, which the user may have edited
+        let kept_rows = self.rows.len().min(prev.rows.len());
+        for (row_ix, (row, prev_row)) in self.rows.iter().zip(&prev.rows).enumerate() {
+            if row.len() != prev_row.len() {
+                element.remove_row(row_ix);
+                element.insert_row(row_ix, text_cells(row));
+                cx.mark_changed();
+                continue;
+            }
+            for (col_ix, text) in row.iter().enumerate() {
+                let changed = element.edit_cell(row_ix, col_ix, |mut cell| {
+                    let mut label = cell.downcast::<widget::Label>();
+                    let changed = label.text().as_ref() != text.as_str();
+                    if changed {
+                        label.set_text(ArcStr::from(text.as_str()));
+                    }
+                    changed
+                });
+                if changed {
+                    cx.mark_changed();
+                }
+            }
+        }
+        while element.widget.row_count() > self.rows.len() {
+            element.remove_row(element.widget.row_count() - 1);
+            cx.mark_changed();
+        }
+        for row in &self.rows[kept_rows..] {
+            element.add_row(text_cells(row));
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in DataGrid::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::CellEdited(row, col, text) if self.on_commit.is_some() => {
+                    MessageResult::Action((self.on_commit.as_ref().unwrap())(
+                        app_state, row, col, text,
+                    ))
+                }
+                // The editor's text only becomes part of the value when the edit is committed
+                masonry::Action::TextChanged(_) | masonry::Action::TextEntered(_) => {
+                    MessageResult::Nop
+                }
+                _ => {
+                    tracing::error!("Wrong action type in DataGrid::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in DataGrid::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{widget::WidgetMut, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A panel showing the frame rate, what the last frame spent its time on, the
+/// number of widgets and the area which was repainted.
+///
+/// This is meant to be shown while working on the performance of an app, such as
+/// at the top of its window. While it is shown, the app redraws every frame.
+pub fn debug_stats_overlay() -> DebugStatsOverlay {
+    DebugStatsOverlay
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct DebugStatsOverlay;
+
+impl<State, Action> MasonryView<State, Action> for DebugStatsOverlay {
+    type Element = masonry::widget::FrameStatsOverlay;
+    type ViewState = ();
+
+    fn build(&self, _cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        (
+            WidgetPod::new(masonry::widget::FrameStatsOverlay::new()),
+            (),
+        )
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        _cx: &mut ViewCx,
+        _prev: &Self,
+        _element: WidgetMut<Self::Element>,
+    ) {
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        _id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in DebugStatsOverlay::message, but DebugStatsOverlay doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
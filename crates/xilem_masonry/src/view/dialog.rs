@@ -0,0 +1,205 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    ArcStr, WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+type Callback<State, Action> = Box<dyn Fn(&mut State, Option<String>) -> Action + 'static>;
+
+/// A dialog which tells the user something, for use in a [`modal_layer`](crate::view::modal_layer).
+///
+/// `on_close` is called when the user clicks "OK" or presses <kbd>Enter</kbd> or
+/// <kbd>Escape</kbd>.
+pub fn alert<State, Action>(
+    title: impl Into<ArcStr>,
+    message: impl Into<ArcStr>,
+    on_close: impl Fn(&mut State) -> Action + 'static,
+) -> Dialog<State, Action> {
+    Dialog {
+        title: title.into(),
+        message: message.into(),
+        input: None,
+        accept_text: ArcStr::from("OK"),
+        cancel_text: None,
+        on_result: Box::new(move |state, _| on_close(state)),
+    }
+}
+
+/// A dialog which asks the user to confirm something, for use in a
+/// [`modal_layer`](crate::view::modal_layer).
+///
+/// `on_result` is called with `true` when the user clicks "OK" or presses
+/// <kbd>Enter</kbd>, and with `false` when they click "Cancel" or press
+/// <kbd>Escape</kbd>.
+pub fn confirm<State, Action>(
+    title: impl Into<ArcStr>,
+    message: impl Into<ArcStr>,
+    on_result: impl Fn(&mut State, bool) -> Action + 'static,
+) -> Dialog<State, Action> {
+    Dialog {
+        title: title.into(),
+        message: message.into(),
+        input: None,
+        accept_text: ArcStr::from("OK"),
+        cancel_text: Some(ArcStr::from("Cancel")),
+        on_result: Box::new(move |state, result| on_result(state, result.is_some())),
+    }
+}
+
+/// A dialog which asks the user for some text, for use in a
+/// [`modal_layer`](crate::view::modal_layer).
+///
+/// The text input starts with `initial_text`. `on_result` is called with the text
+/// when the user clicks "OK" or presses <kbd>Enter</kbd>, and with `None` when they
+/// click "Cancel" or press <kbd>Escape</kbd>.
+pub fn prompt<State, Action>(
+    title: impl Into<ArcStr>,
+    message: impl Into<ArcStr>,
+    initial_text: impl Into<String>,
+    on_result: impl Fn(&mut State, Option<String>) -> Action + 'static,
+) -> Dialog<State, Action> {
+    Dialog {
+        title: title.into(),
+        message: message.into(),
+        input: Some(initial_text.into()),
+        accept_text: ArcStr::from("OK"),
+        cancel_text: Some(ArcStr::from("Cancel")),
+        on_result: Box::new(on_result),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Dialog<State, Action> {
+    title: ArcStr,
+    message: ArcStr,
+    /// The initial text of the input, if there is one
+    input: Option<String>,
+    accept_text: ArcStr,
+    cancel_text: Option<ArcStr>,
+    on_result: Callback<State, Action>,
+}
+
+impl<State, Action> Dialog<State, Action> {
+    /// The text of the button which accepts the dialog.
+    pub fn accept_text(mut self, text: impl Into<ArcStr>) -> Self {
+        self.accept_text = text.into();
+        self
+    }
+
+    /// The text of the button which cancels the dialog.
+    ///
+    /// This adds a cancel button to an [`alert`].
+    pub fn cancel_text(mut self, text: impl Into<ArcStr>) -> Self {
+        self.cancel_text = Some(text.into());
+        self
+    }
+}
+
+// The parts of the dialog are routed here too, so that their actions aren't reported as unknown
+fn input_pod(cx: &mut ViewCx, text: &str) -> WidgetPod<widget::Textbox> {
+    cx.with_action_widget(|_| WidgetPod::new(widget::Textbox::new(text)))
+}
+
+fn button_pod(cx: &mut ViewCx, text: &ArcStr) -> WidgetPod<widget::Button> {
+    cx.with_action_widget(|_| WidgetPod::new(widget::Button::new(text.clone())))
+}
+
+impl<State: 'static, Action: 'static> MasonryView<State, Action> for Dialog<State, Action> {
+    type Element = widget::Dialog;
+    type ViewState = ();
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let input = self.input.as_deref().map(|text| input_pod(cx, text));
+        let accept = button_pod(cx, &self.accept_text);
+        let cancel = self.cancel_text.as_ref().map(|text| button_pod(cx, text));
+        cx.with_leaf_action_widget(|_| {
+            WidgetPod::new(widget::Dialog::from_pods(
+                self.title.clone(),
+                self.message.clone(),
+                input,
+                accept,
+                cancel,
+            ))
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.title != self.title {
+            element.set_title(self.title.clone());
+            cx.mark_changed();
+        }
+        if prev.message != self.message {
+            element.set_message(self.message.clone());
+            cx.mark_changed();
+        }
+        // The input keeps what the user typed, unless it is added or removed
+        if prev.input.is_some() != self.input.is_some() {
+            let input = self.input.as_deref().map(|text| input_pod(cx, text));
+            element.set_input_pod(input);
+            cx.mark_changed();
+        }
+        if prev.accept_text != self.accept_text {
+            element
+                .accept_button_mut()
+                .set_text(self.accept_text.clone());
+            cx.mark_changed();
+        }
+        match (&prev.cancel_text, &self.cancel_text) {
+            (Some(prev_text), Some(text)) if prev_text != text => {
+                element.cancel_button_mut().unwrap().set_text(text.clone());
+                cx.mark_changed();
+            }
+            (None, Some(_)) | (Some(_), None) => {
+                let cancel = self.cancel_text.as_ref().map(|text| button_pod(cx, text));
+                element.set_cancel_button_pod(cancel);
+                cx.mark_changed();
+            }
+            _ => {}
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Dialog::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::DialogAccepted(text) => {
+                    MessageResult::Action((self.on_result)(app_state, Some(text)))
+                }
+                masonry::Action::DialogCancelled => {
+                    MessageResult::Action((self.on_result)(app_state, None))
+                }
+                // The dialog reports the result, rather than its parts
+                masonry::Action::ButtonPressed
+                | masonry::Action::TextChanged(_)
+                | masonry::Action::TextEntered(_) => MessageResult::Nop,
+                _ => {
+                    tracing::error!("Wrong action type in Dialog::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Dialog::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{widget::WidgetMut, Widget, WidgetPod};
+
+use crate::any_view::DynWidget;
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A view which is one of two views, such as the content of a view and what to show
+/// while it is loading.
+///
+/// Unlike a [`BoxedMasonryView`](crate::BoxedMasonryView), the two possible views
+/// are known statically. When the view switches to the other variant, its widget is
+/// replaced.
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub enum Either<A, B> {
+    A(A),
+    B(B),
+}
+
+pub struct EitherState<A, B> {
+    inner: Either<A, B>,
+    /// Incremented each time the variant changes, so that messages for the old one are stale
+    generation: u64,
+}
+
+/// The widget and view state of the variant an [`Either`] is showing.
+type Variant<A, B> = (WidgetPod<Box<dyn Widget>>, Either<A, B>);
+
+impl<A, B> Either<A, B> {
+    fn build_variant<State, Action>(
+        &self,
+        generation: u64,
+        cx: &mut ViewCx,
+    ) -> Variant<A::ViewState, B::ViewState>
+    where
+        A: MasonryView<State, Action>,
+        B: MasonryView<State, Action>,
+    {
+        match self {
+            Either::A(view) => {
+                let (element, state) =
+                    cx.with_id(ViewId::for_type::<A>(generation), |cx| view.build(cx));
+                (element.boxed(), Either::A(state))
+            }
+            Either::B(view) => {
+                let (element, state) =
+                    cx.with_id(ViewId::for_type::<B>(generation), |cx| view.build(cx));
+                (element.boxed(), Either::B(state))
+            }
+        }
+    }
+}
+
+impl<State, Action, A, B> MasonryView<State, Action> for Either<A, B>
+where
+    A: MasonryView<State, Action>,
+    B: MasonryView<State, Action>,
+{
+    type Element = DynWidget;
+    type ViewState = EitherState<A::ViewState, B::ViewState>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let generation = 0;
+        let (element, inner) = self.build_variant(generation, cx);
+        (
+            WidgetPod::new(DynWidget::new(element)),
+            EitherState { inner, generation },
+        )
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        let generation = view_state.generation;
+        match (self, prev, &mut view_state.inner) {
+            (Either::A(view), Either::A(prev), Either::A(state)) => {
+                DynWidget::downcast(&mut element, |element| match element {
+                    Some(element) => cx.with_id(ViewId::for_type::<A>(generation), |cx| {
+                        view.rebuild(state, cx, prev, element);
+                    }),
+                    None => tracing::error!("downcast of element failed in Either::rebuild"),
+                });
+            }
+            (Either::B(view), Either::B(prev), Either::B(state)) => {
+                DynWidget::downcast(&mut element, |element| match element {
+                    Some(element) => cx.with_id(ViewId::for_type::<B>(generation), |cx| {
+                        view.rebuild(state, cx, prev, element);
+                    }),
+                    None => tracing::error!("downcast of element failed in Either::rebuild"),
+                });
+            }
+            _ => {
+                match prev {
+                    Either::A(_) => cx.teardown(ViewId::for_type::<A>(generation)),
+                    Either::B(_) => cx.teardown(ViewId::for_type::<B>(generation)),
+                }
+                view_state.generation = generation.wrapping_add(1);
+                let (new_element, inner) = self.build_variant(view_state.generation, cx);
+                view_state.inner = inner;
+                DynWidget::replace_inner(&mut element, new_element);
+                cx.mark_changed();
+            }
+        }
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        let Some((start, rest)) = id_path.split_first() else {
+            tracing::error!("Got empty id path in Either::message");
+            return MessageResult::Stale(message);
+        };
+        if start.routing_id() != view_state.generation {
+            return MessageResult::Stale(message);
+        }
+        match (self, &mut view_state.inner) {
+            (Either::A(view), Either::A(state)) => view.message(state, rest, message, app_state),
+            (Either::B(view), Either::B(state)) => view.message(state, rest, message, app_state),
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::widget::{Button, Label, RootWidget, WidgetRef};
+
+    use super::*;
+    use crate::view::{button, label};
+
+    #[test]
+    fn switching_variant_replaces_widget() {
+        fn press(count: &mut u32) {
+            *count += 1;
+        }
+
+        let mut cx = ViewCx::new();
+        let first: Either<_, crate::view::Label> =
+            Either::A(button("Press", press as fn(&mut u32)));
+        let (pod, mut view_state) = MasonryView::<u32>::build(&first, &mut cx);
+        let mut harness = TestHarness::create(RootWidget::from_pod(pod));
+        fn inner(harness: &TestHarness) -> WidgetRef<'_, dyn Widget> {
+            harness.root_widget().children()[0].children()[0]
+        }
+        assert!(inner(&harness).downcast::<Button>().is_some());
+
+        let next = Either::B(label("Not a button"));
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<RootWidget<DynWidget>>();
+            next.rebuild(&mut view_state, &mut cx, &first, root.get_element());
+        });
+        assert!(inner(&harness).downcast::<Label>().is_some());
+
+        // Messages for the button are now stale
+        let first_id = ViewId::for_type::<crate::view::Button<fn(&mut u32)>>(0);
+        let result = MasonryView::<u32>::message(
+            &next,
+            &mut view_state,
+            &[first_id],
+            Box::new(masonry::Action::ButtonPressed),
+            &mut 0,
+        );
+        assert!(matches!(result, MessageResult::Stale(_)));
+    }
+}
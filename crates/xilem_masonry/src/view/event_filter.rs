@@ -0,0 +1,85 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use masonry::{
+    widget::{self, FilterDecision, FilteredEvent, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A view which passes the events on their way to `child` to `filter`, which
+/// decides whether to let them through, change them, or swallow them.
+///
+/// For example, to block input to a form while it is being submitted:
+///
+/// ```ignore
+/// event_filter(form(state), move |_| {
+///     if submitting {
+///         FilterDecision::Swallow
+///     } else {
+///         FilterDecision::Pass
+///     }
+/// })
+/// ```
+pub fn event_filter<V, F>(child: V, filter: F) -> EventFilter<V, F>
+where
+    F: Fn(FilteredEvent<'_>) -> FilterDecision + 'static,
+{
+    EventFilter {
+        child,
+        filter: Arc::new(filter),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct EventFilter<V, F> {
+    child: V,
+    filter: Arc<F>,
+}
+
+impl<State, Action, V, F> MasonryView<State, Action> for EventFilter<V, F>
+where
+    V: MasonryView<State, Action>,
+    F: Fn(FilteredEvent<'_>) -> FilterDecision + 'static,
+{
+    type Element = widget::EventFilter;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let filter = self.filter.clone();
+        let widget = widget::EventFilter::new_pod(child.boxed(), move |event| filter(event));
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        {
+            let mut child = element.child_mut();
+            let child = child.downcast();
+            self.child.rebuild(child_state, cx, &prev.child, child);
+        }
+        // Closures can't be compared, and the filter may capture new state, so it is
+        // always replaced. This doesn't need a relayout or a repaint.
+        let filter = self.filter.clone();
+        element.set_filter(move |event| filter(event));
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
@@ -0,0 +1,566 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use accesskit::Role;
+use masonry::parley::fontique::Weight;
+use masonry::task_pool::Task;
+use masonry::widget::{SpanStyle, TextSpan, WidgetMut};
+use masonry::{
+    AccessCtx, AccessEvent, ArcStr, BoxConstraints, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+use smallvec::SmallVec;
+use vello::Scene;
+
+use crate::sequence::WasAView;
+use crate::view::{
+    flex, flex_item, label, portal, textbox, virtual_list, Flex, FlexItem, Label, Portal, Textbox,
+    VirtualList,
+};
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// The parts of some text which matched a search query.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextMatch {
+    /// How well the text matched; higher is better.
+    pub score: i64,
+    /// The byte ranges of the text which matched the query, in order.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Match `query` against `text` as a subsequence, ignoring case.
+///
+/// Matches score higher when the matched characters are consecutive, start words,
+/// or are close to the start of `text`. Every text matches an empty query.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<TextMatch> {
+    let mut result = TextMatch::default();
+    let mut query_chars = query.chars().peekable();
+    let mut prev_char: Option<char> = None;
+    let mut prev_matched = false;
+    for (ix, c) in text.char_indices() {
+        let Some(&next) = query_chars.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(next.to_lowercase());
+        if matched {
+            query_chars.next();
+            result.score += 1;
+            if prev_matched {
+                result.score += 3;
+            }
+            if !prev_char.is_some_and(char::is_alphanumeric) {
+                result.score += 2;
+            }
+            match result.ranges.last_mut() {
+                Some(range) if range.end == ix => range.end = ix + c.len_utf8(),
+                _ => result.ranges.push(ix..ix + c.len_utf8()),
+            }
+        } else if result.ranges.is_empty() {
+            // Penalise matches which start late in the text
+            result.score -= 1;
+        }
+        prev_char = Some(c);
+        prev_matched = matched;
+    }
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    Some(result)
+}
+
+/// A search input above the items which match its query.
+///
+/// `query` is the current text of the search input, and `query_accessor` gives the
+/// place in the app state where it is stored, to be updated as the user types.
+/// Each item is checked with `matcher` (such as [`fuzzy_match`] applied to a field
+/// of the item), and the items which match are shown with `item_view`, best match
+/// first. `item_view` is passed the match, which [`highlighted`] uses to show the
+/// matched parts of the text in bold.
+///
+/// The results are shown in a scrolling [`virtual_list`], so only the views of the
+/// visible items are built, each [`item_height`](FilterableList::item_height) tall.
+/// The items are matched again when `query` changes, or when `items` is replaced
+/// by a different list; lists of a thousand items or more are matched on the
+/// [task pool](ViewCx::task_pool), and the previous results are shown until the
+/// new ones are ready.
+pub fn filterable_list<State, Action, T, M, F, V>(
+    query: &str,
+    query_accessor: impl Fn(&mut State) -> &mut String + 'static,
+    items: Arc<[T]>,
+    matcher: M,
+    item_view: F,
+) -> FilterableList<State, Action, T, M, F, V>
+where
+    T: Send + Sync + 'static,
+    M: Fn(&str, &T) -> Option<TextMatch> + Send + Sync + 'static,
+    F: Fn(&T, &TextMatch) -> V + 'static,
+{
+    FilterableList {
+        query: query.to_string(),
+        query_accessor: Rc::new(query_accessor),
+        items,
+        matcher: Arc::new(matcher),
+        item_view: Rc::new(item_view),
+        item_height: DEFAULT_ITEM_HEIGHT,
+        phantom: PhantomData,
+    }
+}
+
+/// A label of `text`, with the parts of it which matched in bold.
+pub fn highlighted(text: impl Into<ArcStr>, text_match: &TextMatch) -> Label {
+    label(text).spans(text_match.spans(SpanStyle::Weight(Weight::BOLD)))
+}
+
+impl TextMatch {
+    /// Spans which style the matched ranges with `style`, for a
+    /// [`Label`](Label::spans).
+    pub fn spans(&self, style: SpanStyle) -> Vec<TextSpan> {
+        self.ranges
+            .iter()
+            .map(|range| TextSpan {
+                range: range.clone(),
+                style: style.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Lists with at least this many items are matched on the task pool, so that
+/// typing in the search input doesn't wait for the matching.
+const BACKGROUND_MATCH_LEN: usize = 1000;
+
+const DEFAULT_ITEM_HEIGHT: f64 = 24.0;
+
+/// The indices of the items which matched and their matches, best match first.
+type Results = Arc<[(usize, TextMatch)]>;
+
+type MatchTask = Task<Results>;
+
+type ItemView<V> = Box<dyn Fn(usize) -> V>;
+
+type Content<State, Action, V> = Flex<
+    (
+        Textbox<State, Action>,
+        FlexItem<Portal<VirtualList<ItemView<V>, V>, State, Action>>,
+    ),
+    (WasAView, WasAView),
+>;
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct FilterableList<State, Action, T, M, F, V> {
+    query: String,
+    query_accessor: Rc<dyn Fn(&mut State) -> &mut String>,
+    items: Arc<[T]>,
+    matcher: Arc<M>,
+    item_view: Rc<F>,
+    item_height: f64,
+    phantom: PhantomData<fn() -> (Action, V)>,
+}
+
+pub struct FilterableListState<State: 'static, Action: 'static, V: MasonryView<State, Action>> {
+    results: Results,
+    /// The matching of a newer query, if it is being done in the background
+    task: Option<MatchTask>,
+    /// The view last built from the results, to be rebuilt from
+    content: Content<State, Action, V>,
+    content_state: <Content<State, Action, V> as MasonryView<State, Action>>::ViewState,
+}
+
+fn match_items<T>(
+    query: &str,
+    items: &[T],
+    matcher: impl Fn(&str, &T) -> Option<TextMatch>,
+) -> Results {
+    let mut results: Vec<(usize, TextMatch)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, item)| Some((ix, matcher(query, item)?)))
+        .collect();
+    // The sort is stable, so equally good matches stay in their original order
+    results.sort_by_key(|(_, text_match)| std::cmp::Reverse(text_match.score));
+    results.into()
+}
+
+impl<State, Action, T, M, F, V> FilterableList<State, Action, T, M, F, V>
+where
+    State: 'static,
+    Action: Default + 'static,
+    T: Send + Sync + 'static,
+    M: Fn(&str, &T) -> Option<TextMatch> + Send + Sync + 'static,
+    F: Fn(&T, &TextMatch) -> V + 'static,
+    V: MasonryView<State, Action>,
+{
+    /// The height of each item, which is 24 by default.
+    pub fn item_height(mut self, item_height: f64) -> Self {
+        self.item_height = item_height;
+        self
+    }
+
+    /// Match the items against the query, straight away for short lists of items
+    /// and as a task for long ones.
+    fn start_matching(&self, cx: &ViewCx) -> Result<Results, MatchTask> {
+        if self.items.len() < BACKGROUND_MATCH_LEN {
+            return Ok(match_items(&self.query, &self.items, &*self.matcher));
+        }
+        let query = self.query.clone();
+        let items = self.items.clone();
+        let matcher = self.matcher.clone();
+        Err(cx
+            .task_pool()
+            .spawn(move || match_items(&query, &items, &*matcher)))
+    }
+
+    fn content(&self, results: &Results) -> Content<State, Action, V> {
+        let query_accessor = self.query_accessor.clone();
+        let search = textbox(self.query.clone(), move |state: &mut State, query| {
+            *query_accessor(state) = query;
+            Action::default()
+        });
+        let items = self.items.clone();
+        let item_view = self.item_view.clone();
+        let shown = results.clone();
+        let item: ItemView<V> = Box::new(move |index| {
+            let (item_index, text_match) = &shown[index];
+            item_view(&items[*item_index], text_match)
+        });
+        let results = portal(virtual_list(results.len(), self.item_height, item));
+        flex((search, flex_item(results, 1.0)))
+    }
+}
+
+impl<State, Action, T, M, F, V> MasonryView<State, Action>
+    for FilterableList<State, Action, T, M, F, V>
+where
+    State: 'static,
+    Action: Default + 'static,
+    T: Send + Sync + 'static,
+    M: Fn(&str, &T) -> Option<TextMatch> + Send + Sync + 'static,
+    F: Fn(&T, &TextMatch) -> V + 'static,
+    V: MasonryView<State, Action>,
+{
+    type Element = ListFilter;
+    type ViewState = FilterableListState<State, Action, V>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (results, task) = match self.start_matching(cx) {
+            Ok(results) => (results, None),
+            Err(task) => (Arc::from([]), Some(task)),
+        };
+        let content = self.content(&results);
+        let (child, content_state) = content.build(cx);
+        let pod = cx.with_action_widget(|_| {
+            WidgetPod::new(ListFilter {
+                child,
+                task: task.clone(),
+            })
+        });
+        let view_state = FilterableListState {
+            results,
+            task,
+            content,
+            content_state,
+        };
+        (pod, view_state)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.query != self.query || !Arc::ptr_eq(&prev.items, &self.items) {
+            match self.start_matching(cx) {
+                Ok(results) => {
+                    view_state.results = results;
+                    view_state.task = None;
+                }
+                Err(task) => {
+                    task.wake_when_done(element.ctx.wake_handle());
+                    view_state.task = Some(task);
+                }
+            }
+            element.widget.task = view_state.task.clone();
+        }
+
+        let content = self.content(&view_state.results);
+        let child = element.ctx.get_mut(&mut element.widget.child);
+        content.rebuild(
+            &mut view_state.content_state,
+            cx,
+            &view_state.content,
+            child,
+        );
+        view_state.content = content;
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if !id_path.is_empty() {
+            return view_state.content.message(
+                &mut view_state.content_state,
+                id_path,
+                message,
+                app_state,
+            );
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::Other(payload) if payload.is::<ItemsMatched>() => {
+                    // The matching of an older query may have finished after the query changed
+                    let Some(result) = view_state.task.as_ref().and_then(Task::take) else {
+                        return MessageResult::Nop;
+                    };
+                    view_state.task = None;
+                    match result {
+                        Ok(results) => view_state.results = results,
+                        Err(_) => {
+                            tracing::error!("Matching the items of a filterable list panicked");
+                        }
+                    }
+                    MessageResult::RequestRebuild
+                }
+                action => {
+                    tracing::error!("Wrong action type in FilterableList::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in FilterableList::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// The action sent by a [`ListFilter`] when its items have been matched.
+struct ItemsMatched;
+
+/// The widget created by [`filterable_list`], which waits for its items to be
+/// matched in the background.
+pub struct ListFilter {
+    child: WidgetPod<masonry::widget::Flex>,
+    /// The matching of the items, until the widget has been woken by it
+    task: Option<MatchTask>,
+}
+
+/// Forward all events to the child widget.
+impl Widget for ListFilter {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _: &mut LifeCycleCtx, _: &StatusChange) {
+        // Intentionally do nothing
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+        match (event, &self.task) {
+            (LifeCycle::WidgetAdded, Some(task)) => task.wake_when_done(ctx.wake_handle()),
+            (LifeCycle::Wake, Some(task)) if task.is_done() => {
+                self.task = None;
+                ctx.submit_action(masonry::Action::Other(Arc::new(ItemsMatched)));
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut vec = SmallVec::new();
+        vec.push(self.child.id());
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use masonry::testing::TestHarness;
+    use masonry::widget::SizedBox;
+
+    use super::*;
+
+    type Matcher = fn(&str, &String) -> Option<TextMatch>;
+    type ItemLabel = fn(&String, &TextMatch) -> Label;
+    type ListView = FilterableList<String, (), String, Matcher, ItemLabel, Label>;
+
+    fn list_view(query: &str, items: &Arc<[String]>) -> ListView {
+        filterable_list(
+            query,
+            |query: &mut String| query,
+            items.clone(),
+            (|query: &str, item: &String| fuzzy_match(query, item)) as Matcher,
+            (|item: &String, text_match: &TextMatch| highlighted(item.clone(), text_match))
+                as ItemLabel,
+        )
+    }
+
+    struct ListHarness {
+        harness: TestHarness,
+        cx: ViewCx,
+        view: ListView,
+        view_state: FilterableListState<String, (), Label>,
+    }
+
+    impl ListHarness {
+        fn new(view: ListView) -> Self {
+            let mut cx = ViewCx::new();
+            let (pod, view_state) = MasonryView::<String>::build(&view, &mut cx);
+            let harness = TestHarness::create_with_size(
+                SizedBox::new_pod(pod.boxed()).expand(),
+                Size::new(200.0, 200.0),
+            );
+            let mut list = ListHarness {
+                harness,
+                cx,
+                view,
+                view_state,
+            };
+            list.handle_actions();
+            list
+        }
+
+        fn rebuild(&mut self, view: ListView) {
+            let ListHarness {
+                harness,
+                cx,
+                view: prev,
+                view_state,
+            } = self;
+            harness.edit_root_widget(|mut root| {
+                let mut root = root.downcast::<SizedBox>();
+                let mut child = root.child_mut().unwrap();
+                MasonryView::<String>::rebuild(&view, view_state, cx, prev, child.downcast());
+            });
+            self.view = view;
+        }
+
+        /// Route the actions of the list to the view, and rebuild it like the driver
+        /// would.
+        fn handle_actions(&mut self) {
+            while let Some((action, widget_id)) = self.harness.pop_action() {
+                let mut id_path = Vec::new();
+                let path = self.cx.widget_map[&widget_id];
+                self.cx.id_paths.resolve_into(path, &mut id_path);
+                let result = MasonryView::<String>::message(
+                    &self.view,
+                    &mut self.view_state,
+                    &id_path,
+                    Box::new(action),
+                    &mut String::new(),
+                );
+                assert!(matches!(result, MessageResult::RequestRebuild));
+                self.rebuild(list_view(&self.view.query, &self.view.items));
+            }
+        }
+
+        /// The labels of the items which are shown, with their spans.
+        fn shown(&self) -> Vec<(String, Vec<TextSpan>)> {
+            let filter = self.harness.root_widget().children()[0];
+            // The portal is in the flex item after the search input
+            let portal = filter.children()[0].children()[1].children()[0];
+            portal.children()[0]
+                .children()
+                .iter()
+                .map(|item| {
+                    let label = item.downcast::<masonry::widget::Label>().unwrap();
+                    (label.text().to_string(), label.spans().to_vec())
+                })
+                .collect()
+        }
+
+        fn shown_text(&self) -> Vec<String> {
+            self.shown().into_iter().map(|(text, _)| text).collect()
+        }
+    }
+
+    #[test]
+    fn short_lists_are_matched_straight_away() {
+        let items: Arc<[String]> = ["Apple", "Banana", "Pineapple", "Grape"]
+            .map(String::from)
+            .into();
+        let mut list = ListHarness::new(list_view("ap", &items));
+        assert_eq!(list.shown_text(), ["Apple", "Grape", "Pineapple"]);
+        let bold = SpanStyle::Weight(Weight::BOLD);
+        assert_eq!(
+            list.shown()[0].1,
+            [TextSpan {
+                range: 0..2,
+                style: bold
+            }]
+        );
+
+        list.rebuild(list_view("gr", &items));
+        list.handle_actions();
+        assert_eq!(list.shown_text(), ["Grape"]);
+    }
+
+    #[test]
+    fn long_lists_are_matched_in_the_background() {
+        let items: Arc<[String]> = (0..BACKGROUND_MATCH_LEN * 2)
+            .map(|ix| format!("Item {ix}"))
+            .collect();
+        let mut list = ListHarness::new(list_view("1999", &items));
+        assert!(list.shown().is_empty());
+
+        assert!(list.harness.wait_for_wake(Duration::from_secs(5)));
+        list.handle_actions();
+        assert_eq!(list.shown_text(), ["Item 1999"]);
+    }
+
+    #[test]
+    fn fuzzy_match_ranges() {
+        let text_match = fuzzy_match("fil", "Filterable list").unwrap();
+        assert_eq!(text_match.ranges, vec![0..3]);
+        let text_match = fuzzy_match("frli", "Filterable list").unwrap();
+        assert_eq!(text_match.ranges, [0..1, 5..6, 8..9, 12..13]);
+        assert!(fuzzy_match("lists", "Filterable list").is_none());
+        assert_eq!(fuzzy_match("", "anything"), Some(TextMatch::default()));
+    }
+
+    #[test]
+    fn fuzzy_match_scores() {
+        let consecutive = fuzzy_match("list", "list of items").unwrap();
+        let scattered = fuzzy_match("list", "large items set").unwrap();
+        let late = fuzzy_match("list", "a long list").unwrap();
+        assert!(consecutive.score > late.score);
+        assert!(late.score > scattered.score);
+    }
+}
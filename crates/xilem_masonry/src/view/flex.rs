@@ -4,13 +4,16 @@
 use std::marker::PhantomData;
 
 use masonry::{
-    widget::{self, Axis, CrossAxisAlignment, MainAxisAlignment, WidgetMut},
+    widget::{self, Axis, CrossAxisAlignment, FlexParams, MainAxisAlignment, WidgetMut},
     Widget, WidgetPod,
 };
 
-use crate::{ElementSplice, MasonryView, VecSplice, ViewSequence};
+use crate::{ElementSplice, MasonryView, MessageResult, VecSplice, ViewCx, ViewId, ViewSequence};
 
-// TODO: Allow configuring flex properties. I think this actually needs its own view trait?
+/// A column of the views in `sequence`, or a row with [`direction`](Flex::direction).
+///
+/// The children keep their own size along the main axis, unless they are wrapped
+/// in a [`flex_item`] to share the free space.
 pub fn flex<VT, Marker>(sequence: VT) -> Flex<VT, Marker> {
     Flex {
         phantom: PhantomData,
@@ -19,15 +22,28 @@ pub fn flex<VT, Marker>(sequence: VT) -> Flex<VT, Marker> {
         cross_axis_alignment: CrossAxisAlignment::Center,
         main_axis_alignment: MainAxisAlignment::Start,
         fill_major_axis: false,
+        gap: None,
     }
 }
 
+/// A row of the views in `sequence`, from left to right. See [`flex`].
+pub fn flex_row<VT, Marker>(sequence: VT) -> Flex<VT, Marker> {
+    flex(sequence).direction(Axis::Horizontal)
+}
+
+/// A column of the views in `sequence`, from top to bottom. See [`flex`].
+pub fn flex_column<VT, Marker>(sequence: VT) -> Flex<VT, Marker> {
+    flex(sequence).direction(Axis::Vertical)
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Flex<VT, Marker> {
     sequence: VT,
     axis: Axis,
     cross_axis_alignment: CrossAxisAlignment,
     main_axis_alignment: MainAxisAlignment,
     fill_major_axis: bool,
+    gap: Option<f64>,
     phantom: PhantomData<fn() -> Marker>,
 }
 
@@ -50,6 +66,19 @@ impl<VT, Marker> Flex<VT, Marker> {
         self.fill_major_axis = fill_major_axis;
         self
     }
+
+    /// The space after each child, which is the theme's padding by default.
+    pub fn gap(mut self, gap: f64) -> Self {
+        self.gap = Some(gap);
+        self
+    }
+
+    fn gap_or_default(&self) -> f64 {
+        self.gap.unwrap_or(match self.axis {
+            Axis::Vertical => masonry::theme::WIDGET_PADDING_VERTICAL,
+            Axis::Horizontal => masonry::theme::WIDGET_PADDING_HORIZONTAL,
+        })
+    }
 }
 
 impl<State, Action, Marker: 'static, Seq> MasonryView<State, Action> for Flex<Seq, Marker>
@@ -77,7 +106,7 @@ where
             "ViewSequence shouldn't leave splice in strange state"
         );
         for item in elements.drain(..) {
-            view = view.with_child_pod(item).with_default_spacer();
+            view = view.with_child_pod(item).with_spacer(self.gap_or_default());
         }
         (WidgetPod::new(view), seq_state)
     }
@@ -116,7 +145,22 @@ where
             element.set_must_fill_main_axis(self.fill_major_axis);
             cx.mark_changed();
         }
-        let mut splice = FlexSplice { ix: 0, element };
+        let gap = self.gap_or_default();
+        if prev.gap_or_default() != gap {
+            // The spacers are the children without a widget
+            for ix in 0..element.widget.len() {
+                if element.child_mut(ix).is_none() {
+                    element.remove_child(ix);
+                    element.insert_spacer(ix, gap);
+                }
+            }
+            cx.mark_changed();
+        }
+        let mut splice = FlexSplice {
+            ix: 0,
+            gap,
+            element,
+        };
         self.sequence
             .rebuild(view_state, cx, &prev.sequence, &mut splice);
     }
@@ -124,51 +168,53 @@ where
 
 struct FlexSplice<'w> {
     ix: usize,
+    gap: f64,
     element: WidgetMut<'w, widget::Flex>,
 }
 
+// Each widget is followed by its spacer, and `ix` is always at the start of a widget
+// and its spacer, or at the end.
 impl ElementSplice for FlexSplice<'_> {
     fn push(&mut self, element: WidgetPod<Box<dyn masonry::Widget>>) {
         self.element.insert_child_pod(self.ix, element);
-        self.element.insert_default_spacer(self.ix);
+        self.element.insert_spacer(self.ix + 1, self.gap);
         self.ix += 2;
     }
 
     fn mutate(&mut self) -> WidgetMut<Box<dyn Widget>> {
-        #[cfg(debug_assertions)]
-        let mut iterations = 0;
-        #[cfg(debug_assertions)]
-        let max = self.element.widget.len();
-        loop {
-            #[cfg(debug_assertions)]
-            {
-                if iterations > max {
-                    panic!("Got into infinite loop in FlexSplice::mutate");
-                }
-                iterations += 1;
-            }
-            let child = self.element.child_mut(self.ix);
-            if child.is_some() {
-                break;
-            }
+        let ix = self.next_widget(self.ix);
+        self.ix = ix + 1;
+        if self.is_spacer(self.ix) {
             self.ix += 1;
         }
-        let child = self.element.child_mut(self.ix).unwrap();
-        self.ix += 1;
-        child
+        self.element.child_mut(ix).unwrap()
     }
 
     fn delete(&mut self, n: usize) {
-        let mut deleted_count = 0;
-        while deleted_count < n {
-            {
-                // TODO: use a drain/retain type method
-                let element = self.element.child_mut(self.ix);
-                if element.is_some() {
-                    deleted_count += 1;
-                }
+        for _ in 0..n {
+            let ix = self.next_widget(self.ix);
+            self.element.remove_child(ix);
+            if self.is_spacer(ix) {
+                self.element.remove_child(ix);
+            }
+        }
+    }
+
+    fn move_from(&mut self, offset: usize, n: usize) {
+        let mut to = self.ix;
+        for _ in 0..n {
+            let mut from = self.next_widget(to);
+            for _ in 0..offset {
+                from = self.next_widget(from + 1);
+            }
+            // The widget's spacer moves with it
+            let has_spacer = self.is_spacer(from + 1);
+            self.element.move_child(from, to);
+            to += 1;
+            if has_spacer {
+                self.element.move_child(from + 1, to);
+                to += 1;
             }
-            self.element.remove_child(self.ix);
         }
     }
 
@@ -176,3 +222,127 @@ impl ElementSplice for FlexSplice<'_> {
         self.ix / 2
     }
 }
+
+impl FlexSplice<'_> {
+    /// The index of the first widget at or after `ix`, skipping spacers.
+    fn next_widget(&mut self, mut ix: usize) -> usize {
+        while self.is_spacer(ix) {
+            ix += 1;
+        }
+        assert!(
+            ix < self.element.widget.len(),
+            "FlexSplice went past its last widget"
+        );
+        ix
+    }
+
+    fn is_spacer(&mut self, ix: usize) -> bool {
+        ix < self.element.widget.len() && self.element.child_mut(ix).is_none()
+    }
+}
+
+/// Lay out `child` with `params` in a [`flex`], such as to share the free space
+/// between the children by their flex factors.
+///
+/// `params` can be just the flex factor, as an `f64`. Outside of a flex, this
+/// has no effect.
+pub fn flex_item<V>(child: V, params: impl Into<FlexParams>) -> FlexItem<V> {
+    FlexItem {
+        child,
+        params: params.into(),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct FlexItem<V> {
+    child: V,
+    params: FlexParams,
+}
+
+impl<State, Action, V> MasonryView<State, Action> for FlexItem<V>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::FlexItem;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let widget = widget::FlexItem::new_pod(child.boxed(), self.params);
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.params != self.params {
+            element.set_params(self.params);
+            cx.mark_changed();
+        }
+        let mut child = element.child_mut();
+        let child = child.downcast();
+        self.child.rebuild(child_state, cx, &prev.child, child);
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::Size;
+
+    use super::*;
+    use crate::view::{label, sized_box};
+    use crate::ViewExt;
+
+    #[test]
+    fn items_share_the_space_after_the_gaps() {
+        // The flex items fill their share of the space
+        let view = |gap| {
+            flex_row((
+                sized_box(label("")).width(20.0),
+                sized_box(label("")).width(f64::INFINITY).flex_item(1.0),
+                sized_box(label("")).width(f64::INFINITY).flex_item(3.0),
+            ))
+            .gap(gap)
+        };
+        let first = view(10.0);
+        let mut cx = ViewCx::new();
+        let (pod, mut seq_state) = MasonryView::<(), ()>::build(&first, &mut cx);
+        let mut harness = TestHarness::create_with_size(
+            widget::Flex::column().with_child_pod(pod.boxed()),
+            Size::new(450.0, 50.0),
+        );
+        let widths = |harness: &TestHarness| {
+            let flex = harness.root_widget().children()[0];
+            flex.children()
+                .iter()
+                .map(|child| child.state().layout_rect().size().width)
+                .collect::<Vec<_>>()
+        };
+        // The fixed child and the three gaps leave 400 pixels to share
+        assert_eq!(widths(&harness), [20.0, 100.0, 300.0]);
+
+        let second = view(30.0);
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<widget::Flex>();
+            let mut flex = root.child_mut(0).unwrap();
+            let flex = flex.downcast();
+            MasonryView::<(), ()>::rebuild(&second, &mut seq_state, &mut cx, &first, flex);
+        });
+        assert_eq!(widths(&harness), [20.0, 85.0, 255.0]);
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::widget::{self, GridParams, TrackSize, WidgetMut};
+use masonry::{Widget, WidgetPod};
+
+use crate::{ElementSplice, MasonryView, MessageResult, VecSplice, ViewCx, ViewId, ViewSequence};
+
+/// The views in `sequence`, in the cells of a grid with the given `columns`.
+///
+/// Views are placed one after another along the rows, unless they are wrapped in
+/// a [`grid_item`] to choose their cell or span several cells. See
+/// [`Grid`](masonry::widget::Grid) for how the columns and rows are sized.
+pub fn grid<VT, Marker>(columns: impl Into<Vec<TrackSize>>, sequence: VT) -> Grid<VT, Marker> {
+    Grid {
+        sequence,
+        columns: columns.into(),
+        rows: Vec::new(),
+        gap: (0.0, 0.0),
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Grid<VT, Marker> {
+    sequence: VT,
+    columns: Vec<TrackSize>,
+    rows: Vec<TrackSize>,
+    gap: (f64, f64),
+    phantom: PhantomData<fn() -> Marker>,
+}
+
+impl<VT, Marker> Grid<VT, Marker> {
+    /// The sizes of the rows, which are [`TrackSize::Auto`] by default.
+    pub fn rows(mut self, rows: impl Into<Vec<TrackSize>>) -> Self {
+        self.rows = rows.into();
+        self
+    }
+
+    /// The space between columns and between rows.
+    pub fn gap(mut self, column_gap: f64, row_gap: f64) -> Self {
+        self.gap = (column_gap, row_gap);
+        self
+    }
+}
+
+impl<State, Action, Marker: 'static, Seq> MasonryView<State, Action> for Grid<Seq, Marker>
+where
+    Seq: ViewSequence<State, Action, Marker>,
+{
+    type Element = widget::Grid;
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let mut elements = Vec::new();
+        let mut scratch = Vec::new();
+        let mut splice = VecSplice::new(&mut elements, &mut scratch);
+        let seq_state = self.sequence.build(cx, &mut splice);
+        let mut widget = widget::Grid::with_columns(self.columns.clone())
+            .with_rows(self.rows.clone())
+            .with_gap(self.gap.0, self.gap.1);
+        for element in elements {
+            widget = widget.with_child_pod(element, GridParams::default());
+        }
+        (WidgetPod::new(widget), seq_state)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.columns != self.columns {
+            element.set_columns(self.columns.clone());
+            cx.mark_changed();
+        }
+        if prev.rows != self.rows {
+            element.set_rows(self.rows.clone());
+            cx.mark_changed();
+        }
+        if prev.gap != self.gap {
+            element.set_gap(self.gap.0, self.gap.1);
+            cx.mark_changed();
+        }
+        let mut splice = GridSplice { ix: 0, element };
+        self.sequence
+            .rebuild(view_state, cx, &prev.sequence, &mut splice);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.sequence
+            .message(view_state, id_path, message, app_state)
+    }
+}
+
+struct GridSplice<'w> {
+    ix: usize,
+    element: WidgetMut<'w, widget::Grid>,
+}
+
+impl ElementSplice for GridSplice<'_> {
+    fn push(&mut self, element: WidgetPod<Box<dyn Widget>>) {
+        self.element
+            .insert_child_pod(self.ix, element, GridParams::default());
+        self.ix += 1;
+    }
+
+    fn mutate(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        let ix = self.ix;
+        self.ix += 1;
+        self.element
+            .child_mut(ix)
+            .expect("GridSplice::mutate past the last child")
+    }
+
+    fn delete(&mut self, n: usize) {
+        for _ in 0..n {
+            self.element.remove_child(self.ix);
+        }
+    }
+
+    fn move_from(&mut self, offset: usize, n: usize) {
+        for _ in 0..n {
+            self.element.move_child(self.ix + offset, self.ix);
+            self.ix += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ix
+    }
+}
+
+/// Place `child` in a [`grid`] with `params`, such as in a given cell or spanning
+/// several cells.
+///
+/// `params` can be just the column and row, as a tuple. Outside of a grid, this
+/// has no effect.
+pub fn grid_item<V>(child: V, params: impl Into<GridParams>) -> GridItem<V> {
+    GridItem {
+        child,
+        params: params.into(),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct GridItem<V> {
+    child: V,
+    params: GridParams,
+}
+
+impl<State, Action, V> MasonryView<State, Action> for GridItem<V>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::GridItem;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let widget = widget::GridItem::new_pod(child.boxed(), self.params);
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.params != self.params {
+            element.set_params(self.params);
+            cx.mark_changed();
+        }
+        let mut child = element.child_mut();
+        let child = child.downcast();
+        self.child.rebuild(child_state, cx, &prev.child, child);
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::{Point, Size};
+
+    use super::*;
+    use crate::view::label;
+    use crate::ViewExt;
+
+    #[test]
+    fn form_rows_follow_the_sequence() {
+        let view = |rows: Vec<&'static str>| {
+            grid(
+                [TrackSize::Fixed(100.0), TrackSize::Fraction(1.0)],
+                (
+                    rows.into_iter()
+                        .map(|name| (label(name), label("")))
+                        .collect::<Vec<_>>(),
+                    label("").grid_item(GridParams::at(0, 5)),
+                ),
+            )
+            .rows([TrackSize::Fixed(20.0)])
+            .gap(10.0, 5.0)
+        };
+        let first = view(vec!["Name", "Email"]);
+        let mut cx = ViewCx::new();
+        let (pod, mut seq_state) = MasonryView::<(), ()>::build(&first, &mut cx);
+        let mut harness = TestHarness::create_with_size(
+            widget::Flex::column().with_child_pod(pod.boxed()),
+            Size::new(300.0, 300.0),
+        );
+        let origins = |harness: &TestHarness| {
+            let grid = harness.root_widget().children()[0];
+            grid.children()
+                .iter()
+                .map(|child| child.state().layout_rect().origin())
+                .collect::<Vec<_>>()
+        };
+        let first_origins = origins(&harness);
+        assert_eq!(first_origins.len(), 5);
+        assert_eq!(first_origins[1], Point::new(110.0, 0.0));
+        assert_eq!(first_origins[2], Point::new(0.0, 25.0));
+
+        let second = view(vec!["Name"]);
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<widget::Flex>();
+            let mut grid = root.child_mut(0).unwrap();
+            let grid = grid.downcast();
+            MasonryView::<(), ()>::rebuild(&second, &mut seq_state, &mut cx, &first, grid);
+        });
+        let second_origins = origins(&harness);
+        assert_eq!(second_origins.len(), 3);
+        // The placed item keeps its cell, which moves up as the row above is now empty
+        assert_eq!(second_origins[2].x, 0.0);
+        assert!(second_origins[2].y < first_origins[4].y);
+    }
+}
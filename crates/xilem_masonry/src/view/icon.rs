@@ -0,0 +1,82 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{theme, widget::WidgetMut, IconName, WidgetPod};
+
+use crate::{Color, MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Show the icon called `name` in the app's icons, which are set with
+/// [`Xilem::with_icons`](crate::Xilem::with_icons).
+///
+/// The icon is sized and colored from the theme, unless set with
+/// [`size`](Icon::size) and [`color`](Icon::color).
+pub fn icon(name: impl Into<IconName>) -> Icon {
+    Icon {
+        name: name.into(),
+        size: theme::ICON_SIZE,
+        color: theme::TEXT_COLOR,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Icon {
+    name: IconName,
+    size: f64,
+    color: Color,
+}
+
+impl Icon {
+    pub fn size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl<State, Action> MasonryView<State, Action> for Icon {
+    type Element = masonry::widget::Icon;
+    type ViewState = ();
+
+    fn build(&self, _cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let widget = masonry::widget::Icon::new(self.name.clone())
+            .with_size(self.size)
+            .with_color(self.color);
+        (WidgetPod::new(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.name != self.name {
+            element.set_name(self.name.clone());
+            cx.mark_changed();
+        }
+        if prev.size != self.size {
+            element.set_size(self.size);
+            cx.mark_changed();
+        }
+        if prev.color != self.color {
+            element.set_color(self.color);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        _id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in Icon::message, but Icon doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
@@ -0,0 +1,854 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use accesskit::Role;
+use masonry::task_pool::Task;
+use masonry::widget::{FillStrat, WidgetMut};
+use masonry::{
+    AccessCtx, AccessEvent, BoxConstraints, EventCtx, Insets, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, PointerEvent, Size, StatusChange, TextEvent, Widget, WidgetId, WidgetPod,
+};
+use smallvec::SmallVec;
+use url::Url;
+use vello::peniko::{Format, Image as ImageBuf};
+use vello::Scene;
+
+use crate::view::{label, Label};
+use crate::{http, MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Display an image.
+///
+/// The image scales to fit its box constraints; use [`fill`](Image::fill) to change
+/// how.
+pub fn image(image_data: ImageBuf) -> Image {
+    Image {
+        image_data,
+        fill: FillStrat::default(),
+        slices: None,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Image {
+    image_data: ImageBuf,
+    fill: FillStrat,
+    slices: Option<Insets>,
+}
+
+impl Image {
+    /// How the image fills the space it is given.
+    pub fn fill(mut self, fill: FillStrat) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Draw the image as a nine-slice image, cut by `insets` in image pixels, so
+    /// that its corners aren't stretched.
+    ///
+    /// See [`masonry::widget::Image::with_slices`].
+    pub fn slices(mut self, insets: Insets) -> Self {
+        self.slices = Some(insets);
+        self
+    }
+}
+
+impl<State, Action> MasonryView<State, Action> for Image {
+    type Element = masonry::widget::Image;
+    type ViewState = ();
+
+    fn build(&self, _cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let mut widget = masonry::widget::Image::new(self.image_data.clone()).fill_mode(self.fill);
+        if let Some(insets) = self.slices {
+            widget = widget.with_slices(insets);
+        }
+        (WidgetPod::new(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.image_data != self.image_data {
+            element.set_image_data(self.image_data.clone());
+            cx.mark_changed();
+        }
+        if prev.fill != self.fill {
+            element.set_fill_mode(self.fill);
+            cx.mark_changed();
+        }
+        if prev.slices != self.slices {
+            element.set_slices(self.slices);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        _id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in Image::message, but Image doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
+
+/// Display the image at `url`, which is fetched in the background.
+///
+/// Until the image is fetched, the [`placeholder`](ImageUrl::placeholder) view is
+/// shown in its place, and if it can't be fetched or decoded, the
+/// [`error`](ImageUrl::error) view is. Both are empty by default; a
+/// [`skeleton`](crate::view::skeleton) of the image's size makes a good placeholder.
+///
+/// Images are cached on disk, and the cached copy is revalidated with its ETag
+/// the first time the image is shown after the app starts. The images shown most
+/// recently are also kept in memory, so that views showing them again don't start
+/// with the placeholder. Images which couldn't be fetched aren't remembered, so
+/// the next view of the same URL tries again.
+///
+/// Only `http`, `https` and `file` URLs are supported for now, and the only supported format
+/// is PNG. `file` URLs are percent-decoded, and can only name a file on this machine. See
+/// [`image_file`] to show an image from a path.
+pub fn image_url(url: impl Into<String>) -> ImageUrl<Label, Label> {
+    image_source(ImageSource::Url(url.into()))
+}
+
+/// Display the image at `path`, which is read and decoded in the background.
+///
+/// This has the same placeholder and error views as an [`image_url`]. The decoded
+/// image is kept in memory while the app runs, so the file is only read once, even
+/// if it changes.
+pub fn image_file(path: impl Into<PathBuf>) -> ImageUrl<Label, Label> {
+    image_source(ImageSource::Path(path.into()))
+}
+
+fn image_source(source: ImageSource) -> ImageUrl<Label, Label> {
+    ImageUrl {
+        source,
+        cache_dir: None,
+        placeholder: label(""),
+        error: label(""),
+        fill: FillStrat::default(),
+        slices: None,
+    }
+}
+
+/// Where the image of an [`ImageUrl`] is loaded from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ImageSource {
+    Url(String),
+    /// A local file, which is read without going through a URL, so that its path
+    /// doesn't need to be escaped
+    Path(PathBuf),
+}
+
+impl Display for ImageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageSource::Url(url) => write!(f, "{url}"),
+            ImageSource::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct ImageUrl<P, E> {
+    source: ImageSource,
+    cache_dir: Option<PathBuf>,
+    placeholder: P,
+    error: E,
+    fill: FillStrat,
+    slices: Option<Insets>,
+}
+
+impl<P, E> ImageUrl<P, E> {
+    /// The view shown while the image is being fetched.
+    pub fn placeholder<P2>(self, placeholder: P2) -> ImageUrl<P2, E> {
+        ImageUrl {
+            source: self.source,
+            cache_dir: self.cache_dir,
+            placeholder,
+            error: self.error,
+            fill: self.fill,
+            slices: self.slices,
+        }
+    }
+
+    /// The view shown if the image couldn't be fetched or decoded.
+    pub fn error<E2>(self, error: E2) -> ImageUrl<P, E2> {
+        ImageUrl {
+            source: self.source,
+            cache_dir: self.cache_dir,
+            placeholder: self.placeholder,
+            error,
+            fill: self.fill,
+            slices: self.slices,
+        }
+    }
+
+    /// How the image fills the space it is given, once it is fetched.
+    pub fn fill(mut self, fill: FillStrat) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Draw the image as a nine-slice image once it is fetched; see [`Image::slices`].
+    pub fn slices(mut self, insets: Insets) -> Self {
+        self.slices = Some(insets);
+        self
+    }
+
+    /// Cache the image on disk in `dir`, rather than in `xilem-image-cache` in the
+    /// system's temporary directory.
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// The view of the fetched image.
+    fn image(&self, image_data: &ImageBuf) -> Image {
+        Image {
+            image_data: image_data.clone(),
+            fill: self.fill,
+            slices: self.slices,
+        }
+    }
+
+    /// Start loading the image, unless it is in the memory cache.
+    fn start_load(&self, cx: &mut ViewCx) -> Load {
+        if let Some(image_data) = memory_cache().lock().unwrap().get(&self.source) {
+            return Load::Loaded(image_data);
+        }
+        let source = self.source.clone();
+        let cache_dir = self.cache_dir.clone().unwrap_or_else(default_cache_dir);
+        Load::Loading(cx.task_pool().spawn(move || {
+            let image = match &source {
+                ImageSource::Url(url) => load_image(url, &cache_dir),
+                ImageSource::Path(path) => load_file(path),
+            };
+            image.map_err(|err| err.to_string())
+        }))
+    }
+}
+
+type LoadTask = Task<Result<ImageBuf, String>>;
+
+enum Load {
+    Loading(LoadTask),
+    Loaded(ImageBuf),
+    Failed,
+}
+
+/// The view state of an [`ImageUrl`], with the view states of its placeholder and
+/// error views.
+pub struct ImageUrlState<P, E> {
+    load: Load,
+    shown: Shown<P, E>,
+    /// Incremented each time the shown view changes, so that messages for the old
+    /// one are stale
+    generation: u64,
+}
+
+/// The view an [`ImageUrl`] is showing, with its view state.
+enum Shown<P, E> {
+    Placeholder(P),
+    Image(Image),
+    Error(E),
+}
+
+/// The widget and view state of the view an [`ImageUrl`] is showing.
+type ShownVariant<P, E> = (WidgetPod<Box<dyn Widget>>, Shown<P, E>);
+
+impl<P, E> ImageUrl<P, E> {
+    /// Build the view for the state of `load`.
+    fn build_shown<State, Action>(
+        &self,
+        load: &Load,
+        generation: u64,
+        cx: &mut ViewCx,
+    ) -> ShownVariant<P::ViewState, E::ViewState>
+    where
+        P: MasonryView<State, Action>,
+        E: MasonryView<State, Action>,
+    {
+        match load {
+            Load::Loading(_) => {
+                let (child, state) = cx.with_id(ViewId::for_type::<P>(generation), |cx| {
+                    self.placeholder.build(cx)
+                });
+                (child.boxed(), Shown::Placeholder(state))
+            }
+            Load::Loaded(image_data) => {
+                let image = self.image(image_data);
+                let (child, ()) = MasonryView::<State, Action>::build(&image, cx);
+                (child.boxed(), Shown::Image(image))
+            }
+            Load::Failed => {
+                let (child, state) =
+                    cx.with_id(ViewId::for_type::<E>(generation), |cx| self.error.build(cx));
+                (child.boxed(), Shown::Error(state))
+            }
+        }
+    }
+}
+
+impl<State, Action, P, E> MasonryView<State, Action> for ImageUrl<P, E>
+where
+    P: MasonryView<State, Action>,
+    E: MasonryView<State, Action>,
+{
+    type Element = ImageLoader;
+    type ViewState = ImageUrlState<P::ViewState, E::ViewState>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let load = self.start_load(cx);
+        let generation = 0;
+        let (child, shown) = self.build_shown(&load, generation, cx);
+        let task = match &load {
+            Load::Loading(task) => Some(task.clone()),
+            _ => None,
+        };
+        let pod = cx.with_action_widget(|_| WidgetPod::new(ImageLoader { child, task }));
+        let view_state = ImageUrlState {
+            load,
+            shown,
+            generation,
+        };
+        (pod, view_state)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.source != self.source || prev.cache_dir != self.cache_dir {
+            view_state.load = self.start_load(cx);
+            if let Load::Loading(task) = &view_state.load {
+                task.wake_when_done(element.ctx.wake_handle());
+                element.widget.task = Some(task.clone());
+            }
+        }
+
+        let generation = view_state.generation;
+        let mut child = element.ctx.get_mut(&mut element.widget.child);
+        match (&view_state.load, &mut view_state.shown) {
+            (Load::Loading(_), Shown::Placeholder(state)) => {
+                cx.with_id(ViewId::for_type::<P>(generation), |cx| {
+                    let child = child.downcast();
+                    self.placeholder
+                        .rebuild(state, cx, &prev.placeholder, child);
+                });
+                return;
+            }
+            (Load::Loaded(image_data), Shown::Image(shown)) => {
+                let image = self.image(image_data);
+                MasonryView::<State, Action>::rebuild(&image, &mut (), cx, shown, child.downcast());
+                *shown = image;
+                return;
+            }
+            (Load::Failed, Shown::Error(state)) => {
+                cx.with_id(ViewId::for_type::<E>(generation), |cx| {
+                    self.error.rebuild(state, cx, &prev.error, child.downcast());
+                });
+                return;
+            }
+            _ => {}
+        }
+        drop(child);
+        match view_state.shown {
+            Shown::Placeholder(_) => cx.teardown(ViewId::for_type::<P>(generation)),
+            Shown::Error(_) => cx.teardown(ViewId::for_type::<E>(generation)),
+            Shown::Image(_) => {}
+        }
+        view_state.generation = generation.wrapping_add(1);
+        let (child, shown) = self.build_shown(&view_state.load, view_state.generation, cx);
+        view_state.shown = shown;
+        ImageLoader::replace_child(&mut element, child);
+        cx.mark_changed();
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((start, rest)) = id_path.split_first() {
+            if start.routing_id() != view_state.generation {
+                return MessageResult::Stale(message);
+            }
+            return match &mut view_state.shown {
+                Shown::Placeholder(state) => {
+                    self.placeholder.message(state, rest, message, app_state)
+                }
+                Shown::Error(state) => self.error.message(state, rest, message, app_state),
+                Shown::Image(_) => MessageResult::Stale(message),
+            };
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::Other(payload) if payload.is::<ImageFetched>() => {
+                    let Load::Loading(task) = &view_state.load else {
+                        return MessageResult::Nop;
+                    };
+                    // The previous image may have finished after the URL changed
+                    let Some(result) = task.take() else {
+                        return MessageResult::Nop;
+                    };
+                    view_state.load = match result {
+                        Ok(Ok(image_data)) => {
+                            memory_cache()
+                                .lock()
+                                .unwrap()
+                                .insert(self.source.clone(), image_data.clone());
+                            Load::Loaded(image_data)
+                        }
+                        Ok(Err(err)) => {
+                            tracing::warn!("Failed to load image from {}: {err}", self.source);
+                            Load::Failed
+                        }
+                        Err(_) => {
+                            tracing::warn!("Loading image from {} panicked", self.source);
+                            Load::Failed
+                        }
+                    };
+                    MessageResult::RequestRebuild
+                }
+                action => {
+                    tracing::error!("Wrong action type in ImageUrl::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in ImageUrl::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+/// The action sent by an [`ImageLoader`] when its image has been fetched.
+struct ImageFetched;
+
+/// The widget created by [`image_url`], which waits for its image to be fetched.
+pub struct ImageLoader {
+    child: WidgetPod<Box<dyn Widget>>,
+    /// The fetch of the image, until the widget has been woken by it
+    task: Option<LoadTask>,
+}
+
+impl ImageLoader {
+    fn replace_child(this: &mut WidgetMut<'_, Self>, child: WidgetPod<Box<dyn Widget>>) {
+        let old_child = std::mem::replace(&mut this.widget.child, child);
+        this.ctx.remove_child(old_child);
+    }
+}
+
+/// Forward all events to the child widget.
+impl Widget for ImageLoader {
+    fn on_pointer_event(&mut self, ctx: &mut EventCtx, event: &PointerEvent) {
+        self.child.on_pointer_event(ctx, event);
+    }
+    fn on_text_event(&mut self, ctx: &mut EventCtx, event: &TextEvent) {
+        self.child.on_text_event(ctx, event);
+    }
+    fn on_access_event(&mut self, ctx: &mut EventCtx, event: &AccessEvent) {
+        self.child.on_access_event(ctx, event);
+    }
+
+    fn on_status_change(&mut self, _: &mut LifeCycleCtx, _: &StatusChange) {
+        // Intentionally do nothing
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        self.child.lifecycle(ctx, event);
+        match (event, &self.task) {
+            (LifeCycle::WidgetAdded, Some(task)) => task.wake_when_done(ctx.wake_handle()),
+            (LifeCycle::Wake, Some(task)) if task.is_done() => {
+                self.task = None;
+                ctx.submit_action(masonry::Action::Other(Arc::new(ImageFetched)));
+            }
+            _ => {}
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints) -> Size {
+        let size = self.child.layout(ctx, bc);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, scene: &mut Scene) {
+        self.child.paint(ctx, scene);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(&mut self, ctx: &mut AccessCtx) {
+        self.child.accessibility(ctx);
+    }
+
+    fn children_ids(&self) -> SmallVec<[WidgetId; 16]> {
+        let mut vec = SmallVec::new();
+        vec.push(self.child.id());
+        vec
+    }
+}
+
+/// How many bytes of decoded images the memory cache keeps.
+const MEMORY_CACHE_LEN: usize = 64 * 1024 * 1024;
+
+/// The images decoded most recently, so that views of an image which was already
+/// fetched can show it straight away.
+struct MemoryCache {
+    /// The images, from least to most recently used
+    images: VecDeque<(ImageSource, ImageBuf)>,
+    len: usize,
+}
+
+impl MemoryCache {
+    fn get(&mut self, source: &ImageSource) -> Option<ImageBuf> {
+        let index = self.images.iter().position(|(key, _)| key == source)?;
+        let entry = self.images.remove(index)?;
+        let image_data = entry.1.clone();
+        self.images.push_back(entry);
+        Some(image_data)
+    }
+
+    /// Add `image_data`, dropping the images used least recently to make room for it.
+    fn insert(&mut self, source: ImageSource, image_data: ImageBuf) {
+        if let Some(index) = self.images.iter().position(|(key, _)| *key == source) {
+            let (_, old) = self.images.remove(index).unwrap();
+            self.len -= old.data.data().len();
+        }
+        self.len += image_data.data.data().len();
+        self.images.push_back((source, image_data));
+        while self.len > MEMORY_CACHE_LEN && self.images.len() > 1 {
+            let (_, old) = self.images.pop_front().unwrap();
+            self.len -= old.data.data().len();
+        }
+    }
+}
+
+fn memory_cache() -> &'static Mutex<MemoryCache> {
+    static MEMORY_CACHE: Mutex<MemoryCache> = Mutex::new(MemoryCache {
+        images: VecDeque::new(),
+        len: 0,
+    });
+    &MEMORY_CACHE
+}
+
+fn default_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("xilem-image-cache")
+}
+
+/// A name for the cache files of `url`, which is stable across runs of the app.
+fn cache_key(url: &str) -> String {
+    // FNV-1a
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+type LoadError = Box<dyn Error + Send + Sync>;
+
+/// Fetch and decode the image at `url`, using the copy cached in `cache_dir` if
+/// it is still current, or if the server can't be reached.
+///
+/// `file` URLs are read directly, without being cached on disk.
+fn load_image(url: &str, cache_dir: &Path) -> Result<ImageBuf, LoadError> {
+    let parsed = Url::parse(url)?;
+    if parsed.scheme() == "file" {
+        let path = parsed
+            .to_file_path()
+            .map_err(|()| format!("{url} isn't the URL of a local file"))?;
+        return load_file(&path);
+    }
+    let key = cache_key(url);
+    let body_path = cache_dir.join(format!("{key}.img"));
+    let etag_path = cache_dir.join(format!("{key}.etag"));
+    let cached = fs::read(&body_path).ok();
+    let etag = cached
+        .as_ref()
+        .and_then(|_| fs::read_to_string(&etag_path).ok());
+
+    let bytes = match (http::get(url, etag.as_deref()), cached) {
+        (Ok(response), Some(cached)) if response.status == 304 => cached,
+        (Ok(response), _) if (200..300).contains(&response.status) => {
+            if let Err(err) = store(cache_dir, &body_path, &etag_path, &response) {
+                tracing::warn!("Failed to cache image from {url}: {err}");
+            }
+            response.body
+        }
+        (Ok(response), _) => {
+            return Err(format!("server responded with status {}", response.status).into());
+        }
+        (Err(err), Some(cached)) => {
+            tracing::warn!("Failed to fetch image from {url}, using cached copy: {err}");
+            cached
+        }
+        (Err(err), None) => return Err(err.into()),
+    };
+    decode(&bytes)
+}
+
+fn load_file(path: &Path) -> Result<ImageBuf, LoadError> {
+    decode(&fs::read(path)?)
+}
+
+fn store(
+    cache_dir: &Path,
+    body_path: &Path,
+    etag_path: &Path,
+    response: &http::Response,
+) -> std::io::Result<()> {
+    fs::create_dir_all(cache_dir)?;
+    fs::write(body_path, &response.body)?;
+    match &response.etag {
+        Some(etag) => fs::write(etag_path, etag),
+        None if etag_path.exists() => fs::remove_file(etag_path),
+        None => Ok(()),
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<ImageBuf, LoadError> {
+    let image = image::load_from_memory(bytes)?.into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok(ImageBuf::new(
+        image.into_raw().into(),
+        Format::Rgba8,
+        width,
+        height,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    use masonry::testing::TestHarness;
+    use masonry::widget::{RootWidget, WidgetRef};
+
+    use super::*;
+
+    fn png() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image::RgbaImage::new(2, 3)
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    /// Serve a PNG with an ETag, answering `requests` requests and returning them.
+    ///
+    /// The first `failures` requests are answered with an error.
+    fn serve(requests: usize, failures: usize) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/image.png", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let body = png();
+            let mut seen = Vec::new();
+            for (ix, stream) in listener.incoming().take(requests).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                let len = stream.read(&mut request).unwrap();
+                let request = String::from_utf8_lossy(&request[..len]).to_string();
+                if ix < failures {
+                    stream
+                        .write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n")
+                        .unwrap();
+                } else if request.contains("If-None-Match: \"v1\"") {
+                    stream
+                        .write_all(b"HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\n\r\n")
+                        .unwrap();
+                } else {
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\n\r\n",
+                        body.len()
+                    );
+                    stream.write_all(head.as_bytes()).unwrap();
+                    stream.write_all(&body).unwrap();
+                }
+                seen.push(request);
+            }
+            seen
+        });
+        (url, server)
+    }
+
+    /// A cache directory for the test `name`, which is removed when it is dropped.
+    struct TestCacheDir(PathBuf);
+
+    impl TestCacheDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("xilem-image-test-{name}-{}", std::process::id()));
+            TestCacheDir(dir)
+        }
+    }
+
+    impl Drop for TestCacheDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    type TestView = ImageUrl<Label, Label>;
+
+    type Built = (TestHarness, ViewCx, ImageUrlState<(), ()>);
+
+    fn build(view: &TestView) -> Built {
+        let mut cx = ViewCx::new();
+        let (pod, view_state) = MasonryView::<()>::build(view, &mut cx);
+        let harness = TestHarness::create(RootWidget::from_pod(pod));
+        (harness, cx, view_state)
+    }
+
+    /// Wait for the image of `view` to be loaded or to fail, and rebuild it.
+    fn load(view: &TestView, (mut harness, mut cx, mut view_state): Built) -> TestHarness {
+        assert!(harness.wait_for_wake(Duration::from_secs(5)));
+        let (action, _) = harness.pop_action().expect("image wasn't loaded");
+        let result =
+            MasonryView::<()>::message(view, &mut view_state, &[], Box::new(action), &mut ());
+        assert!(matches!(result, MessageResult::RequestRebuild));
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<RootWidget<ImageLoader>>();
+            MasonryView::<()>::rebuild(view, &mut view_state, &mut cx, view, root.get_element());
+        });
+        harness
+    }
+
+    fn shown(harness: &TestHarness) -> WidgetRef<'_, dyn Widget> {
+        harness.root_widget().children()[0].children()[0]
+    }
+
+    fn shown_label(harness: &TestHarness) -> Option<String> {
+        let label = shown(harness).downcast::<masonry::widget::Label>()?;
+        Some(label.text().to_string())
+    }
+
+    #[test]
+    fn cached_image_is_revalidated() {
+        let (url, server) = serve(2, 0);
+        let cache_dir = TestCacheDir::new("revalidated");
+
+        let first = load_image(&url, &cache_dir.0).unwrap();
+        let second = load_image(&url, &cache_dir.0).unwrap();
+        assert_eq!((first.width, first.height), (2, 3));
+        assert_eq!(first.data.data(), second.data.data());
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("If-None-Match"));
+        assert!(requests[1].contains("If-None-Match: \"v1\""));
+    }
+
+    #[test]
+    fn files_are_loaded_in_the_background() {
+        // Characters with a meaning in URLs are taken literally in paths
+        let path = std::env::temp_dir().join(format!(
+            "xilem-image-file-test-100%25 #{}.png",
+            std::process::id()
+        ));
+        fs::write(&path, png()).unwrap();
+        let view = image_file(&path).placeholder(label("Loading"));
+        let harness = load(&view, build(&view));
+        let image = shown(&harness).downcast::<masonry::widget::Image>();
+        assert!(image.is_some());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn file_urls_are_percent_decoded() {
+        let path =
+            std::env::temp_dir().join(format!("xilem-image-url-test {}.png", std::process::id()));
+        fs::write(&path, png()).unwrap();
+        let url = Url::from_file_path(&path).unwrap().to_string();
+        assert!(url.contains("%20"));
+        let cache_dir = TestCacheDir::new("file-url");
+        let image = load_image(&url, &cache_dir.0).unwrap();
+        assert_eq!((image.width, image.height), (2, 3));
+        fs::remove_file(path).unwrap();
+
+        // Files on other machines can't be read
+        assert!(load_image("file://example.com/image.png", &cache_dir.0).is_err());
+        assert!(!cache_dir.0.exists());
+    }
+
+    #[test]
+    fn image_replaces_placeholder() {
+        let (url, server) = serve(1, 0);
+        let cache_dir = TestCacheDir::new("placeholder");
+        let view = image_url(url.as_str())
+            .cache_dir(&cache_dir.0)
+            .placeholder(label("Loading"))
+            .error(label("Failed"));
+
+        let built = build(&view);
+        assert_eq!(shown_label(&built.0).as_deref(), Some("Loading"));
+        let harness = load(&view, built);
+        assert!(shown(&harness)
+            .downcast::<masonry::widget::Image>()
+            .is_some());
+        assert!(cache_dir.0.exists());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn failed_fetch_is_retried() {
+        let (url, server) = serve(2, 1);
+        let cache_dir = TestCacheDir::new("retried");
+        let view = image_url(url.as_str())
+            .cache_dir(&cache_dir.0)
+            .error(label("Failed"));
+
+        let harness = load(&view, build(&view));
+        assert_eq!(shown_label(&harness).as_deref(), Some("Failed"));
+
+        // The failure isn't remembered, so a new view fetches the image again
+        let harness = load(&view, build(&view));
+        assert!(shown(&harness)
+            .downcast::<masonry::widget::Image>()
+            .is_some());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn memory_cache_drops_least_recently_used() {
+        let image_data =
+            |len: usize| ImageBuf::new(vec![0; len].into(), Format::Rgba8, len as u32 / 4, 1);
+        let mut cache = MemoryCache {
+            images: VecDeque::new(),
+            len: 0,
+        };
+        let url = |url: &str| ImageSource::Url(url.to_string());
+        cache.insert(url("a"), image_data(MEMORY_CACHE_LEN / 2));
+        cache.insert(url("b"), image_data(MEMORY_CACHE_LEN / 4));
+        assert!(cache.get(&url("a")).is_some());
+        cache.insert(url("c"), image_data(MEMORY_CACHE_LEN / 2));
+        assert!(cache.get(&url("b")).is_none());
+        assert!(cache.get(&url("a")).is_some());
+        assert!(cache.get(&url("c")).is_some());
+        assert_eq!(cache.len, MEMORY_CACHE_LEN);
+    }
+}
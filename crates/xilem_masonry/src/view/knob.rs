@@ -0,0 +1,153 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::RangeInclusive;
+
+use masonry::widget::{KnobDrag, WidgetMut};
+use masonry::WidgetPod;
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A rotary knob for a value in `range`, such as the gain of an audio channel.
+///
+/// `callback` is called with the new value when the user turns the knob.
+pub fn knob<F, State, Action>(value: f64, range: RangeInclusive<f64>, callback: F) -> Knob<F>
+where
+    F: Fn(&mut State, f64) -> Action + 'static,
+{
+    Knob {
+        value,
+        range,
+        default_value: None,
+        step: None,
+        drag: KnobDrag::default(),
+        disabled: false,
+        callback,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Knob<F> {
+    value: f64,
+    range: RangeInclusive<f64>,
+    default_value: Option<f64>,
+    step: Option<f64>,
+    drag: KnobDrag,
+    disabled: bool,
+    callback: F,
+}
+
+impl<F> Knob<F> {
+    /// The value which double-clicking the knob resets to, which is the start of the
+    /// range by default.
+    pub fn default_value(mut self, default_value: f64) -> Self {
+        self.default_value = Some(default_value);
+        self
+    }
+
+    /// How much the arrow keys change the value, which is a hundredth of the range
+    /// by default.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    /// How dragging turns the knob.
+    pub fn drag(mut self, drag: KnobDrag) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    /// Disable the knob, so that it can't be turned.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    fn default_value_or_start(&self) -> f64 {
+        self.default_value.unwrap_or(*self.range.start())
+    }
+
+    fn step_or_default(&self) -> f64 {
+        self.step
+            .unwrap_or((self.range.end() - self.range.start()) / 100.0)
+    }
+}
+
+impl<F, State, Action> MasonryView<State, Action> for Knob<F>
+where
+    F: Fn(&mut State, f64) -> Action + 'static,
+{
+    type Element = masonry::widget::Knob;
+    type ViewState = ();
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        cx.with_leaf_action_widget(|_| {
+            WidgetPod::new(
+                masonry::widget::Knob::new(self.value, self.range.clone())
+                    .with_default_value(self.default_value_or_start())
+                    .with_step(self.step_or_default())
+                    .with_drag(self.drag),
+            )
+            .with_disabled(self.disabled)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.range != self.range {
+            element.set_range(self.range.clone());
+            cx.mark_changed();
+        }
+        if prev.value != self.value || prev.range != self.range {
+            element.set_value(self.value);
+            cx.mark_changed();
+        }
+        if prev.default_value_or_start() != self.default_value_or_start() {
+            element.set_default_value(self.default_value_or_start());
+        }
+        if prev.step_or_default() != self.step_or_default() {
+            element.set_step(self.step_or_default());
+            cx.mark_changed();
+        }
+        if prev.drag != self.drag {
+            element.set_drag(self.drag);
+        }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Knob::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::ValueChanged(value) = *action {
+                    MessageResult::Action((self.callback)(app_state, value))
+                } else {
+                    tracing::error!("Wrong action type in Knob::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in Knob::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
-use masonry::{widget::WidgetMut, ArcStr, WidgetPod};
+use masonry::widget::{TextSpan, WidgetMut};
+use masonry::{ArcStr, WidgetPod};
 
 use crate::{Color, MasonryView, MessageResult, TextAlignment, ViewCx, ViewId};
 
@@ -11,14 +12,17 @@ pub fn label(label: impl Into<ArcStr>) -> Label {
         text_color: Color::WHITE,
         alignment: TextAlignment::default(),
         disabled: false,
+        spans: Vec::new(),
     }
 }
 
+#[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Label {
     label: ArcStr,
     text_color: Color,
     alignment: TextAlignment,
     disabled: bool,
+    spans: Vec<TextSpan>,
     // TODO: add more attributes of `masonry::widget::Label`
 }
 
@@ -33,8 +37,16 @@ impl Label {
         self
     }
 
-    pub fn disabled(mut self) -> Self {
-        self.disabled = true;
+    /// Show the label greyed out, like the disabled widgets around it.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Style parts of the text differently, such as the parts of a search result
+    /// which matched the query.
+    pub fn spans(mut self, spans: Vec<TextSpan>) -> Self {
+        self.spans = spans;
         self
     }
 }
@@ -47,8 +59,10 @@ impl<State, Action> MasonryView<State, Action> for Label {
         let widget_pod = WidgetPod::new(
             masonry::widget::Label::new(self.label.clone())
                 .with_text_brush(self.text_color)
-                .with_text_alignment(self.alignment),
-        );
+                .with_text_alignment(self.alignment)
+                .with_spans(self.spans.clone()),
+        )
+        .with_disabled(self.disabled);
         (widget_pod, ())
     }
 
@@ -63,10 +77,10 @@ impl<State, Action> MasonryView<State, Action> for Label {
             element.set_text(self.label.clone());
             cx.mark_changed();
         }
-        // if prev.disabled != self.disabled {
-        //     element.set_disabled(self.disabled);
-        //     cx.mark_changed();
-        // }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
         if prev.text_color != self.text_color {
             element.set_text_brush(self.text_color);
             cx.mark_changed();
@@ -75,6 +89,10 @@ impl<State, Action> MasonryView<State, Action> for Label {
             element.set_alignment(self.alignment);
             cx.mark_changed();
         }
+        if prev.spans != self.spans {
+            element.set_spans(self.spans.clone());
+            cx.mark_changed();
+        }
     }
 
     fn message(
@@ -0,0 +1,101 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::{widget::WidgetMut, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Convert the actions of `view` to the actions of its parent.
+///
+/// `map` is called with the app state whenever `view` (or one of its descendants)
+/// returns an action. This allows views to define their own action type, which is
+/// then handled by the parent, or by the app's reducer.
+pub fn map_action<State, ParentAction, ChildAction, V, F>(
+    view: V,
+    map: F,
+) -> MapAction<V, F, ChildAction>
+where
+    V: MasonryView<State, ChildAction>,
+    F: Fn(&mut State, ChildAction) -> ParentAction + 'static,
+{
+    MapAction {
+        view,
+        map,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct MapAction<V, F, ChildAction> {
+    view: V,
+    map: F,
+    phantom: PhantomData<fn() -> ChildAction>,
+}
+
+impl<State, ParentAction, ChildAction, V, F> MasonryView<State, ParentAction>
+    for MapAction<V, F, ChildAction>
+where
+    V: MasonryView<State, ChildAction>,
+    F: Fn(&mut State, ChildAction) -> ParentAction + 'static,
+    ChildAction: 'static,
+{
+    type Element = V::Element;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        self.view.build(cx)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        element: WidgetMut<Self::Element>,
+    ) {
+        self.view.rebuild(view_state, cx, &prev.view, element);
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<ParentAction> {
+        match self.view.message(view_state, id_path, message, app_state) {
+            MessageResult::Action(action) => MessageResult::Action((self.map)(app_state, action)),
+            MessageResult::RequestRebuild => MessageResult::RequestRebuild,
+            MessageResult::Nop => MessageResult::Nop,
+            MessageResult::Stale(message) => MessageResult::Stale(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::button;
+
+    #[test]
+    fn map_button_action() {
+        let view = map_action(button("Press", |_: &mut u32| 1_u32), |count, action| {
+            *count += action;
+            *count * 10
+        });
+        let mut cx = ViewCx::new();
+        let (_, mut view_state) = view.build(&mut cx);
+
+        let mut count = 1;
+        let result = view.message(
+            &mut view_state,
+            &[],
+            Box::new(masonry::Action::ButtonPressed),
+            &mut count,
+        );
+        assert!(matches!(result, MessageResult::Action(20)));
+        assert_eq!(count, 2);
+    }
+}
@@ -1,20 +1,112 @@
 // Copyright 2024 the Xilem Authors
 // SPDX-License-Identifier: Apache-2.0
 
+mod align;
+pub use align::*;
+
+mod autocomplete;
+pub use autocomplete::*;
+
+#[cfg(feature = "effects")]
+mod backdrop_blur;
+#[cfg(feature = "effects")]
+pub use backdrop_blur::*;
+
 mod button;
 pub use button::*;
 
+mod cache_paint;
+pub use cache_paint::*;
+
+mod canvas;
+pub use canvas::*;
+
 mod checkbox;
 pub use checkbox::*;
 
+mod composite;
+pub use composite::*;
+
+mod data_grid;
+pub use data_grid::*;
+
+mod debug_stats_overlay;
+pub use debug_stats_overlay::*;
+
+mod dialog;
+pub use dialog::*;
+
+mod either;
+pub use either::*;
+
+mod event_filter;
+pub use event_filter::*;
+
+mod filterable_list;
+pub use filterable_list::*;
+
 mod flex;
 pub use flex::*;
 
+mod grid;
+pub use grid::*;
+
+mod icon;
+pub use icon::*;
+
+mod image;
+pub use image::*;
+
+mod knob;
+pub use knob::*;
+
 mod label;
 pub use label::*;
 
+mod map_action;
+pub use map_action::*;
+
+mod modal_layer;
+pub use modal_layer::*;
+
+mod on_click;
+pub use on_click::*;
+
+mod pager;
+pub use pager::*;
+
+mod popover;
+pub use popover::*;
+
+mod portal;
+pub use portal::*;
+
 mod prose;
 pub use prose::*;
 
+mod segmented_control;
+pub use segmented_control::*;
+
+mod sized_box;
+pub use sized_box::*;
+
+mod skeleton;
+pub use skeleton::*;
+
+mod slider;
+pub use slider::*;
+
+mod sticky;
+pub use sticky::*;
+
 mod textbox;
 pub use textbox::*;
+
+mod toggle_group;
+pub use toggle_group::*;
+
+mod token_field;
+pub use token_field::*;
+
+mod virtual_list;
+pub use virtual_list::*;
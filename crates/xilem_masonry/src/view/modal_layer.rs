@@ -0,0 +1,136 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Show `modal`, such as an [`alert`](crate::view::alert), above `content`.
+///
+/// While the modal is shown, the content can't be interacted with, and keyboard
+/// focus stays in the modal.
+pub fn modal_layer<State, Action, C, M>(content: C, modal: Option<M>) -> ModalLayer<C, M>
+where
+    C: MasonryView<State, Action>,
+    M: MasonryView<State, Action>,
+{
+    ModalLayer { content, modal }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct ModalLayer<C, M> {
+    content: C,
+    modal: Option<M>,
+}
+
+pub struct ModalLayerState<C, M> {
+    content: C,
+    modal: Option<M>,
+    /// Incremented each time a modal is shown, so that messages for earlier modals are stale
+    generation: u64,
+}
+
+/// The routing id of the content; modals use the generation, which starts at 1.
+const CONTENT_ID: u64 = 0;
+
+impl<State, Action, C, M> MasonryView<State, Action> for ModalLayer<C, M>
+where
+    C: MasonryView<State, Action>,
+    M: MasonryView<State, Action>,
+{
+    type Element = widget::ModalLayer;
+    type ViewState = ModalLayerState<C::ViewState, M::ViewState>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (content, content_state) = cx.with_id(ViewId::for_type::<C>(CONTENT_ID), |cx| {
+            self.content.build(cx)
+        });
+        let mut layer = widget::ModalLayer::new_pod(content.boxed());
+        let mut state = ModalLayerState {
+            content: content_state,
+            modal: None,
+            generation: CONTENT_ID,
+        };
+        if let Some(modal) = &self.modal {
+            state.generation += 1;
+            let (modal, modal_state) = cx.with_id(ViewId::for_type::<M>(state.generation), |cx| {
+                modal.build(cx)
+            });
+            layer = layer.with_modal_pod(modal.boxed());
+            state.modal = Some(modal_state);
+        }
+        (WidgetPod::new(layer), state)
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        cx.with_id(ViewId::for_type::<C>(CONTENT_ID), |cx| {
+            let mut content = element.child_mut();
+            let content = content.downcast();
+            self.content
+                .rebuild(&mut view_state.content, cx, &prev.content, content);
+        });
+
+        match (&prev.modal, &self.modal, &mut view_state.modal) {
+            (Some(prev_modal), Some(modal), Some(modal_state)) => {
+                cx.with_id(ViewId::for_type::<M>(view_state.generation), |cx| {
+                    let mut element = element.modal_mut().unwrap();
+                    let element = element.downcast();
+                    modal.rebuild(modal_state, cx, prev_modal, element);
+                });
+            }
+            (_, Some(modal), _) => {
+                if view_state.modal.is_some() {
+                    cx.teardown(ViewId::for_type::<M>(view_state.generation));
+                }
+                view_state.generation += 1;
+                let (modal, modal_state) = cx
+                    .with_id(ViewId::for_type::<M>(view_state.generation), |cx| {
+                        modal.build(cx)
+                    });
+                element.set_modal_pod(modal.boxed());
+                view_state.modal = Some(modal_state);
+                cx.mark_changed();
+            }
+            (_, None, _) => {
+                if view_state.modal.take().is_some() {
+                    element.remove_modal();
+                    cx.teardown(ViewId::for_type::<M>(view_state.generation));
+                    cx.mark_changed();
+                }
+            }
+        }
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        let Some((start, rest)) = id_path.split_first() else {
+            tracing::error!("Got empty id path in ModalLayer::message");
+            return MessageResult::Stale(message);
+        };
+        if start.routing_id() == CONTENT_ID {
+            return self
+                .content
+                .message(&mut view_state.content, rest, message, app_state);
+        }
+        match (&self.modal, &mut view_state.modal) {
+            (Some(modal), Some(modal_state)) if start.routing_id() == view_state.generation => {
+                modal.message(modal_state, rest, message, app_state)
+            }
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
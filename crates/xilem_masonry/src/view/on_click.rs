@@ -0,0 +1,90 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Call `callback` when `child` is clicked, such as to open a card or select a
+/// row of a list.
+///
+/// Nothing is painted besides `child`, unlike [`button`](crate::view::button).
+pub fn on_click<State, Action, V, F>(child: V, callback: F) -> OnClick<V, F>
+where
+    V: MasonryView<State, Action>,
+    F: Fn(&mut State) -> Action + 'static,
+{
+    OnClick { child, callback }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct OnClick<V, F> {
+    child: V,
+    callback: F,
+}
+
+/// The routing id of the child.
+const CHILD_ID: u64 = 0;
+
+impl<State, Action, V, F> MasonryView<State, Action> for OnClick<V, F>
+where
+    V: MasonryView<State, Action>,
+    F: Fn(&mut State) -> Action + 'static,
+{
+    type Element = widget::Clickable;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) =
+            cx.with_id(ViewId::for_type::<V>(CHILD_ID), |cx| self.child.build(cx));
+        let pod =
+            cx.with_action_widget(|_| WidgetPod::new(widget::Clickable::new_pod(child.boxed())));
+        (pod, child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        cx.with_id(ViewId::for_type::<V>(CHILD_ID), |cx| {
+            let mut child = element.child_mut();
+            let child = child.downcast();
+            self.child.rebuild(child_state, cx, &prev.child, child);
+        });
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((start, rest)) = id_path.split_first() {
+            if start.routing_id() == CHILD_ID {
+                return self.child.message(child_state, rest, message, app_state);
+            }
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::ButtonPressed = *action {
+                    MessageResult::Action((self.callback)(app_state))
+                } else {
+                    tracing::error!("Wrong action type in OnClick::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in OnClick::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
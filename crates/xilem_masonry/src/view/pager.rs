@@ -0,0 +1,220 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+
+use masonry::{
+    widget::{self, WidgetMut},
+    Widget, WidgetPod,
+};
+
+use crate::{ElementSplice, MasonryView, MessageResult, VecSplice, ViewCx, ViewId, ViewSequence};
+
+/// Show one of `pages` at a time, which the user can turn by swiping, by clicking
+/// the dots below them, or with the arrow keys.
+///
+/// The pager shows `current_page`, and calls `on_page_change` with the new page
+/// when the user turns it. The app should store the new page and pass it back as
+/// `current_page`, which also lets it turn the page itself.
+pub fn pager<State, Action, Seq, Marker, F>(
+    current_page: usize,
+    pages: Seq,
+    on_page_change: F,
+) -> Pager<Seq, Marker, F>
+where
+    Seq: ViewSequence<State, Action, Marker>,
+    F: Fn(&mut State, usize) -> Action + 'static,
+{
+    Pager {
+        pages,
+        current_page,
+        on_page_change,
+        prewarm: false,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Pager<Seq, Marker, F> {
+    pages: Seq,
+    current_page: usize,
+    on_page_change: F,
+    prewarm: bool,
+    phantom: PhantomData<fn() -> Marker>,
+}
+
+impl<Seq, Marker, F> Pager<Seq, Marker, F> {
+    /// Only lay out the pages on screen, and the pages next to the current one when
+    /// the app is idle, which suits pagers with many expensive pages.
+    pub fn prewarm(mut self, prewarm: bool) -> Self {
+        self.prewarm = prewarm;
+        self
+    }
+}
+
+/// The routing id of the pages.
+const PAGES_ID: u64 = 0;
+
+impl<State, Action, Marker: 'static, Seq, F> MasonryView<State, Action> for Pager<Seq, Marker, F>
+where
+    Seq: ViewSequence<State, Action, Marker>,
+    F: Fn(&mut State, usize) -> Action + 'static,
+{
+    type Element = widget::Pager;
+    type ViewState = Seq::SeqState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let mut elements = Vec::new();
+        let mut scratch = Vec::new();
+        let seq_state = cx.with_id(ViewId::for_type::<Seq>(PAGES_ID), |cx| {
+            let mut splice = VecSplice::new(&mut elements, &mut scratch);
+            self.pages.build(cx, &mut splice)
+        });
+        let pod = cx.with_action_widget(|_| {
+            let mut pager = widget::Pager::new();
+            for page in elements.drain(..) {
+                pager = pager.with_page_pod(page);
+            }
+            WidgetPod::new(
+                pager
+                    .with_current_page(self.current_page)
+                    .with_prewarm(self.prewarm),
+            )
+        });
+        (pod, seq_state)
+    }
+
+    fn rebuild(
+        &self,
+        seq_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        cx.with_id(ViewId::for_type::<Seq>(PAGES_ID), |cx| {
+            let mut splice = PagerSplice {
+                ix: 0,
+                element: &mut element,
+            };
+            self.pages.rebuild(seq_state, cx, &prev.pages, &mut splice);
+        });
+        // The pager turns on its own when the user turns it, so we always tell it
+        // which page the app wants
+        element.set_current_page(self.current_page);
+        if prev.prewarm != self.prewarm {
+            element.set_prewarm(self.prewarm);
+        }
+        if prev.current_page != self.current_page {
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        seq_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((start, rest)) = id_path.split_first() {
+            if start.routing_id() == PAGES_ID {
+                return self.pages.message(seq_state, rest, message, app_state);
+            }
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::PageChanged(page) = *action {
+                    MessageResult::Action((self.on_page_change)(app_state, page))
+                } else {
+                    tracing::error!("Wrong action type in Pager::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in Pager::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+struct PagerSplice<'a, 'w> {
+    ix: usize,
+    element: &'a mut WidgetMut<'w, widget::Pager>,
+}
+
+impl ElementSplice for PagerSplice<'_, '_> {
+    fn push(&mut self, element: WidgetPod<Box<dyn Widget>>) {
+        self.element.insert_page_pod(self.ix, element);
+        self.ix += 1;
+    }
+
+    fn mutate(&mut self) -> WidgetMut<'_, Box<dyn Widget>> {
+        let child = self.element.page_mut(self.ix).unwrap();
+        self.ix += 1;
+        child
+    }
+
+    fn delete(&mut self, n: usize) {
+        for _ in 0..n {
+            self.element.remove_page(self.ix);
+        }
+    }
+
+    fn move_from(&mut self, offset: usize, n: usize) {
+        for i in 0..n {
+            self.element.move_page(self.ix + offset + i, self.ix + i);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+
+    use super::*;
+    use crate::view::{button, label};
+
+    #[test]
+    fn actions_reach_pages_and_pager() {
+        let view = pager(
+            1,
+            (label("One"), button("Two", |count: &mut u32| *count += 1)),
+            |count: &mut u32, page| *count += 10 * page as u32,
+        );
+        let mut cx = ViewCx::new();
+        let (pod, mut seq_state) = MasonryView::<u32, ()>::build(&view, &mut cx);
+        let mut harness = TestHarness::create(widget::Flex::row().with_child_pod(pod.boxed()));
+        let pager_ref = harness.root_widget().children()[0];
+        let pager = pager_ref.downcast::<widget::Pager>().unwrap();
+        assert_eq!((pager.len(), pager.current_page()), (2, 1));
+
+        // The button's action is routed to the pages, and the pager's to the pager
+        let id_path = |cx: &ViewCx, id| {
+            let mut id_path = Vec::new();
+            cx.id_paths.resolve_into(cx.widget_map[&id], &mut id_path);
+            id_path
+        };
+        let button_id = pager_ref.children()[1].id();
+        harness.mouse_click_on(button_id);
+        let mut count = 0;
+        while let Some((action, id)) = harness.pop_action() {
+            let id_path = id_path(&cx, id);
+            let action = Box::new(action);
+            let _ =
+                MasonryView::<u32>::message(&view, &mut seq_state, &id_path, action, &mut count);
+        }
+        assert_eq!(count, 1);
+
+        let pager_id = harness.root_widget().children()[0].id();
+        let action = Box::new(masonry::Action::PageChanged(1));
+        let id_path = id_path(&cx, pager_id);
+        let _ = MasonryView::<u32>::message(&view, &mut seq_state, &id_path, action, &mut count);
+        assert_eq!(count, 11);
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    popup::Placement,
+    widget::{self, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Show `content` in a panel next to `anchor` while `is_open` is true.
+///
+/// The panel is shown below the anchor if there is room in the window, and moved
+/// to stay inside the window. Use [`placement`](Popover::placement) to prefer
+/// another side.
+pub fn popover<State, Action, A, C>(anchor: A, is_open: bool, content: C) -> Popover<A, C>
+where
+    A: MasonryView<State, Action>,
+    C: MasonryView<State, Action>,
+{
+    Popover {
+        anchor,
+        content,
+        is_open,
+        placement: Placement::default(),
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Popover<A, C> {
+    anchor: A,
+    content: C,
+    is_open: bool,
+    placement: Placement,
+}
+
+impl<A, C> Popover<A, C> {
+    /// The side of the anchor to show the content on, if there is room.
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+}
+
+/// The routing ids of the children.
+const ANCHOR_ID: u64 = 0;
+const CONTENT_ID: u64 = 1;
+
+impl<State, Action, A, C> MasonryView<State, Action> for Popover<A, C>
+where
+    A: MasonryView<State, Action>,
+    C: MasonryView<State, Action>,
+{
+    type Element = widget::Popover;
+    type ViewState = (A::ViewState, C::ViewState);
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (anchor, anchor_state) =
+            cx.with_id(ViewId::for_type::<A>(ANCHOR_ID), |cx| self.anchor.build(cx));
+        let (content, content_state) = cx.with_id(ViewId::for_type::<C>(CONTENT_ID), |cx| {
+            self.content.build(cx)
+        });
+        let popover = widget::Popover::new_pod(anchor.boxed(), content.boxed())
+            .with_open(self.is_open)
+            .with_placement(self.placement);
+        (WidgetPod::new(popover), (anchor_state, content_state))
+    }
+
+    fn rebuild(
+        &self,
+        (anchor_state, content_state): &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        cx.with_id(ViewId::for_type::<A>(ANCHOR_ID), |cx| {
+            let mut anchor = element.anchor_mut();
+            let anchor = anchor.downcast();
+            self.anchor.rebuild(anchor_state, cx, &prev.anchor, anchor);
+        });
+        cx.with_id(ViewId::for_type::<C>(CONTENT_ID), |cx| {
+            let mut content = element.content_mut();
+            let content = content.downcast();
+            self.content
+                .rebuild(content_state, cx, &prev.content, content);
+        });
+        if prev.is_open != self.is_open {
+            element.set_open(self.is_open);
+            cx.mark_changed();
+        }
+        if prev.placement != self.placement {
+            element.set_placement(self.placement);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        (anchor_state, content_state): &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        let Some((start, rest)) = id_path.split_first() else {
+            tracing::error!("Got empty id path in Popover::message");
+            return MessageResult::Stale(message);
+        };
+        match start.routing_id() {
+            ANCHOR_ID => self.anchor.message(anchor_state, rest, message, app_state),
+            CONTENT_ID => self
+                .content
+                .message(content_state, rest, message, app_state),
+            _ => MessageResult::Stale(message),
+        }
+    }
+}
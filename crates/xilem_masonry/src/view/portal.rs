@@ -0,0 +1,278 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    Point, Widget, WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId, ViewPath};
+
+type Callback<State, Action> = Box<dyn Fn(&mut State) -> Action + 'static>;
+
+/// A view which lets the user scroll through `child`.
+///
+/// Use [`on_reach_end`](Portal::on_reach_end) to load more content as the user
+/// scrolls down, and [`on_refresh`](Portal::on_refresh) to let them pull the
+/// content down to refresh it.
+pub fn portal<State, Action, V>(child: V) -> Portal<V, State, Action>
+where
+    V: MasonryView<State, Action>,
+{
+    Portal {
+        child,
+        on_reach_end: None,
+        on_refresh: None,
+        refreshing: false,
+        scroll_key: None,
+        scroll_to: None,
+        scroll_to_view: None,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Portal<V, State, Action = ()> {
+    child: V,
+    on_reach_end: Option<(f64, Callback<State, Action>)>,
+    on_refresh: Option<Callback<State, Action>>,
+    refreshing: bool,
+    scroll_key: Option<String>,
+    scroll_to: Option<Point>,
+    scroll_to_view: Option<ViewPath>,
+}
+
+impl<V, State, Action> Portal<V, State, Action> {
+    /// Call `callback` when the bottom of the view comes within `threshold` of the
+    /// bottom of the content.
+    ///
+    /// It is called once each time the view gets there, including when the content
+    /// is too short to fill the view.
+    pub fn on_reach_end(
+        mut self,
+        threshold: f64,
+        callback: impl Fn(&mut State) -> Action + 'static,
+    ) -> Self {
+        self.on_reach_end = Some((threshold, Box::new(callback)));
+        self
+    }
+
+    /// Call `callback` when the user pulls the content down past its top.
+    ///
+    /// A spinner is shown above the content while [`refreshing`](Self::refreshing)
+    /// is true, so `callback` should start the refresh and set the state that makes
+    /// it true until the refresh completes.
+    pub fn on_refresh(mut self, callback: impl Fn(&mut State) -> Action + 'static) -> Self {
+        self.on_refresh = Some(Box::new(callback));
+        self
+    }
+
+    /// Whether a refresh started by [`on_refresh`](Self::on_refresh) is in progress.
+    pub fn refreshing(mut self, refreshing: bool) -> Self {
+        self.refreshing = refreshing;
+        self
+    }
+
+    /// Remember the scroll position under `key`, so that the view is scrolled back
+    /// there when it is shown again, such as when the user navigates back to it.
+    ///
+    /// See [`Portal::with_scroll_key`](widget::Portal::with_scroll_key) for more.
+    pub fn scroll_key(mut self, key: impl Into<String>) -> Self {
+        self.scroll_key = Some(key.into());
+        self
+    }
+
+    /// Scroll so that `position` in the content is at the top left of the view.
+    ///
+    /// This happens when the view is built, and whenever `position` changes, so the
+    /// user can scroll away from it in between.
+    pub fn scroll_to(mut self, position: Point) -> Self {
+        self.scroll_to = Some(position);
+        self
+    }
+
+    /// Scroll as little as possible to show the view at `path`, such as one which
+    /// was just added, when the view is built, and whenever `path` changes.
+    ///
+    /// Only views which send actions, such as buttons, can be found, so the view at
+    /// `path` must be one of them, or contain one. See [`ViewCx::view_path`] for how
+    /// to get the path of a view.
+    pub fn scroll_to_view(mut self, path: ViewPath) -> Self {
+        self.scroll_to_view = Some(path);
+        self
+    }
+
+    fn end_threshold(&self) -> Option<f64> {
+        self.on_reach_end.as_ref().map(|(threshold, _)| *threshold)
+    }
+}
+
+/// The routing id of the child.
+const CHILD_ID: u64 = 0;
+
+impl<V, State: 'static, Action: 'static> MasonryView<State, Action> for Portal<V, State, Action>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::Portal<Box<dyn Widget>>;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) =
+            cx.with_id(ViewId::for_type::<V>(CHILD_ID), |cx| self.child.build(cx));
+        let pod = cx.with_action_widget(|cx| {
+            let mut portal = widget::Portal::new_pod(child.boxed())
+                .with_pull_to_refresh(self.on_refresh.is_some())
+                .with_refreshing(self.refreshing);
+            if let Some(threshold) = self.end_threshold() {
+                portal = portal.with_end_threshold(threshold);
+            }
+            if let Some(key) = &self.scroll_key {
+                portal = portal.with_scroll_key(key.clone());
+            }
+            if let Some(position) = self.scroll_to {
+                portal = portal.with_scroll_to(position);
+            }
+            if let Some(id) = self
+                .scroll_to_view
+                .as_ref()
+                .and_then(|path| cx.widget_at_view_path(path))
+            {
+                portal = portal.with_scroll_to_widget(id);
+            }
+            WidgetPod::new(portal)
+        });
+        (pod, child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        cx.with_id(ViewId::for_type::<V>(CHILD_ID), |cx| {
+            let mut child = element.child_mut();
+            let child = child.downcast();
+            self.child.rebuild(child_state, cx, &prev.child, child);
+        });
+        if prev.end_threshold() != self.end_threshold() {
+            element.set_end_threshold(self.end_threshold());
+            cx.mark_changed();
+        }
+        if prev.on_refresh.is_some() != self.on_refresh.is_some() {
+            element.set_pull_to_refresh(self.on_refresh.is_some());
+            cx.mark_changed();
+        }
+        if prev.scroll_key != self.scroll_key {
+            element.set_scroll_key(self.scroll_key.clone());
+            cx.mark_changed();
+        }
+        if let Some(position) = self.scroll_to.filter(|_| prev.scroll_to != self.scroll_to) {
+            element.scroll_to(position);
+            cx.mark_changed();
+        }
+        if prev.scroll_to_view != self.scroll_to_view {
+            if let Some(id) = self
+                .scroll_to_view
+                .as_ref()
+                .and_then(|path| cx.widget_at_view_path(path))
+            {
+                element.scroll_to_widget(id);
+                cx.mark_changed();
+            }
+        }
+        // The portal starts refreshing on its own when pulled, so we always tell it
+        // whether the app is still refreshing
+        element.set_refreshing(self.refreshing);
+        if prev.refreshing != self.refreshing {
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((start, rest)) = id_path.split_first() {
+            if start.routing_id() == CHILD_ID {
+                return self.child.message(child_state, rest, message, app_state);
+            }
+            return MessageResult::Stale(message);
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match (*action, &self.on_reach_end, &self.on_refresh) {
+                (masonry::Action::ScrollReachedEnd, Some((_, callback)), _)
+                | (masonry::Action::RefreshRequested, _, Some(callback)) => {
+                    MessageResult::Action(callback(app_state))
+                }
+                (masonry::Action::ScrollReachedEnd | masonry::Action::RefreshRequested, ..) => {
+                    MessageResult::Nop
+                }
+                (action, ..) => {
+                    tracing::error!("Wrong action type in Portal::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in Portal::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::Size;
+
+    use super::*;
+    use crate::view::{button, flex, label};
+    use crate::ViewExt;
+
+    #[test]
+    fn scrolls_to_the_view_at_a_path() {
+        let view = |target: Option<ViewPath>| {
+            let portal = portal(flex((
+                label("").height(500.0),
+                button("Bottom", |_: &mut ()| ()),
+            )));
+            match target {
+                Some(target) => portal.scroll_to_view(target),
+                None => portal,
+            }
+        };
+        let first = view(None);
+        let mut cx = ViewCx::new();
+        let (pod, mut child_state) = MasonryView::<(), ()>::build(&first, &mut cx);
+        let portal_id = pod.id();
+        let mut harness = TestHarness::create_with_size(
+            widget::SizedBox::new_pod(pod.boxed()).expand(),
+            Size::new(200.0, 200.0),
+        );
+        let viewport_pos = |harness: &TestHarness| {
+            let portal = harness.root_widget().children()[0];
+            let portal = portal
+                .downcast::<widget::Portal<Box<dyn Widget>>>()
+                .unwrap();
+            portal.get_viewport_pos()
+        };
+        assert_eq!(viewport_pos(&harness), Point::ORIGIN);
+
+        let button_id = *cx.widget_map.keys().find(|id| **id != portal_id).unwrap();
+        let button_path = cx.widget_view_path(button_id).unwrap();
+        let second = view(Some(button_path));
+        harness.edit_root_widget(|mut root| {
+            let mut root = root.downcast::<widget::SizedBox>();
+            let mut portal = root.child_mut().unwrap();
+            let portal = portal.downcast();
+            MasonryView::<(), ()>::rebuild(&second, &mut child_state, &mut cx, &first, portal);
+        });
+        let button_rect = harness.get_widget(button_id).state().layout_rect();
+        assert_eq!(viewport_pos(&harness).y, button_rect.max_y() - 200.0);
+    }
+}
@@ -14,6 +14,7 @@ pub fn prose(label: impl Into<ArcStr>) -> Prose {
     }
 }
 
+#[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Prose {
     label: ArcStr,
     text_brush: TextBrush,
@@ -34,8 +35,9 @@ impl Prose {
         self
     }
 
-    pub fn disabled(mut self) -> Self {
-        self.disabled = true;
+    /// Show the text greyed out, and don't let it be selected.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
         self
     }
 }
@@ -49,7 +51,8 @@ impl<State, Action> MasonryView<State, Action> for Prose {
             masonry::widget::Prose::new(self.label.clone())
                 .with_text_brush(self.text_brush.clone())
                 .with_text_alignment(self.alignment),
-        );
+        )
+        .with_disabled(self.disabled);
         (widget_pod, ())
     }
 
@@ -64,10 +67,10 @@ impl<State, Action> MasonryView<State, Action> for Prose {
             element.set_text(self.label.clone());
             cx.mark_changed();
         }
-        // if prev.disabled != self.disabled {
-        //     element.set_disabled(self.disabled);
-        //     cx.mark_changed();
-        // }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
         if prev.text_brush != self.text_brush {
             element.set_text_brush(self.text_brush.clone());
             cx.mark_changed();
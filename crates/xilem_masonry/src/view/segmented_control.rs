@@ -0,0 +1,118 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{widget::WidgetMut, ArcStr, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A row of mutually exclusive options drawn as joined buttons, such as to switch
+/// a calendar between days, weeks and months.
+///
+/// `callback` is called with the index of the segment the user selects.
+pub fn segmented_control<F, State, Action>(
+    segments: impl IntoIterator<Item = impl Into<ArcStr>>,
+    selected: usize,
+    callback: F,
+) -> SegmentedControl<F>
+where
+    F: Fn(&mut State, usize) -> Action + 'static,
+{
+    SegmentedControl {
+        segments: segments.into_iter().map(Into::into).collect(),
+        selected,
+        disabled: false,
+        callback,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct SegmentedControl<F> {
+    segments: Vec<ArcStr>,
+    selected: usize,
+    disabled: bool,
+    callback: F,
+}
+
+impl<F> SegmentedControl<F> {
+    /// Disable the control, so that another segment can't be selected.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl<F, State, Action> MasonryView<State, Action> for SegmentedControl<F>
+where
+    F: Fn(&mut State, usize) -> Action + 'static,
+{
+    type Element = masonry::widget::SegmentedControl;
+    type ViewState = ();
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        cx.with_leaf_action_widget(|_| {
+            WidgetPod::new(
+                masonry::widget::SegmentedControl::new(self.segments.iter().cloned())
+                    .with_selected(self.selected),
+            )
+            .with_disabled(self.disabled)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.segments != self.segments {
+            for (ix, (prev_text, text)) in prev.segments.iter().zip(&self.segments).enumerate() {
+                if prev_text != text {
+                    element.set_segment_text(ix, text.clone());
+                }
+            }
+            for text in self.segments.iter().skip(prev.segments.len()) {
+                element.add_segment(text.clone());
+            }
+            for ix in (self.segments.len()..prev.segments.len()).rev() {
+                element.remove_segment(ix);
+            }
+            cx.mark_changed();
+        }
+        if prev.selected != self.selected || prev.segments.len() != self.segments.len() {
+            element.set_selected(self.selected);
+            cx.mark_changed();
+        }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in SegmentedControl::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::SegmentSelected(ix) = *action {
+                    MessageResult::Action((self.callback)(app_state, ix))
+                } else {
+                    tracing::error!("Wrong action type in SegmentedControl::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in SegmentedControl::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    direction::EdgeInsets,
+    widget::{self, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{Color, MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A box around `child`, which can set its size and paint a background and border
+/// behind it.
+pub fn sized_box<V>(child: V) -> SizedBox<V> {
+    SizedBox {
+        child,
+        width: None,
+        height: None,
+        background: None,
+        border: None,
+        corner_radius: 0.0,
+        padding: EdgeInsets::ZERO,
+        elevation: 0.0,
+    }
+}
+
+/// Add `padding` around `child`, in a [`sized_box`] of its own.
+///
+/// Unlike [`SizedBox::padding`], this can pad a view which is already in a sized
+/// box, such as to add space outside of its border.
+pub fn padding<V>(child: V, padding: impl Into<EdgeInsets>) -> SizedBox<V> {
+    sized_box(child).padding(padding)
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct SizedBox<V> {
+    child: V,
+    width: Option<f64>,
+    height: Option<f64>,
+    background: Option<Color>,
+    border: Option<(Color, f64)>,
+    corner_radius: f64,
+    padding: EdgeInsets,
+    elevation: f64,
+}
+
+impl<V> SizedBox<V> {
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: f64) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn border(mut self, color: Color, width: f64) -> Self {
+        self.border = Some((color, width));
+        self
+    }
+
+    pub fn rounded(mut self, radius: f64) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// The space between the border and the child, which can be given with
+    /// [logical edges](crate::LogicalInsets) to be flipped in right-to-left layouts.
+    pub fn padding(mut self, padding: impl Into<EdgeInsets>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// How far the box is raised above what is behind it, shown with a shadow.
+    pub fn elevation(mut self, elevation: f64) -> Self {
+        self.elevation = elevation;
+        self
+    }
+}
+
+impl<State, Action, V> MasonryView<State, Action> for SizedBox<V>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::SizedBox;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let mut widget = widget::SizedBox::new_pod(child.boxed())
+            .rounded(self.corner_radius)
+            .padding(self.padding)
+            .elevation(self.elevation);
+        if let Some(width) = self.width {
+            widget = widget.width(width);
+        }
+        if let Some(height) = self.height {
+            widget = widget.height(height);
+        }
+        if let Some(background) = self.background {
+            widget = widget.background(background);
+        }
+        if let Some((color, width)) = self.border {
+            widget = widget.border(color, width);
+        }
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if let Some(mut child) = element.child_mut() {
+            let child = child.downcast();
+            self.child.rebuild(child_state, cx, &prev.child, child);
+        }
+        if prev.width != self.width {
+            match self.width {
+                Some(width) => element.set_width(width),
+                None => element.unset_width(),
+            }
+            cx.mark_changed();
+        }
+        if prev.height != self.height {
+            match self.height {
+                Some(height) => element.set_height(height),
+                None => element.unset_height(),
+            }
+            cx.mark_changed();
+        }
+        if prev.background != self.background {
+            match self.background {
+                Some(background) => element.set_background(background),
+                None => element.clear_background(),
+            }
+            cx.mark_changed();
+        }
+        if prev.border != self.border {
+            match self.border {
+                Some((color, width)) => element.set_border(color, width),
+                None => element.clear_border(),
+            }
+            cx.mark_changed();
+        }
+        if prev.corner_radius != self.corner_radius {
+            element.set_rounded(self.corner_radius);
+            cx.mark_changed();
+        }
+        if prev.padding != self.padding {
+            element.set_padding(self.padding);
+            cx.mark_changed();
+        }
+        if prev.elevation != self.elevation {
+            element.set_elevation(self.elevation);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
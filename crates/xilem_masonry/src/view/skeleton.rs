@@ -0,0 +1,76 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{widget::WidgetMut, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A shimmering block of `width` by `height`, which stands in for content that is
+/// still loading.
+///
+/// This is useful as the placeholder of views which load in the background, such
+/// as [`image_url`](crate::view::image_url).
+pub fn skeleton(width: f64, height: f64) -> Skeleton {
+    Skeleton {
+        shape: Shape::Block { width, height },
+    }
+}
+
+/// Shimmering lines which stand in for text that is still loading, and fill the
+/// available width.
+pub fn skeleton_text(lines: usize) -> Skeleton {
+    Skeleton {
+        shape: Shape::Text { lines },
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Shape {
+    Block { width: f64, height: f64 },
+    Text { lines: usize },
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Skeleton {
+    shape: Shape,
+}
+
+impl<State, Action> MasonryView<State, Action> for Skeleton {
+    type Element = masonry::widget::Skeleton;
+    type ViewState = ();
+
+    fn build(&self, _cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let widget = match self.shape {
+            Shape::Block { width, height } => masonry::widget::Skeleton::new(width, height),
+            Shape::Text { lines } => masonry::widget::Skeleton::text(lines),
+        };
+        (WidgetPod::new(widget), ())
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.shape != self.shape {
+            match self.shape {
+                Shape::Block { width, height } => element.set_size(width, height),
+                Shape::Text { lines } => element.set_lines(lines),
+            }
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        _id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        _app_state: &mut State,
+    ) -> MessageResult<Action> {
+        tracing::error!("Message arrived in Skeleton::message, but Skeleton doesn't consume any messages, this is a bug");
+        MessageResult::Stale(message)
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::RangeInclusive;
+
+use masonry::widget::WidgetMut;
+use masonry::WidgetPod;
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A horizontal slider for a value in `range`.
+///
+/// `callback` is called with the new value when the user moves the slider.
+pub fn slider<F, State, Action>(value: f64, range: RangeInclusive<f64>, callback: F) -> Slider<F>
+where
+    F: Fn(&mut State, f64) -> Action + 'static,
+{
+    Slider {
+        value,
+        range,
+        step: None,
+        disabled: false,
+        callback,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Slider<F> {
+    value: f64,
+    range: RangeInclusive<f64>,
+    step: Option<f64>,
+    disabled: bool,
+    callback: F,
+}
+
+impl<F> Slider<F> {
+    /// How much the arrow keys change the value, which is a hundredth of the range
+    /// by default.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    /// Disable the slider, so that it can't be moved.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    fn step_or_default(&self) -> f64 {
+        self.step
+            .unwrap_or((self.range.end() - self.range.start()) / 100.0)
+    }
+}
+
+impl<F, State, Action> MasonryView<State, Action> for Slider<F>
+where
+    F: Fn(&mut State, f64) -> Action + 'static,
+{
+    type Element = masonry::widget::Slider;
+    type ViewState = ();
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        cx.with_leaf_action_widget(|_| {
+            WidgetPod::new(
+                masonry::widget::Slider::new(self.value, self.range.clone())
+                    .with_step(self.step_or_default()),
+            )
+            .with_disabled(self.disabled)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.range != self.range {
+            element.set_range(self.range.clone());
+            cx.mark_changed();
+        }
+        if prev.value != self.value || prev.range != self.range {
+            element.set_value(self.value);
+            cx.mark_changed();
+        }
+        if prev.step_or_default() != self.step_or_default() {
+            element.set_step(self.step_or_default());
+            cx.mark_changed();
+        }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in Slider::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::ValueChanged(value) = *action {
+                    MessageResult::Action((self.callback)(app_state, value))
+                } else {
+                    tracing::error!("Wrong action type in Slider::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in Slider::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
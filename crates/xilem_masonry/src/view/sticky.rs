@@ -0,0 +1,59 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{
+    widget::{self, WidgetMut},
+    WidgetPod,
+};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// Keep `child` in view while the children after it in a [`flex`](crate::view::flex)
+/// are scrolled past, such as the header of a section in a [`portal`](crate::view::portal).
+///
+/// The child stays in view until the next `sticky` sibling pushes it out. See
+/// [`Sticky`](masonry::widget::Sticky) for when this applies.
+pub fn sticky<V>(child: V) -> Sticky<V> {
+    Sticky { child }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct Sticky<V> {
+    child: V,
+}
+
+impl<State, Action, V> MasonryView<State, Action> for Sticky<V>
+where
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::Sticky;
+    type ViewState = V::ViewState;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let (child, child_state) = self.child.build(cx);
+        let widget = widget::Sticky::new_pod(child.boxed());
+        (WidgetPod::new(widget), child_state)
+    }
+
+    fn rebuild(
+        &self,
+        child_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        let mut child = element.child_mut();
+        let child = child.downcast();
+        self.child.rebuild(child_state, cx, &prev.child, child);
+    }
+
+    fn message(
+        &self,
+        child_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        self.child.message(child_state, id_path, message, app_state)
+    }
+}
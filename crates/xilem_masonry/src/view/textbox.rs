@@ -3,17 +3,17 @@
 
 use masonry::{text2::TextBrush, widget::WidgetMut, WidgetPod};
 
-use crate::{Color, MasonryView, MessageResult, TextAlignment, ViewCx, ViewId};
+use crate::{Color, InputHint, MasonryView, MessageResult, TextAlignment, ViewCx, ViewId};
 
 // FIXME - A major problem of the current approach (always setting the textbox contents)
 // is that if the user forgets to hook up the modify the state's contents in the callback,
 // the textbox will always be reset to the initial state. This will be very annoying for the user.
 
-type Callback<State, Action> = Box<dyn Fn(&mut State, String) -> Action + Send + 'static>;
+type Callback<State, Action> = Box<dyn Fn(&mut State, String) -> Action + 'static>;
 
 pub fn textbox<F, State, Action>(contents: String, on_changed: F) -> Textbox<State, Action>
 where
-    F: Fn(&mut State, String) -> Action + Send + 'static,
+    F: Fn(&mut State, String) -> Action + 'static,
 {
     // TODO: Allow setting a placeholder
     Textbox {
@@ -23,9 +23,11 @@ where
         text_brush: Color::WHITE.into(),
         alignment: TextAlignment::default(),
         disabled: false,
+        input_hint: InputHint::Text,
     }
 }
 
+#[must_use = "View values do nothing unless provided to Xilem."]
 pub struct Textbox<State, Action> {
     contents: String,
     on_changed: Callback<State, Action>,
@@ -33,6 +35,7 @@ pub struct Textbox<State, Action> {
     text_brush: TextBrush,
     alignment: TextAlignment,
     disabled: bool,
+    input_hint: InputHint,
     // TODO: add more attributes of `masonry::widget::Label`
 }
 
@@ -48,14 +51,21 @@ impl<State, Action> Textbox<State, Action> {
         self
     }
 
-    pub fn disabled(mut self) -> Self {
-        self.disabled = true;
+    /// Disable the textbox, so that it can't be edited.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// The kind of text the textbox takes, such as a password, which is masked.
+    pub fn input_hint(mut self, input_hint: InputHint) -> Self {
+        self.input_hint = input_hint;
         self
     }
 
     pub fn on_enter<F>(mut self, on_enter: F) -> Self
     where
-        F: Fn(&mut State, String) -> Action + Send + 'static,
+        F: Fn(&mut State, String) -> Action + 'static,
     {
         self.on_enter = Some(Box::new(on_enter));
         self
@@ -71,8 +81,10 @@ impl<State: 'static, Action: 'static> MasonryView<State, Action> for Textbox<Sta
             WidgetPod::new(
                 masonry::widget::Textbox::new(self.contents.clone())
                     .with_text_brush(self.text_brush.clone())
-                    .with_text_alignment(self.alignment),
+                    .with_text_alignment(self.alignment)
+                    .with_input_hint(self.input_hint),
             )
+            .with_disabled(self.disabled)
         })
     }
 
@@ -93,10 +105,10 @@ impl<State: 'static, Action: 'static> MasonryView<State, Action> for Textbox<Sta
             cx.mark_changed();
         }
 
-        // if prev.disabled != self.disabled {
-        //     element.set_disabled(self.disabled);
-        //     cx.mark_changed();
-        // }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
         if prev.text_brush != self.text_brush {
             element.set_text_brush(self.text_brush.clone());
             cx.mark_changed();
@@ -105,6 +117,10 @@ impl<State: 'static, Action: 'static> MasonryView<State, Action> for Textbox<Sta
             element.set_alignment(self.alignment);
             cx.mark_changed();
         }
+        if prev.input_hint != self.input_hint {
+            element.set_input_hint(self.input_hint);
+            cx.mark_changed();
+        }
     }
 
     fn message(
@@ -0,0 +1,139 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::widget::{ToggleMode, WidgetMut};
+use masonry::{ArcStr, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A row of toggle buttons, such as the bold, italic and underline buttons of a
+/// toolbar.
+///
+/// `pressed` is whether each button is pressed, and `callback` is called with the
+/// new state of all the buttons when the user toggles one. State kept as a
+/// `Vec<bool>` can be updated with [`assign`](crate::assign), and state kept as
+/// flags can be converted to and from the list in the view and the callback.
+pub fn toggle_group<F, State, Action>(
+    buttons: impl IntoIterator<Item = impl Into<ArcStr>>,
+    pressed: impl IntoIterator<Item = bool>,
+    callback: F,
+) -> ToggleGroup<F>
+where
+    F: Fn(&mut State, Vec<bool>) -> Action + 'static,
+{
+    ToggleGroup {
+        buttons: buttons.into_iter().map(Into::into).collect(),
+        pressed: pressed.into_iter().collect(),
+        mode: ToggleMode::default(),
+        disabled: false,
+        callback,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct ToggleGroup<F> {
+    buttons: Vec<ArcStr>,
+    pressed: Vec<bool>,
+    mode: ToggleMode,
+    disabled: bool,
+    callback: F,
+}
+
+impl<F> ToggleGroup<F> {
+    /// Press at most one button at a time, releasing the others when one is
+    /// pressed, such as for the alignment of text.
+    pub fn exclusive(mut self, exclusive: bool) -> Self {
+        self.mode = if exclusive {
+            ToggleMode::Exclusive
+        } else {
+            ToggleMode::Multiple
+        };
+        self
+    }
+
+    /// Disable the group, so that its buttons can't be toggled.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+}
+
+impl<F, State, Action> MasonryView<State, Action> for ToggleGroup<F>
+where
+    F: Fn(&mut State, Vec<bool>) -> Action + 'static,
+{
+    type Element = masonry::widget::ToggleGroup;
+    type ViewState = ();
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        cx.with_leaf_action_widget(|_| {
+            WidgetPod::new(
+                masonry::widget::ToggleGroup::new(self.buttons.iter().cloned())
+                    .with_pressed(self.pressed.iter().copied())
+                    .with_mode(self.mode),
+            )
+            .with_disabled(self.disabled)
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.buttons != self.buttons {
+            for (ix, (prev_text, text)) in prev.buttons.iter().zip(&self.buttons).enumerate() {
+                if prev_text != text {
+                    element.set_button_text(ix, text.clone());
+                }
+            }
+            for text in self.buttons.iter().skip(prev.buttons.len()) {
+                element.add_button(text.clone());
+            }
+            for ix in (self.buttons.len()..prev.buttons.len()).rev() {
+                element.remove_button(ix);
+            }
+            cx.mark_changed();
+        }
+        if prev.pressed != self.pressed || prev.buttons.len() != self.buttons.len() {
+            element.set_pressed(self.pressed.iter().copied());
+            cx.mark_changed();
+        }
+        if prev.mode != self.mode {
+            element.set_mode(self.mode);
+        }
+        if prev.disabled != self.disabled {
+            element.ctx.set_disabled(self.disabled);
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in ToggleGroup::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => {
+                if let masonry::Action::TogglesChanged(pressed) = *action {
+                    MessageResult::Action((self.callback)(app_state, pressed))
+                } else {
+                    tracing::error!("Wrong action type in ToggleGroup::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            }
+            Err(message) => {
+                tracing::error!("Wrong message type in ToggleGroup::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::{widget::WidgetMut, ArcStr, WidgetPod};
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// A text input which turns what the user types into a list of tokens, such as
+/// email recipients or tags.
+///
+/// A token is added when the user presses <kbd>Enter</kbd> or types a comma, and
+/// removed by clicking it or with <kbd>Backspace</kbd>. `on_changed` is called with
+/// the new tokens after every change.
+pub fn token_field<F, State, Action>(tokens: Vec<String>, on_changed: F) -> TokenField<F>
+where
+    F: Fn(&mut State, Vec<String>) -> Action + 'static,
+{
+    TokenField { tokens, on_changed }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct TokenField<F> {
+    tokens: Vec<String>,
+    on_changed: F,
+}
+
+impl<F, State, Action> MasonryView<State, Action> for TokenField<F>
+where
+    F: Fn(&mut State, Vec<String>) -> Action + 'static,
+{
+    type Element = masonry::widget::TokenField;
+    type ViewState = ();
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        // The text box's actions are routed here too, so that they aren't reported as unknown
+        let textbox = cx.with_action_widget(|_| WidgetPod::new(masonry::widget::Textbox::new("")));
+        cx.with_leaf_action_widget(|_| {
+            WidgetPod::new(masonry::widget::TokenField::from_pod(
+                textbox,
+                self.tokens.iter().map(|token| ArcStr::from(token.as_str())),
+            ))
+        })
+    }
+
+    fn rebuild(
+        &self,
+        _view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        _prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        // As in `Textbox`, we compare to the element's tokens, which the user may have changed
+        if !self
+            .tokens
+            .iter()
+            .map(String::as_str)
+            .eq(element.widget.tokens())
+        {
+            element.set_tokens(self.tokens.iter().map(|token| token.as_str()));
+            cx.mark_changed();
+        }
+    }
+
+    fn message(
+        &self,
+        _view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        debug_assert!(
+            id_path.is_empty(),
+            "id path should be empty in TokenField::message"
+        );
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::TokensChanged(tokens) => {
+                    MessageResult::Action((self.on_changed)(app_state, tokens))
+                }
+                // The text which hasn't become a token yet isn't part of the value
+                masonry::Action::TextChanged(_) | masonry::Action::TextEntered(_) => {
+                    MessageResult::Nop
+                }
+                _ => {
+                    tracing::error!("Wrong action type in TokenField::message: {action:?}");
+                    MessageResult::Stale(action)
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in TokenField::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use masonry::widget::{self, WidgetMut};
+use masonry::WidgetPod;
+
+use crate::{MasonryView, MessageResult, ViewCx, ViewId};
+
+/// How many items are built before the list knows how many are shown, which is
+/// enough to fill most windows.
+const INITIAL_ITEMS: usize = 32;
+
+/// A list of `item_count` items, each `item_height` tall, which only builds the
+/// views of the items which are scrolled into view, by calling `item` with their
+/// index.
+///
+/// The list should be in a [`portal`](crate::view::portal), which scrolls it:
+///
+/// ```ignore
+/// portal(virtual_list(rows.len(), 24.0, move |index| label(rows[index].clone())))
+/// ```
+///
+/// As items are scrolled out of view, their widgets are reused for the items
+/// scrolled into view, so the views returned by `item` should be cheap to rebuild
+/// from one another. Widget state which isn't set by the view, such as the
+/// selection of a text box, isn't kept for items scrolled out of view.
+pub fn virtual_list<V, F>(item_count: usize, item_height: f64, item: F) -> VirtualList<F, V>
+where
+    F: Fn(usize) -> V + 'static,
+{
+    VirtualList {
+        item_count,
+        item_height,
+        item,
+        phantom: PhantomData,
+    }
+}
+
+#[must_use = "View values do nothing unless provided to Xilem."]
+pub struct VirtualList<F, V> {
+    item_count: usize,
+    item_height: f64,
+    item: F,
+    phantom: PhantomData<fn() -> V>,
+}
+
+pub struct VirtualListState<V, S> {
+    /// The items the children are for
+    items: Range<usize>,
+    /// The items the list last asked for
+    requested: Range<usize>,
+    /// The children, in the order of their items
+    slots: Vec<Slot<V, S>>,
+    next_id: u64,
+}
+
+struct Slot<V, S> {
+    /// The routing id of the child, which stays the same as it is reused
+    id: u64,
+    view: V,
+    state: S,
+}
+
+impl<F, V> VirtualList<F, V> {
+    /// The items of `requested` which are in the list.
+    fn clamp(&self, requested: &Range<usize>) -> Range<usize> {
+        requested.start.min(self.item_count)..requested.end.min(self.item_count)
+    }
+}
+
+impl<State, Action, F, V> MasonryView<State, Action> for VirtualList<F, V>
+where
+    F: Fn(usize) -> V + 'static,
+    V: MasonryView<State, Action>,
+{
+    type Element = widget::VirtualList;
+    type ViewState = VirtualListState<V, V::ViewState>;
+
+    fn build(&self, cx: &mut ViewCx) -> (WidgetPod<Self::Element>, Self::ViewState) {
+        let items = 0..self.item_count.min(INITIAL_ITEMS);
+        let mut widget = widget::VirtualList::new(self.item_count, self.item_height);
+        let mut slots = Vec::new();
+        for (id, index) in (0..).zip(items.clone()) {
+            let view = (self.item)(index);
+            let (child, state) = cx.with_id(ViewId::for_type::<V>(id), |cx| view.build(cx));
+            widget = widget.with_child_pod(child.boxed());
+            slots.push(Slot { id, view, state });
+        }
+        let view_state = VirtualListState {
+            requested: items.clone(),
+            next_id: items.end as u64,
+            items,
+            slots,
+        };
+        (
+            cx.with_action_widget(|_| WidgetPod::new(widget)),
+            view_state,
+        )
+    }
+
+    fn rebuild(
+        &self,
+        view_state: &mut Self::ViewState,
+        cx: &mut ViewCx,
+        prev: &Self,
+        mut element: WidgetMut<Self::Element>,
+    ) {
+        if prev.item_count != self.item_count {
+            element.set_item_count(self.item_count);
+            cx.mark_changed();
+        }
+        if prev.item_height != self.item_height {
+            element.set_item_height(self.item_height);
+            cx.mark_changed();
+        }
+
+        // The children of the items which are still shown keep their widgets, and the
+        // others are moved to the other end of the list, to be reused for the new items
+        let items = self.clamp(&view_state.requested);
+        let old = view_state.items.clone();
+        let len = view_state.slots.len();
+        if items.start > old.start {
+            let moved = (items.start - old.start).min(len);
+            view_state.slots.rotate_left(moved);
+            for _ in 0..moved {
+                element.move_child(0, len - 1);
+            }
+        } else {
+            let moved = (old.start - items.start).min(len);
+            view_state.slots.rotate_right(moved);
+            for _ in 0..moved {
+                element.move_child(len - 1, 0);
+            }
+        }
+        if items.start != old.start {
+            element.set_first_item(items.start);
+            cx.mark_changed();
+        }
+
+        for (ix, index) in items.clone().enumerate() {
+            let view = (self.item)(index);
+            if let Some(slot) = view_state.slots.get_mut(ix) {
+                cx.with_id(ViewId::for_type::<V>(slot.id), |cx| {
+                    let mut child = element.child_mut(ix).unwrap();
+                    view.rebuild(&mut slot.state, cx, &slot.view, child.downcast());
+                });
+                slot.view = view;
+            } else {
+                let id = view_state.next_id;
+                view_state.next_id += 1;
+                let (child, state) = cx.with_id(ViewId::for_type::<V>(id), |cx| view.build(cx));
+                element.insert_child_pod(ix, child.boxed());
+                view_state.slots.push(Slot { id, view, state });
+                cx.mark_changed();
+            }
+        }
+        while view_state.slots.len() > items.len() {
+            let slot = view_state
+                .slots
+                .pop()
+                .expect("There are more slots than items");
+            element.remove_child(view_state.slots.len());
+            cx.teardown(ViewId::for_type::<V>(slot.id));
+            cx.mark_changed();
+        }
+        view_state.items = items;
+    }
+
+    fn message(
+        &self,
+        view_state: &mut Self::ViewState,
+        id_path: &[ViewId],
+        message: Box<dyn std::any::Any>,
+        app_state: &mut State,
+    ) -> MessageResult<Action> {
+        if let Some((start, rest)) = id_path.split_first() {
+            let slot = view_state
+                .slots
+                .iter_mut()
+                .find(|slot| slot.id == start.routing_id());
+            return match slot {
+                Some(slot) => slot.view.message(&mut slot.state, rest, message, app_state),
+                None => MessageResult::Stale(message),
+            };
+        }
+        match message.downcast::<masonry::Action>() {
+            Ok(action) => match *action {
+                masonry::Action::VisibleItemsChanged(items) => {
+                    view_state.requested = items;
+                    MessageResult::RequestRebuild
+                }
+                action => {
+                    tracing::error!("Wrong action type in VirtualList::message: {action:?}");
+                    MessageResult::Stale(Box::new(action))
+                }
+            },
+            Err(message) => {
+                tracing::error!("Wrong message type in VirtualList::message");
+                MessageResult::Stale(message)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::testing::TestHarness;
+    use masonry::{Point, Size, Vec2};
+
+    use super::*;
+    use crate::view::{label, portal};
+
+    #[test]
+    fn scrolling_reuses_the_children() {
+        let view = portal(virtual_list(10_000, 20.0, |index| {
+            label(format!("{index}"))
+        }));
+        let mut cx = ViewCx::new();
+        let (pod, mut view_state) = MasonryView::<(), ()>::build(&view, &mut cx);
+        let mut harness = TestHarness::create_with_size(
+            widget::SizedBox::new_pod(pod.boxed()).expand(),
+            Size::new(200.0, 100.0),
+        );
+        // Route the list's actions to the view, and rebuild it like the driver would
+        let mut handle_actions = |harness: &mut TestHarness| {
+            while let Some((action, widget_id)) = harness.pop_action() {
+                let path = cx.widget_map[&widget_id];
+                let mut id_path = Vec::new();
+                cx.id_paths.resolve_into(path, &mut id_path);
+                let result = MasonryView::<(), ()>::message(
+                    &view,
+                    &mut view_state,
+                    &id_path,
+                    Box::new(action),
+                    &mut (),
+                );
+                assert!(matches!(result, MessageResult::RequestRebuild));
+                harness.edit_root_widget(|mut root| {
+                    let mut root = root.downcast::<widget::SizedBox>();
+                    let mut portal = root.child_mut().unwrap();
+                    let portal = portal.downcast();
+                    MasonryView::<(), ()>::rebuild(&view, &mut view_state, &mut cx, &view, portal);
+                });
+            }
+        };
+        let items = |harness: &TestHarness| {
+            let list = harness.root_widget().children()[0].children()[0];
+            let labels = list.children();
+            let text = |ix: usize| {
+                let label = labels[ix].downcast::<widget::Label>().unwrap();
+                label.text().to_string()
+            };
+            let ids = labels.iter().map(|label| label.id()).collect::<Vec<_>>();
+            (text(0), ids)
+        };
+        handle_actions(&mut harness);
+        let (first, ids) = items(&harness);
+        assert_eq!((first.as_str(), ids.len()), ("0", 7));
+
+        harness.mouse_move(Point::new(100.0, 50.0));
+        harness.mouse_wheel(Vec2::new(0.0, 20.0 * 5000.0));
+        handle_actions(&mut harness);
+        let (first, before) = items(&harness);
+        assert_eq!((first.as_str(), before.len()), ("4998", 9));
+
+        // Scrolling by one item keeps the widgets of the items still shown
+        harness.mouse_wheel(Vec2::new(0.0, -20.0));
+        handle_actions(&mut harness);
+        let (first, after) = items(&harness);
+        assert_eq!(first, "4997");
+        assert_eq!(after[1..], before[..8]);
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2024 the Xilem Authors
+// SPDX-License-Identifier: Apache-2.0
+
+use masonry::direction::EdgeInsets;
+use masonry::widget::{FilterDecision, FilteredEvent, GridParams};
+
+use crate::view::{
+    align, cache_paint, event_filter, flex_item, grid_item, map_action, on_click, sized_box, Align,
+    CachePaint, EventFilter, FlexItem, GridItem, MapAction, OnClick, SizedBox,
+};
+use crate::{BoxedMasonryView, Color, FlexParams, MasonryView, UnitPoint};
+
+/// Methods to wrap a view in the common adapter views, so that a view tree reads
+/// from the content to its decorations:
+///
+/// ```ignore
+/// label("Open").padding(8.0).background(Color::DARK_SLATE_GRAY).on_click(open)
+/// ```
+///
+/// Each method is the same as the free function of the same name, such as
+/// [`sized_box`] for the size and decoration methods. Calling several of those in
+/// a row configures a single [`SizedBox`], as its own methods take precedence.
+///
+/// This is implemented for all types, but its methods only return views when
+/// called on a view.
+pub trait ViewExt: Sized + 'static {
+    /// Add `padding` between this view and the edges of a [`SizedBox`].
+    fn padding(self, padding: impl Into<EdgeInsets>) -> SizedBox<Self> {
+        sized_box(self).padding(padding)
+    }
+
+    /// Fix the width of this view.
+    fn width(self, width: f64) -> SizedBox<Self> {
+        sized_box(self).width(width)
+    }
+
+    /// Fix the height of this view.
+    fn height(self, height: f64) -> SizedBox<Self> {
+        sized_box(self).height(height)
+    }
+
+    /// Paint `color` behind this view.
+    fn background(self, color: Color) -> SizedBox<Self> {
+        sized_box(self).background(color)
+    }
+
+    /// Paint a border of `width` around this view.
+    fn border(self, color: Color, width: f64) -> SizedBox<Self> {
+        sized_box(self).border(color, width)
+    }
+
+    /// Round the corners of the background and border of this view.
+    fn rounded(self, radius: f64) -> SizedBox<Self> {
+        sized_box(self).rounded(radius)
+    }
+
+    /// Place this view at `alignment` in the space it's given. See [`align`].
+    fn align(self, alignment: UnitPoint) -> Align<Self> {
+        align(self, alignment)
+    }
+
+    /// Lay out this view with `params` in a [`flex`](crate::view::flex). See
+    /// [`flex_item`].
+    fn flex_item(self, params: impl Into<FlexParams>) -> FlexItem<Self> {
+        flex_item(self, params)
+    }
+
+    /// Place this view with `params` in a [`grid`](crate::view::grid). See
+    /// [`grid_item`].
+    fn grid_item(self, params: impl Into<GridParams>) -> GridItem<Self> {
+        grid_item(self, params)
+    }
+
+    /// Call `callback` when this view is clicked. See [`on_click`].
+    fn on_click<State, Action, F>(self, callback: F) -> OnClick<Self, F>
+    where
+        Self: MasonryView<State, Action>,
+        F: Fn(&mut State) -> Action + 'static,
+    {
+        on_click(self, callback)
+    }
+
+    /// Convert the actions of this view to those of its parent. See [`map_action`].
+    fn map_action<State, ParentAction, Action, F>(self, map: F) -> MapAction<Self, F, Action>
+    where
+        Self: MasonryView<State, Action>,
+        F: Fn(&mut State, Action) -> ParentAction + 'static,
+    {
+        map_action(self, map)
+    }
+
+    /// Pass the events on their way to this view to `filter`. See [`event_filter`].
+    fn event_filter<F>(self, filter: F) -> EventFilter<Self, F>
+    where
+        F: Fn(FilteredEvent<'_>) -> FilterDecision + 'static,
+    {
+        event_filter(self, filter)
+    }
+
+    /// Draw this view from an image until it changes. See [`cache_paint`].
+    fn cache_paint(self) -> CachePaint<Self> {
+        cache_paint(self)
+    }
+
+    /// Blur what is painted beneath this view. See
+    /// [`backdrop_blur`](crate::view::backdrop_blur).
+    #[cfg(feature = "effects")]
+    fn backdrop_blur(self, radius: f64) -> crate::view::BackdropBlur<Self> {
+        crate::view::backdrop_blur(radius, self)
+    }
+
+    /// Erase the type of this view, such as to return different views from the
+    /// branches of a `match`.
+    fn boxed<State, Action>(self) -> BoxedMasonryView<State, Action>
+    where
+        Self: MasonryView<State, Action>,
+        Self::ViewState: 'static,
+    {
+        Box::new(self)
+    }
+}
+
+impl<V: 'static> ViewExt for V {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::label;
+    use crate::{MessageResult, ViewCx};
+
+    #[test]
+    fn click_on_decorated_label() {
+        let view = label("Open")
+            .padding(8.0)
+            .background(Color::BLACK)
+            .on_click(|count: &mut u32| {
+                *count += 1;
+                *count
+            });
+        let mut cx = ViewCx::new();
+        let (_, mut view_state) = view.build(&mut cx);
+
+        let mut count = 0;
+        let result = view.message(
+            &mut view_state,
+            &[],
+            Box::new(masonry::Action::ButtonPressed),
+            &mut count,
+        );
+        assert!(matches!(result, MessageResult::Action(1)));
+    }
+}